@@ -2,21 +2,23 @@ use std::sync::Arc;
 
 use assistant_tool::ToolWorkingSet;
 use collections::HashMap;
+use editor::Editor;
 use gpui::{
-    list, AbsoluteLength, AnyElement, App, DefiniteLength, EdgesRefinement, Empty, Entity, Length,
-    ListAlignment, ListOffset, ListState, StyleRefinement, Subscription, TextStyleRefinement,
-    UnderlineStyle, WeakEntity,
+    list, AbsoluteLength, AnyElement, App, DefiniteLength, EdgesRefinement, Empty, Entity,
+    Focusable, Length, ListAlignment, ListOffset, ListState, StyleRefinement, Subscription,
+    TextStyleRefinement, UnderlineStyle, WeakEntity,
 };
 use language::LanguageRegistry;
-use language_model::Role;
+use language_model::{LanguageModelRegistry, Role};
 use markdown::{Markdown, MarkdownStyle};
 use settings::Settings as _;
 use theme::ThemeSettings;
-use ui::prelude::*;
+use ui::{prelude::*, Tooltip};
 use workspace::Workspace;
 
-use crate::thread::{MessageId, Thread, ThreadError, ThreadEvent};
+use crate::thread::{MessageId, RequestKind, Thread, ThreadError, ThreadEvent};
 use crate::thread_store::ThreadStore;
+use crate::token_usage::TokenUsageSummary;
 use crate::ui::ContextPill;
 
 pub struct ActiveThread {
@@ -28,6 +30,7 @@ pub struct ActiveThread {
     messages: Vec<MessageId>,
     list_state: ListState,
     rendered_messages_by_id: HashMap<MessageId, Entity<Markdown>>,
+    editing_message: Option<(MessageId, Entity<Editor>)>,
     last_error: Option<ThreadError>,
     _subscriptions: Vec<Subscription>,
 }
@@ -62,6 +65,7 @@ impl ActiveThread {
                         .unwrap()
                 }
             }),
+            editing_message: None,
             last_error: None,
             _subscriptions: subscriptions,
         };
@@ -103,6 +107,87 @@ impl ActiveThread {
         self.last_error.take();
     }
 
+    fn rebuild_messages(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let old_len = self.messages.len();
+        self.messages.clear();
+        self.rendered_messages_by_id.clear();
+        self.list_state.splice(0..old_len, 0);
+
+        for message in self.thread.read(cx).messages().cloned().collect::<Vec<_>>() {
+            self.push_message(&message.id, message.text.clone(), window, cx);
+        }
+
+        if let Some((message_id, _)) = self.editing_message.as_ref() {
+            if !self.messages.contains(message_id) {
+                self.editing_message.take();
+            }
+        }
+    }
+
+    fn start_editing_message(
+        &mut self,
+        message_id: MessageId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(message_text) = self
+            .thread
+            .read(cx)
+            .message(message_id)
+            .map(|message| message.text.clone())
+        else {
+            return;
+        };
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(10, window, cx);
+            editor.set_text(message_text, window, cx);
+            editor.set_show_indent_guides(false, cx);
+            editor
+        });
+        window.focus(&editor.focus_handle(cx));
+
+        self.editing_message = Some((message_id, editor));
+        cx.notify();
+    }
+
+    fn cancel_editing_message(&mut self, cx: &mut Context<Self>) {
+        self.editing_message.take();
+        cx.notify();
+    }
+
+    /// Replaces the message being edited on a new branch and regenerates the response from there.
+    fn confirm_editing_message(&mut self, cx: &mut Context<Self>) {
+        let Some((message_id, editor)) = self.editing_message.take() else {
+            return;
+        };
+
+        let text = editor.read(cx).text(cx);
+        if text.trim().is_empty() {
+            cx.notify();
+            return;
+        }
+
+        let Some(model) = LanguageModelRegistry::read_global(cx).active_model() else {
+            return;
+        };
+
+        self.last_error.take();
+        self.thread.update(cx, |thread, cx| {
+            if thread.edit_user_message(message_id, text, cx).is_some() {
+                let request = thread.to_completion_request(RequestKind::Chat, cx);
+                thread.stream_completion(request, model, cx);
+            }
+        });
+        cx.notify();
+    }
+
+    fn activate_branch(&mut self, message_id: MessageId, cx: &mut Context<Self>) {
+        self.thread.update(cx, |thread, cx| {
+            thread.activate_branch(message_id, cx);
+        });
+    }
+
     fn push_message(
         &mut self,
         id: &MessageId,
@@ -219,6 +304,17 @@ impl ActiveThread {
                     });
                 }
             }
+            ThreadEvent::BranchChanged => {
+                self.rebuild_messages(window, cx);
+
+                self.thread_store
+                    .update(cx, |thread_store, cx| {
+                        thread_store.save_thread(&self.thread, cx)
+                    })
+                    .detach_and_log_err(cx);
+
+                cx.notify();
+            }
             ThreadEvent::MessageAdded(message_id) => {
                 if let Some(message_text) = self
                     .thread
@@ -268,7 +364,12 @@ impl ActiveThread {
 
     fn render_message(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
         let message_id = self.messages[ix];
-        let Some(message) = self.thread.read(cx).message(message_id) else {
+        let Some(role) = self
+            .thread
+            .read(cx)
+            .message(message_id)
+            .map(|message| message.role)
+        else {
             return Empty.into_any();
         };
 
@@ -277,10 +378,35 @@ impl ActiveThread {
         };
 
         let context = self.thread.read(cx).context_for_message(message_id);
+        let token_usage = self
+            .thread
+            .read(cx)
+            .token_usage_for_message(message_id)
+            .map(|usage| TokenUsageSummary::new([usage], cx));
+
+        let editing_editor = self
+            .editing_message
+            .as_ref()
+            .filter(|(editing_message_id, _)| *editing_message_id == message_id)
+            .map(|(_, editor)| editor.clone());
+        let user_message_controls = (role == Role::User).then(|| {
+            let is_streaming = self.thread.read(cx).is_streaming();
+            let siblings = self.thread.read(cx).message_siblings(message_id);
+            self.render_user_message_controls(
+                message_id,
+                &siblings,
+                editing_editor.is_some(),
+                is_streaming,
+                cx,
+            )
+        });
         let colors = cx.theme().colors();
 
         let message_content = v_flex()
-            .child(div().p_2p5().text_ui(cx).child(markdown.clone()))
+            .map(|parent| match editing_editor.as_ref() {
+                Some(editor) => parent.child(div().p_2p5().text_ui(cx).child(editor.clone())),
+                None => parent.child(div().p_2p5().text_ui(cx).child(markdown.clone())),
+            })
             .when_some(context, |parent, context| {
                 if !context.is_empty() {
                     parent.child(
@@ -295,7 +421,7 @@ impl ActiveThread {
                 }
             });
 
-        let styled_message = match message.role {
+        let styled_message = match role {
             Role::User => v_flex()
                 .id(("message-container", ix))
                 .pt_2p5()
@@ -329,11 +455,23 @@ impl ActiveThread {
                                                 .size(LabelSize::Small)
                                                 .color(Color::Muted),
                                         ),
-                                ),
+                                )
+                                .children(user_message_controls),
                         )
                         .child(message_content),
                 ),
-            Role::Assistant => div().id(("message-container", ix)).child(message_content),
+            Role::Assistant => div()
+                .id(("message-container", ix))
+                .child(message_content)
+                .when_some(token_usage, |parent, token_usage| {
+                    parent.child(
+                        h_flex().px_2p5().pb_1().justify_end().child(
+                            Label::new(token_usage.label())
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        ),
+                    )
+                }),
             Role::System => div().id(("message-container", ix)).py_1().px_2().child(
                 v_flex()
                     .bg(colors.editor_background)
@@ -344,12 +482,113 @@ impl ActiveThread {
 
         styled_message.into_any()
     }
+
+    fn render_user_message_controls(
+        &self,
+        message_id: MessageId,
+        siblings: &[MessageId],
+        is_editing: bool,
+        is_streaming: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let branch_ix = siblings.iter().position(|id| *id == message_id);
+        let previous_branch = branch_ix
+            .and_then(|ix| ix.checked_sub(1))
+            .and_then(|ix| siblings.get(ix).copied());
+        let next_branch = branch_ix.and_then(|ix| siblings.get(ix + 1).copied());
+
+        h_flex()
+            .gap_1()
+            .when_some(branch_ix.filter(|_| siblings.len() > 1), |parent, ix| {
+                parent
+                    .child(
+                        IconButton::new("previous-branch", IconName::ChevronLeft)
+                            .icon_size(IconSize::XSmall)
+                            .disabled(previous_branch.is_none() || is_streaming)
+                            .tooltip(Tooltip::text("Previous Branch"))
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                if let Some(previous_branch) = previous_branch {
+                                    this.activate_branch(previous_branch, cx);
+                                }
+                            })),
+                    )
+                    .child(
+                        Label::new(format!("{}/{}", ix + 1, siblings.len()))
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        IconButton::new("next-branch", IconName::ChevronRight)
+                            .icon_size(IconSize::XSmall)
+                            .disabled(next_branch.is_none() || is_streaming)
+                            .tooltip(Tooltip::text("Next Branch"))
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                if let Some(next_branch) = next_branch {
+                                    this.activate_branch(next_branch, cx);
+                                }
+                            })),
+                    )
+            })
+            .map(|parent| {
+                if is_editing {
+                    parent
+                        .child(
+                            Button::new("cancel-edit-message", "Cancel")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.cancel_editing_message(cx);
+                                })),
+                        )
+                        .child(
+                            Button::new("confirm-edit-message", "Regenerate")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.confirm_editing_message(cx);
+                                })),
+                        )
+                } else {
+                    parent.child(
+                        IconButton::new("edit-message", IconName::Pencil)
+                            .icon_size(IconSize::XSmall)
+                            .disabled(is_streaming)
+                            .tooltip(Tooltip::text("Edit and Regenerate"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.start_editing_message(message_id, window, cx);
+                            })),
+                    )
+                }
+            })
+    }
 }
 
 impl Render for ActiveThread {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let token_usage_by_model = self.thread.read(cx).token_usage_by_model();
+        let token_usage = TokenUsageSummary::new(&token_usage_by_model, cx);
+
         v_flex()
             .size_full()
             .child(list(self.list_state.clone()).flex_grow())
+            .when(!token_usage.is_empty(), |parent| {
+                parent.child(
+                    h_flex()
+                        .px_2p5()
+                        .py_1()
+                        .gap_1()
+                        .justify_end()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Label::new("Thread Usage:")
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            Label::new(token_usage.label())
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        ),
+                )
+            })
     }
 }
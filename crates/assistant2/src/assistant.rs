@@ -10,11 +10,13 @@ mod context_strip;
 mod inline_assistant;
 mod inline_prompt_editor;
 mod message_editor;
+mod project_rules;
 mod terminal_codegen;
 mod terminal_inline_assistant;
 mod thread;
 mod thread_history;
 mod thread_store;
+mod token_usage;
 mod ui;
 
 use std::sync::Arc;
@@ -41,6 +43,10 @@ actions!(
         RemoveAllContext,
         OpenHistory,
         OpenPromptEditorHistory,
+        OpenThreadAsMarkdown,
+        ExportThreadAsMarkdown,
+        ExportThreadAsJson,
+        ImportThread,
         OpenConfiguration,
         RemoveSelectedThread,
         Chat,
@@ -15,7 +15,8 @@ use editor::Editor;
 use fs::Fs;
 use gpui::{
     prelude::*, px, svg, Action, AnyElement, App, AsyncWindowContext, Corner, Entity, EventEmitter,
-    FocusHandle, Focusable, FontWeight, Pixels, Subscription, Task, UpdateGlobal, WeakEntity,
+    FocusHandle, Focusable, FontWeight, PathPromptOptions, Pixels, Subscription, Task,
+    UpdateGlobal, WeakEntity,
 };
 use language::LanguageRegistry;
 use language_model::{LanguageModelProviderTosView, LanguageModelRegistry};
@@ -24,8 +25,9 @@ use prompt_library::{open_prompt_library, PromptBuilder, PromptLibrary};
 use settings::{update_settings_file, Settings};
 use time::UtcOffset;
 use ui::{prelude::*, ContextMenu, KeyBinding, PopoverMenu, PopoverMenuHandle, Tab, Tooltip};
-use util::ResultExt as _;
+use util::{paths, ResultExt as _};
 use workspace::dock::{DockPosition, Panel, PanelEvent};
+use workspace::notifications::DetachAndPromptErr as _;
 use workspace::Workspace;
 use zed_actions::assistant::{DeployPromptLibrary, ToggleFocus};
 
@@ -34,10 +36,10 @@ use crate::assistant_configuration::{AssistantConfiguration, AssistantConfigurat
 use crate::message_editor::MessageEditor;
 use crate::thread::{Thread, ThreadError, ThreadId};
 use crate::thread_history::{PastThread, ThreadHistory};
-use crate::thread_store::ThreadStore;
+use crate::thread_store::{ExportedThread, ThreadStore};
 use crate::{
-    InlineAssistant, NewPromptEditor, NewThread, OpenConfiguration, OpenHistory,
-    OpenPromptEditorHistory,
+    ExportThreadAsJson, ExportThreadAsMarkdown, ImportThread, InlineAssistant, NewPromptEditor,
+    NewThread, OpenConfiguration, OpenHistory, OpenPromptEditorHistory, OpenThreadAsMarkdown,
 };
 
 pub fn init(cx: &mut App) {
@@ -73,12 +75,52 @@ pub fn init(cx: &mut App) {
                         workspace.focus_panel::<AssistantPanel>(window, cx);
                         panel.update(cx, |panel, cx| panel.open_configuration(window, cx));
                     }
+                })
+                .register_action(|workspace, _: &OpenThreadAsMarkdown, window, cx| {
+                    if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                        panel.update(cx, |panel, cx| panel.open_thread_as_markdown(window, cx));
+                    }
+                })
+                .register_action(|workspace, _: &ExportThreadAsMarkdown, window, cx| {
+                    if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                        panel.update(cx, |panel, cx| {
+                            panel.export_thread(ThreadExportFormat::Markdown, window, cx)
+                        });
+                    }
+                })
+                .register_action(|workspace, _: &ExportThreadAsJson, window, cx| {
+                    if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                        panel.update(cx, |panel, cx| {
+                            panel.export_thread(ThreadExportFormat::Json, window, cx)
+                        });
+                    }
+                })
+                .register_action(|workspace, _: &ImportThread, window, cx| {
+                    if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                        workspace.focus_panel::<AssistantPanel>(window, cx);
+                        panel.update(cx, |panel, cx| panel.import_thread(window, cx));
+                    }
                 });
         },
     )
     .detach();
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ThreadExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ThreadExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+}
+
 enum ActiveView {
     Thread,
     PromptEditor,
@@ -478,6 +520,98 @@ impl AssistantPanel {
             .update(cx, |this, cx| this.delete_thread(thread_id, cx))
             .detach_and_log_err(cx);
     }
+
+    /// Opens the active thread as Markdown in a new editor buffer.
+    fn open_thread_as_markdown(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let markdown = self.active_thread(cx).read(cx).to_markdown();
+        let markdown_language = self.language_registry.language_for_name("Markdown");
+        let create_buffer = self
+            .project
+            .update(cx, |project, cx| project.create_buffer(cx));
+        let workspace = self.workspace.clone();
+        let project = self.project.clone();
+
+        cx.spawn_in(window, |_, mut cx| async move {
+            let markdown_language = markdown_language.await?;
+            let buffer = create_buffer.await?;
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.set_text(markdown, cx);
+                buffer.set_language(Some(markdown_language), cx);
+            })?;
+
+            workspace.update_in(&mut cx, |workspace, window, cx| {
+                let editor = cx.new(|cx| Editor::for_buffer(buffer, Some(project), window, cx));
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to open thread", window, cx, |_, _, _| None);
+    }
+
+    /// Prompts for a location and writes the active thread to it in the given format.
+    fn export_thread(
+        &mut self,
+        format: ThreadExportFormat,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let thread = self.active_thread(cx);
+        let contents = match format {
+            ThreadExportFormat::Markdown => Ok(thread.read(cx).to_markdown()),
+            ThreadExportFormat::Json => ExportedThread::new(thread.read(cx)).to_json(),
+        };
+
+        let start_directory = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .unwrap_or_else(|| paths::home_dir().clone());
+        let path = cx.prompt_for_new_path(&start_directory);
+        let fs = self.fs.clone();
+
+        cx.spawn_in(window, |_, _| async move {
+            let contents = contents?;
+            let Some(mut path) = path.await?? else {
+                return Ok(());
+            };
+            if path.extension().is_none() {
+                path.set_extension(format.extension());
+            }
+            fs.atomic_write(path, contents).await
+        })
+        .detach_and_prompt_err("Failed to export thread", window, cx, |_, _, _| None);
+    }
+
+    /// Prompts for a previously exported JSON thread and adds it to the thread history.
+    fn import_thread(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        let fs = self.fs.clone();
+        let thread_store = self.thread_store.clone();
+
+        cx.spawn_in(window, |this, mut cx| async move {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let json = fs.load(&path).await?;
+            let exported = ExportedThread::from_json(&json)?;
+            let thread_id = thread_store
+                .update(&mut cx, |thread_store, cx| {
+                    thread_store.import_thread(exported.thread, cx)
+                })?
+                .await?;
+
+            this.update_in(&mut cx, |this, window, cx| {
+                this.open_thread(&thread_id, window, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to import thread", window, cx, |_, _, _| None);
+    }
 }
 
 impl Focusable for AssistantPanel {
@@ -692,6 +826,20 @@ impl AssistantPanel {
                                             "Prompt Editor History",
                                             OpenPromptEditorHistory.boxed_clone(),
                                         )
+                                        .separator()
+                                        .action(
+                                            "Open Thread as Markdown",
+                                            OpenThreadAsMarkdown.boxed_clone(),
+                                        )
+                                        .action(
+                                            "Export Thread as Markdown…",
+                                            ExportThreadAsMarkdown.boxed_clone(),
+                                        )
+                                        .action(
+                                            "Export Thread as JSON…",
+                                            ExportThreadAsJson.boxed_clone(),
+                                        )
+                                        .action("Import Thread…", ImportThread.boxed_clone())
                                 }))
                             }),
                     )
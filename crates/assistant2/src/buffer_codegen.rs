@@ -1,6 +1,7 @@
 use crate::context::attach_context_to_message;
 use crate::context_store::ContextStore;
 use crate::inline_prompt_editor::CodegenStatus;
+use crate::project_rules::ProjectRules;
 use anyhow::{Context as _, Result};
 use client::telemetry::Telemetry;
 use collections::HashSet;
@@ -40,6 +41,7 @@ pub struct BufferCodegen {
     range: Range<Anchor>,
     initial_transaction_id: Option<TransactionId>,
     context_store: Entity<ContextStore>,
+    project_rules: Option<Entity<ProjectRules>>,
     telemetry: Arc<Telemetry>,
     builder: Arc<PromptBuilder>,
     pub is_insertion: bool,
@@ -51,6 +53,7 @@ impl BufferCodegen {
         range: Range<Anchor>,
         initial_transaction_id: Option<TransactionId>,
         context_store: Entity<ContextStore>,
+        project_rules: Option<Entity<ProjectRules>>,
         telemetry: Arc<Telemetry>,
         builder: Arc<PromptBuilder>,
         cx: &mut Context<Self>,
@@ -61,6 +64,7 @@ impl BufferCodegen {
                 range.clone(),
                 false,
                 Some(context_store.clone()),
+                project_rules.clone(),
                 Some(telemetry.clone()),
                 builder.clone(),
                 cx,
@@ -76,6 +80,7 @@ impl BufferCodegen {
             range,
             initial_transaction_id,
             context_store,
+            project_rules,
             telemetry,
             builder,
         };
@@ -149,6 +154,7 @@ impl BufferCodegen {
                     self.range.clone(),
                     false,
                     Some(self.context_store.clone()),
+                    self.project_rules.clone(),
                     Some(self.telemetry.clone()),
                     self.builder.clone(),
                     cx,
@@ -229,6 +235,7 @@ pub struct CodegenAlternative {
     generation: Task<()>,
     diff: Diff,
     context_store: Option<Entity<ContextStore>>,
+    project_rules: Option<Entity<ProjectRules>>,
     telemetry: Option<Arc<Telemetry>>,
     _subscription: gpui::Subscription,
     builder: Arc<PromptBuilder>,
@@ -249,6 +256,7 @@ impl CodegenAlternative {
         range: Range<Anchor>,
         active: bool,
         context_store: Option<Entity<ContextStore>>,
+        project_rules: Option<Entity<ProjectRules>>,
         telemetry: Option<Arc<Telemetry>>,
         builder: Arc<PromptBuilder>,
         cx: &mut Context<Self>,
@@ -290,6 +298,7 @@ impl CodegenAlternative {
             generation: Task::ready(()),
             diff: Diff::default(),
             context_store,
+            project_rules,
             telemetry,
             _subscription: cx.subscribe(&buffer, Self::handle_buffer_event),
             builder,
@@ -420,11 +429,25 @@ impl CodegenAlternative {
 
         request_message.content.push(prompt.into());
 
+        let mut messages = Vec::new();
+        if let Some(rules) = self
+            .project_rules
+            .as_ref()
+            .and_then(|project_rules| project_rules.read(cx).text(cx))
+        {
+            messages.push(LanguageModelRequestMessage {
+                role: Role::System,
+                content: vec![rules.into()],
+                cache: false,
+            });
+        }
+        messages.push(request_message);
+
         Ok(LanguageModelRequest {
             tools: Vec::new(),
             stop: Vec::new(),
             temperature: None,
-            messages: vec![request_message],
+            messages,
         })
     }
 
@@ -1091,6 +1114,7 @@ mod tests {
                 true,
                 None,
                 None,
+                None,
                 prompt_builder,
                 cx,
             )
@@ -1155,6 +1179,7 @@ mod tests {
                 true,
                 None,
                 None,
+                None,
                 prompt_builder,
                 cx,
             )
@@ -1222,6 +1247,7 @@ mod tests {
                 true,
                 None,
                 None,
+                None,
                 prompt_builder,
                 cx,
             )
@@ -1289,6 +1315,7 @@ mod tests {
                 true,
                 None,
                 None,
+                None,
                 prompt_builder,
                 cx,
             )
@@ -1344,6 +1371,7 @@ mod tests {
                 false,
                 None,
                 None,
+                None,
                 prompt_builder,
                 cx,
             )
@@ -1,10 +1,13 @@
+use std::ops::Range;
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use file_icons::FileIcons;
-use gpui::{App, Entity, SharedString};
+use gpui::{App, Entity, EntityId, SharedString};
 use language::Buffer;
 use language_model::{LanguageModelRequestMessage, MessageContent};
+use project::ProjectPath;
 use serde::{Deserialize, Serialize};
 use text::BufferId;
 use ui::IconName;
@@ -22,7 +25,7 @@ impl ContextId {
 }
 
 /// Some context attached to a message in a thread.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextSnapshot {
     pub id: ContextId,
     pub name: SharedString,
@@ -34,12 +37,17 @@ pub struct ContextSnapshot {
     pub text: Box<[SharedString]>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ContextKind {
     File,
     Directory,
     FetchedUrl,
     Thread,
+    Symbol,
+    Diagnostics,
+    GitDiff,
+    Selection,
+    Terminal,
 }
 
 impl ContextKind {
@@ -49,6 +57,11 @@ impl ContextKind {
             ContextKind::Directory => "Folder",
             ContextKind::FetchedUrl => "Fetch",
             ContextKind::Thread => "Thread",
+            ContextKind::Symbol => "Symbol",
+            ContextKind::Diagnostics => "Diagnostics",
+            ContextKind::GitDiff => "Git Diff",
+            ContextKind::Selection => "Selection",
+            ContextKind::Terminal => "Terminal",
         }
     }
 
@@ -58,6 +71,11 @@ impl ContextKind {
             ContextKind::Directory => IconName::Folder,
             ContextKind::FetchedUrl => IconName::Globe,
             ContextKind::Thread => IconName::MessageCircle,
+            ContextKind::Symbol => IconName::Code,
+            ContextKind::Diagnostics => IconName::Warning,
+            ContextKind::GitDiff => IconName::Diff,
+            ContextKind::Selection => IconName::CursorIBeam,
+            ContextKind::Terminal => IconName::Terminal,
         }
     }
 }
@@ -68,6 +86,11 @@ pub enum AssistantContext {
     Directory(DirectoryContext),
     FetchedUrl(FetchedUrlContext),
     Thread(ThreadContext),
+    Symbol(SymbolContext),
+    Diagnostics(DiagnosticsContext),
+    GitDiff(GitDiffContext),
+    Selection(SelectionContext),
+    Terminal(TerminalContext),
 }
 
 impl AssistantContext {
@@ -77,6 +100,11 @@ impl AssistantContext {
             Self::Directory(directory) => directory.snapshot.id,
             Self::FetchedUrl(url) => url.id,
             Self::Thread(thread) => thread.id,
+            Self::Symbol(symbol) => symbol.id,
+            Self::Diagnostics(diagnostics) => diagnostics.id,
+            Self::GitDiff(git_diff) => git_diff.id,
+            Self::Selection(selection) => selection.id,
+            Self::Terminal(terminal) => terminal.id,
         }
     }
 }
@@ -111,6 +139,50 @@ pub struct ThreadContext {
     pub text: SharedString,
 }
 
+#[derive(Debug)]
+pub struct SymbolContext {
+    pub id: ContextId,
+    pub project_path: ProjectPath,
+    pub name: SharedString,
+    /// The range of the symbol, in rows.
+    pub row_range: Range<u32>,
+    pub text: SharedString,
+}
+
+#[derive(Debug)]
+pub struct DiagnosticsContext {
+    pub id: ContextId,
+    /// The file the diagnostics belong to, or `None` for the whole project.
+    pub project_path: Option<ProjectPath>,
+    pub name: SharedString,
+    pub text: SharedString,
+}
+
+/// The uncommitted changes in the active repository.
+#[derive(Debug)]
+pub struct GitDiffContext {
+    pub id: ContextId,
+    pub repository_name: SharedString,
+    pub text: SharedString,
+}
+
+#[derive(Debug)]
+pub struct SelectionContext {
+    pub id: ContextId,
+    pub path: Arc<Path>,
+    /// The selected rows, starting from zero.
+    pub row_range: Range<u32>,
+    pub text: SharedString,
+}
+
+#[derive(Debug)]
+pub struct TerminalContext {
+    pub id: ContextId,
+    pub terminal_id: EntityId,
+    pub title: SharedString,
+    pub text: SharedString,
+}
+
 // TODO: Model<Buffer> holds onto the buffer even if the file is deleted and closed. Should remove
 // the context from the message editor in this case.
 
@@ -129,6 +201,11 @@ impl AssistantContext {
             Self::Directory(directory_context) => Some(directory_context.snapshot()),
             Self::FetchedUrl(fetched_url_context) => Some(fetched_url_context.snapshot()),
             Self::Thread(thread_context) => Some(thread_context.snapshot(cx)),
+            Self::Symbol(symbol_context) => Some(symbol_context.snapshot()),
+            Self::Diagnostics(diagnostics_context) => Some(diagnostics_context.snapshot()),
+            Self::GitDiff(git_diff_context) => Some(git_diff_context.snapshot()),
+            Self::Selection(selection_context) => Some(selection_context.snapshot(cx)),
+            Self::Terminal(terminal_context) => Some(terminal_context.snapshot()),
         }
     }
 }
@@ -235,6 +312,96 @@ impl ThreadContext {
     }
 }
 
+impl SymbolContext {
+    pub fn snapshot(&self) -> ContextSnapshot {
+        let path = self.project_path.path.to_string_lossy().into_owned();
+        let parent = self
+            .project_path
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into());
+
+        ContextSnapshot {
+            id: self.id,
+            name: self.name.clone(),
+            parent,
+            tooltip: Some(format!("{path}:{}", self.row_range.start + 1).into()),
+            icon_path: None,
+            kind: ContextKind::Symbol,
+            text: Box::new([self.text.clone()]),
+        }
+    }
+}
+
+impl DiagnosticsContext {
+    pub fn snapshot(&self) -> ContextSnapshot {
+        ContextSnapshot {
+            id: self.id,
+            name: self.name.clone(),
+            parent: None,
+            tooltip: self
+                .project_path
+                .as_ref()
+                .map(|project_path| project_path.path.to_string_lossy().into_owned().into()),
+            icon_path: None,
+            kind: ContextKind::Diagnostics,
+            text: Box::new([self.text.clone()]),
+        }
+    }
+}
+
+impl GitDiffContext {
+    pub fn snapshot(&self) -> ContextSnapshot {
+        ContextSnapshot {
+            id: self.id,
+            name: "Git Diff".into(),
+            parent: Some(self.repository_name.clone()),
+            tooltip: Some(format!("Uncommitted changes in {}", self.repository_name).into()),
+            icon_path: None,
+            kind: ContextKind::GitDiff,
+            text: Box::new([self.text.clone()]),
+        }
+    }
+}
+
+impl SelectionContext {
+    pub fn snapshot(&self, cx: &App) -> ContextSnapshot {
+        let name = match self.path.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => self.path.to_string_lossy().into_owned(),
+        };
+        let lines = if self.row_range.start == self.row_range.end {
+            format!("{}", self.row_range.start + 1)
+        } else {
+            format!("{}-{}", self.row_range.start + 1, self.row_range.end + 1)
+        };
+
+        ContextSnapshot {
+            id: self.id,
+            name: format!("{name}:{lines}").into(),
+            parent: None,
+            tooltip: Some(format!("{}:{lines}", self.path.to_string_lossy()).into()),
+            icon_path: FileIcons::get_icon(&self.path, cx),
+            kind: ContextKind::Selection,
+            text: Box::new([self.text.clone()]),
+        }
+    }
+}
+
+impl TerminalContext {
+    pub fn snapshot(&self) -> ContextSnapshot {
+        ContextSnapshot {
+            id: self.id,
+            name: self.title.clone(),
+            parent: None,
+            tooltip: None,
+            icon_path: None,
+            kind: ContextKind::Terminal,
+            text: Box::new([self.text.clone()]),
+        }
+    }
+}
+
 pub fn attach_context_to_message(
     message: &mut LanguageModelRequestMessage,
     contexts: impl Iterator<Item = ContextSnapshot>,
@@ -243,6 +410,11 @@ pub fn attach_context_to_message(
     let mut directory_context = Vec::new();
     let mut fetch_context = Vec::new();
     let mut thread_context = Vec::new();
+    let mut symbol_context = Vec::new();
+    let mut diagnostics_context = Vec::new();
+    let mut git_diff_context = Vec::new();
+    let mut selection_context = Vec::new();
+    let mut terminal_context = Vec::new();
 
     let mut capacity = 0;
     for context in contexts {
@@ -252,6 +424,11 @@ pub fn attach_context_to_message(
             ContextKind::Directory => directory_context.push(context),
             ContextKind::FetchedUrl => fetch_context.push(context),
             ContextKind::Thread => thread_context.push(context),
+            ContextKind::Symbol => symbol_context.push(context),
+            ContextKind::Diagnostics => diagnostics_context.push(context),
+            ContextKind::GitDiff => git_diff_context.push(context),
+            ContextKind::Selection => selection_context.push(context),
+            ContextKind::Terminal => terminal_context.push(context),
         }
     }
     if !file_context.is_empty() {
@@ -266,6 +443,17 @@ pub fn attach_context_to_message(
     if !thread_context.is_empty() {
         capacity += 1 + thread_context.len();
     }
+    for context in [
+        &symbol_context,
+        &diagnostics_context,
+        &git_diff_context,
+        &selection_context,
+        &terminal_context,
+    ] {
+        if !context.is_empty() {
+            capacity += 1;
+        }
+    }
     if capacity == 0 {
         return;
     }
@@ -310,6 +498,35 @@ pub fn attach_context_to_message(
         }
     }
 
+    for (header, context) in [
+        ("The following symbols are available:\n", &symbol_context),
+        (
+            "The following diagnostics are available:\n",
+            &diagnostics_context,
+        ),
+        (
+            "The following uncommitted changes are available:\n",
+            &git_diff_context,
+        ),
+        (
+            "The following selections are available:\n",
+            &selection_context,
+        ),
+        (
+            "The following terminal output is available:\n",
+            &terminal_context,
+        ),
+    ] {
+        if !context.is_empty() {
+            context_chunks.push(header);
+            for context in context {
+                for chunk in &context.text {
+                    context_chunks.push(&chunk);
+                }
+            }
+        }
+    }
+
     debug_assert!(
         context_chunks.len() == capacity,
         "attach_context_message calculated capacity of {}, but length was {}",
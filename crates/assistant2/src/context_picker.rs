@@ -1,6 +1,8 @@
+mod diagnostics_context_picker;
 mod directory_context_picker;
 mod fetch_context_picker;
 mod file_context_picker;
+mod symbol_context_picker;
 mod thread_context_picker;
 
 use std::path::PathBuf;
@@ -11,14 +13,18 @@ use editor::Editor;
 use file_context_picker::render_file_context_entry;
 use gpui::{App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity};
 use project::ProjectPath;
+use terminal::Terminal;
+use terminal_view::{terminal_panel::TerminalPanel, TerminalView};
 use thread_context_picker::{render_thread_context_entry, ThreadContextEntry};
 use ui::{prelude::*, ContextMenu, ContextMenuEntry, ContextMenuItem};
-use workspace::{notifications::NotifyResultExt, Workspace};
+use workspace::{dock::Panel as _, notifications::NotifyResultExt, Workspace};
 
 use crate::context::ContextKind;
+use crate::context_picker::diagnostics_context_picker::DiagnosticsContextPicker;
 use crate::context_picker::directory_context_picker::DirectoryContextPicker;
 use crate::context_picker::fetch_context_picker::FetchContextPicker;
 use crate::context_picker::file_context_picker::FileContextPicker;
+use crate::context_picker::symbol_context_picker::SymbolContextPicker;
 use crate::context_picker::thread_context_picker::ThreadContextPicker;
 use crate::context_store::ContextStore;
 use crate::thread_store::ThreadStore;
@@ -37,6 +43,8 @@ enum ContextPickerMode {
     Directory(Entity<DirectoryContextPicker>),
     Fetch(Entity<FetchContextPicker>),
     Thread(Entity<ThreadContextPicker>),
+    Symbol(Entity<SymbolContextPicker>),
+    Diagnostics(Entity<DiagnosticsContextPicker>),
 }
 
 pub(super) struct ContextPicker {
@@ -96,6 +104,17 @@ impl ContextPicker {
             if self.allow_threads() {
                 context_kinds.push(ContextKind::Thread);
             }
+            context_kinds.extend([
+                ContextKind::Symbol,
+                ContextKind::Diagnostics,
+                ContextKind::GitDiff,
+            ]);
+            if self.active_editor(cx).is_some() {
+                context_kinds.push(ContextKind::Selection);
+            }
+            if self.active_terminal(cx).is_some() {
+                context_kinds.push(ContextKind::Terminal);
+            }
 
             let menu = menu
                 .when(has_recent, |menu| {
@@ -198,12 +217,109 @@ impl ContextPicker {
                     }));
                 }
             }
+            ContextKind::Symbol => {
+                self.mode = ContextPickerMode::Symbol(cx.new(|cx| {
+                    SymbolContextPicker::new(
+                        context_picker.clone(),
+                        self.workspace.clone(),
+                        self.context_store.clone(),
+                        self.confirm_behavior,
+                        window,
+                        cx,
+                    )
+                }));
+            }
+            ContextKind::Diagnostics => {
+                self.mode = ContextPickerMode::Diagnostics(cx.new(|cx| {
+                    DiagnosticsContextPicker::new(
+                        context_picker.clone(),
+                        self.workspace.clone(),
+                        self.context_store.clone(),
+                        self.confirm_behavior,
+                        window,
+                        cx,
+                    )
+                }));
+            }
+            // The remaining kinds are added immediately, without a picker.
+            ContextKind::GitDiff => {
+                let Some(context_store) = self.context_store.upgrade() else {
+                    return;
+                };
+                let task =
+                    context_store.update(cx, |context_store, cx| context_store.add_git_diff(cx));
+                self.add_context_and_confirm(task, window, cx);
+                return;
+            }
+            ContextKind::Selection => {
+                let Some(editor) = self.active_editor(cx) else {
+                    return;
+                };
+                let result = self
+                    .context_store
+                    .update(cx, |context_store, cx| {
+                        context_store.add_selection(editor, cx)
+                    })
+                    .and_then(|result| result);
+                self.add_context_and_confirm(Task::ready(result), window, cx);
+                return;
+            }
+            ContextKind::Terminal => {
+                let Some(terminal) = self.active_terminal(cx) else {
+                    return;
+                };
+                let result = self.context_store.update(cx, |context_store, cx| {
+                    context_store.add_terminal_output(terminal, cx)
+                });
+                self.add_context_and_confirm(Task::ready(result), window, cx);
+                return;
+            }
         }
 
         cx.notify();
         cx.focus_self(window);
     }
 
+    /// Waits for context to be added, then closes the picker if it's configured to close on
+    /// confirm.
+    fn add_context_and_confirm(
+        &self,
+        task: Task<Result<()>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let confirm_behavior = self.confirm_behavior;
+        cx.spawn_in(window, |this, mut cx| async move {
+            if task.await.notify_async_err(&mut cx).is_some() {
+                this.update(&mut cx, |_, cx| match confirm_behavior {
+                    ConfirmBehavior::KeepOpen => cx.notify(),
+                    ConfirmBehavior::Close => cx.emit(DismissEvent),
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+
+    /// Returns the editor in the center of the workspace, whose selection can be added as context.
+    fn active_editor(&self, cx: &App) -> Option<Entity<Editor>> {
+        let workspace = self.workspace.upgrade()?;
+        workspace.read(cx).active_item(cx)?.act_as::<Editor>(cx)
+    }
+
+    /// Returns the active terminal in the terminal panel, or in the center of the workspace.
+    fn active_terminal(&self, cx: &App) -> Option<Entity<Terminal>> {
+        let workspace = self.workspace.upgrade()?;
+        let workspace = workspace.read(cx);
+        let terminal_view = workspace
+            .panel::<TerminalPanel>(cx)
+            .and_then(|terminal_panel| terminal_panel.read(cx).pane())
+            .and_then(|pane| pane.read(cx).active_item())
+            .and_then(|item| item.downcast::<TerminalView>())
+            .or_else(|| workspace.active_item(cx)?.act_as::<TerminalView>(cx))?;
+        Some(terminal_view.read(cx).terminal().clone())
+    }
+
     fn recent_menu_item(
         &self,
         context_picker: Entity<ContextPicker>,
@@ -397,6 +513,10 @@ impl Focusable for ContextPicker {
             ContextPickerMode::Directory(directory_picker) => directory_picker.focus_handle(cx),
             ContextPickerMode::Fetch(fetch_picker) => fetch_picker.focus_handle(cx),
             ContextPickerMode::Thread(thread_picker) => thread_picker.focus_handle(cx),
+            ContextPickerMode::Symbol(symbol_picker) => symbol_picker.focus_handle(cx),
+            ContextPickerMode::Diagnostics(diagnostics_picker) => {
+                diagnostics_picker.focus_handle(cx)
+            }
         }
     }
 }
@@ -414,6 +534,10 @@ impl Render for ContextPicker {
                 }
                 ContextPickerMode::Fetch(fetch_picker) => parent.child(fetch_picker.clone()),
                 ContextPickerMode::Thread(thread_picker) => parent.child(thread_picker.clone()),
+                ContextPickerMode::Symbol(symbol_picker) => parent.child(symbol_picker.clone()),
+                ContextPickerMode::Diagnostics(diagnostics_picker) => {
+                    parent.child(diagnostics_picker.clone())
+                }
             })
     }
 }
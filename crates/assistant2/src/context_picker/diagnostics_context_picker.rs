@@ -0,0 +1,280 @@
+use std::sync::Arc;
+
+use collections::BTreeMap;
+use fuzzy::StringMatchCandidate;
+use gpui::{App, DismissEvent, Entity, FocusHandle, Focusable, Task, WeakEntity};
+use picker::{Picker, PickerDelegate};
+use project::{DiagnosticSummary, ProjectPath};
+use ui::{prelude::*, ListItem};
+use workspace::{notifications::NotifyResultExt, Workspace};
+
+use crate::context_picker::{ConfirmBehavior, ContextPicker};
+use crate::context_store::ContextStore;
+
+pub struct DiagnosticsContextPicker {
+    picker: Entity<Picker<DiagnosticsContextPickerDelegate>>,
+}
+
+impl DiagnosticsContextPicker {
+    pub fn new(
+        context_picker: WeakEntity<ContextPicker>,
+        workspace: WeakEntity<Workspace>,
+        context_store: WeakEntity<ContextStore>,
+        confirm_behavior: ConfirmBehavior,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = DiagnosticsContextPickerDelegate::new(
+            context_picker,
+            workspace,
+            context_store,
+            confirm_behavior,
+        );
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        Self { picker }
+    }
+}
+
+impl Focusable for DiagnosticsContextPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for DiagnosticsContextPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        self.picker.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DiagnosticsEntry {
+    /// The file the diagnostics belong to, or `None` for the whole project.
+    project_path: Option<ProjectPath>,
+    label: SharedString,
+    summary: DiagnosticSummary,
+}
+
+pub struct DiagnosticsContextPickerDelegate {
+    context_picker: WeakEntity<ContextPicker>,
+    workspace: WeakEntity<Workspace>,
+    context_store: WeakEntity<ContextStore>,
+    confirm_behavior: ConfirmBehavior,
+    matches: Vec<DiagnosticsEntry>,
+    selected_index: usize,
+}
+
+impl DiagnosticsContextPickerDelegate {
+    pub fn new(
+        context_picker: WeakEntity<ContextPicker>,
+        workspace: WeakEntity<Workspace>,
+        context_store: WeakEntity<ContextStore>,
+        confirm_behavior: ConfirmBehavior,
+    ) -> Self {
+        Self {
+            context_picker,
+            workspace,
+            context_store,
+            confirm_behavior,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn entries(&self, cx: &App) -> Vec<DiagnosticsEntry> {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return Vec::new();
+        };
+        let project = workspace.read(cx).project().read(cx);
+
+        let mut summaries_by_path = BTreeMap::<ProjectPath, DiagnosticSummary>::default();
+        for (project_path, _, summary) in project.diagnostic_summaries(false, cx) {
+            let path_summary = summaries_by_path.entry(project_path).or_default();
+            path_summary.error_count += summary.error_count;
+            path_summary.warning_count += summary.warning_count;
+        }
+
+        let mut entries = Vec::with_capacity(summaries_by_path.len() + 1);
+        entries.push(DiagnosticsEntry {
+            project_path: None,
+            label: "Entire Project".into(),
+            summary: project.diagnostic_summary(false, cx),
+        });
+        entries.extend(
+            summaries_by_path
+                .into_iter()
+                .map(|(project_path, summary)| DiagnosticsEntry {
+                    label: project_path.path.to_string_lossy().into_owned().into(),
+                    project_path: Some(project_path),
+                    summary,
+                }),
+        );
+        entries
+    }
+}
+
+impl PickerDelegate for DiagnosticsContextPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search files with diagnostics…".into()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let entries = self.entries(cx);
+
+        let executor = cx.background_executor().clone();
+        let search_task = cx.background_executor().spawn(async move {
+            if query.is_empty() {
+                entries
+            } else {
+                let candidates = entries
+                    .iter()
+                    .enumerate()
+                    .map(|(id, entry)| StringMatchCandidate::new(id, &entry.label))
+                    .collect::<Vec<_>>();
+                let matches = fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    executor,
+                )
+                .await;
+
+                matches
+                    .into_iter()
+                    .map(|mat| entries[mat.candidate_id].clone())
+                    .collect()
+            }
+        });
+
+        cx.spawn_in(window, |this, mut cx| async move {
+            let matches = search_task.await;
+            this.update(&mut cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index) else {
+            return;
+        };
+
+        let project_path = entry.project_path.clone();
+        let Some(task) = self
+            .context_store
+            .update(cx, |context_store, cx| {
+                context_store.add_diagnostics(project_path, cx)
+            })
+            .ok()
+        else {
+            return;
+        };
+
+        let confirm_behavior = self.confirm_behavior;
+        cx.spawn_in(window, |this, mut cx| async move {
+            match task.await.notify_async_err(&mut cx) {
+                None => anyhow::Ok(()),
+                Some(()) => this.update_in(&mut cx, |this, window, cx| match confirm_behavior {
+                    ConfirmBehavior::KeepOpen => {}
+                    ConfirmBehavior::Close => this.delegate.dismissed(window, cx),
+                }),
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+        let added = self.context_store.upgrade().map_or(false, |context_store| {
+            context_store
+                .read(cx)
+                .includes_diagnostics(entry.project_path.as_ref())
+                .is_some()
+        });
+
+        Some(
+            ListItem::new(ix).inset(true).toggle_state(selected).child(
+                h_flex()
+                    .gap_1p5()
+                    .w_full()
+                    .child(
+                        Icon::new(IconName::Warning)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(Label::new(entry.label.clone()))
+                    .child(div().w_full())
+                    .when(entry.summary.error_count > 0, |el| {
+                        el.child(
+                            Label::new(format!("{} errors", entry.summary.error_count))
+                                .size(LabelSize::Small)
+                                .color(Color::Error),
+                        )
+                    })
+                    .when(entry.summary.warning_count > 0, |el| {
+                        el.child(
+                            Label::new(format!("{} warnings", entry.summary.warning_count))
+                                .size(LabelSize::Small)
+                                .color(Color::Warning),
+                        )
+                    })
+                    .when(added, |el| {
+                        el.child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Icon::new(IconName::Check)
+                                        .size(IconSize::Small)
+                                        .color(Color::Success),
+                                )
+                                .child(Label::new("Added").size(LabelSize::Small)),
+                        )
+                    }),
+            ),
+        )
+    }
+}
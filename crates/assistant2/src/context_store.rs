@@ -1,23 +1,32 @@
+use std::fmt::Write as _;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Result};
-use collections::{BTreeMap, HashMap, HashSet};
+use collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use editor::Editor;
 use futures::{self, future, Future, FutureExt};
-use gpui::{App, AsyncApp, Context, Entity, SharedString, Task, WeakEntity};
-use language::Buffer;
-use project::{ProjectPath, Worktree};
+use gpui::{App, AsyncApp, Context, Entity, EntityId, SharedString, Task, WeakEntity};
+use language::{Bias, Buffer, BufferSnapshot, DiagnosticSeverity, Point, ToPoint as _};
+use project::{Project, ProjectPath, Symbol, Worktree};
 use rope::Rope;
+use terminal::Terminal;
 use text::BufferId;
+use util::ResultExt as _;
 use workspace::Workspace;
 
 use crate::context::{
-    AssistantContext, ContextBuffer, ContextId, ContextSnapshot, DirectoryContext,
-    FetchedUrlContext, FileContext, ThreadContext,
+    AssistantContext, ContextBuffer, ContextId, ContextSnapshot, DiagnosticsContext,
+    DirectoryContext, FetchedUrlContext, FileContext, GitDiffContext, SelectionContext,
+    SymbolContext, TerminalContext, ThreadContext,
 };
 use crate::context_strip::SuggestedContext;
 use crate::thread::{Thread, ThreadId};
 
+/// The number of lines of terminal output to include when adding a terminal as context.
+const TERMINAL_CONTEXT_LINES: usize = 100;
+
 pub struct ContextStore {
     workspace: WeakEntity<Workspace>,
     context: Vec<AssistantContext>,
@@ -42,6 +51,12 @@ impl ContextStore {
         }
     }
 
+    /// Makes the context added from now on use IDs starting at `next_context_id`, such as those
+    /// after the context of a thread that was loaded.
+    pub fn set_next_context_id(&mut self, next_context_id: ContextId) {
+        self.next_context_id = self.next_context_id.max(next_context_id);
+    }
+
     pub fn snapshot<'a>(&'a self, cx: &'a App) -> impl Iterator<Item = ContextSnapshot> + 'a {
         self.context()
             .iter()
@@ -284,6 +299,279 @@ impl ContextStore {
             }));
     }
 
+    pub fn add_symbol(&mut self, symbol: Symbol, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let Some(project) = self.project(cx) else {
+            return Task::ready(Err(anyhow!("failed to read project")));
+        };
+
+        if let Some(context_id) = self.includes_symbol(&symbol.path, &symbol.name) {
+            self.remove_context(context_id);
+            return Task::ready(Ok(()));
+        }
+
+        let open_buffer_task = project.update(cx, |project, cx| {
+            project.open_buffer_for_symbol(&symbol, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let buffer = open_buffer_task.await?;
+            this.update(&mut cx, |this, cx| {
+                let snapshot = buffer.read(cx).snapshot();
+                let start = snapshot
+                    .clip_point_utf16(symbol.range.start, Bias::Left)
+                    .to_point(&snapshot);
+                let end = snapshot
+                    .clip_point_utf16(symbol.range.end, Bias::Left)
+                    .to_point(&snapshot);
+                let row_range = start.row..end.row;
+                let content = snapshot
+                    .text_for_range(
+                        Point::new(row_range.start, 0)
+                            ..Point::new(row_range.end, snapshot.line_len(row_range.end)),
+                    )
+                    .collect::<String>();
+                let text = to_fenced_excerpt(&symbol.path.path, &row_range, &content);
+
+                let id = this.next_context_id.post_inc();
+                this.context.push(AssistantContext::Symbol(SymbolContext {
+                    id,
+                    project_path: symbol.path,
+                    name: symbol.name.into(),
+                    row_range,
+                    text,
+                }));
+            })
+        })
+    }
+
+    /// Adds the diagnostics for the given file, or for every file in the project if `None`.
+    pub fn add_diagnostics(
+        &mut self,
+        project_path: Option<ProjectPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project) = self.project(cx) else {
+            return Task::ready(Err(anyhow!("failed to read project")));
+        };
+
+        if let Some(context_id) = self.includes_diagnostics(project_path.as_ref()) {
+            self.remove_context(context_id);
+            return Task::ready(Ok(()));
+        }
+
+        let paths = match &project_path {
+            Some(project_path) => vec![project_path.clone()],
+            None => project
+                .read(cx)
+                .diagnostic_summaries(false, cx)
+                .map(|(path, _, _)| path)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        };
+
+        let open_buffers_task = project.update(cx, |project, cx| {
+            future::join_all(
+                paths
+                    .into_iter()
+                    .map(|path| project.open_buffer(path, cx))
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            let buffers = open_buffers_task.await;
+
+            this.update(&mut cx, |this, cx| {
+                let mut text = String::new();
+                for buffer in buffers.into_iter().flat_map(|buffer| buffer.log_err()) {
+                    let buffer = buffer.read(cx);
+                    if let Some(path) = buffer_path_log_err(buffer) {
+                        collect_buffer_diagnostics(&path, &buffer.snapshot(), &mut text);
+                    }
+                }
+
+                if text.is_empty() {
+                    bail!("No diagnostics found");
+                }
+
+                let name = match &project_path {
+                    Some(project_path) => match project_path.path.file_name() {
+                        Some(name) => name.to_string_lossy().into_owned().into(),
+                        None => project_path.path.to_string_lossy().into_owned().into(),
+                    },
+                    None => "Project Diagnostics".into(),
+                };
+
+                let id = this.next_context_id.post_inc();
+                this.context
+                    .push(AssistantContext::Diagnostics(DiagnosticsContext {
+                        id,
+                        project_path,
+                        name,
+                        text: text.into(),
+                    }));
+
+                anyhow::Ok(())
+            })?
+        })
+    }
+
+    /// Adds the uncommitted changes in the project's active repository.
+    pub fn add_git_diff(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let Some(project) = self.project(cx) else {
+            return Task::ready(Err(anyhow!("failed to read project")));
+        };
+
+        if let Some(context_id) = self.includes_git_diff() {
+            self.remove_context(context_id);
+            return Task::ready(Ok(()));
+        }
+
+        let Some(repository) = project.read(cx).active_repository(cx) else {
+            return Task::ready(Err(anyhow!("no git repository found")));
+        };
+        let repository_name = repository.display_name(project.read(cx), cx);
+        let changed_files = repository
+            .status()
+            .filter_map(|entry| {
+                let project_path = repository.unrelativize(&entry.repo_path)?;
+                let committed_text = repository.load_committed_text(entry.repo_path, cx);
+                Some((project_path, entry.status.is_deleted(), committed_text))
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(|this, mut cx| async move {
+            let mut file_diffs = Vec::new();
+            for (project_path, is_deleted, committed_text) in changed_files {
+                let Some(committed_text) = committed_text.await.log_err() else {
+                    continue;
+                };
+                let text = if is_deleted {
+                    None
+                } else {
+                    let Some(buffer) = project
+                        .update(&mut cx, |project, cx| {
+                            project.open_buffer(project_path.clone(), cx)
+                        })?
+                        .await
+                        .log_err()
+                    else {
+                        continue;
+                    };
+                    Some(buffer.read_with(&cx, |buffer, _| buffer.text())?)
+                };
+                if committed_text != text {
+                    file_diffs.push((project_path.path, committed_text, text));
+                }
+            }
+
+            if file_diffs.is_empty() {
+                bail!("No uncommitted changes found");
+            }
+
+            let text = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut diff = String::from("```diff\n");
+                    for (path, committed_text, text) in file_diffs {
+                        diff.push_str(&unified_diff(
+                            &path,
+                            committed_text.as_deref(),
+                            text.as_deref(),
+                        ));
+                    }
+                    diff.push_str("```\n");
+                    diff
+                })
+                .await;
+
+            this.update(&mut cx, |this, _cx| {
+                let id = this.next_context_id.post_inc();
+                this.context.push(AssistantContext::GitDiff(GitDiffContext {
+                    id,
+                    repository_name,
+                    text: text.into(),
+                }));
+            })
+        })
+    }
+
+    /// Adds the newest selection in the given editor.
+    pub fn add_selection(&mut self, editor: Entity<Editor>, cx: &mut Context<Self>) -> Result<()> {
+        let selection = editor.update(cx, |editor, cx| editor.selections.newest::<Point>(cx));
+        if selection.is_empty() {
+            bail!("Nothing is selected");
+        }
+
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let (buffer, start, _) = snapshot
+            .point_to_buffer_point(selection.start)
+            .ok_or_else(|| anyhow!("invalid selection"))?;
+        let (end_buffer, end, _) = snapshot
+            .point_to_buffer_point(selection.end)
+            .ok_or_else(|| anyhow!("invalid selection"))?;
+        if buffer.remote_id() != end_buffer.remote_id() {
+            bail!("Selections spanning multiple files are not supported");
+        }
+        let path = buffer
+            .file()
+            .map(|file| file.path().clone())
+            .ok_or_else(|| anyhow!("Buffer has no path."))?;
+
+        let row_range = selection_row_range(start, end);
+        if let Some(context_id) = self.includes_selection(&path, &row_range) {
+            self.remove_context(context_id);
+            return Ok(());
+        }
+
+        let content = buffer.text_for_range(start..end).collect::<String>();
+        let text = to_fenced_excerpt(&path, &row_range, &content);
+
+        let id = self.next_context_id.post_inc();
+        self.context
+            .push(AssistantContext::Selection(SelectionContext {
+                id,
+                path,
+                row_range,
+                text,
+            }));
+
+        Ok(())
+    }
+
+    /// Adds the most recent output of the given terminal.
+    pub fn add_terminal_output(&mut self, terminal: Entity<Terminal>, cx: &mut Context<Self>) {
+        let terminal_id = terminal.entity_id();
+        if let Some(context_id) = self.includes_terminal(terminal_id) {
+            self.remove_context(context_id);
+            return;
+        }
+
+        let terminal = terminal.read(cx);
+        let title = terminal.title(true);
+        let mut text = String::from("```console\n");
+        for line in terminal.last_n_non_empty_lines(TERMINAL_CONTEXT_LINES) {
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text.push_str("```\n");
+
+        let id = self.next_context_id.post_inc();
+        self.context
+            .push(AssistantContext::Terminal(TerminalContext {
+                id,
+                terminal_id,
+                title: title.into(),
+                text: text.into(),
+            }));
+    }
+
+    fn project(&self, cx: &App) -> Option<Entity<Project>> {
+        self.workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().clone())
+    }
+
     pub fn accept_suggested_context(
         &mut self,
         suggested: &SuggestedContext,
@@ -326,6 +614,11 @@ impl ContextStore {
             AssistantContext::Thread(_) => {
                 self.threads.retain(|_, context_id| *context_id != id);
             }
+            AssistantContext::Symbol(_)
+            | AssistantContext::Diagnostics(_)
+            | AssistantContext::GitDiff(_)
+            | AssistantContext::Selection(_)
+            | AssistantContext::Terminal(_) => {}
         }
     }
 
@@ -391,6 +684,55 @@ impl ContextStore {
         self.fetched_urls.get(url).copied()
     }
 
+    pub fn includes_symbol(&self, project_path: &ProjectPath, name: &str) -> Option<ContextId> {
+        self.context.iter().find_map(|context| match context {
+            AssistantContext::Symbol(symbol)
+                if symbol.project_path == *project_path && symbol.name.as_ref() == name =>
+            {
+                Some(symbol.id)
+            }
+            _ => None,
+        })
+    }
+
+    pub fn includes_diagnostics(&self, project_path: Option<&ProjectPath>) -> Option<ContextId> {
+        self.context.iter().find_map(|context| match context {
+            AssistantContext::Diagnostics(diagnostics)
+                if diagnostics.project_path.as_ref() == project_path =>
+            {
+                Some(diagnostics.id)
+            }
+            _ => None,
+        })
+    }
+
+    pub fn includes_git_diff(&self) -> Option<ContextId> {
+        self.context.iter().find_map(|context| match context {
+            AssistantContext::GitDiff(git_diff) => Some(git_diff.id),
+            _ => None,
+        })
+    }
+
+    pub fn includes_selection(&self, path: &Path, row_range: &Range<u32>) -> Option<ContextId> {
+        self.context.iter().find_map(|context| match context {
+            AssistantContext::Selection(selection)
+                if *selection.path == *path && selection.row_range == *row_range =>
+            {
+                Some(selection.id)
+            }
+            _ => None,
+        })
+    }
+
+    pub fn includes_terminal(&self, terminal_id: EntityId) -> Option<ContextId> {
+        self.context.iter().find_map(|context| match context {
+            AssistantContext::Terminal(terminal) if terminal.terminal_id == terminal_id => {
+                Some(terminal.id)
+            }
+            _ => None,
+        })
+    }
+
     /// Replaces the context that matches the ID of the new context, if any match.
     fn replace_context(&mut self, new_context: AssistantContext) {
         let id = new_context.id();
@@ -412,7 +754,12 @@ impl ContextStore {
                 }
                 AssistantContext::Directory(_)
                 | AssistantContext::FetchedUrl(_)
-                | AssistantContext::Thread(_) => None,
+                | AssistantContext::Thread(_)
+                | AssistantContext::Symbol(_)
+                | AssistantContext::Diagnostics(_)
+                | AssistantContext::GitDiff(_)
+                | AssistantContext::Selection(_)
+                | AssistantContext::Terminal(_) => None,
             })
             .collect()
     }
@@ -511,6 +858,53 @@ fn to_fenced_codeblock(path: &Path, content: Rope) -> SharedString {
     buffer.into()
 }
 
+/// Formats an excerpt of a file as a fenced code block, labeled with its (one-based) lines.
+fn to_fenced_excerpt(path: &Path, row_range: &Range<u32>, content: &str) -> SharedString {
+    let mut text = String::from("```");
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        text.push_str(extension);
+        text.push(' ');
+    }
+    write!(
+        text,
+        "{}:{}-{}\n{content}",
+        path.to_string_lossy(),
+        row_range.start + 1,
+        row_range.end + 1
+    )
+    .ok();
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str("```\n");
+    text.into()
+}
+
+fn collect_buffer_diagnostics(path: &Path, snapshot: &BufferSnapshot, text: &mut String) {
+    for entry in snapshot.diagnostics_in_range::<_, Point>(0..snapshot.len(), false) {
+        let diagnostic = &entry.diagnostic;
+        if !diagnostic.is_primary {
+            continue;
+        }
+
+        let severity = match diagnostic.severity {
+            DiagnosticSeverity::ERROR => "error",
+            DiagnosticSeverity::WARNING => "warning",
+            DiagnosticSeverity::INFORMATION => "info",
+            _ => "hint",
+        };
+        writeln!(
+            text,
+            "{}:{}:{}: {severity}: {}",
+            path.to_string_lossy(),
+            entry.range.start.row + 1,
+            entry.range.start.column + 1,
+            diagnostic.message
+        )
+        .ok();
+    }
+}
+
 fn collect_files_in_path(worktree: &Worktree, path: &Path) -> Vec<Arc<Path>> {
     let mut files = Vec::new();
 
@@ -525,6 +919,36 @@ fn collect_files_in_path(worktree: &Worktree, path: &Path) -> Vec<Arc<Path>> {
     files
 }
 
+/// Returns the rows a selection covers, leaving out the last row when the selection ends at
+/// its start, as it does after selecting whole lines.
+fn selection_row_range(start: Point, end: Point) -> Range<u32> {
+    if end.column == 0 && end.row > start.row {
+        start.row..end.row - 1
+    } else {
+        start.row..end.row
+    }
+}
+
+/// Returns a unified diff of a file between HEAD and the working tree, where `None` means the
+/// file is missing on that side.
+fn unified_diff(path: &Path, committed_text: Option<&str>, text: Option<&str>) -> String {
+    let path = path.to_string_lossy();
+    let old_header = if committed_text.is_some() {
+        format!("a/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    let new_header = if text.is_some() {
+        format!("b/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    similar::TextDiff::from_lines(committed_text.unwrap_or_default(), text.unwrap_or_default())
+        .unified_diff()
+        .header(&old_header, &new_header)
+        .to_string()
+}
+
 pub fn refresh_context_store_text(
     context_store: Entity<ContextStore>,
     cx: &App,
@@ -552,6 +976,12 @@ pub fn refresh_context_store_text(
             // and doing the caching properly could be tricky (unless it's already handled by
             // the HttpClient?).
             AssistantContext::FetchedUrl(_) => {}
+            // The remaining kinds capture a point in time, so they're left as they were added.
+            AssistantContext::Symbol(_)
+            | AssistantContext::Diagnostics(_)
+            | AssistantContext::GitDiff(_)
+            | AssistantContext::Selection(_)
+            | AssistantContext::Terminal(_) => {}
         }
     }
 
@@ -657,3 +1087,191 @@ fn refresh_context_buffer(
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use git::status::{FileStatus, StatusCode};
+    use gpui::{AppContext as _, TestAppContext};
+    use indoc::indoc;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_add_git_diff(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "modified.txt": "one\nthree\n",
+                "unchanged.txt": "same\n",
+            }),
+        )
+        .await;
+        fs.set_head_for_repo(
+            Path::new("/root/.git"),
+            &[
+                (Path::new("modified.txt"), "one\ntwo\n".into()),
+                (Path::new("unchanged.txt"), "same\n".into()),
+            ],
+        );
+        fs.set_status_for_repo_via_git_operation(
+            Path::new("/root/.git"),
+            &[
+                (
+                    Path::new("modified.txt"),
+                    FileStatus::worktree(StatusCode::Modified),
+                ),
+                (
+                    Path::new("unchanged.txt"),
+                    FileStatus::worktree(StatusCode::Modified),
+                ),
+            ],
+        );
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        cx.run_until_parked();
+        let context_store = cx.new(|_cx| ContextStore::new(workspace.downgrade()));
+
+        context_store
+            .update(cx, |context_store, cx| context_store.add_git_diff(cx))
+            .await
+            .unwrap();
+        context_store.read_with(cx, |context_store, _cx| {
+            let [AssistantContext::GitDiff(git_diff)] = context_store.context().as_slice() else {
+                panic!("expected a single git diff context");
+            };
+            assert_eq!(
+                git_diff.text.as_ref(),
+                indoc! {"
+                    ```diff
+                    --- a/modified.txt
+                    +++ b/modified.txt
+                    @@ -1,2 +1,2 @@
+                     one
+                    -two
+                    +three
+                    ```
+                "}
+            );
+        });
+
+        // Adding the git diff again removes it.
+        context_store
+            .update(cx, |context_store, cx| context_store.add_git_diff(cx))
+            .await
+            .unwrap();
+        context_store.read_with(cx, |context_store, _cx| {
+            assert!(context_store.context().is_empty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_add_selection_twice(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "main.rs": "one\ntwo\nthree\n" }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/main.rs", cx)
+            })
+            .await
+            .unwrap();
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let editor = cx.new_window_entity(|window, cx| {
+            let mut editor = Editor::for_buffer(buffer, Some(project), window, cx);
+            editor.change_selections(None, window, cx, |selections| {
+                selections.select_ranges([Point::new(1, 0)..Point::new(2, 2)])
+            });
+            editor
+        });
+        let context_store = cx.new(|_cx| ContextStore::new(workspace.downgrade()));
+
+        context_store.update(cx, |context_store, cx| {
+            context_store.add_selection(editor.clone(), cx).unwrap();
+            let [AssistantContext::Selection(selection)] = context_store.context().as_slice()
+            else {
+                panic!("expected a single selection context");
+            };
+            assert_eq!(selection.row_range, 1..2);
+
+            // Adding the same selection again removes it instead of duplicating it.
+            context_store.add_selection(editor.clone(), cx).unwrap();
+            assert!(context_store.context().is_empty());
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            client::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init_settings(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    #[test]
+    fn test_selection_row_range() {
+        assert_eq!(
+            selection_row_range(Point::new(2, 4), Point::new(5, 1)),
+            2..5
+        );
+        assert_eq!(
+            selection_row_range(Point::new(2, 0), Point::new(5, 0)),
+            2..4,
+            "A selection ending at the start of a row shouldn't include that row"
+        );
+        assert_eq!(
+            selection_row_range(Point::new(2, 1), Point::new(2, 3)),
+            2..2
+        );
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(
+            unified_diff(
+                Path::new("src/main.rs"),
+                Some("one\ntwo\n"),
+                Some("one\n2\n")
+            ),
+            indoc! {"
+                --- a/src/main.rs
+                +++ b/src/main.rs
+                @@ -1,2 +1,2 @@
+                 one
+                -two
+                +2
+            "}
+        );
+        assert_eq!(
+            unified_diff(Path::new("deleted.rs"), Some("gone\n"), None),
+            indoc! {"
+                --- a/deleted.rs
+                +++ /dev/null
+                @@ -1 +0,0 @@
+                -gone
+            "}
+        );
+        assert_eq!(
+            unified_diff(Path::new("added.rs"), None, Some("new\n")),
+            indoc! {"
+                --- /dev/null
+                +++ b/added.rs
+                @@ -0,0 +1 @@
+                +new
+            "}
+        );
+    }
+}
@@ -15,6 +15,7 @@ use workspace::{notifications::NotifyResultExt, Workspace};
 use crate::context::ContextKind;
 use crate::context_picker::{ConfirmBehavior, ContextPicker};
 use crate::context_store::ContextStore;
+use crate::project_rules::ProjectRules;
 use crate::thread::Thread;
 use crate::thread_store::ThreadStore;
 use crate::ui::ContextPill;
@@ -25,6 +26,7 @@ use crate::{
 
 pub struct ContextStrip {
     context_store: Entity<ContextStore>,
+    project_rules: Option<Entity<ProjectRules>>,
     pub context_picker: Entity<ContextPicker>,
     context_picker_menu_handle: PopoverMenuHandle<ContextPicker>,
     focus_handle: FocusHandle,
@@ -59,16 +61,24 @@ impl ContextStrip {
             )
         });
 
+        let project_rules = thread_store
+            .and_then(|thread_store| thread_store.upgrade())
+            .map(|thread_store| thread_store.read(cx).project_rules().clone());
+
         let focus_handle = cx.focus_handle();
 
-        let subscriptions = vec![
+        let mut subscriptions = vec![
             cx.subscribe_in(&context_picker, window, Self::handle_context_picker_event),
             cx.on_focus(&focus_handle, window, Self::handle_focus),
             cx.on_blur(&focus_handle, window, Self::handle_blur),
         ];
+        if let Some(project_rules) = &project_rules {
+            subscriptions.push(cx.observe(project_rules, |_, _, cx| cx.notify()));
+        }
 
         Self {
             context_store: context_store.clone(),
+            project_rules,
             context_picker,
             context_picker_menu_handle,
             focus_handle,
@@ -371,6 +381,18 @@ impl Render for ContextStrip {
         let focus_handle = self.focus_handle.clone();
 
         let suggested_context = self.suggested_context(cx);
+        let rules_files = self
+            .project_rules
+            .as_ref()
+            .map(|project_rules| {
+                project_rules
+                    .read(cx)
+                    .files()
+                    .iter()
+                    .map(|file| file.display_path.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
 
         let dupe_names = context
             .iter()
@@ -403,38 +425,47 @@ impl Render for ContextStrip {
                 }
             })
             .child(
-                PopoverMenu::new("context-picker")
-                    .menu(move |window, cx| {
-                        context_picker.update(cx, |this, cx| {
-                            this.init(window, cx);
-                        });
-
-                        Some(context_picker.clone())
-                    })
-                    .trigger(
-                        IconButton::new("add-context", IconName::Plus)
-                            .icon_size(IconSize::Small)
-                            .style(ui::ButtonStyle::Filled)
-                            .tooltip({
-                                let focus_handle = focus_handle.clone();
-                                move |window, cx| {
-                                    Tooltip::for_action_in(
-                                        "Add Context",
-                                        &ToggleContextPicker,
-                                        &focus_handle,
-                                        window,
-                                        cx,
-                                    )
-                                }
-                            }),
+                // The rules pill shares a child with the picker so that it isn't counted
+                // among the focusable context pills.
+                h_flex()
+                    .gap_1()
+                    .child(
+                        PopoverMenu::new("context-picker")
+                            .menu(move |window, cx| {
+                                context_picker.update(cx, |this, cx| {
+                                    this.init(window, cx);
+                                });
+
+                                Some(context_picker.clone())
+                            })
+                            .trigger(
+                                IconButton::new("add-context", IconName::Plus)
+                                    .icon_size(IconSize::Small)
+                                    .style(ui::ButtonStyle::Filled)
+                                    .tooltip({
+                                        let focus_handle = focus_handle.clone();
+                                        move |window, cx| {
+                                            Tooltip::for_action_in(
+                                                "Add Context",
+                                                &ToggleContextPicker,
+                                                &focus_handle,
+                                                window,
+                                                cx,
+                                            )
+                                        }
+                                    }),
+                            )
+                            .attach(gpui::Corner::TopLeft)
+                            .anchor(gpui::Corner::BottomLeft)
+                            .offset(gpui::Point {
+                                x: px(0.0),
+                                y: px(-2.0),
+                            })
+                            .with_handle(self.context_picker_menu_handle.clone()),
                     )
-                    .attach(gpui::Corner::TopLeft)
-                    .anchor(gpui::Corner::BottomLeft)
-                    .offset(gpui::Point {
-                        x: px(0.0),
-                        y: px(-2.0),
-                    })
-                    .with_handle(self.context_picker_menu_handle.clone()),
+                    .when(!rules_files.is_empty(), |parent| {
+                        parent.child(ContextPill::rules(rules_files))
+                    }),
             )
             .when(context.is_empty() && suggested_context.is_none(), {
                 |parent| {
@@ -43,6 +43,7 @@ use workspace::{notifications::NotificationId, ItemHandle, Toast, Workspace};
 use crate::buffer_codegen::{BufferCodegen, CodegenAlternative, CodegenEvent};
 use crate::context_store::ContextStore;
 use crate::inline_prompt_editor::{CodegenStatus, InlineAssistId, PromptEditor, PromptEditorEvent};
+use crate::project_rules::ProjectRules;
 use crate::terminal_inline_assistant::TerminalInlineAssistant;
 use crate::thread_store::ThreadStore;
 use crate::AssistantPanel;
@@ -381,6 +382,7 @@ impl InlineAssistant {
             }
         }
 
+        let project_rules = project_rules_for_thread_store(thread_store.as_ref(), cx);
         let assist_group_id = self.next_assist_group_id.post_inc();
         let prompt_buffer =
             cx.new(|cx| MultiBuffer::singleton(cx.new(|cx| Buffer::local(String::new(), cx)), cx));
@@ -396,6 +398,7 @@ impl InlineAssistant {
                     range.clone(),
                     None,
                     context_store.clone(),
+                    project_rules.clone(),
                     self.telemetry.clone(),
                     self.prompt_builder.clone(),
                     cx,
@@ -503,6 +506,7 @@ impl InlineAssistant {
         }
 
         let context_store = cx.new(|_cx| ContextStore::new(workspace.clone()));
+        let project_rules = project_rules_for_thread_store(thread_store.as_ref(), cx);
 
         let codegen = cx.new(|cx| {
             BufferCodegen::new(
@@ -510,6 +514,7 @@ impl InlineAssistant {
                 range.clone(),
                 initial_transaction_id,
                 context_store.clone(),
+                project_rules,
                 self.telemetry.clone(),
                 self.prompt_builder.clone(),
                 cx,
@@ -1791,6 +1796,14 @@ impl CodeActionProvider for AssistantCodeActionProvider {
     }
 }
 
+pub(crate) fn project_rules_for_thread_store(
+    thread_store: Option<&WeakEntity<ThreadStore>>,
+    cx: &App,
+) -> Option<Entity<ProjectRules>> {
+    let thread_store = thread_store?.upgrade()?;
+    Some(thread_store.read(cx).project_rules().clone())
+}
+
 fn merge_ranges(ranges: &mut Vec<Range<Anchor>>, buffer: &MultiBufferSnapshot) {
     ranges.sort_unstable_by(|a, b| {
         a.start
@@ -49,7 +49,12 @@ impl MessageEditor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
-        let context_store = cx.new(|_cx| ContextStore::new(workspace.clone()));
+        let next_context_id = thread.read(cx).next_context_id();
+        let context_store = cx.new(|_cx| {
+            let mut context_store = ContextStore::new(workspace.clone());
+            context_store.set_next_context_id(next_context_id);
+            context_store
+        });
         let context_picker_menu_handle = PopoverMenuHandle::default();
         let inline_context_picker_menu_handle = PopoverMenuHandle::default();
         let model_selector_menu_handle = PopoverMenuHandle::default();
@@ -0,0 +1,322 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use assistant_settings::AssistantSettings;
+use gpui::{App, Context, Entity, SharedString, Subscription, Task};
+use language::Buffer;
+use project::{Project, ProjectPath};
+use settings::{Settings as _, SettingsStore};
+use util::ResultExt as _;
+
+/// A rules file found at the root of one of the project's worktrees.
+pub struct RulesFile {
+    /// The path to display to the user, prefixed with the worktree's root name.
+    pub display_path: SharedString,
+    buffer: Entity<Buffer>,
+}
+
+/// Tracks the project rules files (e.g. `.rules`) that are included as instructions in every
+/// request made by the assistant.
+///
+/// The rules files are opened as buffers, so they stay up-to-date as they change and are
+/// available in remote projects.
+pub struct ProjectRules {
+    project: Entity<Project>,
+    rules_files: Vec<String>,
+    files: Vec<RulesFile>,
+    pending_reload: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ProjectRules {
+    pub fn new(project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        let subscriptions = vec![
+            cx.subscribe(&project, Self::handle_project_event),
+            cx.observe_global::<SettingsStore>(|this, cx| {
+                if AssistantSettings::get_global(cx).project_rules_files != this.rules_files {
+                    this.reload(cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            project,
+            rules_files: Vec::new(),
+            files: Vec::new(),
+            pending_reload: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.reload(cx);
+        this
+    }
+
+    pub fn files(&self) -> &[RulesFile] {
+        &self.files
+    }
+
+    /// Returns the contents of the rules files, formatted for inclusion in a prompt.
+    pub fn text(&self, cx: &App) -> Option<String> {
+        let mut text = String::new();
+        for file in &self.files {
+            let contents = file.buffer.read(cx).text();
+            if contents.trim().is_empty() {
+                continue;
+            }
+
+            if text.is_empty() {
+                text.push_str(
+                    "The following project rules were provided by the user. \
+                    Follow them when responding:\n",
+                );
+            }
+            writeln!(text, "\n```{}", file.display_path).ok();
+            text.push_str(&contents);
+            if !contents.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str("```\n");
+        }
+
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn handle_project_event(
+        &mut self,
+        _project: Entity<Project>,
+        event: &project::Event,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            project::Event::WorktreeAdded(_)
+            | project::Event::WorktreeRemoved(_)
+            | project::Event::WorktreeOrderChanged => self.reload(cx),
+            project::Event::WorktreeUpdatedEntries(_, updated_entries) => {
+                let affects_rules = updated_entries.iter().any(|(path, _, _)| {
+                    self.rules_files
+                        .iter()
+                        .any(|rules_file| path.as_ref() == Path::new(rules_file))
+                });
+                if affects_rules {
+                    self.reload(cx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.rules_files = AssistantSettings::get_global(cx)
+            .project_rules_files
+            .clone();
+
+        let mut rules_paths = Vec::new();
+        for worktree in self.project.read(cx).visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            for rules_file in &self.rules_files {
+                let Some(entry) = worktree.entry_for_path(rules_file) else {
+                    continue;
+                };
+                if entry.is_file() {
+                    let display_path = Path::new(worktree.root_name()).join(&entry.path);
+                    rules_paths.push((
+                        ProjectPath {
+                            worktree_id: worktree.id(),
+                            path: entry.path.clone(),
+                        },
+                        SharedString::from(display_path.to_string_lossy().to_string()),
+                    ));
+                }
+            }
+        }
+
+        let open_buffers = rules_paths
+            .into_iter()
+            .map(|(project_path, display_path)| {
+                let open_buffer = self
+                    .project
+                    .update(cx, |project, cx| project.open_buffer(project_path, cx));
+                async move {
+                    let buffer = open_buffer.await.log_err()?;
+                    Some(RulesFile {
+                        display_path,
+                        buffer,
+                    })
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.pending_reload = cx.spawn(|this, mut cx| async move {
+            let files = futures::future::join_all(open_buffers)
+                .await
+                .into_iter()
+                .flatten()
+                .collect();
+
+            this.update(&mut cx, |this, cx| {
+                this.files = files;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use assistant_tool::ToolWorkingSet;
+    use fs::{FakeFs, Fs as _};
+    use gpui::{AppContext as _, TestAppContext};
+    use language_model::{MessageContent, Role};
+    use serde_json::json;
+
+    use super::*;
+    use crate::thread::{RequestKind, Thread};
+
+    #[gpui::test]
+    async fn test_rules_discovery(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".rules": "Use tabs.",
+                "src": { ".rules": "Nested rules are ignored." },
+                ".github": { "copilot-instructions.md": "Write tests.\n" },
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let project_rules = cx.new(|cx| ProjectRules::new(project, cx));
+        cx.run_until_parked();
+
+        project_rules.read_with(cx, |project_rules, cx| {
+            let display_paths = project_rules
+                .files()
+                .iter()
+                .map(|file| file.display_path.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                display_paths,
+                ["root/.rules", "root/.github/copilot-instructions.md"]
+            );
+            assert_eq!(
+                project_rules.text(cx).unwrap(),
+                "The following project rules were provided by the user. \
+                Follow them when responding:\n\
+                \n```root/.rules\nUse tabs.\n```\n\
+                \n```root/.github/copilot-instructions.md\nWrite tests.\n```\n"
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rules_reload(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "main.rs": "fn main() {}" }))
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let project_rules = cx.new(|cx| ProjectRules::new(project, cx));
+        let reloads = Rc::new(Cell::new(0));
+        cx.update({
+            let reloads = reloads.clone();
+            |cx| {
+                cx.observe(&project_rules, move |_, _| reloads.set(reloads.get() + 1))
+                    .detach()
+            }
+        });
+        cx.run_until_parked();
+        project_rules.read_with(cx, |project_rules, cx| {
+            assert!(project_rules.files().is_empty());
+            assert_eq!(project_rules.text(cx), None);
+        });
+
+        // Creating a rules file picks it up.
+        fs.insert_file("/root/.cursorrules", b"Be terse.".to_vec())
+            .await;
+        cx.run_until_parked();
+        assert_eq!(rules_paths(&project_rules, cx), ["root/.cursorrules"]);
+        let reload_count = reloads.get();
+
+        // Unrelated file and settings changes don't reload the rules.
+        fs.insert_file("/root/lib.rs", b"".to_vec()).await;
+        update_assistant_settings(cx, json!({ "default_width": 500 }));
+        cx.run_until_parked();
+        assert_eq!(reloads.get(), reload_count);
+
+        // Changing the rules files setting reloads them.
+        update_assistant_settings(cx, json!({ "project_rules_files": ["main.rs"] }));
+        cx.run_until_parked();
+        assert_eq!(rules_paths(&project_rules, cx), ["root/main.rs"]);
+
+        // Deleting a rules file drops it.
+        fs.remove_file("/root/main.rs".as_ref(), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert!(rules_paths(&project_rules, cx).is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_rules_in_thread_request(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ ".rules": "Use tabs." }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let project_rules = cx.new(|cx| ProjectRules::new(project, cx));
+        let thread =
+            cx.new(|cx| Thread::new(Arc::new(ToolWorkingSet::default()), project_rules, cx));
+        cx.run_until_parked();
+
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Hello", Vec::new(), cx);
+            let request = thread.to_completion_request(RequestKind::Chat, cx);
+            assert_eq!(request.messages.len(), 2);
+            assert_eq!(request.messages[0].role, Role::System);
+            let MessageContent::Text(rules) = &request.messages[0].content[0] else {
+                panic!("expected the rules as text");
+            };
+            assert!(rules.contains("```root/.rules\nUse tabs.\n```"));
+            assert_eq!(request.messages[1].role, Role::User);
+        });
+    }
+
+    fn rules_paths(project_rules: &Entity<ProjectRules>, cx: &TestAppContext) -> Vec<String> {
+        project_rules.read_with(cx, |project_rules, _| {
+            project_rules
+                .files()
+                .iter()
+                .map(|file| file.display_path.to_string())
+                .collect()
+        })
+    }
+
+    fn update_assistant_settings(cx: &mut TestAppContext, content: serde_json::Value) {
+        let mut assistant = json!({ "version": "2" });
+        assistant
+            .as_object_mut()
+            .unwrap()
+            .extend(content.as_object().unwrap().clone());
+        let settings = json!({ "assistant": assistant }).to_string();
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.set_user_settings(&settings, cx).unwrap();
+            })
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+        });
+    }
+}
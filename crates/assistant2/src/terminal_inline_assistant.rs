@@ -1,8 +1,10 @@
 use crate::context::attach_context_to_message;
 use crate::context_store::ContextStore;
+use crate::inline_assistant::project_rules_for_thread_store;
 use crate::inline_prompt_editor::{
     CodegenStatus, PromptEditor, PromptEditorEvent, TerminalInlineAssistId,
 };
+use crate::project_rules::ProjectRules;
 use crate::terminal_codegen::{CodegenEvent, TerminalCodegen, CLEAR_INPUT};
 use crate::thread_store::ThreadStore;
 use anyhow::{Context as _, Result};
@@ -76,6 +78,7 @@ impl TerminalInlineAssistant {
         let prompt_buffer =
             cx.new(|cx| MultiBuffer::singleton(cx.new(|cx| Buffer::local(String::new(), cx)), cx));
         let context_store = cx.new(|_cx| ContextStore::new(workspace.clone()));
+        let project_rules = project_rules_for_thread_store(thread_store.as_ref(), cx);
         let codegen = cx.new(|_| TerminalCodegen::new(terminal, self.telemetry.clone()));
 
         let prompt_editor = cx.new(|cx| {
@@ -107,6 +110,7 @@ impl TerminalInlineAssistant {
             prompt_editor,
             workspace.clone(),
             context_store,
+            project_rules,
             window,
             cx,
         );
@@ -257,8 +261,22 @@ impl TerminalInlineAssistant {
 
         request_message.content.push(prompt.into());
 
+        let mut messages = Vec::new();
+        if let Some(rules) = assist
+            .project_rules
+            .as_ref()
+            .and_then(|project_rules| project_rules.read(cx).text(cx))
+        {
+            messages.push(LanguageModelRequestMessage {
+                role: Role::System,
+                content: vec![rules.into()],
+                cache: false,
+            });
+        }
+        messages.push(request_message);
+
         Ok(LanguageModelRequest {
-            messages: vec![request_message],
+            messages,
             tools: Vec::new(),
             stop: Vec::new(),
             temperature: None,
@@ -373,6 +391,7 @@ struct TerminalInlineAssist {
     codegen: Entity<TerminalCodegen>,
     workspace: WeakEntity<Workspace>,
     context_store: Entity<ContextStore>,
+    project_rules: Option<Entity<ProjectRules>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -383,6 +402,7 @@ impl TerminalInlineAssist {
         prompt_editor: Entity<PromptEditor<TerminalCodegen>>,
         workspace: WeakEntity<Workspace>,
         context_store: Entity<ContextStore>,
+        project_rules: Option<Entity<ProjectRules>>,
         window: &mut Window,
         cx: &mut App,
     ) -> Self {
@@ -393,6 +413,7 @@ impl TerminalInlineAssist {
             codegen: codegen.clone(),
             workspace: workspace.clone(),
             context_store,
+            project_rules,
             _subscriptions: vec![
                 window.subscribe(&prompt_editor, cx, |prompt_editor, event, window, cx| {
                     TerminalInlineAssistant::update_global(cx, |this, cx| {
@@ -1,3 +1,4 @@
+use std::fmt::Write as _;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -6,7 +7,7 @@ use chrono::{DateTime, Utc};
 use collections::{BTreeMap, HashMap, HashSet};
 use futures::future::Shared;
 use futures::{FutureExt as _, StreamExt as _};
-use gpui::{App, Context, EventEmitter, SharedString, Task};
+use gpui::{App, Context, Entity, EventEmitter, SharedString, Task};
 use language_model::{
     LanguageModel, LanguageModelCompletionEvent, LanguageModelRegistry, LanguageModelRequest,
     LanguageModelRequestMessage, LanguageModelToolResult, LanguageModelToolUse,
@@ -18,7 +19,9 @@ use util::{post_inc, TryFutureExt as _};
 use uuid::Uuid;
 
 use crate::context::{attach_context_to_message, ContextId, ContextSnapshot};
+use crate::project_rules::ProjectRules;
 use crate::thread_store::SavedThread;
+use crate::token_usage::{merge_model_token_usage, ModelTokenUsage};
 
 #[derive(Debug, Clone, Copy)]
 pub enum RequestKind {
@@ -41,7 +44,7 @@ impl std::fmt::Display for ThreadId {
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
-pub struct MessageId(usize);
+pub struct MessageId(pub(crate) usize);
 
 impl MessageId {
     fn post_inc(&mut self) -> Self {
@@ -53,45 +56,62 @@ impl MessageId {
 #[derive(Debug, Clone)]
 pub struct Message {
     pub id: MessageId,
+    /// The message this message follows, or `None` if it starts the thread.
+    pub parent_id: Option<MessageId>,
     pub role: Role,
     pub text: String,
 }
 
 /// A thread of conversation with the LLM.
+///
+/// Editing an earlier message creates a new branch, leaving the original continuation in
+/// place as a sibling branch. Only the messages on the active branch are sent to the model.
 pub struct Thread {
     id: ThreadId,
     updated_at: DateTime<Utc>,
     summary: Option<SharedString>,
     pending_summary: Task<Option<()>>,
+    /// The messages on the active branch, in order.
     messages: Vec<Message>,
+    /// The messages on all other branches.
+    inactive_messages: Vec<Message>,
     next_message_id: MessageId,
     context: BTreeMap<ContextId, ContextSnapshot>,
     context_by_message: HashMap<MessageId, Vec<ContextId>>,
     completion_count: usize,
     pending_completions: Vec<PendingCompletion>,
     tools: Arc<ToolWorkingSet>,
+    project_rules: Entity<ProjectRules>,
     tool_uses_by_message: HashMap<MessageId, Vec<LanguageModelToolUse>>,
     tool_results_by_message: HashMap<MessageId, Vec<LanguageModelToolResult>>,
     pending_tool_uses_by_id: HashMap<LanguageModelToolUseId, PendingToolUse>,
+    token_usage_by_message: HashMap<MessageId, ModelTokenUsage>,
 }
 
 impl Thread {
-    pub fn new(tools: Arc<ToolWorkingSet>, _cx: &mut Context<Self>) -> Self {
+    pub fn new(
+        tools: Arc<ToolWorkingSet>,
+        project_rules: Entity<ProjectRules>,
+        _cx: &mut Context<Self>,
+    ) -> Self {
         Self {
             id: ThreadId::new(),
             updated_at: Utc::now(),
             summary: None,
             pending_summary: Task::ready(None),
             messages: Vec::new(),
+            inactive_messages: Vec::new(),
             next_message_id: MessageId(0),
             context: BTreeMap::default(),
             context_by_message: HashMap::default(),
             completion_count: 0,
             pending_completions: Vec::new(),
             tools,
+            project_rules,
             tool_uses_by_message: HashMap::default(),
             tool_results_by_message: HashMap::default(),
             pending_tool_uses_by_id: HashMap::default(),
+            token_usage_by_message: HashMap::default(),
         }
     }
 
@@ -99,33 +119,96 @@ impl Thread {
         id: ThreadId,
         saved: SavedThread,
         tools: Arc<ToolWorkingSet>,
+        project_rules: Entity<ProjectRules>,
         _cx: &mut Context<Self>,
     ) -> Self {
-        let next_message_id = MessageId(saved.messages.len());
+        let next_message_id = MessageId(
+            saved
+                .messages
+                .iter()
+                .chain(&saved.inactive_messages)
+                .map(|message| message.id.0 + 1)
+                .max()
+                .unwrap_or(0),
+        );
+        let token_usage_by_message = saved
+            .messages
+            .iter()
+            .chain(&saved.inactive_messages)
+            .filter_map(|message| Some((message.id, message.token_usage.clone()?)))
+            .collect();
+        let tool_uses_by_message = saved
+            .messages
+            .iter()
+            .chain(&saved.inactive_messages)
+            .filter(|message| !message.tool_uses.is_empty())
+            .map(|message| (message.id, message.tool_uses.clone()))
+            .collect();
+        let tool_results_by_message = saved
+            .messages
+            .iter()
+            .chain(&saved.inactive_messages)
+            .filter(|message| !message.tool_results.is_empty())
+            .map(|message| (message.id, message.tool_results.clone()))
+            .collect();
+        let mut context = BTreeMap::default();
+        let mut context_by_message = HashMap::default();
+        for message in saved.messages.iter().chain(&saved.inactive_messages) {
+            if message.context.is_empty() {
+                continue;
+            }
+            let context_ids = message.context.iter().map(|context| context.id).collect();
+            context_by_message.insert(message.id, context_ids);
+            context.extend(
+                message
+                    .context
+                    .iter()
+                    .map(|context| (context.id, context.clone())),
+            );
+        }
+
+        // The messages on the active branch are saved in order, so each one follows the one
+        // before it.
+        let mut parent_id = None;
+        let messages = saved
+            .messages
+            .into_iter()
+            .map(|message| Message {
+                id: message.id,
+                parent_id: parent_id.replace(message.id),
+                role: message.role,
+                text: message.text,
+            })
+            .collect();
+        let inactive_messages = saved
+            .inactive_messages
+            .into_iter()
+            .map(|message| Message {
+                id: message.id,
+                parent_id: message.parent_id,
+                role: message.role,
+                text: message.text,
+            })
+            .collect();
 
         Self {
             id,
             updated_at: saved.updated_at,
             summary: Some(saved.summary),
             pending_summary: Task::ready(None),
-            messages: saved
-                .messages
-                .into_iter()
-                .map(|message| Message {
-                    id: message.id,
-                    role: message.role,
-                    text: message.text,
-                })
-                .collect(),
+            messages,
+            inactive_messages,
             next_message_id,
-            context: BTreeMap::default(),
-            context_by_message: HashMap::default(),
+            context,
+            context_by_message,
             completion_count: 0,
             pending_completions: Vec::new(),
             tools,
-            tool_uses_by_message: HashMap::default(),
-            tool_results_by_message: HashMap::default(),
+            project_rules,
+            tool_uses_by_message,
+            tool_results_by_message,
             pending_tool_uses_by_id: HashMap::default(),
+            token_usage_by_message,
         }
     }
 
@@ -163,10 +246,129 @@ impl Thread {
         self.messages.iter().find(|message| message.id == id)
     }
 
+    /// Returns the messages on the active branch.
     pub fn messages(&self) -> impl Iterator<Item = &Message> {
         self.messages.iter()
     }
 
+    /// Returns the messages that are not on the active branch.
+    pub fn inactive_messages(&self) -> impl Iterator<Item = &Message> {
+        self.inactive_messages.iter()
+    }
+
+    /// Returns the IDs of the messages that share a parent with the given message, including
+    /// the message itself, in the order they were created.
+    pub fn message_siblings(&self, id: MessageId) -> Vec<MessageId> {
+        let Some(message) = self.all_messages().find(|message| message.id == id) else {
+            return Vec::new();
+        };
+
+        let mut siblings = self
+            .all_messages()
+            .filter(|sibling| sibling.parent_id == message.parent_id)
+            .map(|sibling| sibling.id)
+            .collect::<Vec<_>>();
+        siblings.sort_unstable();
+        siblings
+    }
+
+    /// Makes the branch containing the given message the active one.
+    ///
+    /// The active branch continues past the message through its most recent descendants.
+    pub fn activate_branch(&mut self, id: MessageId, cx: &mut Context<Self>) {
+        if self.messages.iter().any(|message| message.id == id) {
+            return;
+        }
+
+        let mut messages_by_id = self
+            .messages
+            .drain(..)
+            .chain(self.inactive_messages.drain(..))
+            .map(|message| (message.id, message))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut path = Vec::new();
+        let mut current = messages_by_id.get(&id).map(|message| message.id);
+        while let Some(current_id) = current {
+            path.push(current_id);
+            current = messages_by_id
+                .get(&current_id)
+                .and_then(|message| message.parent_id);
+        }
+        path.reverse();
+
+        while let Some(child_id) = path.last().and_then(|last_id| {
+            messages_by_id
+                .values()
+                .filter(|message| message.parent_id == Some(*last_id))
+                .map(|message| message.id)
+                .max()
+        }) {
+            path.push(child_id);
+        }
+
+        self.messages = path
+            .iter()
+            .filter_map(|id| messages_by_id.remove(id))
+            .collect();
+        self.inactive_messages = messages_by_id.into_values().collect();
+
+        self.touch_updated_at();
+        cx.emit(ThreadEvent::BranchChanged);
+    }
+
+    /// Replaces the given User message with `text` on a new branch.
+    ///
+    /// The original message and everything after it are kept as a sibling branch. Returns the
+    /// ID of the new message.
+    pub fn edit_user_message(
+        &mut self,
+        id: MessageId,
+        text: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) -> Option<MessageId> {
+        let ix = self
+            .messages
+            .iter()
+            .position(|message| message.id == id && message.role == Role::User)?;
+
+        // Any in-flight completion belongs to the branch we're leaving.
+        self.pending_completions.clear();
+
+        let previous_branch = self.messages.split_off(ix);
+        let parent_id = previous_branch[0].parent_id;
+        self.inactive_messages.extend(previous_branch);
+        // Tool uses requested on the branch we're leaving will never be sent back to the model.
+        self.pending_tool_uses_by_id.retain(|_, tool_use| {
+            self.messages
+                .iter()
+                .any(|message| message.id == tool_use.assistant_message_id)
+        });
+
+        let new_id = self.next_message_id.post_inc();
+        self.messages.push(Message {
+            id: new_id,
+            parent_id,
+            role: Role::User,
+            text: text.into(),
+        });
+
+        if let Some(context_ids) = self.context_by_message.get(&id).cloned() {
+            self.context_by_message.insert(new_id, context_ids);
+        }
+        if let Some(tool_results) = self.tool_results_by_message.get(&id).cloned() {
+            self.tool_results_by_message.insert(new_id, tool_results);
+        }
+
+        self.touch_updated_at();
+        cx.emit(ThreadEvent::BranchChanged);
+        Some(new_id)
+    }
+
+    fn all_messages(&self) -> impl Iterator<Item = &Message> {
+        self.messages.iter().chain(self.inactive_messages.iter())
+    }
+
     pub fn is_streaming(&self) -> bool {
         !self.pending_completions.is_empty()
     }
@@ -175,6 +377,17 @@ impl Thread {
         &self.tools
     }
 
+    /// Returns an ID that's greater than the IDs of all the context attached to this thread, so
+    /// that context attached from then on doesn't replace it.
+    pub fn next_context_id(&self) -> ContextId {
+        ContextId(
+            self.context
+                .keys()
+                .next_back()
+                .map_or(0, |context_id| context_id.0 + 1),
+        )
+    }
+
     pub fn context_for_message(&self, id: MessageId) -> Option<Vec<ContextSnapshot>> {
         let context = self.context_by_message.get(&id)?;
         Some(
@@ -186,6 +399,35 @@ impl Thread {
         )
     }
 
+    pub fn tool_uses_for_message(&self, id: MessageId) -> &[LanguageModelToolUse] {
+        self.tool_uses_by_message
+            .get(&id)
+            .map_or(&[], |tool_uses| tool_uses.as_slice())
+    }
+
+    pub fn tool_results_for_message(&self, id: MessageId) -> &[LanguageModelToolResult] {
+        self.tool_results_by_message
+            .get(&id)
+            .map_or(&[], |tool_results| tool_results.as_slice())
+    }
+
+    /// Returns the tokens consumed by the completion that produced the given Assistant message.
+    pub fn token_usage_for_message(&self, id: MessageId) -> Option<&ModelTokenUsage> {
+        self.token_usage_by_message.get(&id)
+    }
+
+    /// Returns the tokens consumed by this thread across all of its branches, grouped by
+    /// language model.
+    pub fn token_usage_by_model(&self) -> Vec<ModelTokenUsage> {
+        let mut usage_by_model = Vec::new();
+        for message in self.all_messages() {
+            if let Some(usage) = self.token_usage_by_message.get(&message.id) {
+                merge_model_token_usage(&mut usage_by_model, usage);
+            }
+        }
+        usage_by_model
+    }
+
     pub fn pending_tool_uses(&self) -> Vec<&PendingToolUse> {
         self.pending_tool_uses_by_id.values().collect()
     }
@@ -210,8 +452,10 @@ impl Thread {
         cx: &mut Context<Self>,
     ) -> MessageId {
         let id = self.next_message_id.post_inc();
+        let parent_id = self.messages.last().map(|message| message.id);
         self.messages.push(Message {
             id,
+            parent_id,
             role,
             text: text.into(),
         });
@@ -241,10 +485,71 @@ impl Thread {
         text
     }
 
+    /// Returns the thread as Markdown, including the context and tool calls of each message.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        writeln!(markdown, "# {}\n", self.summary_or_default()).ok();
+
+        for message in &self.messages {
+            writeln!(
+                markdown,
+                "## {}\n",
+                match message.role {
+                    Role::User => "User",
+                    Role::Assistant => "Assistant",
+                    Role::System => "System",
+                }
+            )
+            .ok();
+
+            if let Some(context) = self.context_for_message(message.id) {
+                if !context.is_empty() {
+                    markdown.push_str("Context:\n\n");
+                    for context in context {
+                        writeln!(markdown, "- {}: {}", context.kind.label(), context.name).ok();
+                    }
+                    markdown.push('\n');
+                }
+            }
+
+            if !message.text.is_empty() {
+                writeln!(markdown, "{}\n", message.text.trim_end()).ok();
+            }
+
+            for tool_use in self.tool_uses_for_message(message.id) {
+                writeln!(
+                    markdown,
+                    "**Use Tool: {} ({})**\n\n```json\n{}\n```\n",
+                    tool_use.name,
+                    tool_use.id,
+                    serde_json::to_string_pretty(&tool_use.input).unwrap_or_default()
+                )
+                .ok();
+            }
+
+            for tool_result in self.tool_results_for_message(message.id) {
+                let heading = if tool_result.is_error {
+                    "Tool Error"
+                } else {
+                    "Tool Result"
+                };
+                writeln!(
+                    markdown,
+                    "**{heading} ({})**\n\n```\n{}\n```\n",
+                    tool_result.tool_use_id,
+                    tool_result.content.trim_end()
+                )
+                .ok();
+            }
+        }
+
+        markdown
+    }
+
     pub fn to_completion_request(
         &self,
         _request_kind: RequestKind,
-        _cx: &App,
+        cx: &App,
     ) -> LanguageModelRequest {
         let mut request = LanguageModelRequest {
             messages: vec![],
@@ -253,6 +558,14 @@ impl Thread {
             temperature: None,
         };
 
+        if let Some(rules) = self.project_rules.read(cx).text(cx) {
+            request.messages.push(LanguageModelRequestMessage {
+                role: Role::System,
+                content: vec![MessageContent::Text(rules)],
+                cache: true,
+            });
+        }
+
         let mut referenced_context_ids = HashSet::default();
 
         for message in &self.messages {
@@ -317,6 +630,8 @@ impl Thread {
         cx: &mut Context<Self>,
     ) {
         let pending_completion_id = post_inc(&mut self.completion_count);
+        let provider_id = model.provider_id().0.to_string();
+        let model_id = model.id().0.to_string();
 
         let task = cx.spawn(|thread, mut cx| async move {
             let stream = model.stream_completion(request, &cx);
@@ -377,6 +692,22 @@ impl Thread {
                                     );
                                 }
                             }
+                            LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                                if let Some(last_assistant_message) = thread
+                                    .messages
+                                    .iter()
+                                    .rfind(|message| message.role == Role::Assistant)
+                                {
+                                    thread.token_usage_by_message.insert(
+                                        last_assistant_message.id,
+                                        ModelTokenUsage {
+                                            provider: provider_id.clone(),
+                                            model: model_id.clone(),
+                                            usage,
+                                        },
+                                    );
+                                }
+                            }
                         }
 
                         thread.touch_updated_at();
@@ -576,6 +907,8 @@ pub enum ThreadEvent {
     StreamedCompletion,
     StreamedAssistantText(MessageId, String),
     MessageAdded(MessageId),
+    /// The active branch of the thread changed.
+    BranchChanged,
     SummaryChanged,
     UsePendingTools,
     ToolFinished {
@@ -613,3 +946,181 @@ impl PendingToolUseStatus {
         matches!(self, PendingToolUseStatus::Idle)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assistant_settings::AssistantSettings;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use language_model::fake_provider::FakeLanguageModel;
+    use language_model::TokenUsage;
+    use project::Project;
+    use settings::{Settings as _, SettingsStore};
+
+    use super::*;
+    use crate::context::ContextKind;
+
+    #[gpui::test]
+    async fn test_edit_user_message_mid_thread(cx: &mut TestAppContext) {
+        init_test(cx);
+        let project = Project::test(FakeFs::new(cx.executor()), [], cx).await;
+        let project_rules = cx.new(|cx| ProjectRules::new(project, cx));
+        let tools = Arc::new(ToolWorkingSet::default());
+        let thread = cx.new(|cx| Thread::new(tools, project_rules, cx));
+
+        let context = ContextSnapshot {
+            id: ContextId(0),
+            name: "main.rs".into(),
+            parent: Some("src".into()),
+            tooltip: None,
+            icon_path: None,
+            kind: ContextKind::File,
+            text: vec!["fn main() {}".into()].into(),
+        };
+        let (first_response_id, edited_id, second_response_id) = thread.update(cx, |thread, cx| {
+            thread.insert_user_message("What's in src?", Vec::new(), cx);
+            let first_response_id = thread.insert_message(Role::Assistant, "Let me look.", cx);
+            thread.insert_user_message("Explain main.rs", vec![context], cx);
+            let edited_id = thread.messages.last().unwrap().id;
+            let second_response_id = thread.insert_message(Role::Assistant, "Let me read it.", cx);
+            for (id, assistant_message_id) in [
+                ("tool-use-1", first_response_id),
+                ("tool-use-2", second_response_id),
+            ] {
+                thread.pending_tool_uses_by_id.insert(
+                    LanguageModelToolUseId::from(id),
+                    PendingToolUse {
+                        id: LanguageModelToolUseId::from(id),
+                        assistant_message_id,
+                        name: "read-file".into(),
+                        input: serde_json::Value::Null,
+                        status: PendingToolUseStatus::Idle,
+                    },
+                );
+            }
+            (first_response_id, edited_id, second_response_id)
+        });
+
+        let new_id = thread
+            .update(cx, |thread, cx| {
+                thread.edit_user_message(edited_id, "Explain lib.rs", cx)
+            })
+            .unwrap();
+
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(
+                thread
+                    .messages()
+                    .map(|message| (message.role, message.text.as_str()))
+                    .collect::<Vec<_>>(),
+                [
+                    (Role::User, "What's in src?"),
+                    (Role::Assistant, "Let me look."),
+                    (Role::User, "Explain lib.rs"),
+                ]
+            );
+            assert_eq!(
+                thread.message(new_id).unwrap().parent_id,
+                Some(first_response_id)
+            );
+            assert_eq!(
+                thread
+                    .inactive_messages()
+                    .map(|message| message.id)
+                    .collect::<Vec<_>>(),
+                [edited_id, second_response_id]
+            );
+            assert_eq!(thread.message_siblings(new_id), [edited_id, new_id]);
+            assert_eq!(
+                thread
+                    .context_for_message(new_id)
+                    .unwrap()
+                    .into_iter()
+                    .map(|context| context.name)
+                    .collect::<Vec<_>>(),
+                ["main.rs"]
+            );
+            assert_eq!(
+                thread
+                    .pending_tool_uses()
+                    .into_iter()
+                    .map(|tool_use| tool_use.assistant_message_id)
+                    .collect::<Vec<_>>(),
+                [first_response_id]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_token_usage_accumulation(cx: &mut TestAppContext) {
+        init_test(cx);
+        let project = Project::test(FakeFs::new(cx.executor()), [], cx).await;
+        let project_rules = cx.new(|cx| ProjectRules::new(project, cx));
+        let tools = Arc::new(ToolWorkingSet::default());
+        let thread = cx.new(|cx| Thread::new(tools, project_rules, cx));
+        // Avoid summarizing the thread once the first completion finishes.
+        thread.update(cx, |thread, cx| thread.set_summary("Token usage", cx));
+        let model = Arc::new(FakeLanguageModel::default());
+
+        let usage = |input_tokens, output_tokens, cache_read_input_tokens| TokenUsage {
+            input_tokens,
+            output_tokens,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens,
+        };
+        for (prompt, usage_updates) in [
+            ("Hello", [usage(10, 1, 0), usage(10, 5, 0)]),
+            ("Hello again", [usage(20, 2, 100), usage(20, 8, 100)]),
+        ] {
+            thread.update(cx, |thread, cx| {
+                thread.insert_user_message(prompt, Vec::new(), cx);
+                let request = thread.to_completion_request(RequestKind::Chat, cx);
+                thread.stream_completion(request, model.clone(), cx);
+            });
+            cx.run_until_parked();
+            model.stream_last_completion_response("Hi!".into());
+            for usage_update in usage_updates {
+                model.send_last_completion_event(LanguageModelCompletionEvent::UsageUpdate(
+                    usage_update,
+                ));
+            }
+            model.end_last_completion_stream();
+            cx.run_until_parked();
+        }
+
+        thread.read_with(cx, |thread, _| {
+            let assistant_message_ids = thread
+                .messages()
+                .filter(|message| message.role == Role::Assistant)
+                .map(|message| message.id)
+                .collect::<Vec<_>>();
+            assert_eq!(assistant_message_ids.len(), 2);
+
+            // Each usage update supersedes the previous one for the same message, as providers
+            // report running totals.
+            let message_usage = |id| thread.token_usage_for_message(id).unwrap().usage;
+            assert_eq!(message_usage(assistant_message_ids[0]), usage(10, 5, 0));
+            assert_eq!(message_usage(assistant_message_ids[1]), usage(20, 8, 100));
+
+            // The usage of every message is added up per model.
+            assert_eq!(
+                thread.token_usage_by_model(),
+                [ModelTokenUsage {
+                    provider: "fake".into(),
+                    model: "fake".into(),
+                    usage: usage(30, 13, 100),
+                }]
+            );
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+        });
+    }
+}
@@ -126,6 +126,7 @@ impl Focusable for ThreadHistory {
 impl Render for ThreadHistory {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let threads = self.thread_store.update(cx, |this, _cx| this.threads());
+        let token_usage = self.thread_store.read(cx).token_usage_summary(cx);
         let selected_index = self.selected_index;
 
         v_flex()
@@ -152,28 +153,49 @@ impl Render for ThreadHistory {
                             ),
                         )
                 } else {
-                    history.child(
-                        uniform_list(
-                            cx.entity().clone(),
-                            "thread-history",
-                            threads.len(),
-                            move |history, range, _window, _cx| {
-                                threads[range]
-                                    .iter()
-                                    .enumerate()
-                                    .map(|(index, thread)| {
-                                        h_flex().w_full().pb_1().child(PastThread::new(
-                                            thread.clone(),
-                                            history.assistant_panel.clone(),
-                                            selected_index == index,
-                                        ))
-                                    })
-                                    .collect()
-                            },
+                    history
+                        .when(!token_usage.is_empty(), |history| {
+                            history.child(
+                                h_flex()
+                                    .w_full()
+                                    .px_2()
+                                    .pb_1()
+                                    .gap_1()
+                                    .justify_between()
+                                    .child(
+                                        Label::new("Usage Across Threads")
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                    .child(
+                                        Label::new(token_usage.label())
+                                            .size(LabelSize::XSmall)
+                                            .color(Color::Muted),
+                                    ),
+                            )
+                        })
+                        .child(
+                            uniform_list(
+                                cx.entity().clone(),
+                                "thread-history",
+                                threads.len(),
+                                move |history, range, _window, _cx| {
+                                    threads[range]
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(index, thread)| {
+                                            h_flex().w_full().pb_1().child(PastThread::new(
+                                                thread.clone(),
+                                                history.assistant_panel.clone(),
+                                                selected_index == index,
+                                            ))
+                                        })
+                                        .collect()
+                                },
+                            )
+                            .track_scroll(self.scroll_handle.clone())
+                            .flex_grow(),
                         )
-                        .track_scroll(self.scroll_handle.clone())
-                        .flex_grow(),
-                    )
                 }
             })
     }
@@ -10,19 +10,23 @@ use context_server::{ContextServerFactoryRegistry, ContextServerTool};
 use futures::future::{self, BoxFuture, Shared};
 use futures::FutureExt as _;
 use gpui::{prelude::*, App, BackgroundExecutor, Context, Entity, SharedString, Task};
-use heed::types::SerdeBincode;
+use heed::types::{SerdeBincode, SerdeJson};
 use heed::Database;
-use language_model::Role;
+use language_model::{LanguageModelToolResult, LanguageModelToolUse, Role};
 use project::Project;
 use serde::{Deserialize, Serialize};
 use util::ResultExt as _;
 
-use crate::thread::{MessageId, Thread, ThreadId};
+use crate::context::ContextSnapshot;
+use crate::project_rules::ProjectRules;
+use crate::thread::{Message, MessageId, Thread, ThreadId};
+use crate::token_usage::{merge_model_token_usage, ModelTokenUsage, TokenUsageSummary};
 
 pub struct ThreadStore {
     #[allow(unused)]
     project: Entity<Project>,
     tools: Arc<ToolWorkingSet>,
+    project_rules: Entity<ProjectRules>,
     context_server_manager: Entity<ContextServerManager>,
     context_server_tool_ids: HashMap<Arc<str>, Vec<ToolId>>,
     threads: Vec<SavedThreadMetadata>,
@@ -41,6 +45,8 @@ impl ThreadStore {
                 ContextServerManager::new(context_server_factory_registry, project.clone(), cx)
             });
 
+            let project_rules = cx.new(|cx| ProjectRules::new(project.clone(), cx));
+
             let executor = cx.background_executor().clone();
             let database_future = executor
                 .spawn({
@@ -55,6 +61,7 @@ impl ThreadStore {
             let this = Self {
                 project,
                 tools,
+                project_rules,
                 context_server_manager,
                 context_server_tool_ids: HashMap::default(),
                 threads: Vec::new(),
@@ -80,12 +87,26 @@ impl ThreadStore {
         threads
     }
 
+    pub fn project_rules(&self) -> &Entity<ProjectRules> {
+        &self.project_rules
+    }
+
     pub fn recent_threads(&self, limit: usize) -> Vec<SavedThreadMetadata> {
         self.threads().into_iter().take(limit).collect()
     }
 
+    /// Returns the tokens consumed across all saved threads.
+    pub fn token_usage_summary(&self, cx: &App) -> TokenUsageSummary {
+        TokenUsageSummary::new(
+            self.threads
+                .iter()
+                .flat_map(|thread| thread.token_usage.iter()),
+            cx,
+        )
+    }
+
     pub fn create_thread(&mut self, cx: &mut Context<Self>) -> Entity<Thread> {
-        cx.new(|cx| Thread::new(self.tools.clone(), cx))
+        cx.new(|cx| Thread::new(self.tools.clone(), self.project_rules.clone(), cx))
     }
 
     pub fn open_thread(
@@ -103,28 +124,22 @@ impl ThreadStore {
                 .ok_or_else(|| anyhow!("no thread found with ID: {id:?}"))?;
 
             this.update(&mut cx, |this, cx| {
-                cx.new(|cx| Thread::from_saved(id.clone(), thread, this.tools.clone(), cx))
+                cx.new(|cx| {
+                    Thread::from_saved(
+                        id.clone(),
+                        thread,
+                        this.tools.clone(),
+                        this.project_rules.clone(),
+                        cx,
+                    )
+                })
             })
         })
     }
 
     pub fn save_thread(&self, thread: &Entity<Thread>, cx: &mut Context<Self>) -> Task<Result<()>> {
         let (metadata, thread) = thread.update(cx, |thread, _cx| {
-            let id = thread.id().clone();
-            let thread = SavedThread {
-                summary: thread.summary_or_default(),
-                updated_at: thread.updated_at(),
-                messages: thread
-                    .messages()
-                    .map(|message| SavedMessage {
-                        id: message.id,
-                        role: message.role,
-                        text: message.text.clone(),
-                    })
-                    .collect(),
-            };
-
-            (id, thread)
+            (thread.id().clone(), SavedThread::from_thread(thread))
         });
 
         let database_future = self.database_future.clone();
@@ -136,6 +151,23 @@ impl ThreadStore {
         })
     }
 
+    /// Saves an exported thread as a new thread, returning its ID.
+    pub fn import_thread(
+        &self,
+        thread: SavedThread,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ThreadId>> {
+        let id = ThreadId::new();
+        let database_future = self.database_future.clone();
+        cx.spawn(|this, mut cx| async move {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.save_thread(id.clone(), thread).await?;
+
+            this.update(&mut cx, |this, cx| this.reload(cx))?.await?;
+            Ok(id)
+        })
+    }
+
     pub fn delete_thread(&mut self, id: &ThreadId, cx: &mut Context<Self>) -> Task<Result<()>> {
         let id = id.clone();
         let database_future = self.database_future.clone();
@@ -237,26 +269,99 @@ pub struct SavedThreadMetadata {
     pub id: ThreadId,
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
+    /// The tokens consumed by the thread, grouped by language model.
+    pub token_usage: Vec<ModelTokenUsage>,
+}
+
+/// The version of [`ExportedThread`] written by this version of Zed.
+const EXPORTED_THREAD_VERSION: u32 = 1;
+
+/// A thread in the JSON format used to share it outside of Zed.
+#[derive(Serialize, Deserialize)]
+pub struct ExportedThread {
+    pub version: u32,
+    pub thread: SavedThread,
+}
+
+impl ExportedThread {
+    pub fn new(thread: &Thread) -> Self {
+        Self {
+            version: EXPORTED_THREAD_VERSION,
+            thread: SavedThread::from_thread(thread),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let exported: Self = serde_json::from_str(json)?;
+        if exported.version > EXPORTED_THREAD_VERSION {
+            return Err(anyhow!(
+                "thread was exported by a newer version of Zed (format version {})",
+                exported.version
+            ));
+        }
+        Ok(exported)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct SavedThread {
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
+    /// The messages on the active branch, in order.
     pub messages: Vec<SavedMessage>,
+    /// The messages on all other branches.
+    #[serde(default)]
+    pub inactive_messages: Vec<SavedMessage>,
+}
+
+impl SavedThread {
+    pub fn from_thread(thread: &Thread) -> Self {
+        let save_message = |message: &Message| SavedMessage {
+            id: message.id,
+            parent_id: message.parent_id,
+            role: message.role,
+            text: message.text.clone(),
+            token_usage: thread.token_usage_for_message(message.id).cloned(),
+            tool_uses: thread.tool_uses_for_message(message.id).to_vec(),
+            tool_results: thread.tool_results_for_message(message.id).to_vec(),
+            context: thread.context_for_message(message.id).unwrap_or_default(),
+        };
+
+        Self {
+            summary: thread.summary_or_default(),
+            updated_at: thread.updated_at(),
+            messages: thread.messages().map(&save_message).collect(),
+            inactive_messages: thread.inactive_messages().map(&save_message).collect(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct SavedMessage {
     pub id: MessageId,
+    #[serde(default)]
+    pub parent_id: Option<MessageId>,
     pub role: Role,
     pub text: String,
+    #[serde(default)]
+    pub token_usage: Option<ModelTokenUsage>,
+    #[serde(default)]
+    pub tool_uses: Vec<LanguageModelToolUse>,
+    #[serde(default)]
+    pub tool_results: Vec<LanguageModelToolResult>,
+    /// The context attached to the message, as it was when the message was sent.
+    #[serde(default)]
+    pub context: Vec<ContextSnapshot>,
 }
 
 struct ThreadsDatabase {
     executor: BackgroundExecutor,
     env: heed::Env,
-    threads: Database<SerdeBincode<ThreadId>, SerdeBincode<SavedThread>>,
+    threads: Database<SerdeBincode<ThreadId>, SerdeJson<SavedThread>>,
 }
 
 impl ThreadsDatabase {
@@ -267,14 +372,16 @@ impl ThreadsDatabase {
         let env = unsafe {
             heed::EnvOpenOptions::new()
                 .map_size(ONE_GB_IN_BYTES)
-                .max_dbs(1)
+                .max_dbs(2) // Threads (possibly v1 as well)
                 .open(path)?
         };
 
         let mut txn = env.write_txn()?;
-        let threads = env.create_database(&mut txn, Some("threads"))?;
+        let threads = env.create_database(&mut txn, Some("threads.v2"))?;
         txn.commit()?;
 
+        Self::upgrade_dbs(&env, threads).log_err();
+
         Ok(Self {
             executor,
             env,
@@ -282,6 +389,74 @@ impl ThreadsDatabase {
         })
     }
 
+    /// Moves the threads saved with bincode by previous versions of Zed into the JSON database,
+    /// which allows adding fields to saved threads without breaking older ones.
+    fn upgrade_dbs(
+        env: &heed::Env,
+        threads_db: Database<SerdeBincode<ThreadId>, SerdeJson<SavedThread>>,
+    ) -> Result<()> {
+        #[derive(Serialize, Deserialize)]
+        struct SavedThreadV1 {
+            summary: SharedString,
+            updated_at: DateTime<Utc>,
+            messages: Vec<SavedMessageV1>,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct SavedMessageV1 {
+            id: MessageId,
+            role: Role,
+            text: String,
+        }
+
+        let mut txn = env.write_txn()?;
+        let Some(threads_v1_db) = env
+            .open_database::<SerdeBincode<ThreadId>, SerdeBincode<SavedThreadV1>>(
+                &txn,
+                Some("threads"),
+            )?
+        else {
+            return Ok(());
+        };
+        let threads_v1 = threads_v1_db
+            .iter(&txn)?
+            .collect::<heed::Result<Vec<_>>>()?;
+
+        for (id, thread_v1) in threads_v1 {
+            if threads_db.get(&txn, &id)?.is_some() {
+                continue;
+            }
+
+            let thread = SavedThread {
+                summary: thread_v1.summary,
+                updated_at: thread_v1.updated_at,
+                messages: thread_v1
+                    .messages
+                    .into_iter()
+                    .map(|message| SavedMessage {
+                        id: message.id,
+                        parent_id: None,
+                        role: message.role,
+                        text: message.text,
+                        token_usage: None,
+                        tool_uses: Vec::new(),
+                        tool_results: Vec::new(),
+                        context: Vec::new(),
+                    })
+                    .collect(),
+                inactive_messages: Vec::new(),
+            };
+            threads_db.put(&mut txn, &id, &thread)?;
+        }
+
+        // The threads are only removed from the old database once they have all been moved, in
+        // the same transaction.
+        threads_v1_db.clear(&mut txn)?;
+        txn.commit()?;
+
+        Ok(())
+    }
+
     pub fn list_threads(&self) -> Task<Result<Vec<SavedThreadMetadata>>> {
         let env = self.env.clone();
         let threads = self.threads;
@@ -291,10 +466,18 @@ impl ThreadsDatabase {
             let mut iter = threads.iter(&txn)?;
             let mut threads = Vec::new();
             while let Some((key, value)) = iter.next().transpose()? {
+                let mut token_usage = Vec::new();
+                for message in value.messages.iter().chain(&value.inactive_messages) {
+                    if let Some(usage) = message.token_usage.as_ref() {
+                        merge_model_token_usage(&mut token_usage, usage);
+                    }
+                }
+
                 threads.push(SavedThreadMetadata {
                     id: key,
                     summary: value.summary,
                     updated_at: value.updated_at,
+                    token_usage,
                 });
             }
 
@@ -337,3 +520,290 @@ impl ThreadsDatabase {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assistant_settings::AssistantSettings;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use language_model::LanguageModelToolUseId;
+    use settings::{Settings as _, SettingsStore};
+
+    use workspace::Workspace;
+
+    use super::*;
+    use crate::context::{ContextId, ContextKind};
+    use crate::context_store::ContextStore;
+
+    #[test]
+    fn test_upgrade_bincode_threads() {
+        #[derive(Serialize, Deserialize)]
+        struct SavedThreadV1 {
+            summary: SharedString,
+            updated_at: DateTime<Utc>,
+            messages: Vec<SavedMessageV1>,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct SavedMessageV1 {
+            id: MessageId,
+            role: Role,
+            text: String,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let env = unsafe {
+            heed::EnvOpenOptions::new()
+                .map_size(1024 * 1024)
+                .max_dbs(2)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        let id = ThreadId::new();
+        let updated_at = Utc::now();
+        let mut txn = env.write_txn().unwrap();
+        let threads_v1_db = env
+            .create_database::<SerdeBincode<ThreadId>, SerdeBincode<SavedThreadV1>>(
+                &mut txn,
+                Some("threads"),
+            )
+            .unwrap();
+        threads_v1_db
+            .put(
+                &mut txn,
+                &id,
+                &SavedThreadV1 {
+                    summary: "Old thread".into(),
+                    updated_at,
+                    messages: vec![
+                        SavedMessageV1 {
+                            id: MessageId(0),
+                            role: Role::User,
+                            text: "Hello".into(),
+                        },
+                        SavedMessageV1 {
+                            id: MessageId(1),
+                            role: Role::Assistant,
+                            text: "Hi!".into(),
+                        },
+                    ],
+                },
+            )
+            .unwrap();
+        let threads_db = env
+            .create_database::<SerdeBincode<ThreadId>, SerdeJson<SavedThread>>(
+                &mut txn,
+                Some("threads.v2"),
+            )
+            .unwrap();
+        txn.commit().unwrap();
+
+        ThreadsDatabase::upgrade_dbs(&env, threads_db).unwrap();
+        // Upgrading again is a no-op.
+        ThreadsDatabase::upgrade_dbs(&env, threads_db).unwrap();
+
+        let txn = env.read_txn().unwrap();
+        let thread = threads_db.get(&txn, &id).unwrap().unwrap();
+        assert_eq!(thread.summary.as_ref(), "Old thread");
+        assert_eq!(thread.updated_at, updated_at);
+        assert_eq!(
+            thread
+                .messages
+                .iter()
+                .map(|message| (message.id, message.role, message.text.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (MessageId(0), Role::User, "Hello"),
+                (MessageId(1), Role::Assistant, "Hi!"),
+            ]
+        );
+        assert_eq!(threads_v1_db.len(&txn).unwrap(), 0);
+    }
+
+    #[gpui::test]
+    async fn test_export_import_round_trip(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+        });
+        let project = Project::test(FakeFs::new(cx.executor()), [], cx).await;
+        let project_rules = cx.new(|cx| ProjectRules::new(project, cx));
+        let tools = Arc::new(ToolWorkingSet::default());
+
+        let tool_use = LanguageModelToolUse {
+            id: LanguageModelToolUseId::from("tool-use-1"),
+            name: "list-directory".into(),
+            input: serde_json::json!({ "path": "src" }),
+        };
+        let saved = SavedThread {
+            summary: "Listing files".into(),
+            updated_at: Utc::now(),
+            messages: vec![
+                SavedMessage {
+                    id: MessageId(0),
+                    parent_id: None,
+                    role: Role::User,
+                    text: "What's in src?".into(),
+                    token_usage: None,
+                    tool_uses: Vec::new(),
+                    tool_results: Vec::new(),
+                    context: Vec::new(),
+                },
+                SavedMessage {
+                    id: MessageId(1),
+                    parent_id: Some(MessageId(0)),
+                    role: Role::Assistant,
+                    text: "Let me look.".into(),
+                    token_usage: None,
+                    tool_uses: vec![tool_use.clone()],
+                    tool_results: Vec::new(),
+                    context: Vec::new(),
+                },
+            ],
+            inactive_messages: Vec::new(),
+        };
+        let thread = cx.new(|cx| {
+            Thread::from_saved(
+                ThreadId::new(),
+                saved,
+                tools.clone(),
+                project_rules.clone(),
+                cx,
+            )
+        });
+        let context = ContextSnapshot {
+            id: ContextId(0),
+            name: "main.rs".into(),
+            parent: Some("src".into()),
+            tooltip: None,
+            icon_path: None,
+            kind: ContextKind::File,
+            text: vec!["fn main() {}".into()].into(),
+        };
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Explain main.rs", vec![context], cx);
+        });
+
+        let json = thread
+            .read_with(cx, |thread, _| ExportedThread::new(thread).to_json())
+            .unwrap();
+        let imported = ExportedThread::from_json(&json).unwrap();
+        let imported = cx.new(|cx| {
+            Thread::from_saved(ThreadId::new(), imported.thread, tools, project_rules, cx)
+        });
+
+        imported.read_with(cx, |imported, _| {
+            assert_eq!(
+                imported
+                    .messages()
+                    .map(|message| (message.id, message.role, message.text.as_str()))
+                    .collect::<Vec<_>>(),
+                [
+                    (MessageId(0), Role::User, "What's in src?"),
+                    (MessageId(1), Role::Assistant, "Let me look."),
+                    (MessageId(2), Role::User, "Explain main.rs"),
+                ]
+            );
+            assert_eq!(imported.tool_uses_for_message(MessageId(1)), [tool_use]);
+            assert!(imported.context_for_message(MessageId(0)).is_none());
+            let context = imported.context_for_message(MessageId(2)).unwrap();
+            assert_eq!(
+                context
+                    .iter()
+                    .map(|context| (
+                        context.name.clone(),
+                        context.parent.clone(),
+                        context.kind,
+                        context.text.to_vec()
+                    ))
+                    .collect::<Vec<_>>(),
+                [(
+                    "main.rs".into(),
+                    Some("src".into()),
+                    ContextKind::File,
+                    vec!["fn main() {}".into()]
+                )]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_context_attached_after_reload(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            client::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+        });
+        let project = Project::test(FakeFs::new(cx.executor()), [], cx).await;
+        let project_rules = cx.new(|cx| ProjectRules::new(project.clone(), cx));
+        let tools = Arc::new(ToolWorkingSet::default());
+
+        let saved = SavedThread {
+            summary: "Explaining main.rs".into(),
+            updated_at: Utc::now(),
+            messages: vec![SavedMessage {
+                id: MessageId(0),
+                parent_id: None,
+                role: Role::User,
+                text: "Explain main.rs".into(),
+                token_usage: None,
+                tool_uses: Vec::new(),
+                tool_results: Vec::new(),
+                context: vec![ContextSnapshot {
+                    id: ContextId(0),
+                    name: "main.rs".into(),
+                    parent: Some("src".into()),
+                    tooltip: None,
+                    icon_path: None,
+                    kind: ContextKind::File,
+                    text: vec!["fn main() {}".into()].into(),
+                }],
+            }],
+            inactive_messages: Vec::new(),
+        };
+        let thread =
+            cx.new(|cx| Thread::from_saved(ThreadId::new(), saved, tools, project_rules, cx));
+
+        // Attach context from a new context store, like the message editor does for a thread
+        // that was loaded.
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let next_context_id = thread.read_with(cx, |thread, _| thread.next_context_id());
+        let context_store = cx.new(|_cx| {
+            let mut context_store = ContextStore::new(workspace.downgrade());
+            context_store.set_next_context_id(next_context_id);
+            context_store
+        });
+        context_store.update(cx, |context_store, _cx| {
+            context_store.add_fetched_url("https://zed.dev".into(), "Zed");
+        });
+        let context = context_store.read_with(cx, |context_store, cx| {
+            context_store.snapshot(cx).collect::<Vec<_>>()
+        });
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Compare it with zed.dev", context, cx);
+        });
+
+        thread.read_with(cx, |thread, _| {
+            let context_names = |id| {
+                thread
+                    .context_for_message(id)
+                    .unwrap()
+                    .into_iter()
+                    .map(|context| context.name)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(context_names(MessageId(0)), ["main.rs"]);
+            assert_eq!(context_names(MessageId(1)), ["https://zed.dev"]);
+        });
+    }
+}
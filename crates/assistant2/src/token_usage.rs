@@ -0,0 +1,103 @@
+use assistant_settings::AssistantSettings;
+use gpui::{App, SharedString};
+use language_model::TokenUsage;
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+
+/// The tokens consumed by a particular language model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelTokenUsage {
+    pub provider: String,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+/// Adds `usage` to the entry for the same model in `target`, inserting one if necessary.
+pub fn merge_model_token_usage(target: &mut Vec<ModelTokenUsage>, usage: &ModelTokenUsage) {
+    if let Some(existing) = target
+        .iter_mut()
+        .find(|existing| existing.provider == usage.provider && existing.model == usage.model)
+    {
+        existing.usage += usage.usage;
+    } else {
+        target.push(usage.clone());
+    }
+}
+
+/// Token usage aggregated across models, along with its estimated cost.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TokenUsageSummary {
+    pub usage: TokenUsage,
+    /// The estimated cost in US dollars of the usage for which pricing is configured.
+    pub cost: Option<f64>,
+    /// Whether some of the usage belongs to models without configured pricing.
+    pub has_unpriced_usage: bool,
+}
+
+impl TokenUsageSummary {
+    pub fn new<'a>(usage: impl IntoIterator<Item = &'a ModelTokenUsage>, cx: &App) -> Self {
+        let settings = AssistantSettings::get_global(cx);
+
+        let mut summary = Self::default();
+        for model_usage in usage {
+            summary.usage += model_usage.usage;
+            match settings.pricing_for(&model_usage.provider, &model_usage.model) {
+                Some(pricing) => {
+                    *summary.cost.get_or_insert(0.) += pricing.cost(&model_usage.usage);
+                }
+                None => summary.has_unpriced_usage = true,
+            }
+        }
+
+        summary
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.usage.is_empty()
+    }
+
+    /// Returns a short, human-readable description of the usage, e.g. `12.3k in · 1.5k out · $0.06`.
+    pub fn label(&self) -> SharedString {
+        let usage = &self.usage;
+        let mut parts = Vec::new();
+
+        let cached_tokens = usage.cache_creation_input_tokens + usage.cache_read_input_tokens;
+        let input_tokens = usage.input_tokens + cached_tokens;
+        if cached_tokens > 0 {
+            parts.push(format!(
+                "{} in ({} cached)",
+                format_token_count(input_tokens),
+                format_token_count(cached_tokens)
+            ));
+        } else {
+            parts.push(format!("{} in", format_token_count(input_tokens)));
+        }
+        parts.push(format!("{} out", format_token_count(usage.output_tokens)));
+
+        if let Some(cost) = self.cost {
+            let approximate = if self.has_unpriced_usage { ">" } else { "" };
+            parts.push(format!("{approximate}{}", format_cost(cost)));
+        }
+
+        parts.join(" · ").into()
+    }
+}
+
+/// Formats a token count compactly, e.g. `950`, `12.3k` or `1.2M`.
+pub fn format_token_count(count: u32) -> String {
+    if count >= 1_000_000 {
+        format!("{:.1}M", count as f64 / 1_000_000.0)
+    } else if count >= 1_000 {
+        format!("{:.1}k", count as f64 / 1_000.0)
+    } else {
+        count.to_string()
+    }
+}
+
+fn format_cost(cost: f64) -> String {
+    if cost > 0. && cost < 0.01 {
+        "<$0.01".to_string()
+    } else {
+        format!("${cost:.2}")
+    }
+}
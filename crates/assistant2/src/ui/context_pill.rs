@@ -21,6 +21,8 @@ pub enum ContextPill {
         focused: bool,
         on_click: Option<Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
     },
+    /// The project rules included in every request, which can't be removed.
+    Rules { files: Vec<SharedString> },
 }
 
 impl ContextPill {
@@ -54,6 +56,10 @@ impl ContextPill {
         }
     }
 
+    pub fn rules(files: Vec<SharedString>) -> Self {
+        Self::Rules { files }
+    }
+
     pub fn on_click(mut self, listener: Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>) -> Self {
         match &mut self {
             ContextPill::Added { on_click, .. } => {
@@ -62,6 +68,7 @@ impl ContextPill {
             ContextPill::Suggested { on_click, .. } => {
                 *on_click = Some(listener);
             }
+            ContextPill::Rules { .. } => {}
         }
         self
     }
@@ -72,6 +79,7 @@ impl ContextPill {
                 ElementId::NamedInteger("context-pill".into(), context.id.0)
             }
             Self::Suggested { .. } => "suggested-context-pill".into(),
+            Self::Rules { .. } => "rules-context-pill".into(),
         }
     }
 
@@ -90,6 +98,7 @@ impl ContextPill {
                 icon_path: None,
                 ..
             } => Icon::new(kind.icon()),
+            Self::Rules { .. } => Icon::new(IconName::Book),
         }
     }
 }
@@ -184,7 +193,12 @@ impl RenderOnce for ContextPill {
                             ContextKind::File => "Active Tab",
                             ContextKind::Thread
                             | ContextKind::Directory
-                            | ContextKind::FetchedUrl => "Active",
+                            | ContextKind::FetchedUrl
+                            | ContextKind::Symbol
+                            | ContextKind::Diagnostics
+                            | ContextKind::GitDiff
+                            | ContextKind::Selection
+                            | ContextKind::Terminal => "Active",
                         })
                         .size(LabelSize::XSmall)
                         .color(Color::Muted),
@@ -202,6 +216,17 @@ impl RenderOnce for ContextPill {
                     let on_click = on_click.clone();
                     element.on_click(move |event, window, cx| on_click(event, window, cx))
                 }),
+            ContextPill::Rules { files } => {
+                let files = files.join("\n");
+                base_pill
+                    .pr_1()
+                    .bg(color.element_background)
+                    .border_color(color.border.opacity(0.5))
+                    .child(Label::new("Rules").size(LabelSize::Small))
+                    .tooltip(move |window, cx| {
+                        Tooltip::with_meta("Project Rules", None, files.clone(), window, cx)
+                    })
+            }
         }
     }
 }
@@ -5,7 +5,7 @@ use anthropic::Model as AnthropicModel;
 use deepseek::Model as DeepseekModel;
 use feature_flags::FeatureFlagAppExt;
 use gpui::{App, Pixels};
-use language_model::{CloudModel, LanguageModel};
+use language_model::{CloudModel, LanguageModel, TokenUsage};
 use lmstudio::Model as LmStudioModel;
 use ollama::Model as OllamaModel;
 use schemars::{schema::Schema, JsonSchema};
@@ -65,12 +65,21 @@ pub struct AssistantSettings {
     pub inline_alternatives: Vec<LanguageModelSelection>,
     pub using_outdated_settings_version: bool,
     pub enable_experimental_live_diffs: bool,
+    pub model_pricing: Vec<ModelPricing>,
+    pub project_rules_files: Vec<String>,
 }
 
 impl AssistantSettings {
     pub fn are_live_diffs_enabled(&self, cx: &App) -> bool {
         cx.is_staff() || self.enable_experimental_live_diffs
     }
+
+    /// Returns the configured pricing for the given model, if any.
+    pub fn pricing_for(&self, provider: &str, model: &str) -> Option<&ModelPricing> {
+        self.model_pricing
+            .iter()
+            .find(|pricing| pricing.provider == provider && pricing.model == model)
+    }
 }
 
 /// Assistant panel settings
@@ -164,6 +173,8 @@ impl AssistantSettingsContent {
                         }),
                     inline_alternatives: None,
                     enable_experimental_live_diffs: None,
+                    model_pricing: None,
+                    project_rules_files: None,
                 },
                 VersionedAssistantSettingsContent::V2(settings) => settings.clone(),
             },
@@ -184,6 +195,8 @@ impl AssistantSettingsContent {
                 }),
                 inline_alternatives: None,
                 enable_experimental_live_diffs: None,
+                model_pricing: None,
+                project_rules_files: None,
             },
         }
     }
@@ -312,6 +325,8 @@ impl Default for VersionedAssistantSettingsContent {
             default_model: None,
             inline_alternatives: None,
             enable_experimental_live_diffs: None,
+            model_pricing: None,
+            project_rules_files: None,
         })
     }
 }
@@ -346,6 +361,15 @@ pub struct AssistantSettingsContentV2 {
     ///
     /// Default: false
     enable_experimental_live_diffs: Option<bool>,
+    /// The price of each language model, used to estimate the cost of threads.
+    ///
+    /// Default: []
+    model_pricing: Option<Vec<ModelPricing>>,
+    /// The paths, relative to each worktree root, of files whose contents are included as
+    /// instructions in every assistant request made within that worktree.
+    ///
+    /// Default: [".rules", ".cursorrules", ".github/copilot-instructions.md"]
+    project_rules_files: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -381,6 +405,44 @@ impl Default for LanguageModelSelection {
     }
 }
 
+/// The price of a language model, in US dollars per million tokens.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ModelPricing {
+    #[schemars(schema_with = "providers_schema")]
+    pub provider: String,
+    pub model: String,
+    /// The price of one million input tokens.
+    pub input: f64,
+    /// The price of one million output tokens.
+    pub output: f64,
+    /// The price of one million input tokens written to the prompt cache.
+    ///
+    /// Defaults to the input price.
+    #[serde(default)]
+    pub cache_write: Option<f64>,
+    /// The price of one million input tokens read from the prompt cache.
+    ///
+    /// Defaults to the input price.
+    #[serde(default)]
+    pub cache_read: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Returns the estimated cost of the given usage, in US dollars.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        const ONE_MILLION: f64 = 1_000_000.0;
+
+        let cache_write = self.cache_write.unwrap_or(self.input);
+        let cache_read = self.cache_read.unwrap_or(self.input);
+
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens as f64 * cache_write
+            + usage.cache_read_input_tokens as f64 * cache_read)
+            / ONE_MILLION
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct AssistantSettingsContentV1 {
     /// Whether the Assistant is enabled.
@@ -474,6 +536,8 @@ impl Settings for AssistantSettings {
                 &mut settings.enable_experimental_live_diffs,
                 value.enable_experimental_live_diffs,
             );
+            merge(&mut settings.model_pricing, value.model_pricing);
+            merge(&mut settings.project_rules_files, value.project_rules_files);
         }
 
         Ok(settings)
@@ -534,6 +598,8 @@ mod tests {
                             default_width: None,
                             default_height: None,
                             enable_experimental_live_diffs: None,
+                            model_pricing: None,
+                            project_rules_files: None,
                         }),
                     )
                 },
@@ -555,4 +621,26 @@ mod tests {
 
         assert!(!assistant_settings.assistant.is_version_outdated());
     }
+
+    #[test]
+    fn test_model_pricing_cost() {
+        let pricing = ModelPricing {
+            provider: "anthropic".into(),
+            model: "claude-3-5-sonnet-latest".into(),
+            input: 3.0,
+            output: 15.0,
+            cache_write: Some(3.75),
+            cache_read: None,
+        };
+
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_creation_input_tokens: 200_000,
+            cache_read_input_tokens: 500_000,
+        };
+
+        assert_eq!(pricing.cost(&usage), 3.0 + 1.5 + 0.75 + 1.5);
+        assert_eq!(pricing.cost(&TokenUsage::default()), 0.0);
+    }
 }
@@ -0,0 +1,609 @@
+mod bookmarks_panel_settings;
+
+use std::{iter, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use bookmarks_panel_settings::BookmarksPanelSettings;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, EditorEvent};
+use file_icons::FileIcons;
+use gpui::{
+    actions, uniform_list, Action, App, AsyncWindowContext, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, KeyContext, Pixels, Render, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window,
+};
+use menu::{Cancel, Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::{BookmarkEntry, BookmarkId, Fs, Project, ProjectPath};
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use text::Point;
+use ui::{prelude::*, IconButtonShape, ListItem, ListItemSpacing, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+actions!(bookmarks_panel, [ToggleFocus, EditLabel, RemoveSelected]);
+
+const BOOKMARKS_PANEL_KEY: &str = "BookmarksPanel";
+
+pub fn init(cx: &mut App) {
+    BookmarksPanelSettings::register(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<BookmarksPanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedBookmarksPanel {
+    width: Option<Pixels>,
+}
+
+#[derive(Clone, Debug)]
+enum ListEntry {
+    File(ProjectPath),
+    Bookmark {
+        path: ProjectPath,
+        bookmark: BookmarkEntry,
+    },
+}
+
+impl ListEntry {
+    fn is_same(&self, other: &ListEntry) -> bool {
+        match (self, other) {
+            (ListEntry::File(a), ListEntry::File(b)) => a == b,
+            (ListEntry::Bookmark { bookmark: a, .. }, ListEntry::Bookmark { bookmark: b, .. }) => {
+                a.id == b.id
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Lists the bookmarks in the project, grouped by file.
+pub struct BookmarksPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    entries: Vec<ListEntry>,
+    selected_entry: Option<usize>,
+    label_editor: Entity<Editor>,
+    editing_label: Option<BookmarkId>,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BookmarksPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<Entity<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(BOOKMARKS_PANEL_KEY) })
+            .await
+            .map_err(|e| anyhow!("Failed to load bookmarks panel: {}", e))
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedBookmarksPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let panel = Self::new(workspace, window, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace = cx.entity().downgrade();
+        cx.new(|cx| {
+            let bookmark_store = project.read(cx).bookmark_store().clone();
+            let label_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Bookmark label", cx);
+                editor
+            });
+            let subscriptions = vec![
+                cx.observe(&bookmark_store, |this, _, cx| this.update_entries(cx)),
+                cx.subscribe(&label_editor, |this, _, event: &EditorEvent, cx| {
+                    if let EditorEvent::Blurred = event {
+                        this.confirm_label(cx);
+                    }
+                }),
+            ];
+
+            let mut this = Self {
+                workspace,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                entries: Vec::new(),
+                selected_entry: None,
+                label_editor,
+                editing_label: None,
+                width: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            };
+            this.update_entries(cx);
+            this
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        BOOKMARKS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedBookmarksPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        let selected_entry = self
+            .selected_entry
+            .and_then(|ix| self.entries.get(ix))
+            .cloned();
+        let bookmarks = self
+            .project
+            .read(cx)
+            .bookmark_store()
+            .read(cx)
+            .bookmarks(cx);
+        self.entries = bookmarks
+            .into_iter()
+            .flat_map(|(path, bookmarks)| {
+                iter::once(ListEntry::File(path.clone())).chain(bookmarks.into_iter().map(
+                    move |bookmark| ListEntry::Bookmark {
+                        path: path.clone(),
+                        bookmark,
+                    },
+                ))
+            })
+            .collect();
+
+        // Keep the same entry selected, or the one that took its place if it was removed.
+        self.selected_entry = selected_entry
+            .and_then(|selected| {
+                self.entries
+                    .iter()
+                    .position(|entry| entry.is_same(&selected))
+            })
+            .or(self.selected_entry)
+            .filter(|_| !self.entries.is_empty())
+            .map(|ix| ix.min(self.entries.len() - 1));
+        if let Some(id) = self.editing_label {
+            let still_exists = self.entries.iter().any(
+                |entry| matches!(entry, ListEntry::Bookmark { bookmark, .. } if bookmark.id == id),
+            );
+            if !still_exists {
+                self.editing_label = None;
+            }
+        }
+        cx.notify();
+    }
+
+    fn dispatch_context(&self, window: &Window, cx: &Context<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("BookmarksPanel");
+        dispatch_context.add("menu");
+        if self.label_editor.focus_handle(cx).is_focused(window) {
+            dispatch_context.add("editing");
+        } else {
+            dispatch_context.add("not_editing");
+        }
+        dispatch_context
+    }
+
+    fn scroll_to_selected_entry(&mut self, cx: &mut Context<Self>) {
+        if let Some(selected_entry) = self.selected_entry {
+            self.scroll_handle
+                .scroll_to_item(selected_entry, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.selected_entry = Some(0);
+            self.scroll_to_selected_entry(cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.selected_entry = Some(self.entries.len() - 1);
+            self.scroll_to_selected_entry(cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        self.selected_entry = Some(match self.selected_entry {
+            Some(ix) => ix.saturating_sub(1),
+            None => self.entries.len() - 1,
+        });
+        self.scroll_to_selected_entry(cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        self.selected_entry = Some(match self.selected_entry {
+            Some(ix) => (ix + 1).min(self.entries.len() - 1),
+            None => 0,
+        });
+        self.scroll_to_selected_entry(cx);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.confirm_label(cx) {
+            window.focus(&self.focus_handle);
+        } else if let Some(ix) = self.selected_entry {
+            self.open_entry(ix, true, window, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing_label.take().is_some() {
+            window.focus(&self.focus_handle);
+            cx.notify();
+        }
+    }
+
+    /// Opens the file of the entry at `ix`, and scrolls to its bookmark if it's a bookmark.
+    fn open_entry(&mut self, ix: usize, focus: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let (path, row) = match entry {
+            ListEntry::File(path) => (path.clone(), None),
+            ListEntry::Bookmark { path, bookmark } => (path.clone(), Some(bookmark.row)),
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let open_task = workspace.update(cx, |workspace, cx| {
+            workspace.open_path(path, None, focus, window, cx)
+        });
+        cx.spawn_in(window, |_, mut cx| async move {
+            let item = open_task.await?;
+            if let Some((row, editor)) = row.zip(item.downcast::<Editor>()) {
+                editor
+                    .downgrade()
+                    .update_in(&mut cx, |editor, window, cx| {
+                        editor.go_to_singleton_buffer_point(Point::new(row, 0), window, cx);
+                    })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn edit_label(&mut self, _: &EditLabel, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.start_editing_label(ix, window, cx);
+        }
+    }
+
+    fn start_editing_label(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(ListEntry::Bookmark { bookmark, .. }) = self.entries.get(ix) else {
+            return;
+        };
+        let label = bookmark.label.clone().unwrap_or_default();
+        self.selected_entry = Some(ix);
+        self.editing_label = Some(bookmark.id);
+        self.label_editor.update(cx, |editor, cx| {
+            editor.set_text(label, window, cx);
+            editor.select_all(&Default::default(), window, cx);
+        });
+        window.focus(&self.label_editor.focus_handle(cx));
+        cx.notify();
+    }
+
+    /// Saves the label being edited, returning whether there was one.
+    fn confirm_label(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(id) = self.editing_label.take() else {
+            return false;
+        };
+        let label = self.label_editor.read(cx).text(cx);
+        self.project
+            .read(cx)
+            .bookmark_store()
+            .clone()
+            .update(cx, |store, cx| store.set_label(id, label, cx));
+        cx.notify();
+        true
+    }
+
+    fn remove_selected(&mut self, _: &RemoveSelected, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.remove_entry(ix, cx);
+        }
+    }
+
+    /// Removes the bookmark at `ix`, or all bookmarks in the file if it's a file.
+    fn remove_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let ids = match self.entries.get(ix) {
+            Some(ListEntry::File(path)) => self
+                .entries
+                .iter()
+                .filter_map(|entry| match entry {
+                    ListEntry::Bookmark {
+                        path: bookmark_path,
+                        bookmark,
+                    } if bookmark_path == path => Some(bookmark.id),
+                    _ => None,
+                })
+                .collect(),
+            Some(ListEntry::Bookmark { bookmark, .. }) => vec![bookmark.id],
+            None => return,
+        };
+        self.project
+            .read(cx)
+            .bookmark_store()
+            .clone()
+            .update(cx, |store, cx| {
+                for id in ids {
+                    store.remove_bookmark(id, cx);
+                }
+            });
+    }
+
+    /// Returns the trimmed text of a bookmarked line, if its file is open.
+    fn line_text(&self, path: &ProjectPath, row: u32, cx: &App) -> Option<String> {
+        let buffer = self.project.read(cx).get_open_buffer(path, cx)?;
+        let buffer = buffer.read(cx);
+        if row > buffer.max_point().row {
+            return None;
+        }
+        let line = buffer
+            .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+            .collect::<String>();
+        Some(line.trim().to_string())
+    }
+
+    fn render_entry(&self, ix: usize, window: &Window, cx: &Context<Self>) -> AnyElement {
+        let selected = self.selected_entry == Some(ix);
+        let focused = self.focus_handle.contains_focused(window, cx);
+        match &self.entries[ix] {
+            ListEntry::File(path) => {
+                let file_name = path
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let directory = path
+                    .path
+                    .parent()
+                    .map(|parent| parent.to_string_lossy().to_string())
+                    .filter(|parent| !parent.is_empty());
+                let icon = FileIcons::get_icon(&path.path, cx)
+                    .map(|icon| Icon::from_path(icon).color(Color::Muted));
+                ListItem::new(ix)
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle_state(selected)
+                    .focused(selected && focused)
+                    .start_slot::<Icon>(icon)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(file_name))
+                            .children(directory.map(|directory| {
+                                Label::new(directory)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                            })),
+                    )
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.selected_entry = Some(ix);
+                        this.open_entry(ix, false, window, cx);
+                    }))
+                    .into_any_element()
+            }
+            ListEntry::Bookmark { path, bookmark } => {
+                let id = bookmark.id;
+                let content = if self.editing_label == Some(id) {
+                    div()
+                        .w_full()
+                        .child(self.label_editor.clone())
+                        .into_any_element()
+                } else if let Some(label) = bookmark.label.clone() {
+                    Label::new(label).single_line().into_any_element()
+                } else {
+                    Label::new(self.line_text(path, bookmark.row, cx).unwrap_or_default())
+                        .single_line()
+                        .color(Color::Muted)
+                        .into_any_element()
+                };
+                ListItem::new(ix)
+                    .indent_level(1)
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle_state(selected)
+                    .focused(selected && focused)
+                    .start_slot(
+                        Label::new((bookmark.row + 1).to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(content)
+                    .end_hover_slot(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                IconButton::new(("edit-label", ix), IconName::Pencil)
+                                    .shape(IconButtonShape::Square)
+                                    .icon_size(IconSize::XSmall)
+                                    .tooltip(Tooltip::text("Edit Label"))
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.start_editing_label(ix, window, cx);
+                                    })),
+                            )
+                            .child(
+                                IconButton::new(("remove", ix), IconName::Close)
+                                    .shape(IconButtonShape::Square)
+                                    .icon_size(IconSize::XSmall)
+                                    .tooltip(Tooltip::text("Remove Bookmark"))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.remove_entry(ix, cx);
+                                    })),
+                            ),
+                    )
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.selected_entry = Some(ix);
+                        this.open_entry(ix, false, window, cx);
+                    }))
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+impl Render for BookmarksPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("bookmarks-panel")
+            .key_context(self.dispatch_context(window, cx))
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::edit_label))
+            .on_action(cx.listener(Self::remove_selected))
+            .map(|this| {
+                if self.entries.is_empty() {
+                    this.child(
+                        v_flex()
+                            .size_full()
+                            .p_4()
+                            .gap_1()
+                            .justify_center()
+                            .items_center()
+                            .child(Label::new("No Bookmarks"))
+                            .child(
+                                Label::new("Toggle a bookmark on a line to list it here.")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.entity().clone(),
+                            "bookmarks",
+                            self.entries.len(),
+                            |this, range, window, cx| {
+                                range.map(|ix| this.render_entry(ix, window, cx)).collect()
+                            },
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for BookmarksPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BookmarksPanel {}
+
+impl Panel for BookmarksPanel {
+    fn persistent_name() -> &'static str {
+        "BookmarksPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        BookmarksPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file::<BookmarksPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| BookmarksPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        Some(IconName::Bookmark).filter(|_| BookmarksPanelSettings::get_global(cx).button)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Bookmarks Panel")
+    }
+
+    fn icon_label(&self, _: &Window, _: &App) -> Option<String> {
+        let count = self
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry, ListEntry::Bookmark { .. }))
+            .count();
+        (count > 0).then(|| count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        9
+    }
+}
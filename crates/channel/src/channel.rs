@@ -1,5 +1,6 @@
 mod channel_buffer;
 mod channel_chat;
+mod channel_reviews;
 mod channel_store;
 
 use client::{Client, UserStore};
@@ -11,6 +12,7 @@ pub use channel_chat::{
     mentions_to_proto, ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId,
     MessageParams,
 };
+pub use channel_reviews::{ChannelReviews, ChannelReviewsEvent, ReviewComment, ReviewThread};
 pub use channel_store::{Channel, ChannelEvent, ChannelMembership, ChannelStore};
 
 #[cfg(test)]
@@ -20,4 +22,5 @@ pub fn init(client: &Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
     channel_store::init(client, user_store, cx);
     channel_buffer::init(&client.clone().into());
     channel_chat::init(&client.clone().into());
+    channel_reviews::init(&client.clone().into());
 }
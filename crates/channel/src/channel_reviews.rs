@@ -0,0 +1,422 @@
+use crate::{Channel, ChannelStore};
+use anyhow::{anyhow, Result};
+use client::{
+    proto,
+    user::{User, UserStore},
+    ChannelId, Client, Subscription, TypedEnvelope,
+};
+use collections::HashSet;
+use gpui::{App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Task};
+use language::proto::{deserialize_anchor, serialize_anchor};
+use rpc::AnyProtoClient;
+use std::{ops::Range, sync::Arc};
+use text::{Anchor, BufferSnapshot, Point, ToPoint as _};
+use time::OffsetDateTime;
+
+/// The review threads in a channel, which comment on ranges of text in the channel's notes or in
+/// the buffers of projects that are shared in the channel's call.
+pub struct ChannelReviews {
+    pub channel_id: ChannelId,
+    threads: Vec<ReviewThread>,
+    channel_store: Entity<ChannelStore>,
+    user_store: Entity<UserStore>,
+    rpc: Arc<Client>,
+    _subscription: Subscription,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReviewThread {
+    pub id: u64,
+    /// The path of the project buffer that's being reviewed, starting with its worktree's root
+    /// name, or `None` for the channel's notes.
+    pub path: Option<Arc<str>>,
+    pub range: Range<Anchor>,
+    /// The project's id for project buffers, or the notes' epoch, which `range` is only valid in.
+    pub anchor_epoch: u64,
+    /// The row the thread was created at, which, along with the quoted text, is used to find the
+    /// range in other buffers, such as after the project has been shared again.
+    pub start_row: u32,
+    pub quoted_text: String,
+    pub created_by: Arc<User>,
+    pub resolved_by: Option<Arc<User>>,
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReviewComment {
+    pub id: u64,
+    pub sender: Arc<User>,
+    pub body: String,
+    pub timestamp: OffsetDateTime,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChannelReviewsEvent {
+    ThreadUpdated { thread_id: u64 },
+}
+
+impl EventEmitter<ChannelReviewsEvent> for ChannelReviews {}
+
+pub fn init(client: &AnyProtoClient) {
+    client.add_model_message_handler(ChannelReviews::handle_thread_updated);
+}
+
+impl ChannelReviews {
+    pub async fn new(
+        channel: Arc<Channel>,
+        channel_store: Entity<ChannelStore>,
+        user_store: Entity<UserStore>,
+        client: Arc<Client>,
+        mut cx: AsyncApp,
+    ) -> Result<Entity<Self>> {
+        let channel_id = channel.id;
+        let subscription = client.subscribe_to_entity(channel_id.0)?;
+
+        let response = client
+            .request(proto::GetReviewThreads {
+                channel_id: channel_id.0,
+            })
+            .await?;
+        let threads = ReviewThread::from_proto_vec(response.threads, &user_store, &mut cx).await?;
+
+        cx.new(|cx| Self {
+            channel_id,
+            threads,
+            channel_store,
+            user_store,
+            rpc: client,
+            _subscription: subscription.set_model(&cx.entity(), &mut cx.to_async()),
+        })
+    }
+
+    pub fn channel(&self, cx: &App) -> Option<Arc<Channel>> {
+        self.channel_store
+            .read(cx)
+            .channel_for_id(self.channel_id)
+            .cloned()
+    }
+
+    /// The channel's review threads, in the order they were created.
+    pub fn threads(&self) -> &[ReviewThread] {
+        &self.threads
+    }
+
+    pub fn thread(&self, thread_id: u64) -> Option<&ReviewThread> {
+        self.threads
+            .binary_search_by_key(&thread_id, |thread| thread.id)
+            .ok()
+            .map(|ix| &self.threads[ix])
+    }
+
+    /// Starts a thread about a range of text, quoting it, with the given comment.
+    pub fn create_thread(
+        &mut self,
+        path: Option<Arc<str>>,
+        range: Range<Anchor>,
+        anchor_epoch: u64,
+        snapshot: &BufferSnapshot,
+        body: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<u64>> {
+        if body.trim().is_empty() {
+            return Task::ready(Err(anyhow!("comment can't be empty")));
+        }
+
+        let request = self.rpc.request(proto::CreateReviewThread {
+            channel_id: self.channel_id.0,
+            path: path.map(|path| path.to_string()),
+            start: Some(serialize_anchor(&range.start)),
+            end: Some(serialize_anchor(&range.end)),
+            anchor_epoch,
+            start_row: range.start.to_point(snapshot).row,
+            quoted_text: snapshot.text_for_range(range).collect(),
+            body,
+        });
+        self.update_thread(request, cx)
+    }
+
+    pub fn reply(
+        &mut self,
+        thread_id: u64,
+        body: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<u64>> {
+        if body.trim().is_empty() {
+            return Task::ready(Err(anyhow!("comment can't be empty")));
+        }
+
+        let request = self.rpc.request(proto::CreateReviewComment {
+            channel_id: self.channel_id.0,
+            thread_id,
+            body,
+        });
+        self.update_thread(request, cx)
+    }
+
+    /// Whether the current user can resolve or reopen the thread, which channel members and the
+    /// thread's author can do.
+    pub fn can_resolve(&self, thread: &ReviewThread, cx: &App) -> bool {
+        let is_author = self
+            .user_store
+            .read(cx)
+            .current_user()
+            .is_some_and(|user| user.id == thread.created_by.id);
+        is_author
+            || matches!(
+                self.channel_store.read(cx).channel_role(self.channel_id),
+                proto::ChannelRole::Admin | proto::ChannelRole::Member
+            )
+    }
+
+    pub fn set_resolved(
+        &mut self,
+        thread_id: u64,
+        resolved: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<u64>> {
+        let request = self.rpc.request(proto::ResolveReviewThread {
+            channel_id: self.channel_id.0,
+            thread_id,
+            resolved,
+        });
+        self.update_thread(request, cx)
+    }
+
+    /// Reloads the threads after reconnecting, since updates may have been missed.
+    pub fn rejoin(&mut self, cx: &mut Context<Self>) {
+        let user_store = self.user_store.clone();
+        let request = self.rpc.request(proto::GetReviewThreads {
+            channel_id: self.channel_id.0,
+        });
+        cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            let threads =
+                ReviewThread::from_proto_vec(response.threads, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                for thread in threads {
+                    this.insert_thread(thread, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn update_thread(
+        &mut self,
+        request: impl 'static + std::future::Future<Output = Result<proto::ReviewThreadResponse>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<u64>> {
+        let user_store = self.user_store.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let thread = request
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("invalid review thread"))?;
+            let thread = ReviewThread::from_proto(thread, &user_store, &mut cx).await?;
+            let thread_id = thread.id;
+            this.update(&mut cx, |this, cx| this.insert_thread(thread, cx))?;
+            Ok(thread_id)
+        })
+    }
+
+    fn insert_thread(&mut self, thread: ReviewThread, cx: &mut Context<Self>) {
+        let thread_id = thread.id;
+        match self
+            .threads
+            .binary_search_by_key(&thread_id, |thread| thread.id)
+        {
+            Ok(ix) => self.threads[ix] = thread,
+            Err(ix) => self.threads.insert(ix, thread),
+        }
+        cx.emit(ChannelReviewsEvent::ThreadUpdated { thread_id });
+        cx.notify();
+    }
+
+    async fn handle_thread_updated(
+        this: Entity<Self>,
+        message: TypedEnvelope<proto::ReviewThreadUpdated>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let user_store = this.update(&mut cx, |this, _| this.user_store.clone())?;
+        let thread = message
+            .payload
+            .thread
+            .ok_or_else(|| anyhow!("empty review thread"))?;
+        let thread = ReviewThread::from_proto(thread, &user_store, &mut cx).await?;
+        this.update(&mut cx, |this, cx| this.insert_thread(thread, cx))
+    }
+}
+
+impl ReviewThread {
+    /// Returns the range that the thread is about in a snapshot of its buffer. When the thread's
+    /// anchors aren't valid in the snapshot, because the buffer has been reloaded or the project
+    /// has been shared again since the thread was created, this is the occurrence of the quoted
+    /// text that's closest to the row the thread was created at, falling back to the start of
+    /// that row.
+    pub fn resolve_range(&self, snapshot: &BufferSnapshot, anchor_epoch: u64) -> Range<Anchor> {
+        if anchor_epoch == self.anchor_epoch
+            && snapshot.can_resolve(&self.range.start)
+            && snapshot.can_resolve(&self.range.end)
+        {
+            self.range.clone()
+        } else if let Some(range) = self.find_quoted_text(snapshot) {
+            range
+        } else {
+            let anchor = snapshot.anchor_before(
+                snapshot.clip_point(Point::new(self.start_row, 0), text::Bias::Left),
+            );
+            anchor..anchor
+        }
+    }
+
+    fn find_quoted_text(&self, snapshot: &BufferSnapshot) -> Option<Range<Anchor>> {
+        if self.quoted_text.is_empty() {
+            return None;
+        }
+        let text = snapshot.text();
+        let start = text
+            .match_indices(self.quoted_text.as_str())
+            .map(|(offset, _)| offset)
+            .min_by_key(|offset| {
+                snapshot
+                    .offset_to_point(*offset)
+                    .row
+                    .abs_diff(self.start_row)
+            })?;
+        Some(snapshot.anchor_before(start)..snapshot.anchor_after(start + self.quoted_text.len()))
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_by.is_some()
+    }
+
+    async fn from_proto(
+        thread: proto::ReviewThread,
+        user_store: &Entity<UserStore>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self::from_proto_vec(vec![thread], user_store, cx)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("invalid review thread"))?)
+    }
+
+    async fn from_proto_vec(
+        threads: Vec<proto::ReviewThread>,
+        user_store: &Entity<UserStore>,
+        cx: &mut AsyncApp,
+    ) -> Result<Vec<Self>> {
+        let user_ids = threads
+            .iter()
+            .flat_map(|thread| {
+                thread
+                    .comments
+                    .iter()
+                    .map(|comment| comment.sender_id)
+                    .chain([thread.created_by])
+                    .chain(thread.resolved_by)
+            })
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let users = user_store
+            .update(cx, |user_store, cx| user_store.get_users(user_ids, cx))?
+            .await?;
+        let user = |user_id: u64| {
+            users
+                .iter()
+                .find(|user| user.id == user_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("no user with id {user_id}"))
+        };
+
+        threads
+            .into_iter()
+            .filter_map(|thread| {
+                let start = thread.start.and_then(deserialize_anchor);
+                let end = thread.end.and_then(deserialize_anchor);
+                let (Some(start), Some(end)) = (start, end) else {
+                    log::error!("review thread {} has invalid anchors", thread.id);
+                    return None;
+                };
+                Some((thread, start..end))
+            })
+            .map(|(thread, range)| {
+                Ok(Self {
+                    id: thread.id,
+                    path: thread.path.map(Arc::from),
+                    range,
+                    anchor_epoch: thread.anchor_epoch,
+                    start_row: thread.start_row,
+                    quoted_text: thread.quoted_text,
+                    created_by: user(thread.created_by)?,
+                    resolved_by: thread.resolved_by.map(user).transpose()?,
+                    comments: thread
+                        .comments
+                        .into_iter()
+                        .map(|comment| {
+                            Ok(ReviewComment {
+                                id: comment.id,
+                                sender: user(comment.sender_id)?,
+                                body: comment.body,
+                                timestamp: OffsetDateTime::from_unix_timestamp(
+                                    comment.timestamp as i64,
+                                )?,
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer, BufferId};
+
+    #[test]
+    fn test_resolve_range() {
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), "one\ntwo\nthree".into());
+        let snapshot = buffer.snapshot();
+        let range =
+            snapshot.anchor_before(Point::new(1, 1))..snapshot.anchor_after(Point::new(2, 2));
+        let mut thread = ReviewThread {
+            id: 1,
+            path: None,
+            range: range.clone(),
+            anchor_epoch: 3,
+            start_row: 1,
+            quoted_text: "wo\nth".into(),
+            created_by: Arc::new(User::default()),
+            resolved_by: None,
+            comments: Vec::new(),
+        };
+        assert_eq!(thread.resolve_range(&snapshot, 3), range);
+
+        // The thread was created before the buffer was reloaded, so the quoted text is found.
+        let range = thread.resolve_range(&snapshot, 2);
+        assert_eq!(range.start.to_point(&snapshot), Point::new(1, 1));
+        assert_eq!(range.end.to_point(&snapshot), Point::new(2, 2));
+
+        // The thread is about the same file in a project that's been shared again, where the
+        // quoted text has moved. The occurrence closest to the thread's row is used.
+        thread.start_row = 5;
+        let other_snapshot = Buffer::new(
+            0,
+            BufferId::new(2).unwrap(),
+            "two\nthree\none\ntwo\nthree\n\n\n\ntwo\nthree".into(),
+        )
+        .snapshot();
+        let range = thread.resolve_range(&other_snapshot, 4);
+        assert_eq!(range.start.to_point(&other_snapshot), Point::new(3, 1));
+        assert_eq!(range.end.to_point(&other_snapshot), Point::new(4, 2));
+
+        // The quoted text has been edited away, so the thread's row is used.
+        thread.quoted_text = "four".into();
+        let range = thread.resolve_range(&other_snapshot, 4);
+        assert_eq!(range.start.to_point(&other_snapshot), Point::new(5, 0));
+        assert_eq!(range.end.to_point(&other_snapshot), Point::new(5, 0));
+    }
+}
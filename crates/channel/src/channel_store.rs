@@ -1,6 +1,9 @@
 mod channel_index;
 
-use crate::{channel_buffer::ChannelBuffer, channel_chat::ChannelChat, ChannelMessage};
+use crate::{
+    channel_buffer::ChannelBuffer, channel_chat::ChannelChat, channel_reviews::ChannelReviews,
+    ChannelMessage,
+};
 use anyhow::{anyhow, Result};
 use channel_index::ChannelIndex;
 use client::{ChannelId, Client, ClientSettings, Subscription, User, UserId, UserStore};
@@ -41,6 +44,7 @@ pub struct ChannelStore {
     update_channels_tx: mpsc::UnboundedSender<proto::UpdateChannels>,
     opened_buffers: HashMap<ChannelId, OpenedModelHandle<ChannelBuffer>>,
     opened_chats: HashMap<ChannelId, OpenedModelHandle<ChannelChat>>,
+    opened_reviews: HashMap<ChannelId, OpenedModelHandle<ChannelReviews>>,
     client: Arc<Client>,
     did_subscribe: bool,
     user_store: Entity<UserStore>,
@@ -194,6 +198,7 @@ impl ChannelStore {
             outgoing_invites: Default::default(),
             opened_buffers: Default::default(),
             opened_chats: Default::default(),
+            opened_reviews: Default::default(),
             update_channels_tx,
             client,
             user_store,
@@ -445,6 +450,22 @@ impl ChannelStore {
         )
     }
 
+    pub fn open_channel_reviews(
+        &mut self,
+        channel_id: ChannelId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<ChannelReviews>>> {
+        let client = self.client.clone();
+        let user_store = self.user_store.clone();
+        let this = cx.entity();
+        self.open_channel_resource(
+            channel_id,
+            |this| &mut this.opened_reviews,
+            |channel, cx| ChannelReviews::new(channel, this, user_store, client, cx),
+            cx,
+        )
+    }
+
     /// Asynchronously open a given resource associated with a channel.
     ///
     /// Make sure that the resource is only opened once, even if this method
@@ -909,6 +930,14 @@ impl ChannelStore {
             }
         }
 
+        for reviews in self.opened_reviews.values() {
+            if let OpenedModelHandle::Open(reviews) = reviews {
+                if let Some(reviews) = reviews.upgrade() {
+                    reviews.update(cx, |reviews, cx| reviews.rejoin(cx));
+                }
+            }
+        }
+
         let mut buffer_versions = Vec::new();
         for buffer in self.opened_buffers.values() {
             if let OpenedModelHandle::Open(buffer) = buffer {
@@ -159,6 +159,13 @@ CREATE TABLE "language_servers" (
 );
 CREATE INDEX "index_language_servers_on_project_id" ON "language_servers" ("project_id");
 
+CREATE TABLE "shared_terminal_grants" (
+    "project_id" INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    "terminal_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY(project_id, terminal_id, user_id)
+);
+
 CREATE TABLE "project_collaborators" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "project_id" INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
@@ -258,6 +265,30 @@ CREATE TABLE "channel_message_mentions" (
     PRIMARY KEY(message_id, start_offset)
 );
 
+CREATE TABLE IF NOT EXISTS "review_threads" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "path" TEXT,
+    "start_anchor" BLOB NOT NULL,
+    "end_anchor" BLOB NOT NULL,
+    "anchor_epoch" INTEGER NOT NULL,
+    "start_row" INTEGER NOT NULL,
+    "quoted_text" TEXT NOT NULL,
+    "created_by" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "resolved_by" INTEGER REFERENCES users (id) ON DELETE SET NULL
+);
+CREATE INDEX "index_review_threads_on_channel_id" ON "review_threads" ("channel_id");
+
+CREATE TABLE IF NOT EXISTS "review_comments" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "thread_id" INTEGER NOT NULL REFERENCES review_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX "index_review_comments_on_thread_id" ON "review_comments" ("thread_id");
+
 CREATE TABLE "channel_members" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS review_threads (
+    id SERIAL PRIMARY KEY,
+    channel_id INTEGER NOT NULL REFERENCES channels(id) ON DELETE CASCADE,
+    path TEXT,
+    start_anchor BYTEA NOT NULL,
+    end_anchor BYTEA NOT NULL,
+    anchor_epoch BIGINT NOT NULL,
+    start_row INTEGER NOT NULL,
+    quoted_text TEXT NOT NULL,
+    created_by INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    resolved_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX "ix_review_threads_on_channel_id" ON review_threads (channel_id);
+
+CREATE TABLE IF NOT EXISTS review_comments (
+    id SERIAL PRIMARY KEY,
+    thread_id INTEGER NOT NULL REFERENCES review_threads(id) ON DELETE CASCADE,
+    sender_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    sent_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX "ix_review_comments_on_thread_id" ON review_comments (thread_id);
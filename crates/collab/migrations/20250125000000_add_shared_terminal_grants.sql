@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS shared_terminal_grants (
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    terminal_id BIGINT NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY (project_id, terminal_id, user_id)
+);
@@ -478,6 +478,11 @@ pub struct CreatedChannelMessage {
     pub notifications: NotificationBatch,
 }
 
+pub struct UpdatedReviewThread {
+    pub channel: channel::Model,
+    pub thread: proto::ReviewThread,
+}
+
 pub struct UpdatedChannelMessage {
     pub message_id: MessageId,
     pub participant_connection_ids: Vec<ConnectionId>,
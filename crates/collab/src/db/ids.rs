@@ -89,6 +89,8 @@ id_type!(NotificationKindId);
 id_type!(ProjectCollaboratorId);
 id_type!(ProjectId);
 id_type!(ReplicaId);
+id_type!(ReviewCommentId);
+id_type!(ReviewThreadId);
 id_type!(RoomId);
 id_type!(RoomParticipantId);
 id_type!(ServerId);
@@ -188,6 +190,15 @@ impl ChannelRole {
         }
     }
 
+    /// True if the role can resolve review threads that were started by someone else.
+    pub fn can_resolve_review_threads(&self) -> bool {
+        use ChannelRole::*;
+        match self {
+            Admin | Member => true,
+            Talker | Guest | Banned => false,
+        }
+    }
+
     /// True if the role can read shared projects.
     pub fn can_read_projects(&self) -> bool {
         use ChannelRole::*;
@@ -1029,6 +1029,88 @@ impl Database {
         .map(|guard| guard.into_inner())
     }
 
+    /// Replaces the grants to type into the given project's shared terminals with the ones that
+    /// its host advertised, returning the connections of the project's guests.
+    pub async fn update_shared_terminal_grants(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        terminals: &[proto::SharedTerminalMetadata],
+    ) -> Result<TransactionGuard<Vec<ConnectionId>>> {
+        self.project_transaction(project_id, |tx| async move {
+            let project = project::Entity::find_by_id(project_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such project"))?;
+            if project.host_connection()? != connection_id {
+                return Err(anyhow!(
+                    "can't share terminals of a project hosted by someone else"
+                ))?;
+            }
+
+            shared_terminal_grant::Entity::delete_many()
+                .filter(shared_terminal_grant::Column::ProjectId.eq(project_id))
+                .exec(&*tx)
+                .await?;
+            let grants = terminals
+                .iter()
+                .flat_map(|terminal| {
+                    terminal.writer_user_ids.iter().map(|user_id| {
+                        shared_terminal_grant::ActiveModel {
+                            project_id: ActiveValue::set(project_id),
+                            terminal_id: ActiveValue::set(terminal.terminal_id as i64),
+                            user_id: ActiveValue::set(UserId::from_proto(*user_id)),
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !grants.is_empty() {
+                shared_terminal_grant::Entity::insert_many(grants)
+                    .exec(&*tx)
+                    .await?;
+            }
+
+            self.project_guest_connection_ids(project_id, &tx).await
+        })
+        .await
+    }
+
+    /// Returns the host connection for input to a shared terminal, if the host lets the given
+    /// guest type into it and their role lets them edit the project.
+    pub async fn host_for_shared_terminal_input(
+        &self,
+        project_id: ProjectId,
+        terminal_id: u64,
+        connection_id: ConnectionId,
+    ) -> Result<ConnectionId> {
+        self.project_transaction(project_id, |tx| async move {
+            let (project, _) = self
+                .access_project(project_id, connection_id, Capability::ReadWrite, &tx)
+                .await?;
+            let collaborator = project_collaborator::Entity::find()
+                .filter(project_collaborator::Column::ProjectId.eq(project_id))
+                .filter(project_collaborator::Column::ConnectionId.eq(connection_id.id as i32))
+                .filter(
+                    project_collaborator::Column::ConnectionServerId
+                        .eq(connection_id.owner_id as i32),
+                )
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("not a collaborator in the project"))?;
+            shared_terminal_grant::Entity::find_by_id((
+                project_id,
+                terminal_id as i64,
+                collaborator.user_id,
+            ))
+            .one(&*tx)
+            .await?
+            .ok_or_else(|| anyhow!("not allowed to type into the terminal"))?;
+            project.host_connection()
+        })
+        .await
+        .map(|guard| guard.into_inner())
+    }
+
     pub async fn connections_for_buffer_update(
         &self,
         project_id: ProjectId,
@@ -0,0 +1,220 @@
+use super::*;
+use prost::Message;
+use time::OffsetDateTime;
+
+impl Database {
+    /// Returns the review threads in the given channel, along with their comments.
+    pub async fn get_review_threads(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ReviewThread>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let rows = review_thread::Entity::find()
+                .filter(review_thread::Column::ChannelId.eq(channel_id))
+                .order_by_asc(review_thread::Column::Id)
+                .all(&*tx)
+                .await?;
+            self.load_review_threads(rows, &tx).await
+        })
+        .await
+    }
+
+    /// Creates a review thread about a range of text, starting with the given comment.
+    pub async fn create_review_thread(
+        &self,
+        user_id: UserId,
+        params: &proto::CreateReviewThread,
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedReviewThread> {
+        self.transaction(|tx| async move {
+            let channel_id = ChannelId::from_proto(params.channel_id);
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let start = params
+                .start
+                .as_ref()
+                .ok_or_else(|| anyhow!("invalid start anchor"))?;
+            let end = params
+                .end
+                .as_ref()
+                .ok_or_else(|| anyhow!("invalid end anchor"))?;
+            let timestamp = to_primitive_date_time(timestamp);
+            let thread = review_thread::ActiveModel {
+                id: ActiveValue::NotSet,
+                channel_id: ActiveValue::Set(channel_id),
+                path: ActiveValue::Set(params.path.clone()),
+                start_anchor: ActiveValue::Set(start.encode_to_vec()),
+                end_anchor: ActiveValue::Set(end.encode_to_vec()),
+                anchor_epoch: ActiveValue::Set(params.anchor_epoch as i64),
+                start_row: ActiveValue::Set(params.start_row as i32),
+                quoted_text: ActiveValue::Set(params.quoted_text.clone()),
+                created_by: ActiveValue::Set(user_id),
+                created_at: ActiveValue::Set(timestamp),
+                resolved_by: ActiveValue::Set(None),
+            }
+            .insert(&*tx)
+            .await?;
+            review_comment::ActiveModel {
+                id: ActiveValue::NotSet,
+                thread_id: ActiveValue::Set(thread.id),
+                sender_id: ActiveValue::Set(user_id),
+                body: ActiveValue::Set(params.body.clone()),
+                sent_at: ActiveValue::Set(timestamp),
+            }
+            .insert(&*tx)
+            .await?;
+
+            let thread = self.load_review_thread(thread, &tx).await?;
+            Ok(UpdatedReviewThread { channel, thread })
+        })
+        .await
+    }
+
+    /// Adds a comment to the end of a review thread.
+    pub async fn create_review_comment(
+        &self,
+        channel_id: ChannelId,
+        thread_id: ReviewThreadId,
+        user_id: UserId,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedReviewThread> {
+        self.transaction(|tx| async move {
+            let (channel, thread, _) = self
+                .review_thread_for_participant(channel_id, thread_id, user_id, &tx)
+                .await?;
+
+            review_comment::ActiveModel {
+                id: ActiveValue::NotSet,
+                thread_id: ActiveValue::Set(thread.id),
+                sender_id: ActiveValue::Set(user_id),
+                body: ActiveValue::Set(body.to_string()),
+                sent_at: ActiveValue::Set(to_primitive_date_time(timestamp)),
+            }
+            .insert(&*tx)
+            .await?;
+
+            let thread = self.load_review_thread(thread, &tx).await?;
+            Ok(UpdatedReviewThread { channel, thread })
+        })
+        .await
+    }
+
+    /// Marks a review thread as resolved by the given user, or reopens it. Only channel members
+    /// and the thread's author can do this.
+    pub async fn set_review_thread_resolved(
+        &self,
+        channel_id: ChannelId,
+        thread_id: ReviewThreadId,
+        user_id: UserId,
+        resolved: bool,
+    ) -> Result<UpdatedReviewThread> {
+        self.transaction(|tx| async move {
+            let (channel, thread, role) = self
+                .review_thread_for_participant(channel_id, thread_id, user_id, &tx)
+                .await?;
+            if thread.created_by != user_id && !role.can_resolve_review_threads() {
+                Err(anyhow!(
+                    "only channel members and the thread's author can resolve it"
+                ))?;
+            }
+
+            let thread = review_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                resolved_by: ActiveValue::Set(resolved.then_some(user_id)),
+                ..Default::default()
+            }
+            .update(&*tx)
+            .await?;
+
+            let thread = self.load_review_thread(thread, &tx).await?;
+            Ok(UpdatedReviewThread { channel, thread })
+        })
+        .await
+    }
+
+    async fn review_thread_for_participant(
+        &self,
+        channel_id: ChannelId,
+        thread_id: ReviewThreadId,
+        user_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<(channel::Model, review_thread::Model, ChannelRole)> {
+        let thread = review_thread::Entity::find_by_id(thread_id)
+            .filter(review_thread::Column::ChannelId.eq(channel_id))
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such review thread"))?;
+        let channel = self.get_channel_internal(channel_id, tx).await?;
+        let role = self
+            .check_user_is_channel_participant(&channel, user_id, tx)
+            .await?;
+        Ok((channel, thread, role))
+    }
+
+    async fn load_review_thread(
+        &self,
+        row: review_thread::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<proto::ReviewThread> {
+        Ok(self
+            .load_review_threads(vec![row], tx)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("failed to load review thread"))?)
+    }
+
+    async fn load_review_threads(
+        &self,
+        rows: Vec<review_thread::Model>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ReviewThread>> {
+        let mut comments = review_comment::Entity::find()
+            .filter(review_comment::Column::ThreadId.is_in(rows.iter().map(|row| row.id)))
+            .order_by_asc(review_comment::Column::Id)
+            .stream(tx)
+            .await?;
+        let mut comments_by_thread =
+            HashMap::<ReviewThreadId, Vec<proto::ReviewComment>>::default();
+        while let Some(comment) = comments.next().await {
+            let comment = comment?;
+            comments_by_thread
+                .entry(comment.thread_id)
+                .or_default()
+                .push(proto::ReviewComment {
+                    id: comment.id.to_proto(),
+                    sender_id: comment.sender_id.to_proto(),
+                    body: comment.body,
+                    timestamp: comment.sent_at.assume_utc().unix_timestamp() as u64,
+                });
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|row| proto::ReviewThread {
+                id: row.id.to_proto(),
+                path: row.path,
+                start: proto::Anchor::decode(row.start_anchor.as_slice()).ok(),
+                end: proto::Anchor::decode(row.end_anchor.as_slice()).ok(),
+                anchor_epoch: row.anchor_epoch as u64,
+                start_row: row.start_row as u32,
+                quoted_text: row.quoted_text,
+                created_by: row.created_by.to_proto(),
+                resolved_by: row.resolved_by.map(|user_id| user_id.to_proto()),
+                comments: comments_by_thread.remove(&row.id).unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+fn to_primitive_date_time(timestamp: OffsetDateTime) -> PrimitiveDateTime {
+    let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+    PrimitiveDateTime::new(timestamp.date(), timestamp.time())
+}
@@ -27,9 +27,12 @@ pub mod processed_stripe_event;
 pub mod project;
 pub mod project_collaborator;
 pub mod rate_buckets;
+pub mod review_comment;
+pub mod review_thread;
 pub mod room;
 pub mod room_participant;
 pub mod server;
+pub mod shared_terminal_grant;
 pub mod signup;
 pub mod user;
 pub mod user_feature;
@@ -0,0 +1,53 @@
+use crate::db::{ChannelId, ReviewThreadId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+/// A thread of review comments about a range of text in a channel's notes or in a project buffer.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "review_threads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: ReviewThreadId,
+    pub channel_id: ChannelId,
+    /// The path of the project buffer, starting with its worktree's root name, or `None` for the
+    /// channel's notes.
+    pub path: Option<String>,
+    /// The start of the range, as an encoded `proto::Anchor`.
+    pub start_anchor: Vec<u8>,
+    /// The end of the range, as an encoded `proto::Anchor`.
+    pub end_anchor: Vec<u8>,
+    /// The project's id for project buffers, or the notes' epoch, which the anchors are only
+    /// valid in.
+    pub anchor_epoch: i64,
+    pub start_row: i32,
+    pub quoted_text: String,
+    pub created_by: UserId,
+    pub created_at: PrimitiveDateTime,
+    pub resolved_by: Option<UserId>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::channel::Column::Id"
+    )]
+    Channel,
+    #[sea_orm(has_many = "super::review_comment::Entity")]
+    Comments,
+}
+
+impl Related<super::channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Channel.def()
+    }
+}
+
+impl Related<super::review_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comments.def()
+    }
+}
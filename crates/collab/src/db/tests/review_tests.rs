@@ -0,0 +1,151 @@
+use super::new_test_user;
+use crate::{
+    db::{ChannelRole, ChannelVisibility, Database, ReviewThreadId},
+    test_both_dbs,
+};
+use rpc::proto;
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+test_both_dbs!(
+    test_review_threads,
+    test_review_threads_postgres,
+    test_review_threads_sqlite
+);
+
+async fn test_review_threads(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let user_c = new_test_user(db, "user_c@example.com").await;
+    let guest = new_test_user(db, "guest@example.com").await;
+    let channel = db.create_root_channel("channel", user_a).await.unwrap();
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+    db.set_channel_visibility(channel, ChannelVisibility::Public, user_a)
+        .await
+        .unwrap();
+    db.invite_channel_member(channel, guest, user_a, ChannelRole::Guest)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, guest, true)
+        .await
+        .unwrap();
+
+    let anchor = |offset| proto::Anchor {
+        replica_id: 1,
+        timestamp: 2,
+        offset,
+        bias: proto::Bias::Left as i32,
+        buffer_id: Some(3),
+    };
+    let thread = db
+        .create_review_thread(
+            user_a,
+            &proto::CreateReviewThread {
+                channel_id: channel.to_proto(),
+                path: Some("zed/src/main.rs".into()),
+                start: Some(anchor(4)),
+                end: Some(anchor(12)),
+                anchor_epoch: 5,
+                start_row: 1,
+                quoted_text: "fn main()".into(),
+                body: "Can this be async?".into(),
+            },
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap()
+        .thread;
+    assert_eq!(thread.path.as_deref(), Some("zed/src/main.rs"));
+    assert_eq!(thread.start, Some(anchor(4)));
+    assert_eq!(thread.end, Some(anchor(12)));
+    assert_eq!(thread.anchor_epoch, 5);
+    assert_eq!(thread.quoted_text, "fn main()");
+    assert_eq!(thread.created_by, user_a.to_proto());
+    assert_eq!(thread.resolved_by, None);
+    assert_eq!(comment_bodies(&thread), ["Can this be async?"]);
+
+    let thread_id = ReviewThreadId::from_proto(thread.id);
+    db.create_review_comment(
+        channel,
+        thread_id,
+        user_b,
+        "Not yet.",
+        OffsetDateTime::now_utc(),
+    )
+    .await
+    .unwrap();
+
+    // Guests can reply to threads, but can't resolve threads that they didn't start.
+    db.create_review_comment(channel, thread_id, guest, "+1", OffsetDateTime::now_utc())
+        .await
+        .unwrap();
+    db.set_review_thread_resolved(channel, thread_id, guest, true)
+        .await
+        .unwrap_err();
+    let updated_thread = db
+        .set_review_thread_resolved(channel, thread_id, user_b, true)
+        .await
+        .unwrap();
+    assert_eq!(updated_thread.channel.id, channel);
+    assert_eq!(updated_thread.thread.resolved_by, Some(user_b.to_proto()));
+
+    let threads = db.get_review_threads(channel, user_a).await.unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(
+        comment_bodies(&threads[0]),
+        ["Can this be async?", "Not yet.", "+1"]
+    );
+    assert_eq!(threads[0].comments[1].sender_id, user_b.to_proto());
+
+    // Guests can resolve the threads they started.
+    let guest_thread = db
+        .create_review_thread(
+            guest,
+            &proto::CreateReviewThread {
+                channel_id: channel.to_proto(),
+                path: None,
+                start: Some(anchor(0)),
+                end: Some(anchor(3)),
+                anchor_epoch: 1,
+                start_row: 0,
+                quoted_text: "Zed".into(),
+                body: "Typo?".into(),
+            },
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap()
+        .thread;
+    let guest_thread_id = ReviewThreadId::from_proto(guest_thread.id);
+    let guest_thread = db
+        .set_review_thread_resolved(channel, guest_thread_id, guest, true)
+        .await
+        .unwrap()
+        .thread;
+    assert_eq!(guest_thread.resolved_by, Some(guest.to_proto()));
+
+    // Users who aren't in the channel can't see or reply to its reviews.
+    db.get_review_threads(channel, user_c).await.unwrap_err();
+    db.create_review_comment(channel, thread_id, user_c, "Hi", OffsetDateTime::now_utc())
+        .await
+        .unwrap_err();
+
+    // Threads can only be changed through the channel they're in.
+    let other_channel = db.create_root_channel("other", user_c).await.unwrap();
+    db.set_review_thread_resolved(other_channel, thread_id, user_c, false)
+        .await
+        .unwrap_err();
+}
+
+fn comment_bodies(thread: &proto::ReviewThread) -> Vec<&str> {
+    thread
+        .comments
+        .iter()
+        .map(|comment| comment.body.as_str())
+        .collect()
+}
@@ -8,7 +8,8 @@ use crate::{
         self, BufferId, Capability, Channel, ChannelId, ChannelRole, ChannelsForUser,
         CreatedChannelMessage, Database, InviteMemberResult, MembershipUpdated, MessageId,
         NotificationId, Project, ProjectId, RejoinedProject, RemoveChannelMemberResult, ReplicaId,
-        RespondToChannelInvite, RoomId, ServerId, UpdatedChannelMessage, User, UserId,
+        RespondToChannelInvite, ReviewThreadId, RoomId, ServerId, UpdatedChannelMessage,
+        UpdatedReviewThread, User, UserId,
     },
     executor::Executor,
     AppState, Config, Error, RateLimit, Result,
@@ -301,6 +302,7 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::GetReferences>)
             .add_request_handler(forward_find_search_candidates_request)
             .add_request_handler(forward_read_only_project_request::<proto::GetDocumentHighlights>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetDocumentSymbols>)
             .add_request_handler(forward_read_only_project_request::<proto::GetProjectSymbols>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferForSymbol>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferById>)
@@ -308,8 +310,10 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
             .add_request_handler(forward_read_only_project_request::<proto::ResolveInlayHint>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
+            .add_request_handler(forward_read_only_project_request::<proto::LoadBinaryFile>)
             .add_request_handler(forward_read_only_project_request::<proto::GitBranches>)
             .add_request_handler(forward_read_only_project_request::<proto::GetStagedText>)
+            .add_request_handler(forward_read_only_project_request::<proto::LoadCommittedText>)
             .add_request_handler(
                 forward_mutating_project_request::<proto::RegisterBufferWithLanguageServers>,
             )
@@ -376,6 +380,10 @@ impl Server {
             .add_request_handler(update_channel_message)
             .add_request_handler(get_channel_messages)
             .add_request_handler(get_channel_messages_by_id)
+            .add_request_handler(get_review_threads)
+            .add_request_handler(create_review_thread)
+            .add_request_handler(create_review_comment)
+            .add_request_handler(resolve_review_thread)
             .add_request_handler(get_notifications)
             .add_request_handler(mark_notification_as_read)
             .add_request_handler(move_channel)
@@ -394,8 +402,13 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::Stage>)
             .add_request_handler(forward_mutating_project_request::<proto::Unstage>)
             .add_request_handler(forward_mutating_project_request::<proto::Commit>)
+            .add_request_handler(forward_mutating_project_request::<proto::SaveBinaryFile>)
             .add_message_handler(broadcast_project_message_from_host::<proto::AdvertiseContexts>)
             .add_message_handler(update_context)
+            .add_message_handler(advertise_shared_terminals)
+            .add_message_handler(broadcast_shared_terminal_message::<proto::UpdateSharedTerminal>)
+            .add_request_handler(forward_read_only_project_request::<proto::OpenSharedTerminal>)
+            .add_request_handler(forward_shared_terminal_input)
             .add_request_handler({
                 let app_state = app_state.clone();
                 move |request, response, session| {
@@ -2301,6 +2314,67 @@ async fn broadcast_project_message_from_host<T: EntityMessage<Entity = ShareProj
     Ok(())
 }
 
+/// Notify other participants of the terminals the host is sharing, storing who the host lets type
+/// into them.
+async fn advertise_shared_terminals(
+    request: proto::AdvertiseSharedTerminals,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let guest_connection_ids = session
+        .db()
+        .await
+        .update_shared_terminal_grants(project_id, session.connection_id, &request.terminals)
+        .await?;
+    broadcast(
+        Some(session.connection_id),
+        guest_connection_ids.iter().copied(),
+        |connection_id| {
+            session
+                .peer
+                .forward_send(session.connection_id, connection_id, request.clone())
+        },
+    );
+    Ok(())
+}
+
+/// Forward a guest's input to a terminal that the host is sharing, if the host lets them type
+/// into it.
+async fn forward_shared_terminal_input(
+    request: proto::SharedTerminalInput,
+    response: Response<proto::SharedTerminalInput>,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let host_connection_id = session
+        .db()
+        .await
+        .host_for_shared_terminal_input(project_id, request.terminal_id, session.connection_id)
+        .await?;
+    let payload = session
+        .peer
+        .forward_request(session.connection_id, host_connection_id, request)
+        .await?;
+    response.send(payload)?;
+    Ok(())
+}
+
+/// Notify other participants of the output of the terminals the host is sharing. Unlike other
+/// project messages, these are rejected from guests, so that they can't pass off output as the
+/// host's.
+async fn broadcast_shared_terminal_message<T: EntityMessage<Entity = ShareProject>>(
+    request: T,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.remote_entity_id());
+    session
+        .db()
+        .await
+        .check_user_is_project_host(project_id, session.connection_id)
+        .await?;
+    broadcast_project_message_from_host(request, session).await
+}
+
 /// Start following another user in a call.
 async fn follow(
     request: proto::Follow,
@@ -3936,6 +4010,118 @@ async fn get_channel_messages_by_id(
     Ok(())
 }
 
+/// Retrieve the review threads in a channel
+async fn get_review_threads(
+    request: proto::GetReviewThreads,
+    response: Response<proto::GetReviewThreads>,
+    session: Session,
+) -> Result<()> {
+    let threads = session
+        .db()
+        .await
+        .get_review_threads(ChannelId::from_proto(request.channel_id), session.user_id())
+        .await?;
+    response.send(proto::GetReviewThreadsResponse { threads })?;
+    Ok(())
+}
+
+/// Start a review thread about a range of text in a channel's notes or a project buffer
+async fn create_review_thread(
+    mut request: proto::CreateReviewThread,
+    response: Response<proto::CreateReviewThread>,
+    session: Session,
+) -> Result<()> {
+    validate_review_comment(&request.body)?;
+    request.body = request.body.trim().to_string();
+    let updated_thread = session
+        .db()
+        .await
+        .create_review_thread(session.user_id(), &request, OffsetDateTime::now_utc())
+        .await?;
+    broadcast_review_thread(updated_thread, response, &session).await
+}
+
+/// Reply to a review thread
+async fn create_review_comment(
+    request: proto::CreateReviewComment,
+    response: Response<proto::CreateReviewComment>,
+    session: Session,
+) -> Result<()> {
+    validate_review_comment(&request.body)?;
+    let updated_thread = session
+        .db()
+        .await
+        .create_review_comment(
+            ChannelId::from_proto(request.channel_id),
+            ReviewThreadId::from_proto(request.thread_id),
+            session.user_id(),
+            request.body.trim(),
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    broadcast_review_thread(updated_thread, response, &session).await
+}
+
+/// Resolve or reopen a review thread
+async fn resolve_review_thread(
+    request: proto::ResolveReviewThread,
+    response: Response<proto::ResolveReviewThread>,
+    session: Session,
+) -> Result<()> {
+    let updated_thread = session
+        .db()
+        .await
+        .set_review_thread_resolved(
+            ChannelId::from_proto(request.channel_id),
+            ReviewThreadId::from_proto(request.thread_id),
+            session.user_id(),
+            request.resolved,
+        )
+        .await?;
+    broadcast_review_thread(updated_thread, response, &session).await
+}
+
+fn validate_review_comment(body: &str) -> Result<()> {
+    let body = body.trim();
+    if body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"))?;
+    }
+    Ok(())
+}
+
+/// Responds with a review thread that changed, and sends it to everyone else who can see its
+/// channel.
+async fn broadcast_review_thread<T: RequestMessage<Response = proto::ReviewThreadResponse>>(
+    updated_thread: UpdatedReviewThread,
+    response: Response<T>,
+    session: &Session,
+) -> Result<()> {
+    let UpdatedReviewThread { channel, thread } = updated_thread;
+    let update = proto::ReviewThreadUpdated {
+        channel_id: channel.id.to_proto(),
+        thread: Some(thread.clone()),
+    };
+    {
+        let pool = session.connection_pool().await;
+        broadcast(
+            Some(session.connection_id),
+            pool.channel_connection_ids(channel.root_id())
+                .filter_map(|(connection_id, role)| {
+                    role.can_see_channel(channel.visibility)
+                        .then_some(connection_id)
+                }),
+            |connection_id| session.peer.send(connection_id, update.clone()),
+        );
+    }
+    response.send(proto::ReviewThreadResponse {
+        thread: Some(thread),
+    })?;
+    Ok(())
+}
+
 /// Retrieve the current users notifications
 async fn get_notifications(
     request: proto::GetNotifications,
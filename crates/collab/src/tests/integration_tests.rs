@@ -35,6 +35,7 @@ use project::{
     DiagnosticSummary, HoverBlockKind, Project, ProjectPath,
 };
 use rand::prelude::*;
+use rpc::proto;
 use serde_json::json;
 use settings::SettingsStore;
 use std::{
@@ -6737,3 +6738,91 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.as_ref(), "totally-new-branch");
 }
+
+#[gpui::test]
+async fn test_shared_terminal_input_requires_grant(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let _project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let _project_c = client_c.join_remote_project(project_id, cx_c).await;
+
+    // The host lets B, but not C, type into one of its terminals.
+    client_a
+        .send(proto::AdvertiseSharedTerminals {
+            project_id,
+            terminals: vec![proto::SharedTerminalMetadata {
+                terminal_id: 1,
+                title: "zsh".into(),
+                writer_user_ids: vec![client_b.user_id().unwrap()],
+            }],
+        })
+        .unwrap();
+    executor.run_until_parked();
+
+    let input = |terminal_id| proto::SharedTerminalInput {
+        project_id,
+        terminal_id,
+        input: b"ls\n".to_vec(),
+    };
+
+    // C's input is rejected by the server, before it reaches the host.
+    let error = client_c.request(input(1)).await.unwrap_err();
+    assert!(error.to_string().contains("not allowed to type"));
+
+    // B's input is forwarded to the host, which isn't actually sharing a terminal here.
+    let error = client_b.request(input(1)).await.unwrap_err();
+    assert!(error.to_string().contains("terminal isn't shared"));
+    let error = client_b.request(input(2)).await.unwrap_err();
+    assert!(error.to_string().contains("not allowed to type"));
+
+    // Guests can't grant themselves access.
+    client_c
+        .send(proto::AdvertiseSharedTerminals {
+            project_id,
+            terminals: vec![proto::SharedTerminalMetadata {
+                terminal_id: 1,
+                title: "zsh".into(),
+                writer_user_ids: vec![client_c.user_id().unwrap()],
+            }],
+        })
+        .unwrap();
+    executor.run_until_parked();
+    let error = client_c.request(input(1)).await.unwrap_err();
+    assert!(error.to_string().contains("not allowed to type"));
+
+    // Once the host stops letting B type, their input is rejected too.
+    client_a
+        .send(proto::AdvertiseSharedTerminals {
+            project_id,
+            terminals: vec![proto::SharedTerminalMetadata {
+                terminal_id: 1,
+                title: "zsh".into(),
+                writer_user_ids: Vec::new(),
+            }],
+        })
+        .unwrap();
+    executor.run_until_parked();
+    let error = client_b.request(input(1)).await.unwrap_err();
+    assert!(error.to_string().contains("not allowed to type"));
+}
@@ -342,6 +342,10 @@ impl ChannelView {
         self.channel_buffer.read(cx).channel(cx)
     }
 
+    pub fn channel_buffer(&self) -> &Entity<ChannelBuffer> {
+        &self.channel_buffer
+    }
+
     fn handle_channel_buffer_event(
         &mut self,
         _: &Entity<ChannelBuffer>,
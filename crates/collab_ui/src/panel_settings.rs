@@ -94,6 +94,13 @@ pub struct NotificationPanelSettings {
     pub default_width: Pixels,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct ReviewPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct PanelSettingsContent {
     /// Whether to show the panel button in the status bar.
@@ -158,6 +165,19 @@ impl Settings for NotificationPanelSettings {
     }
 }
 
+impl Settings for ReviewPanelSettings {
+    const KEY: Option<&'static str> = Some("review_panel");
+
+    type FileContent = PanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::App,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
+
 impl Settings for MessageEditorSettings {
     const KEY: Option<&'static str> = Some("message_editor");
 
@@ -0,0 +1,1026 @@
+use crate::{channel_view::ChannelView, ReviewPanelSettings};
+use anyhow::{anyhow, Result};
+use call::{room, ActiveCall};
+use channel::{ChannelReviews, ChannelReviewsEvent, ChannelStore, ReviewThread};
+use client::ChannelId;
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    scroll::Autoscroll,
+    Editor, EditorElement, EditorStyle,
+};
+use gpui::{
+    actions, div, AnyElement, App, AsyncWindowContext, Context, Entity, EntityId, EventEmitter,
+    FocusHandle, Focusable, FontStyle, InteractiveElement, IntoElement, ParentElement, Render,
+    ScrollHandle, StatefulInteractiveElement, Styled, Subscription, Task, TextStyle, WeakEntity,
+    Window,
+};
+use language::{Anchor, BufferSnapshot, Point, ToOffset, ToPoint};
+use menu::{Cancel, Confirm};
+use project::{Fs, Project};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::{ops::Range, path::Path, sync::Arc};
+use theme::ThemeSettings;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Avatar, Tab, TextSize, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::NotificationId,
+    Toast, Workspace,
+};
+
+const REVIEW_PANEL_KEY: &str = "ReviewPanel";
+const QUOTED_LINES: usize = 4;
+
+actions!(review_panel, [ToggleFocus, CommentOnSelection]);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<ReviewPanel>(window, cx);
+        });
+        workspace.register_action(|workspace, _: &CommentOnSelection, window, cx| {
+            let Some(panel) = workspace.panel::<ReviewPanel>(cx) else {
+                return;
+            };
+            match panel.read(cx).quote_active_item(workspace, cx) {
+                Ok(quote) => {
+                    workspace.focus_panel::<ReviewPanel>(window, cx);
+                    panel.update(cx, |panel, cx| panel.start_thread(quote, window, cx));
+                }
+                Err(message) => {
+                    struct ReviewCommentToast;
+
+                    workspace.show_toast(
+                        Toast::new(NotificationId::unique::<ReviewCommentToast>(), message),
+                        cx,
+                    );
+                }
+            }
+        });
+    })
+    .detach();
+}
+
+/// A panel for discussing code with the members of the channel you're in a call with.
+pub struct ReviewPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    channel_store: Entity<ChannelStore>,
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    focus_handle: FocusHandle,
+    active_reviews: Option<(Entity<ChannelReviews>, Subscription)>,
+    comment_editor: Entity<Editor>,
+    pending_quote: Option<ReviewQuote>,
+    replying_to: Option<u64>,
+    selected_thread: Option<u64>,
+    show_resolved: bool,
+    scroll_handle: ScrollHandle,
+    reviewed_editors: HashMap<EntityId, (WeakEntity<Editor>, Vec<CustomBlockId>)>,
+    local_timezone: UtcOffset,
+    subscriptions: Vec<Subscription>,
+}
+
+/// The text that a new thread will be about.
+struct ReviewQuote {
+    path: Option<Arc<str>>,
+    range: Range<Anchor>,
+    anchor_epoch: u64,
+    snapshot: BufferSnapshot,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedReviewPanel {
+    width: Option<Pixels>,
+}
+
+impl ReviewPanel {
+    pub fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let project = workspace.project().clone();
+        let workspace_entity = cx.entity();
+
+        cx.new(|cx| {
+            let comment_editor = cx.new(|cx| {
+                let mut editor = Editor::auto_height(6, window, cx);
+                editor.set_placeholder_text("Leave a comment…", cx);
+                editor
+            });
+
+            let local_offset = chrono::Local::now().offset().local_minus_utc();
+            let mut this = Self {
+                workspace: workspace_entity.downgrade(),
+                project: project.clone(),
+                channel_store: ChannelStore::global(cx),
+                fs,
+                width: None,
+                pending_serialization: Task::ready(None),
+                focus_handle: cx.focus_handle(),
+                active_reviews: None,
+                comment_editor,
+                pending_quote: None,
+                replying_to: None,
+                selected_thread: None,
+                show_resolved: false,
+                scroll_handle: ScrollHandle::new(),
+                reviewed_editors: HashMap::default(),
+                local_timezone: UtcOffset::from_whole_seconds(local_offset).unwrap(),
+                subscriptions: Vec::new(),
+            };
+
+            let channel_id = ActiveCall::global(cx)
+                .read(cx)
+                .room()
+                .and_then(|room| room.read(cx).channel_id());
+            this.set_channel(channel_id, cx);
+
+            this.subscriptions.extend([
+                cx.subscribe(
+                    &ActiveCall::global(cx),
+                    |this, _, event: &room::Event, cx| match event {
+                        room::Event::RoomJoined { channel_id } => this.set_channel(*channel_id, cx),
+                        room::Event::RoomLeft { .. } => this.set_channel(None, cx),
+                        _ => {}
+                    },
+                ),
+                cx.subscribe(
+                    &workspace_entity,
+                    |this, _, event: &workspace::Event, cx| {
+                        if let workspace::Event::ItemAdded { .. } = event {
+                            this.refresh_blocks(cx);
+                        }
+                    },
+                ),
+                cx.subscribe(&project, |this, _, event: &project::Event, cx| {
+                    if let project::Event::RemoteIdChanged(_) = event {
+                        this.refresh_blocks(cx);
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+            ]);
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(REVIEW_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedReviewPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update_in(&mut cx, |workspace, window, cx| {
+                let panel = Self::new(workspace, window, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        REVIEW_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedReviewPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    pub fn reviews(&self) -> Option<&Entity<ChannelReviews>> {
+        self.active_reviews.as_ref().map(|(reviews, _)| reviews)
+    }
+
+    fn set_channel(&mut self, channel_id: Option<ChannelId>, cx: &mut Context<Self>) {
+        if self.reviews().map(|reviews| reviews.read(cx).channel_id) == channel_id {
+            return;
+        }
+
+        self.active_reviews = None;
+        self.pending_quote = None;
+        self.replying_to = None;
+        self.selected_thread = None;
+        self.refresh_blocks(cx);
+        cx.notify();
+
+        let Some(channel_id) = channel_id else {
+            return;
+        };
+        let open_reviews = self
+            .channel_store
+            .update(cx, |store, cx| store.open_channel_reviews(channel_id, cx));
+        cx.spawn(|this, mut cx| async move {
+            let reviews = open_reviews.await?;
+            this.update(&mut cx, |this, cx| {
+                let call_channel_id = ActiveCall::global(cx)
+                    .read(cx)
+                    .room()
+                    .and_then(|room| room.read(cx).channel_id());
+                if call_channel_id != Some(channel_id) {
+                    return;
+                }
+                let subscription = cx.subscribe(&reviews, Self::on_reviews_event);
+                this.active_reviews = Some((reviews, subscription));
+                this.refresh_blocks(cx);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn on_reviews_event(
+        &mut self,
+        _: Entity<ChannelReviews>,
+        event: &ChannelReviewsEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            ChannelReviewsEvent::ThreadUpdated { .. } => self.refresh_blocks(cx),
+        }
+        cx.notify();
+    }
+
+    fn quote_active_item(
+        &self,
+        workspace: &Workspace,
+        cx: &App,
+    ) -> Result<ReviewQuote, &'static str> {
+        let Some(reviews) = self.reviews() else {
+            return Err("Join a channel call to review code with its members");
+        };
+        let Some(item) = workspace.active_item(cx) else {
+            return Err("Select some text to comment on");
+        };
+
+        if let Some(channel_view) = item.downcast::<ChannelView>() {
+            let channel_view = channel_view.read(cx);
+            let channel_buffer = channel_view.channel_buffer().read(cx);
+            if channel_buffer.channel_id != reviews.read(cx).channel_id {
+                return Err("Only the notes of the channel you're in a call with can be reviewed");
+            }
+            return quote_selection(&channel_view.editor, None, channel_buffer.epoch(), cx)
+                .ok_or("Select some text to comment on");
+        }
+
+        let Some(editor) = item.downcast::<Editor>() else {
+            return Err("Select some text to comment on");
+        };
+        let Some(path) = project_buffer_path(&editor, cx) else {
+            return Err("Only files in the project can be reviewed");
+        };
+        let Some(project_id) = self.project.read(cx).remote_id() else {
+            return Err("Share the project to review its files");
+        };
+        quote_selection(&editor, Some(path), project_id, cx).ok_or("Select some text to comment on")
+    }
+
+    fn start_thread(&mut self, quote: ReviewQuote, window: &mut Window, cx: &mut Context<Self>) {
+        self.pending_quote = Some(quote);
+        self.replying_to = None;
+        window.focus(&self.comment_editor.read(cx).focus_handle(cx));
+        cx.notify();
+    }
+
+    fn reply_to(&mut self, thread_id: u64, window: &mut Window, cx: &mut Context<Self>) {
+        self.pending_quote = None;
+        self.replying_to = Some(thread_id);
+        self.selected_thread = Some(thread_id);
+        window.focus(&self.comment_editor.read(cx).focus_handle(cx));
+        cx.notify();
+    }
+
+    fn select_thread(&mut self, thread_id: u64, cx: &mut Context<Self>) {
+        let Some(reviews) = self.reviews() else {
+            return;
+        };
+        if reviews
+            .read(cx)
+            .thread(thread_id)
+            .is_some_and(|thread| thread.is_resolved())
+        {
+            self.show_resolved = true;
+        }
+        if let Some(ix) = self
+            .visible_threads(cx)
+            .iter()
+            .position(|thread| thread.id == thread_id)
+        {
+            self.scroll_handle.scroll_to_item(ix);
+        }
+        self.selected_thread = Some(thread_id);
+        cx.notify();
+    }
+
+    fn set_resolved(&mut self, thread_id: u64, resolved: bool, cx: &mut Context<Self>) {
+        if let Some(reviews) = self.reviews() {
+            reviews
+                .update(cx, |reviews, cx| {
+                    reviews.set_resolved(thread_id, resolved, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(reviews) = self.reviews().cloned() else {
+            return;
+        };
+        let body = self.comment_editor.read(cx).text(cx);
+        if body.trim().is_empty() {
+            return;
+        }
+
+        let task = if let Some(quote) = self.pending_quote.take() {
+            reviews.update(cx, |reviews, cx| {
+                reviews.create_thread(
+                    quote.path,
+                    quote.range,
+                    quote.anchor_epoch,
+                    &quote.snapshot,
+                    body,
+                    cx,
+                )
+            })
+        } else if let Some(thread_id) = self.replying_to.take() {
+            reviews.update(cx, |reviews, cx| reviews.reply(thread_id, body, cx))
+        } else {
+            return;
+        };
+
+        self.comment_editor
+            .update(cx, |editor, cx| editor.clear(window, cx));
+        window.focus(&self.focus_handle);
+        cx.spawn(|this, mut cx| async move {
+            let thread_id = task.await?;
+            this.update(&mut cx, |this, cx| this.select_thread(thread_id, cx))
+        })
+        .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_quote.take().is_some() || self.replying_to.take().is_some() {
+            self.comment_editor
+                .update(cx, |editor, cx| editor.clear(window, cx));
+            window.focus(&self.focus_handle);
+            cx.notify();
+        }
+    }
+
+    fn visible_threads<'a>(&self, cx: &'a App) -> Vec<&'a ReviewThread> {
+        let Some((reviews, _)) = self.active_reviews.as_ref() else {
+            return Vec::new();
+        };
+        reviews
+            .read(cx)
+            .threads()
+            .iter()
+            .filter(|thread| self.show_resolved || !thread.is_resolved())
+            .collect()
+    }
+
+    fn unresolved_thread_count(&self, cx: &App) -> usize {
+        self.reviews().map_or(0, |reviews| {
+            reviews
+                .read(cx)
+                .threads()
+                .iter()
+                .filter(|thread| !thread.is_resolved())
+                .count()
+        })
+    }
+
+    /// Shows the unresolved threads below the lines they're about, in every open editor for a
+    /// reviewable buffer.
+    fn refresh_blocks(&mut self, cx: &mut Context<Self>) {
+        for (_, (editor, block_ids)) in self.reviewed_editors.drain() {
+            if let Some(editor) = editor.upgrade() {
+                editor.update(cx, |editor, cx| {
+                    editor.remove_blocks(block_ids.into_iter().collect(), None, cx)
+                });
+            }
+        }
+
+        let (Some(workspace), Some(reviews)) = (self.workspace.upgrade(), self.reviews()) else {
+            return;
+        };
+        let reviews = reviews.read(cx);
+        let threads = reviews
+            .threads()
+            .iter()
+            .filter(|thread| !thread.is_resolved())
+            .cloned()
+            .collect::<Vec<_>>();
+        if threads.is_empty() {
+            return;
+        }
+
+        let mut editors = Vec::new();
+        let workspace_ref = workspace.read(cx);
+        if let Some(project_id) = self.project.read(cx).remote_id() {
+            for editor in workspace_ref.items_of_type::<Editor>(cx) {
+                if let Some(path) = project_buffer_path(&editor, cx) {
+                    editors.push((editor, Some(path), project_id));
+                }
+            }
+        }
+        for channel_view in workspace_ref.items_of_type::<ChannelView>(cx) {
+            let channel_view = channel_view.read(cx);
+            let channel_buffer = channel_view.channel_buffer().read(cx);
+            if channel_buffer.channel_id == reviews.channel_id {
+                editors.push((channel_view.editor.clone(), None, channel_buffer.epoch()));
+            }
+        }
+
+        let panel = cx.entity().downgrade();
+        let workspace = workspace.downgrade();
+        for (editor, path, anchor_epoch) in editors {
+            let block_ids = editor.update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let Some((excerpt_id, _, buffer_snapshot)) = snapshot.as_singleton() else {
+                    return Vec::new();
+                };
+                let blocks = threads
+                    .iter()
+                    .filter(|thread| thread.path == path)
+                    .filter_map(|thread| {
+                        let range = thread.resolve_range(buffer_snapshot, anchor_epoch);
+                        let position = snapshot.anchor_in_excerpt(*excerpt_id, range.end)?;
+                        Some(BlockProperties {
+                            placement: BlockPlacement::Below(position),
+                            height: 1,
+                            style: BlockStyle::Flex,
+                            render: render_thread_block(thread, panel.clone(), workspace.clone()),
+                            priority: 0,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                editor.insert_blocks(blocks, None, cx)
+            });
+            if !block_ids.is_empty() {
+                self.reviewed_editors
+                    .insert(editor.entity_id(), (editor.downgrade(), block_ids));
+            }
+        }
+    }
+
+    /// Opens the file or notes that a thread is about, selecting the text it quotes.
+    fn open_thread_location(
+        &mut self,
+        thread_id: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(workspace), Some(reviews)) = (self.workspace.upgrade(), self.reviews()) else {
+            return;
+        };
+        let reviews = reviews.read(cx);
+        let channel_id = reviews.channel_id;
+        let Some(thread) = reviews.thread(thread_id).cloned() else {
+            return;
+        };
+
+        let open_editor: Task<Result<(Entity<Editor>, u64)>> = match thread.path.clone() {
+            Some(path) => {
+                let project = self.project.read(cx);
+                let (Some(project_id), Some(project_path)) = (
+                    project.remote_id(),
+                    project.find_project_path(Path::new(path.as_ref()), cx),
+                ) else {
+                    return;
+                };
+                let open_path = workspace.update(cx, |workspace, cx| {
+                    workspace.open_path(project_path, None, true, window, cx)
+                });
+                cx.spawn(|_, _| async move {
+                    let editor = open_path
+                        .await?
+                        .downcast::<Editor>()
+                        .ok_or_else(|| anyhow!("{path} isn't a text file"))?;
+                    Ok((editor, project_id))
+                })
+            }
+            None => {
+                let open_notes = ChannelView::open(channel_id, None, workspace, window, cx);
+                cx.spawn(|_, cx| async move {
+                    let channel_view = open_notes.await?;
+                    channel_view.read_with(&cx, |channel_view, cx| {
+                        (
+                            channel_view.editor.clone(),
+                            channel_view.channel_buffer().read(cx).epoch(),
+                        )
+                    })
+                })
+            }
+        };
+
+        self.selected_thread = Some(thread_id);
+        cx.notify();
+        cx.spawn_in(window, |_, mut cx| async move {
+            let (editor, anchor_epoch) = open_editor.await?;
+            editor.update_in(&mut cx, |editor, window, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let Some((excerpt_id, _, buffer_snapshot)) = snapshot.as_singleton() else {
+                    return;
+                };
+                let range = thread.resolve_range(buffer_snapshot, anchor_epoch);
+                let (Some(start), Some(end)) = (
+                    snapshot.anchor_in_excerpt(*excerpt_id, range.start),
+                    snapshot.anchor_in_excerpt(*excerpt_id, range.end),
+                ) else {
+                    return;
+                };
+                editor.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+                    selections.select_anchor_ranges([start..end])
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_thread(&self, thread: &ReviewThread, cx: &mut Context<Self>) -> impl IntoElement {
+        let thread_id = thread.id;
+        let is_resolved = thread.is_resolved();
+        let is_selected = self.selected_thread == Some(thread_id);
+        let can_resolve = self
+            .reviews()
+            .is_some_and(|reviews| reviews.read(cx).can_resolve(thread, cx));
+        let now = OffsetDateTime::now_utc();
+
+        v_flex()
+            .id(("review-thread", thread_id))
+            .m_2()
+            .p_2()
+            .gap_2()
+            .rounded_md()
+            .border_1()
+            .border_color(if is_selected {
+                cx.theme().colors().border_focused
+            } else {
+                cx.theme().colors().border_variant
+            })
+            .when(is_resolved, |this| this.opacity(0.6))
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.selected_thread = Some(thread_id);
+                cx.notify();
+            }))
+            .child(
+                h_flex()
+                    .justify_between()
+                    .gap_1()
+                    .child(
+                        Button::new(
+                            ("review-thread-location", thread_id),
+                            thread_location(thread.path.as_deref(), thread.start_row),
+                        )
+                        .icon(if thread.path.is_some() {
+                            IconName::FileText
+                        } else {
+                            IconName::MessageBubbles
+                        })
+                        .icon_size(IconSize::XSmall)
+                        .icon_color(Color::Muted)
+                        .icon_position(IconPosition::Start)
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text("Go to Code"))
+                        .on_click(cx.listener(
+                            move |this, _, window, cx| {
+                                this.open_thread_location(thread_id, window, cx)
+                            },
+                        )),
+                    )
+                    .when_some(thread.resolved_by.as_ref(), |this, user| {
+                        this.child(
+                            Label::new(format!("Resolved by {}", user.github_login))
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(render_quote(&thread.quoted_text, cx))
+            .children(thread.comments.iter().map(|comment| {
+                let timestamp = time_format::format_localized_timestamp(
+                    comment.timestamp,
+                    now,
+                    self.local_timezone,
+                    time_format::TimestampFormat::Relative,
+                );
+                v_flex()
+                    .gap_0p5()
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(Avatar::new(comment.sender.avatar_uri.clone()).size(rems(0.9)))
+                            .child(
+                                Label::new(comment.sender.github_login.clone())
+                                    .size(LabelSize::Small),
+                            )
+                            .child(
+                                Label::new(timestamp)
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .child(Label::new(comment.body.clone()).size(LabelSize::Small))
+            }))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new(("review-thread-reply", thread_id), "Reply")
+                            .icon(IconName::ReplyArrowRight)
+                            .icon_size(IconSize::XSmall)
+                            .icon_position(IconPosition::Start)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.reply_to(thread_id, window, cx)
+                            })),
+                    )
+                    .when(can_resolve, |this| {
+                        this.child(
+                            Button::new(
+                                ("review-thread-resolve", thread_id),
+                                if is_resolved { "Reopen" } else { "Resolve" },
+                            )
+                            .icon(if is_resolved {
+                                IconName::Undo
+                            } else {
+                                IconName::Check
+                            })
+                            .icon_size(IconSize::XSmall)
+                            .icon_position(IconPosition::Start)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(
+                                move |this, _, _, cx| {
+                                    this.set_resolved(thread_id, !is_resolved, cx)
+                                },
+                            )),
+                        )
+                    }),
+            )
+    }
+
+    fn render_composer(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let (title, quoted_text) = if let Some(quote) = self.pending_quote.as_ref() {
+            let row = quote.range.start.to_point(&quote.snapshot).row;
+            let quoted_text = quote
+                .snapshot
+                .text_for_range(quote.range.clone())
+                .collect::<String>();
+            (
+                format!(
+                    "Commenting on {}",
+                    thread_location(quote.path.as_deref(), row)
+                ),
+                Some(quoted_text),
+            )
+        } else {
+            let thread_id = self.replying_to?;
+            let thread = self.reviews()?.read(cx).thread(thread_id)?;
+            (
+                format!(
+                    "Replying to {}",
+                    thread_location(thread.path.as_deref(), thread.start_row)
+                ),
+                None,
+            )
+        };
+
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
+            font_size: TextSize::Small.rems(cx).into(),
+            font_weight: settings.ui_font.weight,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            ..Default::default()
+        };
+
+        Some(
+            v_flex()
+                .p_2()
+                .gap_1()
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    h_flex()
+                        .justify_between()
+                        .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+                        .child(
+                            IconButton::new("cancel-review-comment", IconName::Close)
+                                .icon_size(IconSize::XSmall)
+                                .tooltip(Tooltip::text("Cancel"))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.cancel(&Cancel, window, cx)
+                                })),
+                        ),
+                )
+                .when_some(quoted_text, |this, quoted_text| {
+                    this.child(render_quote(&quoted_text, cx))
+                })
+                .child(
+                    div()
+                        .w_full()
+                        .px_2()
+                        .py_1()
+                        .bg(cx.theme().colors().editor_background)
+                        .rounded_md()
+                        .child(EditorElement::new(
+                            &self.comment_editor,
+                            EditorStyle {
+                                local_player: cx.theme().players().local(),
+                                text: text_style,
+                                ..Default::default()
+                            },
+                        )),
+                ),
+        )
+    }
+}
+
+impl Render for ReviewPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let threads = self
+            .visible_threads(cx)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context("ReviewPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Reviews"))
+                    .child(
+                        IconButton::new("toggle-resolved-review-threads", IconName::Eye)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(self.show_resolved)
+                            .tooltip(Tooltip::text(if self.show_resolved {
+                                "Hide Resolved Threads"
+                            } else {
+                                "Show Resolved Threads"
+                            }))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.show_resolved = !this.show_resolved;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .map(|this| {
+                if self.reviews().is_none() {
+                    this.child(render_placeholder(
+                        "Join a channel call to review code with its members.",
+                    ))
+                } else if threads.is_empty() {
+                    this.child(render_placeholder(
+                        "No open threads. Select some code and run \"review panel: comment on selection\" to start one.",
+                    ))
+                } else {
+                    this.child(
+                        v_flex()
+                            .id("review-threads")
+                            .flex_1()
+                            .overflow_y_scroll()
+                            .track_scroll(&self.scroll_handle)
+                            .children(
+                                threads
+                                    .iter()
+                                    .map(|thread| self.render_thread(thread, cx)),
+                            ),
+                    )
+                }
+            })
+            .children(self.render_composer(cx))
+    }
+}
+
+impl Focusable for ReviewPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for ReviewPanel {}
+
+impl Panel for ReviewPanel {
+    fn persistent_name() -> &'static str {
+        "ReviewPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        ReviewPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file::<ReviewPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| ReviewPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        ReviewPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::MessageBubbles)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Review Panel")
+    }
+
+    fn icon_label(&self, _window: &Window, cx: &App) -> Option<String> {
+        match self.unresolved_thread_count(cx) {
+            0 => None,
+            count => Some(count.to_string()),
+        }
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+}
+
+fn quote_selection(
+    editor: &Entity<Editor>,
+    path: Option<Arc<str>>,
+    anchor_epoch: u64,
+    cx: &App,
+) -> Option<ReviewQuote> {
+    let editor = editor.read(cx);
+    let buffer = editor.buffer().read(cx).as_singleton()?;
+    let snapshot = buffer.read(cx).snapshot();
+    let selection = editor.selections.newest_anchor();
+    let mut range = selection.start.text_anchor..selection.end.text_anchor;
+    if range.start.to_offset(&snapshot) == range.end.to_offset(&snapshot) {
+        let row = range.start.to_point(&snapshot).row;
+        range = snapshot.anchor_before(Point::new(row, 0))
+            ..snapshot.anchor_after(Point::new(row, snapshot.line_len(row)));
+    }
+    Some(ReviewQuote {
+        path,
+        range,
+        anchor_epoch,
+        snapshot,
+    })
+}
+
+fn project_buffer_path(editor: &Entity<Editor>, cx: &App) -> Option<Arc<str>> {
+    let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+    let file = buffer.read(cx).file()?;
+    Some(file.full_path(cx).to_string_lossy().into())
+}
+
+fn thread_location(path: Option<&str>, row: u32) -> String {
+    match path {
+        Some(path) => format!("{path}:{}", row + 1),
+        None => format!("Channel Notes:{}", row + 1),
+    }
+}
+
+fn render_quote(quoted_text: &str, cx: &App) -> impl IntoElement {
+    let mut lines = quoted_text.lines();
+    let mut text = lines
+        .by_ref()
+        .take(QUOTED_LINES)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if lines.next().is_some() {
+        text.push_str("\n…");
+    }
+
+    div()
+        .px_2()
+        .py_1()
+        .rounded_sm()
+        .bg(cx.theme().colors().editor_background)
+        .font_buffer(cx)
+        .text_xs()
+        .text_color(cx.theme().colors().text_muted)
+        .child(text)
+}
+
+fn render_placeholder(text: &'static str) -> impl IntoElement {
+    v_flex()
+        .p_4()
+        .child(Label::new(text).color(Color::Muted).size(LabelSize::Small))
+}
+
+fn render_thread_block(
+    thread: &ReviewThread,
+    panel: WeakEntity<ReviewPanel>,
+    workspace: WeakEntity<Workspace>,
+) -> editor::display_map::RenderBlock {
+    let thread_id = thread.id;
+    let first_comment = thread.comments.first().cloned();
+    let reply_count = thread.comments.len().saturating_sub(1);
+    Arc::new(move |cx: &mut BlockContext| -> AnyElement {
+        let panel = panel.clone();
+        let workspace = workspace.clone();
+        h_flex()
+            .id(("review-thread-block", thread_id))
+            .pl(cx.gutter_dimensions.full_width())
+            .gap_1()
+            .cursor_pointer()
+            .child(
+                Icon::new(IconName::MessageBubbles)
+                    .size(IconSize::Small)
+                    .color(Color::Accent),
+            )
+            .when_some(first_comment.as_ref(), |this, comment| {
+                this.child(Avatar::new(comment.sender.avatar_uri.clone()).size(rems(0.9)))
+                    .child(Label::new(comment.sender.github_login.clone()).size(LabelSize::Small))
+                    .child(
+                        Label::new(comment.body.lines().next().unwrap_or_default().to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+            })
+            .when(reply_count > 0, |this| {
+                this.child(
+                    Label::new(if reply_count == 1 {
+                        "1 reply".to_string()
+                    } else {
+                        format!("{reply_count} replies")
+                    })
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted),
+                )
+            })
+            .on_click(move |_, window, cx| {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.focus_panel::<ReviewPanel>(window, cx);
+                    })
+                    .ok();
+                panel
+                    .update(cx, |panel, cx| panel.select_thread(thread_id, cx))
+                    .ok();
+            })
+            .into_any_element()
+    })
+}
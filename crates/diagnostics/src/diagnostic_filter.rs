@@ -0,0 +1,200 @@
+use anyhow::{anyhow, Result};
+use language::{Diagnostic, DiagnosticSeverity};
+use std::path::Path;
+use util::paths::PathMatcher;
+
+use crate::project_diagnostics_settings::DiagnosticGrouping;
+
+/// Which diagnostics are shown in the project diagnostics view.
+///
+/// Parsed from a query like `severity:warning source:clippy code:E0308 path:src/** unused`,
+/// where values of the same key are alternatives, different keys must all match and the
+/// remaining words must be contained in the diagnostic message.
+#[derive(Default)]
+pub(crate) struct DiagnosticFilter {
+    severities: Vec<DiagnosticSeverity>,
+    sources: Vec<String>,
+    codes: Vec<String>,
+    paths: Option<PathMatcher>,
+    words: Vec<String>,
+}
+
+impl DiagnosticFilter {
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut filter = Self::default();
+        let mut path_globs = Vec::new();
+        for token in query.split_whitespace() {
+            match token.split_once(':') {
+                Some(("severity", severity)) => {
+                    filter.severities.push(parse_severity(severity)?);
+                }
+                Some(("source", source)) => filter.sources.push(source.to_lowercase()),
+                Some(("code", code)) => filter.codes.push(code.to_lowercase()),
+                Some(("path", glob)) => path_globs.push(glob.to_string()),
+                _ => filter.words.push(token.to_lowercase()),
+            }
+        }
+        if !path_globs.is_empty() {
+            filter.paths = Some(PathMatcher::new(&path_globs)?);
+        }
+        Ok(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.severities.is_empty()
+            && self.sources.is_empty()
+            && self.codes.is_empty()
+            && self.paths.is_none()
+            && self.words.is_empty()
+    }
+
+    pub fn matches_path(&self, path: &Path) -> bool {
+        self.paths
+            .as_ref()
+            .map_or(true, |paths| paths.is_match(path))
+    }
+
+    /// Whether the diagnostic passes the filter. Diagnostics less severe than `max_severity`
+    /// are only shown when the filter asks for their severity explicitly.
+    pub fn matches(
+        &self,
+        diagnostic: &Diagnostic,
+        server_name: Option<&str>,
+        max_severity: DiagnosticSeverity,
+    ) -> bool {
+        let severity_matches = if self.severities.is_empty() {
+            diagnostic.severity <= max_severity
+        } else {
+            self.severities.contains(&diagnostic.severity)
+        };
+        let source_matches = self.sources.is_empty()
+            || diagnostic
+                .source
+                .as_deref()
+                .into_iter()
+                .chain(server_name)
+                .any(|source| self.sources.contains(&source.to_lowercase()));
+        let code_matches = self.codes.is_empty()
+            || diagnostic
+                .code
+                .as_ref()
+                .map_or(false, |code| self.codes.contains(&code.to_lowercase()));
+        let message = diagnostic.message.to_lowercase();
+        let words_match = self.words.iter().all(|word| message.contains(word));
+        severity_matches && source_matches && code_matches && words_match
+    }
+}
+
+fn parse_severity(severity: &str) -> Result<DiagnosticSeverity> {
+    match severity.to_lowercase().as_str() {
+        "error" => Ok(DiagnosticSeverity::ERROR),
+        "warning" | "warn" => Ok(DiagnosticSeverity::WARNING),
+        "information" | "info" => Ok(DiagnosticSeverity::INFORMATION),
+        "hint" => Ok(DiagnosticSeverity::HINT),
+        _ => Err(anyhow!("unknown severity `{severity}`")),
+    }
+}
+
+/// Returns the key of the section a diagnostic is shown in, or `None` for the section of
+/// diagnostics that have no code or source, which is the only section when grouping by file.
+pub(crate) fn group_key(
+    grouping: DiagnosticGrouping,
+    diagnostic: &Diagnostic,
+    server_name: Option<&str>,
+) -> Option<String> {
+    match grouping {
+        DiagnosticGrouping::File => None,
+        DiagnosticGrouping::Code => diagnostic.code.clone(),
+        DiagnosticGrouping::Source => diagnostic
+            .source
+            .clone()
+            .or_else(|| server_name.map(ToString::to_string)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(severity: DiagnosticSeverity, source: &str, code: &str) -> Diagnostic {
+        Diagnostic {
+            severity,
+            source: Some(source.to_string()),
+            code: Some(code.to_string()),
+            message: "unused variable: `x`".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diagnostic_filter() {
+        let unused = diagnostic(DiagnosticSeverity::WARNING, "rustc", "unused_variables");
+        let mismatch = diagnostic(DiagnosticSeverity::ERROR, "rustc", "E0308");
+        let hint = diagnostic(DiagnosticSeverity::HINT, "clippy", "needless_return");
+        let server = Some("rust-analyzer");
+
+        let filter = DiagnosticFilter::parse("").unwrap();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&unused, server, DiagnosticSeverity::WARNING));
+        assert!(!filter.matches(&unused, server, DiagnosticSeverity::ERROR));
+        assert!(!filter.matches(&hint, server, DiagnosticSeverity::WARNING));
+
+        let filter = DiagnosticFilter::parse("severity:hint severity:error").unwrap();
+        assert!(!filter.matches(&unused, server, DiagnosticSeverity::WARNING));
+        assert!(filter.matches(&mismatch, server, DiagnosticSeverity::WARNING));
+        assert!(
+            filter.matches(&hint, server, DiagnosticSeverity::WARNING),
+            "Explicitly requested severities should be shown"
+        );
+
+        let filter = DiagnosticFilter::parse("source:Rust-Analyzer code:e0308").unwrap();
+        assert!(filter.matches(&mismatch, server, DiagnosticSeverity::WARNING));
+        assert!(!filter.matches(&unused, server, DiagnosticSeverity::WARNING));
+        assert!(!filter.matches(&mismatch, None, DiagnosticSeverity::WARNING));
+
+        let filter = DiagnosticFilter::parse("UNUSED `x`").unwrap();
+        assert!(filter.matches(&unused, server, DiagnosticSeverity::WARNING));
+        assert!(!DiagnosticFilter::parse("unused y").unwrap().matches(
+            &unused,
+            server,
+            DiagnosticSeverity::WARNING
+        ));
+
+        let filter = DiagnosticFilter::parse("path:src/**/*.rs path:tests").unwrap();
+        assert!(!filter.is_empty());
+        assert!(filter.matches_path(Path::new("src/editor/element.rs")));
+        assert!(filter.matches_path(Path::new("tests/integration.rs")));
+        assert!(!filter.matches_path(Path::new("benches/main.rs")));
+
+        assert!(DiagnosticFilter::parse("severity:fatal").is_err());
+        assert!(DiagnosticFilter::parse("path:[").is_err());
+    }
+
+    #[test]
+    fn test_group_key() {
+        let unused = diagnostic(DiagnosticSeverity::WARNING, "rustc", "unused_variables");
+        let no_source = Diagnostic {
+            source: None,
+            ..unused.clone()
+        };
+        let server = Some("rust-analyzer");
+        assert_eq!(group_key(DiagnosticGrouping::File, &unused, server), None);
+        assert_eq!(
+            group_key(DiagnosticGrouping::Code, &unused, server).as_deref(),
+            Some("unused_variables")
+        );
+        assert_eq!(
+            group_key(DiagnosticGrouping::Source, &unused, server).as_deref(),
+            Some("rustc")
+        );
+        assert_eq!(
+            group_key(DiagnosticGrouping::Source, &no_source, server).as_deref(),
+            Some("rust-analyzer"),
+            "Diagnostics without a source should be grouped by their language server"
+        );
+        assert_eq!(
+            group_key(DiagnosticGrouping::Source, &no_source, None),
+            None
+        );
+    }
+}
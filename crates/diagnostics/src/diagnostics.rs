@@ -1,3 +1,4 @@
+mod diagnostic_filter;
 pub mod items;
 mod project_diagnostics_settings;
 mod toolbar_controls;
@@ -6,7 +7,8 @@ mod toolbar_controls;
 mod diagnostics_tests;
 
 use anyhow::Result;
-use collections::{BTreeSet, HashSet};
+use collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use diagnostic_filter::{group_key, DiagnosticFilter};
 use editor::{
     diagnostic_block_renderer,
     display_map::{BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock},
@@ -20,11 +22,12 @@ use gpui::{
     SharedString, Styled, StyledText, Subscription, Task, WeakEntity, Window,
 };
 use language::{
-    Bias, Buffer, BufferRow, BufferSnapshot, Diagnostic, DiagnosticEntry, DiagnosticSeverity,
-    Point, Selection, SelectionGoal, ToTreeSitterPoint,
+    Bias, Buffer, BufferRow, BufferSnapshot, Diagnostic, DiagnosticEntry, DiagnosticGroup,
+    DiagnosticSeverity, Point, Selection, SelectionGoal, ToTreeSitterPoint, Transaction,
 };
-use lsp::LanguageServerId;
-use project::{DiagnosticSummary, Project, ProjectPath};
+use lsp::{CodeActionKind, LanguageServerId};
+use project::{CodeAction, DiagnosticSummary, Project, ProjectPath};
+pub use project_diagnostics_settings::DiagnosticGrouping;
 use project_diagnostics_settings::ProjectDiagnosticsSettings;
 use settings::Settings;
 use std::{
@@ -46,7 +49,18 @@ use workspace::{
     ItemNavHistory, ToolbarItemLocation, Workspace,
 };
 
-actions!(diagnostics, [Deploy, ToggleWarnings]);
+actions!(
+    diagnostics,
+    [
+        Deploy,
+        FixAllOfKind,
+        GroupByCode,
+        GroupByFile,
+        GroupBySource,
+        ToggleFilter,
+        ToggleWarnings
+    ]
+);
 
 struct IncludeWarnings(bool);
 impl Global for IncludeWarnings {}
@@ -66,16 +80,31 @@ struct ProjectDiagnosticsEditor {
     path_states: Vec<PathState>,
     paths_to_update: BTreeSet<(ProjectPath, Option<LanguageServerId>)>,
     include_warnings: bool,
+    grouping: DiagnosticGrouping,
+    filter: DiagnosticFilter,
+    filter_editor: Entity<Editor>,
+    filter_error: Option<SharedString>,
+    show_filter: bool,
     context: u32,
     update_excerpts_task: Option<Task<Result<()>>>,
+    _fix_all_task: Option<Task<Result<()>>>,
     _subscription: Subscription,
 }
 
 struct PathState {
+    /// The code or source shared by the diagnostics of this state, depending on the grouping.
+    key: Option<String>,
     path: ProjectPath,
     diagnostic_groups: Vec<DiagnosticGroupState>,
 }
 
+impl PathState {
+    /// Path states are ordered by their key, then by path. Diagnostics without a key come last.
+    fn cmp_position(&self, key: &Option<String>, path: &ProjectPath) -> Ordering {
+        (self.key.is_none(), &self.key, &self.path).cmp(&(key.is_none(), key, path))
+    }
+}
+
 struct DiagnosticGroupState {
     language_server_id: LanguageServerId,
     primary_diagnostic: DiagnosticEntry<language::Anchor>,
@@ -92,23 +121,43 @@ const DIAGNOSTICS_UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
 impl Render for ProjectDiagnosticsEditor {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let child = if self.path_states.is_empty() {
+            let message = if self.filter.is_empty() {
+                "No problems in workspace"
+            } else {
+                "No problems match the filter"
+            };
             div()
                 .key_context("EmptyPane")
                 .bg(cx.theme().colors().editor_background)
                 .flex()
                 .items_center()
                 .justify_center()
-                .size_full()
-                .child(Label::new("No problems in workspace"))
+                .flex_1()
+                .w_full()
+                .child(Label::new(message))
         } else {
-            div().size_full().child(self.editor.clone())
+            div().flex_1().w_full().child(self.editor.clone())
         };
 
-        div()
+        v_flex()
             .key_context("Diagnostics")
             .track_focus(&self.focus_handle(cx))
             .size_full()
             .on_action(cx.listener(Self::toggle_warnings))
+            .on_action(cx.listener(Self::toggle_filter))
+            .on_action(cx.listener(Self::fix_all_of_kind))
+            .on_action(cx.listener(|this, _: &GroupByFile, window, cx| {
+                this.set_grouping(DiagnosticGrouping::File, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &GroupByCode, window, cx| {
+                this.set_grouping(DiagnosticGrouping::Code, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &GroupBySource, window, cx| {
+                this.set_grouping(DiagnosticGrouping::Source, window, cx)
+            }))
+            .when(self.show_filter || !self.filter.is_empty(), |this| {
+                this.child(self.render_filter_bar(cx))
+            })
             .child(child)
     }
 }
@@ -203,12 +252,34 @@ impl ProjectDiagnosticsEditor {
         })
         .detach();
 
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor
+                .set_placeholder_text("Filter by severity:, source:, code:, path: or message…", cx);
+            editor
+        });
+        cx.subscribe_in(
+            &filter_editor,
+            window,
+            |this, _, event: &EditorEvent, window, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.filter_changed(window, cx);
+                }
+            },
+        )
+        .detach();
+
         let project = project_handle.read(cx);
         let mut this = Self {
             project: project_handle.clone(),
             context,
             summary: project.diagnostic_summary(false, cx),
             include_warnings,
+            grouping: ProjectDiagnosticsSettings::get_global(cx).group_by,
+            filter: DiagnosticFilter::default(),
+            filter_editor,
+            filter_error: None,
+            show_filter: false,
             workspace,
             excerpts,
             focus_handle,
@@ -216,6 +287,7 @@ impl ProjectDiagnosticsEditor {
             path_states: Default::default(),
             paths_to_update: Default::default(),
             update_excerpts_task: None,
+            _fix_all_task: None,
             _subscription: project_event_subscription,
         };
         this.update_all_excerpts(window, cx);
@@ -310,6 +382,151 @@ impl ProjectDiagnosticsEditor {
         cx.notify();
     }
 
+    fn toggle_filter(&mut self, _: &ToggleFilter, window: &mut Window, cx: &mut Context<Self>) {
+        if self.filter_editor.focus_handle(cx).is_focused(window) {
+            self.show_filter = false;
+            if self.path_states.is_empty() {
+                window.focus(&self.focus_handle);
+            } else {
+                window.focus(&self.editor.focus_handle(cx));
+            }
+        } else {
+            self.show_filter = true;
+            window.focus(&self.filter_editor.focus_handle(cx));
+        }
+        cx.notify();
+    }
+
+    fn filter_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.filter_editor.read(cx).text(cx);
+        match DiagnosticFilter::parse(&query) {
+            Ok(filter) => {
+                self.filter = filter;
+                self.filter_error = None;
+                self.update_all_excerpts(window, cx);
+            }
+            Err(error) => self.filter_error = Some(error.to_string().into()),
+        }
+        cx.notify();
+    }
+
+    fn set_grouping(
+        &mut self,
+        grouping: DiagnosticGrouping,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.grouping != grouping {
+            self.grouping = grouping;
+            self.update_all_excerpts(window, cx);
+            cx.notify();
+        }
+    }
+
+    /// Applies the quick fix of every diagnostic in the view that has the same code and source
+    /// as the one under the cursor, so that all of them can be undone at once.
+    fn fix_all_of_kind(&mut self, _: &FixAllOfKind, window: &mut Window, cx: &mut Context<Self>) {
+        let cursor = self.editor.read(cx).selections.newest_anchor().head();
+        let Some((source, code)) = self
+            .path_states
+            .iter()
+            .flat_map(|state| &state.diagnostic_groups)
+            .find(|group| group.excerpts.contains(&cursor.excerpt_id))
+            .map(|group| &group.primary_diagnostic.diagnostic)
+            .filter(|diagnostic| diagnostic.code.is_some())
+            .map(|diagnostic| (diagnostic.source.clone(), diagnostic.code.clone()))
+        else {
+            return;
+        };
+        let entries_to_fix = self
+            .path_states
+            .iter()
+            .flat_map(|state| {
+                state
+                    .diagnostic_groups
+                    .iter()
+                    .map(move |group| (&state.path, &group.primary_diagnostic))
+            })
+            .filter(|(_, entry)| entry.diagnostic.source == source && entry.diagnostic.code == code)
+            .map(|(path, entry)| (path.clone(), entry.clone()))
+            .collect::<Vec<_>>();
+
+        let project = self.project.clone();
+        let excerpts = self.excerpts.clone();
+        self._fix_all_task = Some(cx.spawn_in(window, |_, mut cx| async move {
+            let mut transactions = HashMap::<Entity<Buffer>, Transaction>::default();
+            // Fixes are requested one by one, so that each of them is computed against the
+            // edits made by the previous ones.
+            for (path, entry) in entries_to_fix {
+                let buffer = project
+                    .update(&mut cx, |project, cx| project.open_buffer(path, cx))?
+                    .await?;
+                let Some(actions) = project
+                    .update(&mut cx, |project, cx| {
+                        project.code_actions(
+                            &buffer,
+                            entry.range.clone(),
+                            Some(vec![CodeActionKind::QUICKFIX]),
+                            cx,
+                        )
+                    })?
+                    .await
+                    .log_err()
+                else {
+                    continue;
+                };
+                let Some(action) = quick_fix_for(actions, &entry.diagnostic) else {
+                    continue;
+                };
+                let Some(project_transaction) = project
+                    .update(&mut cx, |project, cx| {
+                        project.apply_code_action(buffer, action, true, cx)
+                    })?
+                    .await
+                    .log_err()
+                else {
+                    continue;
+                };
+                for (buffer, transaction) in project_transaction.0 {
+                    if let Some(destination) = transactions.get(&buffer) {
+                        let destination = destination.id;
+                        buffer.update(&mut cx, |buffer, _| {
+                            buffer.merge_transactions(transaction.id, destination)
+                        })?;
+                    } else {
+                        transactions.insert(buffer, transaction);
+                    }
+                }
+            }
+
+            excerpts.update(&mut cx, |excerpts, cx| {
+                excerpts.push_transaction(&transactions, cx);
+            })?;
+            Ok(())
+        }));
+    }
+
+    fn render_filter_bar(&self, cx: &App) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .bg(colors.editor_background)
+            .border_b_1()
+            .border_color(colors.border_variant)
+            .child(
+                Icon::new(IconName::Filter)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1().child(self.filter_editor.clone()))
+            .when_some(self.filter_error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+    }
+
     fn focus_in(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.focus_handle.is_focused(window) && !self.path_states.is_empty() {
             self.editor.focus_handle(cx).focus(window)
@@ -353,15 +570,197 @@ impl ProjectDiagnosticsEditor {
     ) {
         let was_empty = self.path_states.is_empty();
         let snapshot = buffer.read(cx).snapshot();
+        let max_severity = if self.include_warnings {
+            DiagnosticSeverity::WARNING
+        } else {
+            DiagnosticSeverity::ERROR
+        };
+        let server_names = self
+            .project
+            .read(cx)
+            .language_server_statuses(cx)
+            .map(|(id, status)| (id, status.name.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let mut new_groups_by_key = BTreeMap::<_, Vec<_>>::new();
+        if self.filter.matches_path(&path_to_update.path) {
+            for (language_server_id, group) in snapshot.diagnostic_groups(server_to_update) {
+                let primary = &group.entries[group.primary_ix].diagnostic;
+                let server_name = server_names.get(&language_server_id).map(String::as_str);
+                if self.filter.matches(primary, server_name, max_severity) {
+                    new_groups_by_key
+                        .entry(group_key(self.grouping, primary, server_name))
+                        .or_default()
+                        .push((language_server_id, group));
+                }
+            }
+        }
+
+        let mut keys = new_groups_by_key.keys().cloned().collect::<BTreeSet<_>>();
+        keys.extend(
+            self.path_states
+                .iter()
+                .filter(|state| state.path == path_to_update)
+                .map(|state| state.key.clone()),
+        );
+        let mut path_ix = self
+            .path_states
+            .partition_point(|state| state.cmp_position(&None, &path_to_update).is_lt());
+        for key in keys {
+            let new_groups = new_groups_by_key.remove(&key).unwrap_or_default();
+            path_ix = self.update_path_state(
+                key,
+                &path_to_update,
+                server_to_update,
+                &buffer,
+                &snapshot,
+                new_groups,
+                cx,
+            );
+        }
+
+        let excerpts_snapshot = self.excerpts.read(cx).snapshot(cx);
+        self.editor.update(cx, |editor, cx| {
+            let groups: Vec<&DiagnosticGroupState>;
+            let mut selections;
+            let new_excerpt_ids_by_selection_id;
+            if was_empty {
+                groups = self.path_states.first()?.diagnostic_groups.iter().collect();
+                new_excerpt_ids_by_selection_id = [(0, ExcerptId::min())].into_iter().collect();
+                selections = vec![Selection {
+                    id: 0,
+                    start: 0,
+                    end: 0,
+                    reversed: false,
+                    goal: SelectionGoal::None,
+                }];
+            } else {
+                let mut path_states = self
+                    .path_states
+                    .iter()
+                    .filter(|state| state.path == path_to_update)
+                    .peekable();
+                groups = if path_states.peek().is_some() {
+                    path_states
+                        .flat_map(|state| &state.diagnostic_groups)
+                        .collect()
+                } else {
+                    self.path_states
+                        .get(path_ix)?
+                        .diagnostic_groups
+                        .iter()
+                        .collect()
+                };
+                new_excerpt_ids_by_selection_id =
+                    editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| s.refresh());
+                selections = editor.selections.all::<usize>(cx);
+            }
+
+            // If any selection has lost its position, move it to start of the next primary diagnostic.
+            let snapshot = editor.snapshot(window, cx);
+            for selection in &mut selections {
+                if let Some(new_excerpt_id) = new_excerpt_ids_by_selection_id.get(&selection.id) {
+                    let group_ix = match groups.binary_search_by(|probe| {
+                        probe
+                            .excerpts
+                            .last()
+                            .unwrap()
+                            .cmp(new_excerpt_id, &snapshot.buffer_snapshot)
+                    }) {
+                        Ok(ix) | Err(ix) => ix,
+                    };
+                    if let Some(group) = groups.get(group_ix) {
+                        if let Some(offset) = excerpts_snapshot
+                            .anchor_in_excerpt(
+                                group.excerpts[group.primary_excerpt_ix],
+                                group.primary_diagnostic.range.start,
+                            )
+                            .map(|anchor| anchor.to_offset(&excerpts_snapshot))
+                        {
+                            selection.start = offset;
+                            selection.end = offset;
+                        }
+                    }
+                }
+            }
+            editor.change_selections(None, window, cx, |s| {
+                s.select(selections);
+            });
+            Some(())
+        });
+
+        if self.path_states.is_empty() {
+            if self.editor.focus_handle(cx).is_focused(window) {
+                window.focus(&self.focus_handle);
+            }
+        } else if self.focus_handle.is_focused(window) {
+            let focus_handle = self.editor.focus_handle(cx);
+            window.focus(&focus_handle);
+        }
+
+        #[cfg(test)]
+        self.check_invariants(cx);
+
+        cx.notify();
+    }
+
+    #[cfg(test)]
+    fn check_invariants(&self, cx: &mut Context<Self>) {
+        let mut excerpts = Vec::new();
+        for (id, buffer, _) in self.excerpts.read(cx).snapshot(cx).excerpts() {
+            if let Some(file) = buffer.file() {
+                excerpts.push((id, file.path().clone()));
+            }
+        }
+
+        if self.grouping == DiagnosticGrouping::File {
+            let mut prev_path = None;
+            for (_, path) in &excerpts {
+                if let Some(prev_path) = prev_path {
+                    if path < prev_path {
+                        panic!("excerpts are not sorted by path {:?}", excerpts);
+                    }
+                }
+                prev_path = Some(path);
+            }
+        }
+
+        for (prev_state, state) in self.path_states.iter().zip(self.path_states.iter().skip(1)) {
+            if prev_state.cmp_position(&state.key, &state.path).is_ge() {
+                panic!(
+                    "path states are not sorted: {:?}",
+                    self.path_states
+                        .iter()
+                        .map(|state| (&state.key, &state.path))
+                        .collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    /// Updates the diagnostic groups of the path state with the given key, and returns the index
+    /// where that state is, or was before being removed because it had no groups left.
+    #[allow(clippy::too_many_arguments)]
+    fn update_path_state(
+        &mut self,
+        key: Option<String>,
+        path_to_update: &ProjectPath,
+        server_to_update: Option<LanguageServerId>,
+        buffer: &Entity<Buffer>,
+        snapshot: &BufferSnapshot,
+        new_groups: Vec<(LanguageServerId, DiagnosticGroup<language::Anchor>)>,
+        cx: &mut Context<Self>,
+    ) -> usize {
         let path_ix = match self
             .path_states
-            .binary_search_by_key(&&path_to_update, |e| &e.path)
+            .binary_search_by(|probe| probe.cmp_position(&key, path_to_update))
         {
             Ok(ix) => ix,
             Err(ix) => {
                 self.path_states.insert(
                     ix,
                     PathState {
+                        key,
                         path: path_to_update.clone(),
                         diagnostic_groups: Default::default(),
                     },
@@ -385,23 +784,12 @@ impl ProjectDiagnosticsEditor {
         let mut blocks_to_add = Vec::new();
         let mut blocks_to_remove = HashSet::default();
         let mut first_excerpt_id = None;
-        let max_severity = if self.include_warnings {
-            DiagnosticSeverity::WARNING
-        } else {
-            DiagnosticSeverity::ERROR
-        };
         let excerpts_snapshot = self.excerpts.update(cx, |excerpts, cx| {
             let mut old_groups = mem::take(&mut path_state.diagnostic_groups)
                 .into_iter()
                 .enumerate()
                 .peekable();
-            let mut new_groups = snapshot
-                .diagnostic_groups(server_to_update)
-                .into_iter()
-                .filter(|(_, group)| {
-                    group.entries[group.primary_ix].diagnostic.severity <= max_severity
-                })
-                .peekable();
+            let mut new_groups = new_groups.into_iter().peekable();
             loop {
                 let mut to_insert = None;
                 let mut to_remove = None;
@@ -419,7 +807,7 @@ impl ProjectDiagnosticsEditor {
                     (Some((_, old_group)), Some((new_language_server_id, new_group))) => {
                         let old_primary = &old_group.primary_diagnostic;
                         let new_primary = &new_group.entries[new_group.primary_ix];
-                        match compare_diagnostics(old_primary, new_primary, &snapshot)
+                        match compare_diagnostics(old_primary, new_primary, snapshot)
                             .then_with(|| old_group.language_server_id.cmp(new_language_server_id))
                         {
                             Ordering::Less => {
@@ -452,9 +840,9 @@ impl ProjectDiagnosticsEditor {
                     let mut pending_range: Option<(Range<Point>, Range<Point>, usize)> = None;
                     let mut is_first_excerpt_for_group = true;
                     for (ix, entry) in group.entries.iter().map(Some).chain([None]).enumerate() {
-                        let resolved_entry = entry.map(|e| e.resolve::<Point>(&snapshot));
+                        let resolved_entry = entry.map(|e| e.resolve::<Point>(snapshot));
                         let expanded_range = resolved_entry.as_ref().map(|entry| {
-                            context_range_for_entry(entry, self.context, &snapshot, cx)
+                            context_range_for_entry(entry, self.context, snapshot, cx)
                         });
                         if let Some((range, context_range, start_ix)) = &mut pending_range {
                             if let Some(expanded_range) = expanded_range.clone() {
@@ -588,93 +976,7 @@ impl ProjectDiagnosticsEditor {
             self.path_states.remove(path_ix);
         }
 
-        self.editor.update(cx, |editor, cx| {
-            let groups;
-            let mut selections;
-            let new_excerpt_ids_by_selection_id;
-            if was_empty {
-                groups = self.path_states.first()?.diagnostic_groups.as_slice();
-                new_excerpt_ids_by_selection_id = [(0, ExcerptId::min())].into_iter().collect();
-                selections = vec![Selection {
-                    id: 0,
-                    start: 0,
-                    end: 0,
-                    reversed: false,
-                    goal: SelectionGoal::None,
-                }];
-            } else {
-                groups = self.path_states.get(path_ix)?.diagnostic_groups.as_slice();
-                new_excerpt_ids_by_selection_id =
-                    editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| s.refresh());
-                selections = editor.selections.all::<usize>(cx);
-            }
-
-            // If any selection has lost its position, move it to start of the next primary diagnostic.
-            let snapshot = editor.snapshot(window, cx);
-            for selection in &mut selections {
-                if let Some(new_excerpt_id) = new_excerpt_ids_by_selection_id.get(&selection.id) {
-                    let group_ix = match groups.binary_search_by(|probe| {
-                        probe
-                            .excerpts
-                            .last()
-                            .unwrap()
-                            .cmp(new_excerpt_id, &snapshot.buffer_snapshot)
-                    }) {
-                        Ok(ix) | Err(ix) => ix,
-                    };
-                    if let Some(group) = groups.get(group_ix) {
-                        if let Some(offset) = excerpts_snapshot
-                            .anchor_in_excerpt(
-                                group.excerpts[group.primary_excerpt_ix],
-                                group.primary_diagnostic.range.start,
-                            )
-                            .map(|anchor| anchor.to_offset(&excerpts_snapshot))
-                        {
-                            selection.start = offset;
-                            selection.end = offset;
-                        }
-                    }
-                }
-            }
-            editor.change_selections(None, window, cx, |s| {
-                s.select(selections);
-            });
-            Some(())
-        });
-
-        if self.path_states.is_empty() {
-            if self.editor.focus_handle(cx).is_focused(window) {
-                window.focus(&self.focus_handle);
-            }
-        } else if self.focus_handle.is_focused(window) {
-            let focus_handle = self.editor.focus_handle(cx);
-            window.focus(&focus_handle);
-        }
-
-        #[cfg(test)]
-        self.check_invariants(cx);
-
-        cx.notify();
-    }
-
-    #[cfg(test)]
-    fn check_invariants(&self, cx: &mut Context<Self>) {
-        let mut excerpts = Vec::new();
-        for (id, buffer, _) in self.excerpts.read(cx).snapshot(cx).excerpts() {
-            if let Some(file) = buffer.file() {
-                excerpts.push((id, file.path().clone()));
-            }
-        }
-
-        let mut prev_path = None;
-        for (_, path) in &excerpts {
-            if let Some(prev_path) = prev_path {
-                if path < prev_path {
-                    panic!("excerpts are not sorted by path {:?}", excerpts);
-                }
-            }
-            prev_path = Some(path);
-        }
+        path_ix
     }
 }
 
@@ -975,6 +1277,41 @@ fn compare_diagnostics(
         .then_with(|| old.diagnostic.message.cmp(&new.diagnostic.message))
 }
 
+/// Picks the quick fix to apply for a diagnostic among the code actions available at its range.
+/// When there are several candidates, only the one preferred by the language server is used.
+fn quick_fix_for(actions: Vec<CodeAction>, diagnostic: &Diagnostic) -> Option<CodeAction> {
+    let mut quick_fixes = actions
+        .into_iter()
+        .filter(|action| {
+            let lsp_action = &action.lsp_action;
+            let is_quick_fix = lsp_action.kind.as_ref().map_or(true, |kind| {
+                kind.as_str().starts_with(CodeActionKind::QUICKFIX.as_str())
+            });
+            let fixes_diagnostic = lsp_action.diagnostics.as_ref().map_or(true, |diagnostics| {
+                diagnostics.iter().any(|lsp_diagnostic| {
+                    let code = lsp_diagnostic.code.as_ref().map(|code| match code {
+                        lsp::NumberOrString::Number(code) => code.to_string(),
+                        lsp::NumberOrString::String(code) => code.clone(),
+                    });
+                    code == diagnostic.code
+                })
+            });
+            is_quick_fix && fixes_diagnostic && lsp_action.disabled.is_none()
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(ix) = quick_fixes
+        .iter()
+        .position(|action| action.lsp_action.is_preferred == Some(true))
+    {
+        Some(quick_fixes.swap_remove(ix))
+    } else if quick_fixes.len() == 1 {
+        quick_fixes.pop()
+    } else {
+        None
+    }
+}
+
 const DIAGNOSTIC_EXPANSION_ROW_LIMIT: u32 = 32;
 
 fn context_range_for_entry(
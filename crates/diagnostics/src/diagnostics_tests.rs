@@ -4,9 +4,11 @@ use editor::{
     display_map::{Block, BlockContext, DisplayRow},
     DisplayPoint, GutterDimensions,
 };
+use futures::StreamExt as _;
 use gpui::{px, AvailableSpace, Stateful, TestAppContext, VisualTestContext};
 use language::{
-    Diagnostic, DiagnosticEntry, DiagnosticSeverity, OffsetRangeExt, PointUtf16, Rope, Unclipped,
+    Diagnostic, DiagnosticEntry, DiagnosticSeverity, FakeLspAdapter, Language, LanguageConfig,
+    LanguageMatcher, OffsetRangeExt, PointUtf16, Rope, Unclipped,
 };
 use pretty_assertions::assert_eq;
 use project::FakeFs;
@@ -746,6 +748,264 @@ async fn test_diagnostics_multiple_servers(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_diagnostics_filter_and_grouping(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/test",
+        json!({
+            "a.rs": "
+                fn a() {}
+                fn b() {}
+                fn c() {}
+            "
+            .unindent(),
+            "b.rs": "
+                fn d() {}
+                fn e() {}
+            "
+            .unindent(),
+        }),
+    )
+    .await;
+
+    let language_server_id = LanguageServerId(0);
+    let project = Project::test(fs.clone(), ["/test".as_ref()], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*window, cx);
+    let workspace = window.root(cx).unwrap();
+
+    let diagnostic = |row, code: &str, source: &str, severity, group_id| DiagnosticEntry {
+        range: Unclipped(PointUtf16::new(row, 3))..Unclipped(PointUtf16::new(row, 4)),
+        diagnostic: Diagnostic {
+            message: format!("{code} in row {row}"),
+            code: Some(code.to_string()),
+            source: Some(source.to_string()),
+            severity,
+            is_primary: true,
+            is_disk_based: true,
+            group_id,
+            ..Default::default()
+        },
+    };
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_diagnostic_entries(
+                language_server_id,
+                PathBuf::from("/test/a.rs"),
+                None,
+                vec![
+                    diagnostic(0, "E0308", "rustc", DiagnosticSeverity::ERROR, 0),
+                    diagnostic(1, "dead_code", "rustc", DiagnosticSeverity::WARNING, 1),
+                    diagnostic(
+                        2,
+                        "needless_return",
+                        "clippy",
+                        DiagnosticSeverity::WARNING,
+                        2,
+                    ),
+                ],
+                cx,
+            )
+            .unwrap();
+        lsp_store
+            .update_diagnostic_entries(
+                language_server_id,
+                PathBuf::from("/test/b.rs"),
+                None,
+                vec![
+                    diagnostic(0, "dead_code", "rustc", DiagnosticSeverity::WARNING, 0),
+                    diagnostic(1, "E0308", "rustc", DiagnosticSeverity::ERROR, 1),
+                ],
+                cx,
+            )
+            .unwrap();
+    });
+
+    let diagnostics = window.build_model(cx, |window, cx| {
+        ProjectDiagnosticsEditor::new_with_context(
+            1,
+            true,
+            project.clone(),
+            workspace.downgrade(),
+            window,
+            cx,
+        )
+    });
+    let update_diagnostics = |cx: &mut VisualTestContext| {
+        cx.executor()
+            .advance_clock(DIAGNOSTICS_UPDATE_DEBOUNCE + Duration::from_millis(10));
+        cx.executor().run_until_parked();
+    };
+    let set_filter = |query: &str, cx: &mut VisualTestContext| {
+        diagnostics.update_in(cx, |diagnostics, window, cx| {
+            diagnostics
+                .filter_editor
+                .update(cx, |editor, cx| editor.set_text(query, window, cx));
+        });
+        update_diagnostics(cx);
+    };
+    update_diagnostics(cx);
+    assert_eq!(
+        path_state_codes(&diagnostics, cx),
+        [
+            "a.rs: E0308, dead_code, needless_return",
+            "b.rs: dead_code, E0308",
+        ]
+    );
+
+    set_filter("code:dead_code", cx);
+    assert_eq!(
+        path_state_codes(&diagnostics, cx),
+        ["a.rs: dead_code", "b.rs: dead_code"]
+    );
+
+    set_filter("source:clippy path:a.rs", cx);
+    assert_eq!(
+        path_state_codes(&diagnostics, cx),
+        ["a.rs: needless_return"]
+    );
+
+    set_filter("severity:error row", cx);
+    assert_eq!(
+        path_state_codes(&diagnostics, cx),
+        ["a.rs: E0308", "b.rs: E0308"]
+    );
+
+    set_filter("severity:fatal", cx);
+    diagnostics.update(cx, |diagnostics, _| {
+        assert!(diagnostics.filter_error.is_some());
+    });
+    assert_eq!(
+        path_state_codes(&diagnostics, cx),
+        ["a.rs: E0308", "b.rs: E0308"],
+        "An invalid filter should keep the previous one"
+    );
+
+    set_filter("", cx);
+    diagnostics.update_in(cx, |diagnostics, window, cx| {
+        assert!(diagnostics.filter_error.is_none());
+        diagnostics.set_grouping(DiagnosticGrouping::Code, window, cx);
+    });
+    update_diagnostics(cx);
+    assert_eq!(
+        path_state_codes(&diagnostics, cx),
+        [
+            "[E0308] a.rs: E0308",
+            "[E0308] b.rs: E0308",
+            "[dead_code] a.rs: dead_code",
+            "[dead_code] b.rs: dead_code",
+            "[needless_return] a.rs: needless_return",
+        ]
+    );
+    assert_eq!(
+        get_diagnostics_excerpts(&diagnostics, cx)
+            .into_iter()
+            .map(|excerpt| excerpt.path)
+            .collect::<Vec<_>>(),
+        ["a.rs", "b.rs", "a.rs", "b.rs", "a.rs"]
+            .into_iter()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>(),
+        "Excerpts should be ordered by code, then by path"
+    );
+
+    diagnostics.update_in(cx, |diagnostics, window, cx| {
+        diagnostics.set_grouping(DiagnosticGrouping::Source, window, cx);
+    });
+    update_diagnostics(cx);
+    assert_eq!(
+        path_state_codes(&diagnostics, cx),
+        [
+            "[clippy] a.rs: needless_return",
+            "[rustc] a.rs: E0308, dead_code",
+            "[rustc] b.rs: dead_code, E0308",
+        ]
+    );
+
+    diagnostics.update_in(cx, |diagnostics, window, cx| {
+        diagnostics.set_grouping(DiagnosticGrouping::File, window, cx);
+    });
+    update_diagnostics(cx);
+    assert_eq!(
+        path_state_codes(&diagnostics, cx),
+        [
+            "a.rs: E0308, dead_code, needless_return",
+            "b.rs: dead_code, E0308",
+        ]
+    );
+}
+
+#[test]
+fn test_quick_fix_for() {
+    let diagnostic = Diagnostic {
+        code: Some("E0308".to_string()),
+        ..Default::default()
+    };
+    let action =
+        |title: &str, kind: Option<CodeActionKind>, code: Option<&str>, preferred| CodeAction {
+            server_id: LanguageServerId(0),
+            range: language::Anchor::MIN..language::Anchor::MAX,
+            lsp_action: lsp::CodeAction {
+                title: title.to_string(),
+                kind,
+                diagnostics: code.map(|code| {
+                    vec![lsp::Diagnostic {
+                        code: Some(lsp::NumberOrString::String(code.to_string())),
+                        ..Default::default()
+                    }]
+                }),
+                is_preferred: preferred,
+                ..Default::default()
+            },
+        };
+    let quick_fix_title =
+        |actions| quick_fix_for(actions, &diagnostic).map(|action| action.lsp_action.title);
+
+    assert_eq!(
+        quick_fix_title(vec![
+            action(
+                "extract",
+                Some(CodeActionKind::REFACTOR_EXTRACT),
+                None,
+                None
+            ),
+            action("fix", Some(CodeActionKind::QUICKFIX), None, None),
+        ]),
+        Some("fix".to_string())
+    );
+    assert_eq!(
+        quick_fix_title(vec![
+            action(
+                "fix other",
+                Some(CodeActionKind::QUICKFIX),
+                Some("E0599"),
+                None
+            ),
+            action("fix", None, Some("E0308"), None),
+        ]),
+        Some("fix".to_string())
+    );
+    assert_eq!(
+        quick_fix_title(vec![
+            action("fix 1", Some(CodeActionKind::QUICKFIX), None, None),
+            action("fix 2", Some(CodeActionKind::QUICKFIX), None, None),
+        ]),
+        None,
+        "Ambiguous quick fixes should not be applied"
+    );
+    assert_eq!(
+        quick_fix_title(vec![
+            action("fix 1", Some(CodeActionKind::QUICKFIX), None, None),
+            action("fix 2", Some(CodeActionKind::QUICKFIX), None, Some(true)),
+        ]),
+        Some("fix 2".to_string())
+    );
+}
+
 #[gpui::test(iterations = 20)]
 async fn test_random_diagnostics(cx: &mut TestAppContext, mut rng: StdRng) {
     init_test(cx);
@@ -902,6 +1162,156 @@ async fn test_random_diagnostics(cx: &mut TestAppContext, mut rng: StdRng) {
     assert_eq!(mutated_excerpts, reference_excerpts);
 }
 
+#[gpui::test]
+async fn test_fix_all_of_kind(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/test",
+        json!({
+            "a.rs": "let x = 1;\nlet y = 2;\n",
+            "b.rs": "let z = 3;\nlet w = 4;\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/test".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    )));
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer_a, _handle_a) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/test/a.rs", cx)
+        })
+        .await
+        .unwrap();
+    let (buffer_b, _handle_b) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/test/b.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    // Every quick fix prefixes the diagnosed variable with an underscore.
+    let _code_action_requests = fake_server
+        .handle_request::<lsp::request::CodeActionRequest, _, _>(|params, _| async move {
+            let position = params.range.start;
+            Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+                lsp::CodeAction {
+                    title: "Prefix with an underscore".to_string(),
+                    kind: Some(lsp::CodeActionKind::QUICKFIX),
+                    edit: Some(lsp::WorkspaceEdit {
+                        changes: Some(std::collections::HashMap::from_iter([(
+                            params.text_document.uri,
+                            vec![lsp::TextEdit::new(
+                                lsp::Range::new(position, position),
+                                "_".to_string(),
+                            )],
+                        )])),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )]))
+        });
+
+    let diagnostic = |row, code: &str| lsp::Diagnostic {
+        range: lsp::Range::new(lsp::Position::new(row, 4), lsp::Position::new(row, 5)),
+        severity: Some(lsp::DiagnosticSeverity::WARNING),
+        code: Some(lsp::NumberOrString::String(code.to_string())),
+        source: Some("fake".to_string()),
+        message: format!("{code} variable"),
+        ..Default::default()
+    };
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(&lsp::PublishDiagnosticsParams {
+        uri: lsp::Url::from_file_path("/test/a.rs").unwrap(),
+        version: None,
+        diagnostics: vec![diagnostic(0, "unused"), diagnostic(1, "unused")],
+    });
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(&lsp::PublishDiagnosticsParams {
+        uri: lsp::Url::from_file_path("/test/b.rs").unwrap(),
+        version: None,
+        diagnostics: vec![diagnostic(0, "unused"), diagnostic(1, "other")],
+    });
+    cx.executor().run_until_parked();
+
+    let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*window, cx);
+    let workspace = window.root(cx).unwrap();
+    let diagnostics = window.build_model(cx, |window, cx| {
+        ProjectDiagnosticsEditor::new_with_context(
+            1,
+            true,
+            project.clone(),
+            workspace.downgrade(),
+            window,
+            cx,
+        )
+    });
+    cx.executor()
+        .advance_clock(DIAGNOSTICS_UPDATE_DEBOUNCE + Duration::from_millis(10));
+    cx.executor().run_until_parked();
+
+    // Fix every "unused" diagnostic from the first excerpt, leaving the "other" one alone.
+    let editor = diagnostics.update(cx, |diagnostics, _| diagnostics.editor.clone());
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |selections| {
+            selections.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+    });
+    diagnostics.update_in(cx, |diagnostics, window, cx| {
+        diagnostics.fix_all_of_kind(&FixAllOfKind, window, cx)
+    });
+    cx.executor().run_until_parked();
+
+    assert_eq!(
+        buffer_a.read_with(cx, |buffer, _| buffer.text()),
+        "let _x = 1;\nlet _y = 2;\n"
+    );
+    assert_eq!(
+        buffer_b.read_with(cx, |buffer, _| buffer.text()),
+        "let _z = 3;\nlet w = 4;\n"
+    );
+
+    // A single undo reverts the fixes in every buffer.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.undo(&editor::actions::Undo, window, cx)
+    });
+    cx.executor().run_until_parked();
+
+    assert_eq!(
+        buffer_a.read_with(cx, |buffer, _| buffer.text()),
+        "let x = 1;\nlet y = 2;\n"
+    );
+    assert_eq!(
+        buffer_b.read_with(cx, |buffer, _| buffer.text()),
+        "let z = 3;\nlet w = 4;\n"
+    );
+}
+
 fn init_test(cx: &mut TestAppContext) {
     cx.update(|cx| {
         let settings = SettingsStore::test(cx);
@@ -965,6 +1375,31 @@ fn get_diagnostics_excerpts(
     })
 }
 
+fn path_state_codes(
+    diagnostics: &Entity<ProjectDiagnosticsEditor>,
+    cx: &mut VisualTestContext,
+) -> Vec<String> {
+    diagnostics.update(cx, |diagnostics, _| {
+        diagnostics
+            .path_states
+            .iter()
+            .map(|state| {
+                let codes = state
+                    .diagnostic_groups
+                    .iter()
+                    .filter_map(|group| group.primary_diagnostic.diagnostic.code.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let path = state.path.path.to_string_lossy();
+                match &state.key {
+                    Some(key) => format!("[{key}] {path}: {codes}"),
+                    None => format!("{path}: {codes}"),
+                }
+            })
+            .collect()
+    })
+}
+
 fn randomly_update_diagnostics_for_path(
     fs: &FakeFs,
     path: &Path,
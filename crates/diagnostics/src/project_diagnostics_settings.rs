@@ -7,6 +7,20 @@ use settings::{Settings, SettingsSources};
 #[derive(Deserialize, Debug)]
 pub struct ProjectDiagnosticsSettings {
     pub include_warnings: bool,
+    pub group_by: DiagnosticGrouping,
+}
+
+/// How diagnostics are grouped in the project diagnostics view.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticGrouping {
+    /// Show the diagnostics of each file together.
+    #[default]
+    File,
+    /// Show diagnostics with the same code together.
+    Code,
+    /// Show diagnostics reported by the same source together.
+    Source,
 }
 
 /// Diagnostics configuration.
@@ -16,6 +30,10 @@ pub struct ProjectDiagnosticsSettingsContent {
     ///
     /// Default: true
     include_warnings: Option<bool>,
+    /// How to group the diagnostics: by file, by diagnostic code or by source.
+    ///
+    /// Default: file
+    group_by: Option<DiagnosticGrouping>,
 }
 
 impl Settings for ProjectDiagnosticsSettings {
@@ -1,7 +1,12 @@
-use crate::ProjectDiagnosticsEditor;
-use gpui::{Context, Entity, EventEmitter, ParentElement, Render, WeakEntity, Window};
+use crate::{
+    DiagnosticGrouping, FixAllOfKind, GroupByCode, GroupByFile, GroupBySource,
+    ProjectDiagnosticsEditor, ToggleFilter,
+};
+use gpui::{
+    Action, Context, Corner, Entity, EventEmitter, ParentElement, Render, WeakEntity, Window,
+};
 use ui::prelude::*;
-use ui::{IconButton, IconButtonShape, IconName, Tooltip};
+use ui::{ContextMenu, IconButton, IconButtonShape, IconName, PopoverMenu, Tooltip};
 use workspace::{item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 
 pub struct ToolbarControls {
@@ -13,10 +18,14 @@ impl Render for ToolbarControls {
         let mut include_warnings = false;
         let mut has_stale_excerpts = false;
         let mut is_updating = false;
+        let mut is_filtered = false;
+        let mut grouping = DiagnosticGrouping::default();
 
         if let Some(editor) = self.diagnostics() {
             let diagnostics = editor.read(cx);
             include_warnings = diagnostics.include_warnings;
+            is_filtered = diagnostics.show_filter || !diagnostics.filter.is_empty();
+            grouping = diagnostics.grouping;
             has_stale_excerpts = !diagnostics.paths_to_update.is_empty();
             is_updating = diagnostics.update_excerpts_task.is_some()
                 || diagnostics
@@ -70,6 +79,32 @@ impl Render for ToolbarControls {
                         }
                     })),
             )
+            .child(
+                IconButton::new("toggle-filter", IconName::Filter)
+                    .shape(IconButtonShape::Square)
+                    .toggle_state(is_filtered)
+                    .tooltip(Tooltip::text("Filter Diagnostics"))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(editor) = this.diagnostics() {
+                            editor.update(cx, |editor, cx| {
+                                editor.toggle_filter(&ToggleFilter, window, cx);
+                            });
+                        }
+                    })),
+            )
+            .child(self.render_grouping_menu(grouping))
+            .child(
+                IconButton::new("fix-all-of-kind", IconName::Wand)
+                    .shape(IconButtonShape::Square)
+                    .tooltip(Tooltip::text("Fix All of This Kind"))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(editor) = this.diagnostics() {
+                            editor.update(cx, |editor, cx| {
+                                editor.fix_all_of_kind(&FixAllOfKind, window, cx);
+                            });
+                        }
+                    })),
+            )
     }
 }
 
@@ -109,4 +144,61 @@ impl ToolbarControls {
     fn diagnostics(&self) -> Option<Entity<ProjectDiagnosticsEditor>> {
         self.editor.as_ref()?.upgrade()
     }
+
+    fn render_grouping_menu(&self, grouping: DiagnosticGrouping) -> impl IntoElement {
+        let editor = self.editor.clone();
+        PopoverMenu::new("diagnostics-grouping-menu")
+            .trigger(
+                IconButton::new("diagnostics-grouping", IconName::ListTree)
+                    .shape(IconButtonShape::Square)
+                    .toggle_state(grouping != DiagnosticGrouping::File)
+                    .tooltip(Tooltip::text("Group Diagnostics")),
+            )
+            .anchor(Corner::TopRight)
+            .menu(move |window, cx| {
+                let editor = editor.clone();
+                Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                    let grouping_entry =
+                        |menu: ContextMenu,
+                         label: &'static str,
+                         entry_grouping: DiagnosticGrouping,
+                         action: Box<dyn Action>| {
+                            let editor = editor.clone();
+                            menu.toggleable_entry(
+                                label,
+                                grouping == entry_grouping,
+                                IconPosition::Start,
+                                Some(action),
+                                move |window, cx| {
+                                    if let Some(editor) = editor.as_ref().and_then(|e| e.upgrade())
+                                    {
+                                        editor.update(cx, |editor, cx| {
+                                            editor.set_grouping(entry_grouping, window, cx);
+                                        });
+                                    }
+                                },
+                            )
+                        };
+                    let menu = menu.header("Group By");
+                    let menu = grouping_entry(
+                        menu,
+                        "File",
+                        DiagnosticGrouping::File,
+                        Box::new(GroupByFile),
+                    );
+                    let menu = grouping_entry(
+                        menu,
+                        "Code",
+                        DiagnosticGrouping::Code,
+                        Box::new(GroupByCode),
+                    );
+                    grouping_entry(
+                        menu,
+                        "Source",
+                        DiagnosticGrouping::Source,
+                        Box::new(GroupBySource),
+                    )
+                }))
+            })
+    }
 }
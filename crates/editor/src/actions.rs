@@ -226,6 +226,9 @@ gpui::actions!(
         Backspace,
         Cancel,
         CancelLanguageServerWork,
+        CompareSelections,
+        CompareWithClipboard,
+        CompareWithFile,
         ConfirmRename,
         ContextMenuFirst,
         ContextMenuLast,
@@ -257,6 +260,7 @@ gpui::actions!(
         DuplicateLineDown,
         DuplicateLineUp,
         DuplicateSelection,
+        EnableLargeFileFeatures,
         ExpandAllHunkDiffs,
         ExpandMacroRecursively,
         FindAllReferences,
@@ -275,8 +279,10 @@ gpui::actions!(
         GoToDefinitionSplit,
         GoToDiagnostic,
         GoToHunk,
+        GoToNextBookmark,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToPrevBookmark,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -371,6 +377,7 @@ gpui::actions!(
         Tab,
         TabPrev,
         ToggleAutoSignatureHelp,
+        ToggleBookmark,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleIndentGuides,
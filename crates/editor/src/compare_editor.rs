@@ -0,0 +1,736 @@
+use crate::{Editor, EditorEvent, GoToHunk, GoToPrevHunk};
+use collections::HashSet;
+use gpui::{
+    actions, AnyView, App, Entity, EventEmitter, FocusHandle, Focusable, Hsla, Render,
+    Subscription, Task,
+};
+use language::{language_settings::SoftWrap, Buffer, BufferEvent, Point};
+use multi_buffer::MultiBuffer;
+use project::{
+    buffer_store::{BufferChangeSet, BufferChangeSetEvent},
+    Project,
+};
+use std::{any::TypeId, ops::Range};
+use text::{ToOffset as _, ToPoint as _};
+use ui::{prelude::*, IconButtonShape, Tooltip};
+use workspace::{searchable::SearchableItemHandle, Item, ItemNavHistory, Workspace};
+
+actions!(compare_editor, [ApplyHunkToLeft, ApplyHunkToRight]);
+
+/// Shows the differences between two buffers side by side.
+///
+/// Each side is diffed against the other, so that its changed lines are highlighted, and the two
+/// sides scroll together. Hunks can be copied in either direction, and both buffers are re-diffed
+/// as they are edited.
+pub struct CompareEditor {
+    left: CompareSide,
+    right: CompareSide,
+    /// The side whose scroll position was last set to follow the other side, and the position it
+    /// was set to, so that the scroll event this causes isn't followed back.
+    following_scroll: Option<(Side, f32)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn other(self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+struct CompareSide {
+    buffer: Entity<Buffer>,
+    title: SharedString,
+    editor: Entity<Editor>,
+    /// The differences between this side's buffer and the other side's text.
+    change_set: Entity<BufferChangeSet>,
+}
+
+/// The row highlights of the lines that differ from the other side.
+enum CompareHunkHighlight {}
+
+impl CompareEditor {
+    /// Opens an editor comparing `left` with `right` in the active pane.
+    pub fn open(
+        left: Entity<Buffer>,
+        left_title: impl Into<SharedString>,
+        right: Entity<Buffer>,
+        right_title: impl Into<SharedString>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let compare_editor =
+            cx.new(|cx| Self::new(left, left_title, right, right_title, project, window, cx));
+        workspace.add_item_to_active_pane(Box::new(compare_editor.clone()), None, true, window, cx);
+        compare_editor
+    }
+
+    pub fn new(
+        left: Entity<Buffer>,
+        left_title: impl Into<SharedString>,
+        right: Entity<Buffer>,
+        right_title: impl Into<SharedString>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let left = CompareSide::new(left, left_title.into(), &right, &project, window, cx);
+        let right = CompareSide::new(
+            right,
+            right_title.into(),
+            &left.buffer,
+            &project,
+            window,
+            cx,
+        );
+
+        let mut subscriptions = Vec::new();
+        for side in [Side::Left, Side::Right] {
+            let compare_side = match side {
+                Side::Left => &left,
+                Side::Right => &right,
+            };
+            subscriptions.push(
+                cx.subscribe(&compare_side.buffer, move |this, _, event, cx| {
+                    if let BufferEvent::Edited = event {
+                        this.update_diffs(side, cx);
+                    }
+                }),
+            );
+            subscriptions.push(cx.subscribe(
+                &compare_side.change_set,
+                move |this, _, event, cx| {
+                    let BufferChangeSetEvent::DiffChanged { .. } = event;
+                    this.highlight_hunks(side, cx);
+                },
+            ));
+            subscriptions.push(cx.subscribe_in(
+                &compare_side.editor,
+                window,
+                move |this, _, event, window, cx| {
+                    if let EditorEvent::ScrollPositionChanged { .. } = event {
+                        this.follow_scroll(side, window, cx);
+                    }
+                },
+            ));
+        }
+
+        Self {
+            left,
+            right,
+            following_scroll: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Creates a buffer that isn't backed by a file, to compare text such as the contents of the
+    /// clipboard. It uses the language of `like`, so that both sides are highlighted the same
+    /// way.
+    pub fn scratch_buffer(text: String, like: &Entity<Buffer>, cx: &mut App) -> Entity<Buffer> {
+        let like = like.read(cx);
+        let language = like.language().cloned();
+        let language_registry = like.language_registry();
+        cx.new(|cx| {
+            let mut buffer = Buffer::local(text, cx);
+            if let Some(language_registry) = language_registry {
+                buffer.set_language_registry(language_registry);
+            }
+            buffer.set_language(language, cx);
+            buffer
+        })
+    }
+
+    /// The title to use for a buffer in a comparison, which is its file name if it has one.
+    pub fn buffer_title(buffer: &Entity<Buffer>, cx: &App) -> SharedString {
+        buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "untitled".into())
+    }
+
+    fn side(&self, side: Side) -> &CompareSide {
+        match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+        }
+    }
+
+    /// The side that has focus, or the right side if neither does.
+    fn focused_side(&self, window: &Window, cx: &App) -> Side {
+        if self.left.editor.focus_handle(cx).is_focused(window) {
+            Side::Left
+        } else {
+            Side::Right
+        }
+    }
+
+    /// Re-diffs both sides after the buffer on the given side was edited.
+    fn update_diffs(&mut self, edited: Side, cx: &mut Context<Self>) {
+        let edited_side = self.side(edited);
+        let edited_snapshot = edited_side.buffer.read(cx).text_snapshot();
+        let edited_text = edited_snapshot.text();
+        edited_side.change_set.update(cx, |change_set, cx| {
+            let _ = change_set.recalculate_diff(edited_snapshot, cx);
+        });
+
+        let other_side = self.side(edited.other());
+        let other_snapshot = other_side.buffer.read(cx).text_snapshot();
+        other_side.change_set.update(cx, |change_set, cx| {
+            let _ = change_set.set_base_text(edited_text, other_snapshot, cx);
+        });
+    }
+
+    fn highlight_hunks(&mut self, side: Side, cx: &mut Context<Self>) {
+        let color = match side {
+            Side::Left => cx.theme().status().deleted_background,
+            Side::Right => cx.theme().status().created_background,
+        };
+        self.side(side).highlight_hunks(color, cx);
+    }
+
+    /// Scrolls the other side to the lines that correspond to the top of the given side.
+    fn follow_scroll(&mut self, scrolled: Side, window: &mut Window, cx: &mut Context<Self>) {
+        let scrolled_side = self.side(scrolled);
+        let scroll_position = scrolled_side
+            .editor
+            .update(cx, |editor, cx| editor.scroll_position(cx));
+        let scroll_top = scrolled_side.other_row(scroll_position.y, cx);
+        if let Some((side, followed_top)) = self.following_scroll.take() {
+            if side == scrolled && (followed_top - scroll_position.y).abs() < 0.01 {
+                return;
+            }
+        }
+
+        let other = scrolled.other();
+        self.following_scroll = Some((other, scroll_top));
+        self.side(other).editor.update(cx, |editor, cx| {
+            let mut position = editor.scroll_position(cx);
+            position.y = scroll_top;
+            editor.set_scroll_position(position, window, cx);
+        });
+    }
+
+    /// Replaces the selected hunks in the left buffer with their text from the right buffer.
+    fn apply_hunk_to_left(
+        &mut self,
+        _: &ApplyHunkToLeft,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.apply_hunks(Side::Left, window, cx);
+    }
+
+    /// Replaces the selected hunks in the right buffer with their text from the left buffer.
+    fn apply_hunk_to_right(
+        &mut self,
+        _: &ApplyHunkToRight,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.apply_hunks(Side::Right, window, cx);
+    }
+
+    /// Copies the hunks that are selected on the focused side to the `target` side.
+    fn apply_hunks(&mut self, target: Side, window: &mut Window, cx: &mut Context<Self>) {
+        let focused = self.focused_side(window, cx);
+        let focused_side = self.side(focused);
+        let hunks = focused_side.editor.update(cx, |editor, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            let selections = editor.selections.all::<Point>(cx);
+            snapshot.hunks_for_ranges(selections.into_iter().map(|selection| selection.range()))
+        });
+
+        let Some(other_text) = focused_side.change_set.read(cx).base_text.clone() else {
+            return;
+        };
+        // The hunks refer to the other side's text as of the last diff, so don't apply them if it
+        // changed since.
+        if other_text.text() != self.side(focused.other()).buffer.read(cx).text() {
+            return;
+        }
+
+        let focused_snapshot = focused_side.buffer.read(cx).snapshot();
+        let edits = hunks
+            .into_iter()
+            .map(|hunk| {
+                let focused_range = hunk.buffer_range.to_offset(&focused_snapshot);
+                if focused == target {
+                    let new_text = other_text
+                        .text_for_range(hunk.diff_base_byte_range)
+                        .collect::<String>();
+                    (focused_range, new_text)
+                } else {
+                    let new_text = focused_snapshot
+                        .text_for_range(focused_range)
+                        .collect::<String>();
+                    (hunk.diff_base_byte_range, new_text)
+                }
+            })
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            return;
+        }
+        self.side(target)
+            .buffer
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+    }
+
+    fn render_header(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self
+            .side(self.focused_side(window, cx))
+            .editor
+            .focus_handle(cx);
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(div().flex_1())
+            .child(
+                IconButton::new("previous-hunk", IconName::ArrowUp)
+                    .shape(IconButtonShape::Square)
+                    .icon_size(IconSize::Small)
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Previous Hunk",
+                                &GoToPrevHunk,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click({
+                        let focus_handle = focus_handle.clone();
+                        move |_, window, cx| {
+                            focus_handle.dispatch_action(&GoToPrevHunk, window, cx);
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("next-hunk", IconName::ArrowDown)
+                    .shape(IconButtonShape::Square)
+                    .icon_size(IconSize::Small)
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Next Hunk",
+                                &GoToHunk,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click({
+                        let focus_handle = focus_handle.clone();
+                        move |_, window, cx| {
+                            focus_handle.dispatch_action(&GoToHunk, window, cx);
+                        }
+                    }),
+            )
+            .child(
+                Button::new("apply-to-left", "Apply to Left")
+                    .label_size(LabelSize::Small)
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Copy the selected hunks to the left side",
+                                &ApplyHunkToLeft,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.apply_hunk_to_left(&ApplyHunkToLeft, window, cx)
+                    })),
+            )
+            .child(
+                Button::new("apply-to-right", "Apply to Right")
+                    .label_size(LabelSize::Small)
+                    .tooltip(move |window, cx| {
+                        Tooltip::for_action_in(
+                            "Copy the selected hunks to the right side",
+                            &ApplyHunkToRight,
+                            &focus_handle,
+                            window,
+                            cx,
+                        )
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.apply_hunk_to_right(&ApplyHunkToRight, window, cx)
+                    })),
+            )
+    }
+
+    fn render_side(&self, side: Side, cx: &mut Context<Self>) -> impl IntoElement {
+        let compare_side = self.side(side);
+        v_flex()
+            .flex_1()
+            .h_full()
+            .min_w_0()
+            .when(side == Side::Right, |this| {
+                this.border_l_1()
+                    .border_color(cx.theme().colors().border_variant)
+            })
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_0p5()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(compare_side.title.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(compare_side.editor.clone())
+    }
+}
+
+impl CompareSide {
+    fn new(
+        buffer: Entity<Buffer>,
+        title: SharedString,
+        other_buffer: &Entity<Buffer>,
+        project: &Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<CompareEditor>,
+    ) -> Self {
+        let change_set = cx.new(|cx| {
+            let mut change_set = BufferChangeSet::new(&buffer, cx);
+            let _ = change_set.set_base_text(
+                other_buffer.read(cx).text(),
+                buffer.read(cx).text_snapshot(),
+                cx,
+            );
+            change_set
+        });
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            multibuffer.add_change_set(change_set.clone(), cx);
+            multibuffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer, Some(project.clone()), true, window, cx);
+            // Lines only correspond between the sides when they aren't wrapped.
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor
+        });
+        Self {
+            buffer,
+            title,
+            editor,
+            change_set,
+        }
+    }
+
+    fn hunk_ranges(&self, cx: &App) -> Vec<(Range<Point>, Range<Point>)> {
+        let change_set = self.change_set.read(cx);
+        let Some(other_text) = change_set.base_text.as_ref() else {
+            return Vec::new();
+        };
+        let snapshot = self.buffer.read(cx).text_snapshot();
+        change_set
+            .diff_hunks_intersecting_range(text::Anchor::MIN..text::Anchor::MAX, &snapshot)
+            .map(|hunk| {
+                (
+                    hunk.buffer_range.start.to_point(&snapshot)
+                        ..hunk.buffer_range.end.to_point(&snapshot),
+                    other_text.offset_to_point(hunk.diff_base_byte_range.start)
+                        ..other_text.offset_to_point(hunk.diff_base_byte_range.end),
+                )
+            })
+            .collect()
+    }
+
+    fn highlight_hunks(&self, color: Hsla, cx: &mut App) {
+        let ranges = self
+            .hunk_ranges(cx)
+            .into_iter()
+            .map(|(range, _)| range)
+            .filter(|range| !range.is_empty())
+            .collect::<Vec<_>>();
+        self.editor.update(cx, |editor, cx| {
+            editor.clear_row_highlights::<CompareHunkHighlight>();
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            for range in ranges {
+                editor.highlight_rows::<CompareHunkHighlight>(
+                    snapshot.anchor_before(range.start)..snapshot.anchor_before(range.end),
+                    color,
+                    false,
+                    cx,
+                );
+            }
+            cx.notify();
+        });
+    }
+
+    /// Returns the row on the other side that corresponds to the given row on this side.
+    fn other_row(&self, row: f32, cx: &App) -> f32 {
+        other_row(&self.hunk_ranges(cx), row)
+    }
+}
+
+/// Maps a row to the other side of a diff, given the row ranges of the diff's hunks on this side
+/// and on the other side. Rows within a hunk map to the same offset into the hunk on the other
+/// side, up to its end.
+fn other_row(hunks: &[(Range<Point>, Range<Point>)], row: f32) -> f32 {
+    let mut row_delta = 0.;
+    for (range, other_range) in hunks {
+        if row < range.start.row as f32 {
+            break;
+        }
+        if row < range.end.row as f32 {
+            return (other_range.start.row as f32 + row - range.start.row as f32)
+                .min(other_range.end.row as f32);
+        }
+        row_delta = other_range.end.row as f32 - range.end.row as f32;
+    }
+    (row + row_delta).max(0.)
+}
+
+impl Render for CompareEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .key_context("CompareEditor")
+            .on_action(cx.listener(Self::apply_hunk_to_left))
+            .on_action(cx.listener(Self::apply_hunk_to_right))
+            .child(self.render_header(window, cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .child(self.render_side(Side::Left, cx))
+                    .child(self.render_side(Side::Right, cx)),
+            )
+    }
+}
+
+impl Focusable for CompareEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.right.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for CompareEditor {}
+
+impl Item for CompareEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff))
+    }
+
+    fn tab_content_text(&self, _window: &Window, _cx: &App) -> Option<SharedString> {
+        Some(format!("{} ↔ {}", self.left.title, self.right.title).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(workspace::item::ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.right.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.right.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for editor in [&self.left.editor, &self.right.editor] {
+            editor.update(cx, |editor, cx| {
+                Item::added_to_workspace(editor, workspace, window, cx)
+            });
+        }
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for editor in [&self.left.editor, &self.right.editor] {
+            editor.update(cx, |editor, cx| editor.deactivated(window, cx));
+        }
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn std::any::Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.right
+            .editor
+            .update(cx, |editor, cx| Item::navigate(editor, data, window, cx))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.right.editor.update(cx, |editor, cx| {
+            Item::set_nav_history(editor, nav_history, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        [&self.left.buffer, &self.right.buffer]
+            .into_iter()
+            .any(|buffer| buffer.read(cx).file().is_some() && buffer.read(cx).is_dirty())
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.left.buffer.read(cx).file().is_some() || self.right.buffer.read(cx).file().is_some()
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let buffers = [&self.left.buffer, &self.right.buffer]
+            .into_iter()
+            .filter(|buffer| buffer.read(cx).file().is_some() && buffer.read(cx).is_dirty())
+            .cloned()
+            .collect::<HashSet<_>>();
+        project.update(cx, |project, cx| project.save_buffers(buffers, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_tests::init_test;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+
+    #[gpui::test]
+    async fn test_apply_hunks(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let left = cx.new(|cx| Buffer::local("one\ntwo\nthree\nfour\nfive\n", cx));
+        let right = cx.new(|cx| Buffer::local("one\nTWO\nthree\nFOUR\nfive\nsix\n", cx));
+        let (compare_editor, cx) = cx.add_window_view(|window, cx| {
+            CompareEditor::new(
+                left.clone(),
+                "left",
+                right.clone(),
+                "right",
+                project,
+                window,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        let select_row = |side: Side, row: u32, cx: &mut VisualTestContext| {
+            compare_editor.update_in(cx, |compare_editor, window, cx| {
+                compare_editor.side(side).editor.update(cx, |editor, cx| {
+                    window.focus(&editor.focus_handle(cx));
+                    editor.change_selections(None, window, cx, |selections| {
+                        selections.select_ranges([Point::new(row, 0)..Point::new(row, 0)])
+                    });
+                });
+            });
+        };
+
+        // Copy a hunk from the focused right side to the left side.
+        select_row(Side::Right, 1, cx);
+        compare_editor.update_in(cx, |compare_editor, window, cx| {
+            compare_editor.apply_hunk_to_left(&ApplyHunkToLeft, window, cx)
+        });
+        cx.run_until_parked();
+        cx.update(|_, cx| {
+            assert_eq!(left.read(cx).text(), "one\nTWO\nthree\nfour\nfive\n");
+            assert_eq!(right.read(cx).text(), "one\nTWO\nthree\nFOUR\nfive\nsix\n");
+        });
+
+        // Copy a hunk from the focused left side to the right side.
+        select_row(Side::Left, 3, cx);
+        compare_editor.update_in(cx, |compare_editor, window, cx| {
+            compare_editor.apply_hunk_to_right(&ApplyHunkToRight, window, cx)
+        });
+        cx.run_until_parked();
+        cx.update(|_, cx| {
+            assert_eq!(left.read(cx).text(), "one\nTWO\nthree\nfour\nfive\n");
+            assert_eq!(right.read(cx).text(), "one\nTWO\nthree\nfour\nfive\nsix\n");
+        });
+
+        // Copy a hunk to the focused right side from the left side, which removes the line that's
+        // only on the right side.
+        select_row(Side::Right, 5, cx);
+        compare_editor.update_in(cx, |compare_editor, window, cx| {
+            compare_editor.apply_hunk_to_right(&ApplyHunkToRight, window, cx)
+        });
+        cx.run_until_parked();
+        cx.update(|_, cx| {
+            assert_eq!(left.read(cx).text(), "one\nTWO\nthree\nfour\nfive\n");
+            assert_eq!(right.read(cx).text(), "one\nTWO\nthree\nfour\nfive\n");
+        });
+    }
+
+    #[test]
+    fn test_other_row() {
+        // One line was changed to three at row 2, and two lines were removed at row 6.
+        let hunks = [
+            (
+                Point::new(2, 0)..Point::new(5, 0),
+                Point::new(2, 0)..Point::new(3, 0),
+            ),
+            (
+                Point::new(6, 0)..Point::new(6, 0),
+                Point::new(4, 0)..Point::new(6, 0),
+            ),
+        ];
+        assert_eq!(other_row(&hunks, 1.), 1.);
+        assert_eq!(other_row(&hunks, 2.), 2.);
+        assert_eq!(other_row(&hunks, 2.5), 2.5);
+        assert_eq!(other_row(&hunks, 4.), 3.);
+        assert_eq!(other_row(&hunks, 5.), 3.);
+        assert_eq!(other_row(&hunks, 6.), 6.);
+        assert_eq!(other_row(&hunks, 10.), 10.);
+    }
+}
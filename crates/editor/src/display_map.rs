@@ -80,7 +80,25 @@ pub trait ToDisplayPoint {
     fn to_display_point(&self, map: &DisplaySnapshot) -> DisplayPoint;
 }
 
-type TextHighlights = TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
+/// Identifies a set of text highlights.
+///
+/// Most highlights are keyed by a marker type, but a feature can use several keys with the same
+/// type when it needs more than one style, e.g. one per nesting depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HighlightKey {
+    Type(TypeId),
+    TypePlus(TypeId, usize),
+}
+
+impl HighlightKey {
+    fn type_id(&self) -> TypeId {
+        match self {
+            HighlightKey::Type(type_id) | HighlightKey::TypePlus(type_id, _) => *type_id,
+        }
+    }
+}
+
+type TextHighlights = TreeMap<HighlightKey, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
@@ -455,12 +473,11 @@ impl DisplayMap {
 
     pub fn highlight_text(
         &mut self,
-        type_id: TypeId,
+        key: HighlightKey,
         ranges: Vec<Range<Anchor>>,
         style: HighlightStyle,
     ) {
-        self.text_highlights
-            .insert(type_id, Arc::new((style, ranges)));
+        self.text_highlights.insert(key, Arc::new((style, ranges)));
     }
 
     pub(crate) fn highlight_inlays(
@@ -483,12 +500,36 @@ impl DisplayMap {
     }
 
     pub fn text_highlights(&self, type_id: TypeId) -> Option<(HighlightStyle, &[Range<Anchor>])> {
-        let highlights = self.text_highlights.get(&type_id)?;
+        let highlights = self.text_highlights.get(&HighlightKey::Type(type_id))?;
         Some((highlights.0, &highlights.1))
     }
+
+    /// Removes the text and inlay highlights for the given type, including any keyed highlights.
     pub fn clear_highlights(&mut self, type_id: TypeId) -> bool {
-        let mut cleared = self.text_highlights.remove(&type_id).is_some();
-        cleared |= self.inlay_highlights.remove(&type_id).is_some();
+        let mut cleared = false;
+        let key = HighlightKey::Type(type_id);
+        if self.text_highlights.get(&key).is_some() {
+            self.text_highlights.remove(&key);
+            cleared = true;
+        }
+
+        // Keyed highlights are ordered by type, so those of this type are next to each other.
+        let first_keyed = HighlightKey::TypePlus(type_id, 0);
+        let keyed = self
+            .text_highlights
+            .iter_from(&first_keyed)
+            .map(|(key, _)| *key)
+            .take_while(|key| key.type_id() == type_id)
+            .collect::<Vec<_>>();
+        for key in &keyed {
+            self.text_highlights.remove(key);
+        }
+        cleared |= !keyed.is_empty();
+
+        if self.inlay_highlights.get(&type_id).is_some() {
+            self.inlay_highlights.remove(&type_id);
+            cleared = true;
+        }
         cleared
     }
 
@@ -1278,7 +1319,17 @@ impl DisplaySnapshot {
         &self,
     ) -> Option<Arc<(HighlightStyle, Vec<Range<Anchor>>)>> {
         let type_id = TypeId::of::<Tag>();
-        self.text_highlights.get(&type_id).cloned()
+        self.text_highlights
+            .get(&HighlightKey::Type(type_id))
+            .cloned()
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn text_highlight_ranges_for_key(
+        &self,
+        key: HighlightKey,
+    ) -> Option<Arc<(HighlightStyle, Vec<Range<Anchor>>)>> {
+        self.text_highlights.get(&key).cloned()
     }
 
     #[allow(unused)]
@@ -2170,7 +2221,7 @@ pub mod tests {
         // Insert a block in the middle of a multi-line diagnostic.
         map.update(cx, |map, cx| {
             map.highlight_text(
-                TypeId::of::<usize>(),
+                HighlightKey::Type(TypeId::of::<usize>()),
                 vec![
                     buffer_snapshot.anchor_before(Point::new(3, 9))
                         ..buffer_snapshot.anchor_after(Point::new(3, 14)),
@@ -2490,7 +2541,7 @@ pub mod tests {
 
         map.update(cx, |map, _cx| {
             map.highlight_text(
-                TypeId::of::<MyType>(),
+                HighlightKey::Type(TypeId::of::<MyType>()),
                 highlighted_ranges
                     .into_iter()
                     .map(|range| {
@@ -17,6 +17,7 @@ mod blame_entry_tooltip;
 mod blink_manager;
 mod clangd_ext;
 mod code_context_menus;
+mod compare_editor;
 pub mod display_map;
 mod editor_settings;
 mod editor_settings_controls;
@@ -28,12 +29,14 @@ mod hover_popover;
 mod indent_guides;
 mod inlay_hint_cache;
 pub mod items;
+mod large_file_banner;
 mod linked_editing_ranges;
 mod lsp_ext;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod proposed_changes_editor;
+mod rainbow_brackets;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
@@ -63,10 +66,10 @@ pub use editor_settings::{
     CurrentLineHighlight, EditorSettings, ScrollBeyondLastLine, SearchSettings, ShowScrollbar,
 };
 pub use editor_settings_controls::*;
-use element::LineWithInvisibles;
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
 };
+use element::{LineWithInvisibles, MinimapLineCache};
 use futures::{future, FutureExt};
 use fuzzy::StringMatchCandidate;
 use zed_predict_onboarding::ZedPredictModal;
@@ -75,15 +78,16 @@ use code_context_menus::{
     AvailableCodeAction, CodeActionContents, CodeActionsItem, CodeActionsMenu, CodeContextMenu,
     CompletionEntry, CompletionsMenu, ContextMenuOrigin,
 };
+pub use compare_editor::CompareEditor;
 use git::blame::GitBlame;
 use gpui::{
     div, impl_actions, point, prelude::*, px, relative, size, Action, AnyElement, App,
     AsyncWindowContext, AvailableSpace, Bounds, ClipboardEntry, ClipboardItem, Context,
     DispatchPhase, ElementId, Entity, EntityInputHandler, EventEmitter, FocusHandle, FocusOutEvent,
     Focusable, FontId, FontWeight, Global, HighlightStyle, Hsla, InteractiveText, KeyContext,
-    MouseButton, MouseDownEvent, PaintQuad, ParentElement, Pixels, Render, SharedString, Size,
-    Styled, StyledText, Subscription, Task, TextStyle, TextStyleRefinement, UTF16Selection,
-    UnderlineStyle, UniformListScrollHandle, WeakEntity, WeakFocusHandle, Window,
+    MouseButton, MouseDownEvent, PaintQuad, ParentElement, PathPromptOptions, Pixels, Render,
+    SharedString, Size, Styled, StyledText, Subscription, Task, TextStyle, TextStyleRefinement,
+    UTF16Selection, UnderlineStyle, UniformListScrollHandle, WeakEntity, WeakFocusHandle, Window,
 };
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
@@ -101,11 +105,13 @@ use language::{
     TreeSitterOptions,
 };
 use language::{point_to_lsp, BufferRow, CharClassifier, Runnable, RunnableRange};
+pub use large_file_banner::LargeFileBanner;
 use linked_editing_ranges::refresh_linked_ranges;
 use mouse_context_menu::MouseContextMenu;
 pub use proposed_changes_editor::{
     ProposedChangeLocation, ProposedChangesEditor, ProposedChangesEditorToolbar,
 };
+use rainbow_brackets::refresh_rainbow_bracket_highlights;
 use similar::{ChangeTag, TextDiff};
 use std::iter::Peekable;
 use task::{ResolvedTask, TaskTemplate, TaskVariables};
@@ -129,8 +135,9 @@ use multi_buffer::{
 use project::{
     lsp_store::{FormatTrigger, LspFormatTarget, OpenLspBufferHandle},
     project_settings::{GitGutterSetting, ProjectSettings},
-    CodeAction, Completion, CompletionIntent, DocumentHighlight, InlayHint, Location, LocationLink,
-    LspStore, PrepareRenameResponse, Project, ProjectItem, ProjectTransaction, TaskSourceKind,
+    CodeAction, Completion, CompletionIntent, DirectoryLister, DocumentHighlight, InlayHint,
+    Location, LocationLink, LspStore, PrepareRenameResponse, Project, ProjectItem,
+    ProjectTransaction, TaskSourceKind,
 };
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
@@ -725,6 +732,7 @@ pub struct Editor {
         >,
     >,
     last_bounds: Option<Bounds<Pixels>>,
+    minimap_line_cache: MinimapLineCache,
     expect_bounds_change: Option<Bounds<Pixels>>,
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
@@ -1253,6 +1261,11 @@ impl Editor {
                         }
                     },
                 ));
+                project_subscriptions.push(cx.observe_in(
+                    project.read(cx).bookmark_store(),
+                    window,
+                    |_, _, _, cx| cx.notify(),
+                ));
                 if let Some(task_inventory) = project
                     .read(cx)
                     .task_store()
@@ -1386,6 +1399,7 @@ impl Editor {
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
             last_bounds: None,
+            minimap_line_cache: MinimapLineCache::default(),
             expect_bounds_change: None,
             gutter_dimensions: GutterDimensions::default(),
             style: None,
@@ -1806,6 +1820,51 @@ impl Editor {
         })
     }
 
+    /// Whether this editor shows a single buffer that was opened in large-file mode.
+    pub fn is_large_file(&self, cx: &App) -> bool {
+        self.buffer
+            .read(cx)
+            .as_singleton()
+            .map_or(false, |buffer| buffer.read(cx).is_large_file())
+    }
+
+    pub fn enable_large_file_features(
+        &mut self,
+        _: &EnableLargeFileFeatures,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for buffer in self.buffer.read(cx).all_buffers() {
+            buffer.update(cx, |buffer, cx| buffer.set_large_file(false, cx));
+        }
+    }
+
+    /// Starts the language features that were skipped while a buffer was in large-file mode.
+    fn large_file_changed(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+            return;
+        };
+        if buffer.read(cx).is_large_file() {
+            return;
+        }
+
+        if self.registered_buffers.remove(&buffer_id).is_some() {
+            if let Some(lsp_store) = self.lsp_store(cx) {
+                let handle = lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store.register_buffer_with_language_servers(&buffer, cx)
+                });
+                self.registered_buffers.insert(buffer_id, handle);
+            }
+        }
+        if self.buffer.read(cx).change_set_for(buffer_id).is_none() {
+            if let Some(project) = &self.project {
+                get_unstaged_changes_for_buffers(project, [buffer], self.buffer.clone(), cx);
+            }
+        }
+        self.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+        cx.notify();
+    }
+
     pub fn range_for_match<T: std::marker::Copy>(&self, range: &Range<T>) -> Range<T> {
         if self.collapse_matches {
             return range.start..range.start;
@@ -3706,6 +3765,10 @@ impl Editor {
                 if worktree_entry.is_ignored {
                     return None;
                 }
+                let buffer_handle = multi_buffer.buffer(buffer.remote_id())?;
+                if buffer_handle.read(cx).is_large_file() {
+                    return None;
+                }
 
                 let language = buffer.language()?;
                 if let Some(restrict_to_languages) = restrict_to_languages {
@@ -3716,7 +3779,7 @@ impl Editor {
                 Some((
                     excerpt_id,
                     (
-                        multi_buffer.buffer(buffer.remote_id()).unwrap(),
+                        buffer_handle,
                         buffer.version().clone(),
                         excerpt_visible_range,
                     ),
@@ -5411,6 +5474,36 @@ impl Editor {
         None
     }
 
+    fn render_bookmark_indicator(&self, row: DisplayRow, cx: &mut Context<Self>) -> IconButton {
+        IconButton::new(
+            ("bookmark_indicator", row.0 as usize),
+            ui::IconName::Bookmark,
+        )
+        .shape(ui::IconButtonShape::Square)
+        .icon_size(IconSize::XSmall)
+        .icon_color(Color::Accent)
+        .tooltip({
+            let focus_handle = self.focus_handle.clone();
+            move |window, cx| {
+                Tooltip::for_action_in(
+                    "Remove Bookmark",
+                    &ToggleBookmark,
+                    &focus_handle,
+                    window,
+                    cx,
+                )
+            }
+        })
+        .on_click(cx.listener(move |editor, _e, window, cx| {
+            window.focus(&editor.focus_handle(cx));
+            let position = DisplayPoint::new(row, 0);
+            editor.change_selections(None, window, cx, |s| {
+                s.select_display_ranges([position..position]);
+            });
+            editor.toggle_bookmark(&ToggleBookmark, window, cx);
+        }))
+    }
+
     fn render_run_indicator(
         &self,
         _style: &EditorStyle,
@@ -10003,6 +10096,123 @@ impl Editor {
         hunk
     }
 
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let selections = self.selections.all::<Point>(cx);
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut lines = Vec::<(Entity<Buffer>, u32)>::new();
+        for selection in selections {
+            let Some((buffer_snapshot, range)) =
+                snapshot.buffer_line_for_row(MultiBufferRow(selection.head().row))
+            else {
+                continue;
+            };
+            let Some(buffer) = multi_buffer.buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let row = range.start.row;
+            if !lines
+                .iter()
+                .any(|(existing, existing_row)| *existing == buffer && *existing_row == row)
+            {
+                lines.push((buffer, row));
+            }
+        }
+
+        project
+            .read(cx)
+            .bookmark_store()
+            .clone()
+            .update(cx, |store, cx| {
+                for (buffer, row) in &lines {
+                    store.toggle_bookmark(buffer, *row, cx);
+                }
+            });
+    }
+
+    /// Returns the sorted rows of this editor's multibuffer that contain a bookmark.
+    pub(crate) fn bookmarked_rows(&self, cx: &App) -> Vec<MultiBufferRow> {
+        let Some(project) = self.project.as_ref() else {
+            return Vec::new();
+        };
+        let bookmark_store = project.read(cx).bookmark_store().read(cx);
+        if bookmark_store.is_empty() {
+            return Vec::new();
+        }
+
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut rows = Vec::new();
+        for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+            let Some(buffer) = multi_buffer.buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let start_row = excerpt_range.context.start.to_point(buffer_snapshot).row;
+            let end_row = excerpt_range.context.end.to_point(buffer_snapshot).row;
+            for row in bookmark_store.bookmarked_rows(&buffer, cx) {
+                if row < start_row || row > end_row {
+                    continue;
+                }
+                let anchor = buffer_snapshot.anchor_after(Point::new(row, 0));
+                if let Some(anchor) = snapshot.anchor_in_excerpt(excerpt_id, anchor) {
+                    rows.push(MultiBufferRow(anchor.to_point(&snapshot).row));
+                }
+            }
+        }
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+
+    fn go_to_next_bookmark(
+        &mut self,
+        _: &GoToNextBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark_impl(Direction::Next, window, cx)
+    }
+
+    fn go_to_prev_bookmark(
+        &mut self,
+        _: &GoToPrevBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark_impl(Direction::Prev, window, cx)
+    }
+
+    fn go_to_bookmark_impl(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let rows = self.bookmarked_rows(cx);
+        let current_row = MultiBufferRow(self.selections.newest::<Point>(cx).head().row);
+        let row = match direction {
+            Direction::Next => rows
+                .iter()
+                .find(|row| **row > current_row)
+                .or_else(|| rows.first()),
+            Direction::Prev => rows
+                .iter()
+                .rev()
+                .find(|row| **row < current_row)
+                .or_else(|| rows.last()),
+        };
+        if let Some(row) = row {
+            let destination = Point::new(row.0, 0);
+            self.unfold_ranges(&[destination..destination], false, false, cx);
+            self.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.select_ranges(vec![destination..destination]);
+            });
+        }
+    }
+
     pub fn go_to_definition(
         &mut self,
         _: &GoToDefinition,
@@ -13235,7 +13445,26 @@ impl Editor {
         cx: &mut Context<Self>,
     ) {
         self.display_map.update(cx, |map, _| {
-            map.highlight_text(TypeId::of::<T>(), ranges, style)
+            map.highlight_text(HighlightKey::Type(TypeId::of::<T>()), ranges, style)
+        });
+        cx.notify();
+    }
+
+    /// Like [`Editor::highlight_text`], but allows several sets of highlights with different
+    /// styles for the same type, distinguished by `key`.
+    pub fn highlight_text_key<T: 'static>(
+        &mut self,
+        key: usize,
+        ranges: Vec<Range<Anchor>>,
+        style: HighlightStyle,
+        cx: &mut Context<Self>,
+    ) {
+        self.display_map.update(cx, |map, _| {
+            map.highlight_text(
+                HighlightKey::TypePlus(TypeId::of::<T>(), key),
+                ranges,
+                style,
+            )
         });
         cx.notify();
     }
@@ -13401,10 +13630,12 @@ impl Editor {
             }
             multi_buffer::Event::ExcerptsExpanded { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                refresh_rainbow_bracket_highlights(self, cx);
                 cx.emit(EditorEvent::ExcerptsExpanded { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
                 self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+                refresh_rainbow_bracket_highlights(self, cx);
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
             }
@@ -13413,6 +13644,7 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, window, cx);
+                refresh_rainbow_bracket_highlights(self, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
@@ -13427,6 +13659,9 @@ impl Editor {
             //     cx.notify();
             // }
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
+            multi_buffer::Event::LargeFileChanged(buffer_id) => {
+                self.large_file_changed(*buffer_id, cx);
+            }
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
                 self.scrollbar_marker_state.dirty = true;
@@ -13470,6 +13705,8 @@ impl Editor {
             cx.emit(EditorEvent::CursorShapeChanged);
         }
 
+        refresh_rainbow_bracket_highlights(self, cx);
+
         let project_settings = ProjectSettings::get_global(cx);
         self.serialize_dirty_buffers = project_settings.session.restore_unsaved_buffers;
 
@@ -13550,6 +13787,153 @@ impl Editor {
         });
     }
 
+    /// Compares the contents of the clipboard with the active buffer.
+    fn compare_with_clipboard(
+        &mut self,
+        _: &CompareWithClipboard,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(workspace), Some(buffer)) =
+            (self.workspace(), self.buffer.read(cx).as_singleton())
+        else {
+            cx.propagate();
+            return;
+        };
+        let Some(clipboard_text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+
+        let clipboard_buffer = CompareEditor::scratch_buffer(clipboard_text, &buffer, cx);
+        let title = CompareEditor::buffer_title(&buffer, cx);
+        window.defer(cx, move |window, cx| {
+            workspace.update(cx, |workspace, cx| {
+                CompareEditor::open(
+                    clipboard_buffer,
+                    "Clipboard",
+                    buffer,
+                    title,
+                    workspace,
+                    window,
+                    cx,
+                );
+            });
+        });
+    }
+
+    /// Compares the text of the first two selections with each other.
+    fn compare_selections(
+        &mut self,
+        _: &CompareSelections,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(workspace), Some(buffer)) =
+            (self.workspace(), self.buffer.read(cx).as_singleton())
+        else {
+            cx.propagate();
+            return;
+        };
+        let selections = self.selections.all::<Point>(cx);
+        let [left, right] = selections.as_slice() else {
+            return;
+        };
+        if left.is_empty() || right.is_empty() {
+            return;
+        }
+
+        let snapshot = buffer.read(cx).snapshot();
+        let [left_buffer, right_buffer] = [left, right].map(|selection| {
+            let text = snapshot
+                .text_for_range(selection.range())
+                .collect::<String>();
+            CompareEditor::scratch_buffer(text, &buffer, cx)
+        });
+        let [left_title, right_title] = [left, right].map(|selection| {
+            format!(
+                "Lines {}-{}",
+                selection.start.row + 1,
+                selection.end.row + 1
+            )
+        });
+        window.defer(cx, move |window, cx| {
+            workspace.update(cx, |workspace, cx| {
+                CompareEditor::open(
+                    left_buffer,
+                    left_title,
+                    right_buffer,
+                    right_title,
+                    workspace,
+                    window,
+                    cx,
+                );
+            });
+        });
+    }
+
+    /// Prompts for a file and compares it with the active buffer.
+    fn compare_with_file(
+        &mut self,
+        _: &CompareWithFile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(workspace), Some(buffer)) =
+            (self.workspace(), self.buffer.read(cx).as_singleton())
+        else {
+            cx.propagate();
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        // Guests can't open files outside of the host's worktrees.
+        if project.read(cx).is_via_collab() {
+            return;
+        }
+        let paths = workspace.update(cx, |workspace, cx| {
+            workspace.prompt_for_open_path(
+                PathPromptOptions {
+                    files: true,
+                    directories: false,
+                    multiple: false,
+                },
+                DirectoryLister::Project(project.clone()),
+                window,
+                cx,
+            )
+        });
+
+        cx.spawn_in(window, |_, mut cx| async move {
+            let Some(abs_path) = paths.await?.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let (worktree, relative_path) = project
+                .update(&mut cx, |project, cx| {
+                    project.find_or_create_worktree(&abs_path, false, cx)
+                })?
+                .await?;
+            let other_buffer = project
+                .update(&mut cx, |project, cx| {
+                    project.open_buffer((worktree.read(cx).id(), relative_path), cx)
+                })?
+                .await?;
+
+            workspace.update_in(&mut cx, |workspace, window, cx| {
+                let left_title = CompareEditor::buffer_title(&other_buffer, cx);
+                let right_title = CompareEditor::buffer_title(&buffer, cx);
+                CompareEditor::open(
+                    other_buffer,
+                    left_title,
+                    buffer,
+                    right_title,
+                    workspace,
+                    window,
+                    cx,
+                );
+            })
+        })
+        .detach_and_prompt_err("Failed to compare files", window, cx, |_, _, _| None);
+    }
+
     pub fn open_excerpts_in_split(
         &mut self,
         _: &OpenExcerptsSplit,
@@ -14145,7 +14529,9 @@ fn get_unstaged_changes_for_buffers(
     let mut tasks = Vec::new();
     project.update(cx, |project, cx| {
         for buffer in buffers {
-            tasks.push(project.open_unstaged_changes(buffer.clone(), cx))
+            if !buffer.read(cx).is_large_file() {
+                tasks.push(project.open_unstaged_changes(buffer.clone(), cx))
+            }
         }
     });
     cx.spawn(|mut cx| async move {
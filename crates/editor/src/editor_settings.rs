@@ -14,6 +14,9 @@ pub struct EditorSettings {
     pub hover_popover_delay: u64,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
+    pub minimap: Minimap,
+    pub sticky_scroll: StickyScroll,
+    pub rainbow_brackets: bool,
     pub gutter: Gutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
@@ -110,6 +113,21 @@ pub struct Scrollbar {
     pub axes: ScrollbarAxes,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Minimap {
+    pub show: ShowMinimap,
+    pub side: MinimapSide,
+    pub width: f32,
+    pub show_slider: ShowMinimapSlider,
+    pub render: MinimapRender,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScroll {
+    pub enabled: bool,
+    pub max_depth: usize,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Gutter {
     pub line_numbers: bool,
@@ -135,6 +153,56 @@ pub enum ShowScrollbar {
     Never,
 }
 
+/// When to show the minimap in the editor.
+///
+/// Default: never
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowMinimap {
+    /// Always show the minimap.
+    Always,
+    /// Show the minimap over the text while the mouse is over the editor.
+    Hover,
+    /// Never show the minimap.
+    Never,
+}
+
+/// Which side of the editor the minimap is shown on.
+///
+/// Default: right
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimapSide {
+    /// Show the minimap between the gutter and the text.
+    Left,
+    /// Show the minimap at the right edge of the editor.
+    Right,
+}
+
+/// When to show the slider marking the visible part of the file in the minimap.
+///
+/// Default: hover
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowMinimapSlider {
+    /// Show the slider only while the mouse is over the minimap.
+    Hover,
+    /// Always show the slider.
+    Always,
+}
+
+/// How lines of text are drawn in the minimap.
+///
+/// Default: blocks
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimapRender {
+    /// Draw each word as a colored block.
+    Blocks,
+    /// Draw the actual characters at a reduced size.
+    Characters,
+}
+
 /// Forcefully enable or disable the scrollbar for each axis
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -290,6 +358,14 @@ pub struct EditorSettingsContent {
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
     pub scrollbar: Option<ScrollbarContent>,
+    /// Minimap related settings
+    pub minimap: Option<MinimapContent>,
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
+    /// Whether to color brackets by their nesting depth, using the theme's accent colors.
+    ///
+    /// Default: false
+    pub rainbow_brackets: Option<bool>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
     /// Whether the editor will scroll beyond the last line.
@@ -441,6 +517,44 @@ pub struct ScrollbarAxesContent {
     vertical: Option<bool>,
 }
 
+/// Minimap related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct MinimapContent {
+    /// When to show the minimap in the editor.
+    ///
+    /// Default: never
+    pub show: Option<ShowMinimap>,
+    /// Which side of the editor to show the minimap on.
+    ///
+    /// Default: right
+    pub side: Option<MinimapSide>,
+    /// The width of the minimap, in pixels.
+    ///
+    /// Default: 100
+    pub width: Option<f32>,
+    /// When to show the slider marking the visible part of the file.
+    ///
+    /// Default: hover
+    pub show_slider: Option<ShowMinimapSlider>,
+    /// Whether to draw the characters of each line or colored blocks for each word.
+    ///
+    /// Default: blocks
+    pub render: Option<MinimapRender>,
+}
+
+/// Sticky scroll related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to pin the scopes enclosing the first visible line to the top of the editor.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The maximum number of nested scopes to pin.
+    ///
+    /// Default: 5
+    pub max_depth: Option<usize>,
+}
+
 /// Gutter related settings
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct GutterContent {
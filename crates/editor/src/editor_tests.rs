@@ -10685,6 +10685,82 @@ async fn test_diagnostics_with_links(cx: &mut TestAppContext) {
     cx.update_editor(|editor, _, _| assert!(editor.hover_state.diagnostic_popover.is_some()))
 }
 
+#[gpui::test]
+async fn test_bookmarks(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({ "file.txt": "one\ntwo\nthree\nfour\nfive\n" }),
+    )
+    .await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/file.txt", cx)
+        })
+        .await
+        .unwrap();
+    let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), multi_buffer, window, cx)
+    });
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 1)..Point::new(1, 1),
+                Point::new(3, 0)..Point::new(3, 2),
+            ])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, window, cx);
+        assert_eq!(
+            editor.bookmarked_rows(cx),
+            [MultiBufferRow(1), MultiBufferRow(3)]
+        );
+
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.go_to_next_bookmark(&GoToNextBookmark, window, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(1, 0)
+        );
+        editor.go_to_next_bookmark(&GoToNextBookmark, window, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(3, 0)
+        );
+        editor.go_to_next_bookmark(&GoToNextBookmark, window, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(1, 0)
+        );
+        editor.go_to_prev_bookmark(&GoToPrevBookmark, window, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(3, 0)
+        );
+
+        // Bookmarks move with their lines, and toggling a bookmarked line removes it.
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("zero\n", window, cx);
+        assert_eq!(
+            editor.bookmarked_rows(cx),
+            [MultiBufferRow(2), MultiBufferRow(4)]
+        );
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(2, 0)..Point::new(2, 0)])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, window, cx);
+        assert_eq!(editor.bookmarked_rows(cx), [MultiBufferRow(4)]);
+    });
+}
+
 #[gpui::test]
 async fn test_go_to_hunk(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -5,8 +5,9 @@ use crate::{
         Block, BlockContext, BlockStyle, DisplaySnapshot, HighlightedChunk, ToDisplayPoint,
     },
     editor_settings::{
-        CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ScrollBeyondLastLine,
-        ScrollbarDiagnostics, ShowScrollbar,
+        CurrentLineHighlight, DoubleClickInMultibuffer, Minimap, MinimapRender, MinimapSide,
+        MultiCursorModifier, ScrollBeyondLastLine, ScrollbarDiagnostics, ShowMinimap,
+        ShowMinimapSlider, ShowScrollbar,
     },
     git::blame::{CommitDetails, GitBlame},
     hover_popover::{
@@ -14,7 +15,7 @@ use crate::{
     },
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition, MouseContextMenu},
-    scroll::{axis_pair, scroll_amount::ScrollAmount, AxisPair},
+    scroll::{axis_pair, scroll_amount::ScrollAmount, Autoscroll, AxisPair},
     BlockId, ChunkReplacement, CursorShape, CustomBlockId, DisplayPoint, DisplayRow,
     DocumentHighlightRead, DocumentHighlightWrite, EditDisplayMode, Editor, EditorMode,
     EditorSettings, EditorSnapshot, EditorStyle, ExpandExcerpts, FocusedBlock, GoToHunk,
@@ -32,11 +33,11 @@ use gpui::{
     anchored, deferred, div, fill, linear_color_stop, linear_gradient, outline, point, px, quad,
     relative, size, svg, transparent_black, Action, AnyElement, App, AvailableSpace, Axis, Bounds,
     ClickEvent, ClipboardItem, ContentMask, Context, Corner, Corners, CursorStyle, DispatchPhase,
-    Edges, Element, ElementInputHandler, Entity, Focusable as _, FontId, GlobalElementId, Hitbox,
-    Hsla, InteractiveElement, IntoElement, Length, ModifiersChangedEvent, MouseButton,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta,
-    ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement, Style, Styled,
-    Subscription, TextRun, TextStyleRefinement, WeakEntity, Window,
+    Edges, Element, ElementInputHandler, Entity, Focusable as _, Font, FontId, GlobalElementId,
+    HighlightStyle, Hitbox, Hsla, InteractiveElement, IntoElement, Length, ModifiersChangedEvent,
+    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels,
+    ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement,
+    Style, Styled, StyledText, Subscription, TextRun, TextStyleRefinement, WeakEntity, Window,
 };
 use itertools::Itertools;
 use language::{
@@ -76,6 +77,8 @@ use util::{RangeExt, ResultExt};
 use workspace::{item::Item, notifications::NotifyTaskExt, Workspace};
 
 const INLINE_BLAME_PADDING_EM_WIDTHS: f32 = 7.;
+/// The size of the minimap's lines and characters relative to the editor's.
+const MINIMAP_SCALE: f32 = 0.15;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum DisplayDiffHunk {
@@ -395,6 +398,16 @@ impl EditorElement {
         register_action(editor, window, Editor::open_excerpts);
         register_action(editor, window, Editor::open_excerpts_in_split);
         register_action(editor, window, Editor::open_proposed_changes_editor);
+        register_action(editor, window, Editor::compare_selections);
+        register_action(editor, window, Editor::compare_with_clipboard);
+        if editor
+            .read(cx)
+            .project
+            .as_ref()
+            .map_or(false, |project| !project.read(cx).is_via_collab())
+        {
+            register_action(editor, window, Editor::compare_with_file);
+        }
         register_action(editor, window, Editor::toggle_soft_wrap);
         register_action(editor, window, Editor::toggle_tab_bar);
         register_action(editor, window, Editor::toggle_line_numbers);
@@ -412,6 +425,10 @@ impl EditorElement {
         register_action(editor, window, Editor::copy_file_location);
         register_action(editor, window, Editor::toggle_git_blame);
         register_action(editor, window, Editor::toggle_git_blame_inline);
+        register_action(editor, window, Editor::enable_large_file_features);
+        register_action(editor, window, Editor::toggle_bookmark);
+        register_action(editor, window, Editor::go_to_next_bookmark);
+        register_action(editor, window, Editor::go_to_prev_bookmark);
         register_action(editor, window, Editor::toggle_selected_diff_hunks);
         register_action(editor, window, Editor::expand_all_diff_hunks);
         register_action(editor, window, |editor, action, window, cx| {
@@ -1489,6 +1506,152 @@ impl EditorElement {
         axis_pair(horizontal_scrollbar, vertical_scrollbar)
     }
 
+    /// Lays out the minimap for the rows around the visible range.
+    ///
+    /// Only the rows that fit in the minimap are read from the display snapshot, using the same
+    /// highlighted chunks as the main text, so the cost doesn't depend on the size of the file.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_minimap(
+        &self,
+        bounds: Bounds<Pixels>,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+        height_in_lines: f32,
+        max_scroll_top: f32,
+        font_size: Pixels,
+        line_height: Pixels,
+        em_advance: Pixels,
+        settings: Minimap,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> MinimapLayout {
+        let minimap_line_height = line_height * MINIMAP_SCALE;
+        let column_width = em_advance * MINIMAP_SCALE;
+        let minimap_row_count = bounds.size.height / minimap_line_height;
+        let total_rows = snapshot.max_point().row().next_row().as_f32();
+
+        let viewport = MinimapViewport::new(
+            total_rows,
+            minimap_row_count,
+            scroll_position.y,
+            max_scroll_top,
+        );
+        let scroll_top = viewport.scroll_top;
+        let slider_bounds = Bounds {
+            origin: point(
+                bounds.left(),
+                bounds.top() + viewport.slider_top * minimap_line_height,
+            ),
+            size: size(bounds.size.width, height_in_lines * minimap_line_height),
+        };
+
+        let start_row = DisplayRow(scroll_top.floor() as u32);
+        let end_row = DisplayRow(cmp::min(
+            (scroll_top + minimap_row_count).ceil() as u32,
+            snapshot.max_point().row().next_row().0,
+        ));
+        let max_columns = (bounds.size.width / column_width).ceil() as usize;
+        let lines = minimap_lines(
+            snapshot.highlighted_chunks(start_row..end_row, true, &self.style),
+            self.style.text.color,
+            max_columns,
+        );
+
+        let first_line_y = bounds.top() + (start_row.as_f32() - scroll_top) * minimap_line_height;
+        let content = match settings.render {
+            MinimapRender::Blocks => {
+                let block_height = minimap_line_height * 0.75;
+                let mut blocks = Vec::new();
+                for (ix, (text, runs)) in lines.iter().enumerate() {
+                    let y = first_line_y + minimap_line_height * ix as f32;
+                    let mut offset = 0;
+                    let mut column = 0;
+                    for (len, color) in runs {
+                        let mut block_start = None;
+                        for ch in text[offset..offset + len].chars() {
+                            if ch.is_whitespace() {
+                                if let Some(start) = block_start.take() {
+                                    blocks.push(minimap_block(
+                                        bounds,
+                                        y,
+                                        start..column,
+                                        column_width,
+                                        block_height,
+                                        *color,
+                                    ));
+                                }
+                            } else if block_start.is_none() {
+                                block_start = Some(column);
+                            }
+                            column += 1;
+                        }
+                        if let Some(start) = block_start {
+                            blocks.push(minimap_block(
+                                bounds,
+                                y,
+                                start..column,
+                                column_width,
+                                block_height,
+                                *color,
+                            ));
+                        }
+                        offset += len;
+                    }
+                }
+                MinimapContent::Blocks(blocks)
+            }
+            MinimapRender::Characters => {
+                let font = self.style.text.font();
+                let font_size = font_size * MINIMAP_SCALE;
+                let buffer_version = (
+                    snapshot.buffer_snapshot.edit_count(),
+                    snapshot.buffer_snapshot.non_text_state_update_count(),
+                );
+                let mut cache = self
+                    .editor
+                    .update(cx, |editor, _| mem::take(&mut editor.minimap_line_cache));
+                cache.invalidate(buffer_version, &font, font_size);
+                let shaped_lines = lines
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(ix, (text, runs))| {
+                        let row = DisplayRow(start_row.0 + ix as u32);
+                        let line = cache.shape_line(row, text, runs, window)?;
+                        let origin = point(
+                            bounds.left(),
+                            first_line_y + minimap_line_height * ix as f32,
+                        );
+                        Some((origin, line))
+                    })
+                    .collect();
+                cache.retain_rows(start_row..end_row);
+                self.editor.update(cx, |editor, _| {
+                    editor.minimap_line_cache = cache;
+                });
+                MinimapContent::Characters(shaped_lines)
+            }
+        };
+
+        if cx.has_active_drag() {
+            self.editor.update(cx, |editor, cx| {
+                editor.scroll_manager.set_is_dragging_minimap(false, cx);
+            });
+        }
+
+        MinimapLayout {
+            hitbox: window.insert_hitbox(bounds, false),
+            side: settings.side,
+            show_slider: settings.show_slider,
+            line_height: minimap_line_height,
+            scroll_top,
+            visible_row_count: height_in_lines,
+            max_scroll_top,
+            slider_row_travel: minimap_line_height * viewport.slider_row_travel,
+            slider_bounds,
+            content,
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn prepaint_crease_toggles(
         &self,
@@ -2007,6 +2170,70 @@ impl EditorElement {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_bookmark_indicators(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        bookmarked_rows: &[MultiBufferRow],
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        rows_with_hunk_bounds: &HashMap<DisplayRow, Bounds<Pixels>>,
+        snapshot: &EditorSnapshot,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            bookmarked_rows
+                .iter()
+                .filter_map(|multibuffer_row| {
+                    // Run indicators take precedence over bookmarks on the same line.
+                    let has_run_indicator = snapshot
+                        .buffer_snapshot
+                        .buffer_line_for_row(*multibuffer_row)
+                        .map_or(false, |(buffer_snapshot, range)| {
+                            editor
+                                .tasks
+                                .contains_key(&(buffer_snapshot.remote_id(), range.start.row))
+                        });
+                    if has_run_indicator {
+                        return None;
+                    }
+                    if snapshot.is_line_folded(*multibuffer_row)
+                        && multibuffer_row
+                            .0
+                            .checked_sub(1)
+                            .map_or(false, |previous_row| {
+                                snapshot.is_line_folded(MultiBufferRow(previous_row))
+                            })
+                    {
+                        return None;
+                    }
+
+                    let display_row = Point::new(multibuffer_row.0, 0)
+                        .to_display_point(snapshot)
+                        .row();
+                    if !range.contains(&display_row) {
+                        return None;
+                    }
+                    let button = editor.render_bookmark_indicator(display_row, cx);
+                    Some(prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        rows_with_hunk_bounds,
+                        window,
+                        cx,
+                    ))
+                })
+                .collect_vec()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_code_actions_indicator(
         &self,
@@ -3161,6 +3388,101 @@ impl EditorElement {
         header
     }
 
+    /// Lays out the lines that start the outline scopes (functions, classes, headings, etc.)
+    /// enclosing the first visible line, pinned to the top of the editor.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_sticky_scopes(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_top: f32,
+        line_height: Pixels,
+        text_hitbox: &Hitbox,
+        content_origin: gpui::Point<Pixels>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        let settings = EditorSettings::get_global(cx).sticky_scroll;
+        if !settings.enabled || snapshot.mode != EditorMode::Full {
+            return Vec::new();
+        }
+        let Some((_, _, buffer)) = snapshot.buffer_snapshot.as_singleton() else {
+            return Vec::new();
+        };
+
+        let first_visible_point = DisplayPoint::new(DisplayRow(scroll_top as u32), 0)
+            .to_point(&snapshot.display_snapshot);
+        let scopes = buffer
+            .sticky_scopes_containing(first_visible_point)
+            .into_iter()
+            .map(|range| {
+                let start_row = range.start.to_display_point(snapshot).row();
+                let end_row = range.end.to_display_point(snapshot).row();
+                (range.start, start_row, end_row)
+            })
+            .collect::<Vec<_>>();
+        let pinned_scopes = pinned_sticky_scopes(
+            scopes
+                .iter()
+                .map(|(_, start_row, end_row)| *start_row..*end_row),
+            scroll_top,
+            settings.max_depth,
+        )
+        .into_iter()
+        .zip(&scopes)
+        .map(|(offset, (scope_start, start_row, _))| {
+            (*scope_start, *start_row, line_height * offset)
+        })
+        .collect::<Vec<_>>();
+
+        let editor_bg_color = cx.theme().colors().editor_background;
+        let border_color = cx.theme().colors().border_variant;
+        let text_padding = content_origin.x - text_hitbox.left();
+        let pinned_scope_count = pinned_scopes.len();
+
+        pinned_scopes
+            .into_iter()
+            .enumerate()
+            .map(|(ix, (scope_start, start_row, y))| {
+                let (text, highlights) = sticky_scope_line(snapshot, start_row, &self.style);
+                let editor = self.editor.clone();
+                let mut element = div()
+                    .id(("sticky-scope", ix))
+                    .w(text_hitbox.size.width)
+                    .h(line_height)
+                    .pl(text_padding)
+                    .bg(editor_bg_color)
+                    .when(ix + 1 == pinned_scope_count, |this| {
+                        this.border_b_1().border_color(border_color)
+                    })
+                    .cursor_pointer()
+                    .child(StyledText::new(text).with_highlights(&self.style.text, highlights))
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .on_click(move |_, window, cx| {
+                        editor.update(cx, |editor, cx| {
+                            editor.change_selections(
+                                Some(Autoscroll::top_relative(ix)),
+                                window,
+                                cx,
+                                |selections| selections.select_ranges([scope_start..scope_start]),
+                            );
+                        });
+                    })
+                    .into_any_element();
+
+                element.prepaint_as_root(
+                    point(text_hitbox.left(), text_hitbox.top() + y),
+                    size(
+                        AvailableSpace::Definite(text_hitbox.size.width),
+                        AvailableSpace::Definite(line_height),
+                    ),
+                    window,
+                    cx,
+                );
+                element
+            })
+            .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_context_menu(
         &self,
@@ -4398,6 +4720,10 @@ impl EditorElement {
                 test_indicator.paint(window, cx);
             }
 
+            for bookmark_indicator in layout.bookmark_indicators.iter_mut() {
+                bookmark_indicator.paint(window, cx);
+            }
+
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(window, cx);
             }
@@ -4918,6 +5244,148 @@ impl EditorElement {
         }
     }
 
+    fn paint_minimap(&mut self, layout: &EditorLayout, window: &mut Window, cx: &mut App) {
+        if layout.mode == EditorMode::Full
+            && EditorSettings::get_global(cx).minimap.show == ShowMinimap::Hover
+        {
+            // Show or hide the minimap when the mouse enters or leaves the editor.
+            let hitbox = layout.hitbox.clone();
+            let was_hovered = hitbox.is_hovered(window);
+            let editor = self.editor.clone();
+            window.on_mouse_event(move |_: &MouseMoveEvent, phase, window, cx| {
+                if phase == DispatchPhase::Bubble && hitbox.is_hovered(window) != was_hovered {
+                    editor.update(cx, |_, cx| cx.notify());
+                }
+            });
+        }
+
+        let Some(minimap) = layout.minimap.as_ref() else {
+            return;
+        };
+        let hitbox = minimap.hitbox.clone();
+        let is_dragging = self.editor.read(cx).scroll_manager.is_dragging_minimap();
+
+        window.paint_layer(hitbox.bounds, |window| {
+            let border_widths = match minimap.side {
+                MinimapSide::Left => Edges {
+                    right: ScrollbarLayout::BORDER_WIDTH,
+                    ..Default::default()
+                },
+                MinimapSide::Right => Edges {
+                    left: ScrollbarLayout::BORDER_WIDTH,
+                    ..Default::default()
+                },
+            };
+            window.paint_quad(quad(
+                hitbox.bounds,
+                Corners::default(),
+                cx.theme().colors().editor_background,
+                border_widths,
+                cx.theme().colors().scrollbar_track_border,
+            ));
+
+            match &minimap.content {
+                MinimapContent::Blocks(blocks) => {
+                    for block in blocks {
+                        window.paint_quad(block.clone());
+                    }
+                }
+                MinimapContent::Characters(lines) => {
+                    for (origin, line) in lines {
+                        line.paint(*origin, minimap.line_height, window, cx)
+                            .log_err();
+                    }
+                }
+            }
+
+            let show_slider = match minimap.show_slider {
+                ShowMinimapSlider::Always => true,
+                ShowMinimapSlider::Hover => is_dragging || hitbox.is_hovered(window),
+            };
+            if show_slider {
+                window.paint_quad(fill(
+                    minimap.slider_bounds,
+                    cx.theme().colors().scrollbar_thumb_background,
+                ));
+            }
+        });
+
+        window.set_cursor_style(CursorStyle::Arrow, &hitbox);
+
+        if is_dragging {
+            window.on_mouse_event({
+                let editor = self.editor.clone();
+                let slider_row_travel = minimap.slider_row_travel;
+                let max_scroll_top = minimap.max_scroll_top;
+                let mut mouse_position = window.mouse_position();
+                move |event: &MouseMoveEvent, phase, window, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        if event.pressed_button == Some(MouseButton::Left) {
+                            let mut position = editor.scroll_position(cx);
+                            position.y += (event.position.y - mouse_position.y) / slider_row_travel;
+                            position.y = position.y.clamp(0., max_scroll_top);
+                            editor.set_scroll_position(position, window, cx);
+                            cx.stop_propagation();
+                        } else {
+                            editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        }
+                    });
+                    mouse_position = event.position;
+                }
+            });
+
+            window.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, _, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            window.on_mouse_event({
+                let editor = self.editor.clone();
+                let slider_bounds = minimap.slider_bounds;
+                let minimap_scroll_top = minimap.scroll_top;
+                let line_height = minimap.line_height;
+                let visible_row_count = minimap.visible_row_count;
+                let max_scroll_top = minimap.max_scroll_top;
+                move |event: &MouseDownEvent, phase, window, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !hitbox.is_hovered(window)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(true, cx);
+
+                        // Clicking outside of the slider centers the viewport on the clicked row.
+                        if !slider_bounds.contains(&event.position) {
+                            let row = minimap_scroll_top
+                                + (event.position.y - hitbox.top()) / line_height;
+                            let mut position = editor.scroll_position(cx);
+                            position.y = (row - visible_row_count / 2.).clamp(0., max_scroll_top);
+                            editor.set_scroll_position(position, window, cx);
+                        }
+
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn collect_fast_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -6502,7 +6970,23 @@ impl Element for EditorElement {
                         self.max_line_number_width(&snapshot, window, cx),
                         cx,
                     );
-                    let text_width = bounds.size.width - gutter_dimensions.width;
+                    let minimap_settings = EditorSettings::get_global(cx).minimap;
+                    let minimap_width = if snapshot.mode == EditorMode::Full
+                        && minimap_settings.show != ShowMinimap::Never
+                    {
+                        px(minimap_settings.width.max(0.))
+                    } else {
+                        Pixels::ZERO
+                    };
+                    // A minimap that's only shown on hover is drawn over the text, so that the
+                    // text doesn't move when it appears.
+                    let reserved_minimap_width = if minimap_settings.show == ShowMinimap::Always {
+                        minimap_width
+                    } else {
+                        Pixels::ZERO
+                    };
+                    let text_width =
+                        bounds.size.width - gutter_dimensions.width - reserved_minimap_width;
 
                     let editor_width = text_width - gutter_dimensions.margin - em_width;
 
@@ -6543,9 +7027,23 @@ impl Element for EditorElement {
                     let hitbox = window.insert_hitbox(bounds, false);
                     let gutter_hitbox =
                         window.insert_hitbox(gutter_bounds(bounds, gutter_dimensions), false);
+                    let (text_origin, minimap_origin) = match minimap_settings.side {
+                        MinimapSide::Left => (
+                            gutter_hitbox.top_right() + point(reserved_minimap_width, Pixels::ZERO),
+                            gutter_hitbox.top_right(),
+                        ),
+                        MinimapSide::Right => (
+                            gutter_hitbox.top_right(),
+                            gutter_hitbox.top_right()
+                                + point(
+                                    text_width + reserved_minimap_width - minimap_width,
+                                    Pixels::ZERO,
+                                ),
+                        ),
+                    };
                     let text_hitbox = window.insert_hitbox(
                         Bounds {
-                            origin: gutter_hitbox.top_right(),
+                            origin: text_origin,
                             size: size(text_width, bounds.size.height),
                         },
                         false,
@@ -6556,7 +7054,7 @@ impl Element for EditorElement {
                         text_hitbox.origin + point(gutter_dimensions.margin, Pixels::ZERO);
 
                     let scrollbar_bounds =
-                        Bounds::from_corners(content_origin, bounds.bottom_right());
+                        Bounds::from_corners(content_origin, text_hitbox.bottom_right());
 
                     let height_in_lines = scrollbar_bounds.size.height / line_height;
 
@@ -6872,6 +7370,18 @@ impl Element for EditorElement {
                         })
                     });
 
+                    let sticky_scopes = window.with_element_namespace("sticky_scopes", |window| {
+                        self.layout_sticky_scopes(
+                            &snapshot,
+                            scroll_position.y,
+                            line_height,
+                            &text_hitbox,
+                            content_origin,
+                            window,
+                            cx,
+                        )
+                    });
+
                     let start_buffer_row =
                         MultiBufferRow(start_anchor.to_point(&snapshot.buffer_snapshot).row);
                     let end_buffer_row =
@@ -7052,6 +7562,33 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let show_minimap = match minimap_settings.show {
+                        ShowMinimap::Always => true,
+                        ShowMinimap::Hover => {
+                            hitbox.is_hovered(window)
+                                || self.editor.read(cx).scroll_manager.is_dragging_minimap()
+                        }
+                        ShowMinimap::Never => false,
+                    };
+                    let minimap = (show_minimap && minimap_width > Pixels::ZERO).then(|| {
+                        self.layout_minimap(
+                            Bounds {
+                                origin: minimap_origin,
+                                size: size(minimap_width, bounds.size.height),
+                            },
+                            &snapshot,
+                            scroll_position,
+                            height_in_lines,
+                            max_scroll_top,
+                            font_size,
+                            line_height,
+                            em_advance,
+                            minimap_settings,
+                            window,
+                            cx,
+                        )
+                    });
+
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
                     let rows_with_hunk_bounds = display_hunks
@@ -7075,6 +7612,7 @@ impl Element for EditorElement {
                                 rows_with_hunk_bounds
                             },
                         );
+                    let bookmarked_rows = self.editor.read(cx).bookmarked_rows(cx);
                     let mut code_actions_indicator = None;
                     if let Some(newest_selection_head) = newest_selection_head {
                         if (start_row..end_row).contains(&newest_selection_head.row()) {
@@ -7112,7 +7650,13 @@ impl Element for EditorElement {
                                             .tasks
                                             .contains_key(&(buffer_id, row));
 
-                                        if !has_test_indicator {
+                                        let has_bookmark_indicator = bookmarked_rows
+                                            .binary_search(&MultiBufferRow(
+                                                newest_selection_point.row,
+                                            ))
+                                            .is_ok();
+
+                                        if !has_test_indicator && !has_bookmark_indicator {
                                             code_actions_indicator = self
                                                 .layout_code_actions_indicator(
                                                     line_height,
@@ -7147,6 +7691,19 @@ impl Element for EditorElement {
                         Vec::new()
                     };
 
+                    let bookmark_indicators = self.layout_bookmark_indicators(
+                        line_height,
+                        start_row..end_row,
+                        &bookmarked_rows,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &rows_with_hunk_bounds,
+                        &snapshot,
+                        window,
+                        cx,
+                    );
+
                     self.layout_signature_help(
                         &hitbox,
                         content_origin,
@@ -7284,6 +7841,7 @@ impl Element for EditorElement {
                         display_hunks,
                         content_origin,
                         scrollbars_layout,
+                        minimap,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -7301,12 +7859,14 @@ impl Element for EditorElement {
                         diff_hunk_controls: hunk_controls,
                         mouse_context_menu,
                         test_indicators,
+                        bookmark_indicators,
                         code_actions_indicator,
                         crease_toggles,
                         crease_trailers,
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scopes,
                     }
                 })
             })
@@ -7373,6 +7933,13 @@ impl Element for EditorElement {
                         }
                     });
 
+                    window.with_element_namespace("sticky_scopes", |window| {
+                        for sticky_scope in &mut layout.sticky_scopes {
+                            sticky_scope.paint(window, cx);
+                        }
+                    });
+
+                    self.paint_minimap(layout, window, cx);
                     self.paint_scrollbars(layout, window, cx);
                     self.paint_inline_completion_popover(layout, window, cx);
                     self.paint_mouse_context_menu(layout, window, cx);
@@ -7455,6 +8022,7 @@ pub struct EditorLayout {
     gutter_hitbox: Hitbox,
     content_origin: gpui::Point<Pixels>,
     scrollbars_layout: AxisPair<Option<ScrollbarLayout>>,
+    minimap: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
@@ -7475,6 +8043,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
+    bookmark_indicators: Vec<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     diff_hunk_controls: Vec<AnyElement>,
     crease_trailers: Vec<Option<CreaseTrailerLayout>>,
@@ -7483,6 +8052,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scopes: Vec<AnyElement>,
 }
 
 impl EditorLayout {
@@ -7626,6 +8196,246 @@ impl ScrollbarLayout {
     }
 }
 
+/// Returns the vertical offset, in lines, of each enclosing scope that is pinned to the top of
+/// the editor, given the display rows the scopes span from the outermost to the innermost.
+fn pinned_sticky_scopes(
+    scopes: impl IntoIterator<Item = Range<DisplayRow>>,
+    scroll_top: f32,
+    max_depth: usize,
+) -> Vec<f32> {
+    let mut offsets = Vec::new();
+    for scope in scopes {
+        if offsets.len() >= max_depth {
+            break;
+        }
+
+        // A scope is pinned when its first line is hidden behind the scopes pinned above it,
+        // and it doesn't end before the line under it.
+        let slot = offsets.len() as f32;
+        if scope.start.as_f32() >= scroll_top + slot || scope.end.as_f32() <= scroll_top + slot {
+            break;
+        }
+
+        // Push the scope up as its last line scrolls under it.
+        offsets.push(slot.min(scope.end.as_f32() - scroll_top - 1.));
+    }
+    offsets
+}
+
+/// Returns the text of a display row, along with its syntax highlights.
+fn sticky_scope_line(
+    snapshot: &EditorSnapshot,
+    row: DisplayRow,
+    style: &EditorStyle,
+) -> (SharedString, Vec<(Range<usize>, HighlightStyle)>) {
+    let mut text = String::new();
+    let mut highlights = Vec::new();
+    for chunk in snapshot.highlighted_chunks(row..row.next_row(), true, style) {
+        let chunk_text = chunk.text.trim_end_matches('\n');
+        if let Some(highlight) = chunk.style {
+            highlights.push((text.len()..text.len() + chunk_text.len(), highlight));
+        }
+        text.push_str(chunk_text);
+    }
+    (text.into(), highlights)
+}
+
+struct MinimapLayout {
+    hitbox: Hitbox,
+    side: MinimapSide,
+    show_slider: ShowMinimapSlider,
+    /// The height of a single line in the minimap.
+    line_height: Pixels,
+    /// The display row at the top of the minimap, which may be fractional.
+    scroll_top: f32,
+    visible_row_count: f32,
+    max_scroll_top: f32,
+    /// How far the slider moves when the editor scrolls by one row.
+    slider_row_travel: Pixels,
+    slider_bounds: Bounds<Pixels>,
+    content: MinimapContent,
+}
+
+enum MinimapContent {
+    Blocks(Vec<PaintQuad>),
+    Characters(Vec<(gpui::Point<Pixels>, ShapedLine)>),
+}
+
+/// Which rows of the file the minimap shows, given how far the editor is scrolled.
+#[derive(Debug, PartialEq)]
+struct MinimapViewport {
+    /// The display row at the top of the minimap, which may be fractional.
+    scroll_top: f32,
+    /// The distance from the top of the minimap to the slider, in minimap lines.
+    slider_top: f32,
+    /// How many minimap lines the slider moves when the editor scrolls by one row.
+    slider_row_travel: f32,
+}
+
+impl MinimapViewport {
+    /// When the file doesn't fit in the minimap, the minimap scrolls proportionally to the editor,
+    /// so that both reach the end of the file at the same time.
+    fn new(
+        total_rows: f32,
+        minimap_row_count: f32,
+        editor_scroll_top: f32,
+        max_scroll_top: f32,
+    ) -> Self {
+        let scroll_ratio = if total_rows > minimap_row_count && max_scroll_top > 0. {
+            (total_rows - minimap_row_count) / max_scroll_top
+        } else {
+            0.
+        };
+        let scroll_top = editor_scroll_top * scroll_ratio;
+        Self {
+            scroll_top,
+            slider_top: editor_scroll_top - scroll_top,
+            // Since the minimap scrolls along with the editor, the slider moves by less than a
+            // minimap line when the editor scrolls by one row.
+            slider_row_travel: if scroll_ratio < 1. {
+                1. - scroll_ratio
+            } else {
+                1.
+            },
+        }
+    }
+}
+
+/// The minimap's shaped lines from previous frames. They're reused while the buffer, the font and
+/// a row's text and colors stay the same, so that only the rows that scroll into the minimap are
+/// shaped.
+#[derive(Default)]
+pub(crate) struct MinimapLineCache {
+    buffer_version: (usize, usize),
+    font: Option<Font>,
+    font_size: Pixels,
+    lines: HashMap<DisplayRow, CachedMinimapLine>,
+}
+
+struct CachedMinimapLine {
+    text: String,
+    runs: Vec<(usize, Hsla)>,
+    line: ShapedLine,
+}
+
+impl MinimapLineCache {
+    /// Forgets the shaped lines if the buffer or the font has changed since they were shaped.
+    fn invalidate(&mut self, buffer_version: (usize, usize), font: &Font, font_size: Pixels) {
+        if self.buffer_version != buffer_version
+            || self.font.as_ref() != Some(font)
+            || self.font_size != font_size
+        {
+            self.buffer_version = buffer_version;
+            self.font = Some(font.clone());
+            self.font_size = font_size;
+            self.lines.clear();
+        }
+    }
+
+    fn shape_line(
+        &mut self,
+        row: DisplayRow,
+        text: String,
+        runs: Vec<(usize, Hsla)>,
+        window: &mut Window,
+    ) -> Option<ShapedLine> {
+        if let Some(cached) = self.lines.get(&row) {
+            if cached.text == text && cached.runs == runs {
+                return Some(cached.line.clone());
+            }
+        }
+
+        let font = self.font.clone()?;
+        let text_runs = runs
+            .iter()
+            .map(|(len, color)| TextRun {
+                len: *len,
+                font: font.clone(),
+                color: *color,
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            })
+            .collect::<Vec<_>>();
+        let line = window
+            .text_system()
+            .shape_line(text.clone().into(), self.font_size, &text_runs)
+            .log_err()?;
+        self.lines.insert(
+            row,
+            CachedMinimapLine {
+                text,
+                runs,
+                line: line.clone(),
+            },
+        );
+        Some(line)
+    }
+
+    /// Forgets the lines that are no longer shown in the minimap.
+    fn retain_rows(&mut self, rows: Range<DisplayRow>) {
+        self.lines.retain(|row, _| rows.contains(row));
+    }
+}
+
+/// Splits highlighted chunks into the text and colored runs of each line, truncating lines to
+/// `max_columns` characters. Run lengths are in bytes.
+fn minimap_lines<'a>(
+    chunks: impl Iterator<Item = HighlightedChunk<'a>>,
+    default_color: Hsla,
+    max_columns: usize,
+) -> Vec<(String, Vec<(usize, Hsla)>)> {
+    let mut lines = vec![(String::new(), Vec::new())];
+    let mut column = 0;
+    for chunk in chunks {
+        let color = chunk
+            .style
+            .and_then(|style| style.color)
+            .unwrap_or(default_color);
+        for (ix, segment) in chunk.text.split('\n').enumerate() {
+            if ix > 0 {
+                lines.push((String::new(), Vec::new()));
+                column = 0;
+            }
+
+            let remaining_columns = max_columns.saturating_sub(column);
+            let len = segment
+                .char_indices()
+                .nth(remaining_columns)
+                .map_or(segment.len(), |(ix, _)| ix);
+            if len == 0 {
+                continue;
+            }
+
+            column += segment[..len].chars().count();
+            let (text, runs) = lines.last_mut().unwrap();
+            text.push_str(&segment[..len]);
+            runs.push((len, color));
+        }
+    }
+    lines
+}
+
+fn minimap_block(
+    minimap_bounds: Bounds<Pixels>,
+    y: Pixels,
+    columns: Range<usize>,
+    column_width: Pixels,
+    height: Pixels,
+    color: Hsla,
+) -> PaintQuad {
+    fill(
+        Bounds {
+            origin: point(
+                minimap_bounds.left() + column_width * columns.start as f32,
+                y,
+            ),
+            size: size(column_width * columns.len() as f32, height),
+        },
+        color.opacity(0.6),
+    )
+}
+
 struct CreaseTrailerLayout {
     element: AnyElement,
     bounds: Bounds<Pixels>,
@@ -8110,6 +8920,7 @@ mod tests {
     use gpui::{TestAppContext, VisualTestContext};
     use language::language_settings;
     use log::info;
+    use settings::SettingsStore;
     use similar::DiffableStr;
     use std::num::NonZeroU32;
     use util::test::sample_text;
@@ -8415,6 +9226,92 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_minimap_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let set_show_minimap = |show, cx: &mut App| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(crate::editor_settings::MinimapContent {
+                        show: Some(show),
+                        width: Some(100.),
+                        render: Some(MinimapRender::Characters),
+                        ..Default::default()
+                    });
+                });
+            });
+        };
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(20, 6, 'a'), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|_, cx| editor.read(cx).style().unwrap().clone());
+
+        // An always visible minimap takes its width from the text.
+        cx.update(|_, cx| set_show_minimap(ShowMinimap::Always, cx));
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style.clone()),
+        );
+        let minimap = state.minimap.as_ref().unwrap();
+        assert_eq!(minimap.hitbox.size.width, px(100.));
+        assert_eq!(minimap.hitbox.left(), state.text_hitbox.right());
+        assert_eq!(
+            state.text_hitbox.size.width,
+            px(400.) - state.gutter_hitbox.size.width
+        );
+        // The shaped lines are kept for the next frame.
+        cx.update(|_, cx| assert_eq!(editor.read(cx).minimap_line_cache.lines.len(), 20));
+
+        // A minimap that's shown on hover isn't shown while the mouse is elsewhere, and doesn't
+        // take any width from the text.
+        cx.update(|_, cx| set_show_minimap(ShowMinimap::Hover, cx));
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style.clone()),
+        );
+        assert!(state.minimap.is_none());
+        assert_eq!(
+            state.text_hitbox.size.width,
+            px(500.) - state.gutter_hitbox.size.width
+        );
+    }
+
+    #[test]
+    fn test_minimap_viewport() {
+        fn assert_close(actual: f32, expected: f32) {
+            assert!(
+                (actual - expected).abs() < 0.001,
+                "expected {expected}, got {actual}"
+            );
+        }
+
+        // A file that fits in the minimap doesn't scroll it, so the slider moves a whole minimap
+        // line for each row that the editor scrolls.
+        let viewport = MinimapViewport::new(50., 200., 10., 46.);
+        assert_close(viewport.scroll_top, 0.);
+        assert_close(viewport.slider_top, 10.);
+        assert_close(viewport.slider_row_travel, 1.);
+
+        // A longer file scrolls the minimap along with the editor, so that the slider reaches the
+        // bottom of the minimap when the editor reaches the end of the file.
+        let (total_rows, minimap_rows, visible_rows) = (1000., 200., 40.);
+        let max_scroll_top = total_rows - visible_rows;
+        let viewport = MinimapViewport::new(total_rows, minimap_rows, 0., max_scroll_top);
+        assert_close(viewport.scroll_top, 0.);
+        assert_close(viewport.slider_top, 0.);
+        let viewport =
+            MinimapViewport::new(total_rows, minimap_rows, max_scroll_top, max_scroll_top);
+        assert_close(viewport.scroll_top, total_rows - minimap_rows);
+        assert_close(viewport.slider_top + visible_rows, minimap_rows);
+        assert_close(viewport.slider_row_travel, 1. - 800. / 960.);
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
@@ -8587,6 +9484,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pinned_sticky_scopes() {
+        let scopes = [
+            DisplayRow(0)..DisplayRow(20),
+            DisplayRow(2)..DisplayRow(10),
+            DisplayRow(4)..DisplayRow(6),
+        ];
+
+        assert_eq!(
+            pinned_sticky_scopes(scopes.clone(), 0., 5),
+            Vec::<f32>::new(),
+            "Scopes whose first line is visible shouldn't be pinned"
+        );
+        assert_eq!(pinned_sticky_scopes(scopes.clone(), 3., 5), [0., 1., 2.]);
+        assert_eq!(
+            pinned_sticky_scopes(scopes.clone(), 3., 2),
+            [0., 1.],
+            "Only up to the maximum depth of scopes should be pinned"
+        );
+        assert_eq!(
+            pinned_sticky_scopes(scopes.clone(), 8.5, 5),
+            [0., 0.5],
+            "Scopes should be pushed up as their last line scrolls under them"
+        );
+        assert_eq!(pinned_sticky_scopes(scopes, 21., 5), Vec::<f32>::new());
+    }
+
     fn collect_invisibles_from_new_editor(
         cx: &mut TestAppContext,
         editor_mode: EditorMode,
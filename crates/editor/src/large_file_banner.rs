@@ -0,0 +1,119 @@
+use gpui::{Entity, EventEmitter, Subscription};
+use ui::prelude::*;
+use workspace::{item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+use crate::{actions::EnableLargeFileFeatures, Editor};
+
+/// A banner shown above editors whose file was opened in large-file mode, explaining which
+/// features were disabled and offering to enable them anyway.
+pub struct LargeFileBanner {
+    active_editor: Option<Entity<Editor>>,
+    location: ToolbarItemLocation,
+    _subscription: Option<Subscription>,
+}
+
+impl Default for LargeFileBanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LargeFileBanner {
+    pub fn new() -> Self {
+        Self {
+            active_editor: None,
+            location: ToolbarItemLocation::Hidden,
+            _subscription: None,
+        }
+    }
+
+    fn determine_toolbar_location(&self, cx: &App) -> ToolbarItemLocation {
+        match &self.active_editor {
+            Some(editor) if editor.read(cx).is_large_file(cx) => ToolbarItemLocation::Secondary,
+            _ => ToolbarItemLocation::Hidden,
+        }
+    }
+
+    fn update_location(&mut self, cx: &mut Context<Self>) {
+        let location = self.determine_toolbar_location(cx);
+        if location != self.location {
+            self.location = location;
+            cx.emit(ToolbarItemEvent::ChangeLocation(location));
+            cx.notify();
+        }
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for LargeFileBanner {}
+
+impl ToolbarItemView for LargeFileBanner {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ToolbarItemLocation {
+        self.active_editor = active_pane_item.and_then(|item| item.act_as::<Editor>(cx));
+        self._subscription = self
+            .active_editor
+            .as_ref()
+            .map(|editor| cx.observe(editor, |this, _, cx| this.update_location(cx)));
+        self.location = self.determine_toolbar_location(cx);
+        cx.notify();
+        self.location
+    }
+}
+
+impl Render for LargeFileBanner {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let size = self
+            .active_editor
+            .as_ref()
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+            .map_or(0, |buffer| buffer.read(cx).len());
+        let read_only = self
+            .active_editor
+            .as_ref()
+            .map_or(false, |editor| editor.read(cx).read_only(cx));
+        let mut message = format!(
+            "This file is {:.1} MB, so syntax highlighting, language servers, inlay hints and git diffs are disabled",
+            size as f64 / 1_000_000.
+        );
+        if read_only {
+            message.push_str(" and it was opened read-only");
+        }
+        message.push('.');
+
+        h_flex()
+            .px_2()
+            .gap_2()
+            .justify_between()
+            .bg(cx.theme().status().warning_background)
+            .rounded_md()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Icon::new(IconName::Warning)
+                            .size(IconSize::Small)
+                            .color(Color::Warning),
+                    )
+                    .child(Label::new(message)),
+            )
+            .child(
+                Button::new("enable-large-file-features", "Enable Features Anyway")
+                    .style(ButtonStyle::Transparent)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(editor) = &this.active_editor {
+                            editor.update(cx, |editor, cx| {
+                                editor.enable_large_file_features(
+                                    &EnableLargeFileFeatures,
+                                    window,
+                                    cx,
+                                )
+                            });
+                        }
+                    })),
+            )
+    }
+}
@@ -0,0 +1,198 @@
+use std::cmp::Reverse;
+
+use crate::{DisplayPoint, DisplayRow, Editor, EditorMode, EditorSettings};
+use gpui::{Context, HighlightStyle};
+use language::Bias;
+use settings::Settings;
+use theme::ActiveTheme;
+
+enum RainbowBracketHighlight {}
+
+/// Colors the brackets in the visible rows of the editor by their nesting depth, cycling through
+/// the theme's accent colors.
+///
+/// Bracket pairs come from each language's bracket query, so this needs to be called again
+/// whenever the editor scrolls or one of its buffers is reparsed.
+///
+/// Indent guides aren't colored here, as their `indent_aware` coloring already cycles through the
+/// same accent colors by depth, which lines up with the depth of the brackets around them.
+pub fn refresh_rainbow_bracket_highlights(editor: &mut Editor, cx: &mut Context<Editor>) {
+    if editor.mode != EditorMode::Full || !EditorSettings::get_global(cx).rainbow_brackets {
+        // The setting may have just been turned off. Clearing only notifies the editor when there
+        // were highlights to clear.
+        editor.clear_highlights::<RainbowBracketHighlight>(cx);
+        return;
+    }
+    let Some(visible_line_count) = editor.visible_line_count() else {
+        return;
+    };
+    let accents = cx.theme().accents().0.clone();
+    if accents.is_empty() {
+        return;
+    }
+
+    let display_snapshot = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+    let buffer = &display_snapshot.buffer_snapshot;
+    let scroll_top = editor
+        .scroll_manager
+        .anchor()
+        .scroll_position(&display_snapshot)
+        .y;
+    let start_row = DisplayRow(scroll_top as u32);
+    let end_row = DisplayRow((scroll_top + visible_line_count).ceil() as u32 + 1);
+    let start = DisplayPoint::new(start_row, 0).to_offset(&display_snapshot, Bias::Left);
+    let end = if end_row > display_snapshot.max_point().row() {
+        buffer.len()
+    } else {
+        DisplayPoint::new(end_row, 0).to_offset(&display_snapshot, Bias::Right)
+    };
+
+    let mut ranges_by_color = vec![Vec::new(); accents.len()];
+    for (buffer_snapshot, range, excerpt_id) in buffer.range_to_buffer_ranges(start..end) {
+        // Bracket queries also match pairs like quotes, which don't nest.
+        let mut pairs = buffer_snapshot
+            .bracket_ranges(range)
+            .filter(|(open, close)| {
+                buffer_snapshot
+                    .text_for_range(open.clone())
+                    .collect::<String>()
+                    != buffer_snapshot
+                        .text_for_range(close.clone())
+                        .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        pairs.sort_by_key(|(open, close)| (open.start, Reverse(close.end)));
+        pairs.dedup();
+
+        // The enclosing pairs overlap the visible range too, so the depth of each
+        // pair is the number of pairs before it that haven't been closed yet.
+        let mut enclosing_ends = Vec::<usize>::new();
+        for (open, close) in pairs {
+            while enclosing_ends
+                .last()
+                .is_some_and(|enclosing_end| *enclosing_end <= open.start)
+            {
+                enclosing_ends.pop();
+            }
+            let color_ix = enclosing_ends.len() % accents.len();
+            enclosing_ends.push(close.end);
+
+            for range in [open, close] {
+                let start =
+                    buffer.anchor_in_excerpt(excerpt_id, buffer_snapshot.anchor_after(range.start));
+                let end =
+                    buffer.anchor_in_excerpt(excerpt_id, buffer_snapshot.anchor_before(range.end));
+                if let Some((start, end)) = start.zip(end) {
+                    ranges_by_color[color_ix].push(start..end);
+                }
+            }
+        }
+    }
+
+    // Depths that are no longer visible would otherwise keep their highlights.
+    editor.clear_highlights::<RainbowBracketHighlight>(cx);
+    for (ix, (mut ranges, color)) in ranges_by_color.into_iter().zip(accents).enumerate() {
+        if ranges.is_empty() {
+            continue;
+        }
+        ranges.sort_by(|a, b| a.start.cmp(&b.start, buffer));
+        editor.highlight_text_key::<RainbowBracketHighlight>(
+            ix,
+            ranges,
+            HighlightStyle {
+                color: Some(color),
+                ..Default::default()
+            },
+            cx,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        display_map::HighlightKey, editor_tests::init_test,
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+    use indoc::indoc;
+    use settings::SettingsStore;
+    use std::any::TypeId;
+
+    #[gpui::test]
+    async fn test_rainbow_bracket_highlights(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.rainbow_brackets = Some(true);
+                });
+            });
+        });
+
+        let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+        cx.set_state(indoc! {r#"
+            fn main() {
+                foo(bar[1], (2), "(");ˇ
+            }
+        "#});
+
+        let brackets_by_depth = cx.update_editor(|editor, window, cx| {
+            editor.set_visible_line_count(10., window, cx);
+            refresh_rainbow_bracket_highlights(editor, cx);
+
+            let snapshot = editor.snapshot(window, cx);
+            (0..4)
+                .map(|ix| {
+                    let key = HighlightKey::TypePlus(TypeId::of::<RainbowBracketHighlight>(), ix);
+                    snapshot
+                        .display_snapshot
+                        .text_highlight_ranges_for_key(key)
+                        .map(|highlights| {
+                            highlights
+                                .1
+                                .iter()
+                                .map(|range| {
+                                    snapshot
+                                        .buffer_snapshot
+                                        .text_for_range(range.clone())
+                                        .collect::<String>()
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(
+            brackets_by_depth,
+            vec![
+                vec!["{", "}"],
+                vec!["(", ")"],
+                vec!["[", "]", "(", ")"],
+                Vec::<&str>::new(),
+            ]
+        );
+
+        // Turning the setting off clears the highlights.
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.rainbow_brackets = Some(false);
+                });
+            });
+        });
+        cx.update_editor(|editor, window, cx| {
+            refresh_rainbow_bracket_highlights(editor, cx);
+            let snapshot = editor.snapshot(window, cx);
+            assert!((0..4).all(|ix| {
+                let key = HighlightKey::TypePlus(TypeId::of::<RainbowBracketHighlight>(), ix);
+                snapshot
+                    .display_snapshot
+                    .text_highlight_ranges_for_key(key)
+                    .is_none()
+            }));
+        });
+    }
+}
@@ -2,8 +2,10 @@
 mod file_finder_tests;
 
 pub mod file_finder_settings;
+mod frecency;
 mod new_path_prompt;
 mod open_path_prompt;
+mod persistence;
 
 use futures::future::join_all;
 pub use open_path_prompt::OpenPathDelegate;
@@ -12,6 +14,7 @@ use collections::HashMap;
 use editor::Editor;
 use file_finder_settings::{FileFinderSettings, FileFinderWidth};
 use file_icons::FileIcons;
+use frecency::Frecency;
 use fuzzy::{CharBag, PathMatch, PathMatchCandidate};
 use gpui::{
     actions, Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle,
@@ -42,7 +45,7 @@ use workspace::{
     Workspace,
 };
 
-actions!(file_finder, [SelectPrev, ToggleMenu]);
+actions!(file_finder, [SelectPrev, ToggleMenu, TogglePin]);
 
 impl ModalView for FileFinder {
     fn on_before_dismiss(
@@ -69,6 +72,7 @@ pub fn init_settings(cx: &mut App) {
 
 pub fn init(cx: &mut App) {
     init_settings(cx);
+    frecency::prune_stale_files(cx);
     cx.observe_new(FileFinder::register).detach();
     cx.observe_new(NewPathPrompt::register).detach();
     cx.observe_new(OpenPathPrompt::register).detach();
@@ -78,8 +82,9 @@ impl FileFinder {
     fn register(
         workspace: &mut Workspace,
         _window: Option<&mut Window>,
-        _: &mut Context<Workspace>,
+        cx: &mut Context<Workspace>,
     ) {
+        frecency::track_file_usage(cx);
         workspace.register_action(
             |workspace, action: &workspace::ToggleFileFinder, window, cx| {
                 let Some(file_finder) = workspace.active_modal::<Self>(cx) else {
@@ -141,12 +146,27 @@ impl FileFinder {
                 }
             })
             .collect::<Vec<_>>();
+        let worktrees = project
+            .worktrees(cx)
+            .map(|worktree| {
+                let worktree = worktree.read(cx);
+                (worktree.id(), worktree.abs_path())
+            })
+            .collect();
+        let frecency = Frecency::load(worktrees, cx);
         cx.spawn_in(window, move |workspace, mut cx| async move {
             let history_items = join_all(history_items).await.into_iter().flatten();
+            let frecency = frecency.await;
 
             workspace
                 .update_in(&mut cx, |workspace, window, cx| {
                     let project = workspace.project().clone();
+                    let history_items = with_frecent_files(
+                        history_items.collect(),
+                        &frecency,
+                        project.read(cx),
+                        cx,
+                    );
                     let weak_workspace = cx.entity().downgrade();
                     workspace.toggle_modal(window, cx, |window, cx| {
                         let delegate = FileFinderDelegate::new(
@@ -154,7 +174,8 @@ impl FileFinder {
                             weak_workspace,
                             project,
                             currently_opened_path,
-                            history_items.collect(),
+                            history_items,
+                            Arc::new(frecency),
                             separate_history,
                             window,
                             cx,
@@ -202,6 +223,13 @@ impl FileFinder {
         window.dispatch_action(Box::new(menu::SelectPrev), cx);
     }
 
+    fn handle_toggle_pin(&mut self, _: &TogglePin, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.toggle_selected_pinned(cx);
+            picker.refresh(window, cx);
+        });
+    }
+
     fn handle_toggle_menu(&mut self, _: &ToggleMenu, window: &mut Window, cx: &mut Context<Self>) {
         self.picker.update(cx, |picker, cx| {
             let menu_handle = &picker.delegate.popover_menu_handle;
@@ -316,6 +344,7 @@ impl Render for FileFinder {
             .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
             .on_action(cx.listener(Self::handle_select_prev))
             .on_action(cx.listener(Self::handle_toggle_menu))
+            .on_action(cx.listener(Self::handle_toggle_pin))
             .on_action(cx.listener(Self::go_to_file_split_left))
             .on_action(cx.listener(Self::go_to_file_split_right))
             .on_action(cx.listener(Self::go_to_file_split_up))
@@ -338,6 +367,7 @@ pub struct FileFinderDelegate {
     has_changed_selected_index: bool,
     cancel_flag: Arc<AtomicBool>,
     history_items: Vec<FoundPath>,
+    frecency: Arc<Frecency>,
     separate_history: bool,
     first_update: bool,
     popover_menu_handle: PopoverMenuHandle<ContextMenu>,
@@ -400,6 +430,16 @@ impl Match {
         }
     }
 
+    fn project_path(&self) -> ProjectPath {
+        match self {
+            Match::History { path, .. } => path.project.clone(),
+            Match::Search(panel_match) => ProjectPath {
+                worktree_id: WorktreeId::from_usize(panel_match.0.worktree_id),
+                path: Arc::clone(&panel_match.0.path),
+            },
+        }
+    }
+
     fn panel_match(&self) -> Option<&ProjectPanelOrdMatch> {
         match self {
             Match::History { panel_match, .. } => panel_match.as_ref(),
@@ -451,6 +491,7 @@ impl Matches {
         query: Option<&FileSearchQuery>,
         new_search_matches: impl Iterator<Item = ProjectPanelOrdMatch>,
         extend_old_matches: bool,
+        frecency: &Frecency,
     ) {
         let Some(query) = query else {
             // assuming that if there's no query, then there's no search matches.
@@ -471,7 +512,8 @@ impl Matches {
             return;
         };
 
-        let new_history_matches = matching_history_items(history_items, currently_opened, query);
+        let new_history_matches =
+            matching_history_items(history_items, currently_opened, query, frecency);
         let new_search_matches: Vec<Match> = new_search_matches
             .filter(|path_match| !new_history_matches.contains_key(&path_match.0.path))
             .map(Match::Search)
@@ -539,6 +581,7 @@ fn matching_history_items<'a>(
     history_items: impl IntoIterator<Item = &'a FoundPath>,
     currently_opened: Option<&'a FoundPath>,
     query: &FileSearchQuery,
+    frecency: &Frecency,
 ) -> HashMap<Arc<Path>, Match> {
     let mut candidates_paths = HashMap::default();
 
@@ -587,12 +630,14 @@ fn matching_history_items<'a>(
                 max_results,
             )
             .into_iter()
-            .filter_map(|path_match| {
+            .filter_map(|mut path_match| {
+                let project_path = ProjectPath {
+                    worktree_id: WorktreeId::from_usize(path_match.worktree_id),
+                    path: Arc::clone(&path_match.path),
+                };
+                path_match.score = frecency.blend(&project_path, path_match.score);
                 candidates_paths
-                    .remove_entry(&ProjectPath {
-                        worktree_id: WorktreeId::from_usize(path_match.worktree_id),
-                        path: Arc::clone(&path_match.path),
-                    })
+                    .remove_entry(&project_path)
                     .map(|(_, found_path)| {
                         (
                             Arc::clone(&path_match.path),
@@ -622,6 +667,34 @@ impl FoundPath {
 
 const MAX_RECENT_SELECTIONS: usize = 20;
 
+/// Adds the most frecent files that still exist to the history items, and moves the pinned
+/// files to the top, so that they're shown even after a restart.
+fn with_frecent_files(
+    mut history_items: Vec<FoundPath>,
+    frecency: &Frecency,
+    project: &Project,
+    cx: &App,
+) -> Vec<FoundPath> {
+    for project_path in frecency.most_frecent() {
+        if history_items.len() >= MAX_RECENT_SELECTIONS && !frecency.is_pinned(project_path) {
+            break;
+        }
+        if history_items
+            .iter()
+            .any(|history_item| &history_item.project == project_path)
+            || project.entry_for_path(project_path, cx).is_none()
+        {
+            continue;
+        }
+        let abs_path = project
+            .worktree_for_id(project_path.worktree_id, cx)
+            .map(|worktree| worktree.read(cx).abs_path().join(&project_path.path));
+        history_items.push(FoundPath::new(project_path.clone(), abs_path));
+    }
+    history_items.sort_by_key(|history_item| !frecency.is_pinned(&history_item.project));
+    history_items
+}
+
 pub enum Event {
     Selected(ProjectPath),
     Dismissed,
@@ -651,6 +724,7 @@ impl FileFinderDelegate {
         project: Entity<Project>,
         currently_opened_path: Option<FoundPath>,
         history_items: Vec<FoundPath>,
+        frecency: Arc<Frecency>,
         separate_history: bool,
         window: &mut Window,
         cx: &mut Context<FileFinder>,
@@ -670,6 +744,7 @@ impl FileFinderDelegate {
             selected_index: 0,
             cancel_flag: Arc::new(AtomicBool::new(false)),
             history_items,
+            frecency,
             separate_history,
             first_update: true,
             popover_menu_handle: PopoverMenuHandle::default(),
@@ -730,8 +805,9 @@ impl FileFinderDelegate {
         self.cancel_flag.store(true, atomic::Ordering::Relaxed);
         self.cancel_flag = Arc::new(AtomicBool::new(false));
         let cancel_flag = self.cancel_flag.clone();
+        let frecency = self.frecency.clone();
         cx.spawn_in(window, |picker, mut cx| async move {
-            let matches = fuzzy::match_path_sets(
+            let matches = fuzzy::match_path_sets_with_boost(
                 candidate_sets.as_slice(),
                 query.path_query(),
                 relative_to,
@@ -739,6 +815,16 @@ impl FileFinderDelegate {
                 100,
                 &cancel_flag,
                 cx.background_executor().clone(),
+                |worktree_id, path, score| {
+                    if frecency.is_empty() {
+                        return score;
+                    }
+                    let project_path = ProjectPath {
+                        worktree_id: WorktreeId::from_usize(worktree_id),
+                        path: Arc::from(path),
+                    };
+                    frecency.blend(&project_path, score)
+                },
             )
             .await
             .into_iter()
@@ -784,6 +870,7 @@ impl FileFinderDelegate {
                 Some(&query),
                 matches.into_iter(),
                 extend_old_matches,
+                &self.frecency,
             );
 
             self.selected_index = selected_match.map_or_else(
@@ -994,6 +1081,45 @@ impl FileFinderDelegate {
         0
     }
 
+    /// Pins the selected file to the top of the file finder, or unpins it.
+    fn toggle_selected_pinned(&mut self, cx: &mut Context<Picker<Self>>) {
+        let Some(project_path) = self
+            .matches
+            .get(self.selected_index)
+            .map(|selected_match| selected_match.project_path())
+        else {
+            return;
+        };
+        let Some(worktree) = self
+            .project
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return;
+        };
+        let worktree_path = worktree.read(cx).abs_path();
+        let pinned = Arc::make_mut(&mut self.frecency).toggle_pinned(
+            project_path.clone(),
+            &worktree_path,
+            cx,
+        );
+        if pinned
+            && !self
+                .history_items
+                .iter()
+                .any(|history_item| history_item.project == project_path)
+        {
+            let abs_path = worktree_path.join(&project_path.path);
+            self.history_items
+                .push(FoundPath::new(project_path, Some(abs_path)));
+        }
+        let frecency = self.frecency.clone();
+        self.history_items
+            .sort_by_key(|history_item| !frecency.is_pinned(&history_item.project));
+        // Force the matches to be updated, even if the query is empty.
+        self.first_update = true;
+    }
+
     fn key_context(&self, window: &Window, cx: &App) -> KeyContext {
         let mut key_context = KeyContext::new_with_defaults();
         key_context.add("FileFinder");
@@ -1076,6 +1202,7 @@ impl PickerDelegate for FileFinderDelegate {
                     None,
                     None.into_iter(),
                     false,
+                    &self.frecency,
                 );
 
                 self.first_update = false;
@@ -1260,6 +1387,10 @@ impl PickerDelegate for FileFinderDelegate {
             .expect("Invalid matches state: no element for index {ix}");
 
         let history_icon = match &path_match {
+            _ if self.frecency.is_pinned(&path_match.project_path()) => Icon::new(IconName::Pin)
+                .color(Color::Muted)
+                .size(IconSize::Small)
+                .into_any_element(),
             Match::History { .. } => Icon::new(IconName::HistoryRerun)
                 .color(Color::Muted)
                 .size(IconSize::Small)
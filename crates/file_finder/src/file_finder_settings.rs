@@ -7,6 +7,7 @@ use settings::{Settings, SettingsSources};
 pub struct FileFinderSettings {
     pub file_icons: bool,
     pub modal_max_width: Option<FileFinderWidth>,
+    pub frecency: bool,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
@@ -19,6 +20,11 @@ pub struct FileFinderSettingsContent {
     ///
     /// Default: small
     pub modal_max_width: Option<FileFinderWidth>,
+    /// Whether to remember how often and how recently files are opened and edited, and rank
+    /// the files that are used often and recently above other matches.
+    ///
+    /// Default: true
+    pub frecency: Option<bool>,
 }
 
 impl Settings for FileFinderSettings {
@@ -6,6 +6,7 @@ use gpui::{Entity, TestAppContext, VisualTestContext};
 use menu::{Confirm, SelectNext, SelectPrev};
 use project::{RemoveOptions, FS_WATCH_LATENCY};
 use serde_json::json;
+use settings::SettingsStore;
 use workspace::{AppState, ToggleFileFinder, Workspace};
 
 #[ctor::ctor]
@@ -1979,6 +1980,72 @@ async fn test_repeat_toggle_action(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_pinned_files(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    cx.update(|cx| set_frecency_enabled(true, cx));
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/pinned_files",
+            json!({
+                "src": {
+                    "first.rs": "// First Rust file",
+                    "second.rs": "// Second Rust file",
+                    "third.rs": "// Third Rust file",
+                }
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/pinned_files".as_ref()], cx).await;
+    let (picker, workspace, cx) = build_find_picker(project, cx);
+    let worktree_id = cx.read(|cx| {
+        let worktrees = workspace.read(cx).worktrees(cx).collect::<Vec<_>>();
+        assert_eq!(worktrees.len(), 1);
+        WorktreeId::from_usize(worktrees[0].entity_id().as_u64() as usize)
+    });
+    let third_path = ProjectPath {
+        worktree_id,
+        path: Arc::from(Path::new("src/third.rs")),
+    };
+
+    cx.simulate_input("third");
+    cx.dispatch_action(TogglePin);
+    picker.update(cx, |picker, _| {
+        assert!(picker.delegate.frecency.is_pinned(&third_path));
+        assert!(
+            picker
+                .delegate
+                .matches
+                .get(0)
+                .is_some_and(|first_match| { first_match.project_path() == third_path }),
+            "Pinned file should be the first match, but got: {:?}",
+            picker.delegate.matches
+        );
+    });
+    cx.dispatch_action(menu::Cancel);
+    cx.run_until_parked();
+
+    let picker = open_file_picker(&workspace, cx);
+    picker.update(cx, |picker, _| {
+        assert_eq!(
+            picker.delegate.history_items,
+            [FoundPath::new(
+                third_path.clone(),
+                Some(PathBuf::from("/pinned_files/src/third.rs"))
+            )],
+            "Pinned file should be shown after reopening the file finder"
+        );
+    });
+
+    cx.dispatch_action(TogglePin);
+    picker.update(cx, |picker, _| {
+        assert!(!picker.delegate.frecency.is_pinned(&third_path));
+    });
+}
+
 async fn open_close_queried_buffer(
     input: &str,
     expected_matches: usize,
@@ -2043,10 +2110,20 @@ fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
         editor::init(cx);
         workspace::init_settings(cx);
         Project::init_settings(cx);
+        // Frecency is persisted across tests, so it's only enabled by the tests that need it.
+        set_frecency_enabled(false, cx);
         state
     })
 }
 
+fn set_frecency_enabled(enabled: bool, cx: &mut App) {
+    cx.update_global::<SettingsStore, _>(|store, cx| {
+        store.update_user_settings::<FileFinderSettings>(cx, |settings| {
+            settings.frecency = Some(enabled);
+        });
+    });
+}
+
 fn test_path_position(test_str: &str) -> FileSearchQuery {
     let path_position = PathWithPosition::parse_str(test_str);
 
@@ -0,0 +1,324 @@
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use collections::HashMap;
+use gpui::{App, Context, Task};
+use project::{Project, ProjectPath, WorktreeId};
+use settings::Settings;
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::{file_finder_settings::FileFinderSettings, persistence::FILE_FINDER_DB};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+/// Files that haven't been used for this long are forgotten, unless they're pinned.
+const MAX_AGE_IN_DAYS: i64 = 180;
+/// How much more an edit counts than an open.
+const EDIT_WEIGHT: f64 = 2.0;
+/// How much frecency boosts fuzzy match scores.
+const FRECENCY_WEIGHT: f64 = 0.25;
+/// Added to the scores of pinned files, which is more than any unpinned file can score.
+const PINNED_BOOST: f64 = 100.0;
+/// Activating a file again within this long of its last counted open doesn't count as another
+/// open, so that switching back and forth between tabs doesn't inflate their scores.
+const MIN_TIME_BETWEEN_OPENS: Duration = Duration::from_secs(30 * 60);
+
+/// How often and how recently a file was opened or edited.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct FileFrecency {
+    pub open_count: u32,
+    pub edit_count: u32,
+    /// Seconds since the Unix epoch.
+    pub last_used_at: i64,
+    pub pinned: bool,
+}
+
+impl FileFrecency {
+    /// The number of times the file was used, weighted by how long ago it was last used.
+    pub fn score(&self, now: i64) -> f64 {
+        let uses = self.open_count as f64 + EDIT_WEIGHT * self.edit_count as f64;
+        let age_in_days = (now - self.last_used_at).max(0) / SECONDS_PER_DAY;
+        let recency = match age_in_days {
+            0..=3 => 1.0,
+            4..=14 => 0.7,
+            15..=31 => 0.5,
+            32..=90 => 0.3,
+            _ => 0.1,
+        };
+        uses * recency
+    }
+
+    /// Boosts a fuzzy match score by the file's frecency, so that files that are used often and
+    /// recently rank above files that match the query only slightly better.
+    pub fn blend(&self, fuzzy_score: f64, now: i64) -> f64 {
+        let score = fuzzy_score * (1.0 + FRECENCY_WEIGHT * self.score(now).ln_1p());
+        if self.pinned {
+            score + PINNED_BOOST
+        } else {
+            score
+        }
+    }
+}
+
+/// The frecencies of the files in a project's worktrees, as of when the file finder was opened.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Frecency {
+    files: HashMap<ProjectPath, FileFrecency>,
+    now: i64,
+}
+
+impl Frecency {
+    /// Loads the frecencies of the files in the given worktrees.
+    pub fn load(worktrees: Vec<(WorktreeId, Arc<Path>)>, cx: &App) -> Task<Self> {
+        if !FileFinderSettings::get_global(cx).frecency {
+            return Task::ready(Self::default());
+        }
+        cx.background_executor().spawn(async move {
+            let now = now();
+            let mut files = HashMap::default();
+            for (worktree_id, worktree_path) in worktrees {
+                let Some(frecencies) = FILE_FINDER_DB
+                    .file_frecencies(worktree_path.to_path_buf())
+                    .log_err()
+                else {
+                    continue;
+                };
+                for (path, open_count, edit_count, last_used_at, pinned) in frecencies {
+                    files.insert(
+                        ProjectPath {
+                            worktree_id,
+                            path: path.into(),
+                        },
+                        FileFrecency {
+                            open_count,
+                            edit_count,
+                            last_used_at,
+                            pinned,
+                        },
+                    );
+                }
+            }
+            Self { files, now }
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn get(&self, project_path: &ProjectPath) -> Option<&FileFrecency> {
+        self.files.get(project_path)
+    }
+
+    pub fn is_pinned(&self, project_path: &ProjectPath) -> bool {
+        self.get(project_path)
+            .map_or(false, |frecency| frecency.pinned)
+    }
+
+    /// Returns the given fuzzy match score, boosted by the file's frecency.
+    pub fn blend(&self, project_path: &ProjectPath, fuzzy_score: f64) -> f64 {
+        match self.get(project_path) {
+            Some(frecency) => frecency.blend(fuzzy_score, self.now),
+            None => fuzzy_score,
+        }
+    }
+
+    /// Returns the files, with the pinned ones first and then by decreasing frecency.
+    pub fn most_frecent(&self) -> Vec<&ProjectPath> {
+        let mut files = self
+            .files
+            .iter()
+            .map(|(project_path, frecency)| {
+                (project_path, frecency.pinned, frecency.score(self.now))
+            })
+            .collect::<Vec<_>>();
+        files.sort_by(|(path_a, pinned_a, score_a), (path_b, pinned_b, score_b)| {
+            pinned_b
+                .cmp(pinned_a)
+                .then_with(|| score_b.total_cmp(score_a))
+                .then_with(|| path_a.cmp(path_b))
+        });
+        files
+            .into_iter()
+            .map(|(project_path, _, _)| project_path)
+            .collect()
+    }
+
+    /// Pins or unpins the file, returning whether it's now pinned.
+    pub fn toggle_pinned(
+        &mut self,
+        project_path: ProjectPath,
+        worktree_path: &Path,
+        cx: &App,
+    ) -> bool {
+        let now = now();
+        let frecency = self
+            .files
+            .entry(project_path.clone())
+            .or_insert_with(|| FileFrecency {
+                last_used_at: now,
+                ..FileFrecency::default()
+            });
+        frecency.pinned = !frecency.pinned;
+        let pinned = frecency.pinned;
+
+        let worktree_path = worktree_path.to_path_buf();
+        let path = project_path.path.to_path_buf();
+        db::write_and_log(cx, move || {
+            FILE_FINDER_DB.set_file_pinned(worktree_path, path, pinned, now)
+        });
+        pinned
+    }
+}
+
+/// Forgets the files that haven't been used for a long time, unless they're pinned.
+pub(crate) fn prune_stale_files(cx: &App) {
+    let oldest_used_at = now() - MAX_AGE_IN_DAYS * SECONDS_PER_DAY;
+    db::write_and_log(cx, move || {
+        FILE_FINDER_DB.delete_stale_file_frecencies(oldest_used_at)
+    });
+}
+
+/// When files were last counted as opened in this session.
+#[derive(Default)]
+struct RecentOpens {
+    opened_at: HashMap<ProjectPath, Instant>,
+}
+
+impl RecentOpens {
+    /// Returns whether activating the file at `now` counts as opening it, which is when it
+    /// wasn't counted as opened within [`MIN_TIME_BETWEEN_OPENS`].
+    fn open(&mut self, project_path: &ProjectPath, now: Instant) -> bool {
+        self.opened_at
+            .retain(|_, opened_at| now.duration_since(*opened_at) < MIN_TIME_BETWEEN_OPENS);
+        if self.opened_at.contains_key(project_path) {
+            return false;
+        }
+        self.opened_at.insert(project_path.clone(), now);
+        true
+    }
+}
+
+/// Records the files that are opened and saved in the workspace, so that the file finder can
+/// rank them by frecency.
+pub(crate) fn track_file_usage(cx: &mut Context<Workspace>) {
+    let mut recent_opens = RecentOpens::default();
+    cx.subscribe(&cx.entity(), move |workspace, _, event, cx| match event {
+        workspace::Event::ActiveItemChanged => {
+            let Some(project_path) = workspace
+                .active_item(cx)
+                .and_then(|item| item.project_path(cx))
+            else {
+                return;
+            };
+            if !recent_opens.open(&project_path, Instant::now()) {
+                return;
+            }
+            record_file_use(workspace.project().read(cx), project_path, 1, 0, cx);
+        }
+        workspace::Event::UserSavedItem { item, .. } => {
+            if let Some(project_path) = item.upgrade().and_then(|item| item.project_path(cx)) {
+                record_file_use(workspace.project().read(cx), project_path, 0, 1, cx);
+            }
+        }
+        _ => {}
+    })
+    .detach();
+}
+
+fn record_file_use(
+    project: &Project,
+    project_path: ProjectPath,
+    open_count: u32,
+    edit_count: u32,
+    cx: &App,
+) {
+    if !FileFinderSettings::get_global(cx).frecency {
+        return;
+    }
+    let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx) else {
+        return;
+    };
+    let worktree_path = worktree.read(cx).abs_path().to_path_buf();
+    let path = project_path.path.to_path_buf();
+    let now = now();
+    db::write_and_log(cx, move || {
+        FILE_FINDER_DB.record_file_use(worktree_path, path, open_count, edit_count, now)
+    });
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frecency_score() {
+        let now = 100 * SECONDS_PER_DAY;
+        let recent = FileFrecency {
+            open_count: 3,
+            edit_count: 1,
+            last_used_at: now - SECONDS_PER_DAY,
+            pinned: false,
+        };
+        assert_eq!(recent.score(now), 5.0);
+
+        let old = FileFrecency {
+            last_used_at: now - 60 * SECONDS_PER_DAY,
+            ..recent
+        };
+        assert_eq!(old.score(now), 1.5);
+        assert_eq!(FileFrecency::default().score(now), 0.0);
+    }
+
+    #[test]
+    fn test_frecency_blend() {
+        let now = 100 * SECONDS_PER_DAY;
+        let frequent = FileFrecency {
+            open_count: 20,
+            edit_count: 5,
+            last_used_at: now,
+            pinned: false,
+        };
+        assert!(
+            frequent.blend(0.6, now) > 0.9,
+            "Frequently used files should rank above slightly better matches"
+        );
+        assert_eq!(FileFrecency::default().blend(0.6, now), 0.6);
+
+        let pinned = FileFrecency {
+            pinned: true,
+            ..FileFrecency::default()
+        };
+        assert!(pinned.blend(0.1, now) > frequent.blend(1.0, now));
+    }
+
+    #[test]
+    fn test_recent_opens() {
+        let project_path = |path: &str| ProjectPath {
+            worktree_id: WorktreeId::from_usize(0),
+            path: Path::new(path).into(),
+        };
+        let start = Instant::now();
+        let mut recent_opens = RecentOpens::default();
+        assert!(recent_opens.open(&project_path("a.rs"), start));
+        assert!(recent_opens.open(&project_path("b.rs"), start));
+
+        // Switching back to a tab shortly after doesn't count as opening it again.
+        let shortly_after = start + Duration::from_secs(60);
+        assert!(!recent_opens.open(&project_path("a.rs"), shortly_after));
+        assert!(!recent_opens.open(&project_path("b.rs"), shortly_after));
+
+        let much_later = start + MIN_TIME_BETWEEN_OPENS;
+        assert!(recent_opens.open(&project_path("a.rs"), much_later));
+        assert!(!recent_opens.open(&project_path("a.rs"), much_later));
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+
+define_connection!(
+    // Current schema shape using pseudo-rust syntax:
+    // file_frecency(
+    //   worktree_path: PathBuf,
+    //   path: PathBuf,
+    //   open_count: u32,
+    //   edit_count: u32,
+    //   last_used_at: i64,
+    //   pinned: bool,
+    // )
+    pub static ref FILE_FINDER_DB: FileFinderDb<()> =
+        &[sql!(
+            CREATE TABLE file_frecency(
+                worktree_path BLOB NOT NULL,
+                path BLOB NOT NULL,
+                open_count INTEGER NOT NULL DEFAULT 0,
+                edit_count INTEGER NOT NULL DEFAULT 0,
+                last_used_at INTEGER NOT NULL,
+                pinned INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY(worktree_path, path)
+            ) STRICT;
+        )];
+);
+
+impl FileFinderDb {
+    query! {
+        pub fn file_frecencies(worktree_path: PathBuf) -> Result<Vec<(PathBuf, u32, u32, i64, bool)>> {
+            SELECT path, open_count, edit_count, last_used_at, pinned FROM file_frecency
+            WHERE worktree_path = ?
+        }
+    }
+
+    query! {
+        pub async fn record_file_use(worktree_path: PathBuf, path: PathBuf, open_count: u32, edit_count: u32, used_at: i64) -> Result<()> {
+            INSERT INTO file_frecency
+                (worktree_path, path, open_count, edit_count, last_used_at)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (worktree_path, path) DO UPDATE SET
+                open_count = open_count + ?3,
+                edit_count = edit_count + ?4,
+                last_used_at = ?5
+        }
+    }
+
+    query! {
+        pub async fn set_file_pinned(worktree_path: PathBuf, path: PathBuf, pinned: bool, used_at: i64) -> Result<()> {
+            INSERT INTO file_frecency
+                (worktree_path, path, last_used_at, pinned)
+            VALUES
+                (?1, ?2, ?4, ?3)
+            ON CONFLICT (worktree_path, path) DO UPDATE SET
+                pinned = ?3
+        }
+    }
+
+    query! {
+        pub async fn delete_stale_file_frecencies(oldest_used_at: i64) -> Result<()> {
+            DELETE FROM file_frecency
+            WHERE last_used_at < ? AND pinned = 0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_file_frecencies() {
+        let db = FileFinderDb(db::open_test_db("test_file_frecencies").await);
+        let worktree_path = PathBuf::from("/root");
+        let path = PathBuf::from("src/main.rs");
+
+        db.record_file_use(worktree_path.clone(), path.clone(), 1, 0, 10)
+            .await
+            .unwrap();
+        db.record_file_use(worktree_path.clone(), path.clone(), 1, 1, 20)
+            .await
+            .unwrap();
+        db.set_file_pinned(worktree_path.clone(), PathBuf::from("README.md"), true, 5)
+            .await
+            .unwrap();
+        db.record_file_use(PathBuf::from("/other"), path.clone(), 1, 0, 30)
+            .await
+            .unwrap();
+
+        let mut frecencies = db.file_frecencies(worktree_path.clone()).unwrap();
+        frecencies.sort();
+        assert_eq!(
+            frecencies,
+            [
+                (PathBuf::from("README.md"), 0, 0, 5, true),
+                (path.clone(), 2, 1, 20, false),
+            ]
+        );
+
+        db.delete_stale_file_frecencies(30).await.unwrap();
+        assert_eq!(
+            db.file_frecencies(worktree_path).unwrap(),
+            [(PathBuf::from("README.md"), 0, 0, 5, true)],
+            "Pinned files should be kept"
+        );
+        assert_eq!(
+            db.file_frecencies(PathBuf::from("/other")).unwrap(),
+            [(path, 1, 0, 30, false)]
+        );
+    }
+}
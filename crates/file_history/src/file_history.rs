@@ -0,0 +1,304 @@
+mod file_history_diff;
+mod file_history_settings;
+mod persistence;
+mod timeline;
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use gpui::{actions, App, Context, Entity};
+use language::{Buffer, BufferEvent};
+use project::{buffer_store::BufferStoreEvent, Project, WorktreeId};
+use settings::Settings;
+use time::{OffsetDateTime, UtcOffset};
+use time_format::TimestampFormat;
+use util::ResultExt as _;
+use worktree::{PathChange, UpdatedEntriesSet};
+
+pub use file_history_diff::FileHistoryDiff;
+pub use file_history_settings::FileHistorySettings;
+pub use persistence::{FileHistoryDb, FileVersion, Retention, VersionSource, FILE_HISTORY_DB};
+pub use timeline::FileHistoryTimeline;
+
+actions!(file_history, [OpenFileHistory, RestoreVersion]);
+
+pub fn init(cx: &mut App) {
+    FileHistorySettings::register(cx);
+
+    cx.observe_new(|project: &mut Project, _window, cx| {
+        if project.is_local() {
+            record_file_versions(project, cx);
+        }
+    })
+    .detach();
+    cx.observe_new(FileHistoryTimeline::register).detach();
+}
+
+/// Records the versions of the project's files as buffers are first edited and saved, and as
+/// files with a history change on disk.
+///
+/// Files that are only opened aren't recorded, as their contents can't be lost.
+fn record_file_versions(project: &mut Project, cx: &mut Context<Project>) {
+    cx.subscribe(project.buffer_store(), |_, _, event, cx| {
+        if let BufferStoreEvent::BufferAdded(buffer) = event {
+            cx.subscribe(buffer, |project, buffer, event, cx| match event {
+                BufferEvent::DirtyChanged if buffer.read(cx).is_dirty() => {
+                    record_contents_before_edit(project, &buffer, cx);
+                }
+                BufferEvent::Saved => record_buffer_version(&buffer, VersionSource::Saved, cx),
+                _ => {}
+            })
+            .detach();
+        }
+    })
+    .detach();
+
+    let project_handle = cx.entity();
+    cx.subscribe(&project_handle, |project, _, event, cx| {
+        if let project::Event::WorktreeUpdatedEntries(worktree_id, updated_entries) = event {
+            record_external_changes(project, *worktree_id, updated_entries, cx);
+        }
+    })
+    .detach();
+}
+
+fn record_buffer_version(buffer: &Entity<Buffer>, source: VersionSource, cx: &App) {
+    let settings = FileHistorySettings::get_global(cx);
+    if !settings.enabled {
+        return;
+    }
+
+    let buffer = buffer.read(cx);
+    let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+        return;
+    };
+    if buffer.len() as u64 > settings.max_file_size_kb * 1024 {
+        return;
+    }
+
+    let abs_path = file.abs_path(cx);
+    let contents = buffer.text();
+    let retention = retention(settings);
+    db::write_and_log(cx, move || {
+        FILE_HISTORY_DB.record_version(abs_path, source, contents, now(), retention)
+    });
+}
+
+/// Records the contents of the file on disk, which the buffer no longer has once it's edited.
+/// This is a no-op when they're the same as the latest recorded version, e.g. when they were
+/// recorded when the buffer was last saved.
+fn record_contents_before_edit(project: &Project, buffer: &Entity<Buffer>, cx: &App) {
+    let settings = FileHistorySettings::get_global(cx);
+    if !settings.enabled {
+        return;
+    }
+
+    let buffer = buffer.read(cx);
+    let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+        return;
+    };
+    // New files have nothing on disk yet.
+    if file.disk_state().mtime().is_none() {
+        return;
+    }
+    if buffer.len() as u64 > settings.max_file_size_kb * 1024 {
+        return;
+    }
+
+    let abs_path = file.abs_path(cx);
+    let fs = project.fs().clone();
+    let retention = retention(settings);
+    cx.background_executor()
+        .spawn(async move {
+            let contents = fs.load(&abs_path).await?;
+            FILE_HISTORY_DB
+                .record_version(
+                    abs_path,
+                    VersionSource::BeforeEdit,
+                    contents,
+                    now(),
+                    retention,
+                )
+                .await
+        })
+        .detach_and_log_err(cx);
+}
+
+fn record_external_changes(
+    project: &Project,
+    worktree_id: WorktreeId,
+    updated_entries: &UpdatedEntriesSet,
+    cx: &App,
+) {
+    let settings = FileHistorySettings::get_global(cx);
+    if !settings.enabled {
+        return;
+    }
+    let Some(worktree) = project.worktree_for_id(worktree_id, cx) else {
+        return;
+    };
+    let worktree = worktree.read(cx);
+
+    let abs_paths = updated_entries
+        .iter()
+        .filter(|(_, _, change)| matches!(change, PathChange::Updated | PathChange::AddedOrUpdated))
+        .filter_map(|(path, _, _)| {
+            let entry = worktree.entry_for_path(path)?;
+            if !entry.is_file() || entry.size > settings.max_file_size_kb * 1024 {
+                return None;
+            }
+            worktree.absolutize(path).log_err()
+        })
+        .collect::<Vec<PathBuf>>();
+    if abs_paths.is_empty() {
+        return;
+    }
+
+    let fs = project.fs().clone();
+    let retention = retention(settings);
+    cx.background_executor()
+        .spawn(async move {
+            for abs_path in abs_paths {
+                // Only files that already have a history are recorded, so avoid loading
+                // the others.
+                if !FILE_HISTORY_DB
+                    .has_versions(abs_path.clone())
+                    .log_err()
+                    .unwrap_or(false)
+                {
+                    continue;
+                }
+                let Some(contents) = fs.load(&abs_path).await.log_err() else {
+                    continue;
+                };
+                FILE_HISTORY_DB
+                    .record_version(
+                        abs_path,
+                        VersionSource::ExternalChange,
+                        contents,
+                        now(),
+                        retention,
+                    )
+                    .await
+                    .log_err();
+            }
+        })
+        .detach();
+}
+
+fn retention(settings: &FileHistorySettings) -> Retention {
+    const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+    Retention {
+        max_versions_per_file: settings.max_versions_per_file,
+        oldest_recorded_at: now()
+            .saturating_sub((settings.max_age_days as i64).saturating_mul(SECONDS_PER_DAY)),
+        max_total_size: settings.max_total_size_mb.saturating_mul(1024 * 1024),
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+fn format_recorded_at(recorded_at: i64, format: TimestampFormat) -> String {
+    let Ok(timestamp) = OffsetDateTime::from_unix_timestamp(recorded_at) else {
+        return "Unknown time".to_string();
+    };
+    let local_offset = chrono::Local::now().offset().local_minus_utc();
+    time_format::format_localized_timestamp(
+        timestamp,
+        OffsetDateTime::now_utc(),
+        UtcOffset::from_whole_seconds(local_offset).unwrap_or(UtcOffset::UTC),
+        format,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use fs::FakeFs;
+    use gpui::{AppContext as _, TestAppContext};
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_capture_and_restore(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/capture_and_restore", json!({ "file.txt": "original\n" }))
+            .await;
+        let project = Project::test(fs, ["/capture_and_restore".as_ref()], cx).await;
+        let abs_path = PathBuf::from("/capture_and_restore/file.txt");
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(&abs_path, cx))
+            .await
+            .unwrap();
+        let sources = |versions: &[FileVersion]| {
+            versions
+                .iter()
+                .map(|version| version.source)
+                .collect::<Vec<_>>()
+        };
+
+        // Editing the buffer records the contents on disk, which it no longer has.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..8, "edited")], None, cx));
+        cx.run_until_parked();
+        let versions = FILE_HISTORY_DB.versions(abs_path.clone()).unwrap();
+        assert_eq!(sources(&versions), [VersionSource::BeforeEdit]);
+
+        // Saving records the buffer's contents.
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let versions = FILE_HISTORY_DB.versions(abs_path.clone()).unwrap();
+        assert_eq!(
+            sources(&versions),
+            [VersionSource::Saved, VersionSource::BeforeEdit]
+        );
+        let contents = |version: &FileVersion| {
+            FILE_HISTORY_DB
+                .version_contents(version.id)
+                .unwrap()
+                .unwrap()
+        };
+        assert_eq!(contents(&versions[0]), "edited\n");
+        assert_eq!(contents(&versions[1]), "original\n");
+
+        // Restoring the version from before the edit reverts the buffer to it, without saving.
+        let version = versions[1].clone();
+        let version_contents = contents(&version);
+        let (diff, cx) = cx.add_window_view(|window, cx| {
+            FileHistoryDiff::new(
+                buffer.clone(),
+                version,
+                "",
+                version_contents,
+                project.clone(),
+                window,
+                cx,
+            )
+        });
+        diff.update_in(cx, |diff, window, cx| {
+            diff.restore(&RestoreVersion, window, cx)
+        });
+        cx.run_until_parked();
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.text(), "original\n");
+            assert!(buffer.is_dirty());
+        });
+    }
+}
@@ -0,0 +1,244 @@
+use std::any::TypeId;
+
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AnyView, App, Entity, EventEmitter, FocusHandle, Focusable, Render, Subscription, Task,
+};
+use language::{Buffer, BufferEvent};
+use multi_buffer::MultiBuffer;
+use project::{buffer_store::BufferChangeSet, Project};
+use ui::prelude::*;
+use workspace::{searchable::SearchableItemHandle, Item, ItemNavHistory, Workspace};
+
+use crate::{FileVersion, RestoreVersion};
+
+/// Shows the changes between a recorded version of a file and its current contents.
+///
+/// The diff is computed against the live buffer, so it stays up-to-date as the file is edited,
+/// and individual hunks can be reverted to the recorded version.
+pub struct FileHistoryDiff {
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    version: FileVersion,
+    version_contents: String,
+    recorded_at: SharedString,
+    _subscription: Subscription,
+}
+
+impl FileHistoryDiff {
+    pub fn new(
+        buffer: Entity<Buffer>,
+        version: FileVersion,
+        recorded_at: impl Into<SharedString>,
+        version_contents: String,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let change_set = cx.new(|cx| {
+            let mut change_set = BufferChangeSet::new(&buffer, cx);
+            let _ = change_set.set_base_text(
+                version_contents.clone(),
+                buffer.read(cx).text_snapshot(),
+                cx,
+            );
+            change_set
+        });
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            multibuffer.add_change_set(change_set.clone(), cx);
+            multibuffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), true, window, cx);
+            editor.set_expand_all_diff_hunks(cx);
+            editor
+        });
+
+        let subscription = cx.subscribe(&buffer, move |_, buffer, event, cx| {
+            if let BufferEvent::Edited = event {
+                let buffer_snapshot = buffer.read(cx).text_snapshot();
+                change_set.update(cx, |change_set, cx| {
+                    let _ = change_set.recalculate_diff(buffer_snapshot, cx);
+                });
+            }
+        });
+
+        Self {
+            editor,
+            buffer,
+            version,
+            version_contents,
+            recorded_at: recorded_at.into(),
+            _subscription: subscription,
+        }
+    }
+
+    pub fn version(&self) -> &FileVersion {
+        &self.version
+    }
+
+    /// Replaces the contents of the buffer with the recorded version, without saving it.
+    pub fn restore(&mut self, _: &RestoreVersion, _window: &mut Window, cx: &mut Context<Self>) {
+        let buffer = self.buffer.clone();
+        let diff = buffer.read(cx).diff(self.version_contents.clone(), cx);
+        cx.spawn(|_, mut cx| async move {
+            let diff = diff.await;
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.apply_diff(diff, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn file_name(&self, cx: &App) -> SharedString {
+        self.buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "untitled".into())
+    }
+}
+
+impl Render for FileHistoryDiff {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .key_context("FileHistoryDiff")
+            .on_action(cx.listener(Self::restore))
+            .child(
+                h_flex()
+                    .w_full()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(format!(
+                            "{} version from {}",
+                            self.version.source.label(),
+                            self.recorded_at
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("restore-version", "Restore This Version")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.restore(&RestoreVersion, window, cx)
+                            })),
+                    ),
+            )
+            .child(self.editor.clone())
+    }
+}
+
+impl Focusable for FileHistoryDiff {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for FileHistoryDiff {}
+
+impl Item for FileHistoryDiff {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun))
+    }
+
+    fn tab_content_text(&self, _window: &Window, cx: &App) -> Option<SharedString> {
+        Some(format!("{} ({})", self.file_name(cx), self.recorded_at).into())
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        Some(
+            format!(
+                "Changes since the version of {} from {}",
+                self.file_name(cx),
+                self.recorded_at
+            )
+            .into(),
+        )
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::added_to_workspace(editor, workspace, window, cx)
+        });
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn std::any::Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| Item::navigate(editor, data, window, cx))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::set_nav_history(editor, nav_history, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.editor.update(cx, |editor, cx| {
+            Item::save(editor, format, project, window, cx)
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use gpui::App;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct FileHistorySettings {
+    pub enabled: bool,
+    pub max_versions_per_file: usize,
+    pub max_age_days: u64,
+    pub max_file_size_kb: u64,
+    pub max_total_size_mb: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct FileHistorySettingsContent {
+    /// Whether to record the versions of local files as they are edited, saved and changed on
+    /// disk.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The maximum number of versions to keep for each file.
+    ///
+    /// Default: 50
+    pub max_versions_per_file: Option<usize>,
+    /// The number of days after which versions are deleted.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u64>,
+    /// Files larger than this size, in kilobytes, are not recorded.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<u64>,
+    /// The maximum size, in megabytes, of the recorded versions of all files. The oldest
+    /// versions are deleted first once it's exceeded.
+    ///
+    /// Default: 256
+    pub max_total_size_mb: Option<u64>,
+}
+
+impl Settings for FileHistorySettings {
+    const KEY: Option<&'static str> = Some("file_history");
+
+    type FileContent = FileHistorySettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -0,0 +1,379 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use db::sqlez::bindable::{Bind, Column, StaticColumnCount};
+use db::sqlez::statement::Statement;
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+
+/// What caused a version of a file to be recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionSource {
+    /// The file's contents on disk when its buffer was first edited.
+    BeforeEdit,
+    /// The buffer was saved.
+    Saved,
+    /// The file was changed on disk by another program.
+    ExternalChange,
+}
+
+impl VersionSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            VersionSource::BeforeEdit => "before_edit",
+            VersionSource::Saved => "saved",
+            VersionSource::ExternalChange => "external_change",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            VersionSource::BeforeEdit => "Before Edits",
+            VersionSource::Saved => "Saved",
+            VersionSource::ExternalChange => "Changed on Disk",
+        }
+    }
+}
+
+impl StaticColumnCount for VersionSource {}
+
+impl Bind for VersionSource {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        self.as_str().bind(statement, start_index)
+    }
+}
+
+impl Column for VersionSource {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (source, next_index) = String::column(statement, start_index)?;
+        let source = match source.as_str() {
+            "before_edit" => VersionSource::BeforeEdit,
+            "saved" => VersionSource::Saved,
+            "external_change" => VersionSource::ExternalChange,
+            _ => return Err(anyhow!("invalid file version source: {source}")),
+        };
+        Ok((source, next_index))
+    }
+}
+
+/// A recorded version of a file, without its contents.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileVersion {
+    pub id: i64,
+    /// Seconds since the Unix epoch.
+    pub recorded_at: i64,
+    pub source: VersionSource,
+}
+
+impl StaticColumnCount for FileVersion {
+    fn column_count() -> usize {
+        3
+    }
+}
+
+impl Column for FileVersion {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (id, next_index) = i64::column(statement, start_index)?;
+        let (recorded_at, next_index) = i64::column(statement, next_index)?;
+        let (source, next_index) = VersionSource::column(statement, next_index)?;
+        Ok((
+            FileVersion {
+                id,
+                recorded_at,
+                source,
+            },
+            next_index,
+        ))
+    }
+}
+
+/// How many versions to keep for each file, for how long, and how much space they can take.
+#[derive(Clone, Copy, Debug)]
+pub struct Retention {
+    pub max_versions_per_file: usize,
+    /// Versions recorded before this time (in seconds since the Unix epoch) are deleted.
+    pub oldest_recorded_at: i64,
+    /// The oldest versions of all files are deleted once their contents take more than this
+    /// many bytes in total.
+    pub max_total_size: u64,
+}
+
+define_connection!(
+    // Current schema shape using pseudo-rust syntax:
+    // file_versions(
+    //   version_id: i64,
+    //   abs_path: PathBuf,
+    //   recorded_at: i64,
+    //   source: VersionSource,
+    //   contents: String,
+    // )
+    pub static ref FILE_HISTORY_DB: FileHistoryDb<()> =
+        &[sql!(
+            CREATE TABLE file_versions(
+                version_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                abs_path BLOB NOT NULL,
+                recorded_at INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                contents TEXT NOT NULL
+            ) STRICT;
+
+            CREATE INDEX file_versions_by_path ON file_versions(abs_path, version_id);
+        )];
+);
+
+impl FileHistoryDb {
+    query! {
+        pub fn versions(abs_path: PathBuf) -> Result<Vec<FileVersion>> {
+            SELECT version_id, recorded_at, source FROM file_versions
+            WHERE abs_path = ?
+            ORDER BY version_id DESC
+        }
+    }
+
+    query! {
+        pub fn has_versions(abs_path: PathBuf) -> Result<bool> {
+            SELECT EXISTS(SELECT 1 FROM file_versions WHERE abs_path = ?)
+        }
+    }
+
+    query! {
+        pub fn version_contents(version_id: i64) -> Result<Option<String>> {
+            SELECT contents FROM file_versions WHERE version_id = ?
+        }
+    }
+
+    /// Records a version of the file at `abs_path`, unless its contents are the same as the
+    /// latest recorded version, and deletes the versions that fall outside of `retention`.
+    ///
+    /// External changes are only recorded for files that already have a history, so that we
+    /// don't store every file touched by other programs.
+    pub async fn record_version(
+        &self,
+        abs_path: PathBuf,
+        source: VersionSource,
+        contents: String,
+        recorded_at: i64,
+        retention: Retention,
+    ) -> Result<()> {
+        self.write(move |connection| {
+            let latest_contents = connection.select_row_bound::<&Path, String>(sql!(
+                SELECT contents FROM file_versions
+                WHERE abs_path = ?
+                ORDER BY version_id DESC
+                LIMIT 1
+            ))?(abs_path.as_path())?;
+            match latest_contents {
+                Some(latest_contents) if latest_contents == contents => return Ok(()),
+                None if source == VersionSource::ExternalChange => return Ok(()),
+                _ => {}
+            }
+
+            connection.exec_bound::<(&Path, i64, VersionSource, String)>(sql!(
+                INSERT INTO file_versions(abs_path, recorded_at, source, contents)
+                VALUES (?, ?, ?, ?)
+            ))?((abs_path.as_path(), recorded_at, source, contents))?;
+
+            connection.exec_bound::<(&Path, i64)>(sql!(
+                DELETE FROM file_versions
+                WHERE abs_path = ?1 AND version_id NOT IN (
+                    SELECT version_id FROM file_versions
+                    WHERE abs_path = ?1
+                    ORDER BY version_id DESC
+                    LIMIT ?2
+                )
+            ))?((abs_path.as_path(), retention.max_versions_per_file as i64))?;
+
+            connection.exec_bound::<i64>(sql!(
+                DELETE FROM file_versions WHERE recorded_at < ?
+            ))?(retention.oldest_recorded_at)?;
+
+            connection.exec_bound::<i64>(sql!(
+                DELETE FROM file_versions WHERE version_id IN (
+                    SELECT version_id FROM (
+                        SELECT
+                            version_id,
+                            SUM(LENGTH(CAST(contents AS BLOB))) OVER (
+                                ORDER BY version_id DESC
+                            ) AS total_size
+                        FROM file_versions
+                    )
+                    WHERE total_size > ?
+                )
+            ))?(retention.max_total_size.try_into().unwrap_or(i64::MAX))
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_recording_versions() {
+        let db = FileHistoryDb(db::open_test_db("test_recording_versions").await);
+        let path = PathBuf::from("/root/file.txt");
+        let retention = Retention {
+            max_versions_per_file: 2,
+            oldest_recorded_at: 0,
+            max_total_size: u64::MAX,
+        };
+
+        // External changes aren't recorded until the file has a history.
+        db.record_version(
+            path.clone(),
+            VersionSource::ExternalChange,
+            "zero".into(),
+            1,
+            retention,
+        )
+        .await
+        .unwrap();
+        assert_eq!(db.versions(path.clone()).unwrap(), Vec::new());
+
+        db.record_version(
+            path.clone(),
+            VersionSource::BeforeEdit,
+            "one".into(),
+            2,
+            retention,
+        )
+        .await
+        .unwrap();
+        db.record_version(
+            path.clone(),
+            VersionSource::Saved,
+            "one".into(),
+            3,
+            retention,
+        )
+        .await
+        .unwrap();
+        db.record_version(
+            path.clone(),
+            VersionSource::Saved,
+            "two".into(),
+            4,
+            retention,
+        )
+        .await
+        .unwrap();
+        let versions = db.versions(path.clone()).unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|version| (version.recorded_at, version.source))
+                .collect::<Vec<_>>(),
+            vec![(4, VersionSource::Saved), (2, VersionSource::BeforeEdit)]
+        );
+        assert_eq!(
+            db.version_contents(versions[1].id).unwrap(),
+            Some("one".to_string())
+        );
+
+        // Only the most recent versions are kept.
+        db.record_version(
+            path.clone(),
+            VersionSource::ExternalChange,
+            "three".into(),
+            5,
+            retention,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.versions(path.clone())
+                .unwrap()
+                .iter()
+                .map(|version| version.recorded_at)
+                .collect::<Vec<_>>(),
+            vec![5, 4]
+        );
+
+        // Versions older than the retention period are deleted.
+        db.record_version(
+            path.clone(),
+            VersionSource::Saved,
+            "four".into(),
+            10,
+            Retention {
+                max_versions_per_file: 2,
+                oldest_recorded_at: 5,
+                max_total_size: u64::MAX,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.versions(path)
+                .unwrap()
+                .iter()
+                .map(|version| version.recorded_at)
+                .collect::<Vec<_>>(),
+            vec![10, 5]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_total_size_limit() {
+        let db = FileHistoryDb(db::open_test_db("test_total_size_limit").await);
+        let first_path = PathBuf::from("/root/first.txt");
+        let second_path = PathBuf::from("/root/second.txt");
+        let retention = Retention {
+            max_versions_per_file: 10,
+            oldest_recorded_at: 0,
+            max_total_size: 12,
+        };
+
+        for (path, contents, recorded_at) in [
+            (&first_path, "aaaa", 1),
+            (&second_path, "bbbb", 2),
+            (&first_path, "cccc", 3),
+        ] {
+            db.record_version(
+                path.clone(),
+                VersionSource::Saved,
+                contents.into(),
+                recorded_at,
+                retention,
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            db.versions(first_path.clone())
+                .unwrap()
+                .iter()
+                .map(|version| version.recorded_at)
+                .collect::<Vec<_>>(),
+            vec![3, 1]
+        );
+
+        // The oldest versions are deleted first, whichever file they belong to.
+        db.record_version(
+            second_path.clone(),
+            VersionSource::Saved,
+            "dddd".into(),
+            4,
+            retention,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.versions(first_path)
+                .unwrap()
+                .iter()
+                .map(|version| version.recorded_at)
+                .collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(
+            db.versions(second_path)
+                .unwrap()
+                .iter()
+                .map(|version| version.recorded_at)
+                .collect::<Vec<_>>(),
+            vec![4, 2]
+        );
+    }
+}
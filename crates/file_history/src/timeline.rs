@@ -0,0 +1,317 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Task,
+    WeakEntity, Window,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use time_format::TimestampFormat;
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace};
+
+use crate::{format_recorded_at, FileHistoryDiff, FileVersion, OpenFileHistory, FILE_HISTORY_DB};
+
+/// Lists the recorded versions of the active file, and opens a diff against the selected one.
+pub struct FileHistoryTimeline {
+    picker: Entity<Picker<FileHistoryTimelineDelegate>>,
+    _load_versions: Task<()>,
+}
+
+impl FileHistoryTimeline {
+    pub(crate) fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &OpenFileHistory, window, cx| {
+            Self::toggle(workspace, window, cx);
+        });
+    }
+
+    fn toggle(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<()> {
+        let buffer = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()?;
+        let abs_path = buffer.read(cx).file()?.as_local()?.abs_path(cx);
+        let workspace_handle = cx.entity().downgrade();
+
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            FileHistoryTimeline::new(workspace_handle, buffer, abs_path, window, cx)
+        });
+        Some(())
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        buffer: Entity<Buffer>,
+        abs_path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = FileHistoryTimelineDelegate::new(cx.entity().downgrade(), workspace, buffer);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        let load_versions = cx
+            .background_executor()
+            .spawn(async move { FILE_HISTORY_DB.versions(abs_path) });
+        let picker_handle = picker.downgrade();
+        let _load_versions = cx.spawn_in(window, |_, mut cx| async move {
+            let versions = load_versions.await.log_err().unwrap_or_default();
+            picker_handle
+                .update_in(&mut cx, |picker, window, cx| {
+                    picker.delegate.set_versions(versions);
+                    picker.refresh(window, cx);
+                })
+                .log_err();
+        });
+
+        Self {
+            picker,
+            _load_versions,
+        }
+    }
+}
+
+impl Render for FileHistoryTimeline {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl Focusable for FileHistoryTimeline {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for FileHistoryTimeline {}
+impl ModalView for FileHistoryTimeline {}
+
+struct TimelineEntry {
+    version: FileVersion,
+    relative_time: String,
+    absolute_time: String,
+}
+
+pub struct FileHistoryTimelineDelegate {
+    timeline: WeakEntity<FileHistoryTimeline>,
+    workspace: WeakEntity<Workspace>,
+    buffer: Entity<Buffer>,
+    /// `None` until the versions have been loaded.
+    entries: Option<Vec<TimelineEntry>>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl FileHistoryTimelineDelegate {
+    fn new(
+        timeline: WeakEntity<FileHistoryTimeline>,
+        workspace: WeakEntity<Workspace>,
+        buffer: Entity<Buffer>,
+    ) -> Self {
+        Self {
+            timeline,
+            workspace,
+            buffer,
+            entries: None,
+            candidates: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn set_versions(&mut self, versions: Vec<FileVersion>) {
+        let entries = versions
+            .into_iter()
+            .map(|version| TimelineEntry {
+                relative_time: format_recorded_at(version.recorded_at, TimestampFormat::Relative),
+                absolute_time: format_recorded_at(
+                    version.recorded_at,
+                    TimestampFormat::EnhancedAbsolute,
+                ),
+                version,
+            })
+            .collect::<Vec<_>>();
+        self.candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| {
+                StringMatchCandidate::new(
+                    id,
+                    &format!("{} {}", entry.version.source.label(), entry.absolute_time),
+                )
+            })
+            .collect();
+        self.entries = Some(entries);
+    }
+
+    fn entry(&self, ix: usize) -> Option<&TimelineEntry> {
+        self.entries
+            .as_ref()?
+            .get(self.matches.get(ix)?.candidate_id)
+    }
+}
+
+impl PickerDelegate for FileHistoryTimelineDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search file history…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> SharedString {
+        match &self.entries {
+            None => "Loading file history…".into(),
+            Some(entries) if entries.is_empty() => "No history recorded for this file".into(),
+            Some(_) => "No matches".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, |this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the versions in chronological order rather than by score.
+                matches.sort_unstable_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(&mut cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.entry(self.selected_index) else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        if let Some(contents) = FILE_HISTORY_DB
+            .version_contents(entry.version.id)
+            .log_err()
+            .flatten()
+        {
+            let buffer = self.buffer.clone();
+            let version = entry.version.clone();
+            let recorded_at = entry.absolute_time.clone();
+            workspace.update(cx, |workspace, cx| {
+                let project = workspace.project().clone();
+                let diff = cx.new(|cx| {
+                    FileHistoryDiff::new(
+                        buffer,
+                        version,
+                        recorded_at,
+                        contents,
+                        project,
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(diff), None, true, window, cx);
+            });
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.timeline
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.entry(ix)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .child(Label::new(entry.version.source.label()))
+                        .child(
+                            Label::new(entry.absolute_time.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(div().flex_1())
+                        .child(
+                            Label::new(entry.relative_time.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
@@ -106,7 +106,12 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
-    async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
+    async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()> {
+        self.atomic_write_bytes(path, text.into_bytes()).await
+    }
+    /// Writes the data to a temporary file and moves it into place, so that the file at `path`
+    /// is never left partially written.
+    async fn atomic_write_bytes(&self, path: PathBuf, data: Vec<u8>) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
@@ -518,7 +523,7 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
-    async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
+    async fn atomic_write_bytes(&self, path: PathBuf, data: Vec<u8>) -> Result<()> {
         smol::unblock(move || {
             let mut tmp_file = if cfg!(any(target_os = "linux", target_os = "freebsd")) {
                 // Use the directory of the destination as temp dir to avoid
@@ -538,7 +543,7 @@ impl Fs for RealFs {
             } else {
                 NamedTempFile::new()
             }?;
-            tmp_file.write_all(data.as_bytes())?;
+            tmp_file.write_all(&data)?;
             tmp_file.persist(path)?;
             Ok::<(), anyhow::Error>(())
         })
@@ -1281,6 +1286,17 @@ impl FakeFs {
         });
     }
 
+    pub fn set_head_for_repo(&self, dot_git: &Path, head_state: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.head_contents.clear();
+            state.head_contents.extend(
+                head_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -1773,10 +1789,10 @@ impl Fs for FakeFs {
         self.load_internal(path).await
     }
 
-    async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
+    async fn atomic_write_bytes(&self, path: PathBuf, data: Vec<u8>) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
-        self.write_file_internal(path, data.into_bytes())?;
+        self.write_file_internal(path, data)?;
         Ok(())
     }
 
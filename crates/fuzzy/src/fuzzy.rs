@@ -5,6 +5,7 @@ mod strings;
 
 pub use char_bag::CharBag;
 pub use paths::{
-    match_fixed_path_set, match_path_sets, PathMatch, PathMatchCandidate, PathMatchCandidateSet,
+    match_fixed_path_set, match_path_sets, match_path_sets_with_boost, PathMatch,
+    PathMatchCandidate, PathMatchCandidateSet,
 };
 pub use strings::{match_strings, StringMatch, StringMatchCandidate};
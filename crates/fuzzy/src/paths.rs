@@ -127,6 +127,37 @@ pub async fn match_path_sets<'a, Set: PathMatchCandidateSet<'a>>(
     cancel_flag: &AtomicBool,
     executor: BackgroundExecutor,
 ) -> Vec<PathMatch> {
+    match_path_sets_with_boost(
+        candidate_sets,
+        query,
+        relative_to,
+        smart_case,
+        max_results,
+        cancel_flag,
+        executor,
+        |_, _, score| score,
+    )
+    .await
+}
+
+/// Like [`match_path_sets`], but replaces the score of each match with the result of calling
+/// `boost` with the match's worktree ID, path and score, before the best `max_results` matches
+/// are kept.
+#[allow(clippy::too_many_arguments)]
+pub async fn match_path_sets_with_boost<'a, Set, F>(
+    candidate_sets: &'a [Set],
+    query: &str,
+    relative_to: Option<Arc<Path>>,
+    smart_case: bool,
+    max_results: usize,
+    cancel_flag: &AtomicBool,
+    executor: BackgroundExecutor,
+    boost: F,
+) -> Vec<PathMatch>
+where
+    Set: PathMatchCandidateSet<'a>,
+    F: Fn(usize, &Path, f64) -> f64 + Sync,
+{
     let path_count: usize = candidate_sets.iter().map(|s| s.len()).sum();
     if path_count == 0 {
         return Vec::new();
@@ -138,6 +169,7 @@ pub async fn match_path_sets<'a, Set: PathMatchCandidateSet<'a>>(
     let lowercase_query = &lowercase_query;
     let query = &query;
     let query_char_bag = CharBag::from(&lowercase_query[..]);
+    let boost = &boost;
 
     let num_cpus = executor.num_cpus().min(path_count);
     let segment_size = (path_count + num_cpus - 1) / num_cpus;
@@ -181,7 +213,7 @@ pub async fn match_path_sets<'a, Set: PathMatchCandidateSet<'a>>(
                                 results,
                                 cancel_flag,
                                 |candidate, score, positions| PathMatch {
-                                    score,
+                                    score: boost(worktree_id, candidate.path, score),
                                     worktree_id,
                                     positions: positions.clone(),
                                     path: Arc::from(candidate.path),
@@ -235,12 +267,91 @@ fn distance_between_paths(path: &Path, relative_to: &Path) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use std::path::Path;
+    use std::path::PathBuf;
 
-    use super::distance_between_paths;
+    use gpui::TestAppContext;
+
+    use super::*;
 
     #[test]
     fn test_distance_between_paths_empty() {
         distance_between_paths(Path::new(""), Path::new(""));
     }
+
+    #[gpui::test]
+    async fn test_match_path_sets_with_boost(cx: &mut TestAppContext) {
+        let candidate_sets = [TestCandidateSet {
+            paths: vec![PathBuf::from("file.rs"), PathBuf::from("f/i/l/e.rs")],
+        }];
+        let cancel_flag = AtomicBool::new(false);
+
+        let matches = match_path_sets(
+            &candidate_sets,
+            "file",
+            None,
+            false,
+            1,
+            &cancel_flag,
+            cx.executor(),
+        )
+        .await;
+        assert_eq!(match_paths(&matches), ["file.rs"]);
+
+        // The boost applies before the matches are truncated, so a boosted match that scores
+        // lower by itself is still kept.
+        let matches = match_path_sets_with_boost(
+            &candidate_sets,
+            "file",
+            None,
+            false,
+            1,
+            &cancel_flag,
+            cx.executor(),
+            |_, path, score| {
+                if path == Path::new("f/i/l/e.rs") {
+                    score + 1.0
+                } else {
+                    score
+                }
+            },
+        )
+        .await;
+        assert_eq!(match_paths(&matches), ["f/i/l/e.rs"]);
+    }
+
+    fn match_paths(matches: &[PathMatch]) -> Vec<&Path> {
+        matches.iter().map(|path_match| &*path_match.path).collect()
+    }
+
+    struct TestCandidateSet {
+        paths: Vec<PathBuf>,
+    }
+
+    impl<'a> PathMatchCandidateSet<'a> for TestCandidateSet {
+        type Candidates = std::vec::IntoIter<PathMatchCandidate<'a>>;
+
+        fn id(&self) -> usize {
+            0
+        }
+
+        fn len(&self) -> usize {
+            self.paths.len()
+        }
+
+        fn prefix(&self) -> Arc<str> {
+            Arc::default()
+        }
+
+        fn candidates(&'a self, start: usize) -> Self::Candidates {
+            self.paths[start..]
+                .iter()
+                .map(|path| PathMatchCandidate {
+                    is_dir: false,
+                    path,
+                    char_bag: CharBag::from(path.to_string_lossy().to_lowercase().as_str()),
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+        }
+    }
 }
@@ -0,0 +1,78 @@
+use gpui::{
+    div, prelude::*, px, rgb, size, App, Application, Bounds, Context, GridTrackSize, Window,
+    WindowBounds, WindowOptions,
+};
+
+struct GridExample;
+
+fn cell(label: &'static str, color: u32) -> gpui::Div {
+    div()
+        .flex()
+        .items_center()
+        .justify_center()
+        .rounded_md()
+        .bg(rgb(color))
+        .child(label)
+}
+
+impl Render for GridExample {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .size_full()
+            .bg(rgb(0x505050))
+            .text_color(rgb(0xffffff))
+            // A page layout with named areas.
+            .child(
+                div()
+                    .grid()
+                    .h(px(240.))
+                    .gap_2()
+                    .grid_template_cols([
+                        GridTrackSize::definite(px(120.)),
+                        GridTrackSize::flex(1.),
+                    ])
+                    .grid_template_rows([
+                        GridTrackSize::auto(),
+                        GridTrackSize::flex(1.),
+                        GridTrackSize::auto(),
+                    ])
+                    .grid_template_areas(["header header", "sidebar main", "footer footer"])
+                    .child(cell("Header", 0x3b82f6).grid_area("header").p_2())
+                    .child(cell("Sidebar", 0x10b981).grid_area("sidebar"))
+                    .child(cell("Main", 0x6366f1).grid_area("main"))
+                    .child(cell("Footer", 0xf59e0b).grid_area("footer").p_2()),
+            )
+            // Equal columns, with items spanning several tracks.
+            .child(
+                div()
+                    .grid()
+                    .grid_cols(4)
+                    .grid_auto_rows([GridTrackSize::definite(px(48.))])
+                    .gap_2()
+                    .child(cell("1", 0xef4444).col_span(2))
+                    .child(cell("2", 0xf97316))
+                    .child(cell("3", 0xeab308).row_span(2))
+                    .child(cell("4", 0x22c55e))
+                    .child(cell("5", 0x14b8a6).col_span(2))
+                    .child(cell("6", 0x8b5cf6).col_span_full()),
+            )
+    }
+}
+
+fn main() {
+    Application::new().run(|cx: &mut App| {
+        let bounds = Bounds::centered(None, size(px(600.), px(500.0)), cx);
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                ..Default::default()
+            },
+            |_, cx| cx.new(|_| GridExample),
+        )
+        .unwrap();
+    });
+}
@@ -15,8 +15,8 @@ use collections::HashSet;
 use refineable::Refineable;
 use smallvec::SmallVec;
 pub use taffy::style::{
-    AlignContent, AlignItems, AlignSelf, Display, FlexDirection, FlexWrap, JustifyContent,
-    Overflow, Position,
+    AlignContent, AlignItems, AlignSelf, Display, FlexDirection, FlexWrap, GridAutoFlow,
+    JustifyContent, JustifyItems, JustifySelf, Overflow, Position,
 };
 
 /// Use this struct for interfacing with the 'debug_below' styling from your own elements.
@@ -200,7 +200,11 @@ pub struct Style {
     pub align_content: Option<AlignContent>,
     /// How should contained within this item be aligned in the main/inline axis
     pub justify_content: Option<JustifyContent>,
-    /// How large should the gaps between items in a flex container be?
+    /// How should this node's children be aligned in the inline axis of their grid area?
+    pub justify_items: Option<JustifyItems>,
+    /// How should this node be aligned in the inline axis of its grid area? Falls back to the parents [`JustifyItems`] if not set
+    pub justify_self: Option<JustifySelf>,
+    /// How large should the gaps between items in a flex or grid container be?
     #[refineable]
     pub gap: Size<DefiniteLength>,
 
@@ -216,6 +220,30 @@ pub struct Style {
     /// The relative rate at which this item shrinks when it is contracting to fit into space, 1.0 is the default value, and this value must be positive.
     pub flex_shrink: f32,
 
+    // Grid properties
+    /// The sizes of the explicit rows of a grid container
+    pub grid_template_rows: Vec<GridTemplateTrack>,
+    /// The sizes of the explicit columns of a grid container
+    pub grid_template_columns: Vec<GridTemplateTrack>,
+    /// The named areas of a grid container, as one string per row
+    pub grid_template_areas: Vec<SharedString>,
+    /// The sizes of rows that are created implicitly, because items were placed outside of the explicit grid
+    pub grid_auto_rows: Vec<GridTrackSize>,
+    /// The sizes of columns that are created implicitly, because items were placed outside of the explicit grid
+    pub grid_auto_columns: Vec<GridTrackSize>,
+    /// How are items that aren't explicitly placed laid out in a grid container?
+    pub grid_auto_flow: GridAutoFlow,
+    /// Where does this item's grid area start in its grid container's rows?
+    pub grid_row_start: GridPlacement,
+    /// Where does this item's grid area end in its grid container's rows?
+    pub grid_row_end: GridPlacement,
+    /// Where does this item's grid area start in its grid container's columns?
+    pub grid_column_start: GridPlacement,
+    /// Where does this item's grid area end in its grid container's columns?
+    pub grid_column_end: GridPlacement,
+    /// The named area of its grid container that this item should be placed in. Takes precedence over the row and column placement.
+    pub grid_area: Option<SharedString>,
+
     /// The fill color of this element
     pub background: Option<Fill>,
 
@@ -253,6 +281,145 @@ impl Styled for StyleRefinement {
     }
 }
 
+/// The minimum or maximum size of a grid track, used by [`GridTrackSize`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridTrackBreadth {
+    /// A fixed length, or a fraction of the grid container's size.
+    Definite(DefiniteLength),
+    /// A share of the grid container's free space, similar to the CSS `fr` unit.
+    /// Only valid as a maximum, and treated as `Auto` when used as a minimum.
+    Flex(f32),
+    /// The size of the track's content, within the available space.
+    Auto,
+    /// The largest minimum content size of the track's items.
+    MinContent,
+    /// The largest maximum content size of the track's items.
+    MaxContent,
+}
+
+/// The size of a row or column in a grid layout, similar to a value of the CSS
+/// `grid-template-columns` property.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridTrackSize {
+    /// The smallest size the track can shrink to.
+    pub min: GridTrackBreadth,
+    /// The largest size the track can grow to.
+    pub max: GridTrackBreadth,
+}
+
+impl GridTrackSize {
+    /// A track of a fixed length, or a fraction of the grid container's size.
+    pub fn definite(length: impl Into<DefiniteLength>) -> Self {
+        Self::from(GridTrackBreadth::Definite(length.into()))
+    }
+
+    /// A track that takes a share of the free space, similar to the CSS `fr` unit.
+    pub fn flex(fraction: f32) -> Self {
+        Self::from(GridTrackBreadth::Flex(fraction))
+    }
+
+    /// A track sized to fit its content.
+    pub fn auto() -> Self {
+        Self::from(GridTrackBreadth::Auto)
+    }
+
+    /// A track sized to the smallest size of its content.
+    pub fn min_content() -> Self {
+        Self::from(GridTrackBreadth::MinContent)
+    }
+
+    /// A track sized to the largest size of its content.
+    pub fn max_content() -> Self {
+        Self::from(GridTrackBreadth::MaxContent)
+    }
+
+    /// A track that's sized between `min` and `max`, similar to the CSS `minmax()` function.
+    pub fn min_max(min: GridTrackBreadth, max: GridTrackBreadth) -> Self {
+        Self { min, max }
+    }
+}
+
+impl From<GridTrackBreadth> for GridTrackSize {
+    fn from(breadth: GridTrackBreadth) -> Self {
+        let min = match breadth {
+            GridTrackBreadth::Flex(_) => GridTrackBreadth::Auto,
+            breadth => breadth,
+        };
+        Self { min, max: breadth }
+    }
+}
+
+/// How many times the tracks in a [`GridTemplateTrack::Repeat`] are repeated, similar to the
+/// first argument of the CSS `repeat()` function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridRepetition {
+    /// Repeat the tracks this many times.
+    Count(u16),
+    /// Repeat the tracks as many times as fit in the grid container.
+    AutoFill,
+    /// Like `AutoFill`, but tracks that end up empty are collapsed.
+    AutoFit,
+}
+
+/// An entry in a grid container's template rows or columns.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridTemplateTrack {
+    /// A single track.
+    Single(GridTrackSize),
+    /// A list of tracks that's repeated, similar to the CSS `repeat()` function.
+    Repeat(GridRepetition, Vec<GridTrackSize>),
+}
+
+impl From<GridTrackSize> for GridTemplateTrack {
+    fn from(size: GridTrackSize) -> Self {
+        Self::Single(size)
+    }
+}
+
+/// Where a grid item's area starts or ends, similar to the CSS `grid-row-start` property
+/// and related properties.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GridPlacement {
+    /// Place the item automatically, according to the grid container's auto flow.
+    #[default]
+    Auto,
+    /// A grid line, counting from 1 at the start of the explicit grid, or from -1 at its end.
+    Line(i16),
+    /// Span this many tracks from the other edge of the item's area.
+    Span(u16),
+}
+
+/// Returns the lines that bound the area called `name` in a grid container's template areas,
+/// as the row and column line ranges.
+///
+/// Returns `None` if there is no area with that name or if its cells don't form a rectangle.
+pub(crate) fn grid_area_lines(
+    template_areas: &[SharedString],
+    name: &str,
+) -> Option<(Range<i16>, Range<i16>)> {
+    let mut rows: Option<Range<i16>> = None;
+    let mut columns: Option<Range<i16>> = None;
+    let mut cell_count = 0;
+    for (row, areas) in template_areas.iter().enumerate() {
+        for (column, area) in areas.split_whitespace().enumerate() {
+            if area != name {
+                continue;
+            }
+            let (row, column) = (row as i16 + 1, column as i16 + 1);
+            let rows = rows.get_or_insert(row..row + 1);
+            rows.start = rows.start.min(row);
+            rows.end = rows.end.max(row + 1);
+            let columns = columns.get_or_insert(column..column + 1);
+            columns.start = columns.start.min(column);
+            columns.end = columns.end.max(column + 1);
+            cell_count += 1;
+        }
+    }
+    let (rows, columns) = (rows?, columns?);
+    let area = (rows.end - rows.start) * (columns.end - columns.start);
+    (area == cell_count).then_some((rows, columns))
+}
+
 /// The value of the visibility property, similar to the CSS property `visibility`
 #[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Visibility {
@@ -699,12 +866,26 @@ impl Default for Style {
             align_self: None,
             align_content: None,
             justify_content: None,
+            justify_items: None,
+            justify_self: None,
             // Flexbox
             flex_direction: FlexDirection::Row,
             flex_wrap: FlexWrap::NoWrap,
             flex_grow: 0.0,
             flex_shrink: 1.0,
             flex_basis: Length::Auto,
+            // Grid
+            grid_template_rows: Vec::new(),
+            grid_template_columns: Vec::new(),
+            grid_template_areas: Vec::new(),
+            grid_auto_rows: Vec::new(),
+            grid_auto_columns: Vec::new(),
+            grid_auto_flow: GridAutoFlow::Row,
+            grid_row_start: GridPlacement::Auto,
+            grid_row_end: GridPlacement::Auto,
+            grid_column_start: GridPlacement::Auto,
+            grid_column_end: GridPlacement::Auto,
+            grid_area: None,
             background: None,
             border_color: None,
             corner_radii: Corners::default(),
@@ -1031,4 +1212,22 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_grid_area_lines() {
+        let areas = [
+            SharedString::from("header header header"),
+            SharedString::from("sidebar main main"),
+            SharedString::from("sidebar main main"),
+            SharedString::from(".  footer ."),
+        ];
+        assert_eq!(grid_area_lines(&areas, "header"), Some((1..2, 1..4)));
+        assert_eq!(grid_area_lines(&areas, "sidebar"), Some((2..4, 1..2)));
+        assert_eq!(grid_area_lines(&areas, "main"), Some((2..4, 2..4)));
+        assert_eq!(grid_area_lines(&areas, "footer"), Some((4..5, 2..3)));
+        assert_eq!(grid_area_lines(&areas, "missing"), None);
+
+        let not_rectangular = [SharedString::from("a a"), SharedString::from("a b")];
+        assert_eq!(grid_area_lines(&not_rectangular, "a"), None);
+    }
 }
@@ -1,8 +1,9 @@
 use crate::TextStyleRefinement;
 use crate::{
     self as gpui, px, relative, rems, AbsoluteLength, AlignItems, CursorStyle, DefiniteLength,
-    Fill, FlexDirection, FlexWrap, Font, FontStyle, FontWeight, Hsla, JustifyContent, Length,
-    SharedString, StrikethroughStyle, StyleRefinement, TextOverflow, WhiteSpace,
+    Fill, FlexDirection, FlexWrap, Font, FontStyle, FontWeight, GridAutoFlow, GridPlacement,
+    GridRepetition, GridTemplateTrack, GridTrackBreadth, GridTrackSize, Hsla, JustifyContent,
+    Length, SharedString, StrikethroughStyle, StyleRefinement, TextOverflow, WhiteSpace,
 };
 pub use gpui_macros::{
     border_style_methods, box_shadow_style_methods, cursor_style_methods, margin_style_methods,
@@ -43,6 +44,13 @@ pub trait Styled: Sized {
         self
     }
 
+    /// Sets the display type of the element to `grid`.
+    /// [Docs](https://tailwindcss.com/docs/display#grid)
+    fn grid(mut self) -> Self {
+        self.style().display = Some(Display::Grid);
+        self
+    }
+
     /// Sets the whitespace of the element to `normal`.
     /// [Docs](https://tailwindcss.com/docs/whitespace#normal)
     fn whitespace_normal(mut self) -> Self {
@@ -328,6 +336,198 @@ pub trait Styled: Sized {
         self
     }
 
+    /// Sets the element to align grid items against the start of their grid areas' inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-items#start)
+    fn justify_items_start(mut self) -> Self {
+        self.style().justify_items = Some(AlignItems::Start);
+        self
+    }
+
+    /// Sets the element to align grid items against the end of their grid areas' inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-items#end)
+    fn justify_items_end(mut self) -> Self {
+        self.style().justify_items = Some(AlignItems::End);
+        self
+    }
+
+    /// Sets the element to align grid items along the center of their grid areas' inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-items#center)
+    fn justify_items_center(mut self) -> Self {
+        self.style().justify_items = Some(AlignItems::Center);
+        self
+    }
+
+    /// Sets the element to stretch grid items to fill their grid areas' inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-items#stretch)
+    fn justify_items_stretch(mut self) -> Self {
+        self.style().justify_items = Some(AlignItems::Stretch);
+        self
+    }
+
+    /// Sets the element's grid to have the given number of equally sized columns.
+    /// [Docs](https://tailwindcss.com/docs/grid-template-columns)
+    fn grid_cols(mut self, columns: u16) -> Self {
+        self.style().grid_template_columns = Some(equal_grid_tracks(columns));
+        self
+    }
+
+    /// Sets the element's grid to have the given number of equally sized rows.
+    /// [Docs](https://tailwindcss.com/docs/grid-template-rows)
+    fn grid_rows(mut self, rows: u16) -> Self {
+        self.style().grid_template_rows = Some(equal_grid_tracks(rows));
+        self
+    }
+
+    /// Sets the sizes of the columns of the element's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-template-columns#arbitrary-values)
+    fn grid_template_cols(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<GridTemplateTrack>>,
+    ) -> Self {
+        self.style().grid_template_columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the sizes of the rows of the element's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-template-rows#arbitrary-values)
+    fn grid_template_rows(
+        mut self,
+        rows: impl IntoIterator<Item = impl Into<GridTemplateTrack>>,
+    ) -> Self {
+        self.style().grid_template_rows = Some(rows.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Names the areas of the element's grid, with one string per row of whitespace-separated
+    /// area names, like the CSS `grid-template-areas` property. Cells can be left unnamed with `.`.
+    /// Children are placed in an area with [`Styled::grid_area`].
+    fn grid_template_areas(
+        mut self,
+        rows: impl IntoIterator<Item = impl Into<SharedString>>,
+    ) -> Self {
+        self.style().grid_template_areas = Some(rows.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the sizes of the columns that are implicitly created in the element's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-columns)
+    fn grid_auto_cols(mut self, columns: impl IntoIterator<Item = GridTrackSize>) -> Self {
+        self.style().grid_auto_columns = Some(columns.into_iter().collect());
+        self
+    }
+
+    /// Sets the sizes of the rows that are implicitly created in the element's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-rows)
+    fn grid_auto_rows(mut self, rows: impl IntoIterator<Item = GridTrackSize>) -> Self {
+        self.style().grid_auto_rows = Some(rows.into_iter().collect());
+        self
+    }
+
+    /// Sets the element's grid to place items that aren't explicitly placed by filling each row in turn.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-flow)
+    fn grid_flow_row(mut self) -> Self {
+        self.style().grid_auto_flow = Some(GridAutoFlow::Row);
+        self
+    }
+
+    /// Sets the element's grid to place items that aren't explicitly placed by filling each column in turn.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-flow)
+    fn grid_flow_col(mut self) -> Self {
+        self.style().grid_auto_flow = Some(GridAutoFlow::Column);
+        self
+    }
+
+    /// Sets the element's grid to place items that aren't explicitly placed by filling rows,
+    /// filling in earlier holes when smaller items come up later.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-flow)
+    fn grid_flow_row_dense(mut self) -> Self {
+        self.style().grid_auto_flow = Some(GridAutoFlow::RowDense);
+        self
+    }
+
+    /// Sets the element's grid to place items that aren't explicitly placed by filling columns,
+    /// filling in earlier holes when smaller items come up later.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-flow)
+    fn grid_flow_col_dense(mut self) -> Self {
+        self.style().grid_auto_flow = Some(GridAutoFlow::ColumnDense);
+        self
+    }
+
+    /// Sets the element to span the given number of columns of its grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-column#spanning-columns)
+    fn col_span(mut self, columns: u16) -> Self {
+        let style = self.style();
+        style.grid_column_start = Some(GridPlacement::Span(columns));
+        style.grid_column_end = Some(GridPlacement::Auto);
+        self
+    }
+
+    /// Sets the element to span all columns of its grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-column#spanning-columns)
+    fn col_span_full(mut self) -> Self {
+        let style = self.style();
+        style.grid_column_start = Some(GridPlacement::Line(1));
+        style.grid_column_end = Some(GridPlacement::Line(-1));
+        self
+    }
+
+    /// Sets the grid line that the element starts at in its grid's columns, counting from 1,
+    /// or from -1 at the end.
+    /// [Docs](https://tailwindcss.com/docs/grid-column#starting-and-ending-lines)
+    fn col_start(mut self, line: i16) -> Self {
+        self.style().grid_column_start = Some(GridPlacement::Line(line));
+        self
+    }
+
+    /// Sets the grid line that the element ends at in its grid's columns, counting from 1,
+    /// or from -1 at the end.
+    /// [Docs](https://tailwindcss.com/docs/grid-column#starting-and-ending-lines)
+    fn col_end(mut self, line: i16) -> Self {
+        self.style().grid_column_end = Some(GridPlacement::Line(line));
+        self
+    }
+
+    /// Sets the element to span the given number of rows of its grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-row#spanning-rows)
+    fn row_span(mut self, rows: u16) -> Self {
+        let style = self.style();
+        style.grid_row_start = Some(GridPlacement::Span(rows));
+        style.grid_row_end = Some(GridPlacement::Auto);
+        self
+    }
+
+    /// Sets the element to span all rows of its grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-row#spanning-rows)
+    fn row_span_full(mut self) -> Self {
+        let style = self.style();
+        style.grid_row_start = Some(GridPlacement::Line(1));
+        style.grid_row_end = Some(GridPlacement::Line(-1));
+        self
+    }
+
+    /// Sets the grid line that the element starts at in its grid's rows, counting from 1,
+    /// or from -1 at the end.
+    /// [Docs](https://tailwindcss.com/docs/grid-row#starting-and-ending-lines)
+    fn row_start(mut self, line: i16) -> Self {
+        self.style().grid_row_start = Some(GridPlacement::Line(line));
+        self
+    }
+
+    /// Sets the grid line that the element ends at in its grid's rows, counting from 1,
+    /// or from -1 at the end.
+    /// [Docs](https://tailwindcss.com/docs/grid-row#starting-and-ending-lines)
+    fn row_end(mut self, line: i16) -> Self {
+        self.style().grid_row_end = Some(GridPlacement::Line(line));
+        self
+    }
+
+    /// Places the element in the area of its grid with the given name, as named by
+    /// [`Styled::grid_template_areas`].
+    fn grid_area(mut self, name: impl Into<SharedString>) -> Self {
+        self.style().grid_area = Some(name.into());
+        self
+    }
+
     /// Sets the background color of the element.
     fn bg<F>(mut self, fill: F) -> Self
     where
@@ -611,3 +811,15 @@ pub trait Styled: Sized {
         self
     }
 }
+
+/// Returns tracks that share a grid equally, like Tailwind's `grid-cols-<n>`. Tracks can shrink
+/// below the size of their content, so that wide items don't make their track wider.
+fn equal_grid_tracks(count: u16) -> Vec<GridTemplateTrack> {
+    vec![GridTemplateTrack::Repeat(
+        GridRepetition::Count(count),
+        vec![GridTrackSize::min_max(
+            GridTrackBreadth::Definite(px(0.).into()),
+            GridTrackBreadth::Flex(1.),
+        )],
+    )]
+}
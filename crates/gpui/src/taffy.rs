@@ -1,12 +1,15 @@
 use crate::{
-    AbsoluteLength, App, Bounds, DefiniteLength, Edges, Length, Pixels, Point, Size, Style, Window,
+    style::grid_area_lines, AbsoluteLength, App, Bounds, DefiniteLength, Edges, GridPlacement,
+    GridRepetition, GridTemplateTrack, GridTrackBreadth, GridTrackSize, Length, Pixels, Point,
+    SharedString, Size, Style, Window,
 };
 use collections::{FxHashMap, FxHashSet};
 use smallvec::SmallVec;
 use std::fmt::Debug;
 use taffy::{
-    geometry::{Point as TaffyPoint, Rect as TaffyRect, Size as TaffySize},
+    geometry::{Line as TaffyLine, Point as TaffyPoint, Rect as TaffyRect, Size as TaffySize},
     style::AvailableSpace as TaffyAvailableSpace,
+    style_helpers::{line, span},
     tree::NodeId,
     TaffyTree, TraversePartialTree as _,
 };
@@ -22,6 +25,9 @@ pub struct TaffyLayoutEngine {
     taffy: TaffyTree<NodeContext>,
     absolute_layout_bounds: FxHashMap<LayoutId, Bounds<Pixels>>,
     computed_layouts: FxHashSet<LayoutId>,
+    /// The named grid areas of nodes, which are resolved to lines once their parent's
+    /// template areas are known.
+    grid_areas: FxHashMap<LayoutId, SharedString>,
 }
 
 const EXPECT_MESSAGE: &str = "we should avoid taffy layout errors by construction if possible";
@@ -32,6 +38,7 @@ impl TaffyLayoutEngine {
             taffy: TaffyTree::new(),
             absolute_layout_bounds: FxHashMap::default(),
             computed_layouts: FxHashSet::default(),
+            grid_areas: FxHashMap::default(),
         }
     }
 
@@ -39,6 +46,7 @@ impl TaffyLayoutEngine {
         self.taffy.clear();
         self.absolute_layout_bounds.clear();
         self.computed_layouts.clear();
+        self.grid_areas.clear();
     }
 
     pub fn request_layout(
@@ -48,6 +56,9 @@ impl TaffyLayoutEngine {
         children: &[LayoutId],
     ) -> LayoutId {
         let taffy_style = style.to_taffy(rem_size);
+        if !style.grid_template_areas.is_empty() {
+            self.place_in_grid_areas(&style.grid_template_areas, children);
+        }
         let layout_id = if children.is_empty() {
             self.taffy
                 .new_leaf(taffy_style)
@@ -64,9 +75,36 @@ impl TaffyLayoutEngine {
                 .into();
             parent_id
         };
+        if let Some(grid_area) = style.grid_area {
+            self.grid_areas.insert(layout_id, grid_area);
+        }
         layout_id
     }
 
+    /// Places the children that name a grid area on the lines of that area.
+    fn place_in_grid_areas(&mut self, template_areas: &[SharedString], children: &[LayoutId]) {
+        for child in children {
+            let Some(grid_area) = self.grid_areas.get(child) else {
+                continue;
+            };
+            let Some((rows, columns)) = grid_area_lines(template_areas, grid_area) else {
+                continue;
+            };
+            let mut child_style = self.taffy.style(child.0).expect(EXPECT_MESSAGE).clone();
+            child_style.grid_row = TaffyLine {
+                start: line(rows.start),
+                end: line(rows.end),
+            };
+            child_style.grid_column = TaffyLine {
+                start: line(columns.start),
+                end: line(columns.end),
+            };
+            self.taffy
+                .set_style(child.0, child_style)
+                .expect(EXPECT_MESSAGE);
+        }
+    }
+
     pub fn request_measured_layout(
         &mut self,
         style: Style,
@@ -76,7 +114,7 @@ impl TaffyLayoutEngine {
     ) -> LayoutId {
         let taffy_style = style.to_taffy(rem_size);
 
-        let layout_id = self
+        let layout_id: LayoutId = self
             .taffy
             .new_leaf_with_context(
                 taffy_style,
@@ -86,6 +124,9 @@ impl TaffyLayoutEngine {
             )
             .expect(EXPECT_MESSAGE)
             .into();
+        if let Some(grid_area) = style.grid_area {
+            self.grid_areas.insert(layout_id, grid_area);
+        }
         layout_id
     }
 
@@ -261,13 +302,106 @@ impl ToTaffy<taffy::style::Style> for Style {
             align_self: self.align_self,
             align_content: self.align_content,
             justify_content: self.justify_content,
+            justify_items: self.justify_items,
+            justify_self: self.justify_self,
             gap: self.gap.to_taffy(rem_size),
             flex_direction: self.flex_direction,
             flex_wrap: self.flex_wrap,
             flex_basis: self.flex_basis.to_taffy(rem_size),
             flex_grow: self.flex_grow,
             flex_shrink: self.flex_shrink,
-            ..Default::default() // Ignore grid properties for now
+            grid_template_rows: self.grid_template_rows.to_taffy(rem_size),
+            grid_template_columns: self.grid_template_columns.to_taffy(rem_size),
+            grid_auto_rows: self.grid_auto_rows.to_taffy(rem_size),
+            grid_auto_columns: self.grid_auto_columns.to_taffy(rem_size),
+            grid_auto_flow: self.grid_auto_flow,
+            grid_row: TaffyLine {
+                start: self.grid_row_start.into(),
+                end: self.grid_row_end.into(),
+            },
+            grid_column: TaffyLine {
+                start: self.grid_column_start.into(),
+                end: self.grid_column_end.into(),
+            },
+        }
+    }
+}
+
+impl<T, U> ToTaffy<Vec<U>> for Vec<T>
+where
+    T: ToTaffy<U>,
+{
+    fn to_taffy(&self, rem_size: Pixels) -> Vec<U> {
+        self.iter().map(|item| item.to_taffy(rem_size)).collect()
+    }
+}
+
+impl ToTaffy<taffy::style::TrackSizingFunction> for GridTemplateTrack {
+    fn to_taffy(&self, rem_size: Pixels) -> taffy::style::TrackSizingFunction {
+        match self {
+            GridTemplateTrack::Single(size) => {
+                taffy::style::TrackSizingFunction::Single(size.to_taffy(rem_size))
+            }
+            GridTemplateTrack::Repeat(repetition, sizes) => {
+                let repetition = match repetition {
+                    GridRepetition::Count(count) => {
+                        taffy::style::GridTrackRepetition::Count(*count)
+                    }
+                    GridRepetition::AutoFill => taffy::style::GridTrackRepetition::AutoFill,
+                    GridRepetition::AutoFit => taffy::style::GridTrackRepetition::AutoFit,
+                };
+                taffy::style::TrackSizingFunction::Repeat(repetition, sizes.to_taffy(rem_size))
+            }
+        }
+    }
+}
+
+impl ToTaffy<taffy::style::NonRepeatedTrackSizingFunction> for GridTrackSize {
+    fn to_taffy(&self, rem_size: Pixels) -> taffy::style::NonRepeatedTrackSizingFunction {
+        taffy::geometry::MinMax {
+            min: self.min.to_taffy(rem_size),
+            max: self.max.to_taffy(rem_size),
+        }
+    }
+}
+
+impl ToTaffy<taffy::style::MinTrackSizingFunction> for GridTrackBreadth {
+    fn to_taffy(&self, rem_size: Pixels) -> taffy::style::MinTrackSizingFunction {
+        match self {
+            GridTrackBreadth::Definite(length) => {
+                taffy::style::MinTrackSizingFunction::Fixed(length.to_taffy(rem_size))
+            }
+            GridTrackBreadth::Flex(_) | GridTrackBreadth::Auto => {
+                taffy::style::MinTrackSizingFunction::Auto
+            }
+            GridTrackBreadth::MinContent => taffy::style::MinTrackSizingFunction::MinContent,
+            GridTrackBreadth::MaxContent => taffy::style::MinTrackSizingFunction::MaxContent,
+        }
+    }
+}
+
+impl ToTaffy<taffy::style::MaxTrackSizingFunction> for GridTrackBreadth {
+    fn to_taffy(&self, rem_size: Pixels) -> taffy::style::MaxTrackSizingFunction {
+        match self {
+            GridTrackBreadth::Definite(length) => {
+                taffy::style::MaxTrackSizingFunction::Fixed(length.to_taffy(rem_size))
+            }
+            GridTrackBreadth::Flex(fraction) => {
+                taffy::style::MaxTrackSizingFunction::Fraction(*fraction)
+            }
+            GridTrackBreadth::Auto => taffy::style::MaxTrackSizingFunction::Auto,
+            GridTrackBreadth::MinContent => taffy::style::MaxTrackSizingFunction::MinContent,
+            GridTrackBreadth::MaxContent => taffy::style::MaxTrackSizingFunction::MaxContent,
+        }
+    }
+}
+
+impl From<GridPlacement> for taffy::style::GridPlacement {
+    fn from(placement: GridPlacement) -> Self {
+        match placement {
+            GridPlacement::Auto => taffy::style::GridPlacement::Auto,
+            GridPlacement::Line(index) => line(index),
+            GridPlacement::Span(count) => span(count),
         }
     }
 }
@@ -0,0 +1,277 @@
+use std::ffi::OsStr;
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Task};
+use project::{Project, ProjectEntryId, ProjectItem, ProjectPath};
+use settings::Settings;
+use worktree::{File, LoadedBinaryFile};
+
+use crate::HexEditorSettings;
+
+pub enum BinaryFileEvent {
+    Edited,
+    Saved,
+    Reloaded,
+    FileHandleChanged,
+}
+
+/// A replacement of `old` bytes at `offset` with `new` bytes, recorded so it can be undone.
+struct Splice {
+    offset: usize,
+    old: Vec<u8>,
+    new: Vec<u8>,
+}
+
+/// The contents of a file loaded as raw bytes, which can be edited and written back.
+///
+/// The file is loaded and saved through its worktree, so it works the same way for local and
+/// remote projects.
+pub struct BinaryFile {
+    file: Arc<File>,
+    bytes: Vec<u8>,
+    undo_stack: Vec<Splice>,
+    redo_stack: Vec<Splice>,
+    is_dirty: bool,
+}
+
+impl EventEmitter<BinaryFileEvent> for BinaryFile {}
+
+impl BinaryFile {
+    pub fn open(
+        project: &Entity<Project>,
+        path: ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        Self::open_internal(project, path, false, cx)
+    }
+
+    /// Opens the file only if its contents aren't valid UTF-8, and so can't be opened as text.
+    fn open_if_binary(
+        project: &Entity<Project>,
+        path: ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        Self::open_internal(project, path, true, cx)
+    }
+
+    fn open_internal(
+        project: &Entity<Project>,
+        path: ProjectPath,
+        only_if_binary: bool,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let Some(worktree) = project.read(cx).worktree_for_id(path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("no such worktree")));
+        };
+        let load = worktree.update(cx, |worktree, cx| {
+            worktree.load_binary_file(path.path.as_ref(), cx)
+        });
+        cx.spawn(|mut cx| async move {
+            let LoadedBinaryFile { file, content } = load.await?;
+            if only_if_binary && std::str::from_utf8(&content).is_ok() {
+                return Err(anyhow!("{:?} is a text file", path.path));
+            }
+            cx.new(|_| Self {
+                file,
+                bytes: content,
+                undo_stack: Vec::new(),
+                redo_stack: Vec::new(),
+                is_dirty: false,
+            })
+        })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn file(&self) -> &Arc<File> {
+        &self.file
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+
+    /// Replaces the bytes in `range` with `new`.
+    pub fn splice(&mut self, range: Range<usize>, new: Vec<u8>, cx: &mut Context<Self>) {
+        let range = range.start.min(self.bytes.len())..range.end.min(self.bytes.len());
+        let old = self
+            .bytes
+            .splice(range.clone(), new.iter().copied())
+            .collect::<Vec<_>>();
+        if old == new {
+            return;
+        }
+        self.undo_stack.push(Splice {
+            offset: range.start,
+            old,
+            new,
+        });
+        self.redo_stack.clear();
+        self.edited(cx);
+    }
+
+    /// Replaces the bytes in `range` with `new` as part of the last edit, so that they're undone
+    /// together, when `range` covers the bytes written by the last edit.
+    pub fn splice_into_last_edit(
+        &mut self,
+        range: Range<usize>,
+        new: Vec<u8>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(last) = self
+            .undo_stack
+            .last_mut()
+            .filter(|last| last.offset == range.start && last.new.len() == range.len())
+        else {
+            self.splice(range, new, cx);
+            return;
+        };
+        self.bytes.splice(range, new.iter().copied());
+        last.new = new;
+        if last.new == last.old {
+            self.undo_stack.pop();
+        }
+        self.redo_stack.clear();
+        self.edited(cx);
+    }
+
+    /// Undoes the last edit, returning the offset at which it was made.
+    pub fn undo(&mut self, cx: &mut Context<Self>) -> Option<usize> {
+        let splice = self.undo_stack.pop()?;
+        let offset = splice.offset;
+        self.bytes.splice(
+            offset..offset + splice.new.len(),
+            splice.old.iter().copied(),
+        );
+        self.redo_stack.push(splice);
+        self.edited(cx);
+        Some(offset)
+    }
+
+    /// Redoes the last undone edit, returning the offset at which it was made.
+    pub fn redo(&mut self, cx: &mut Context<Self>) -> Option<usize> {
+        let splice = self.redo_stack.pop()?;
+        let offset = splice.offset;
+        self.bytes.splice(
+            offset..offset + splice.old.len(),
+            splice.new.iter().copied(),
+        );
+        self.undo_stack.push(splice);
+        self.edited(cx);
+        Some(offset)
+    }
+
+    fn edited(&mut self, cx: &mut Context<Self>) {
+        self.is_dirty = true;
+        cx.emit(BinaryFileEvent::Edited);
+        cx.notify();
+    }
+
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let path = self.file.path.clone();
+        self.write(path, cx)
+    }
+
+    pub fn save_as(&mut self, path: ProjectPath, cx: &mut Context<Self>) -> Task<Result<()>> {
+        if path.worktree_id != self.file.worktree_id(cx) {
+            return Task::ready(Err(anyhow!(
+                "binary files can only be saved in their own worktree"
+            )));
+        }
+        self.write(path.path, cx)
+    }
+
+    fn write(&mut self, path: Arc<std::path::Path>, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let content = self.bytes.clone();
+        let write = self.file.worktree.update(cx, |worktree, cx| {
+            worktree.write_binary_file(path.as_ref(), content, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let file = write.await?;
+            this.update(&mut cx, |this, cx| {
+                let path_changed = file.path != this.file.path;
+                this.file = file;
+                this.is_dirty = false;
+                cx.emit(BinaryFileEvent::Saved);
+                if path_changed {
+                    cx.emit(BinaryFileEvent::FileHandleChanged);
+                }
+                cx.notify();
+            })
+        })
+    }
+
+    /// Discards any edits and loads the file's contents from disk again.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let path = self.file.path.clone();
+        let load = self.file.worktree.update(cx, |worktree, cx| {
+            worktree.load_binary_file(path.as_ref(), cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let LoadedBinaryFile { file, content } = load.await?;
+            this.update(&mut cx, |this, cx| {
+                this.file = file;
+                this.bytes = content;
+                this.undo_stack.clear();
+                this.redo_stack.clear();
+                this.is_dirty = false;
+                cx.emit(BinaryFileEvent::Reloaded);
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl ProjectItem for BinaryFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let extension = path.path.extension().and_then(OsStr::to_str)?;
+        let settings = HexEditorSettings::get_global(cx);
+        if !settings
+            .file_extensions
+            .iter()
+            .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+        {
+            return None;
+        }
+        Some(Self::open(project, path.clone(), cx))
+    }
+
+    fn try_open_as_fallback(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        Some(Self::open_if_binary(project, path.clone(), cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.file.entry_id
+    }
+
+    fn project_path(&self, cx: &App) -> Option<ProjectPath> {
+        Some(ProjectPath {
+            worktree_id: self.file.worktree_id(cx),
+            path: self.file.path.clone(),
+        })
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+}
@@ -0,0 +1,831 @@
+mod binary_file;
+mod hex_editor_settings;
+mod prompt;
+
+use std::ops::Range;
+
+use editor::actions::{
+    Backspace, Delete, MoveDown, MoveLeft, MovePageDown, MovePageUp, MoveRight, MoveToBeginning,
+    MoveToEnd, MoveUp, Redo, Undo,
+};
+use file_icons::FileIcons;
+use gpui::{
+    actions, div, px, uniform_list, AnyElement, App, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, KeyDownEvent, MouseButton, Render, ScrollStrategy, Stateful, Task,
+    UniformListScrollHandle, Window,
+};
+use project::{Project, ProjectPath};
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent, ProjectItem},
+    notifications::DetachAndPromptErr,
+    ItemSettings, Workspace, WorkspaceId,
+};
+
+pub use binary_file::{BinaryFile, BinaryFileEvent};
+pub use hex_editor_settings::HexEditorSettings;
+use prompt::{HexEditorPrompt, PromptKind};
+
+actions!(
+    hex_editor,
+    [
+        OpenInHexEditor,
+        GoToOffset,
+        FindBytes,
+        FindNext,
+        FindPrevious,
+        ToggleInsertMode,
+        SwitchColumn,
+    ]
+);
+
+/// The number of rows moved by page up and page down.
+const PAGE_ROWS: usize = 32;
+
+pub fn init(cx: &mut App) {
+    HexEditorSettings::register(cx);
+    workspace::register_project_item::<HexEditor>(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(HexEditor::open_in_hex_editor);
+    })
+    .detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditMode {
+    Overwrite,
+    Insert,
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    TitleChanged,
+}
+
+/// Shows the bytes of a file as rows of offsets, hex values and ASCII characters, and allows
+/// them to be edited in place.
+pub struct HexEditor {
+    binary_file: Entity<BinaryFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// The offset of the byte under the cursor, which is the length of the file when the cursor
+    /// is past its last byte.
+    cursor: usize,
+    column: Column,
+    mode: EditMode,
+    /// Whether the high nibble of the byte under the cursor was just typed in the hex column.
+    typed_high_nibble: bool,
+    search_query: Vec<u8>,
+    active_match: Option<Range<usize>>,
+}
+
+impl HexEditor {
+    pub fn new(
+        binary_file: Entity<BinaryFile>,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        cx.subscribe(&binary_file, Self::on_binary_file_event)
+            .detach();
+        Self {
+            binary_file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            column: Column::Hex,
+            mode: EditMode::Overwrite,
+            typed_high_nibble: false,
+            search_query: Vec::new(),
+            active_match: None,
+        }
+    }
+
+    fn open_in_hex_editor(
+        workspace: &mut Workspace,
+        _: &OpenInHexEditor,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(project_path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        else {
+            return;
+        };
+        let pane = workspace.active_pane().clone();
+        let open = BinaryFile::open(workspace.project(), project_path, cx);
+        cx.spawn_in(window, |workspace, mut cx| async move {
+            let binary_file = open.await?;
+            workspace.update_in(&mut cx, |workspace, window, cx| {
+                workspace.open_project_item::<HexEditor>(pane, binary_file, true, true, window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to open hex editor", window, cx, |_, _, _| None);
+    }
+
+    fn on_binary_file_event(
+        &mut self,
+        _: Entity<BinaryFile>,
+        event: &BinaryFileEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            BinaryFileEvent::Edited => {
+                self.active_match = None;
+                cx.emit(HexEditorEvent::Edited);
+            }
+            BinaryFileEvent::Reloaded => {
+                self.active_match = None;
+                self.set_cursor(self.cursor, cx);
+                cx.emit(HexEditorEvent::TitleChanged);
+            }
+            BinaryFileEvent::Saved | BinaryFileEvent::FileHandleChanged => {
+                cx.emit(HexEditorEvent::TitleChanged);
+            }
+        }
+        cx.notify();
+    }
+
+    fn bytes_per_row(cx: &App) -> usize {
+        HexEditorSettings::get_global(cx).bytes_per_row.max(1)
+    }
+
+    fn len(&self, cx: &App) -> usize {
+        self.binary_file.read(cx).len()
+    }
+
+    fn set_cursor(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.cursor = offset.min(self.len(cx));
+        self.typed_high_nibble = false;
+        self.scroll_handle
+            .scroll_to_item(self.cursor / Self::bytes_per_row(cx), ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn move_cursor_by(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        self.set_cursor(offset, cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-1, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-(Self::bytes_per_row(cx) as isize), cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(Self::bytes_per_row(cx) as isize, cx);
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-((Self::bytes_per_row(cx) * PAGE_ROWS) as isize), cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by((Self::bytes_per_row(cx) * PAGE_ROWS) as isize, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(0, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(self.len(cx), cx);
+    }
+
+    fn toggle_insert_mode(&mut self, _: &ToggleInsertMode, _: &mut Window, cx: &mut Context<Self>) {
+        self.mode = match self.mode {
+            EditMode::Overwrite => EditMode::Insert,
+            EditMode::Insert => EditMode::Overwrite,
+        };
+        self.typed_high_nibble = false;
+        cx.notify();
+    }
+
+    fn switch_column(&mut self, _: &SwitchColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.typed_high_nibble = false;
+        cx.notify();
+    }
+
+    fn backspace(&mut self, _: &Backspace, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor == 0 {
+            return;
+        }
+        match self.mode {
+            EditMode::Overwrite => self.move_cursor_by(-1, cx),
+            EditMode::Insert => {
+                let offset = self.cursor - 1;
+                self.binary_file.update(cx, |binary_file, cx| {
+                    binary_file.splice(offset..offset + 1, Vec::new(), cx)
+                });
+                self.set_cursor(offset, cx);
+            }
+        }
+    }
+
+    fn delete(&mut self, _: &Delete, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.cursor;
+        if offset >= self.len(cx) {
+            return;
+        }
+        self.binary_file.update(cx, |binary_file, cx| {
+            binary_file.splice(offset..offset + 1, Vec::new(), cx)
+        });
+        self.set_cursor(offset, cx);
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(offset) = self
+            .binary_file
+            .update(cx, |binary_file, cx| binary_file.undo(cx))
+        {
+            self.set_cursor(offset, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(offset) = self
+            .binary_file
+            .update(cx, |binary_file, cx| binary_file.redo(cx))
+        {
+            self.set_cursor(offset, cx);
+        }
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(character) = event
+            .keystroke
+            .key_char
+            .as_ref()
+            .and_then(|key_char| key_char.chars().next())
+        else {
+            return;
+        };
+
+        let handled = match self.column {
+            Column::Hex => character
+                .to_digit(16)
+                .map(|nibble| self.type_nibble(nibble as u8, cx))
+                .is_some(),
+            Column::Ascii => (character.is_ascii_graphic() || character == ' ')
+                .then(|| self.type_byte(character as u8, cx))
+                .is_some(),
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    /// Writes a nibble at the cursor. The first nibble typed for a byte replaces its high half,
+    /// and the second one replaces its low half and advances the cursor. Both are undone at once.
+    fn type_nibble(&mut self, nibble: u8, cx: &mut Context<Self>) {
+        let offset = self.cursor;
+        let current = self.binary_file.read(cx).bytes().get(offset).copied();
+        if self.typed_high_nibble {
+            let byte = (current.unwrap_or(0) & 0xF0) | nibble;
+            self.binary_file.update(cx, |binary_file, cx| {
+                binary_file.splice_into_last_edit(offset..offset + 1, vec![byte], cx)
+            });
+            self.set_cursor(offset + 1, cx);
+        } else {
+            let replaced = match (self.mode, current) {
+                (EditMode::Overwrite, Some(_)) => offset..offset + 1,
+                _ => offset..offset,
+            };
+            let byte =
+                (nibble << 4) | (current.filter(|_| !replaced.is_empty()).unwrap_or(0) & 0x0F);
+            self.binary_file.update(cx, |binary_file, cx| {
+                binary_file.splice(replaced, vec![byte], cx)
+            });
+            self.typed_high_nibble = true;
+            cx.notify();
+        }
+    }
+
+    fn type_byte(&mut self, byte: u8, cx: &mut Context<Self>) {
+        let offset = self.cursor;
+        let replaced = if self.mode == EditMode::Overwrite && offset < self.len(cx) {
+            offset..offset + 1
+        } else {
+            offset..offset
+        };
+        self.binary_file.update(cx, |binary_file, cx| {
+            binary_file.splice(replaced, vec![byte], cx)
+        });
+        self.set_cursor(offset + 1, cx);
+    }
+
+    pub(crate) fn go_to_offset(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.set_cursor(offset, cx);
+        self.scroll_handle.scroll_to_item(
+            self.cursor / Self::bytes_per_row(cx),
+            ScrollStrategy::Center,
+        );
+    }
+
+    /// Selects the next occurrence of `query` after the cursor, or the previous one before it,
+    /// wrapping around the end of the file.
+    pub(crate) fn find(&mut self, query: Vec<u8>, forward: bool, cx: &mut Context<Self>) {
+        let found = find_offset(
+            self.binary_file.read(cx).bytes(),
+            &query,
+            self.cursor,
+            forward,
+        );
+        self.active_match = found.map(|offset| offset..offset + query.len());
+        self.search_query = query;
+        match found {
+            Some(offset) => self.go_to_offset(offset, cx),
+            None => cx.notify(),
+        }
+    }
+
+    fn find_next(&mut self, _: &FindNext, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search_query.is_empty() {
+            self.find_bytes(&FindBytes, window, cx);
+        } else {
+            self.find(self.search_query.clone(), true, cx);
+        }
+    }
+
+    fn find_previous(&mut self, _: &FindPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search_query.is_empty() {
+            self.find_bytes(&FindBytes, window, cx);
+        } else {
+            self.find(self.search_query.clone(), false, cx);
+        }
+    }
+
+    fn go_to_offset_prompt(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        let initial_query = format!("0x{:X}", self.cursor);
+        self.toggle_prompt(PromptKind::GoToOffset, initial_query, window, cx);
+    }
+
+    fn find_bytes(&mut self, _: &FindBytes, window: &mut Window, cx: &mut Context<Self>) {
+        let initial_query = self
+            .search_query
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.toggle_prompt(PromptKind::FindBytes, initial_query, window, cx);
+    }
+
+    fn toggle_prompt(
+        &mut self,
+        kind: PromptKind,
+        initial_query: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        let hex_editor = cx.entity().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                HexEditorPrompt::new(kind, hex_editor, initial_query, window, cx)
+            });
+        });
+    }
+
+    fn file_name(&self, cx: &App) -> SharedString {
+        self.binary_file
+            .read(cx)
+            .file()
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "untitled".into())
+    }
+
+    fn render_status(&self, cx: &Context<Self>) -> impl IntoElement {
+        let len = self.len(cx);
+        let mode = match self.mode {
+            EditMode::Overwrite => "Overwrite",
+            EditMode::Insert => "Insert",
+        };
+        let mut status = format!(
+            "Offset 0x{:08X} ({}) of {} bytes",
+            self.cursor, self.cursor, len
+        );
+        if let Some(byte) = self.binary_file.read(cx).bytes().get(self.cursor) {
+            status.push_str(&format!(" · Value {byte} (0x{byte:02X})"));
+        }
+        if let Some(active_match) = &self.active_match {
+            status.push_str(&format!(" · Match at 0x{:X}", active_match.start));
+        }
+
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(status)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1())
+            .child(Label::new(mode).size(LabelSize::Small).color(Color::Muted))
+    }
+
+    fn render_rows(
+        &mut self,
+        rows: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let bytes_per_row = Self::bytes_per_row(cx);
+        let bytes = self.binary_file.read(cx).bytes();
+        let colors = cx.theme().colors();
+        let cursor_background = colors.element_selected;
+        let inactive_cursor_background = colors.ghost_element_selected;
+        let match_background = colors.search_match_background;
+        let muted_text = colors.text_muted;
+
+        rows.map(|row| {
+            let row_start = row * bytes_per_row;
+            let cell_background = |offset: usize, column: Column| {
+                if offset == self.cursor {
+                    Some(if column == self.column {
+                        cursor_background
+                    } else {
+                        inactive_cursor_background
+                    })
+                } else if self
+                    .active_match
+                    .as_ref()
+                    .is_some_and(|active_match| active_match.contains(&offset))
+                {
+                    Some(match_background)
+                } else {
+                    None
+                }
+            };
+            let cell = |offset: usize, column: Column, text: String| -> Stateful<Div> {
+                let id = match column {
+                    Column::Hex => ("hex-cell", offset),
+                    Column::Ascii => ("ascii-cell", offset),
+                };
+                div()
+                    .id(id)
+                    .when_some(cell_background(offset, column), |cell, background| {
+                        cell.bg(background)
+                    })
+                    .child(text)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _, window, cx| {
+                            this.column = column;
+                            this.set_cursor(offset, cx);
+                            window.focus(&this.focus_handle);
+                        }),
+                    )
+            };
+
+            let hex = (0..bytes_per_row).map(|ix| {
+                let offset = row_start + ix;
+                let text = bytes
+                    .get(offset)
+                    .map_or_else(|| "  ".to_string(), |byte| format!("{byte:02X}"));
+                cell(offset, Column::Hex, text).when(ix > 0 && ix % 8 == 0, |cell| cell.ml_2())
+            });
+            let ascii = (0..bytes_per_row).map(|ix| {
+                let offset = row_start + ix;
+                let text = bytes.get(offset).map_or_else(
+                    || " ".to_string(),
+                    |&byte| {
+                        if byte.is_ascii_graphic() || byte == b' ' {
+                            (byte as char).to_string()
+                        } else {
+                            ".".to_string()
+                        }
+                    },
+                );
+                cell(offset, Column::Ascii, text)
+            });
+
+            h_flex()
+                .px_2()
+                .gap_4()
+                .child(
+                    div()
+                        .text_color(muted_text)
+                        .child(format!("{row_start:08X}")),
+                )
+                .child(h_flex().gap_1().children(hex))
+                .child(h_flex().children(ascii))
+                .into_any_element()
+        })
+        .collect()
+    }
+}
+
+/// Returns the offset of the first occurrence of `query` after `cursor`, or the last one before
+/// it, wrapping around the end of `bytes`.
+fn find_offset(bytes: &[u8], query: &[u8], cursor: usize, forward: bool) -> Option<usize> {
+    if query.is_empty() || query.len() > bytes.len() {
+        return None;
+    }
+    let positions = bytes.windows(query.len()).enumerate();
+    let is_match = |(_, window): &(usize, &[u8])| *window == query;
+    let found = if forward {
+        let start = cursor.saturating_add(1);
+        positions
+            .clone()
+            .skip(start)
+            .chain(positions.take(start))
+            .find(is_match)
+    } else {
+        positions
+            .clone()
+            .take(cursor)
+            .rev()
+            .chain(positions.skip(cursor).rev())
+            .find(is_match)
+    };
+    found.map(|(offset, _)| offset)
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme_settings = ThemeSettings::get_global(cx);
+        let buffer_font = theme_settings.buffer_font.clone();
+        let buffer_font_size = theme_settings.buffer_font_size();
+        let row_count = self.len(cx) / Self::bytes_per_row(cx) + 1;
+
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_key_down(cx.listener(Self::key_down))
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::toggle_insert_mode))
+            .on_action(cx.listener(Self::switch_column))
+            .on_action(cx.listener(Self::backspace))
+            .on_action(cx.listener(Self::delete))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(Self::go_to_offset_prompt))
+            .on_action(cx.listener(Self::find_bytes))
+            .on_action(cx.listener(Self::find_next))
+            .on_action(cx.listener(Self::find_previous))
+            .child(self.render_status(cx))
+            .child(
+                uniform_list(cx.entity(), "hex-editor-rows", row_count, Self::render_rows)
+                    .track_scroll(self.scroll_handle.clone())
+                    .flex_grow()
+                    .py_1()
+                    .font(buffer_font)
+                    .text_size(buffer_font_size)
+                    .line_height(px(buffer_font_size.0 * 1.5)),
+            )
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::TitleChanged => {
+                f(ItemEvent::UpdateTab);
+                f(ItemEvent::UpdateBreadcrumbs);
+            }
+        }
+    }
+
+    fn tab_content_text(&self, _: &Window, cx: &App) -> Option<SharedString> {
+        Some(self.file_name(cx))
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.binary_file.read(cx).file().path.clone();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(&path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let file = self.binary_file.read(cx).file();
+        Some(file.path.to_string_lossy().into_owned().into())
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.binary_file.entity_id(), self.binary_file.read(cx))
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Self>> {
+        Some(cx.new(|cx| Self::new(self.binary_file.clone(), self.project.clone(), cx)))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.binary_file.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        _project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.binary_file
+            .update(cx, |binary_file, cx| binary_file.save(cx))
+    }
+
+    fn save_as(
+        &mut self,
+        _project: Entity<Project>,
+        path: ProjectPath,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.binary_file
+            .update(cx, |binary_file, cx| binary_file.save_as(path, cx))
+    }
+
+    fn reload(
+        &mut self,
+        _project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.binary_file
+            .update(cx, |binary_file, cx| binary_file.reload(cx))
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = BinaryFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        item: Entity<Self::Item>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new(item, project, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+
+    #[test]
+    fn test_find_offset() {
+        let bytes = [0xAA, 0x01, 0x02, 0xAA, 0x01, 0x02];
+        assert_eq!(find_offset(&bytes, &[0x01, 0x02], 0, true), Some(1));
+        assert_eq!(find_offset(&bytes, &[0x01, 0x02], 1, true), Some(4));
+        assert_eq!(find_offset(&bytes, &[0x01, 0x02], 4, true), Some(1));
+        assert_eq!(find_offset(&bytes, &[0x01, 0x02], 4, false), Some(1));
+        assert_eq!(find_offset(&bytes, &[0x01, 0x02], 1, false), Some(4));
+        assert_eq!(find_offset(&bytes, &[0x03], 0, true), None);
+        assert_eq!(find_offset(&bytes, &[], 0, true), None);
+    }
+
+    #[gpui::test]
+    async fn test_editing_binary_file(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            HexEditorSettings::register(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/blob.bin", vec![0x00, 0xFF, 0x10, 0x80])
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+
+        let binary_file = cx
+            .update(|cx| {
+                BinaryFile::open(
+                    &project,
+                    ProjectPath {
+                        worktree_id,
+                        path: Path::new("blob.bin").into(),
+                    },
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let hex_editor = cx.new(|cx| HexEditor::new(binary_file.clone(), project.clone(), cx));
+
+        hex_editor.update(cx, |hex_editor, cx| {
+            // Overwrite the first byte, one nibble at a time.
+            hex_editor.type_nibble(0xA, cx);
+            hex_editor.type_nibble(0xB, cx);
+            assert_eq!(hex_editor.cursor, 1);
+
+            // Insert a byte from the ASCII column.
+            hex_editor.mode = EditMode::Insert;
+            hex_editor.type_byte(b'Z', cx);
+            assert_eq!(hex_editor.cursor, 2);
+
+            hex_editor.find(vec![0x10, 0x80], true, cx);
+            assert_eq!(hex_editor.active_match, Some(3..5));
+            assert_eq!(hex_editor.cursor, 3);
+        });
+        binary_file.read_with(cx, |binary_file, _| {
+            assert_eq!(binary_file.bytes(), &[0xAB, b'Z', 0xFF, 0x10, 0x80]);
+            assert!(binary_file.is_dirty());
+        });
+
+        binary_file
+            .update(cx, |binary_file, cx| binary_file.save(cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            fs.load_bytes(Path::new("/root/blob.bin")).await.unwrap(),
+            vec![0xAB, b'Z', 0xFF, 0x10, 0x80]
+        );
+
+        binary_file.update(cx, |binary_file, cx| {
+            assert!(!binary_file.is_dirty());
+            assert_eq!(binary_file.undo(cx), Some(1));
+            assert_eq!(binary_file.bytes(), &[0xAB, 0xFF, 0x10, 0x80]);
+
+            // Both nibbles of a byte are undone at once.
+            assert_eq!(binary_file.undo(cx), Some(0));
+            assert_eq!(binary_file.bytes(), &[0x00, 0xFF, 0x10, 0x80]);
+            assert_eq!(binary_file.undo(cx), None);
+        });
+    }
+}
@@ -0,0 +1,33 @@
+use gpui::App;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct HexEditorSettings {
+    pub file_extensions: Vec<String>,
+    pub bytes_per_row: usize,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct HexEditorSettingsContent {
+    /// The extensions of files that are opened in the hex editor rather than as text.
+    /// Files that aren't valid UTF-8 are opened in the hex editor regardless.
+    ///
+    /// Default: ["bin", "dat", "pb", "img", "rom", "fw", "elf", "o", "so", "dylib", "dll", "exe", "wasm", "class"]
+    pub file_extensions: Option<Vec<String>>,
+    /// The number of bytes to show on each row.
+    ///
+    /// Default: 16
+    pub bytes_per_row: Option<usize>,
+}
+
+impl Settings for HexEditorSettings {
+    const KEY: Option<&'static str> = Some("hex_editor");
+
+    type FileContent = HexEditorSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
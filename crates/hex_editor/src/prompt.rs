@@ -0,0 +1,212 @@
+use editor::Editor;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Subscription,
+    WeakEntity, Window,
+};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::HexEditor;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PromptKind {
+    GoToOffset,
+    FindBytes,
+}
+
+/// A single-line modal used to enter an offset to jump to, or a sequence of bytes to search for.
+pub(crate) struct HexEditorPrompt {
+    kind: PromptKind,
+    query_editor: Entity<Editor>,
+    hex_editor: WeakEntity<HexEditor>,
+    _subscription: Subscription,
+}
+
+impl ModalView for HexEditorPrompt {}
+
+impl EventEmitter<DismissEvent> for HexEditorPrompt {}
+
+impl Focusable for HexEditorPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl HexEditorPrompt {
+    pub(crate) fn new(
+        kind: PromptKind,
+        hex_editor: WeakEntity<HexEditor>,
+        initial_query: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                match kind {
+                    PromptKind::GoToOffset => "Offset, e.g. 1024 or 0x400",
+                    PromptKind::FindBytes => "Hex bytes, e.g. DE AD BE EF, or \"text\"",
+                },
+                cx,
+            );
+            editor.set_text(initial_query, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &query_editor,
+            window,
+            |_, _, event: &editor::EditorEvent, _, cx| {
+                if let editor::EditorEvent::Blurred = event {
+                    cx.emit(DismissEvent);
+                }
+            },
+        );
+
+        Self {
+            kind,
+            query_editor,
+            hex_editor,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        let kind = self.kind;
+        let confirmed = self
+            .hex_editor
+            .update(cx, |hex_editor, cx| {
+                let confirmed = match kind {
+                    PromptKind::GoToOffset => parse_offset(&query)
+                        .map(|offset| hex_editor.go_to_offset(offset, cx))
+                        .is_some(),
+                    PromptKind::FindBytes => parse_bytes(&query)
+                        .map(|bytes| hex_editor.find(bytes, true, cx))
+                        .is_some(),
+                };
+                if confirmed {
+                    hex_editor.focus_handle(cx).focus(window);
+                }
+                confirmed
+            })
+            .unwrap_or(true);
+        if confirmed {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn help_text(&self, cx: &App) -> SharedString {
+        let query = self.query_editor.read(cx).text(cx);
+        match self.kind {
+            PromptKind::GoToOffset => match parse_offset(&query) {
+                Some(offset) => format!("Go to offset {offset} (0x{offset:X})").into(),
+                None => "Enter a decimal or 0x-prefixed hexadecimal offset".into(),
+            },
+            PromptKind::FindBytes => match parse_bytes(&query) {
+                Some(bytes) => format!("Find {} bytes", bytes.len()).into(),
+                None => "Enter hex bytes, or text in quotes".into(),
+            },
+        }
+    }
+}
+
+impl Render for HexEditorPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("HexEditorPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.query_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(self.help_text(cx)).color(Color::Muted)),
+            )
+    }
+}
+
+/// Parses a decimal offset, or a hexadecimal one prefixed with `0x`.
+pub(crate) fn parse_offset(query: &str) -> Option<usize> {
+    let query = query.trim();
+    match query
+        .strip_prefix("0x")
+        .or_else(|| query.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => query.parse().ok(),
+    }
+}
+
+/// Parses a sequence of hex bytes separated by optional whitespace, or quoted text.
+pub(crate) fn parse_bytes(query: &str) -> Option<Vec<u8>> {
+    let query = query.trim();
+    if let Some(text) = query
+        .strip_prefix('"')
+        .map(|text| text.strip_suffix('"').unwrap_or(text))
+    {
+        return (!text.is_empty()).then(|| text.as_bytes().to_vec());
+    }
+
+    let digits = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let high = pair[0].to_digit(16)?;
+            let low = pair[1].to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("1024"), Some(1024));
+        assert_eq!(parse_offset(" 0x400 "), Some(1024));
+        assert_eq!(parse_offset("0XfF"), Some(255));
+        assert_eq!(parse_offset("ff"), None);
+        assert_eq!(parse_offset(""), None);
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(
+            parse_bytes("DE AD be ef"),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(parse_bytes("cafe"), Some(vec![0xCA, 0xFE]));
+        assert_eq!(parse_bytes("\"ELF\""), Some(b"ELF".to_vec()));
+        assert_eq!(
+            parse_bytes("\"unterminated"),
+            Some(b"unterminated".to_vec())
+        );
+        assert_eq!(parse_bytes("abc"), None);
+        assert_eq!(parse_bytes("zz"), None);
+        assert_eq!(parse_bytes("\"\""), None);
+    }
+}
@@ -123,6 +123,9 @@ pub struct Buffer {
     completion_triggers_timestamp: clock::Lamport,
     deferred_ops: OperationQueue<Operation>,
     capability: Capability,
+    /// Whether the buffer's file exceeded the large-file threshold when it was opened, in which
+    /// case syntax parsing, language servers, inlay hints and git diffs are skipped for it.
+    large_file: bool,
     has_conflict: bool,
     /// Memoize calls to has_changes_since(saved_version).
     /// The contents of a cell are (self.version, has_changes) at the time of a last call.
@@ -345,6 +348,8 @@ pub enum BufferEvent {
     DiagnosticsUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
+    /// The buffer entered or left large-file mode.
+    LargeFileChanged,
     /// The buffer was explicitly requested to close.
     Closed,
     /// The buffer was discarded when closing.
@@ -938,6 +943,7 @@ impl Buffer {
             completion_triggers_per_language_server: Default::default(),
             completion_triggers_timestamp: Default::default(),
             deferred_ops: OperationQueue::new(),
+            large_file: false,
             has_conflict: false,
             _subscriptions: Vec::new(),
         }
@@ -1186,6 +1192,19 @@ impl Buffer {
         cx.emit(BufferEvent::CapabilityChanged)
     }
 
+    /// Whether this buffer was opened in large-file mode.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// Enables or disables large-file mode, in which language features are skipped for this buffer.
+    pub fn set_large_file(&mut self, large_file: bool, cx: &mut Context<Self>) {
+        if self.large_file != large_file {
+            self.large_file = large_file;
+            cx.emit(BufferEvent::LargeFileChanged);
+        }
+    }
+
     /// This method is called to signal that the buffer has been saved.
     pub fn did_save(
         &mut self,
@@ -1202,6 +1221,23 @@ impl Buffer {
         cx.notify();
     }
 
+    /// Appends text that was loaded from the buffer's file, without adding it
+    /// to the undo history or making the buffer dirty.
+    pub fn append_loaded_text(&mut self, text: &str, cx: &mut Context<Self>) {
+        let was_dirty = self.is_dirty();
+        let len = self.len();
+        self.start_transaction();
+        self.edit([(len..len, text)], None, cx);
+        if let Some(transaction_id) = self.end_transaction(cx) {
+            self.forget_transaction(transaction_id);
+        }
+        if !was_dirty {
+            self.saved_version = self.version();
+            self.has_unsaved_edits
+                .set((self.saved_version().clone(), false));
+        }
+    }
+
     /// This method is called to signal that the buffer has been discarded.
     pub fn discarded(&self, cx: &mut Context<Self>) {
         cx.emit(BufferEvent::Discarded);
@@ -3009,6 +3045,91 @@ impl BufferSnapshot {
             .layers_for_range(0..self.len(), &self.text, true)
     }
 
+    /// Returns the ranges within `range` that contain natural-language text worth spell checking.
+    ///
+    /// In code, these are the ranges captured as comments or strings by each syntax layer's
+    /// highlights query. In prose languages like Markdown, this is all of the text apart from
+    /// code spans, link destinations and embedded code in other languages.
+    pub fn spell_check_ranges(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut prose_ranges = Vec::new();
+        let mut excluded_ranges = Vec::new();
+        for layer in self
+            .syntax
+            .layers_for_range(range.clone(), &self.text, true)
+        {
+            if layer.language.config.prose {
+                prose_ranges.push(layer.node().byte_range());
+            } else {
+                excluded_ranges.push(layer.node().byte_range());
+            }
+        }
+
+        let mut text_ranges = Vec::new();
+        let captures = self.syntax.captures(range.clone(), &self.text, |grammar| {
+            grammar.highlights_query.as_ref()
+        });
+        let capture_names = captures
+            .grammars()
+            .iter()
+            .copied()
+            .map(|grammar| {
+                grammar
+                    .highlights_query
+                    .as_ref()
+                    .map_or(&[][..], |query| query.capture_names())
+            })
+            .collect::<Vec<_>>();
+        for capture in captures {
+            let Some(name) = capture_names[capture.grammar_index].get(capture.index as usize)
+            else {
+                continue;
+            };
+            let mut name = name.split('.');
+            match (name.next(), name.next()) {
+                (Some("comment"), _) | (Some("string"), None | Some("doc")) => {
+                    text_ranges.push(capture.node.byte_range())
+                }
+                (Some("text"), Some("literal")) | (Some("link_uri"), _) => {
+                    excluded_ranges.push(capture.node.byte_range())
+                }
+                _ => {}
+            }
+        }
+
+        excluded_ranges.sort_unstable_by_key(|range| range.start);
+        for prose_range in prose_ranges {
+            let mut start = prose_range.start;
+            for excluded_range in &excluded_ranges {
+                if excluded_range.end <= start || excluded_range.start >= prose_range.end {
+                    continue;
+                }
+                if excluded_range.start > start {
+                    text_ranges.push(start..excluded_range.start);
+                }
+                start = start.max(excluded_range.end);
+            }
+            if start < prose_range.end {
+                text_ranges.push(start..prose_range.end);
+            }
+        }
+
+        text_ranges.sort_unstable_by_key(|range| range.start);
+        let mut result: Vec<Range<usize>> = Vec::new();
+        for text_range in text_ranges {
+            let text_range = text_range.start.max(range.start)..text_range.end.min(range.end);
+            if text_range.is_empty() {
+                continue;
+            }
+            match result.last_mut() {
+                Some(last) if last.end >= text_range.start => {
+                    last.end = last.end.max(text_range.end)
+                }
+                _ => result.push(text_range),
+            }
+        }
+        result
+    }
+
     pub fn syntax_layer_at<D: ToOffset>(&self, position: D) -> Option<SyntaxLayer> {
         let offset = position.to_offset(self);
         self.syntax
@@ -3235,6 +3356,54 @@ impl BufferSnapshot {
         Some(items)
     }
 
+    /// Returns the ranges of the scopes that contain the given position, from the outermost to
+    /// the innermost, whose first lines are pinned to the top of the editor by sticky scroll.
+    ///
+    /// Scopes are captured by the language's sticky scroll query, falling back to its outline.
+    pub fn sticky_scopes_containing<T: ToOffset>(&self, position: T) -> Vec<Range<Point>> {
+        let position = position.to_offset(self);
+        let mut matches = self.syntax.matches(
+            position.saturating_sub(1)..self.len().min(position + 1),
+            &self.text,
+            |grammar| grammar.sticky_scroll_config.as_ref().map(|c| &c.query),
+        );
+        if matches.grammars().is_empty() {
+            return self
+                .symbols_containing(position, None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|item| item.range.start.to_point(self)..item.range.end.to_point(self))
+                .collect();
+        }
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|g| g.sticky_scroll_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut scopes = Vec::new();
+        while let Some(mat) = matches.peek() {
+            let config = &configs[mat.grammar_index];
+            for capture in mat.captures {
+                let range = capture.node.byte_range();
+                if capture.index == config.scope_capture_ix
+                    && range.start <= position
+                    && position < range.end
+                {
+                    scopes.push(range);
+                }
+            }
+            matches.advance();
+        }
+
+        scopes.sort_by_key(|range| (range.start, Reverse(range.end)));
+        scopes.dedup();
+        scopes
+            .into_iter()
+            .map(|range| self.offset_to_point(range.start)..self.offset_to_point(range.end))
+            .collect()
+    }
+
     pub fn outline_range_containing<T: ToOffset>(&self, range: Range<T>) -> Option<Range<Point>> {
         let range = range.to_offset(self);
         let mut matches = self.syntax.matches(range.clone(), &self.text, |grammar| {
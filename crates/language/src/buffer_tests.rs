@@ -944,6 +944,77 @@ fn test_text_objects(cx: &mut App) {
     )
 }
 
+#[gpui::test]
+fn test_sticky_scopes_containing(cx: &mut App) {
+    let text = indoc! {"
+        impl Hello {
+            fn say() -> u8 {
+                1
+            }
+        }
+    "};
+    let position = text.find('1').unwrap();
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(
+        snapshot.sticky_scopes_containing(position),
+        [
+            Point::new(0, 0)..Point::new(4, 1),
+            Point::new(1, 4)..Point::new(3, 5),
+        ],
+        "Scopes should fall back to the outline"
+    );
+
+    let language = rust_lang()
+        .with_sticky_scroll_query("(impl_item) @scope")
+        .unwrap();
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(
+        snapshot.sticky_scopes_containing(position),
+        [Point::new(0, 0)..Point::new(4, 1)]
+    );
+}
+
+#[gpui::test]
+fn test_spell_check_ranges(cx: &mut App) {
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    )
+    .with_highlights_query(
+        r#"
+        (block_comment) @comment
+        (string_literal) @string
+        (escape_sequence) @string.escape
+        (identifier) @variable
+        "#,
+    )
+    .unwrap();
+    let text = r#"fn main() { /* a coment */ let x = "a strng"; }"#;
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    let ranges = snapshot
+        .spell_check_ranges(0..text.len())
+        .into_iter()
+        .map(|range| &text[range])
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, &["/* a coment */", "\"a strng\""]);
+
+    let ranges = snapshot
+        .spell_check_ranges(15..text.len())
+        .into_iter()
+        .map(|range| &text[range])
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, &["a coment */", "\"a strng\""]);
+}
+
 #[gpui::test]
 fn test_enclosing_bracket_ranges(cx: &mut App) {
     let mut assert = |selection_text, range_markers| {
@@ -633,6 +633,10 @@ pub struct LanguageConfig {
     /// languages, but should not appear to the user as a distinct language.
     #[serde(default)]
     pub hidden: bool,
+    /// If true, this language's text is prose that is spell checked in full, rather than only
+    /// within comments and strings.
+    #[serde(default)]
+    pub prose: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default, JsonSchema)]
@@ -720,6 +724,7 @@ impl Default for LanguageConfig {
             soft_wrap: None,
             prettier_parser_name: None,
             hidden: false,
+            prose: false,
         }
     }
 }
@@ -877,6 +882,7 @@ pub struct Grammar {
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub text_object_config: Option<TextObjectConfig>,
+    pub(crate) sticky_scroll_config: Option<StickyScrollConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
     pub(crate) injection_config: Option<InjectionConfig>,
     pub(crate) override_config: Option<OverrideConfig>,
@@ -940,6 +946,11 @@ pub struct TextObjectConfig {
     pub text_objects_by_capture_ix: Vec<(u32, TextObject)>,
 }
 
+struct StickyScrollConfig {
+    query: Query,
+    scope_capture_ix: u32,
+}
+
 #[derive(Debug)]
 pub struct EmbeddingConfig {
     pub query: Query,
@@ -1018,6 +1029,7 @@ impl Language {
                     brackets_config: None,
                     outline_config: None,
                     text_object_config: None,
+                    sticky_scroll_config: None,
                     embedding_config: None,
                     indents_config: None,
                     injection_config: None,
@@ -1095,6 +1107,11 @@ impl Language {
                 .with_text_object_query(query.as_ref())
                 .context("Error loading textobject query")?;
         }
+        if let Some(query) = queries.sticky_scroll {
+            self = self
+                .with_sticky_scroll_query(query.as_ref())
+                .context("Error loading sticky scroll query")?;
+        }
         Ok(self)
     }
 
@@ -1190,6 +1207,22 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_sticky_scroll_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut scope_capture_ix = None;
+        get_capture_indices(&query, &mut [("scope", &mut scope_capture_ix)]);
+        if let Some(scope_capture_ix) = scope_capture_ix {
+            grammar.sticky_scroll_config = Some(StickyScrollConfig {
+                query,
+                scope_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
     pub fn with_embedding_query(mut self, source: &str) -> Result<Self> {
         let grammar = self
             .grammar_mut()
@@ -194,6 +194,7 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("redactions", |q| &mut q.redactions),
     ("runnables", |q| &mut q.runnables),
     ("textobjects", |q| &mut q.text_objects),
+    ("sticky_scroll", |q| &mut q.sticky_scroll),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -209,6 +210,7 @@ pub struct LanguageQueries {
     pub redactions: Option<Cow<'static, str>>,
     pub runnables: Option<Cow<'static, str>>,
     pub text_objects: Option<Cow<'static, str>>,
+    pub sticky_scroll: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
@@ -261,6 +261,9 @@ impl LanguageModelRequest {
                 })
                 .collect(),
             stream,
+            stream_options: stream.then_some(open_ai::StreamOptions {
+                include_usage: true,
+            }),
             stop: self.stop,
             temperature: self.temperature.unwrap_or(1.0),
             max_tokens: max_output_tokens,
@@ -472,6 +475,9 @@ impl LanguageModelRequest {
             model,
             messages: merged_messages,
             stream: true,
+            stream_options: Some(deepseek::StreamOptions {
+                include_usage: true,
+            }),
             max_tokens: max_output_tokens,
             temperature: if is_reasoner { None } else { self.temperature },
             response_format: None,
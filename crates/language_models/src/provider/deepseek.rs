@@ -9,7 +9,7 @@ use http_client::HttpClient;
 use language_model::{
     LanguageModel, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
     LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role, TokenUsage,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -302,21 +302,36 @@ impl LanguageModel for DeepSeekLanguageModel {
         async move {
             let stream = stream.await?;
             Ok(stream
-                .map(|result| {
-                    result.and_then(|response| {
-                        response
-                            .choices
-                            .first()
-                            .ok_or_else(|| anyhow!("Empty response"))
-                            .map(|choice| {
-                                choice
-                                    .delta
-                                    .content
-                                    .clone()
-                                    .unwrap_or_default()
-                                    .map(LanguageModelCompletionEvent::Text)
-                            })
-                    })
+                .flat_map(|result| {
+                    let mut events = Vec::new();
+                    match result {
+                        Ok(response) => {
+                            if let Some(text) = response
+                                .choices
+                                .into_iter()
+                                .next()
+                                .and_then(|choice| choice.delta.content)
+                            {
+                                events.push(Ok(LanguageModelCompletionEvent::Text(text)));
+                            }
+
+                            if let Some(usage) = response.usage {
+                                let cache_hit_tokens = usage.prompt_cache_hit_tokens.unwrap_or(0);
+                                events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(
+                                    TokenUsage {
+                                        input_tokens: usage.prompt_cache_miss_tokens.unwrap_or(
+                                            usage.prompt_tokens.saturating_sub(cache_hit_tokens),
+                                        ),
+                                        output_tokens: usage.completion_tokens,
+                                        cache_creation_input_tokens: 0,
+                                        cache_read_input_tokens: cache_hit_tokens,
+                                    },
+                                )));
+                            }
+                        }
+                        Err(error) => events.push(Err(error)),
+                    }
+                    futures::stream::iter(events)
                 })
                 .boxed())
         }
@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 use collections::BTreeMap;
 use editor::{Editor, EditorElement, EditorStyle};
-use futures::{future::BoxFuture, FutureExt, StreamExt};
+use futures::{future::BoxFuture, FutureExt, Stream, StreamExt};
 use gpui::{
     AnyView, App, AsyncApp, Context, Entity, FontStyle, Subscription, Task, TextStyle, WhiteSpace,
 };
@@ -9,10 +9,11 @@ use http_client::HttpClient;
 use language_model::{
     LanguageModel, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
     LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role, TokenUsage,
 };
 use open_ai::{
     stream_completion, FunctionDefinition, ResponseStreamEvent, ToolChoice, ToolDefinition,
+    OPEN_AI_API_URL,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -222,7 +223,7 @@ pub struct OpenAiLanguageModel {
 impl OpenAiLanguageModel {
     fn stream_completion(
         &self,
-        request: open_ai::Request,
+        mut request: open_ai::Request,
         cx: &AsyncApp,
     ) -> BoxFuture<'static, Result<futures::stream::BoxStream<'static, Result<ResponseStreamEvent>>>>
     {
@@ -233,6 +234,10 @@ impl OpenAiLanguageModel {
         }) else {
             return futures::future::ready(Err(anyhow!("App state dropped"))).boxed();
         };
+        // Some OpenAI-compatible servers reject requests with fields they don't know about.
+        if api_url.trim_end_matches('/') != OPEN_AI_API_URL {
+            request.stream_options = None;
+        }
 
         let future = self.request_limiter.stream(async move {
             let api_key = api_key.ok_or_else(|| anyhow!("Missing OpenAI API Key"))?;
@@ -292,12 +297,8 @@ impl LanguageModel for OpenAiLanguageModel {
     > {
         let request = request.into_open_ai(self.model.id().into(), self.max_output_tokens());
         let completions = self.stream_completion(request, cx);
-        async move {
-            Ok(open_ai::extract_text_from_events(completions.await?)
-                .map(|result| result.map(LanguageModelCompletionEvent::Text))
-                .boxed())
-        }
-        .boxed()
+        async move { Ok(map_to_language_model_completion_events(completions.await?).boxed()) }
+            .boxed()
     }
 
     fn use_any_tool(
@@ -338,6 +339,38 @@ impl LanguageModel for OpenAiLanguageModel {
     }
 }
 
+pub fn map_to_language_model_completion_events(
+    events: impl Stream<Item = Result<ResponseStreamEvent>>,
+) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
+    events.flat_map(|event| {
+        let mut completion_events = Vec::new();
+        match event {
+            Ok(mut event) => {
+                if let Some(text) = event.choices.pop().and_then(|choice| choice.delta.content) {
+                    completion_events.push(Ok(LanguageModelCompletionEvent::Text(text)));
+                }
+
+                // The usage is only reported in the final chunk of the stream.
+                if let Some(usage) = event.usage {
+                    let cached_tokens = usage
+                        .prompt_tokens_details
+                        .map_or(0, |details| details.cached_tokens);
+                    completion_events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(
+                        TokenUsage {
+                            input_tokens: usage.prompt_tokens.saturating_sub(cached_tokens),
+                            output_tokens: usage.completion_tokens,
+                            cache_creation_input_tokens: 0,
+                            cache_read_input_tokens: cached_tokens,
+                        },
+                    )));
+                }
+            }
+            Err(error) => completion_events.push(Err(error)),
+        }
+        futures::stream::iter(completion_events)
+    })
+}
+
 pub fn count_open_ai_tokens(
     request: LanguageModelRequest,
     model: open_ai::Model,
@@ -0,0 +1,2 @@
+(section
+    (atx_heading)) @scope
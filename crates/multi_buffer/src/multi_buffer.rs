@@ -104,6 +104,7 @@ pub enum Event {
 
     LanguageChanged(BufferId),
     CapabilityChanged,
+    LargeFileChanged(BufferId),
     Reparsed(BufferId),
     Saved,
     FileHandleChanged,
@@ -2000,6 +2001,9 @@ impl MultiBuffer {
                 self.capability = buffer.read(cx).capability();
                 Event::CapabilityChanged
             }
+            language::BufferEvent::LargeFileChanged => {
+                Event::LargeFileChanged(buffer.read(cx).remote_id())
+            }
             language::BufferEvent::Operation { .. } => return,
         });
     }
@@ -0,0 +1,371 @@
+use std::ops::Range;
+
+use collections::BTreeSet;
+use language::{BufferSnapshot, OutlineItem};
+use project::DocumentSymbol;
+use serde::{Deserialize, Serialize};
+
+use crate::Outline;
+
+const FUNCTION_KEYWORDS: &[&str] = &[
+    "constructor",
+    "def",
+    "defmacro",
+    "defp",
+    "fn",
+    "fun",
+    "func",
+    "function",
+    "method",
+    "proc",
+    "sub",
+];
+const CONSTANT_KEYWORDS: &[&str] = &["const", "static"];
+const VISIBILITY_KEYWORDS: &[&str] = &[
+    "crate",
+    "in",
+    "internal",
+    "private",
+    "protected",
+    "pub",
+    "public",
+    "readonly",
+    "self",
+    "super",
+];
+const TEST_KEYWORDS: &[&str] = &["describe", "it", "test"];
+
+/// The kind of an outline item, used to show or hide groups of items in the outline panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OutlineKind {
+    Function,
+    Field,
+    Constant,
+    Test,
+    Other,
+}
+
+/// Which outline items are shown in the outline panel, and in which order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct OutlineFilter {
+    #[serde(default)]
+    pub hidden_kinds: BTreeSet<OutlineKind>,
+    #[serde(default)]
+    pub sort_by_name: bool,
+}
+
+impl OutlineFilter {
+    pub fn is_active(&self) -> bool {
+        !self.hidden_kinds.is_empty() || self.sort_by_name
+    }
+
+    pub fn toggle_kind(&mut self, kind: OutlineKind) {
+        if !self.hidden_kinds.remove(&kind) {
+            self.hidden_kinds.insert(kind);
+        }
+    }
+
+    /// Removes the items of the hidden kinds along with their children, and sorts the
+    /// remaining siblings by name if needed.
+    pub fn apply<T>(&self, outlines: Vec<(OutlineItem<T>, OutlineKind)>) -> Vec<OutlineItem<T>> {
+        let mut visible_outlines = Vec::with_capacity(outlines.len());
+        let mut hidden_depth = None;
+        for (outline, kind) in outlines {
+            if let Some(depth) = hidden_depth {
+                if outline.depth > depth {
+                    continue;
+                }
+                hidden_depth = None;
+            }
+            if self.hidden_kinds.contains(&kind) {
+                hidden_depth = Some(outline.depth);
+            } else {
+                visible_outlines.push(outline);
+            }
+        }
+
+        if self.sort_by_name {
+            sorted_by_name(visible_outlines)
+        } else {
+            visible_outlines
+        }
+    }
+}
+
+/// Sorts sibling outline items by name, keeping every item followed by its children.
+fn sorted_by_name<T>(outlines: Vec<OutlineItem<T>>) -> Vec<OutlineItem<T>> {
+    let Some(min_depth) = outlines.iter().map(|outline| outline.depth).min() else {
+        return outlines;
+    };
+    let mut groups = Vec::<Vec<OutlineItem<T>>>::new();
+    for outline in outlines {
+        match groups.last_mut() {
+            Some(group) if outline.depth > min_depth => group.push(outline),
+            _ => groups.push(vec![outline]),
+        }
+    }
+    groups.sort_by_cached_key(|group| outline_name(&group[0]).to_lowercase());
+    groups
+        .into_iter()
+        .flat_map(|mut group| {
+            let children = group.split_off(1);
+            group.extend(sorted_by_name(children));
+            group
+        })
+        .collect()
+}
+
+fn outline_name<T>(outline: &OutlineItem<T>) -> &str {
+    match (outline.name_ranges.first(), outline.name_ranges.last()) {
+        (Some(first), Some(last)) => &outline.text[first.start..last.end],
+        _ => &outline.text,
+    }
+}
+
+/// Guesses the kind of a tree-sitter outline item from the keywords around its name.
+pub(crate) fn outline_kind(outline: &Outline, buffer: &BufferSnapshot) -> OutlineKind {
+    let name = outline_name(outline);
+    let context = &outline.text[..outline.name_ranges.first().map_or(0, |range| range.start)];
+    let context_words = context
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    let name_end = outline.name_ranges.last().map_or(0, |range| range.end);
+    let is_function = context_words
+        .iter()
+        .any(|word| FUNCTION_KEYWORDS.contains(word))
+        || outline.text[name_end..].trim_start().starts_with('(');
+
+    let has_test_attribute = outline.annotation_range.as_ref().map_or(false, |range| {
+        buffer
+            .text_for_range(range.clone())
+            .collect::<String>()
+            .lines()
+            .map(str::trim_start)
+            .filter(|line| line.starts_with("#[") || line.starts_with('@'))
+            .any(|line| line.to_lowercase().contains("test"))
+    });
+    if has_test_attribute
+        || (is_function && (name.starts_with("test_") || name.starts_with("Test")))
+        || context_words
+            .first()
+            .map_or(false, |word| TEST_KEYWORDS.contains(word))
+    {
+        OutlineKind::Test
+    } else if is_function {
+        OutlineKind::Function
+    } else if context_words
+        .iter()
+        .any(|word| CONSTANT_KEYWORDS.contains(word))
+    {
+        OutlineKind::Constant
+    } else if outline.depth > 0
+        && context_words
+            .iter()
+            .all(|word| VISIBILITY_KEYWORDS.contains(word))
+    {
+        OutlineKind::Field
+    } else {
+        OutlineKind::Other
+    }
+}
+
+/// Returns the kind of a language server symbol.
+pub(crate) fn symbol_kind(kind: lsp::SymbolKind, name: &str) -> OutlineKind {
+    let is_test = ["test_", "test ", "test(", "describe ", "describe("]
+        .iter()
+        .any(|prefix| name.starts_with(prefix));
+    match kind {
+        _ if is_test => OutlineKind::Test,
+        lsp::SymbolKind::FUNCTION | lsp::SymbolKind::METHOD | lsp::SymbolKind::CONSTRUCTOR => {
+            if name.starts_with("Test") {
+                OutlineKind::Test
+            } else {
+                OutlineKind::Function
+            }
+        }
+        lsp::SymbolKind::FIELD | lsp::SymbolKind::PROPERTY | lsp::SymbolKind::ENUM_MEMBER => {
+            OutlineKind::Field
+        }
+        lsp::SymbolKind::CONSTANT => OutlineKind::Constant,
+        _ => OutlineKind::Other,
+    }
+}
+
+/// Converts the language server symbols that intersect the given range into outline items.
+pub(crate) fn outlines_from_symbols(
+    symbols: &[DocumentSymbol],
+    range: &Range<language::Anchor>,
+    buffer: &BufferSnapshot,
+) -> Vec<(Outline, OutlineKind)> {
+    fn push_outlines(
+        symbols: &[DocumentSymbol],
+        depth: usize,
+        range: &Range<language::Anchor>,
+        buffer: &BufferSnapshot,
+        outlines: &mut Vec<(Outline, OutlineKind)>,
+    ) {
+        let mut symbols = symbols
+            .iter()
+            .filter(|symbol| {
+                symbol.range.start.cmp(&range.end, buffer).is_le()
+                    && symbol.range.end.cmp(&range.start, buffer).is_ge()
+            })
+            .collect::<Vec<_>>();
+        symbols.sort_by(|a, b| a.range.start.cmp(&b.range.start, buffer));
+        for symbol in symbols {
+            let mut text = symbol.name.clone();
+            let name_range = 0..text.len();
+            if let Some(detail) = symbol
+                .detail
+                .as_deref()
+                .map(str::trim)
+                .filter(|detail| !detail.is_empty() && !detail.contains('\n'))
+            {
+                text.push(' ');
+                text.push_str(detail);
+            }
+            outlines.push((
+                OutlineItem {
+                    depth,
+                    range: symbol.range.clone(),
+                    text,
+                    highlight_ranges: Vec::new(),
+                    name_ranges: vec![name_range],
+                    body_range: None,
+                    annotation_range: None,
+                },
+                symbol_kind(symbol.kind, &symbol.name),
+            ));
+            push_outlines(&symbol.children, depth + 1, range, buffer, outlines);
+        }
+    }
+
+    let mut outlines = Vec::new();
+    push_outlines(symbols, 0, range, buffer, &mut outlines);
+    outlines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(depth: usize, name: &str) -> OutlineItem<usize> {
+        OutlineItem {
+            depth,
+            range: 0..0,
+            text: format!("fn {name}"),
+            highlight_ranges: Vec::new(),
+            name_ranges: vec![3..3 + name.len()],
+            body_range: None,
+            annotation_range: None,
+        }
+    }
+
+    fn names<T>(outlines: &[OutlineItem<T>]) -> Vec<(usize, &str)> {
+        outlines
+            .iter()
+            .map(|outline| (outline.depth, outline_name(outline)))
+            .collect()
+    }
+
+    #[test]
+    fn test_sorted_by_name() {
+        let outlines = vec![
+            outline(0, "zeta"),
+            outline(1, "gamma"),
+            outline(1, "Beta"),
+            outline(2, "nested"),
+            outline(0, "alpha"),
+        ];
+        assert_eq!(
+            names(&sorted_by_name(outlines)),
+            [
+                (0, "alpha"),
+                (0, "zeta"),
+                (1, "Beta"),
+                (2, "nested"),
+                (1, "gamma"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_filter() {
+        let outlines = || {
+            vec![
+                (outline(0, "Config"), OutlineKind::Other),
+                (outline(1, "name"), OutlineKind::Field),
+                (outline(0, "MAX_SIZE"), OutlineKind::Constant),
+                (outline(0, "tests"), OutlineKind::Test),
+                (outline(1, "test_parse"), OutlineKind::Test),
+                (outline(1, "helper"), OutlineKind::Function),
+                (outline(0, "parse"), OutlineKind::Function),
+            ]
+        };
+        assert_eq!(
+            names(&OutlineFilter::default().apply(outlines())),
+            [
+                (0, "Config"),
+                (1, "name"),
+                (0, "MAX_SIZE"),
+                (0, "tests"),
+                (1, "test_parse"),
+                (1, "helper"),
+                (0, "parse"),
+            ]
+        );
+
+        let mut filter = OutlineFilter::default();
+        filter.toggle_kind(OutlineKind::Test);
+        filter.toggle_kind(OutlineKind::Field);
+        assert_eq!(
+            names(&filter.apply(outlines())),
+            [(0, "Config"), (0, "MAX_SIZE"), (0, "parse")],
+            "Children of hidden items should be hidden too"
+        );
+
+        filter.toggle_kind(OutlineKind::Test);
+        filter.sort_by_name = true;
+        assert_eq!(
+            names(&filter.apply(outlines())),
+            [
+                (0, "Config"),
+                (0, "MAX_SIZE"),
+                (0, "parse"),
+                (0, "tests"),
+                (1, "helper"),
+                (1, "test_parse"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_symbol_kind() {
+        assert_eq!(
+            symbol_kind(lsp::SymbolKind::FUNCTION, "main"),
+            OutlineKind::Function
+        );
+        assert_eq!(
+            symbol_kind(lsp::SymbolKind::FUNCTION, "test_main"),
+            OutlineKind::Test
+        );
+        assert_eq!(
+            symbol_kind(lsp::SymbolKind::MODULE, "describe \"parser\""),
+            OutlineKind::Test
+        );
+        assert_eq!(
+            symbol_kind(lsp::SymbolKind::STRUCT, "TestAppContext"),
+            OutlineKind::Other
+        );
+        assert_eq!(
+            symbol_kind(lsp::SymbolKind::ENUM_MEMBER, "Some"),
+            OutlineKind::Field
+        );
+        assert_eq!(
+            symbol_kind(lsp::SymbolKind::CONSTANT, "MAX"),
+            OutlineKind::Constant
+        );
+    }
+}
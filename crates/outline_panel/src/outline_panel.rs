@@ -1,3 +1,4 @@
+mod outline_filter;
 mod outline_panel_settings;
 
 use std::{
@@ -21,11 +22,12 @@ use editor::{
     ExcerptRange, MultiBufferSnapshot, RangeToAnchorExt, ShowScrollbar,
 };
 use file_icons::FileIcons;
+use futures::FutureExt as _;
 use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
 use gpui::{
     actions, anchored, deferred, div, point, px, size, uniform_list, Action, AnyElement, App,
-    AppContext as _, AssetSource, AsyncWindowContext, Bounds, ClipboardItem, Context, DismissEvent,
-    Div, ElementId, Entity, EventEmitter, FocusHandle, Focusable, HighlightStyle,
+    AppContext as _, AssetSource, AsyncWindowContext, Bounds, ClipboardItem, Context, Corner,
+    DismissEvent, Div, ElementId, Entity, EventEmitter, FocusHandle, Focusable, HighlightStyle,
     InteractiveElement, IntoElement, KeyContext, ListHorizontalSizingBehavior, ListSizingBehavior,
     MouseButton, MouseDownEvent, ParentElement, Pixels, Point, Render, ScrollStrategy,
     SharedString, Stateful, StatefulInteractiveElement as _, Styled, Subscription, Task,
@@ -35,7 +37,10 @@ use itertools::Itertools;
 use language::{BufferId, BufferSnapshot, OffsetRangeExt, OutlineItem};
 use menu::{Cancel, SelectFirst, SelectLast, SelectNext, SelectPrev};
 
-use outline_panel_settings::{OutlinePanelDockPosition, OutlinePanelSettings, ShowIndentGuides};
+use outline_filter::{OutlineFilter, OutlineKind};
+use outline_panel_settings::{
+    OutlinePanelDockPosition, OutlinePanelSettings, OutlineSource, ShowIndentGuides,
+};
 use project::{File, Fs, Project, ProjectItem};
 use search::{BufferSearchBar, ProjectSearchView};
 use serde::{Deserialize, Serialize};
@@ -50,9 +55,9 @@ use workspace::{
     searchable::{SearchEvent, SearchableItem},
     ui::{
         h_flex, v_flex, ActiveTheme, ButtonCommon, Clickable, Color, ContextMenu, FluentBuilder,
-        HighlightedLabel, Icon, IconButton, IconButtonShape, IconName, IconSize, Label,
-        LabelCommon, ListItem, Scrollbar, ScrollbarState, StyledExt, StyledTypography, Toggleable,
-        Tooltip,
+        HighlightedLabel, Icon, IconButton, IconButtonShape, IconName, IconPosition, IconSize,
+        Label, LabelCommon, ListItem, PopoverMenu, Scrollbar, ScrollbarState, StyledExt,
+        StyledTypography, Toggleable, Tooltip,
     },
     OpenInTerminal, WeakItemHandle, Workspace,
 };
@@ -72,7 +77,12 @@ actions!(
         RevealInFileManager,
         SelectParent,
         ToggleActiveEditorPin,
+        ToggleConstants,
+        ToggleFields,
         ToggleFocus,
+        ToggleFunctions,
+        ToggleSortByName,
+        ToggleTests,
         UnfoldDirectory,
     ]
 );
@@ -118,6 +128,7 @@ pub struct OutlinePanel {
     hide_scrollbar_task: Option<Task<()>>,
     max_width_item_index: Option<usize>,
     preserve_selection_on_buffer_fold_toggles: HashSet<BufferId>,
+    outline_filter: OutlineFilter,
 }
 
 #[derive(Debug)]
@@ -655,6 +666,8 @@ pub enum Event {
 struct SerializedOutlinePanel {
     width: Option<Pixels>,
     active: Option<bool>,
+    #[serde(default)]
+    outline_filter: OutlineFilter,
 }
 
 pub fn init_settings(cx: &mut App) {
@@ -696,6 +709,7 @@ impl OutlinePanel {
                 panel.update(cx, |panel, cx| {
                     panel.width = serialized_panel.width.map(|px| px.round());
                     panel.active = serialized_panel.active.unwrap_or(false);
+                    panel.outline_filter = serialized_panel.outline_filter;
                     cx.notify();
                 });
             }
@@ -769,7 +783,9 @@ impl OutlinePanel {
                 cx.observe_global_in::<SettingsStore>(window, move |outline_panel, window, cx| {
                     let new_settings = OutlinePanelSettings::get_global(cx);
                     let new_theme = ThemeSettings::get_global(cx);
-                    if &current_theme != new_theme {
+                    if &current_theme != new_theme
+                        || outline_panel_settings.outline_source != new_settings.outline_source
+                    {
                         outline_panel_settings = *new_settings;
                         current_theme = new_theme.clone();
                         for excerpts in outline_panel.excerpts.values_mut() {
@@ -816,6 +832,7 @@ impl OutlinePanel {
                 updating_fs_entries: false,
                 new_entries_for_fs_update: HashSet::default(),
                 preserve_selection_on_buffer_fold_toggles: HashSet::default(),
+                outline_filter: OutlineFilter::default(),
                 fs_entries_update_task: Task::ready(()),
                 cached_entries_update_task: Task::ready(()),
                 reveal_selection_task: Task::ready(Ok(())),
@@ -843,12 +860,17 @@ impl OutlinePanel {
     fn serialize(&mut self, cx: &mut Context<Self>) {
         let width = self.width;
         let active = Some(self.active);
+        let outline_filter = self.outline_filter.clone();
         self.pending_serialization = cx.background_executor().spawn(
             async move {
                 KEY_VALUE_STORE
                     .write_kvp(
                         OUTLINE_PANEL_KEY.into(),
-                        serde_json::to_string(&SerializedOutlinePanel { width, active })?,
+                        serde_json::to_string(&SerializedOutlinePanel {
+                            width,
+                            active,
+                            outline_filter,
+                        })?,
                     )
                     .await?;
                 anyhow::Ok(())
@@ -3188,23 +3210,63 @@ impl OutlinePanel {
         }
 
         let syntax_theme = cx.theme().syntax().clone();
+        let outline_source = OutlinePanelSettings::get_global(cx).outline_source;
         for (buffer_id, (buffer_snapshot, excerpt_ranges)) in excerpt_fetch_ranges {
+            let buffer = match outline_source {
+                OutlineSource::TreeSitter => None,
+                OutlineSource::LanguageServer => self.project.read(cx).buffer_for_id(buffer_id, cx),
+            };
+            let document_symbols = buffer.map(|buffer| {
+                let document_symbols = self
+                    .project
+                    .update(cx, |project, cx| project.document_symbols(&buffer, cx));
+                cx.background_executor()
+                    .spawn(async move { document_symbols.await.log_err() })
+                    .shared()
+            });
             for (excerpt_id, excerpt_range) in excerpt_ranges {
                 let syntax_theme = syntax_theme.clone();
                 let buffer_snapshot = buffer_snapshot.clone();
+                let document_symbols = document_symbols.clone();
+                let filter = self.outline_filter.clone();
                 self.outline_fetch_tasks.insert(
                     (buffer_id, excerpt_id),
                     cx.spawn_in(window, |outline_panel, mut cx| async move {
+                        let document_symbols = match document_symbols {
+                            Some(document_symbols) => document_symbols.await,
+                            None => None,
+                        };
                         let fetched_outlines = cx
                             .background_executor()
                             .spawn(async move {
-                                buffer_snapshot
-                                    .outline_items_containing(
-                                        excerpt_range.context,
-                                        false,
-                                        Some(&syntax_theme),
-                                    )
-                                    .unwrap_or_default()
+                                let outlines = match document_symbols
+                                    .filter(|document_symbols| !document_symbols.is_empty())
+                                {
+                                    Some(document_symbols) => {
+                                        outline_filter::outlines_from_symbols(
+                                            &document_symbols,
+                                            &excerpt_range.context,
+                                            &buffer_snapshot,
+                                        )
+                                    }
+                                    None => buffer_snapshot
+                                        .outline_items_containing(
+                                            excerpt_range.context,
+                                            false,
+                                            Some(&syntax_theme),
+                                        )
+                                        .unwrap_or_default()
+                                        .into_iter()
+                                        .map(|outline| {
+                                            let kind = outline_filter::outline_kind(
+                                                &outline,
+                                                &buffer_snapshot,
+                                            );
+                                            (outline, kind)
+                                        })
+                                        .collect(),
+                                };
+                                filter.apply(outlines)
                             })
                             .await;
                         outline_panel
@@ -4177,6 +4239,64 @@ impl OutlinePanel {
         cx.notify();
     }
 
+    fn toggle_functions(
+        &mut self,
+        _: &ToggleFunctions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_outline_kind(OutlineKind::Function, window, cx);
+    }
+
+    fn toggle_fields(&mut self, _: &ToggleFields, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_outline_kind(OutlineKind::Field, window, cx);
+    }
+
+    fn toggle_constants(
+        &mut self,
+        _: &ToggleConstants,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_outline_kind(OutlineKind::Constant, window, cx);
+    }
+
+    fn toggle_tests(&mut self, _: &ToggleTests, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_outline_kind(OutlineKind::Test, window, cx);
+    }
+
+    fn toggle_sort_by_name(
+        &mut self,
+        _: &ToggleSortByName,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.outline_filter.sort_by_name = !self.outline_filter.sort_by_name;
+        self.refetch_outlines(window, cx);
+        self.serialize(cx);
+    }
+
+    fn toggle_outline_kind(
+        &mut self,
+        kind: OutlineKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.outline_filter.toggle_kind(kind);
+        self.refetch_outlines(window, cx);
+        self.serialize(cx);
+    }
+
+    fn refetch_outlines(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for excerpts in self.excerpts.values_mut() {
+            for excerpt in excerpts.values_mut() {
+                excerpt.invalidate_outlines();
+            }
+        }
+        self.update_non_fs_items(window, cx);
+        cx.notify();
+    }
+
     fn selected_entry(&self) -> Option<&PanelEntry> {
         match &self.selected_entry {
             SelectedEntry::Invalidated(entry) => entry.as_ref(),
@@ -4614,6 +4734,7 @@ impl OutlinePanel {
                 .p_2()
                 .w_full()
                 .child(self.filter_editor.clone())
+                .child(self.render_outline_filter_menu(cx))
                 .child(
                     div().child(
                         IconButton::new(
@@ -4644,6 +4765,79 @@ impl OutlinePanel {
         )
     }
 
+    fn render_outline_filter_menu(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let outline_panel = cx.entity().downgrade();
+        let outline_filter = self.outline_filter.clone();
+        PopoverMenu::new("outline-panel-filter-menu")
+            .trigger(
+                IconButton::new("outline-panel-filter", IconName::Filter)
+                    .shape(IconButtonShape::Square)
+                    .toggle_state(outline_filter.is_active())
+                    .tooltip(Tooltip::text("Filter Outline")),
+            )
+            .anchor(Corner::BottomRight)
+            .menu(move |window, cx| {
+                let outline_panel = outline_panel.clone();
+                let outline_filter = outline_filter.clone();
+                Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                    let kind_entry =
+                        |menu: ContextMenu,
+                         label: &'static str,
+                         kind: OutlineKind,
+                         action: Box<dyn Action>| {
+                            let outline_panel = outline_panel.clone();
+                            menu.toggleable_entry(
+                                label,
+                                !outline_filter.hidden_kinds.contains(&kind),
+                                IconPosition::Start,
+                                Some(action),
+                                move |window, cx| {
+                                    outline_panel
+                                        .update(cx, |outline_panel, cx| {
+                                            outline_panel.toggle_outline_kind(kind, window, cx);
+                                        })
+                                        .ok();
+                                },
+                            )
+                        };
+                    let menu = menu.header("Show");
+                    let menu = kind_entry(
+                        menu,
+                        "Functions",
+                        OutlineKind::Function,
+                        Box::new(ToggleFunctions),
+                    );
+                    let menu =
+                        kind_entry(menu, "Fields", OutlineKind::Field, Box::new(ToggleFields));
+                    let menu = kind_entry(
+                        menu,
+                        "Constants",
+                        OutlineKind::Constant,
+                        Box::new(ToggleConstants),
+                    );
+                    let menu = kind_entry(menu, "Tests", OutlineKind::Test, Box::new(ToggleTests));
+                    let outline_panel = outline_panel.clone();
+                    menu.separator().toggleable_entry(
+                        "Sort by Name",
+                        outline_filter.sort_by_name,
+                        IconPosition::Start,
+                        Some(Box::new(ToggleSortByName)),
+                        move |window, cx| {
+                            outline_panel
+                                .update(cx, |outline_panel, cx| {
+                                    outline_panel.toggle_sort_by_name(
+                                        &ToggleSortByName,
+                                        window,
+                                        cx,
+                                    );
+                                })
+                                .ok();
+                        },
+                    )
+                }))
+            })
+    }
+
     fn buffers_inside_directory(
         &self,
         dir_worktree: WorktreeId,
@@ -4883,6 +5077,11 @@ impl Render for OutlinePanel {
             .on_action(cx.listener(Self::copy_path))
             .on_action(cx.listener(Self::copy_relative_path))
             .on_action(cx.listener(Self::toggle_active_editor_pin))
+            .on_action(cx.listener(Self::toggle_functions))
+            .on_action(cx.listener(Self::toggle_fields))
+            .on_action(cx.listener(Self::toggle_constants))
+            .on_action(cx.listener(Self::toggle_tests))
+            .on_action(cx.listener(Self::toggle_sort_by_name))
             .on_action(cx.listener(Self::unfold_directory))
             .on_action(cx.listener(Self::fold_directory))
             .on_action(cx.listener(Self::open_excerpts))
@@ -5995,6 +6194,153 @@ outline: struct OutlineEntryExcerpt
         });
     }
 
+    #[gpui::test]
+    async fn test_outline_filter(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let root = "/root";
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            root,
+            json!({
+                "src": {
+                    "lib.rs": indoc!("
+struct Config {
+    name: String,
+}
+
+const MAX_SIZE: usize = 10;
+
+fn parse() {}
+
+#[test]
+fn check_parse() {}"),
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [root.as_ref()], cx).await;
+        project.read_with(cx, |project, _| {
+            project.languages().add(Arc::new(
+                rust_lang()
+                    .with_outline_query(
+                        r#"
+                (attribute_item) @annotation
+
+                (struct_item
+                    "struct" @context
+                    name: (_) @name) @item
+
+                (field_declaration
+                    (visibility_modifier)? @context
+                    name: (_) @name) @item
+
+                (const_item
+                    "const" @context
+                    name: (_) @name) @item
+
+                (function_item
+                    "fn" @context
+                    name: (_) @name) @item
+"#,
+                    )
+                    .unwrap(),
+            ))
+        });
+        let workspace = add_outline_panel(&project, cx).await;
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let outline_panel = outline_panel(&workspace, cx);
+        cx.update(|window, cx| {
+            outline_panel.update(cx, |outline_panel, cx| {
+                outline_panel.set_active(true, window, cx)
+            });
+        });
+
+        workspace
+            .update(cx, |workspace, window, cx| {
+                workspace.open_abs_path(PathBuf::from("/root/src/lib.rs"), true, window, cx)
+            })
+            .unwrap()
+            .await
+            .expect("Failed to open Rust source file");
+        cx.executor()
+            .advance_clock(UPDATE_DEBOUNCE + Duration::from_millis(100));
+        cx.run_until_parked();
+        outline_panel.update(cx, |outline_panel, cx| {
+            assert_eq!(
+                display_entries(
+                    &snapshot(&outline_panel, cx),
+                    &outline_panel.cached_entries,
+                    outline_panel.selected_entry()
+                ),
+                indoc!(
+                    "
+outline: struct Config
+  outline: name
+outline: const MAX_SIZE
+outline: fn parse
+outline: fn check_parse"
+                )
+            );
+        });
+
+        // Update the filter directly, as the serialized panel state is shared between tests.
+        cx.update(|window, cx| {
+            outline_panel.update(cx, |outline_panel, cx| {
+                outline_panel.outline_filter.toggle_kind(OutlineKind::Field);
+                outline_panel.outline_filter.toggle_kind(OutlineKind::Test);
+                outline_panel.refetch_outlines(window, cx);
+            });
+        });
+        cx.executor()
+            .advance_clock(UPDATE_DEBOUNCE + Duration::from_millis(100));
+        cx.run_until_parked();
+        outline_panel.update(cx, |outline_panel, cx| {
+            assert_eq!(
+                display_entries(
+                    &snapshot(&outline_panel, cx),
+                    &outline_panel.cached_entries,
+                    outline_panel.selected_entry()
+                ),
+                indoc!(
+                    "
+outline: struct Config
+outline: const MAX_SIZE
+outline: fn parse"
+                ),
+                "Fields and tests should be hidden"
+            );
+        });
+
+        cx.update(|window, cx| {
+            outline_panel.update(cx, |outline_panel, cx| {
+                outline_panel.outline_filter.toggle_kind(OutlineKind::Test);
+                outline_panel.outline_filter.sort_by_name = true;
+                outline_panel.refetch_outlines(window, cx);
+            });
+        });
+        cx.executor()
+            .advance_clock(UPDATE_DEBOUNCE + Duration::from_millis(100));
+        cx.run_until_parked();
+        outline_panel.update(cx, |outline_panel, cx| {
+            assert_eq!(
+                display_entries(
+                    &snapshot(&outline_panel, cx),
+                    &outline_panel.cached_entries,
+                    outline_panel.selected_entry()
+                ),
+                indoc!(
+                    "
+outline: fn check_parse
+outline: struct Config
+outline: const MAX_SIZE
+outline: fn parse"
+                ),
+                "Outline items should be sorted by name"
+            );
+        });
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_frontend_repo_structure(cx: &mut TestAppContext) {
         init_test(cx);
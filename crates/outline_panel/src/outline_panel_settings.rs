@@ -18,6 +18,17 @@ pub enum ShowIndentGuides {
     Never,
 }
 
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlineSource {
+    /// Build the outline from the language's tree-sitter outline queries.
+    #[default]
+    TreeSitter,
+    /// Build the outline from the language server's document symbols,
+    /// falling back to tree-sitter when no language server provides them.
+    LanguageServer,
+}
+
 #[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct OutlinePanelSettings {
     pub button: bool,
@@ -30,6 +41,7 @@ pub struct OutlinePanelSettings {
     pub indent_guides: IndentGuidesSettings,
     pub auto_reveal_entries: bool,
     pub auto_fold_dirs: bool,
+    pub outline_source: OutlineSource,
     pub scrollbar: ScrollbarSettings,
 }
 
@@ -101,6 +113,10 @@ pub struct OutlinePanelSettingsContent {
     ///
     /// Default: true
     pub auto_fold_dirs: Option<bool>,
+    /// Where to get the outline items from.
+    ///
+    /// Default: tree_sitter
+    pub outline_source: Option<OutlineSource>,
     /// Settings related to indent guides in the outline panel.
     pub indent_guides: Option<IndentGuidesSettingsContent>,
     /// Scrollbar-related settings
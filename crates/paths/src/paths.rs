@@ -157,6 +157,28 @@ pub fn tasks_file() -> &'static PathBuf {
     TASKS_FILE.get_or_init(|| config_dir().join("tasks.json"))
 }
 
+/// Returns the path to the `words.txt` file, which lists words the spell checker accepts.
+pub fn words_file() -> &'static PathBuf {
+    static WORDS_FILE: OnceLock<PathBuf> = OnceLock::new();
+    WORDS_FILE.get_or_init(|| config_dir().join("words.txt"))
+}
+
+/// Returns the path to the dictionaries directory.
+///
+/// This is where Hunspell dictionaries used by the spell checker can be stored.
+pub fn dictionaries_dir() -> &'static PathBuf {
+    static DICTIONARIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DICTIONARIES_DIR.get_or_init(|| config_dir().join("dictionaries"))
+}
+
+/// Returns the path to the shell integration directory.
+///
+/// This is where the scripts that integrate shells with Zed's terminal are written.
+pub fn shell_integration_dir() -> &'static PathBuf {
+    static SHELL_INTEGRATION_DIR: OnceLock<PathBuf> = OnceLock::new();
+    SHELL_INTEGRATION_DIR.get_or_init(|| support_dir().join("shell_integration"))
+}
+
 /// Returns the path to the extensions directory.
 ///
 /// This is where installed extensions are stored.
@@ -311,6 +333,11 @@ pub fn local_tasks_file_relative_path() -> &'static Path {
     Path::new(".zed/tasks.json")
 }
 
+/// Returns the relative path to a `words.txt` file within a project.
+pub fn local_words_file_relative_path() -> &'static Path {
+    Path::new(".zed/words.txt")
+}
+
 /// Returns the relative path to a `.vscode/tasks.json` file within a project.
 pub fn local_vscode_tasks_file_relative_path() -> &'static Path {
     Path::new(".vscode/tasks.json")
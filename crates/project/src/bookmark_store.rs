@@ -0,0 +1,300 @@
+use collections::BTreeMap;
+use gpui::{App, Context, Entity, EventEmitter, Subscription};
+use language::{Anchor, Buffer, BufferEvent, BufferSnapshot, Point, ToPoint as _};
+use worktree::File;
+
+use crate::{
+    buffer_store::{BufferStore, BufferStoreEvent},
+    ProjectPath,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BookmarkId(usize);
+
+/// Where a bookmark is in its file. Bookmarks are anchored while their buffer is open so that
+/// they follow their line as the buffer is edited, and only store a row otherwise, such as after
+/// they've been restored from a previous session.
+#[derive(Clone, Debug)]
+enum BookmarkPosition {
+    Anchor(Anchor),
+    Row(u32),
+}
+
+#[derive(Clone, Debug)]
+struct Bookmark {
+    id: BookmarkId,
+    position: BookmarkPosition,
+    label: Option<String>,
+}
+
+impl Bookmark {
+    fn row(&self, snapshot: Option<&BufferSnapshot>) -> u32 {
+        match (&self.position, snapshot) {
+            (BookmarkPosition::Anchor(anchor), Some(snapshot)) => anchor.to_point(snapshot).row,
+            (BookmarkPosition::Anchor(_), None) => 0,
+            (BookmarkPosition::Row(row), _) => *row,
+        }
+    }
+}
+
+struct FileBookmarks {
+    buffer: Option<(Entity<Buffer>, Subscription)>,
+    bookmarks: Vec<Bookmark>,
+}
+
+impl FileBookmarks {
+    fn snapshot(&self, cx: &App) -> Option<BufferSnapshot> {
+        self.buffer
+            .as_ref()
+            .map(|(buffer, _)| buffer.read(cx).snapshot())
+    }
+}
+
+/// A bookmark resolved to the row it's currently on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BookmarkEntry {
+    pub id: BookmarkId,
+    pub row: u32,
+    pub label: Option<String>,
+}
+
+pub enum BookmarkStoreEvent {
+    /// Bookmarks were added, removed or relabeled, or their file was renamed. Edits that move
+    /// bookmarks to other rows only notify observers.
+    BookmarksChanged,
+}
+
+/// Stores the lines that have been bookmarked across all files in a project.
+///
+/// The buffers of files with bookmarks are kept open, so that their bookmarks stay anchored.
+pub struct BookmarkStore {
+    buffer_store: Entity<BufferStore>,
+    files: BTreeMap<ProjectPath, FileBookmarks>,
+    next_bookmark_id: usize,
+    _subscription: Subscription,
+}
+
+impl BookmarkStore {
+    pub fn new(buffer_store: Entity<BufferStore>, cx: &mut Context<Self>) -> Self {
+        let subscription =
+            cx.subscribe(
+                &buffer_store,
+                |this, _, event: &BufferStoreEvent, cx| match event {
+                    BufferStoreEvent::BufferAdded(buffer) => this.buffer_opened(buffer, cx),
+                    BufferStoreEvent::BufferChangedFilePath { buffer, old_file } => {
+                        let old_path = File::from_dyn(old_file.as_ref()).map(|file| ProjectPath {
+                            worktree_id: file.worktree_id(cx),
+                            path: file.path.clone(),
+                        });
+                        let new_path = project_path(buffer, cx);
+                        if let Some((old_path, new_path)) = old_path.zip(new_path) {
+                            if let Some(file) = this.files.remove(&old_path) {
+                                this.files.insert(new_path, file);
+                                cx.emit(BookmarkStoreEvent::BookmarksChanged);
+                                cx.notify();
+                            }
+                        }
+                    }
+                    BufferStoreEvent::BufferDropped(_) => {}
+                },
+            );
+        Self {
+            buffer_store,
+            files: BTreeMap::default(),
+            next_bookmark_id: 0,
+            _subscription: subscription,
+        }
+    }
+
+    fn buffer_opened(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(path) = project_path(buffer, cx) else {
+            return;
+        };
+        let Some(file) = self.files.get_mut(&path) else {
+            return;
+        };
+        if file.buffer.is_none() {
+            Self::anchor_bookmarks(file, buffer, cx);
+            cx.notify();
+        }
+    }
+
+    /// Anchors the bookmarks of a file whose buffer has just been opened.
+    fn anchor_bookmarks(file: &mut FileBookmarks, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let snapshot = buffer.read(cx).snapshot();
+        for bookmark in &mut file.bookmarks {
+            if let BookmarkPosition::Row(row) = bookmark.position {
+                bookmark.position = BookmarkPosition::Anchor(anchor_for_row(&snapshot, row));
+            }
+        }
+        // Notify when edits move bookmarks to other rows.
+        let subscription = cx.subscribe(buffer, |_, _, event: &BufferEvent, cx| {
+            if let BufferEvent::Edited = event {
+                cx.notify();
+            }
+        });
+        file.buffer = Some((buffer.clone(), subscription));
+    }
+
+    fn next_id(&mut self) -> BookmarkId {
+        let id = BookmarkId(self.next_bookmark_id);
+        self.next_bookmark_id += 1;
+        id
+    }
+
+    /// Adds a bookmark to the given row of `buffer`, or removes the bookmarks on that row if
+    /// there are any.
+    pub fn toggle_bookmark(&mut self, buffer: &Entity<Buffer>, row: u32, cx: &mut Context<Self>) {
+        let Some(path) = project_path(buffer, cx) else {
+            return;
+        };
+        let id = self.next_id();
+        let file = self
+            .files
+            .entry(path.clone())
+            .or_insert_with(|| FileBookmarks {
+                buffer: None,
+                bookmarks: Vec::new(),
+            });
+        if file.buffer.is_none() {
+            Self::anchor_bookmarks(file, buffer, cx);
+        }
+
+        let snapshot = buffer.read(cx).snapshot();
+        let bookmark_count = file.bookmarks.len();
+        file.bookmarks
+            .retain(|bookmark| bookmark.row(Some(&snapshot)) != row);
+        if file.bookmarks.len() == bookmark_count {
+            file.bookmarks.push(Bookmark {
+                id,
+                position: BookmarkPosition::Anchor(anchor_for_row(&snapshot, row)),
+                label: None,
+            });
+        } else if file.bookmarks.is_empty() {
+            self.files.remove(&path);
+        }
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    /// Returns the sorted rows of the bookmarks in `buffer`.
+    pub fn bookmarked_rows(&self, buffer: &Entity<Buffer>, cx: &App) -> Vec<u32> {
+        let Some(file) = project_path(buffer, cx).and_then(|path| self.files.get(&path)) else {
+            return Vec::new();
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let mut rows = file
+            .bookmarks
+            .iter()
+            .map(|bookmark| bookmark.row(Some(&snapshot)))
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+
+    /// Returns the bookmarks in every file, ordered by path and then by row.
+    pub fn bookmarks(&self, cx: &App) -> Vec<(ProjectPath, Vec<BookmarkEntry>)> {
+        self.files
+            .iter()
+            .map(|(path, file)| {
+                let snapshot = file.snapshot(cx);
+                let mut entries = file
+                    .bookmarks
+                    .iter()
+                    .map(|bookmark| BookmarkEntry {
+                        id: bookmark.id,
+                        row: bookmark.row(snapshot.as_ref()),
+                        label: bookmark.label.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                entries.sort_by_key(|entry| entry.row);
+                (path.clone(), entries)
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Sets the label shown for a bookmark, or clears it if `label` is empty.
+    pub fn set_label(&mut self, id: BookmarkId, label: String, cx: &mut Context<Self>) {
+        let label = label.trim();
+        if let Some(bookmark) = self
+            .files
+            .values_mut()
+            .flat_map(|file| file.bookmarks.iter_mut())
+            .find(|bookmark| bookmark.id == id)
+        {
+            bookmark.label = (!label.is_empty()).then(|| label.to_string());
+            cx.emit(BookmarkStoreEvent::BookmarksChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn remove_bookmark(&mut self, id: BookmarkId, cx: &mut Context<Self>) {
+        for file in self.files.values_mut() {
+            file.bookmarks.retain(|bookmark| bookmark.id != id);
+        }
+        self.files.retain(|_, file| !file.bookmarks.is_empty());
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        if !self.files.is_empty() {
+            self.files.clear();
+            cx.emit(BookmarkStoreEvent::BookmarksChanged);
+            cx.notify();
+        }
+    }
+
+    /// Adds bookmarks from a previous session, given as rows. Bookmarks in files that are
+    /// already open are anchored right away, and the others once their file is opened.
+    pub fn restore(
+        &mut self,
+        bookmarks: impl IntoIterator<Item = (ProjectPath, u32, Option<String>)>,
+        cx: &mut Context<Self>,
+    ) {
+        for (path, row, label) in bookmarks {
+            let id = self.next_id();
+            let file = self
+                .files
+                .entry(path.clone())
+                .or_insert_with(|| FileBookmarks {
+                    buffer: None,
+                    bookmarks: Vec::new(),
+                });
+            let position = match file.snapshot(cx) {
+                Some(snapshot) => BookmarkPosition::Anchor(anchor_for_row(&snapshot, row)),
+                None => BookmarkPosition::Row(row),
+            };
+            file.bookmarks.push(Bookmark {
+                id,
+                position,
+                label,
+            });
+            if file.buffer.is_none() {
+                if let Some(buffer) = self.buffer_store.read(cx).get_by_path(&path, cx) {
+                    Self::anchor_bookmarks(file, &buffer, cx);
+                }
+            }
+        }
+        cx.notify();
+    }
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+fn project_path(buffer: &Entity<Buffer>, cx: &App) -> Option<ProjectPath> {
+    let file = File::from_dyn(buffer.read(cx).file())?;
+    Some(ProjectPath {
+        worktree_id: file.worktree_id(cx),
+        path: file.path.clone(),
+    })
+}
+
+fn anchor_for_row(snapshot: &BufferSnapshot, row: u32) -> Anchor {
+    snapshot.anchor_after(Point::new(row.min(snapshot.max_point().row), 0))
+}
@@ -1,5 +1,6 @@
 use crate::{
     lsp_store::OpenLspBufferHandle,
+    project_settings::ProjectSettings,
     search::SearchQuery,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
     ProjectItem as _, ProjectPath,
@@ -9,7 +10,11 @@ use anyhow::{anyhow, bail, Context as _, Result};
 use client::Client;
 use collections::{hash_map, HashMap, HashSet};
 use fs::Fs;
-use futures::{channel::oneshot, future::Shared, Future, FutureExt as _, StreamExt};
+use futures::{
+    channel::{mpsc, oneshot},
+    future::Shared,
+    Future, FutureExt as _, StreamExt,
+};
 use git::{blame::Blame, diff::BufferDiff, repository::RepoPath};
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
@@ -24,6 +29,7 @@ use language::{
 };
 use rpc::{proto, AnyProtoClient, ErrorExt as _, TypedEnvelope};
 use serde::Deserialize;
+use settings::{Settings as _, SettingsLocation};
 use smol::channel::Receiver;
 use std::{
     io,
@@ -36,7 +42,10 @@ use std::{
 };
 use text::{BufferId, LineEnding, Rope};
 use util::{debug_panic, maybe, ResultExt as _, TryFutureExt};
-use worktree::{File, PathChange, ProjectEntryId, UpdatedGitRepositoriesSet, Worktree, WorktreeId};
+use worktree::{
+    File, LoadedFile, PathChange, ProjectEntryId, StreamedFile, UpdatedGitRepositoriesSet,
+    Worktree, WorktreeId,
+};
 
 /// A set of open buffers.
 pub struct BufferStore {
@@ -789,40 +798,77 @@ impl LocalBufferStore {
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<Entity<Buffer>>> {
         let load_buffer = worktree.update(cx, |worktree, cx| {
-            let load_file = worktree.load_file(path.as_ref(), cx);
             let reservation = cx.reserve_entity();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
-            cx.spawn(move |_, mut cx| async move {
-                let loaded = load_file.await?;
-                let text_buffer = cx
-                    .background_executor()
-                    .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
-                    .await;
-                cx.insert_entity(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
+            let large_file_settings = ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.id(),
+                    path: &path,
+                }),
+                cx,
+            )
+            .large_file;
+            let streamed = large_file_settings.read_only
+                && worktree.entry_for_path(&path).map_or(false, |entry| {
+                    large_file_settings.is_large(entry.size as usize)
+                });
+            if streamed {
+                let load_file = worktree.load_file_streamed(path.as_ref(), STREAMED_CHUNK_LEN, cx);
+                cx.spawn(move |_, mut cx| async move {
+                    let StreamedFile {
+                        file,
+                        text,
+                        remaining_text,
+                    } = load_file.await?;
+                    let text_buffer = cx
+                        .background_executor()
+                        .spawn(async move { text::Buffer::new(0, buffer_id, text) })
+                        .await;
+                    let buffer = cx.insert_entity(reservation, |cx| {
+                        let mut buffer =
+                            Buffer::build(text_buffer, Some(file), Capability::ReadOnly);
+                        buffer.set_large_file(true, cx);
+                        buffer
+                    })?;
+                    anyhow::Ok((buffer, Some(remaining_text)))
                 })
-            })
+            } else {
+                let load_file = worktree.load_file(path.as_ref(), cx);
+                cx.spawn(move |_, mut cx| async move {
+                    let LoadedFile { file, text } = load_file.await?;
+                    let text_buffer = cx
+                        .background_executor()
+                        .spawn(async move { text::Buffer::new(0, buffer_id, text) })
+                        .await;
+                    let buffer = cx.insert_entity(reservation, |_| {
+                        Buffer::build(text_buffer, Some(file), Capability::ReadWrite)
+                    })?;
+                    anyhow::Ok((buffer, None))
+                })
+            }
         });
 
         cx.spawn(move |this, mut cx| async move {
-            let buffer = match load_buffer.await {
-                Ok(buffer) => Ok(buffer),
-                Err(error) if is_not_found_error(&error) => cx.new(|cx| {
-                    let buffer_id = BufferId::from(cx.entity_id().as_non_zero_u64());
-                    let text_buffer = text::Buffer::new(0, buffer_id, "".into());
-                    Buffer::build(
-                        text_buffer,
-                        Some(Arc::new(File {
-                            worktree,
-                            path,
-                            disk_state: DiskState::New,
-                            entry_id: None,
-                            is_local: true,
-                            is_private: false,
-                        })),
-                        Capability::ReadWrite,
-                    )
-                }),
+            let (buffer, remaining_text) = match load_buffer.await {
+                Ok(loaded) => Ok(loaded),
+                Err(error) if is_not_found_error(&error) => cx
+                    .new(|cx| {
+                        let buffer_id = BufferId::from(cx.entity_id().as_non_zero_u64());
+                        let text_buffer = text::Buffer::new(0, buffer_id, "".into());
+                        Buffer::build(
+                            text_buffer,
+                            Some(Arc::new(File {
+                                worktree,
+                                path,
+                                disk_state: DiskState::New,
+                                entry_id: None,
+                                is_local: true,
+                                is_private: false,
+                            })),
+                            Capability::ReadWrite,
+                        )
+                    })
+                    .map(|buffer| (buffer, None)),
                 Err(e) => Err(e),
             }?;
             this.update(&mut cx, |this, cx| {
@@ -847,6 +893,16 @@ impl LocalBufferStore {
                 anyhow::Ok(())
             })??;
 
+            if let Some(remaining_text) = remaining_text {
+                let weak_buffer = buffer.downgrade();
+                cx.spawn(|cx| async move {
+                    stream_remaining_text(weak_buffer, remaining_text, cx)
+                        .await
+                        .log_err();
+                })
+                .detach();
+            }
+
             Ok(buffer)
         })
     }
@@ -1337,6 +1393,27 @@ impl BufferStore {
             }
         }
 
+        let is_large_file = File::from_dyn(buffer.read(cx).file()).map_or(false, |file| {
+            // Entry sizes are replicated to remote worktrees, so a large file is detected
+            // there even though its contents have already been loaded by the host.
+            let file_size = file
+                .entry_id
+                .and_then(|entry_id| file.worktree.read(cx).entry_for_id(entry_id))
+                .map_or(0, |entry| entry.size as usize);
+            ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: file.worktree_id(cx),
+                    path: &file.path,
+                }),
+                cx,
+            )
+            .large_file
+            .is_large(file_size.max(buffer.read(cx).len()))
+        });
+        if is_large_file {
+            buffer.update(cx, |buffer, cx| buffer.set_large_file(true, cx));
+        }
+
         cx.subscribe(&buffer, Self::on_buffer_event).detach();
         cx.emit(BufferStoreEvent::BufferAdded(buffer));
         Ok(())
@@ -2384,6 +2461,24 @@ impl OpenBuffer {
     }
 }
 
+/// The number of bytes read from disk at a time when streaming a large file into its buffer.
+const STREAMED_CHUNK_LEN: usize = 1024 * 1024;
+
+/// Appends the rest of a read-only large file to its buffer as it is read from disk, so that
+/// the beginning of the file can be shown while the rest is still being loaded.
+async fn stream_remaining_text(
+    buffer: WeakEntity<Buffer>,
+    mut chunks: mpsc::Receiver<Result<String>>,
+    mut cx: AsyncApp,
+) -> Result<()> {
+    while let Some(chunk) = chunks.next().await {
+        let mut chunk = chunk?;
+        LineEnding::normalize(&mut chunk);
+        buffer.update(&mut cx, |buffer, cx| buffer.append_loaded_text(&chunk, cx))?;
+    }
+    Ok(())
+}
+
 fn is_not_found_error(error: &anyhow::Error) -> bool {
     error
         .root_cause()
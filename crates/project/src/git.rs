@@ -9,7 +9,8 @@ use git::{
     status::{GitSummary, TrackedSummary},
 };
 use gpui::{
-    App, AppContext as _, Context, Entity, EventEmitter, SharedString, Subscription, WeakEntity,
+    App, AppContext as _, Context, Entity, EventEmitter, SharedString, Subscription, Task,
+    WeakEntity,
 };
 use language::{Buffer, LanguageRegistry};
 use rpc::{proto, AnyProtoClient};
@@ -369,6 +370,38 @@ impl RepositoryHandle {
         Some((self.worktree_id, path).into())
     }
 
+    /// Loads the contents of a file as of the HEAD commit, which is `None` if the file isn't
+    /// committed.
+    pub fn load_committed_text(
+        &self,
+        repo_path: RepoPath,
+        cx: &App,
+    ) -> Task<anyhow::Result<Option<String>>> {
+        match self.git_repo.clone() {
+            Some(GitRepo::Local(repo)) => cx
+                .background_executor()
+                .spawn(async move { Ok(repo.load_committed_text(&repo_path.0)) }),
+            Some(GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            }) => cx.background_executor().spawn(async move {
+                let response = client
+                    .request(proto::LoadCommittedText {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                        path: repo_path.to_proto(),
+                    })
+                    .await
+                    .context("sending load committed text request")?;
+                Ok(response.text)
+            }),
+            None => Task::ready(Err(anyhow!("no git repository"))),
+        }
+    }
+
     pub fn commit_message(&self) -> Entity<Buffer> {
         self.commit_message.clone()
     }
@@ -2,9 +2,10 @@ mod signature_help;
 
 use crate::{
     lsp_store::{LocalLspStore, LspStore},
-    CodeAction, CoreCompletion, DocumentHighlight, Hover, HoverBlock, HoverBlockKind, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
-    LocationLink, MarkupContent, PrepareRenameResponse, ProjectTransaction, ResolveState,
+    CodeAction, CoreCompletion, DocumentHighlight, DocumentSymbol, Hover, HoverBlock,
+    HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip,
+    InlayHintTooltip, Location, LocationLink, MarkupContent, PrepareRenameResponse,
+    ProjectTransaction, ResolveState,
 };
 use anyhow::{anyhow, Context as _, Result};
 use async_trait::async_trait;
@@ -200,6 +201,9 @@ pub(crate) struct GetDocumentHighlights {
     pub position: PointUtf16,
 }
 
+#[derive(Debug)]
+pub(crate) struct GetDocumentSymbols;
+
 #[derive(Clone, Debug)]
 pub(crate) struct GetSignatureHelp {
     pub position: PointUtf16,
@@ -1475,6 +1479,231 @@ impl LspCommand for GetDocumentHighlights {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentSymbols {
+    type Response = Vec<DocumentSymbol>;
+    type LspRequest = lsp::request::DocumentSymbolRequest;
+    type ProtoRequest = proto::GetDocumentSymbols;
+
+    fn display_name(&self) -> &str {
+        "Get document symbols"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .document_symbol_provider
+            .is_some_and(|provider| !matches!(provider, OneOf::Left(false)))
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentSymbolParams> {
+        Ok(lsp::DocumentSymbolParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        response: Option<lsp::DocumentSymbolResponse>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentSymbol>> {
+        buffer.update(&mut cx, |buffer, _| {
+            let to_anchor_range = |range: lsp::Range| {
+                let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                buffer.anchor_after(start)..buffer.anchor_before(end)
+            };
+            match response {
+                Some(lsp::DocumentSymbolResponse::Nested(lsp_symbols)) => {
+                    fn document_symbol(
+                        lsp_symbol: lsp::DocumentSymbol,
+                        to_anchor_range: &impl Fn(lsp::Range) -> Range<Anchor>,
+                    ) -> DocumentSymbol {
+                        DocumentSymbol {
+                            name: lsp_symbol.name,
+                            detail: lsp_symbol.detail,
+                            kind: lsp_symbol.kind,
+                            range: to_anchor_range(lsp_symbol.range),
+                            selection_range: to_anchor_range(lsp_symbol.selection_range),
+                            children: lsp_symbol
+                                .children
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|child| document_symbol(child, to_anchor_range))
+                                .collect(),
+                        }
+                    }
+
+                    lsp_symbols
+                        .into_iter()
+                        .map(|lsp_symbol| document_symbol(lsp_symbol, &to_anchor_range))
+                        .collect()
+                }
+                Some(lsp::DocumentSymbolResponse::Flat(mut lsp_symbols)) => {
+                    // Flat symbols have no hierarchy, so nest them by their ranges instead.
+                    lsp_symbols.sort_by_key(|symbol| {
+                        (
+                            symbol.location.range.start,
+                            Reverse(symbol.location.range.end),
+                        )
+                    });
+                    let mut symbols = Vec::new();
+                    let mut stack = Vec::<(lsp::Range, DocumentSymbol)>::new();
+                    for lsp_symbol in lsp_symbols {
+                        let lsp_range = lsp_symbol.location.range;
+                        while let Some((parent_range, _)) = stack.last() {
+                            if parent_range.end >= lsp_range.end {
+                                break;
+                            }
+                            let (_, symbol) = stack.pop().unwrap();
+                            match stack.last_mut() {
+                                Some((_, parent)) => parent.children.push(symbol),
+                                None => symbols.push(symbol),
+                            }
+                        }
+                        let range = to_anchor_range(lsp_range);
+                        stack.push((
+                            lsp_range,
+                            DocumentSymbol {
+                                name: lsp_symbol.name,
+                                detail: None,
+                                kind: lsp_symbol.kind,
+                                selection_range: range.clone(),
+                                range,
+                                children: Vec::new(),
+                            },
+                        ));
+                    }
+                    while let Some((_, symbol)) = stack.pop() {
+                        match stack.last_mut() {
+                            Some((_, parent)) => parent.children.push(symbol),
+                            None => symbols.push(symbol),
+                        }
+                    }
+                    symbols
+                }
+                None => Vec::new(),
+            }
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentSymbols {
+        proto::GetDocumentSymbols {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentSymbols,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<DocumentSymbol>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentSymbolsResponse {
+        fn serialize_document_symbol(symbol: DocumentSymbol) -> proto::DocumentSymbol {
+            proto::DocumentSymbol {
+                name: symbol.name,
+                detail: symbol.detail,
+                kind: unsafe { std::mem::transmute::<lsp::SymbolKind, i32>(symbol.kind) },
+                start: Some(serialize_anchor(&symbol.range.start)),
+                end: Some(serialize_anchor(&symbol.range.end)),
+                selection_start: Some(serialize_anchor(&symbol.selection_range.start)),
+                selection_end: Some(serialize_anchor(&symbol.selection_range.end)),
+                children: symbol
+                    .children
+                    .into_iter()
+                    .map(serialize_document_symbol)
+                    .collect(),
+            }
+        }
+
+        proto::GetDocumentSymbolsResponse {
+            symbols: response
+                .into_iter()
+                .map(serialize_document_symbol)
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentSymbolsResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentSymbol>> {
+        fn deserialize_document_symbol(
+            symbol: proto::DocumentSymbol,
+            anchors: &mut Vec<Anchor>,
+        ) -> Result<DocumentSymbol> {
+            let mut deserialize = |anchor: Option<proto::Anchor>| {
+                let anchor = anchor
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid document symbol range"))?;
+                anchors.push(anchor);
+                anyhow::Ok(anchor)
+            };
+            let range = deserialize(symbol.start)?..deserialize(symbol.end)?;
+            let selection_range =
+                deserialize(symbol.selection_start)?..deserialize(symbol.selection_end)?;
+            Ok(DocumentSymbol {
+                name: symbol.name,
+                detail: symbol.detail,
+                kind: unsafe { std::mem::transmute::<i32, lsp::SymbolKind>(symbol.kind) },
+                range,
+                selection_range,
+                children: symbol
+                    .children
+                    .into_iter()
+                    .map(|child| deserialize_document_symbol(child, anchors))
+                    .collect::<Result<_>>()?,
+            })
+        }
+
+        let mut anchors = Vec::new();
+        let symbols = message
+            .symbols
+            .into_iter()
+            .map(|symbol| deserialize_document_symbol(symbol, &mut anchors))
+            .collect::<Result<Vec<_>>>()?;
+        buffer
+            .update(&mut cx, |buffer, _| buffer.wait_for_anchors(anchors))?
+            .await?;
+        Ok(symbols)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentSymbols) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 #[async_trait(?Send)]
 impl LspCommand for GetSignatureHelp {
     type Response = Option<SignatureHelp>;
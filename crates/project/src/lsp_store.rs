@@ -2880,6 +2880,7 @@ impl LspStore {
         client.add_model_request_handler(Self::handle_lsp_command::<GetDeclaration>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetTypeDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetReferences>);
         client.add_model_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_model_request_handler(Self::handle_lsp_command::<PerformRename>);
@@ -3209,6 +3210,12 @@ impl LspStore {
                 self.on_buffer_saved(buffer, cx);
             }
 
+            language::BufferEvent::LargeFileChanged => {
+                if !buffer.read(cx).is_large_file() && buffer.read(cx).language().is_none() {
+                    self.detect_language_for_buffer(&buffer, cx);
+                }
+            }
+
             _ => {}
         }
     }
@@ -3239,6 +3246,9 @@ impl LspStore {
         let buffer_id = buffer.read(cx).remote_id();
 
         let handle = cx.new(|_| buffer.clone());
+        if buffer.read(cx).is_large_file() {
+            return handle;
+        }
 
         if let Some(local) = self.as_local_mut() {
             let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
@@ -3368,6 +3378,9 @@ impl LspStore {
     ) -> Option<language::AvailableLanguage> {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
+        if buffer.is_large_file() {
+            return None;
+        }
         let file = buffer.file()?;
 
         let content = buffer.as_rope();
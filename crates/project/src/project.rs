@@ -1,3 +1,4 @@
+pub mod bookmark_store;
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
@@ -10,6 +11,7 @@ pub mod lsp_store;
 pub mod prettier_store;
 pub mod project_settings;
 pub mod search;
+pub mod shared_terminal_store;
 mod task_inventory;
 pub mod task_store;
 pub mod terminals;
@@ -28,6 +30,7 @@ mod yarn;
 
 use crate::git::GitState;
 use anyhow::{anyhow, Context as _, Result};
+pub use bookmark_store::{BookmarkEntry, BookmarkId, BookmarkStore, BookmarkStoreEvent};
 use buffer_store::{BufferChangeSet, BufferStore, BufferStoreEvent};
 use client::{
     proto, Client, Collaborator, PendingEntitySubscription, ProjectId, TypedEnvelope, UserStore,
@@ -78,6 +81,7 @@ use rpc::{
 use search::{SearchInputKind, SearchQuery, SearchResult};
 use search_history::SearchHistory;
 use settings::{InvalidSettingsError, Settings, SettingsLocation, SettingsStore};
+use shared_terminal_store::SharedTerminalStore;
 use smol::channel::Receiver;
 use snippet::Snippet;
 use snippet_provider::SnippetProvider;
@@ -129,6 +133,18 @@ pub trait ProjectItem {
     ) -> Option<Task<Result<Entity<Self>>>>
     where
         Self: Sized;
+    /// Like [`Self::try_open`], but only called for files that the project item that claimed
+    /// them failed to open, e.g. because they aren't valid UTF-8.
+    fn try_open_as_fallback(
+        _project: &Entity<Project>,
+        _path: &ProjectPath,
+        _cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>>
+    where
+        Self: Sized,
+    {
+        None
+    }
     fn entry_id(&self, cx: &App) -> Option<ProjectEntryId>;
     fn project_path(&self, cx: &App) -> Option<ProjectPath>;
     fn is_dirty(&self) -> bool;
@@ -162,6 +178,7 @@ pub struct Project {
     client_subscriptions: Vec<client::Subscription>,
     worktree_store: Entity<WorktreeStore>,
     buffer_store: Entity<BufferStore>,
+    bookmark_store: Entity<BookmarkStore>,
     image_store: Entity<ImageStore>,
     lsp_store: Entity<LspStore>,
     _subscriptions: Vec<gpui::Subscription>,
@@ -169,6 +186,7 @@ pub struct Project {
     git_diff_debouncer: DebouncedDelay<Self>,
     remotely_created_models: Arc<Mutex<RemotelyCreatedModels>>,
     terminals: Terminals,
+    shared_terminal_store: Entity<SharedTerminalStore>,
     node: Option<NodeRuntime>,
     search_history: SearchHistory,
     search_included_history: SearchHistory,
@@ -473,6 +491,17 @@ pub struct DocumentHighlight {
     pub kind: DocumentHighlightKind,
 }
 
+/// A symbol in a buffer, as reported by the language server's `textDocument/documentSymbol`.
+#[derive(Clone, Debug)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub detail: Option<String>,
+    pub kind: lsp::SymbolKind,
+    pub range: Range<language::Anchor>,
+    pub selection_range: Range<language::Anchor>,
+    pub children: Vec<DocumentSymbol>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Symbol {
     pub language_server_name: LanguageServerName,
@@ -517,6 +546,7 @@ enum EntitySubscription {
     WorktreeStore(PendingEntitySubscription<WorktreeStore>),
     LspStore(PendingEntitySubscription<LspStore>),
     SettingsObserver(PendingEntitySubscription<SettingsObserver>),
+    SharedTerminalStore(PendingEntitySubscription<SharedTerminalStore>),
 }
 
 #[derive(Clone)]
@@ -609,6 +639,7 @@ impl Project {
         client.add_model_request_handler(Self::handle_stage);
         client.add_model_request_handler(Self::handle_unstage);
         client.add_model_request_handler(Self::handle_commit);
+        client.add_model_request_handler(Self::handle_load_committed_text);
 
         WorktreeStore::init(&client);
         BufferStore::init(&client);
@@ -616,6 +647,7 @@ impl Project {
         SettingsObserver::init(&client);
         TaskStore::init(Some(&client));
         ToolchainStore::init(&client);
+        SharedTerminalStore::init(&client);
     }
 
     pub fn local(
@@ -640,6 +672,8 @@ impl Project {
             cx.subscribe(&buffer_store, Self::on_buffer_store_event)
                 .detach();
 
+            let bookmark_store = cx.new(|cx| BookmarkStore::new(buffer_store.clone(), cx));
+
             let image_store = cx.new(|cx| ImageStore::local(worktree_store.clone(), cx));
             cx.subscribe(&image_store, Self::on_image_store_event)
                 .detach();
@@ -710,6 +744,7 @@ impl Project {
                 collaborators: Default::default(),
                 worktree_store,
                 buffer_store,
+                bookmark_store,
                 image_store,
                 lsp_store,
                 join_project_response_message_id: 0,
@@ -731,6 +766,7 @@ impl Project {
                 terminals: Terminals {
                     local_handles: Vec::new(),
                 },
+                shared_terminal_store: cx.new(|_| SharedTerminalStore::local()),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -785,6 +821,7 @@ impl Project {
             });
             cx.subscribe(&buffer_store, Self::on_buffer_store_event)
                 .detach();
+            let bookmark_store = cx.new(|cx| BookmarkStore::new(buffer_store.clone(), cx));
             let toolchain_store = cx
                 .new(|cx| ToolchainStore::remote(SSH_PROJECT_ID, ssh.read(cx).proto_client(), cx));
             let task_store = cx.new(|cx| {
@@ -839,6 +876,7 @@ impl Project {
                 collaborators: Default::default(),
                 worktree_store,
                 buffer_store,
+                bookmark_store,
                 image_store,
                 lsp_store,
                 join_project_response_message_id: 0,
@@ -875,6 +913,7 @@ impl Project {
                 terminals: Terminals {
                     local_handles: Vec::new(),
                 },
+                shared_terminal_store: cx.new(|_| SharedTerminalStore::local()),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -949,6 +988,9 @@ impl Project {
             EntitySubscription::SettingsObserver(
                 client.subscribe_to_entity::<SettingsObserver>(remote_id)?,
             ),
+            EntitySubscription::SharedTerminalStore(
+                client.subscribe_to_entity::<SharedTerminalStore>(remote_id)?,
+            ),
         ];
         let response = client
             .request_envelope(proto::JoinProject {
@@ -971,7 +1013,7 @@ impl Project {
     #[allow(clippy::too_many_arguments)]
     async fn from_join_project_response(
         response: TypedEnvelope<proto::JoinProjectResponse>,
-        subscriptions: [EntitySubscription; 5],
+        subscriptions: [EntitySubscription; 6],
         client: Arc<Client>,
         run_tasks: bool,
         user_store: Entity<UserStore>,
@@ -1038,6 +1080,10 @@ impl Project {
         }))
         .transpose()?;
 
+        let shared_terminal_store = cx.new(|_| {
+            SharedTerminalStore::remote(client.clone().into(), remote_id, client.user_id())
+        })?;
+
         let this = cx.new(|cx| {
             let replica_id = response.payload.replica_id as ReplicaId;
 
@@ -1060,12 +1106,14 @@ impl Project {
             cx.subscribe(&buffer_store, Self::on_buffer_store_event)
                 .detach();
             cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
+            let bookmark_store = cx.new(|cx| BookmarkStore::new(buffer_store.clone(), cx));
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
 
             let mut this = Self {
                 buffer_ordered_messages_tx: tx,
                 buffer_store: buffer_store.clone(),
+                bookmark_store,
                 image_store,
                 worktree_store: worktree_store.clone(),
                 lsp_store: lsp_store.clone(),
@@ -1094,6 +1142,7 @@ impl Project {
                 terminals: Terminals {
                     local_handles: Vec::new(),
                 },
+                shared_terminal_store: shared_terminal_store.clone(),
                 node: None,
                 search_history: Self::new_search_history(),
                 search_included_history: Self::new_search_history(),
@@ -1125,6 +1174,9 @@ impl Project {
                 EntitySubscription::LspStore(subscription) => {
                     subscription.set_model(&lsp_store, &mut cx)
                 }
+                EntitySubscription::SharedTerminalStore(subscription) => {
+                    subscription.set_model(&shared_terminal_store, &mut cx)
+                }
             })
             .collect::<Vec<_>>();
 
@@ -1647,6 +1699,9 @@ impl Project {
             self.client
                 .subscribe_to_entity(project_id)?
                 .set_model(&self.settings_observer, &mut cx.to_async()),
+            self.client
+                .subscribe_to_entity(project_id)?
+                .set_model(&self.shared_terminal_store, &mut cx.to_async()),
         ]);
 
         self.buffer_store.update(cx, |buffer_store, cx| {
@@ -1664,6 +1719,10 @@ impl Project {
         self.settings_observer.update(cx, |settings_observer, cx| {
             settings_observer.shared(project_id, self.client.clone().into(), cx)
         });
+        self.shared_terminal_store
+            .update(cx, |shared_terminal_store, cx| {
+                shared_terminal_store.shared(project_id, self.client.clone().into(), cx)
+            });
 
         self.client_state = ProjectClientState::Shared {
             remote_id: project_id,
@@ -1686,6 +1745,7 @@ impl Project {
         self.worktree_store.update(cx, |worktree_store, cx| {
             worktree_store.send_project_updates(cx);
         });
+        self.shared_terminal_store.read(cx).advertise_terminals(cx);
         cx.notify();
         cx.emit(Event::Reshared);
         Ok(())
@@ -1748,6 +1808,10 @@ impl Project {
             self.settings_observer.update(cx, |settings_observer, cx| {
                 settings_observer.unshared(cx);
             });
+            self.shared_terminal_store
+                .update(cx, |shared_terminal_store, cx| {
+                    shared_terminal_store.unshared(cx)
+                });
 
             self.client
                 .send(proto::UnshareProject {
@@ -1777,6 +1841,10 @@ impl Project {
                 Capability::ReadOnly
             };
         if let ProjectClientState::Remote { capability, .. } = &mut self.client_state {
+            self.shared_terminal_store
+                .update(cx, |shared_terminal_store, cx| {
+                    shared_terminal_store.set_capability(new_capability, cx)
+                });
             if *capability == new_capability {
                 return;
             }
@@ -1804,6 +1872,10 @@ impl Project {
             });
             self.lsp_store
                 .update(cx, |lsp_store, _cx| lsp_store.disconnected_from_host());
+            self.shared_terminal_store
+                .update(cx, |shared_terminal_store, cx| {
+                    shared_terminal_store.disconnected_from_host(cx)
+                });
         }
     }
 
@@ -2810,6 +2882,19 @@ impl Project {
         self.document_highlights_impl(buffer, position, cx)
     }
 
+    pub fn document_symbols(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentSymbol>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentSymbols,
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -3621,6 +3706,7 @@ impl Project {
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            this.shared_terminal_store.read(cx).advertise_terminals(cx);
             cx.notify();
         })?;
 
@@ -4084,6 +4170,33 @@ impl Project {
         }
     }
 
+    async fn handle_load_committed_text(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadCommittedText>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadCommittedTextResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let load = this.update(&mut cx, |project, cx| {
+            let repository_handle = project
+                .git_state()
+                .context("missing git state")?
+                .read(cx)
+                .all_repositories()
+                .into_iter()
+                .find(|repository_handle| {
+                    repository_handle.worktree_id == worktree_id
+                        && repository_handle.repository_entry.work_directory_id()
+                            == work_directory_id
+                })
+                .context("missing repository handle")?;
+            let repo_path = RepoPath::new(PathBuf::from(envelope.payload.path));
+            anyhow::Ok(repository_handle.load_committed_text(repo_path, cx))
+        })??;
+
+        Ok(proto::LoadCommittedTextResponse { text: load.await? })
+    }
+
     fn respond_to_open_buffer_request(
         this: Entity<Self>,
         buffer: Entity<Buffer>,
@@ -4285,6 +4398,14 @@ impl Project {
         &self.buffer_store
     }
 
+    pub fn bookmark_store(&self) -> &Entity<BookmarkStore> {
+        &self.bookmark_store
+    }
+
+    pub fn shared_terminal_store(&self) -> &Entity<SharedTerminalStore> {
+        &self.shared_terminal_store
+    }
+
     pub fn git_state(&self) -> Option<&Entity<GitState>> {
         self.git_state.as_ref()
     }
@@ -56,6 +56,10 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Configuration for how large files are opened
+    #[serde(default)]
+    pub large_file: LargeFileSettings,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -203,6 +207,36 @@ impl Default for SessionSettings {
     }
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LargeFileSettings {
+    /// The size in bytes above which files are opened without syntax parsing,
+    /// language servers, inlay hints or git diffs.
+    ///
+    /// Default: 20000000
+    pub threshold_bytes: u64,
+    /// Whether to open large files read-only, showing their beginning right away
+    /// while the rest is streamed into the buffer.
+    ///
+    /// Default: false
+    pub read_only: bool,
+}
+
+impl Default for LargeFileSettings {
+    fn default() -> Self {
+        Self {
+            threshold_bytes: 20_000_000,
+            read_only: false,
+        }
+    }
+}
+
+impl LargeFileSettings {
+    pub fn is_large(&self, len: usize) -> bool {
+        len as u64 > self.threshold_bytes
+    }
+}
+
 impl Settings for ProjectSettings {
     const KEY: Option<&'static str> = None;
 
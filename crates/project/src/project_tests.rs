@@ -2978,6 +2978,201 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_bookmarks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.txt": "one\ntwo\nthree\nfour",
+            "b.txt": "alpha\nbeta\ngamma",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store().clone());
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let b_path = ProjectPath {
+        worktree_id,
+        path: Path::new("b.txt").into(),
+    };
+
+    let changes = Arc::new(Mutex::new(0));
+    cx.update(|cx| {
+        cx.subscribe(&bookmark_store, {
+            let changes = changes.clone();
+            move |_, event, _| match event {
+                BookmarkStoreEvent::BookmarksChanged => *changes.lock() += 1,
+            }
+        })
+        .detach();
+    });
+
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    bookmark_store.update(cx, |store, cx| {
+        store.toggle_bookmark(&buffer_a, 1, cx);
+        store.toggle_bookmark(&buffer_a, 3, cx);
+        store.restore([(b_path.clone(), 2, Some("gamma".to_string()))], cx);
+    });
+
+    // Restored bookmarks don't need to be saved again.
+    assert_eq!(*changes.lock(), 2);
+
+    // Bookmarks follow their lines as the buffer is edited, which doesn't count as changing them.
+    buffer_a.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    assert_eq!(*changes.lock(), 2);
+    bookmark_store.read_with(cx, |store, cx| {
+        assert_eq!(store.bookmarked_rows(&buffer_a, cx), vec![2, 4]);
+        let bookmarks = store
+            .bookmarks(cx)
+            .into_iter()
+            .map(|(path, entries)| {
+                let rows = entries
+                    .into_iter()
+                    .map(|entry| (entry.row, entry.label))
+                    .collect::<Vec<_>>();
+                (path.path.to_string_lossy().into_owned(), rows)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bookmarks,
+            vec![
+                ("a.txt".to_string(), vec![(2, None), (4, None)]),
+                ("b.txt".to_string(), vec![(2, Some("gamma".to_string()))]),
+            ]
+        );
+    });
+
+    // Restored bookmarks are anchored once their file is opened.
+    let buffer_b = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/b.txt", cx)
+        })
+        .await
+        .unwrap();
+    buffer_b.update(cx, |buffer, cx| buffer.edit([(0..0, "\n\n")], None, cx));
+    bookmark_store.read_with(cx, |store, cx| {
+        assert_eq!(store.bookmarked_rows(&buffer_b, cx), vec![4]);
+    });
+
+    // Toggling a bookmarked row removes its bookmark.
+    bookmark_store.update(cx, |store, cx| store.toggle_bookmark(&buffer_a, 2, cx));
+    bookmark_store.read_with(cx, |store, cx| {
+        assert_eq!(store.bookmarked_rows(&buffer_a, cx), vec![4]);
+    });
+    assert_eq!(*changes.lock(), 3);
+}
+
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.large_file.threshold_bytes = 100;
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "small.rs": "fn a() {}",
+            "large.rs": "fn a() {}\n".repeat(20),
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/small.rs", cx)
+        })
+        .await
+        .unwrap();
+    let large_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/large.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    small_buffer.read_with(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert_eq!(buffer.language().unwrap().name(), "Rust".into());
+    });
+    large_buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.is_large_file());
+        assert!(buffer.language().is_none());
+        assert!(!buffer.read_only());
+    });
+
+    // Enabling features anyway detects the buffer's language.
+    large_buffer.update(cx, |buffer, cx| buffer.set_large_file(false, cx));
+    cx.executor().run_until_parked();
+    large_buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.language().unwrap().name(), "Rust".into());
+    });
+}
+
+#[gpui::test]
+async fn test_large_file_mode_streams_read_only_buffers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.large_file.threshold_bytes = 100;
+                settings.large_file.read_only = true;
+            });
+        })
+    });
+
+    let contents = "a line of text\r\n".repeat(200_000);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "large.log": contents.clone() }))
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/large.log", cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.is_large_file());
+        assert!(buffer.read_only());
+        assert!(buffer.len() < contents.len());
+        assert!(!buffer.is_dirty());
+    });
+
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), contents.replace("\r\n", "\n"));
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert!(!buffer.is_dirty());
+
+        // The streamed text isn't part of the undo history.
+        assert_eq!(buffer.undo(cx), None);
+        assert_eq!(buffer.text(), contents.replace("\r\n", "\n"));
+    });
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
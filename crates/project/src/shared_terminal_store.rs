@@ -0,0 +1,685 @@
+//! Terminals that the host of a shared project shares with its guests.
+//!
+//! The host mirrors the output of each shared terminal's PTY to guests, who show it in terminals
+//! of their own, starting from a snapshot of the host's terminal. The host grants each guest
+//! permission to type into a terminal separately, and guests can only use it while their role in
+//! the call lets them edit the project. The collab server keeps the grants that the host
+//! advertises, and checks both before forwarding a guest's input.
+
+use anyhow::{anyhow, Context as _, Result};
+use collections::{HashMap, HashSet};
+use futures::{
+    channel::{mpsc, oneshot},
+    future::Either,
+    FutureExt as _, StreamExt as _,
+};
+use gpui::{App, AppContext as _, AsyncApp, Context, Entity, Subscription, Task, WeakEntity};
+use language::Capability;
+use rpc::{proto, AnyProtoClient, TypedEnvelope};
+use settings::Settings as _;
+use terminal::{
+    terminal_settings::TerminalSettings, SharedOutput, Terminal, TerminalBuilder, TerminalSnapshot,
+};
+use util::{post_inc, ResultExt as _};
+
+/// A terminal that the host is sharing, as advertised to guests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedTerminalMetadata {
+    pub id: u64,
+    pub title: String,
+    /// The users who can type into the terminal, as long as their role lets them edit the
+    /// project.
+    pub writer_user_ids: Vec<u64>,
+}
+
+impl SharedTerminalMetadata {
+    fn from_proto(metadata: proto::SharedTerminalMetadata) -> Self {
+        Self {
+            id: metadata.terminal_id,
+            title: metadata.title,
+            writer_user_ids: metadata.writer_user_ids,
+        }
+    }
+
+    fn to_proto(&self) -> proto::SharedTerminalMetadata {
+        proto::SharedTerminalMetadata {
+            terminal_id: self.id,
+            title: self.title.clone(),
+            writer_user_ids: self.writer_user_ids.clone(),
+        }
+    }
+
+    /// Returns whether a guest, whose role gives them the given capability, can't type into
+    /// the terminal.
+    fn is_read_only(&self, user_id: Option<u64>, capability: Capability) -> bool {
+        capability == Capability::ReadOnly
+            || user_id.map_or(true, |user_id| !self.writer_user_ids.contains(&user_id))
+    }
+}
+
+pub struct SharedTerminalStore {
+    mode: SharedTerminalStoreMode,
+    next_terminal_id: u64,
+}
+
+enum SharedTerminalStoreMode {
+    Local {
+        downstream_client: Option<(AnyProtoClient, u64)>,
+        shared_terminals: HashMap<u64, SharedTerminal>,
+    },
+    Remote {
+        upstream_client: AnyProtoClient,
+        project_id: u64,
+        user_id: Option<u64>,
+        /// Whether our role lets us edit the project, and so type into shared terminals.
+        capability: Capability,
+        host_terminals: Vec<SharedTerminalMetadata>,
+        opened_terminals: HashMap<u64, OpenedTerminal>,
+    },
+}
+
+/// A terminal that the host is sharing.
+struct SharedTerminal {
+    terminal: WeakEntity<Terminal>,
+    /// The users that the host lets type into the terminal.
+    writer_user_ids: HashSet<u64>,
+    snapshot_requests_tx: mpsc::UnboundedSender<oneshot::Sender<(TerminalSnapshot, u64)>>,
+    _mirror_output: Task<()>,
+    _subscriptions: [Subscription; 2],
+}
+
+/// A terminal that the host shared, as opened by a guest.
+enum OpenedTerminal {
+    /// The snapshot of the terminal hasn't been received yet, so updates are kept until it has.
+    Opening {
+        pending_updates: Vec<proto::UpdateSharedTerminal>,
+    },
+    Open {
+        terminal: WeakEntity<Terminal>,
+        /// The version of the last update that was applied to the terminal.
+        version: u64,
+        _forward_input: Task<()>,
+    },
+}
+
+impl SharedTerminalStore {
+    pub fn init(client: &AnyProtoClient) {
+        client.add_model_message_handler(Self::handle_advertise_shared_terminals);
+        client.add_model_request_handler(Self::handle_open_shared_terminal);
+        client.add_model_message_handler(Self::handle_update_shared_terminal);
+        client.add_model_request_handler(Self::handle_shared_terminal_input);
+    }
+
+    pub fn local() -> Self {
+        Self {
+            mode: SharedTerminalStoreMode::Local {
+                downstream_client: None,
+                shared_terminals: HashMap::default(),
+            },
+            next_terminal_id: 0,
+        }
+    }
+
+    pub fn remote(upstream_client: AnyProtoClient, project_id: u64, user_id: Option<u64>) -> Self {
+        Self {
+            mode: SharedTerminalStoreMode::Remote {
+                upstream_client,
+                project_id,
+                user_id,
+                capability: Capability::ReadOnly,
+                host_terminals: Vec::new(),
+                opened_terminals: HashMap::default(),
+            },
+            next_terminal_id: 0,
+        }
+    }
+
+    pub fn shared(&mut self, project_id: u64, client: AnyProtoClient, cx: &mut Context<Self>) {
+        if let SharedTerminalStoreMode::Local {
+            downstream_client, ..
+        } = &mut self.mode
+        {
+            *downstream_client = Some((client, project_id));
+            cx.notify();
+        }
+    }
+
+    pub fn unshared(&mut self, cx: &mut Context<Self>) {
+        if let SharedTerminalStoreMode::Local {
+            downstream_client,
+            shared_terminals,
+        } = &mut self.mode
+        {
+            *downstream_client = None;
+            // Dropping the shared terminals stops mirroring their output.
+            shared_terminals.clear();
+            cx.notify();
+        }
+    }
+
+    /// Sets whether our role in the call lets us type into the host's terminals.
+    pub fn set_capability(&mut self, new_capability: Capability, cx: &mut Context<Self>) {
+        if let SharedTerminalStoreMode::Remote { capability, .. } = &mut self.mode {
+            *capability = new_capability;
+            self.update_opened_terminals(cx);
+        }
+    }
+
+    pub fn disconnected_from_host(&mut self, cx: &mut Context<Self>) {
+        if let SharedTerminalStoreMode::Remote {
+            host_terminals,
+            opened_terminals,
+            ..
+        } = &mut self.mode
+        {
+            host_terminals.clear();
+            Self::close_opened_terminals(opened_terminals, cx);
+            cx.notify();
+        }
+    }
+
+    /// Shares a terminal with the project's guests, who can't type into it until they're allowed
+    /// to.
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Entity<Terminal>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let SharedTerminalStoreMode::Local {
+            downstream_client: Some((client, project_id)),
+            shared_terminals,
+        } = &mut self.mode
+        else {
+            return Err(anyhow!("only terminals in shared projects can be shared"));
+        };
+
+        if !shared_terminals
+            .values()
+            .any(|shared_terminal| shared_terminal.terminal == *terminal)
+        {
+            let mut mirror = terminal
+                .read(cx)
+                .share()
+                .context("terminal can't be shared")?;
+            let terminal_id = post_inc(&mut self.next_terminal_id);
+            let (snapshot_requests_tx, mut snapshot_requests_rx) = mpsc::unbounded();
+            let client = client.clone();
+            let project_id = *project_id;
+            let mirror_output = cx.background_executor().spawn(async move {
+                let mut version = 0;
+                let send_output = |output: SharedOutput, version: u64| {
+                    client
+                        .send(proto::UpdateSharedTerminal {
+                            project_id,
+                            terminal_id,
+                            version,
+                            columns: output.columns as u32,
+                            lines: output.lines as u32,
+                            output: output.bytes,
+                        })
+                        .log_err();
+                };
+                loop {
+                    let next = {
+                        let output = mirror.next_output().fuse();
+                        let snapshot_request = snapshot_requests_rx.next().fuse();
+                        futures::pin_mut!(output, snapshot_request);
+                        futures::select_biased! {
+                            response_tx = snapshot_request => Either::Left(response_tx),
+                            output = output => Either::Right(output),
+                        }
+                    };
+                    match next {
+                        Either::Left(Some(response_tx)) => {
+                            let (output, snapshot) = mirror.snapshot();
+                            if let Some(output) = output {
+                                version += 1;
+                                send_output(output, version);
+                            }
+                            response_tx.send((snapshot, version)).ok();
+                        }
+                        Either::Right(Some(output)) => {
+                            version += 1;
+                            send_output(output, version);
+                        }
+                        Either::Left(None) | Either::Right(None) => break,
+                    }
+                }
+            });
+            let subscriptions = [
+                cx.subscribe(terminal, |this, _, event, cx| {
+                    if matches!(event, terminal::Event::TitleChanged) {
+                        this.advertise_terminals(cx);
+                    }
+                }),
+                cx.observe_release(terminal, move |this, _, cx| {
+                    if let SharedTerminalStoreMode::Local {
+                        shared_terminals, ..
+                    } = &mut this.mode
+                    {
+                        shared_terminals.remove(&terminal_id);
+                        this.advertise_terminals(cx);
+                    }
+                }),
+            ];
+            shared_terminals.insert(
+                terminal_id,
+                SharedTerminal {
+                    terminal: terminal.downgrade(),
+                    writer_user_ids: HashSet::default(),
+                    snapshot_requests_tx,
+                    _mirror_output: mirror_output,
+                    _subscriptions: subscriptions,
+                },
+            );
+        }
+
+        self.advertise_terminals(cx);
+        Ok(())
+    }
+
+    /// Allows a guest to type into a terminal that we're sharing, or stops allowing them to.
+    pub fn set_terminal_writer(
+        &mut self,
+        terminal: &Entity<Terminal>,
+        user_id: u64,
+        can_write: bool,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let SharedTerminalStoreMode::Local {
+            shared_terminals, ..
+        } = &mut self.mode
+        else {
+            return Err(anyhow!("only the host's terminals can be shared"));
+        };
+        let shared_terminal = shared_terminals
+            .values_mut()
+            .find(|shared_terminal| shared_terminal.terminal == *terminal)
+            .context("terminal isn't shared")?;
+        if can_write {
+            shared_terminal.writer_user_ids.insert(user_id);
+        } else {
+            shared_terminal.writer_user_ids.remove(&user_id);
+        }
+        self.advertise_terminals(cx);
+        Ok(())
+    }
+
+    pub fn unshare_terminal(&mut self, terminal: &Entity<Terminal>, cx: &mut Context<Self>) {
+        if let SharedTerminalStoreMode::Local {
+            shared_terminals, ..
+        } = &mut self.mode
+        {
+            shared_terminals.retain(|_, shared_terminal| shared_terminal.terminal != *terminal);
+            self.advertise_terminals(cx);
+        }
+    }
+
+    /// Returns the users who can type into a terminal that we're sharing, or `None` if we aren't
+    /// sharing it.
+    pub fn terminal_sharing(&self, terminal: &Entity<Terminal>) -> Option<&HashSet<u64>> {
+        let SharedTerminalStoreMode::Local {
+            shared_terminals, ..
+        } = &self.mode
+        else {
+            return None;
+        };
+        shared_terminals
+            .values()
+            .find(|shared_terminal| shared_terminal.terminal == *terminal)
+            .map(|shared_terminal| &shared_terminal.writer_user_ids)
+    }
+
+    /// The terminals that the host is sharing with us.
+    pub fn host_terminals(&self) -> &[SharedTerminalMetadata] {
+        match &self.mode {
+            SharedTerminalStoreMode::Local { .. } => &[],
+            SharedTerminalStoreMode::Remote { host_terminals, .. } => host_terminals,
+        }
+    }
+
+    /// Sends the terminals that we're sharing to the project's guests, which is done whenever
+    /// they change or a guest joins.
+    pub fn advertise_terminals(&self, cx: &App) {
+        let SharedTerminalStoreMode::Local {
+            downstream_client: Some((client, project_id)),
+            shared_terminals,
+        } = &self.mode
+        else {
+            return;
+        };
+
+        let mut terminals = shared_terminals
+            .iter()
+            .filter_map(|(id, shared_terminal)| {
+                let terminal = shared_terminal.terminal.upgrade()?;
+                Some(SharedTerminalMetadata {
+                    id: *id,
+                    title: terminal.read(cx).title(false),
+                    writer_user_ids: shared_terminal.writer_user_ids(),
+                })
+            })
+            .collect::<Vec<_>>();
+        terminals.sort_by_key(|terminal| terminal.id);
+        client
+            .send(proto::AdvertiseSharedTerminals {
+                project_id: *project_id,
+                terminals: terminals
+                    .iter()
+                    .map(SharedTerminalMetadata::to_proto)
+                    .collect(),
+            })
+            .log_err();
+    }
+
+    /// Opens a terminal that the host is sharing with us, which shows the host's terminal and
+    /// sends our input to it.
+    pub fn open_host_terminal(
+        &mut self,
+        terminal_id: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Terminal>>> {
+        let SharedTerminalStoreMode::Remote {
+            upstream_client,
+            project_id,
+            opened_terminals,
+            ..
+        } = &mut self.mode
+        else {
+            return Task::ready(Err(anyhow!("only the host's terminals can be opened")));
+        };
+
+        match opened_terminals.get(&terminal_id) {
+            Some(OpenedTerminal::Open { terminal, .. }) => {
+                if let Some(terminal) = terminal.upgrade() {
+                    return Task::ready(Ok(terminal));
+                }
+            }
+            Some(OpenedTerminal::Opening { .. }) => {
+                return Task::ready(Err(anyhow!("terminal is already being opened")));
+            }
+            None => {}
+        }
+        opened_terminals.insert(
+            terminal_id,
+            OpenedTerminal::Opening {
+                pending_updates: Vec::new(),
+            },
+        );
+
+        let client = upstream_client.clone();
+        let project_id = *project_id;
+        let request = client.request(proto::OpenSharedTerminal {
+            project_id,
+            terminal_id,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await;
+            this.update(&mut cx, |this, cx| {
+                let SharedTerminalStoreMode::Remote {
+                    user_id,
+                    capability,
+                    opened_terminals,
+                    ..
+                } = &mut this.mode
+                else {
+                    return Err(anyhow!("project is no longer remote"));
+                };
+                let pending_updates = match opened_terminals.remove(&terminal_id) {
+                    Some(OpenedTerminal::Opening { pending_updates }) => pending_updates,
+                    _ => Vec::new(),
+                };
+                let response = response?;
+                let metadata = SharedTerminalMetadata::from_proto(
+                    response.metadata.context("invalid terminal metadata")?,
+                );
+                let snapshot = TerminalSnapshot {
+                    columns: response.columns as usize,
+                    lines: response.lines as usize,
+                    content: response.content,
+                };
+
+                let (input_tx, mut input_rx) = mpsc::unbounded::<Vec<u8>>();
+                let settings = TerminalSettings::get_global(cx);
+                let read_only = metadata.is_read_only(*user_id, *capability);
+                let builder = TerminalBuilder::new_shared(
+                    metadata.title,
+                    snapshot,
+                    read_only,
+                    input_tx,
+                    settings.cursor_shape.unwrap_or_default(),
+                    settings.max_scroll_history_lines,
+                );
+                let terminal = cx.new(|cx| builder.subscribe(cx));
+
+                let mut version = response.version;
+                terminal.update(cx, |terminal, cx| {
+                    for update in pending_updates {
+                        if update.version > version {
+                            version = update.version;
+                            terminal.write_shared_output(shared_output(update), cx);
+                        }
+                    }
+                });
+
+                // Input is sent in order, so that what's typed quickly isn't jumbled.
+                let forward_input = cx.background_executor().spawn(async move {
+                    while let Some(input) = input_rx.next().await {
+                        client
+                            .request(proto::SharedTerminalInput {
+                                project_id,
+                                terminal_id,
+                                input,
+                            })
+                            .await
+                            .log_err();
+                    }
+                });
+                opened_terminals.insert(
+                    terminal_id,
+                    OpenedTerminal::Open {
+                        terminal: terminal.downgrade(),
+                        version,
+                        _forward_input: forward_input,
+                    },
+                );
+                Ok(terminal)
+            })?
+        })
+    }
+
+    async fn handle_advertise_shared_terminals(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::AdvertiseSharedTerminals>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if let SharedTerminalStoreMode::Remote { host_terminals, .. } = &mut this.mode {
+                *host_terminals = envelope
+                    .payload
+                    .terminals
+                    .into_iter()
+                    .map(SharedTerminalMetadata::from_proto)
+                    .collect();
+                this.update_opened_terminals(cx);
+                cx.notify();
+            }
+        })
+    }
+
+    async fn handle_open_shared_terminal(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::OpenSharedTerminal>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenSharedTerminalResponse> {
+        let terminal_id = envelope.payload.terminal_id;
+        let (metadata, snapshot) = this.update(&mut cx, |this, cx| {
+            let SharedTerminalStoreMode::Local {
+                shared_terminals, ..
+            } = &this.mode
+            else {
+                return Err(anyhow!("only the host's terminals can be opened"));
+            };
+            let shared_terminal = shared_terminals
+                .get(&terminal_id)
+                .context("terminal isn't shared")?;
+            let terminal = shared_terminal
+                .terminal
+                .upgrade()
+                .context("terminal was closed")?;
+            let (response_tx, response_rx) = oneshot::channel();
+            shared_terminal
+                .snapshot_requests_tx
+                .unbounded_send(response_tx)
+                .map_err(|_| anyhow!("terminal is no longer shared"))?;
+            let metadata = SharedTerminalMetadata {
+                id: terminal_id,
+                title: terminal.read(cx).title(false),
+                writer_user_ids: shared_terminal.writer_user_ids(),
+            };
+            anyhow::Ok((metadata, response_rx))
+        })??;
+
+        let (snapshot, version) = snapshot.await?;
+        Ok(proto::OpenSharedTerminalResponse {
+            metadata: Some(metadata.to_proto()),
+            columns: snapshot.columns as u32,
+            lines: snapshot.lines as u32,
+            content: snapshot.content,
+            version,
+        })
+    }
+
+    async fn handle_update_shared_terminal(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminal>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let SharedTerminalStoreMode::Remote {
+                opened_terminals, ..
+            } = &mut this.mode
+            else {
+                return;
+            };
+            let update = envelope.payload;
+            let terminal_id = update.terminal_id;
+            match opened_terminals.get_mut(&terminal_id) {
+                Some(OpenedTerminal::Opening { pending_updates }) => pending_updates.push(update),
+                Some(OpenedTerminal::Open {
+                    terminal, version, ..
+                }) => {
+                    if update.version > *version {
+                        *version = update.version;
+                        let is_open = terminal
+                            .update(cx, |terminal, cx| {
+                                terminal.write_shared_output(shared_output(update), cx)
+                            })
+                            .is_ok();
+                        if !is_open {
+                            opened_terminals.remove(&terminal_id);
+                        }
+                    }
+                }
+                None => {}
+            }
+        })
+    }
+
+    async fn handle_shared_terminal_input(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::SharedTerminalInput>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            let SharedTerminalStoreMode::Local {
+                shared_terminals, ..
+            } = &this.mode
+            else {
+                return Err(anyhow!("only the host's terminals accept input"));
+            };
+            let shared_terminal = shared_terminals
+                .get(&envelope.payload.terminal_id)
+                .context("terminal isn't shared")?;
+            // The collab server has already checked that the sender is allowed to type.
+            if shared_terminal.writer_user_ids.is_empty() {
+                return Err(anyhow!("terminal is shared read-only"));
+            }
+            let terminal = shared_terminal
+                .terminal
+                .upgrade()
+                .context("terminal was closed")?;
+            terminal.update(cx, |terminal, _| {
+                terminal.input_bytes(envelope.payload.input)
+            });
+            Ok(proto::Ack {})
+        })?
+    }
+
+    /// Updates the titles of the host's terminals that we've opened, and whether we can type into
+    /// them, closing the ones that the host stopped sharing.
+    fn update_opened_terminals(&mut self, cx: &mut Context<Self>) {
+        let SharedTerminalStoreMode::Remote {
+            user_id,
+            capability,
+            host_terminals,
+            opened_terminals,
+            ..
+        } = &mut self.mode
+        else {
+            return;
+        };
+
+        opened_terminals.retain(|terminal_id, opened_terminal| {
+            let Some(metadata) = host_terminals
+                .iter()
+                .find(|metadata| metadata.id == *terminal_id)
+            else {
+                if let OpenedTerminal::Open { terminal, .. } = opened_terminal {
+                    terminal
+                        .update(cx, |terminal, cx| terminal.set_read_only(true, cx))
+                        .ok();
+                }
+                return false;
+            };
+            match opened_terminal {
+                OpenedTerminal::Opening { .. } => true,
+                OpenedTerminal::Open { terminal, .. } => terminal
+                    .update(cx, |terminal, cx| {
+                        terminal.set_shared_title(metadata.title.clone(), cx);
+                        terminal.set_read_only(metadata.is_read_only(*user_id, *capability), cx);
+                    })
+                    .is_ok(),
+            }
+        });
+    }
+
+    fn close_opened_terminals(
+        opened_terminals: &mut HashMap<u64, OpenedTerminal>,
+        cx: &mut Context<Self>,
+    ) {
+        for (_, opened_terminal) in opened_terminals.drain() {
+            if let OpenedTerminal::Open { terminal, .. } = opened_terminal {
+                terminal
+                    .update(cx, |terminal, cx| terminal.set_read_only(true, cx))
+                    .ok();
+            }
+        }
+    }
+}
+
+impl SharedTerminal {
+    fn writer_user_ids(&self) -> Vec<u64> {
+        let mut writer_user_ids = self.writer_user_ids.iter().copied().collect::<Vec<_>>();
+        writer_user_ids.sort_unstable();
+        writer_user_ids
+    }
+}
+
+fn shared_output(update: proto::UpdateSharedTerminal) -> SharedOutput {
+    SharedOutput {
+        bytes: update.output,
+        columns: update.columns as usize,
+        lines: update.lines as usize,
+    }
+}
@@ -78,6 +78,8 @@ impl WorktreeStore {
         client.add_model_request_handler(Self::handle_expand_all_for_project_entry);
         client.add_model_request_handler(Self::handle_git_branches);
         client.add_model_request_handler(Self::handle_update_branch);
+        client.add_model_request_handler(Self::handle_load_binary_file);
+        client.add_model_request_handler(Self::handle_save_binary_file);
     }
 
     pub fn local(retain_worktrees: bool, fs: Arc<dyn Fs>) -> Self {
@@ -1103,6 +1105,30 @@ impl WorktreeStore {
         Worktree::handle_expand_all_for_entry(worktree, envelope.payload, cx).await
     }
 
+    pub async fn handle_load_binary_file(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadBinaryFile>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadBinaryFileResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let worktree = this
+            .update(&mut cx, |this, cx| this.worktree_for_id(worktree_id, cx))?
+            .ok_or_else(|| anyhow!("worktree not found"))?;
+        Worktree::handle_load_binary_file(worktree, envelope.payload, cx).await
+    }
+
+    pub async fn handle_save_binary_file(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::SaveBinaryFile>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::SaveBinaryFileResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let worktree = this
+            .update(&mut cx, |this, cx| this.worktree_for_id(worktree_id, cx))?
+            .ok_or_else(|| anyhow!("worktree not found"))?;
+        Worktree::handle_save_binary_file(worktree, envelope.payload, cx).await
+    }
+
     pub async fn handle_git_branches(
         this: Entity<Self>,
         branches: TypedEnvelope<proto::GitBranches>,
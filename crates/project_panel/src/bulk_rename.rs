@@ -0,0 +1,803 @@
+use anyhow::{bail, Result};
+use collections::{HashMap, HashSet};
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AnyView, App, Entity, EventEmitter, FocusHandle, Focusable, Render, Subscription, Task,
+};
+use language::Buffer;
+use project::{Project, ProjectEntryId, ProjectPath, WorktreeId};
+use std::{
+    any::TypeId,
+    cmp::Reverse,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::{searchable::SearchableItemHandle, Item, ItemNavHistory, Workspace};
+
+/// Renames and moves several entries at once by editing their paths in a buffer, one per line.
+///
+/// Saving applies the renames through the project, so that language servers can update the
+/// references to the renamed files.
+pub struct BulkRenameEditor {
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    project: Entity<Project>,
+    entries: Vec<RenamedEntry>,
+    /// Whether the paths are prefixed with the names of their worktrees.
+    include_root: bool,
+    /// The text of the buffer as of the last time the renames were applied.
+    saved_text: String,
+    _subscription: Subscription,
+}
+
+struct RenamedEntry {
+    worktree_id: WorktreeId,
+    entry_id: ProjectEntryId,
+    path: Arc<Path>,
+}
+
+impl BulkRenameEditor {
+    /// Opens an editor for renaming the given entries in the active pane.
+    pub fn open(
+        entries: impl IntoIterator<Item = (WorktreeId, ProjectEntryId)>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Entity<Self>> {
+        let project = workspace.project().clone();
+        let entries = entries
+            .into_iter()
+            .filter_map(|(worktree_id, entry_id)| {
+                let worktree = project.read(cx).worktree_for_id(worktree_id, cx)?;
+                let entry = worktree.read(cx).entry_for_id(entry_id)?;
+                Some(RenamedEntry {
+                    worktree_id,
+                    entry_id,
+                    path: entry.path.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return None;
+        }
+
+        let bulk_rename_editor = cx.new(|cx| Self::new(entries, project, window, cx));
+        workspace.add_item_to_active_pane(
+            Box::new(bulk_rename_editor.clone()),
+            None,
+            true,
+            window,
+            cx,
+        );
+        Some(bulk_rename_editor)
+    }
+
+    fn new(
+        entries: Vec<RenamedEntry>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let include_root = project.read(cx).visible_worktrees(cx).count() > 1;
+        let text = paths_text(&display_paths(&entries, include_root, &project, cx));
+        let buffer = cx.new(|cx| Buffer::local(text.clone(), cx));
+        let editor =
+            cx.new(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx));
+        let subscription = cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            if let EditorEvent::BufferEdited = event {
+                cx.emit(EditorEvent::DirtyChanged);
+            }
+            cx.emit(event.clone());
+        });
+
+        Self {
+            editor,
+            buffer,
+            project,
+            entries,
+            include_root,
+            saved_text: text,
+            _subscription: subscription,
+        }
+    }
+
+    /// Returns the renames that were made by editing the buffer.
+    fn renames(&self, cx: &App) -> Result<Vec<PathRename>> {
+        let project = self.project.read(cx);
+        let old_display_paths = display_paths(&self.entries, self.include_root, &self.project, cx);
+        let renames = parse_renames(&old_display_paths, &self.buffer.read(cx).text())?;
+
+        let renames = renames
+            .into_iter()
+            .map(|(ix, new_display_path)| {
+                let entry = &self.entries[ix];
+                let Some(worktree) = project.worktree_for_id(entry.worktree_id, cx) else {
+                    bail!("{:?} is no longer in the project", old_display_paths[ix]);
+                };
+                let worktree = worktree.read(cx);
+                if worktree.entry_for_id(entry.entry_id).is_none() {
+                    bail!("{:?} no longer exists", old_display_paths[ix]);
+                }
+                let new_path = if self.include_root {
+                    match new_display_path.strip_prefix(worktree.root_name()) {
+                        Ok(path) => path.to_path_buf(),
+                        Err(_) => bail!(
+                            "Can't move {:?} out of {:?}",
+                            old_display_paths[ix],
+                            worktree.root_name()
+                        ),
+                    }
+                } else {
+                    new_display_path
+                };
+                if new_path.as_os_str().is_empty() {
+                    bail!("Can't rename {:?} to its worktree", old_display_paths[ix]);
+                }
+                Ok(PathRename {
+                    entry_ix: ix,
+                    worktree_id: entry.worktree_id,
+                    old_path: entry.path.clone(),
+                    new_path: new_path.into(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Check that no entry is renamed to a path that another entry, which isn't renamed
+        // itself, ends up at.
+        let final_paths = final_paths(&renames)?;
+        for (rename, final_path) in renames.iter().zip(&final_paths) {
+            let existing_path = original_path(&renames, &final_paths, rename, final_path);
+            let is_renamed = renames.iter().any(|other| {
+                other.worktree_id == rename.worktree_id && other.old_path == existing_path
+            });
+            let exists = project
+                .entry_for_path(
+                    &ProjectPath {
+                        worktree_id: rename.worktree_id,
+                        path: existing_path,
+                    },
+                    cx,
+                )
+                .is_some();
+            if exists && !is_renamed {
+                bail!(
+                    "Can't rename {:?} to {:?}, because it already exists",
+                    rename.old_path,
+                    final_path
+                );
+            }
+        }
+
+        Ok(renames)
+    }
+
+    fn apply_renames(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let (renames, final_paths, planned_renames) = match self.renames(cx).and_then(|renames| {
+            let final_paths = final_paths(&renames)?;
+            let planned_renames = planned_renames(&renames)?;
+            Ok((renames, final_paths, planned_renames))
+        }) {
+            Ok(renames) => renames,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let project = self.project.clone();
+        cx.spawn(|this, mut cx| async move {
+            let mut completed_steps = Vec::new();
+            let result = async {
+                for (rename_ix, new_path) in planned_renames {
+                    let rename = &renames[rename_ix];
+                    let entry_id =
+                        this.update(&mut cx, |this, _| this.entries[rename.entry_ix].entry_id)?;
+                    let old_path = project.update(&mut cx, |project, cx| {
+                        let worktree = project.worktree_for_entry(entry_id, cx)?;
+                        let entry = worktree.read(cx).entry_for_id(entry_id)?;
+                        Some(entry.path.clone())
+                    })?;
+                    let Some(old_path) = old_path else {
+                        bail!("{:?} no longer exists", rename.old_path);
+                    };
+
+                    // Create the missing ancestors of the new path, outermost first.
+                    let missing_ancestors = project.update(&mut cx, |project, cx| {
+                        let mut missing_ancestors = new_path
+                            .ancestors()
+                            .skip(1)
+                            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+                            .map(|ancestor| ProjectPath {
+                                worktree_id: rename.worktree_id,
+                                path: ancestor.into(),
+                            })
+                            .take_while(|ancestor| project.entry_for_path(ancestor, cx).is_none())
+                            .collect::<Vec<_>>();
+                        missing_ancestors.reverse();
+                        missing_ancestors
+                    })?;
+                    for ancestor in missing_ancestors {
+                        let created_entry = project
+                            .update(&mut cx, |project, cx| {
+                                project.create_entry(ancestor, true, cx)
+                            })?
+                            .await?;
+                        if let Some(entry) = created_entry.to_included() {
+                            completed_steps.push(RenameStep::Created(entry.id));
+                        }
+                    }
+
+                    project
+                        .update(&mut cx, |project, cx| {
+                            project.rename_entry(entry_id, new_path.clone(), cx)
+                        })?
+                        .await?;
+                    completed_steps.push(RenameStep::Renamed(entry_id, old_path));
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            if let Err(error) = result {
+                // Undo the completed steps, so that no temporary paths are left behind.
+                for step in completed_steps.into_iter().rev() {
+                    match step {
+                        RenameStep::Created(entry_id) => {
+                            let delete = project.update(&mut cx, |project, cx| {
+                                project.delete_entry(entry_id, false, cx)
+                            })?;
+                            if let Some(delete) = delete {
+                                delete.await.log_err();
+                            }
+                        }
+                        RenameStep::Renamed(entry_id, old_path) => {
+                            project
+                                .update(&mut cx, |project, cx| {
+                                    project.rename_entry(entry_id, old_path, cx)
+                                })?
+                                .await
+                                .log_err();
+                        }
+                    }
+                }
+                return Err(error);
+            }
+
+            this.update(&mut cx, |this, cx| {
+                for (rename, final_path) in renames.iter().zip(final_paths) {
+                    this.entries[rename.entry_ix].path = final_path;
+                }
+                this.reset_text(cx)
+            })
+        })
+    }
+
+    /// Replaces the buffer's text with the current paths of the entries.
+    fn reset_text(&mut self, cx: &mut Context<Self>) {
+        let text = paths_text(&display_paths(
+            &self.entries,
+            self.include_root,
+            &self.project,
+            cx,
+        ));
+        if self.buffer.read(cx).text() != text {
+            self.buffer
+                .update(cx, |buffer, cx| buffer.set_text(text.clone(), cx));
+        }
+        self.saved_text = text;
+        cx.emit(EditorEvent::Saved);
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Icon::new(IconName::Pencil)
+                    .size(IconSize::XSmall)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(
+                    "Edit the paths below, one per line, and save to rename them. \
+                     Lines can't be added or removed.",
+                )
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+    }
+}
+
+/// A change to the path of an entry, as edited in the buffer.
+#[derive(Debug)]
+struct PathRename {
+    entry_ix: usize,
+    worktree_id: WorktreeId,
+    old_path: Arc<Path>,
+    new_path: Arc<Path>,
+}
+
+/// A step of applying the renames, which is undone if a later step fails.
+enum RenameStep {
+    Created(ProjectEntryId),
+    Renamed(ProjectEntryId, Arc<Path>),
+}
+
+/// Returns the path that each renamed entry ends up at.
+///
+/// A new path inside the old path of a directory that's also renamed is moved along with the
+/// directory, so renaming `a` to `b` and `a/x` to either `a/y` or `b/y` moves `x` to `b/y`.
+fn final_paths(renames: &[PathRename]) -> Result<Vec<Arc<Path>>> {
+    fn final_path(
+        ix: usize,
+        renames: &[PathRename],
+        final_paths: &mut [Option<Arc<Path>>],
+        visiting: &mut HashSet<usize>,
+    ) -> Result<Arc<Path>> {
+        if let Some(final_path) = &final_paths[ix] {
+            return Ok(final_path.clone());
+        }
+        let rename = &renames[ix];
+        if rename.new_path.starts_with(&rename.old_path) {
+            bail!("Can't move {:?} into itself", rename.old_path);
+        }
+        if !visiting.insert(ix) {
+            bail!(
+                "Can't move {:?} into a path that's moved into it",
+                rename.old_path
+            );
+        }
+        let parent = renames
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| {
+                other.worktree_id == rename.worktree_id
+                    && rename.new_path.starts_with(&other.old_path)
+                    && rename.new_path != other.old_path
+            })
+            .max_by_key(|(_, other)| other.old_path.components().count());
+        let path = match parent {
+            Some((parent_ix, parent)) => {
+                let relative_path = rename.new_path.strip_prefix(&parent.old_path)?;
+                final_path(parent_ix, renames, final_paths, visiting)?
+                    .join(relative_path)
+                    .into()
+            }
+            None => rename.new_path.clone(),
+        };
+        final_paths[ix] = Some(path.clone());
+        Ok(path)
+    }
+
+    let mut final_paths = vec![None; renames.len()];
+    for ix in 0..renames.len() {
+        final_path(ix, renames, &mut final_paths, &mut HashSet::default())?;
+    }
+    let final_paths = final_paths.into_iter().flatten().collect::<Vec<_>>();
+
+    let mut renames_by_final_path = HashMap::default();
+    for (rename, final_path) in renames.iter().zip(&final_paths) {
+        if let Some(other) =
+            renames_by_final_path.insert((rename.worktree_id, final_path.clone()), rename)
+        {
+            bail!(
+                "Can't rename both {:?} and {:?} to {:?}",
+                other.old_path,
+                rename.old_path,
+                final_path
+            );
+        }
+    }
+    Ok(final_paths)
+}
+
+/// Returns the path that an entry that isn't renamed would need to have now to end up at
+/// `final_path`, given that it moves along with the renamed directory that contains it.
+fn original_path(
+    renames: &[PathRename],
+    final_paths: &[Arc<Path>],
+    rename: &PathRename,
+    final_path: &Path,
+) -> Arc<Path> {
+    renames
+        .iter()
+        .zip(final_paths)
+        .filter(|(other, other_final_path)| {
+            other.worktree_id == rename.worktree_id
+                && final_path.starts_with(other_final_path)
+                && final_path != other_final_path.as_ref()
+        })
+        .max_by_key(|(_, other_final_path)| other_final_path.components().count())
+        .map_or_else(
+            || final_path.into(),
+            |(other, other_final_path)| {
+                other
+                    .old_path
+                    .join(final_path.strip_prefix(other_final_path).unwrap())
+                    .into()
+            },
+        )
+}
+
+/// Returns the renames to apply, in order, as the index of the rename and the path to move its
+/// entry to.
+///
+/// Entries are renamed before the directories that contain them, to paths inside those
+/// directories' old paths, so that they're moved along with them. Entries whose path is taken
+/// by another entry are first moved out of the way, so that entries can be swapped.
+fn planned_renames(renames: &[PathRename]) -> Result<Vec<(usize, Arc<Path>)>> {
+    let final_paths = final_paths(renames)?;
+    let mut current_paths = renames
+        .iter()
+        .map(|rename| rename.old_path.clone())
+        .collect::<Vec<_>>();
+    let mut order = (0..renames.len()).collect::<Vec<_>>();
+    order.sort_by_key(|ix| Reverse(renames[*ix].old_path.components().count()));
+
+    let mut planned_renames = Vec::new();
+    for (position, &ix) in order.iter().enumerate() {
+        let worktree_id = renames[ix].worktree_id;
+        let remaining = &order[position + 1..];
+
+        // Rename the entry to where the remaining renames of its new ancestors will move it to
+        // its final path.
+        let final_path = &final_paths[ix];
+        let target_path: Arc<Path> = remaining
+            .iter()
+            .filter(|&&other_ix| {
+                renames[other_ix].worktree_id == worktree_id
+                    && final_path.starts_with(&final_paths[other_ix])
+            })
+            .max_by_key(|&&other_ix| final_paths[other_ix].components().count())
+            .map_or_else(
+                || final_path.clone(),
+                |&other_ix| {
+                    current_paths[other_ix]
+                        .join(final_path.strip_prefix(&final_paths[other_ix]).unwrap())
+                        .into()
+                },
+            );
+        if target_path == current_paths[ix] {
+            continue;
+        }
+
+        if let Some(&other_ix) = remaining.iter().find(|&&other_ix| {
+            renames[other_ix].worktree_id == worktree_id && current_paths[other_ix] == target_path
+        }) {
+            let file_name = target_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            let temporary_path: Arc<Path> = target_path
+                .with_file_name(format!(".{file_name}.renaming-{other_ix}"))
+                .into();
+            planned_renames.push((other_ix, temporary_path.clone()));
+            current_paths[other_ix] = temporary_path;
+        }
+
+        planned_renames.push((ix, target_path.clone()));
+        current_paths[ix] = target_path;
+    }
+    Ok(planned_renames)
+}
+
+/// The paths of the entries as they're shown in the buffer.
+fn display_paths(
+    entries: &[RenamedEntry],
+    include_root: bool,
+    project: &Entity<Project>,
+    cx: &App,
+) -> Vec<PathBuf> {
+    entries
+        .iter()
+        .map(|entry| {
+            let worktree = project.read(cx).worktree_for_id(entry.worktree_id, cx);
+            match worktree {
+                Some(worktree) if include_root => {
+                    Path::new(worktree.read(cx).root_name()).join(&entry.path)
+                }
+                _ => entry.path.to_path_buf(),
+            }
+        })
+        .collect()
+}
+
+fn paths_text(paths: &[PathBuf]) -> String {
+    let mut text = String::new();
+    for path in paths {
+        text.push_str(&path.to_string_lossy());
+        text.push('\n');
+    }
+    text
+}
+
+/// Parses the edited text, which has one path per line in the same order as `old_paths`, and
+/// returns the index and new path of each entry whose path was changed.
+fn parse_renames(old_paths: &[PathBuf], text: &str) -> Result<Vec<(usize, PathBuf)>> {
+    let mut new_paths = text
+        .lines()
+        .map(|line| line.trim().trim_end_matches('/'))
+        .collect::<Vec<_>>();
+    while new_paths.len() > old_paths.len() && new_paths.last() == Some(&"") {
+        new_paths.pop();
+    }
+    if new_paths.len() != old_paths.len() {
+        bail!(
+            "Expected {} paths, one per line, but found {}. Lines can't be added or removed.",
+            old_paths.len(),
+            new_paths.len()
+        );
+    }
+
+    let mut lines_by_path = HashMap::default();
+    let mut renames = Vec::new();
+    for (ix, (old_path, new_path)) in old_paths.iter().zip(new_paths).enumerate() {
+        let line = ix + 1;
+        if new_path.is_empty() {
+            bail!("Line {line}: the path can't be empty");
+        }
+        let new_path = PathBuf::from(new_path);
+        if !new_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            bail!("Line {line}: {new_path:?} must be a relative path without `.` or `..`");
+        }
+        if let Some(other_line) = lines_by_path.insert(new_path.clone(), line) {
+            bail!("Lines {other_line} and {line} have the same path {new_path:?}");
+        }
+        if new_path != *old_path {
+            renames.push((ix, new_path));
+        }
+    }
+    Ok(renames)
+}
+
+impl Render for BulkRenameEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .key_context("BulkRenameEditor")
+            .child(self.render_header(cx))
+            .child(self.editor.clone())
+    }
+}
+
+impl Focusable for BulkRenameEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for BulkRenameEditor {}
+
+impl Item for BulkRenameEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Pencil))
+    }
+
+    fn tab_content_text(&self, _window: &Window, _cx: &App) -> Option<SharedString> {
+        Some("Bulk Rename".into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(workspace::item::ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::added_to_workspace(editor, workspace, window, cx)
+        });
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn std::any::Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| Item::navigate(editor, data, window, cx))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::set_nav_history(editor, nav_history, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).text() != self.saved_text
+    }
+
+    fn can_save(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.apply_renames(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_parse_renames() {
+        let old_paths = paths(&["src/a.rs", "src/b.rs", "tests"]);
+        assert_eq!(
+            parse_renames(&old_paths, "src/a.rs\nsrc/b.rs\ntests\n").unwrap(),
+            []
+        );
+        assert_eq!(
+            parse_renames(&old_paths, "src/c.rs\n  src/b.rs  \nspecs/\n\n").unwrap(),
+            [(0, PathBuf::from("src/c.rs")), (2, PathBuf::from("specs"))]
+        );
+        // Swapping paths is allowed.
+        assert_eq!(
+            parse_renames(&old_paths, "src/b.rs\nsrc/a.rs\ntests").unwrap(),
+            [
+                (0, PathBuf::from("src/b.rs")),
+                (1, PathBuf::from("src/a.rs"))
+            ]
+        );
+    }
+
+    fn path_renames(renames: &[(&str, &str)]) -> Vec<PathRename> {
+        renames
+            .iter()
+            .enumerate()
+            .map(|(ix, (old_path, new_path))| PathRename {
+                entry_ix: ix,
+                worktree_id: WorktreeId::from_usize(1),
+                old_path: Path::new(old_path).into(),
+                new_path: Path::new(new_path).into(),
+            })
+            .collect()
+    }
+
+    fn planned_paths(renames: &[(&str, &str)]) -> Vec<(usize, String)> {
+        planned_renames(&path_renames(renames))
+            .unwrap()
+            .into_iter()
+            .map(|(ix, path)| (ix, path.to_string_lossy().into_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_planned_renames_swap() {
+        assert_eq!(
+            planned_paths(&[("a", "b"), ("b", "a")]),
+            [
+                (1, ".b.renaming-1".to_string()),
+                (0, "b".to_string()),
+                (1, "a".to_string())
+            ]
+        );
+        assert_eq!(
+            planned_paths(&[("src/a.rs", "src/b.rs"), ("src/b.rs", "src/a.rs")]),
+            [
+                (1, "src/.b.rs.renaming-1".to_string()),
+                (0, "src/b.rs".to_string()),
+                (1, "src/a.rs".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_planned_renames_parent_and_child() {
+        // The child is renamed inside its parent before the parent is renamed, whether its new
+        // path is written inside the parent's old or new path.
+        for child_path in ["a/y", "b/y"] {
+            let renames = path_renames(&[("a", "b"), ("a/x", child_path)]);
+            assert_eq!(
+                final_paths(&renames).unwrap(),
+                [Arc::from(Path::new("b")), Arc::from(Path::new("b/y"))]
+            );
+            assert_eq!(
+                planned_paths(&[("a", "b"), ("a/x", child_path)]),
+                [(1, "a/y".to_string()), (0, "b".to_string())]
+            );
+        }
+
+        // A child that's moved out of its parent is moved before the parent.
+        assert_eq!(
+            planned_paths(&[("a", "b"), ("a/x", "x")]),
+            [(1, "x".to_string()), (0, "b".to_string())]
+        );
+
+        // A parent that's moved into its child's old path.
+        assert_eq!(
+            planned_paths(&[("a", "c/a"), ("a/c", "c")]),
+            [(1, "c".to_string()), (0, "c/a".to_string())]
+        );
+
+        // Renaming only the parent's new name leaves the child where it is.
+        assert_eq!(
+            planned_paths(&[("a", "b"), ("a/x", "b/x")]),
+            [(0, "b".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_invalid_planned_renames() {
+        for renames in [
+            &[("a", "a/b")][..],
+            &[("a", "b/a"), ("b", "a/b")],
+            &[("a", "c"), ("b", "c")],
+            &[("a", "b"), ("a/x", "b/x"), ("c", "b/x")],
+        ] {
+            assert!(
+                planned_renames(&path_renames(renames)).is_err(),
+                "{renames:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_renames() {
+        let old_paths = paths(&["src/a.rs", "src/b.rs"]);
+        for text in [
+            "src/a.rs\n",
+            "src/a.rs\nsrc/b.rs\nsrc/c.rs\n",
+            "src/a.rs\n\n",
+            "src/a.rs\n../b.rs\n",
+            "src/a.rs\n/src/b.rs\n",
+            "src/c.rs\nsrc/c.rs\n",
+        ] {
+            assert!(
+                parse_renames(&old_paths, text).is_err(),
+                "{text:?} should be invalid"
+            );
+        }
+    }
+}
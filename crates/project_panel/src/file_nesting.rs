@@ -0,0 +1,317 @@
+use collections::HashMap;
+use git::status::GitSummary;
+use project::ProjectEntryId;
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+};
+use worktree::GitEntry;
+
+/// Rules for nesting files under a related file in the same directory, e.g. generated code
+/// under its source, or lockfiles under their manifest.
+#[derive(Debug, Default)]
+pub(crate) struct FileNestingRules {
+    rules: Vec<NestingRule>,
+}
+
+#[derive(Debug)]
+struct NestingRule {
+    /// The name of the parent file, where a `*` matches any text.
+    parent: String,
+    /// The names of the files nested under the parent, where `${capture}` is replaced with the
+    /// text that `*` matched in the parent's name, and `${basename}` with the parent's name up
+    /// to its last extension.
+    children: Vec<String>,
+}
+
+/// The files that are nested under an entry in the project panel.
+#[derive(Debug)]
+pub(crate) struct NestedFiles {
+    pub children: Vec<ProjectEntryId>,
+    pub git_summary: GitSummary,
+}
+
+impl FileNestingRules {
+    pub fn new(patterns: &BTreeMap<String, String>) -> Self {
+        let rules = patterns
+            .iter()
+            .filter(|(parent, _)| parent.matches('*').count() <= 1)
+            .map(|(parent, children)| NestingRule {
+                parent: parent.trim().to_string(),
+                children: children
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|child| !child.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+            .filter(|rule| !rule.parent.is_empty() && !rule.children.is_empty())
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the index of the file that each of the given sibling files is nested under.
+    ///
+    /// Files are always nested under a file that isn't nested itself, so if `b` would be nested
+    /// under `a`, and `c` under `b`, both `b` and `c` are nested under `a`.
+    pub fn nest(&self, names: &[&str]) -> Vec<Option<usize>> {
+        let indices_by_name = names
+            .iter()
+            .enumerate()
+            .map(|(ix, name)| (*name, ix))
+            .collect::<HashMap<_, _>>();
+
+        let mut parents = vec![None; names.len()];
+        for (parent_ix, parent_name) in names.iter().enumerate() {
+            for rule in &self.rules {
+                let Some(capture) = match_parent(&rule.parent, parent_name) else {
+                    continue;
+                };
+                let basename = parent_name
+                    .rsplit_once('.')
+                    .map_or(*parent_name, |(basename, _)| basename);
+
+                for child in &rule.children {
+                    let child = child
+                        .replace("${capture}", capture)
+                        .replace("${basename}", basename);
+                    let mut nest = |child_ix: usize| {
+                        if child_ix != parent_ix && parents[child_ix].is_none() {
+                            parents[child_ix] = Some(parent_ix);
+                        }
+                    };
+                    if child.contains('*') {
+                        for (child_ix, name) in names.iter().enumerate() {
+                            if matches_glob(&child, name) {
+                                nest(child_ix);
+                            }
+                        }
+                    } else if let Some(child_ix) = indices_by_name.get(child.as_str()) {
+                        nest(*child_ix);
+                    }
+                }
+            }
+        }
+
+        (0..names.len())
+            .map(|ix| {
+                let mut root = parents[ix]?;
+                // Stop at cycles, in which case none of the files are nested.
+                for _ in 0..names.len() {
+                    match parents[root] {
+                        Some(parent) if parent == ix => return None,
+                        Some(parent) => root = parent,
+                        None => return Some(root),
+                    }
+                }
+                None
+            })
+            .collect()
+    }
+}
+
+/// Returns the text matched by the `*` in the pattern, if it matches the name.
+fn match_parent<'a>(pattern: &str, name: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            if name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+            {
+                Some(&name[prefix.len()..name.len() - suffix.len()])
+            } else {
+                None
+            }
+        }
+        None => (pattern == name).then_some(""),
+    }
+}
+
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return pattern == name;
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(ix) => rest = &rest[ix + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Moves the files that are nested under another file after it in the sorted worktree entries,
+/// removing them unless the file they're nested under is expanded.
+pub(crate) fn nest_entries(
+    entries: Vec<GitEntry>,
+    rules: &FileNestingRules,
+    expanded_entry_ids: &mut HashSet<ProjectEntryId>,
+    revealed_entry_id: Option<ProjectEntryId>,
+    nesting_parents: &mut HashMap<ProjectEntryId, NestedFiles>,
+    nested_entries: &mut HashMap<ProjectEntryId, ProjectEntryId>,
+) -> Vec<GitEntry> {
+    let mut files_by_dir = HashMap::<&Path, Vec<usize>>::default();
+    for (ix, entry) in entries.iter().enumerate() {
+        if entry.is_file() && entry.id != crate::NEW_ENTRY_ID {
+            if let Some(dir) = entry.path.parent() {
+                files_by_dir.entry(dir).or_default().push(ix);
+            }
+        }
+    }
+
+    let mut children_by_parent = HashMap::<usize, Vec<usize>>::default();
+    for files in files_by_dir.values() {
+        let names = files
+            .iter()
+            .map(|ix| {
+                entries[*ix]
+                    .path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        for (file_ix, parent_ix) in rules.nest(&names).into_iter().enumerate() {
+            if let Some(parent_ix) = parent_ix {
+                children_by_parent
+                    .entry(files[parent_ix])
+                    .or_default()
+                    .push(files[file_ix]);
+            }
+        }
+    }
+    if children_by_parent.is_empty() {
+        return entries;
+    }
+
+    for (parent_ix, children) in &children_by_parent {
+        if children
+            .iter()
+            .any(|child_ix| Some(entries[*child_ix].id) == revealed_entry_id)
+        {
+            expanded_entry_ids.insert(entries[*parent_ix].id);
+        }
+    }
+
+    let is_nested = children_by_parent
+        .values()
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>();
+    let mut entries = entries.into_iter().map(Some).collect::<Vec<_>>();
+    let mut visible_entries = Vec::with_capacity(entries.len());
+    for ix in 0..entries.len() {
+        if is_nested.contains(&ix) {
+            continue;
+        }
+        let Some(entry) = entries[ix].take() else {
+            continue;
+        };
+        let parent_id = entry.id;
+        visible_entries.push(entry);
+
+        let Some(children) = children_by_parent.get_mut(&ix) else {
+            continue;
+        };
+        children.sort_unstable();
+        let is_expanded = expanded_entry_ids.contains(&parent_id);
+        let mut nested_files = NestedFiles {
+            children: Vec::with_capacity(children.len()),
+            git_summary: GitSummary::default(),
+        };
+        for child_ix in children.iter() {
+            let Some(child) = entries[*child_ix].take() else {
+                continue;
+            };
+            nested_files.children.push(child.id);
+            nested_files.git_summary = nested_files.git_summary + child.git_summary;
+            if is_expanded {
+                nested_entries.insert(child.id, parent_id);
+                visible_entries.push(child);
+            }
+        }
+        nesting_parents.insert(parent_id, nested_files);
+    }
+    visible_entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(patterns: &[(&str, &str)]) -> FileNestingRules {
+        FileNestingRules::new(
+            &patterns
+                .iter()
+                .map(|(parent, children)| (parent.to_string(), children.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn test_nest() {
+        let rules = rules(&[
+            ("*.ts", "${capture}.js, ${capture}.d.ts, ${capture}.js.map"),
+            ("*.js", "${basename}.*.js"),
+            ("Cargo.toml", "Cargo.lock"),
+            ("package.json", "package-lock.json, *.lock"),
+        ]);
+        let names = [
+            "Cargo.lock",
+            "Cargo.toml",
+            "main.d.ts",
+            "main.js",
+            "main.js.map",
+            "main.min.js",
+            "main.ts",
+            "other.js",
+            "package.json",
+            "yarn.lock",
+        ];
+        assert_eq!(
+            rules.nest(&names),
+            [
+                Some(1),
+                None,
+                Some(6),
+                Some(6),
+                Some(6),
+                // Nested under `main.js`, which is nested under `main.ts`.
+                Some(6),
+                None,
+                None,
+                None,
+                Some(8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nest_cycles() {
+        let rules = rules(&[("a.*", "b.${capture}"), ("b.*", "a.${capture}")]);
+        assert_eq!(rules.nest(&["a.txt", "b.txt", "c.txt"]), [None, None, None]);
+    }
+
+    #[test]
+    fn test_matches_glob() {
+        assert!(matches_glob("*.lock", "yarn.lock"));
+        assert!(matches_glob("main.*.js", "main.min.js"));
+        assert!(matches_glob("*", "anything"));
+        assert!(matches_glob("a*b*c", "abc"));
+        assert!(!matches_glob("a*b*c", "acb"));
+        assert!(!matches_glob("main.*.js", "main.js"));
+        assert!(!matches_glob("*.lock", "lock"));
+    }
+}
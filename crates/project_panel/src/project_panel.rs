@@ -1,7 +1,10 @@
+mod bulk_rename;
+mod file_nesting;
 mod project_panel_settings;
 mod utils;
 
 use anyhow::{anyhow, Context as _, Result};
+use bulk_rename::BulkRenameEditor;
 use client::{ErrorCode, ErrorExt};
 use collections::{hash_map, BTreeSet, HashMap};
 use command_palette_hooks::CommandPaletteFilter;
@@ -12,9 +15,10 @@ use editor::{
         entry_diagnostic_aware_icon_name_and_color, entry_git_aware_label_color,
     },
     scroll::{Autoscroll, ScrollbarAutoHide},
-    Editor, EditorEvent, EditorSettings, ShowScrollbar,
+    CompareEditor, Editor, EditorEvent, EditorSettings, ShowScrollbar,
 };
 use file_icons::FileIcons;
+use file_nesting::{FileNestingRules, NestedFiles};
 use git::status::GitSummary;
 use gpui::{
     actions, anchored, deferred, div, impl_actions, point, px, size, uniform_list, Action,
@@ -51,9 +55,9 @@ use std::{
 };
 use theme::ThemeSettings;
 use ui::{
-    prelude::*, v_flex, ContextMenu, DecoratedIcon, Icon, IconDecoration, IconDecorationKind,
-    IndentGuideColors, IndentGuideLayout, KeyBinding, Label, ListItem, ListItemSpacing, Scrollbar,
-    ScrollbarState, Tooltip,
+    prelude::*, v_flex, ContextMenu, DecoratedIcon, Disclosure, Icon, IconDecoration,
+    IconDecorationKind, IndentGuideColors, IndentGuideLayout, KeyBinding, Label, ListItem,
+    ListItemSpacing, Scrollbar, ScrollbarState, Tooltip,
 };
 use util::{maybe, paths::compare_paths, ResultExt, TakeUntilExt, TryFutureExt};
 use workspace::{
@@ -85,6 +89,12 @@ pub struct ProjectPanel {
     last_external_paths_drag_over_entry: Option<ProjectEntryId>,
     expanded_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
     unfolded_dir_ids: HashSet<ProjectEntryId>,
+    /// Files with other files nested under them that are expanded, see file nesting settings.
+    expanded_nesting_parent_ids: HashSet<ProjectEntryId>,
+    /// Maps from the visible entries that have files nested under them to those files.
+    nesting_parents: HashMap<ProjectEntryId, NestedFiles>,
+    /// Maps from visible nested files to the file they're nested under.
+    nested_entries: HashMap<ProjectEntryId, ProjectEntryId>,
     // Currently selected leaf entry (see auto-folding for a definition of that) in a file tree
     selection: Option<SelectedEntry>,
     marked_entries: BTreeSet<SelectedEntry>,
@@ -92,6 +102,8 @@ pub struct ProjectPanel {
     edit_state: Option<EditState>,
     filename_editor: Entity<Editor>,
     clipboard: Option<ClipboardEntry>,
+    /// The file chosen with "Select for Compare", to be compared with the next selected file.
+    compare_source: Option<ProjectPath>,
     _dragged_entry_destination: Option<Arc<Path>>,
     workspace: WeakEntity<Workspace>,
     width: Option<Pixels>,
@@ -159,6 +171,7 @@ struct EntryDetails {
     is_private: bool,
     worktree_id: WorktreeId,
     canonical_path: Option<Box<Path>>,
+    has_nested_files: bool,
 }
 
 #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
@@ -193,6 +206,7 @@ actions!(
         Cut,
         Paste,
         Rename,
+        BulkRename,
         Open,
         OpenPermanent,
         ToggleFocus,
@@ -206,6 +220,8 @@ actions!(
         SelectPrevDiagnostic,
         SelectNextDirectory,
         SelectPrevDirectory,
+        SelectForCompare,
+        CompareWithSelected,
     ]
 );
 
@@ -407,10 +423,13 @@ impl ProjectPanel {
             })
             .detach();
 
-            let mut project_panel_settings = *ProjectPanelSettings::get_global(cx);
+            let mut project_panel_settings = ProjectPanelSettings::get_global(cx).clone();
             cx.observe_global::<SettingsStore>(move |this, cx| {
-                let new_settings = *ProjectPanelSettings::get_global(cx);
+                let new_settings = ProjectPanelSettings::get_global(cx).clone();
                 if project_panel_settings != new_settings {
+                    if project_panel_settings.file_nesting != new_settings.file_nesting {
+                        this.update_visible_entries(None, cx);
+                    }
                     project_panel_settings = new_settings;
                     this.update_diagnostics(cx);
                     cx.notify();
@@ -432,12 +451,16 @@ impl ProjectPanel {
                 last_selection_drag_over_entry: None,
                 expanded_dir_ids: Default::default(),
                 unfolded_dir_ids: Default::default(),
+                expanded_nesting_parent_ids: Default::default(),
+                nesting_parents: Default::default(),
+                nested_entries: Default::default(),
                 selection: None,
                 marked_entries: Default::default(),
                 edit_state: None,
                 context_menu: None,
                 filename_editor,
                 clipboard: None,
+                compare_source: None,
                 _dragged_entry_destination: None,
                 workspace: workspace.weak_handle(),
                 width: None,
@@ -684,6 +707,7 @@ impl ProjectPanel {
             let is_read_only = project.is_read_only(cx);
             let is_remote = project.is_via_collab();
             let is_local = project.is_local();
+            let can_compare = self.compare_files(cx).is_some();
 
             let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
                 menu.context(self.focus_handle.clone()).map(|menu| {
@@ -715,6 +739,16 @@ impl ProjectPanel {
                             .when(is_foldable, |menu| {
                                 menu.action("Fold Directory", Box::new(FoldDirectory))
                             })
+                            .when(!is_dir, |menu| {
+                                menu.separator()
+                                    .action("Select for Compare", Box::new(SelectForCompare))
+                                    .when(can_compare, |menu| {
+                                        menu.action(
+                                            "Compare with Selected",
+                                            Box::new(CompareWithSelected),
+                                        )
+                                    })
+                            })
                             .separator()
                             .action("Cut", Box::new(Cut))
                             .action("Copy", Box::new(Copy))
@@ -732,6 +766,9 @@ impl ProjectPanel {
                             .action("Copy Relative Path", Box::new(CopyRelativePath))
                             .separator()
                             .action("Rename", Box::new(Rename))
+                            .when(!is_root, |menu| {
+                                menu.action("Bulk Rename…", Box::new(BulkRename))
+                            })
                             .when(!is_root & !is_remote, |menu| {
                                 menu.action("Trash", Box::new(Trash { skip_prompt: false }))
                             })
@@ -810,7 +847,16 @@ impl ProjectPanel {
                     return;
                 }
             }
-            if entry.is_dir() {
+            if self.nesting_parents.contains_key(&entry.id) {
+                let worktree_id = worktree.id();
+                let entry_id = entry.id;
+                if self.expanded_nesting_parent_ids.insert(entry_id) {
+                    self.update_visible_entries(Some((worktree_id, entry_id)), cx);
+                    cx.notify();
+                } else {
+                    self.select_next(&SelectNext, window, cx);
+                }
+            } else if entry.is_dir() {
                 let worktree_id = worktree.id();
                 let entry_id = entry.id;
                 let expanded_dir_ids =
@@ -850,6 +896,20 @@ impl ProjectPanel {
 
     fn collapse_entry(&mut self, entry: Entry, worktree: Entity<Worktree>, cx: &mut Context<Self>) {
         let worktree = worktree.read(cx);
+        let nesting_parent_id = if self.expanded_nesting_parent_ids.contains(&entry.id)
+            && self.nesting_parents.contains_key(&entry.id)
+        {
+            Some(entry.id)
+        } else {
+            self.nested_entries.get(&entry.id).copied()
+        };
+        if let Some(nesting_parent_id) = nesting_parent_id {
+            let worktree_id = worktree.id();
+            self.expanded_nesting_parent_ids.remove(&nesting_parent_id);
+            self.update_visible_entries(Some((worktree_id, nesting_parent_id)), cx);
+            cx.notify();
+            return;
+        }
         if let Some(folded_ancestors) = self.ancestors.get_mut(&entry.id) {
             if folded_ancestors.current_ancestor_depth + 1 < folded_ancestors.max_ancestor_depth() {
                 folded_ancestors.current_ancestor_depth += 1;
@@ -928,6 +988,22 @@ impl ProjectPanel {
         }
     }
 
+    fn toggle_nested_files(
+        &mut self,
+        entry_id: ProjectEntryId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) {
+            if !self.expanded_nesting_parent_ids.remove(&entry_id) {
+                self.expanded_nesting_parent_ids.insert(entry_id);
+            }
+            self.update_visible_entries(Some((worktree_id, entry_id)), cx);
+            window.focus(&self.focus_handle);
+            cx.notify();
+        }
+    }
+
     fn toggle_expand_all(
         &mut self,
         entry_id: ProjectEntryId,
@@ -1381,6 +1457,43 @@ impl ProjectPanel {
         self.rename_impl(None, window, cx);
     }
 
+    /// Opens the marked entries' paths in an editor, where they can be renamed and moved at once.
+    fn bulk_rename(&mut self, _: &BulkRename, window: &mut Window, cx: &mut Context<Self>) {
+        let project = self.project.read(cx);
+        let mut entries = self
+            .disjoint_entries(cx)
+            .into_iter()
+            .filter_map(|selection| {
+                let worktree = project.worktree_for_id(selection.worktree_id, cx)?;
+                let entry = worktree.read(cx).entry_for_id(selection.entry_id)?;
+                Some((
+                    selection.worktree_id,
+                    selection.entry_id,
+                    entry.path.clone(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return;
+        }
+        entries.sort_by(|(worktree_a, _, path_a), (worktree_b, _, path_b)| {
+            worktree_a.cmp(worktree_b).then_with(|| path_a.cmp(path_b))
+        });
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                BulkRenameEditor::open(
+                    entries
+                        .into_iter()
+                        .map(|(worktree_id, entry_id, _)| (worktree_id, entry_id)),
+                    workspace,
+                    window,
+                    cx,
+                );
+            })
+            .ok();
+    }
+
     fn trash(&mut self, action: &Trash, window: &mut Window, cx: &mut Context<Self>) {
         self.remove(true, action.skip_prompt, window, cx);
     }
@@ -2250,6 +2363,70 @@ impl ProjectPanel {
         }
     }
 
+    fn select_for_compare(&mut self, _: &SelectForCompare, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some((worktree, entry)) = self.selected_sub_entry(cx) {
+            if entry.is_file() {
+                self.compare_source = Some(ProjectPath {
+                    worktree_id: worktree.read(cx).id(),
+                    path: entry.path.clone(),
+                });
+            }
+        }
+    }
+
+    /// The files to compare: either the two marked files, or the file selected for compare and
+    /// the selected file.
+    fn compare_files(&self, cx: &App) -> Option<(ProjectPath, ProjectPath)> {
+        let project = self.project.read(cx);
+        let file_path = |entry: SelectedEntry| {
+            let path = project.path_for_entry(entry.entry_id, cx)?;
+            project
+                .entry_for_path(&path, cx)
+                .filter(|entry| entry.is_file())
+                .map(|_| path)
+        };
+
+        let entries = self.effective_entries();
+        if entries.len() == 2 {
+            let mut entries = entries.into_iter();
+            let left = file_path(entries.next()?)?;
+            let right = file_path(entries.next()?)?;
+            return Some((left, right));
+        }
+
+        let left = self.compare_source.clone()?;
+        let right = file_path(*entries.first()?)?;
+        (left != right).then_some((left, right))
+    }
+
+    fn compare_with_selected(
+        &mut self,
+        _: &CompareWithSelected,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((left_path, right_path)) = self.compare_files(cx) else {
+            return;
+        };
+        let (left, right) = self.project.update(cx, |project, cx| {
+            (
+                project.open_buffer(left_path, cx),
+                project.open_buffer(right_path, cx),
+            )
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, |_, mut cx| async move {
+            let left = left.await?;
+            let right = right.await?;
+            workspace.update_in(&mut cx, |workspace, window, cx| {
+                let left_title = CompareEditor::buffer_title(&left, cx);
+                let right_title = CompareEditor::buffer_title(&right, cx);
+                CompareEditor::open(left, left_title, right, right_title, workspace, window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to compare files", window, cx, |_, _, _| None);
+    }
+
     pub fn new_search_in_directory(
         &mut self,
         _: &NewSearchInDirectory,
@@ -2546,7 +2723,13 @@ impl ProjectPanel {
         new_selected_entry: Option<(WorktreeId, ProjectEntryId)>,
         cx: &mut Context<Self>,
     ) {
-        let auto_collapse_dirs = ProjectPanelSettings::get_global(cx).auto_fold_dirs;
+        let settings = ProjectPanelSettings::get_global(cx);
+        let auto_collapse_dirs = settings.auto_fold_dirs;
+        let file_nesting_rules = if settings.file_nesting.enabled {
+            FileNestingRules::new(&settings.file_nesting.patterns)
+        } else {
+            FileNestingRules::default()
+        };
         let project = self.project.read(cx);
         self.last_worktree_root_id = project
             .visible_worktrees(cx)
@@ -2556,6 +2739,8 @@ impl ProjectPanel {
 
         let old_ancestors = std::mem::take(&mut self.ancestors);
         self.visible_entries.clear();
+        self.nesting_parents.clear();
+        self.nested_entries.clear();
         let mut max_width_item = None;
         for worktree in project.visible_worktrees(cx) {
             let snapshot = worktree.read(cx).snapshot();
@@ -2743,6 +2928,18 @@ impl ProjectPanel {
             }
 
             project::sort_worktree_entries(&mut visible_worktree_entries);
+            if !file_nesting_rules.is_empty() {
+                visible_worktree_entries = file_nesting::nest_entries(
+                    visible_worktree_entries,
+                    &file_nesting_rules,
+                    &mut self.expanded_nesting_parent_ids,
+                    new_selected_entry
+                        .filter(|(selected_worktree_id, _)| *selected_worktree_id == worktree_id)
+                        .map(|(_, entry_id)| entry_id),
+                    &mut self.nesting_parents,
+                    &mut self.nested_entries,
+                );
+            }
 
             self.visible_entries
                 .push((worktree_id, visible_worktree_entries, OnceCell::new()));
@@ -2891,6 +3088,7 @@ impl ProjectPanel {
         cx: &mut Context<Self>,
     ) {
         let should_copy = window.modifiers().alt;
+        let selections = self.with_nested_files(selections.items());
         if should_copy {
             let _ = maybe!({
                 let project = self.project.read(cx);
@@ -2903,7 +3101,7 @@ impl ProjectPanel {
 
                 let mut copy_tasks = Vec::new();
                 let mut disambiguation_range = None;
-                for selection in selections.items() {
+                for selection in &selections {
                     let (new_path, new_disambiguation_range) = self.create_paste_path(
                         selection,
                         (target_worktree.clone(), &target_entry),
@@ -2947,12 +3145,37 @@ impl ProjectPanel {
                 Some(())
             });
         } else {
-            for selection in selections.items() {
+            for selection in &selections {
                 self.move_entry(selection.entry_id, target_entry_id, is_file, cx);
             }
         }
     }
 
+    /// Adds the files nested under the given entries, so that they're moved or copied together.
+    fn with_nested_files<'a>(
+        &self,
+        selections: impl Iterator<Item = &'a SelectedEntry>,
+    ) -> Vec<SelectedEntry> {
+        let mut result = Vec::new();
+        for selection in selections {
+            if !result.contains(selection) {
+                result.push(*selection);
+            }
+            if let Some(nested_files) = self.nesting_parents.get(&selection.entry_id) {
+                for entry_id in &nested_files.children {
+                    let nested_selection = SelectedEntry {
+                        worktree_id: selection.worktree_id,
+                        entry_id: *entry_id,
+                    };
+                    if !result.contains(&nested_selection) {
+                        result.push(nested_selection);
+                    }
+                }
+            }
+        }
+        result
+    }
+
     fn index_for_entry(
         &self,
         entry_id: ProjectEntryId,
@@ -3066,10 +3289,18 @@ impl ProjectPanel {
                         .collect()
                 });
                 for entry in visible_worktree_entries[entry_range].iter() {
-                    let status = git_status_setting
-                        .then_some(entry.git_summary)
-                        .unwrap_or_default();
-                    let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok();
+                    let nested_files = self.nesting_parents.get(&entry.id);
+                    let is_expanded = if nested_files.is_some() {
+                        self.expanded_nesting_parent_ids.contains(&entry.id)
+                    } else {
+                        expanded_entry_ids.binary_search(&entry.id).is_ok()
+                    };
+                    let mut status = entry.git_summary;
+                    if let Some(nested_files) = nested_files.filter(|_| !is_expanded) {
+                        // Show the status of the collapsed nested files on the file they're nested under.
+                        status = status + nested_files.git_summary;
+                    }
+                    let status = git_status_setting.then_some(status).unwrap_or_default();
                     let icon = match entry.kind {
                         EntryKind::File => {
                             if show_file_icons {
@@ -3087,8 +3318,9 @@ impl ProjectPanel {
                         }
                     };
 
-                    let (depth, difference) =
+                    let (mut depth, difference) =
                         ProjectPanel::calculate_depth_and_difference(&entry, entries);
+                    depth += self.nesting_depth(entry.id);
 
                     let filename = match difference {
                         diff if diff > 1 => entry
@@ -3142,6 +3374,7 @@ impl ProjectPanel {
                         is_private: entry.is_private,
                         worktree_id: *worktree_id,
                         canonical_path: entry.canonical_path.clone(),
+                        has_nested_files: nested_files.is_some(),
                     };
 
                     if let Some(edit_state) = &self.edit_state {
@@ -3443,6 +3676,15 @@ impl ProjectPanel {
         last_found
     }
 
+    /// Returns how much deeper an entry is shown because it's nested under another file.
+    fn nesting_depth(&self, entry_id: ProjectEntryId) -> usize {
+        if self.nested_entries.contains_key(&entry_id) {
+            1
+        } else {
+            0
+        }
+    }
+
     fn calculate_depth_and_difference(
         entry: &Entry,
         visible_worktree_entries: &HashSet<Arc<Path>>,
@@ -3481,6 +3723,8 @@ impl ProjectPanel {
         const GROUP_NAME: &str = "project_entry";
 
         let kind = details.kind;
+        let has_nested_files = details.has_nested_files;
+        let is_expanded = details.is_expanded;
         let settings = ProjectPanelSettings::get_global(cx);
         let show_editor = details.is_editing && !details.is_processing;
 
@@ -3995,6 +4239,25 @@ impl ProjectPanel {
                                             .single_line()
                                             .color(filename_text_color),
                                     )
+                                    .when(has_nested_files, |this| {
+                                        this.child(
+                                            Disclosure::new(
+                                                (
+                                                    "project_panel_nested_files",
+                                                    entry_id.to_usize(),
+                                                ),
+                                                is_expanded,
+                                            )
+                                            .on_click(cx.listener(
+                                                move |this, _, window, cx| {
+                                                    cx.stop_propagation();
+                                                    this.toggle_nested_files(
+                                                        entry_id, window, cx,
+                                                    );
+                                                },
+                                            )),
+                                        )
+                                    })
                                 }
                             })
                         }
@@ -4273,6 +4536,7 @@ impl ProjectPanel {
 
             // Calculate the actual depth of the entry, taking into account that directories can be auto-folded.
             let (depth, _) = Self::calculate_depth_and_difference(entry, visible_worktree_entries);
+            let depth = depth + self.nesting_depth(entry.id);
             (start..end, depth)
         };
 
@@ -4418,6 +4682,8 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::cancel))
                 .on_action(cx.listener(Self::copy_path))
                 .on_action(cx.listener(Self::copy_relative_path))
+                .on_action(cx.listener(Self::select_for_compare))
+                .on_action(cx.listener(Self::compare_with_selected))
                 .on_action(cx.listener(Self::new_search_in_directory))
                 .on_action(cx.listener(Self::unfold_directory))
                 .on_action(cx.listener(Self::fold_directory))
@@ -4426,6 +4692,7 @@ impl Render for ProjectPanel {
                     el.on_action(cx.listener(Self::new_file))
                         .on_action(cx.listener(Self::new_directory))
                         .on_action(cx.listener(Self::rename))
+                        .on_action(cx.listener(Self::bulk_rename))
                         .on_action(cx.listener(Self::delete))
                         .on_action(cx.listener(Self::trash))
                         .on_action(cx.listener(Self::cut))
@@ -4506,7 +4773,7 @@ impl Render for ProjectPanel {
                                             let (depth, _) = Self::calculate_depth_and_difference(
                                                 entry, entries,
                                             );
-                                            items.push(depth);
+                                            items.push(depth + this.nesting_depth(entry.id));
                                         },
                                     );
                                     items
@@ -5102,7 +5369,7 @@ mod tests {
             cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
         let cx = &mut VisualTestContext::from_window(*workspace, cx);
         cx.update(|_, cx| {
-            let settings = *ProjectPanelSettings::get_global(cx);
+            let settings = ProjectPanelSettings::get_global(cx).clone();
             ProjectPanelSettings::override_global(
                 ProjectPanelSettings {
                     auto_fold_dirs: true,
@@ -6811,6 +7078,201 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_file_nesting(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<ProjectPanelSettings>(cx, |project_panel_settings| {
+                    project_panel_settings.file_nesting =
+                        Some(project_panel_settings::FileNestingSettingsContent {
+                            enabled: Some(true),
+                            patterns: Some(
+                                [
+                                    ("*.ts".to_string(), "${capture}.js".to_string()),
+                                    ("Cargo.toml".to_string(), "Cargo.lock".to_string()),
+                                ]
+                                .into_iter()
+                                .collect(),
+                            ),
+                        });
+                });
+            })
+        });
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/project_root",
+            json!({
+                "dir_1": {
+                    "Cargo.lock": "",
+                },
+                "Cargo.lock": "",
+                "Cargo.toml": "",
+                "main.js": "",
+                "main.ts": "",
+                "other.js": "",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/project_root".as_ref()], cx).await;
+        let workspace =
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace.update(cx, ProjectPanel::new).unwrap();
+
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    > dir_1",
+                "      Cargo.toml",
+                "      main.ts",
+                "      other.js",
+            ],
+            "Nested files should be hidden until their parent is expanded"
+        );
+
+        select_path(&panel, "project_root/Cargo.toml", cx);
+        panel.update_in(cx, |panel, window, cx| {
+            panel.expand_selected_entry(&Default::default(), window, cx)
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    > dir_1",
+                "      Cargo.toml  <== selected",
+                "          Cargo.lock",
+                "      main.ts",
+                "      other.js",
+            ]
+        );
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.select_next(&Default::default(), window, cx);
+            panel.collapse_selected_entry(&Default::default(), window, cx);
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    > dir_1",
+                "      Cargo.toml  <== selected",
+                "      main.ts",
+                "      other.js",
+            ],
+            "Collapsing a nested file should collapse and select its parent"
+        );
+
+        let main_js = find_project_entry(&panel, "project_root/main.js", cx).unwrap();
+        panel.update(cx, |panel, cx| {
+            panel.reveal_entry(project.clone(), main_js, true, cx)
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    > dir_1",
+                "      Cargo.toml",
+                "      main.ts",
+                "          main.js  <== selected",
+                "      other.js",
+            ],
+            "Revealing a nested file should expand its parent"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_bulk_rename(cx: &mut gpui::TestAppContext) {
+        use project::Fs as _;
+
+        init_test_with_editor(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/project_root",
+            json!({
+                "fixtures": {
+                    "a.txt": "a",
+                    "b.txt": "b",
+                    "c.txt": "c",
+                },
+                "other.txt": "",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/project_root".as_ref()], cx).await;
+        let workspace =
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace.update(cx, ProjectPanel::new).unwrap();
+
+        toggle_expand_dir(&panel, "project_root/fixtures", cx);
+        select_path_with_mark(&panel, "project_root/fixtures/a.txt", cx);
+        select_path_with_mark(&panel, "project_root/fixtures/b.txt", cx);
+        select_path_with_mark(&panel, "project_root/fixtures/c.txt", cx);
+        panel.update_in(cx, |panel, window, cx| {
+            panel.bulk_rename(&BulkRename, window, cx)
+        });
+        let (item, editor) = workspace
+            .update(cx, |workspace, _, cx| {
+                let item = workspace.active_item(cx).unwrap();
+                let editor = item.act_as::<Editor>(cx).unwrap();
+                (item, editor)
+            })
+            .unwrap();
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "fixtures/a.txt\nfixtures/b.txt\nfixtures/c.txt\n"
+        );
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_text("fixtures/a.txt\nother.txt\nfixtures/c.txt\n", window, cx)
+        });
+        let save = cx.update(|window, cx| item.save(true, project.clone(), window, cx));
+        assert!(
+            save.await.is_err(),
+            "Renaming a file to an existing path should fail"
+        );
+        cx.update(|_, cx| assert!(item.is_dirty(cx)));
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_text(
+                "fixtures/b.txt\nfixtures/a.txt\nfixtures/renamed/c.txt\n",
+                window,
+                cx,
+            )
+        });
+        let save = cx.update(|window, cx| item.save(true, project.clone(), window, cx));
+        save.await.unwrap();
+        cx.executor().run_until_parked();
+        cx.update(|_, cx| assert!(!item.is_dirty(cx)));
+
+        assert_eq!(
+            fs.load(Path::new("/project_root/fixtures/a.txt"))
+                .await
+                .unwrap(),
+            "b"
+        );
+        assert_eq!(
+            fs.load(Path::new("/project_root/fixtures/b.txt"))
+                .await
+                .unwrap(),
+            "a"
+        );
+        assert_eq!(
+            fs.load(Path::new("/project_root/fixtures/renamed/c.txt"))
+                .await
+                .unwrap(),
+            "c"
+        );
+        assert!(!fs.is_file(Path::new("/project_root/fixtures/c.txt")).await);
+    }
+
     #[gpui::test]
     async fn test_collapse_all_entries(cx: &mut gpui::TestAppContext) {
         init_test_with_editor(cx);
@@ -8580,7 +9042,7 @@ mod tests {
 
         // Test 1: When auto-fold is enabled
         cx.update(|_, cx| {
-            let settings = *ProjectPanelSettings::get_global(cx);
+            let settings = ProjectPanelSettings::get_global(cx).clone();
             ProjectPanelSettings::override_global(
                 ProjectPanelSettings {
                     auto_fold_dirs: true,
@@ -8642,7 +9104,7 @@ mod tests {
 
         // Test 2: When auto-fold is disabled
         cx.update(|_, cx| {
-            let settings = *ProjectPanelSettings::get_global(cx);
+            let settings = ProjectPanelSettings::get_global(cx).clone();
             ProjectPanelSettings::override_global(
                 ProjectPanelSettings {
                     auto_fold_dirs: false,
@@ -8807,7 +9269,7 @@ mod tests {
         // Test 2: With auto-fold enabled
         {
             cx.update(|_, cx| {
-                let settings = *ProjectPanelSettings::get_global(cx);
+                let settings = ProjectPanelSettings::get_global(cx).clone();
                 ProjectPanelSettings::override_global(
                     ProjectPanelSettings {
                         auto_fold_dirs: true,
@@ -8862,7 +9324,7 @@ mod tests {
         // Test 3: With auto-fold disabled
         {
             cx.update(|_, cx| {
-                let settings = *ProjectPanelSettings::get_global(cx);
+                let settings = ProjectPanelSettings::get_global(cx).clone();
                 ProjectPanelSettings::override_global(
                     ProjectPanelSettings {
                         auto_fold_dirs: false,
@@ -3,6 +3,7 @@ use gpui::Pixels;
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, Copy, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -28,7 +29,7 @@ pub enum EntrySpacing {
     Standard,
 }
 
-#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct ProjectPanelSettings {
     pub button: bool,
     pub default_width: Pixels,
@@ -43,6 +44,7 @@ pub struct ProjectPanelSettings {
     pub auto_fold_dirs: bool,
     pub scrollbar: ScrollbarSettings,
     pub show_diagnostics: ShowDiagnostics,
+    pub file_nesting: FileNestingSettings,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -56,6 +58,25 @@ pub struct IndentGuidesSettingsContent {
     pub show: Option<ShowIndentGuides>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FileNestingSettings {
+    pub enabled: bool,
+    pub patterns: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FileNestingSettingsContent {
+    /// Whether to nest related files under a parent file.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Maps the name of a parent file to a comma-separated list of the names of the files
+    /// nested under it. In a parent's name, `*` matches any text. In the nested files' names,
+    /// `*` matches any text, `${capture}` is the text that `*` matched in the parent's name,
+    /// and `${basename}` is the parent's name up to its last extension.
+    pub patterns: Option<BTreeMap<String, String>>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct ScrollbarSettings {
     /// When to show the scrollbar in the project panel.
@@ -140,6 +161,8 @@ pub struct ProjectPanelSettingsContent {
     pub show_diagnostics: Option<ShowDiagnostics>,
     /// Settings related to indent guides in the project panel.
     pub indent_guides: Option<IndentGuidesSettingsContent>,
+    /// Settings related to nesting files under related files in the project panel.
+    pub file_nesting: Option<FileNestingSettingsContent>,
 }
 
 impl Settings for ProjectPanelSettings {
@@ -199,6 +199,8 @@ messages!(
     (GetDeclarationResponse, Background),
     (GetDocumentHighlights, Background),
     (GetDocumentHighlightsResponse, Background),
+    (GetDocumentSymbols, Background),
+    (GetDocumentSymbolsResponse, Background),
     (GetHover, Background),
     (GetHoverResponse, Background),
     (GetNotifications, Foreground),
@@ -240,6 +242,10 @@ messages!(
     (LeaveChannelChat, Foreground),
     (LeaveProject, Foreground),
     (LeaveRoom, Foreground),
+    (LoadBinaryFile, Background),
+    (LoadBinaryFileResponse, Background),
+    (LoadCommittedText, Background),
+    (LoadCommittedTextResponse, Background),
     (MarkNotificationRead, Foreground),
     (MoveChannel, Foreground),
     (OnTypeFormatting, Background),
@@ -281,6 +287,8 @@ messages!(
     (RespondToChannelInvite, Foreground),
     (RespondToContactRequest, Foreground),
     (RoomUpdated, Foreground),
+    (SaveBinaryFile, Background),
+    (SaveBinaryFileResponse, Background),
     (SaveBuffer, Foreground),
     (SetChannelMemberRole, Foreground),
     (SetChannelVisibility, Foreground),
@@ -344,6 +352,18 @@ messages!(
     (UpdateContext, Foreground),
     (SynchronizeContexts, Foreground),
     (SynchronizeContextsResponse, Foreground),
+    (AdvertiseSharedTerminals, Foreground),
+    (OpenSharedTerminal, Foreground),
+    (OpenSharedTerminalResponse, Foreground),
+    (UpdateSharedTerminal, Foreground),
+    (SharedTerminalInput, Foreground),
+    (GetReviewThreads, Foreground),
+    (GetReviewThreadsResponse, Foreground),
+    (CreateReviewThread, Foreground),
+    (CreateReviewComment, Foreground),
+    (ResolveReviewThread, Foreground),
+    (ReviewThreadResponse, Foreground),
+    (ReviewThreadUpdated, Foreground),
     (LspExtSwitchSourceHeader, Background),
     (LspExtSwitchSourceHeaderResponse, Background),
     (AddWorktree, Foreground),
@@ -414,6 +434,7 @@ request_messages!(
     (GetDeclaration, GetDeclarationResponse),
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
+    (GetDocumentSymbols, GetDocumentSymbolsResponse),
     (GetHover, GetHoverResponse),
     (GetLlmToken, GetLlmTokenResponse),
     (GetNotifications, GetNotificationsResponse),
@@ -437,6 +458,8 @@ request_messages!(
     (JoinRoom, JoinRoomResponse),
     (LeaveChannelBuffer, Ack),
     (LeaveRoom, Ack),
+    (LoadBinaryFile, LoadBinaryFileResponse),
+    (LoadCommittedText, LoadCommittedTextResponse),
     (MarkNotificationRead, Ack),
     (MoveChannel, Ack),
     (OnTypeFormatting, OnTypeFormattingResponse),
@@ -466,6 +489,7 @@ request_messages!(
     (ResolveInlayHint, ResolveInlayHintResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
+    (SaveBinaryFile, SaveBinaryFileResponse),
     (SaveBuffer, BufferSaved),
     (Stage, Ack),
     (FindSearchCandidates, FindSearchCandidatesResponse),
@@ -491,6 +515,12 @@ request_messages!(
     (OpenContext, OpenContextResponse),
     (CreateContext, CreateContextResponse),
     (SynchronizeContexts, SynchronizeContextsResponse),
+    (OpenSharedTerminal, OpenSharedTerminalResponse),
+    (SharedTerminalInput, Ack),
+    (GetReviewThreads, GetReviewThreadsResponse),
+    (CreateReviewThread, ReviewThreadResponse),
+    (CreateReviewComment, ReviewThreadResponse),
+    (ResolveReviewThread, ReviewThreadResponse),
     (LspExtSwitchSourceHeader, LspExtSwitchSourceHeaderResponse),
     (AddWorktree, AddWorktreeResponse),
     (ShutdownRemoteServer, Ack),
@@ -537,6 +567,7 @@ entity_messages!(
     GetDeclaration,
     GetImplementation,
     GetDocumentHighlights,
+    GetDocumentSymbols,
     GetHover,
     GetProjectSymbols,
     GetReferences,
@@ -547,6 +578,8 @@ entity_messages!(
     JoinProject,
     LeaveProject,
     LinkedEditingRange,
+    LoadBinaryFile,
+    LoadCommittedText,
     MultiLspQuery,
     RestartLanguageServers,
     OnTypeFormatting,
@@ -562,6 +595,7 @@ entity_messages!(
     RenameProjectEntry,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
+    SaveBinaryFile,
     SaveBuffer,
     Stage,
     StartLanguageServer,
@@ -585,6 +619,10 @@ entity_messages!(
     CreateContext,
     UpdateContext,
     SynchronizeContexts,
+    AdvertiseSharedTerminals,
+    OpenSharedTerminal,
+    UpdateSharedTerminal,
+    SharedTerminalInput,
     LspExtSwitchSourceHeader,
     LanguageServerLog,
     Toast,
@@ -610,6 +648,7 @@ entity_messages!(
     UpdateChannelMessage,
     UpdateChannelBuffer,
     UpdateChannelBufferCollaborators,
+    ReviewThreadUpdated,
 );
 
 impl From<Timestamp> for SystemTime {
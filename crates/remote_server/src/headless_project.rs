@@ -201,6 +201,7 @@ impl HeadlessProject {
         client.add_model_request_handler(Self::handle_stage);
         client.add_model_request_handler(Self::handle_unstage);
         client.add_model_request_handler(Self::handle_commit);
+        client.add_model_request_handler(Self::handle_load_committed_text);
 
         client.add_request_handler(
             extensions.clone().downgrade(),
@@ -731,6 +732,32 @@ impl HeadlessProject {
             Ok(proto::Ack {})
         }
     }
+
+    async fn handle_load_committed_text(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadCommittedText>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadCommittedTextResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let load = this.update(&mut cx, |project, cx| {
+            let repository_handle = project
+                .git_state
+                .read(cx)
+                .all_repositories()
+                .into_iter()
+                .find(|repository_handle| {
+                    repository_handle.worktree_id == worktree_id
+                        && repository_handle.repository_entry.work_directory_id()
+                            == work_directory_id
+                })
+                .context("missing repository handle")?;
+            let repo_path = RepoPath::new(PathBuf::from(envelope.payload.path));
+            anyhow::Ok(repository_handle.load_committed_text(repo_path, cx))
+        })??;
+
+        Ok(proto::LoadCommittedTextResponse { text: load.await? })
+    }
 }
 
 fn prompt_to_proto(
@@ -0,0 +1,490 @@
+use anyhow::{anyhow, Result};
+use collections::{HashMap, HashSet};
+
+/// A word list loaded from a Hunspell dictionary (a `.aff` file and a `.dic` file).
+///
+/// Every word in the `.dic` file is expanded with its prefix and suffix rules up front, so that
+/// looking up a word is a single hash set lookup.
+pub struct Dictionary {
+    words: HashSet<String>,
+    try_chars: Vec<char>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FlagMode {
+    Short,
+    Long,
+    Numeric,
+    Utf8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+struct Affix {
+    kind: AffixKind,
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<ConditionPart>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ConditionPart {
+    Any,
+    Char(char),
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl Dictionary {
+    /// Parses a dictionary from the contents of its `.aff` and `.dic` files.
+    pub fn parse(aff: &[u8], dic: &[u8]) -> Result<Self> {
+        let latin1 = String::from_utf8_lossy(aff).lines().any(|line| {
+            let mut parts = line.split_whitespace();
+            parts.next() == Some("SET")
+                && parts
+                    .next()
+                    .map_or(false, |encoding| encoding.to_ascii_uppercase() != "UTF-8")
+        });
+        let aff = decode(aff, latin1);
+        let dic = decode(dic, latin1);
+
+        let mut flag_mode = FlagMode::Short;
+        let mut try_chars = Vec::new();
+        let mut skipped_flags = HashSet::default();
+        let mut need_affix_flags = HashSet::default();
+        let mut affixes = HashMap::<String, Affix>::default();
+
+        for line in aff.lines() {
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                Some("FLAG") => {
+                    flag_mode = match parts.next() {
+                        Some("long") => FlagMode::Long,
+                        Some("num") => FlagMode::Numeric,
+                        Some("UTF-8") => FlagMode::Utf8,
+                        _ => FlagMode::Short,
+                    }
+                }
+                Some("TRY") => {
+                    try_chars = parts.next().unwrap_or_default().chars().collect();
+                }
+                Some("FORBIDDENWORD" | "ONLYINCOMPOUND") => {
+                    skipped_flags.extend(parts.next().map(str::to_string));
+                }
+                Some("NEEDAFFIX") => {
+                    need_affix_flags.extend(parts.next().map(str::to_string));
+                }
+                Some(keyword @ ("PFX" | "SFX")) => {
+                    let kind = if keyword == "PFX" {
+                        AffixKind::Prefix
+                    } else {
+                        AffixKind::Suffix
+                    };
+                    let Some(flag) = parts.next() else {
+                        continue;
+                    };
+                    let Some(second) = parts.next() else {
+                        continue;
+                    };
+                    let Some(third) = parts.next() else {
+                        continue;
+                    };
+                    match parts.next() {
+                        // A header line: `SFX flag cross_product count`.
+                        None => {
+                            affixes.insert(
+                                flag.to_string(),
+                                Affix {
+                                    kind,
+                                    cross_product: second == "Y",
+                                    rules: Vec::new(),
+                                },
+                            );
+                        }
+                        // A rule line: `SFX flag strip add condition`.
+                        Some(condition) => {
+                            let Some(affix) = affixes.get_mut(flag) else {
+                                continue;
+                            };
+                            let add = third.split('/').next().unwrap_or_default();
+                            affix.rules.push(AffixRule {
+                                strip: if second == "0" { "" } else { second }.to_string(),
+                                add: if add == "0" { "" } else { add }.to_string(),
+                                condition: parse_condition(condition)?,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut words = HashSet::default();
+        for line in dic.lines().skip(1) {
+            let entry = line.split_whitespace().next().unwrap_or_default();
+            if entry.is_empty() {
+                continue;
+            }
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => (word, parse_flags(flags, flag_mode)),
+                None => (entry, Vec::new()),
+            };
+            if flags.iter().any(|flag| skipped_flags.contains(flag)) {
+                continue;
+            }
+            if !flags.iter().any(|flag| need_affix_flags.contains(flag)) {
+                words.insert(word.to_string());
+            }
+
+            let entry_affixes = flags
+                .iter()
+                .filter_map(|flag| affixes.get(flag))
+                .collect::<Vec<_>>();
+            let mut cross_product_suffixed = Vec::new();
+            for affix in &entry_affixes {
+                if affix.kind == AffixKind::Suffix {
+                    for suffixed in affix.apply(word) {
+                        if affix.cross_product {
+                            cross_product_suffixed.push(suffixed.clone());
+                        }
+                        words.insert(suffixed);
+                    }
+                }
+            }
+            for affix in &entry_affixes {
+                if affix.kind == AffixKind::Prefix {
+                    words.extend(affix.apply(word));
+                    if affix.cross_product {
+                        for suffixed in &cross_product_suffixed {
+                            words.extend(affix.apply(suffixed));
+                        }
+                    }
+                }
+            }
+        }
+
+        if words.is_empty() {
+            return Err(anyhow!("dictionary contains no words"));
+        }
+        Ok(Self { words, try_chars })
+    }
+
+    /// Returns whether the dictionary contains `word`, allowing for capitalization at the start
+    /// of a sentence and for words written in all caps.
+    pub fn contains(&self, word: &str) -> bool {
+        if self.words.contains(word) {
+            return true;
+        }
+        match Case::of(word) {
+            Case::Capitalized => self.words.contains(&word.to_lowercase()),
+            Case::Upper => {
+                let lowercase = word.to_lowercase();
+                self.words.contains(&capitalize(&lowercase)) || self.words.contains(&lowercase)
+            }
+            Case::Lower | Case::Mixed => false,
+        }
+    }
+
+    /// Returns up to `limit` words from the dictionary that are one or two edits away from
+    /// `word`, closest first, in the same case as `word`.
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let case = Case::of(word);
+        let lowercase = word.to_lowercase();
+        let alphabet = if self.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.try_chars
+                .iter()
+                .copied()
+                .filter(|c| c.is_lowercase())
+                .collect::<Vec<_>>()
+        };
+
+        let mut suggestions = Vec::new();
+        let mut seen = HashSet::default();
+        let first_edits = edits(&lowercase, &alphabet);
+        for candidate in &first_edits {
+            if self.add_suggestion(candidate, case, &mut seen, &mut suggestions) >= limit {
+                return suggestions;
+            }
+        }
+        if suggestions.is_empty() {
+            for first_edit in &first_edits {
+                for candidate in edits(first_edit, &alphabet) {
+                    if self.add_suggestion(&candidate, case, &mut seen, &mut suggestions) >= limit {
+                        return suggestions;
+                    }
+                }
+            }
+        }
+        suggestions
+    }
+
+    fn add_suggestion(
+        &self,
+        candidate: &str,
+        case: Case,
+        seen: &mut HashSet<String>,
+        suggestions: &mut Vec<String>,
+    ) -> usize {
+        let found = if self.words.contains(candidate) {
+            Some(candidate.to_string())
+        } else {
+            let capitalized = capitalize(candidate);
+            self.words.contains(&capitalized).then_some(capitalized)
+        };
+        if let Some(found) = found {
+            let found = match case {
+                Case::Upper => found.to_uppercase(),
+                Case::Capitalized => capitalize(&found),
+                Case::Lower | Case::Mixed => found,
+            };
+            if seen.insert(found.clone()) {
+                suggestions.push(found);
+            }
+        }
+        suggestions.len()
+    }
+}
+
+impl Affix {
+    fn apply(&self, word: &str) -> Vec<String> {
+        self.rules
+            .iter()
+            .filter_map(|rule| match self.kind {
+                AffixKind::Prefix => {
+                    let stem = word.strip_prefix(rule.strip.as_str())?;
+                    matches_condition(word.chars(), &rule.condition)
+                        .then(|| format!("{}{}", rule.add, stem))
+                }
+                AffixKind::Suffix => {
+                    let stem = word.strip_suffix(rule.strip.as_str())?;
+                    let condition_len = rule.condition.len();
+                    let char_count = word.chars().count();
+                    (char_count >= condition_len
+                        && matches_condition(
+                            word.chars().skip(char_count - condition_len),
+                            &rule.condition,
+                        ))
+                    .then(|| format!("{}{}", stem, rule.add))
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Case {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+impl Case {
+    fn of(word: &str) -> Self {
+        let mut chars = word.chars().filter(|c| c.is_alphabetic());
+        let Some(first) = chars.next() else {
+            return Case::Lower;
+        };
+        let rest = chars.collect::<Vec<_>>();
+        if first.is_lowercase() {
+            if rest.iter().all(|c| c.is_lowercase()) {
+                Case::Lower
+            } else {
+                Case::Mixed
+            }
+        } else if rest.iter().all(|c| c.is_lowercase()) {
+            Case::Capitalized
+        } else if rest.iter().all(|c| c.is_uppercase()) {
+            Case::Upper
+        } else {
+            Case::Mixed
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Returns every string that is one deletion, transposition, replacement or insertion away
+/// from `word`.
+fn edits(word: &str, alphabet: &[char]) -> Vec<String> {
+    let chars = word.chars().collect::<Vec<_>>();
+    let mut edits = Vec::new();
+    for i in 0..chars.len() {
+        edits.push(chars[..i].iter().chain(&chars[i + 1..]).collect());
+    }
+    for i in 1..chars.len() {
+        let mut transposed = chars.clone();
+        transposed.swap(i - 1, i);
+        edits.push(transposed.into_iter().collect());
+    }
+    for i in 0..chars.len() {
+        for &c in alphabet {
+            if c != chars[i] {
+                let mut replaced = chars.clone();
+                replaced[i] = c;
+                edits.push(replaced.into_iter().collect());
+            }
+        }
+    }
+    for i in 0..=chars.len() {
+        for &c in alphabet {
+            let mut inserted = chars.clone();
+            inserted.insert(i, c);
+            edits.push(inserted.into_iter().collect());
+        }
+    }
+    edits
+}
+
+fn decode(bytes: &[u8], latin1: bool) -> String {
+    if latin1 {
+        bytes.iter().map(|&byte| byte as char).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+fn parse_flags(flags: &str, mode: FlagMode) -> Vec<String> {
+    match mode {
+        FlagMode::Short | FlagMode::Utf8 => flags.chars().map(String::from).collect(),
+        FlagMode::Long => flags
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|pair| pair.iter().collect())
+            .collect(),
+        FlagMode::Numeric => flags.split(',').map(str::to_string).collect(),
+    }
+}
+
+fn parse_condition(condition: &str) -> Result<Vec<ConditionPart>> {
+    if condition == "." {
+        return Ok(Vec::new());
+    }
+    let mut parts = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => parts.push(ConditionPart::Any),
+            '[' => {
+                let mut set = Vec::new();
+                let mut negated = false;
+                loop {
+                    match chars.next() {
+                        Some('^') if set.is_empty() && !negated => negated = true,
+                        Some(']') => break,
+                        Some(c) => set.push(c),
+                        None => return Err(anyhow!("unterminated affix condition {condition:?}")),
+                    }
+                }
+                parts.push(ConditionPart::Set {
+                    chars: set,
+                    negated,
+                });
+            }
+            c => parts.push(ConditionPart::Char(c)),
+        }
+    }
+    Ok(parts)
+}
+
+fn matches_condition(mut chars: impl Iterator<Item = char>, condition: &[ConditionPart]) -> bool {
+    condition.iter().all(|part| {
+        let Some(c) = chars.next() else {
+            return false;
+        };
+        match part {
+            ConditionPart::Any => true,
+            ConditionPart::Char(expected) => c == *expected,
+            ConditionPart::Set { chars, negated } => chars.contains(&c) != *negated,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+
+FORBIDDENWORD !
+
+PFX U Y 1
+PFX U 0 un .
+
+SFX S Y 3
+SFX S y ies [^aeiou]y
+SFX S 0 s [aeiou]y
+SFX S 0 s [^y]
+
+SFX D N 2
+SFX D 0 ed [^e]
+SFX D 0 d e
+";
+
+    const DIC: &str = "6
+happy/U
+try/S
+day/S
+tune/DU
+colour
+recieve/!
+";
+
+    #[test]
+    fn test_affix_expansion() {
+        let dictionary = Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap();
+        for word in [
+            "happy", "unhappy", "try", "tries", "day", "days", "tune", "tuned", "untune", "colour",
+        ] {
+            assert!(dictionary.contains(word), "{word} should be a word");
+        }
+        for word in ["trys", "daies", "untuned", "recieve", "unday", "color"] {
+            assert!(!dictionary.contains(word), "{word} should not be a word");
+        }
+    }
+
+    #[test]
+    fn test_case() {
+        let dictionary = Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap();
+        assert!(dictionary.contains("Happy"));
+        assert!(dictionary.contains("HAPPY"));
+        assert!(!dictionary.contains("hAppy"));
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let dictionary = Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap();
+        assert_eq!(dictionary.suggestions("colur", 5), vec!["colour"]);
+        assert_eq!(dictionary.suggestions("Tunde", 5), vec!["Tune", "Tuned"]);
+        assert_eq!(dictionary.suggestions("DAYZ", 1), vec!["DAY"]);
+        assert_eq!(dictionary.suggestions("xyzzyq", 5), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_latin1_and_long_flags() {
+        let aff = b"SET ISO8859-1\nFLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let dic = b"1\ncaf\xe9/Aa\n";
+        let dictionary = Dictionary::parse(aff, dic).unwrap();
+        assert!(dictionary.contains("café"));
+        assert!(dictionary.contains("cafés"));
+    }
+}
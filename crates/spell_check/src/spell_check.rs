@@ -0,0 +1,710 @@
+mod dictionary;
+mod spell_check_settings;
+mod words;
+
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use collections::{HashMap, HashSet};
+use editor::{
+    Addon, CodeActionProvider, Editor, EditorEvent, EditorMode, ExcerptId, MultiBufferSnapshot,
+    ToPoint as _,
+};
+use fs::Fs;
+use futures::lock::Mutex;
+use gpui::{
+    px, App, AppContext as _, AsyncApp, Context, Entity, Global, HighlightStyle, Subscription,
+    Task, UnderlineStyle, WeakEntity, Window,
+};
+use language::{Bias, Buffer, BufferId, Point, ToOffset as _};
+use project::{CodeAction, Project, ProjectTransaction};
+use serde::{Deserialize, Serialize};
+use settings::{Settings as _, SettingsStore};
+use theme::ActiveTheme as _;
+use workspace::{item::ItemHandle, Workspace};
+
+pub use dictionary::Dictionary;
+pub use spell_check_settings::SpellCheckSettings;
+pub use words::words;
+
+const CHECK_DEBOUNCE: Duration = Duration::from_millis(300);
+const MAX_SUGGESTIONS: usize = 5;
+const CODE_ACTION_PROVIDER_ID: &str = "spell_check";
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    SpellCheckSettings::register(cx);
+
+    let spell_checker = cx.new(|cx| SpellChecker::new(fs, cx));
+    cx.set_global(GlobalSpellChecker(spell_checker));
+
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        let project = workspace.project().downgrade();
+        let workspace = cx.entity();
+        window
+            .subscribe(&workspace, cx, move |_, event, window, cx| {
+                if let workspace::Event::ItemAdded { item } = event {
+                    register_item(item.as_ref(), project.clone(), window, cx);
+                }
+            })
+            .detach();
+    })
+    .detach();
+}
+
+fn register_item(
+    item: &dyn ItemHandle,
+    project: WeakEntity<Project>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(editor) = item.act_as::<Editor>(cx) else {
+        return;
+    };
+    if let Some(project) = project.upgrade() {
+        if let Some(spell_checker) = SpellChecker::global(cx) {
+            spell_checker.update(cx, |spell_checker, cx| {
+                spell_checker.load_project_words(&project, cx)
+            });
+        }
+    }
+    let editor_state = editor.read(cx);
+    if editor_state.mode() != EditorMode::Full || editor_state.addon::<SpellCheckAddon>().is_some()
+    {
+        return;
+    }
+
+    let spell_check = cx.new(|cx| EditorSpellCheck::new(&editor, project.clone(), cx));
+    editor.update(cx, |editor, cx| {
+        editor.register_addon(SpellCheckAddon {
+            _spell_check: spell_check,
+        });
+        editor.add_code_action_provider(
+            Rc::new(SpellCheckCodeActionProvider { project }),
+            window,
+            cx,
+        );
+    });
+}
+
+struct GlobalSpellChecker(Entity<SpellChecker>);
+
+impl Global for GlobalSpellChecker {}
+
+/// Holds the dictionaries and word lists that words are checked against.
+///
+/// Words can be accepted in a user word list, stored in Zed's config directory, or in a project
+/// word list, stored in a `.zed/words.txt` file at the root of a local worktree.
+pub struct SpellChecker {
+    fs: Arc<dyn Fs>,
+    settings: SpellCheckSettings,
+    dictionaries: Vec<Arc<Dictionary>>,
+    user_words: Arc<HashSet<String>>,
+    project_words: HashMap<PathBuf, Arc<HashSet<String>>>,
+    /// Held while appending to a word list, so that concurrent additions don't overwrite each
+    /// other.
+    word_list_write_lock: Arc<Mutex<()>>,
+    _load_dictionaries: Task<()>,
+    _load_user_words: Task<()>,
+    _settings_subscription: Subscription,
+}
+
+impl SpellChecker {
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalSpellChecker>()
+            .map(|global| global.0.clone())
+    }
+
+    fn new(fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Self {
+        let settings = SpellCheckSettings::get_global(cx).clone();
+        let load_user_words = cx.spawn({
+            let fs = fs.clone();
+            |this, mut cx| async move {
+                let words = load_words(&fs, paths::words_file()).await;
+                this.update(&mut cx, |this, cx| {
+                    this.user_words = Arc::new(words);
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
+        let settings_subscription = cx.observe_global::<SettingsStore>(|this, cx| {
+            let settings = SpellCheckSettings::get_global(cx);
+            if *settings != this.settings {
+                this.settings = settings.clone();
+                this.load_dictionaries(cx);
+            }
+        });
+
+        let mut this = Self {
+            fs,
+            settings,
+            dictionaries: Vec::new(),
+            user_words: Arc::default(),
+            project_words: Default::default(),
+            word_list_write_lock: Arc::default(),
+            _load_dictionaries: Task::ready(()),
+            _load_user_words: load_user_words,
+            _settings_subscription: settings_subscription,
+        };
+        this.load_dictionaries(cx);
+        this
+    }
+
+    fn load_dictionaries(&mut self, cx: &mut Context<Self>) {
+        let settings = self.settings.clone();
+        let fs = self.fs.clone();
+        self._load_dictionaries = cx.spawn(|this, mut cx| async move {
+            let mut dictionaries = Vec::new();
+            if settings.enabled {
+                let directories = settings.search_directories();
+                for name in &settings.dictionaries {
+                    match load_dictionary(&fs, name, &directories, &cx).await {
+                        Ok(dictionary) => dictionaries.push(Arc::new(dictionary)),
+                        Err(error) => {
+                            log::warn!("failed to load spell check dictionary {name:?}: {error:#}")
+                        }
+                    }
+                }
+            }
+            this.update(&mut cx, |this, cx| {
+                this.dictionaries = dictionaries;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Loads the word lists of any of the project's local worktrees that haven't been loaded yet.
+    fn load_project_words(&mut self, project: &Entity<Project>, cx: &mut Context<Self>) {
+        if !project.read(cx).is_local() {
+            return;
+        }
+        let roots = project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .collect::<Vec<_>>();
+        for root in roots {
+            if self.project_words.contains_key(&root) {
+                continue;
+            }
+            self.project_words.insert(root.clone(), Arc::default());
+            let fs = self.fs.clone();
+            cx.spawn(|this, mut cx| async move {
+                let words =
+                    load_words(&fs, &root.join(paths::local_words_file_relative_path())).await;
+                this.update(&mut cx, |this, cx| {
+                    this.project_words.insert(root, Arc::new(words));
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+    }
+
+    /// Returns the dictionaries and word lists to check words in the given project against.
+    pub fn word_list(&self, project: Option<&Entity<Project>>, cx: &App) -> WordList {
+        let mut words = vec![self.user_words.clone()];
+        if let Some(project) = project {
+            for worktree in project.read(cx).visible_worktrees(cx) {
+                if let Some(project_words) = self
+                    .project_words
+                    .get(worktree.read(cx).abs_path().as_ref())
+                {
+                    words.push(project_words.clone());
+                }
+            }
+        }
+        WordList {
+            dictionaries: self.dictionaries.clone(),
+            words,
+        }
+    }
+
+    /// Accepts `word` everywhere, or only within the worktree at `worktree_root` if given, and
+    /// appends it to the corresponding word list on disk.
+    pub fn add_word(
+        &mut self,
+        word: String,
+        worktree_root: Option<PathBuf>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let (words, path) = match worktree_root {
+            Some(root) => {
+                let path = root.join(paths::local_words_file_relative_path());
+                (self.project_words.entry(root).or_default(), path)
+            }
+            None => (&mut self.user_words, paths::words_file().clone()),
+        };
+        if !Arc::make_mut(words).insert(word.clone()) {
+            return Task::ready(Ok(()));
+        }
+        cx.notify();
+
+        let fs = self.fs.clone();
+        let write_lock = self.word_list_write_lock.clone();
+        cx.background_executor().spawn(async move {
+            let _guard = write_lock.lock().await;
+            let mut content = fs.load(&path).await.unwrap_or_default();
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(&word);
+            content.push('\n');
+            if let Some(parent) = path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            fs.atomic_write(path, content).await
+        })
+    }
+}
+
+async fn load_dictionary(
+    fs: &Arc<dyn Fs>,
+    name: &str,
+    directories: &[PathBuf],
+    cx: &AsyncApp,
+) -> Result<Dictionary> {
+    for directory in directories {
+        let aff_path = directory.join(format!("{name}.aff"));
+        let dic_path = directory.join(format!("{name}.dic"));
+        if !fs.is_file(&aff_path).await || !fs.is_file(&dic_path).await {
+            continue;
+        }
+        let aff = fs.load_bytes(&aff_path).await?;
+        let dic = fs.load_bytes(&dic_path).await?;
+        return cx
+            .background_executor()
+            .spawn(async move { Dictionary::parse(&aff, &dic) })
+            .await;
+    }
+    Err(anyhow!(
+        "no {name}.aff and {name}.dic files found in {directories:?}"
+    ))
+}
+
+/// Loads a word list with one word per line, ignoring blank lines and lines starting with `#`.
+async fn load_words(fs: &Arc<dyn Fs>, path: &Path) -> HashSet<String> {
+    fs.load(path)
+        .await
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A snapshot of the dictionaries and word lists used to check words, which can be sent to a
+/// background thread.
+#[derive(Clone)]
+pub struct WordList {
+    dictionaries: Vec<Arc<Dictionary>>,
+    words: Vec<Arc<HashSet<String>>>,
+}
+
+impl WordList {
+    /// Returns whether `word` is spelled correctly. Every word is considered correct when no
+    /// dictionaries could be loaded.
+    pub fn is_correct(&self, word: &str) -> bool {
+        if self.dictionaries.is_empty() {
+            return true;
+        }
+        let word = word.replace(words::is_apostrophe, "'");
+        let lowercase = word.to_lowercase();
+        self.words
+            .iter()
+            .any(|words| words.contains(&word) || words.contains(&lowercase))
+            || self
+                .dictionaries
+                .iter()
+                .any(|dictionary| dictionary.contains(&word))
+    }
+
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let word = word.replace(words::is_apostrophe, "'");
+        let mut suggestions = Vec::new();
+        for dictionary in &self.dictionaries {
+            for suggestion in dictionary.suggestions(&word, limit) {
+                if suggestions.len() < limit && !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        suggestions
+    }
+}
+
+/// Returns the ranges of the misspelled words in the comments, strings and prose within `range`,
+/// skipping the buffers in `skipped_buffers`.
+fn misspelled_ranges(
+    snapshot: &MultiBufferSnapshot,
+    range: Range<Point>,
+    skipped_buffers: &HashSet<BufferId>,
+    word_list: &WordList,
+) -> Vec<Range<editor::Anchor>> {
+    let mut ranges = Vec::new();
+    for (buffer, range, excerpt_id) in snapshot.range_to_buffer_ranges(range) {
+        if skipped_buffers.contains(&buffer.remote_id()) {
+            continue;
+        }
+        for text_range in buffer.spell_check_ranges(range) {
+            let text = buffer
+                .text_for_range(text_range.clone())
+                .collect::<String>();
+            for word_range in words(&text) {
+                if word_list.is_correct(&text[word_range.clone()]) {
+                    continue;
+                }
+                let start = buffer.anchor_after(text_range.start + word_range.start);
+                let end = buffer.anchor_before(text_range.start + word_range.end);
+                if let Some((start, end)) = snapshot
+                    .anchor_in_excerpt(excerpt_id, start)
+                    .zip(snapshot.anchor_in_excerpt(excerpt_id, end))
+                {
+                    ranges.push(start..end);
+                }
+            }
+        }
+    }
+    ranges
+}
+
+/// Returns the range of the multibuffer to check, which is the visible part of the editor
+/// extended by a screen above and below it, so that words are underlined as they scroll in.
+fn checked_range(editor: &Editor, snapshot: &MultiBufferSnapshot) -> Range<Point> {
+    let visible_start = editor.scroll_manager.anchor().anchor.to_point(snapshot);
+    let visible_line_count = editor.visible_line_count().unwrap_or(0.).ceil() as u32;
+    let start = Point::new(visible_start.row.saturating_sub(visible_line_count), 0);
+    let end = snapshot.clip_point(
+        Point::new(visible_start.row + 2 * visible_line_count, 0),
+        Bias::Left,
+    );
+    start..end
+}
+
+struct SpellCheckHighlight;
+
+/// Keeps an editor's misspelled words underlined as its buffers change.
+struct EditorSpellCheck {
+    editor: WeakEntity<Editor>,
+    project: WeakEntity<Project>,
+    check_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EditorSpellCheck {
+    fn new(editor: &Entity<Editor>, project: WeakEntity<Project>, cx: &mut Context<Self>) -> Self {
+        let mut subscriptions =
+            vec![
+                cx.subscribe(editor, |this, _, event: &EditorEvent, cx| match event {
+                    EditorEvent::BufferEdited
+                    | EditorEvent::Reparsed(_)
+                    | EditorEvent::ExcerptsAdded { .. }
+                    | EditorEvent::ExcerptsExpanded { .. }
+                    | EditorEvent::ScrollPositionChanged { .. } => this.schedule_check(true, cx),
+                    _ => {}
+                }),
+            ];
+        if let Some(spell_checker) = SpellChecker::global(cx) {
+            subscriptions
+                .push(cx.observe(&spell_checker, |this, _, cx| this.schedule_check(false, cx)));
+        }
+
+        let mut this = Self {
+            editor: editor.downgrade(),
+            project,
+            check_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.schedule_check(false, cx);
+        this
+    }
+
+    fn schedule_check(&mut self, debounce: bool, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let Some(spell_checker) = SpellChecker::global(cx) else {
+            return;
+        };
+        if !SpellCheckSettings::get_global(cx).enabled {
+            self.check_task = Task::ready(());
+            editor.update(cx, |editor, cx| {
+                editor.clear_highlights::<SpellCheckHighlight>(cx)
+            });
+            return;
+        }
+
+        let word_list = spell_checker
+            .read(cx)
+            .word_list(self.project.upgrade().as_ref(), cx);
+        let editor_state = editor.read(cx);
+        let multibuffer = editor_state.buffer().read(cx);
+        let snapshot = multibuffer.snapshot(cx);
+        let range = checked_range(editor_state, &snapshot);
+        let skipped_buffers = multibuffer
+            .all_buffers()
+            .into_iter()
+            .filter(|buffer| buffer.read(cx).is_large_file())
+            .map(|buffer| buffer.read(cx).remote_id())
+            .collect::<HashSet<_>>();
+        let style = HighlightStyle {
+            underline: Some(UnderlineStyle {
+                thickness: px(1.),
+                color: Some(cx.theme().status().info),
+                wavy: true,
+            }),
+            ..Default::default()
+        };
+
+        let editor = self.editor.clone();
+        self.check_task = cx.spawn(|_, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(CHECK_DEBOUNCE).await;
+            }
+            let ranges =
+                cx.background_executor()
+                    .spawn(async move {
+                        misspelled_ranges(&snapshot, range, &skipped_buffers, &word_list)
+                    })
+                    .await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.highlight_text::<SpellCheckHighlight>(ranges, style, cx)
+                })
+                .ok();
+        });
+    }
+}
+
+struct SpellCheckAddon {
+    _spell_check: Entity<EditorSpellCheck>,
+}
+
+impl Addon for SpellCheckAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum SpellCheckAction {
+    Replace {
+        replacement: String,
+    },
+    AddToUserWords {
+        word: String,
+    },
+    AddToProjectWords {
+        word: String,
+        worktree_root: PathBuf,
+    },
+}
+
+/// Offers corrections for a misspelled word under the cursor, and offers to add it to the user's
+/// or the project's word list.
+struct SpellCheckCodeActionProvider {
+    project: WeakEntity<Project>,
+}
+
+impl SpellCheckCodeActionProvider {
+    fn worktree_root(&self, buffer: &Entity<Buffer>, cx: &App) -> Option<PathBuf> {
+        let project = self.project.upgrade()?;
+        let project = project.read(cx);
+        if !project.is_local() {
+            return None;
+        }
+        let file = buffer.read(cx).file()?;
+        let worktree = project.worktree_for_id(file.worktree_id(cx), cx)?;
+        Some(worktree.read(cx).abs_path().to_path_buf())
+    }
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        CODE_ACTION_PROVIDER_ID.into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let Some(spell_checker) = SpellChecker::global(cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        if !SpellCheckSettings::get_global(cx).enabled || buffer.read(cx).is_large_file() {
+            return Task::ready(Ok(Vec::new()));
+        }
+
+        let word_list = spell_checker
+            .read(cx)
+            .word_list(self.project.upgrade().as_ref(), cx);
+        let worktree_root = self.worktree_root(buffer, cx);
+        let snapshot = buffer.read(cx).snapshot();
+        cx.background_executor().spawn(async move {
+            let range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+            let start_row = snapshot.offset_to_point(range.start).row;
+            let end_row = snapshot.offset_to_point(range.end).row;
+            let line_range = Point::new(start_row, 0).to_offset(&snapshot)
+                ..Point::new(end_row, snapshot.line_len(end_row)).to_offset(&snapshot);
+
+            let mut actions = Vec::new();
+            for text_range in snapshot.spell_check_ranges(line_range) {
+                let text = snapshot
+                    .text_for_range(text_range.clone())
+                    .collect::<String>();
+                for word_range in words(&text) {
+                    let word = &text[word_range.clone()];
+                    let word_range =
+                        text_range.start + word_range.start..text_range.start + word_range.end;
+                    if word_range.end < range.start
+                        || word_range.start > range.end
+                        || word_list.is_correct(word)
+                    {
+                        continue;
+                    }
+
+                    let word_range = snapshot.anchor_before(word_range.start)
+                        ..snapshot.anchor_after(word_range.end);
+                    for replacement in word_list.suggestions(word, MAX_SUGGESTIONS) {
+                        actions.push(code_action(
+                            word_range.clone(),
+                            format!("Change to \"{replacement}\""),
+                            SpellCheckAction::Replace { replacement },
+                        )?);
+                    }
+                    actions.push(code_action(
+                        word_range.clone(),
+                        format!("Add \"{word}\" to user words"),
+                        SpellCheckAction::AddToUserWords {
+                            word: word.to_string(),
+                        },
+                    )?);
+                    if let Some(worktree_root) = worktree_root.clone() {
+                        actions.push(code_action(
+                            word_range,
+                            format!("Add \"{word}\" to project words"),
+                            SpellCheckAction::AddToProjectWords {
+                                word: word.to_string(),
+                                worktree_root,
+                            },
+                        )?);
+                    }
+                }
+            }
+            Ok(actions)
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _excerpt_id: ExcerptId,
+        _push_to_history: bool,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let spell_check_action = action
+            .lsp_action
+            .data
+            .and_then(|data| serde_json::from_value::<SpellCheckAction>(data).ok());
+        let add_word = match spell_check_action {
+            Some(SpellCheckAction::Replace { replacement }) => {
+                buffer.update(cx, |buffer, cx| {
+                    buffer.edit([(action.range, replacement)], None, cx)
+                });
+                return Task::ready(Ok(ProjectTransaction::default()));
+            }
+            Some(SpellCheckAction::AddToUserWords { word }) => (word, None),
+            Some(SpellCheckAction::AddToProjectWords {
+                word,
+                worktree_root,
+            }) => (word, Some(worktree_root)),
+            None => return Task::ready(Err(anyhow!("invalid spell check code action"))),
+        };
+
+        let Some(spell_checker) = SpellChecker::global(cx) else {
+            return Task::ready(Err(anyhow!("spell checker is not initialized")));
+        };
+        let (word, worktree_root) = add_word;
+        let add_word = spell_checker.update(cx, |spell_checker, cx| {
+            spell_checker.add_word(word, worktree_root, cx)
+        });
+        cx.background_executor().spawn(async move {
+            add_word.await?;
+            Ok(ProjectTransaction::default())
+        })
+    }
+}
+
+fn code_action(
+    range: Range<text::Anchor>,
+    title: String,
+    action: SpellCheckAction,
+) -> Result<CodeAction> {
+    Ok(CodeAction {
+        server_id: language::LanguageServerId(0),
+        range,
+        lsp_action: lsp::CodeAction {
+            title,
+            kind: Some(lsp::CodeActionKind::QUICKFIX),
+            data: Some(serde_json::to_value(action)?),
+            ..Default::default()
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_add_words_concurrently(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            SpellCheckSettings::register(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/project", serde_json::json!({})).await;
+        let spell_checker = cx.new(|cx| SpellChecker::new(fs.clone(), cx));
+        cx.run_until_parked();
+
+        let root = PathBuf::from("/project");
+        let add_words = spell_checker.update(cx, |spell_checker, cx| {
+            ["foo", "bar", "baz"]
+                .map(|word| spell_checker.add_word(word.to_string(), Some(root.clone()), cx))
+        });
+        for add_word in add_words {
+            add_word.await.unwrap();
+        }
+
+        let content = fs
+            .load(&root.join(paths::local_words_file_relative_path()))
+            .await
+            .unwrap();
+        let mut words = content.lines().collect::<Vec<_>>();
+        words.sort();
+        assert_eq!(words, ["bar", "baz", "foo"]);
+    }
+}
@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use gpui::App;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct SpellCheckSettings {
+    pub enabled: bool,
+    pub dictionaries: Vec<String>,
+    pub dictionary_directories: Vec<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to underline misspelled words in comments, strings and prose.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The names of the Hunspell dictionaries to check words against, such as "en_US".
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+    /// Additional directories to search for `<name>.aff` and `<name>.dic` dictionary files,
+    /// before the `dictionaries` directory in Zed's config directory and the system's
+    /// Hunspell directories.
+    ///
+    /// Default: []
+    pub dictionary_directories: Option<Vec<String>>,
+}
+
+impl SpellCheckSettings {
+    /// Returns the directories to search for dictionaries, in order.
+    pub fn search_directories(&self) -> Vec<PathBuf> {
+        let mut directories = self
+            .dictionary_directories
+            .iter()
+            .map(|directory| match directory.strip_prefix("~/") {
+                Some(relative) => util::paths::home_dir().join(relative),
+                None => PathBuf::from(directory),
+            })
+            .collect::<Vec<_>>();
+        directories.push(paths::dictionaries_dir().clone());
+        if cfg!(target_os = "macos") {
+            directories.push(util::paths::home_dir().join("Library/Spelling"));
+        }
+        directories.push(PathBuf::from("/usr/share/hunspell"));
+        directories.push(PathBuf::from("/usr/share/myspell"));
+        directories
+    }
+}
+
+impl Settings for SpellCheckSettings {
+    const KEY: Option<&'static str> = Some("spell_check");
+
+    type FileContent = SpellCheckSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
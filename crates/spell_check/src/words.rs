@@ -0,0 +1,120 @@
+use std::ops::Range;
+
+/// Subwords shorter than this are never checked, as they're usually abbreviations.
+const MIN_WORD_LEN: usize = 3;
+
+/// Returns the byte ranges of the words in `text` that should be spell checked.
+///
+/// URLs and email addresses are skipped. Identifiers are split into their camelCase and
+/// snake_case parts, and parts containing digits, written in all caps (which are usually
+/// acronyms), or shorter than three characters are skipped.
+pub fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut token_start = None;
+    for (ix, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        if c.is_whitespace() {
+            if let Some(start) = token_start.take() {
+                push_token_words(text, start..ix, &mut words);
+            }
+        } else if token_start.is_none() {
+            token_start = Some(ix);
+        }
+    }
+    words
+}
+
+pub(crate) fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+fn push_token_words(text: &str, token: Range<usize>, words: &mut Vec<Range<usize>>) {
+    let token_text = &text[token.clone()];
+    if token_text.contains("://") || token_text.contains('@') {
+        return;
+    }
+
+    let chars = token_text.char_indices().collect::<Vec<_>>();
+    let is_word_char = |ix: usize| {
+        let c = chars[ix].1;
+        c.is_alphanumeric()
+            || c == '_'
+            || (is_apostrophe(c)
+                && ix > 0
+                && chars[ix - 1].1.is_alphabetic()
+                && chars
+                    .get(ix + 1)
+                    .map_or(false, |(_, next)| next.is_alphabetic()))
+    };
+
+    let mut ix = 0;
+    while ix < chars.len() {
+        if !is_word_char(ix) {
+            ix += 1;
+            continue;
+        }
+        let run_start = ix;
+        while ix < chars.len() && is_word_char(ix) {
+            ix += 1;
+        }
+        let run = &chars[run_start..ix];
+        if run.iter().any(|(_, c)| c.is_numeric()) {
+            continue;
+        }
+        for part in run.split(|(_, c)| *c == '_') {
+            push_subwords(part, token.start, words);
+        }
+    }
+}
+
+/// Splits a part of an identifier on camelCase boundaries, including after acronyms as in
+/// `HTTPServer`.
+fn push_subwords(part: &[(usize, char)], token_start: usize, words: &mut Vec<Range<usize>>) {
+    let mut start = 0;
+    for ix in 1..=part.len() {
+        let is_boundary = ix == part.len() || {
+            let (prev, c) = (part[ix - 1].1, part[ix].1);
+            let next = part.get(ix + 1).map(|(_, c)| *c);
+            c.is_uppercase()
+                && (prev.is_lowercase()
+                    || (prev.is_uppercase() && next.map_or(false, |next| next.is_lowercase())))
+        };
+        if !is_boundary {
+            continue;
+        }
+
+        let subword = &part[start..ix];
+        let is_acronym = subword.len() > 1 && subword.iter().all(|(_, c)| !c.is_lowercase());
+        if subword.len() >= MIN_WORD_LEN && !is_acronym {
+            let (last_offset, last_char) = subword[subword.len() - 1];
+            words
+                .push(token_start + subword[0].0..token_start + last_offset + last_char.len_utf8());
+        }
+        start = ix;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word_texts(text: &str) -> Vec<&str> {
+        words(text).into_iter().map(|range| &text[range]).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            word_texts("// Teh quick, brown fox; it's a test."),
+            ["Teh", "quick", "brown", "fox", "it's", "test"]
+        );
+        assert_eq!(
+            word_texts("parseHTTPServer my_snake_case 'quoted' v2beta"),
+            ["parse", "Server", "snake", "case", "quoted"]
+        );
+        assert_eq!(
+            word_texts("see https://zed.dev/docs or mail hi@zed.dev"),
+            ["see", "mail"]
+        );
+        assert_eq!(word_texts("naïve café’s"), ["naïve", "café’s"]);
+    }
+}
@@ -0,0 +1,47 @@
+# Zed's shell integration for bash, which reports prompts, commands and the working directory to
+# Zed's terminal using OSC 133 and OSC 7 escape sequences.
+#
+# Zed starts bash with `--init-file` pointing to this file, so the user's startup files are loaded
+# from here instead.
+
+if [[ -n "$ZED_SHELL_LOGIN" ]]; then
+    builtin unset ZED_SHELL_LOGIN
+    if [[ -r /etc/profile ]]; then
+        builtin source /etc/profile
+    fi
+    for __zed_profile in ~/.bash_profile ~/.bash_login ~/.profile; do
+        if [[ -r "$__zed_profile" ]]; then
+            builtin source "$__zed_profile"
+            break
+        fi
+    done
+    builtin unset __zed_profile
+elif [[ -r ~/.bashrc ]]; then
+    builtin source ~/.bashrc
+fi
+
+if [[ -z "$__zed_shell_integration" ]]; then
+    __zed_shell_integration=1
+
+    __zed_prompt_command() {
+        local exit_code=$?
+        # Zed ignores this when no command has been run since the last prompt.
+        builtin printf '\e]133;D;%s\a' "$exit_code"
+        builtin printf '\e]7;file://%s%s\a' "$HOSTNAME" "$PWD"
+        builtin printf '\e]133;A\a'
+        if [[ "$PS1" != *'133;B'* ]]; then
+            PS1="$PS1"'\[\e]133;B\a\]'
+        fi
+        return "$exit_code"
+    }
+
+    if [[ "$PS0" != *'133;C'* ]]; then
+        PS0="$PS0"'\e]133;C\a'
+    fi
+    # The hook runs before the user's prompt commands, to see the exit code of the last command.
+    if [[ "$(builtin declare -p PROMPT_COMMAND 2>/dev/null)" == "declare -a"* ]]; then
+        PROMPT_COMMAND=(__zed_prompt_command "${PROMPT_COMMAND[@]}")
+    else
+        PROMPT_COMMAND="__zed_prompt_command${PROMPT_COMMAND:+; $PROMPT_COMMAND}"
+    fi
+fi
@@ -0,0 +1,28 @@
+# Zed's shell integration for fish, which reports prompts, commands and the working directory to
+# Zed's terminal using OSC 133 and OSC 7 escape sequences.
+#
+# Zed adds this file's data directory to XDG_DATA_DIRS so that fish loads it. It's removed again
+# here, so that programs started from the shell don't inherit it.
+
+if set -q ZED_SHELL_INTEGRATION_DIR
+    set --path XDG_DATA_DIRS (string match --invert -- $ZED_SHELL_INTEGRATION_DIR $XDG_DATA_DIRS)
+    set -q XDG_DATA_DIRS[1]; or set -e XDG_DATA_DIRS
+    set -e ZED_SHELL_INTEGRATION_DIR
+end
+
+status is-interactive; or exit
+set -q __zed_shell_integration; and exit
+set -g __zed_shell_integration 1
+
+function __zed_prompt_start --on-event fish_prompt
+    printf '\e]7;file://%s%s\a' $hostname $PWD
+    printf '\e]133;A\a'
+end
+
+function __zed_preexec --on-event fish_preexec
+    printf '\e]133;C\a'
+end
+
+function __zed_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end
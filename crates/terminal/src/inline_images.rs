@@ -0,0 +1,507 @@
+//! Inline images, which programs show with iTerm2's inline images protocol:
+//! `OSC 1337 ; File = [arguments] : base64 data ST`.
+//!
+//! Alacritty ignores these sequences, so they're found in the PTY's output by
+//! [`ShellIntegrationPty`](crate::shell_integration::ShellIntegrationPty), which records each image
+//! at the cursor position it was written at and moves the cursor past it. Only the size of an
+//! image is read while the output is parsed, and the image is decoded in the background.
+
+use std::{collections::VecDeque, io::Cursor, sync::Arc};
+
+use alacritty_terminal::index::{Column, Line, Point as AlacPoint};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use gpui::{px, size, Pixels, RenderImage, Size};
+use util::ResultExt as _;
+
+/// The start of the payload of the OSC sequences that show inline images.
+pub(crate) const INLINE_IMAGE_PREFIX: &[u8] = b"1337;File=";
+/// Inline image sequences longer than this are ignored.
+pub(crate) const MAX_INLINE_IMAGE_LEN: usize = 32 * 1024 * 1024;
+/// The most inline images that are kept in a terminal's output.
+const MAX_INLINE_IMAGES: usize = 256;
+/// The most bytes of decoded inline images that are kept in a terminal's output.
+const MAX_DECODED_INLINE_IMAGES_LEN: usize = 256 * 1024 * 1024;
+
+/// The width or height an inline image is requested to be shown at.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Dimension {
+    /// The image's own size.
+    #[default]
+    Auto,
+    Cells(f32),
+    Pixels(f32),
+    /// A percentage of the terminal's size.
+    Percent(f32),
+}
+
+impl Dimension {
+    fn parse(value: &str) -> Option<Self> {
+        if value == "auto" {
+            Some(Self::Auto)
+        } else if let Some(pixels) = value.strip_suffix("px") {
+            pixels.parse().ok().map(Self::Pixels)
+        } else if let Some(percent) = value.strip_suffix('%') {
+            percent.parse().ok().map(Self::Percent)
+        } else {
+            value.parse().ok().map(Self::Cells)
+        }
+    }
+
+    /// Returns the dimension in pixels, given the size of a cell and of the terminal along it.
+    fn to_pixels(self, cell: f32, terminal: f32) -> Option<f32> {
+        match self {
+            Self::Auto => None,
+            Self::Cells(cells) => Some(cells * cell),
+            Self::Pixels(pixels) => Some(pixels),
+            Self::Percent(percent) => Some(terminal * percent / 100.),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ImageArguments {
+    /// Whether the file is shown in the terminal, rather than downloaded.
+    inline: bool,
+    width: Dimension,
+    height: Dimension,
+    preserve_aspect_ratio: bool,
+    move_cursor: bool,
+}
+
+impl Default for ImageArguments {
+    fn default() -> Self {
+        Self {
+            inline: false,
+            width: Dimension::Auto,
+            height: Dimension::Auto,
+            preserve_aspect_ratio: true,
+            move_cursor: true,
+        }
+    }
+}
+
+impl ImageArguments {
+    /// Parses the `key=value` arguments of an inline image sequence, which are separated by `;`.
+    fn parse(arguments: &str) -> Option<Self> {
+        let mut parsed = Self::default();
+        for argument in arguments.split(';') {
+            let Some((key, value)) = argument.split_once('=') else {
+                continue;
+            };
+            match key {
+                "inline" => parsed.inline = value == "1",
+                "width" => parsed.width = Dimension::parse(value)?,
+                "height" => parsed.height = Dimension::parse(value)?,
+                "preserveAspectRatio" => parsed.preserve_aspect_ratio = value != "0",
+                "doNotMoveCursor" => parsed.move_cursor = value != "1",
+                _ => {}
+            }
+        }
+        Some(parsed)
+    }
+
+    /// Returns the size an image is shown at in pixels, given its own size, the size of a cell, and
+    /// the number of columns and lines in the terminal. Images are shrunk to fit the terminal's
+    /// width.
+    fn image_size(
+        &self,
+        image_size: Size<f32>,
+        cell_size: Size<f32>,
+        columns: usize,
+        lines: usize,
+    ) -> Size<f32> {
+        let terminal_width = cell_size.width * columns as f32;
+        let terminal_height = cell_size.height * lines as f32;
+        let width = self.width.to_pixels(cell_size.width, terminal_width);
+        let height = self.height.to_pixels(cell_size.height, terminal_height);
+        let scaled = |scale: f32| size(image_size.width * scale, image_size.height * scale);
+
+        let image_size = match (width, height) {
+            (None, None) => image_size,
+            (Some(width), None) if self.preserve_aspect_ratio => scaled(width / image_size.width),
+            (Some(width), None) => size(width, image_size.height),
+            (None, Some(height)) if self.preserve_aspect_ratio => {
+                scaled(height / image_size.height)
+            }
+            (None, Some(height)) => size(image_size.width, height),
+            (Some(width), Some(height)) if self.preserve_aspect_ratio => {
+                scaled((width / image_size.width).min(height / image_size.height))
+            }
+            (Some(width), Some(height)) => size(width, height),
+        };
+        if image_size.width > terminal_width {
+            let scale = terminal_width / image_size.width;
+            size(terminal_width, image_size.height * scale)
+        } else {
+            image_size
+        }
+    }
+}
+
+/// An inline image sequence, with its image still encoded.
+pub(crate) struct InlineImageSequence {
+    /// The image file.
+    bytes: Vec<u8>,
+    /// The image's own size in pixels.
+    image_size: Size<u32>,
+    arguments: ImageArguments,
+}
+
+impl InlineImageSequence {
+    /// Parses the payload of an inline image sequence and reads the size of its image. Returns
+    /// `None` for files that aren't shown inline and images whose size can't be read.
+    pub(crate) fn parse(payload: &[u8]) -> Option<Self> {
+        let payload = payload.strip_prefix(INLINE_IMAGE_PREFIX)?;
+        let separator = payload.iter().position(|byte| *byte == b':')?;
+        let arguments = ImageArguments::parse(std::str::from_utf8(&payload[..separator]).ok()?)?;
+        if !arguments.inline {
+            return None;
+        }
+
+        let data = payload[separator + 1..]
+            .iter()
+            .copied()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .collect::<Vec<_>>();
+        let bytes = STANDARD.decode(data).log_err()?;
+        let (width, height) = image::ImageReader::new(Cursor::new(&bytes))
+            .with_guessed_format()
+            .log_err()?
+            .into_dimensions()
+            .log_err()?;
+        if width == 0 || height == 0 {
+            return None;
+        }
+        Some(Self {
+            bytes,
+            image_size: size(width, height),
+            arguments,
+        })
+    }
+
+    /// Decodes the image into BGRA format, which is slow for large images, so it's done in the
+    /// background.
+    pub(crate) fn decode(self) -> Option<Arc<RenderImage>> {
+        let mut image = image::load_from_memory(&self.bytes).log_err()?.into_rgba8();
+        // Convert from RGBA to BGRA.
+        for pixel in image.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        Some(Arc::new(RenderImage::new(vec![image::Frame::new(image)])))
+    }
+
+    /// The number of bytes the image takes once it's decoded.
+    fn decoded_len(&self) -> usize {
+        self.image_size.width as usize * self.image_size.height as usize * 4
+    }
+
+    /// Returns the size the image is shown at, given the size of a cell and the number of columns
+    /// and lines in the terminal.
+    pub(crate) fn size(&self, cell_size: Size<f32>, columns: usize, lines: usize) -> Size<f32> {
+        let image_size = size(self.image_size.width as f32, self.image_size.height as f32);
+        self.arguments
+            .image_size(image_size, cell_size, columns, lines)
+    }
+
+    /// Whether the cursor is moved past the image, so that the next output doesn't cover it.
+    pub(crate) fn move_cursor(&self) -> bool {
+        self.arguments.move_cursor
+    }
+}
+
+/// An image shown in the terminal's output.
+#[derive(Clone, Debug)]
+pub struct TerminalImage {
+    pub image: Arc<RenderImage>,
+    /// The cell at the image's top left corner.
+    pub point: AlacPoint,
+    pub size: Size<Pixels>,
+}
+
+struct StoredImage {
+    id: usize,
+    /// The decoded image, which is `None` until it's been decoded in the background.
+    image: Option<Arc<RenderImage>>,
+    decoded_len: usize,
+    /// The line of the image's top, counted from the first line the terminal printed.
+    line: usize,
+    column: Column,
+    lines: usize,
+    size: Size<Pixels>,
+}
+
+/// The images shown in a terminal's output, which scroll with it.
+#[derive(Default)]
+pub(crate) struct InlineImages {
+    /// How many lines have scrolled off the top of the screen.
+    pub(crate) scrolled_lines: usize,
+    images: VecDeque<StoredImage>,
+    /// The number of bytes taken by the images once they're decoded.
+    decoded_len: usize,
+    next_image_id: usize,
+    removed_images: Vec<Arc<RenderImage>>,
+}
+
+impl InlineImages {
+    /// Adds an image shown with its top left corner at `point`, which covers `lines` lines, and
+    /// returns its id to set the image with once it's decoded. Older images are removed to make
+    /// room for it, and `None` is returned for images that would take too much memory.
+    pub(crate) fn add(
+        &mut self,
+        sequence: &InlineImageSequence,
+        point: AlacPoint,
+        size: Size<f32>,
+        lines: usize,
+    ) -> Option<usize> {
+        let decoded_len = sequence.decoded_len();
+        if decoded_len > MAX_DECODED_INLINE_IMAGES_LEN {
+            return None;
+        }
+        while self.images.len() >= MAX_INLINE_IMAGES
+            || self.decoded_len + decoded_len > MAX_DECODED_INLINE_IMAGES_LEN
+        {
+            self.remove_first();
+        }
+
+        let id = self.next_image_id;
+        self.next_image_id += 1;
+        self.decoded_len += decoded_len;
+        self.images.push_back(StoredImage {
+            id,
+            image: None,
+            decoded_len,
+            line: (self.scrolled_lines as i64 + point.line.0 as i64).max(0) as usize,
+            column: point.column,
+            lines,
+            size: gpui::size(px(size.width), px(size.height)),
+        });
+        Some(id)
+    }
+
+    /// Sets the decoded image of an image that was added, unless it's been removed since.
+    pub(crate) fn set_image(&mut self, id: usize, image: Arc<RenderImage>) {
+        if let Some(stored_image) = self.images.iter_mut().find(|image| image.id == id) {
+            stored_image.image = Some(image);
+        }
+    }
+
+    fn remove_first(&mut self) {
+        if let Some(image) = self.images.pop_front() {
+            self.decoded_len -= image.decoded_len;
+            self.removed_images.extend(image.image);
+        }
+    }
+
+    /// Forgets the images that have scrolled out of the terminal's history.
+    pub(crate) fn prune(&mut self, history_size: usize) {
+        let topmost_line = self.scrolled_lines.saturating_sub(history_size);
+        while self
+            .images
+            .front()
+            .map_or(false, |image| image.line + image.lines <= topmost_line)
+        {
+            self.remove_first();
+        }
+    }
+
+    /// Forgets all images, as the terminal's lines have been cleared.
+    pub(crate) fn clear(&mut self) {
+        self.decoded_len = 0;
+        self.removed_images
+            .extend(self.images.drain(..).filter_map(|image| image.image));
+    }
+
+    /// Returns the images that are at least partly within the given grid lines.
+    pub(crate) fn images_within(&self, lines: std::ops::Range<i32>) -> Vec<TerminalImage> {
+        self.images
+            .iter()
+            .filter_map(|image| {
+                let line = image.line as i64 - self.scrolled_lines as i64;
+                if line >= lines.end as i64 || line + image.lines as i64 <= lines.start as i64 {
+                    return None;
+                }
+                Some(TerminalImage {
+                    image: image.image.clone()?,
+                    point: AlacPoint::new(Line(line as i32), image.column),
+                    size: image.size,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the images that have been removed since this was last called, so that they can be
+    /// dropped from the window they were painted in.
+    pub(crate) fn take_removed_images(&mut self) -> Vec<Arc<RenderImage>> {
+        std::mem::take(&mut self.removed_images)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_arguments() {
+        assert_eq!(
+            ImageArguments::parse("name=cGxvdC5wbmc=;size=1024;inline=1;width=50%;height=10px"),
+            Some(ImageArguments {
+                inline: true,
+                width: Dimension::Percent(50.),
+                height: Dimension::Pixels(10.),
+                ..ImageArguments::default()
+            })
+        );
+        assert_eq!(
+            ImageArguments::parse("inline=1;width=8;preserveAspectRatio=0;doNotMoveCursor=1"),
+            Some(ImageArguments {
+                inline: true,
+                width: Dimension::Cells(8.),
+                preserve_aspect_ratio: false,
+                move_cursor: false,
+                ..ImageArguments::default()
+            })
+        );
+        assert_eq!(ImageArguments::parse("inline=1;width=wide"), None);
+    }
+
+    #[test]
+    fn test_image_size() {
+        let image_size = size(200., 100.);
+        let cell_size = size(10., 20.);
+        let arguments = |arguments: &str| ImageArguments::parse(arguments).unwrap();
+
+        assert_eq!(
+            arguments("").image_size(image_size, cell_size, 80, 24),
+            size(200., 100.)
+        );
+        assert_eq!(
+            arguments("width=10").image_size(image_size, cell_size, 80, 24),
+            size(100., 50.)
+        );
+        assert_eq!(
+            arguments("width=50%").image_size(image_size, cell_size, 80, 24),
+            size(400., 200.)
+        );
+        assert_eq!(
+            arguments("width=100px;height=100px").image_size(image_size, cell_size, 80, 24),
+            size(100., 50.)
+        );
+        assert_eq!(
+            arguments("width=100px;height=100px;preserveAspectRatio=0")
+                .image_size(image_size, cell_size, 80, 24),
+            size(100., 100.)
+        );
+        // Images are shrunk to fit the terminal's width.
+        assert_eq!(
+            arguments("").image_size(image_size, cell_size, 10, 24),
+            size(100., 50.)
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_image() {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(3, 2, image::Rgba([255, 0, 0, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let payload = format!("1337;File=inline=1:{}", STANDARD.encode(&png));
+
+        let sequence = InlineImageSequence::parse(payload.as_bytes()).unwrap();
+        assert_eq!(sequence.size(size(10., 20.), 80, 24), size(3., 2.));
+        assert_eq!(sequence.decoded_len(), 3 * 2 * 4);
+        let image = sequence.decode().unwrap();
+        assert_eq!(image.as_bytes(0).unwrap()[..4], [0, 0, 255, 255]);
+
+        let payload = format!("1337;File=name=cGxvdC5wbmc=:{}", STANDARD.encode(&png));
+        assert!(InlineImageSequence::parse(payload.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_inline_images_scroll() {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(1, 1, image::Rgba([0, 0, 0, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let payload = format!("1337;File=inline=1:{}", STANDARD.encode(&png));
+        let sequence = || InlineImageSequence::parse(payload.as_bytes()).unwrap();
+        let point = |line, column| AlacPoint::new(Line(line), Column(column));
+        let mut inline_images = InlineImages::default();
+
+        let id = inline_images
+            .add(&sequence(), point(2, 4), size(30., 40.), 2)
+            .unwrap();
+        // Images are only shown once they've been decoded.
+        assert!(inline_images.images_within(0..24).is_empty());
+        inline_images.set_image(id, sequence().decode().unwrap());
+        let images = inline_images.images_within(0..24);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].point, point(2, 4));
+        assert_eq!(images[0].size, size(px(30.), px(40.)));
+
+        // Images keep their place in the output as it scrolls.
+        inline_images.scrolled_lines += 3;
+        assert_eq!(inline_images.images_within(0..24)[0].point, point(-1, 4));
+        assert!(inline_images.images_within(1..25).is_empty());
+
+        // Images are forgotten once they've scrolled out of the history.
+        inline_images.prune(2);
+        assert_eq!(inline_images.images_within(-2..24).len(), 1);
+        inline_images.scrolled_lines += 3;
+        inline_images.prune(2);
+        assert!(inline_images.images_within(-2..24).is_empty());
+        assert_eq!(inline_images.take_removed_images().len(), 1);
+        assert!(inline_images.take_removed_images().is_empty());
+    }
+
+    #[test]
+    fn test_inline_images_memory_limit() {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(1, 1, image::Rgba([0, 0, 0, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let payload = format!("1337;File=inline=1:{}", STANDARD.encode(&png));
+        let decoded_image = || {
+            InlineImageSequence::parse(payload.as_bytes())
+                .unwrap()
+                .decode()
+                .unwrap()
+        };
+        // Each of these images takes a quarter of the memory limit once decoded.
+        let side = ((MAX_DECODED_INLINE_IMAGES_LEN / 16) as f64).sqrt() as u32;
+        let sequence = |side| InlineImageSequence {
+            bytes: Vec::new(),
+            image_size: size(side, side),
+            arguments: ImageArguments::default(),
+        };
+        let point = AlacPoint::new(Line(0), Column(0));
+        let mut inline_images = InlineImages::default();
+
+        let mut ids = Vec::new();
+        for _ in 0..5 {
+            let id = inline_images
+                .add(&sequence(side), point, size(10., 10.), 1)
+                .unwrap();
+            inline_images.set_image(id, decoded_image());
+            ids.push(id);
+        }
+        // The first image was removed to make room for the fifth.
+        assert_eq!(inline_images.images_within(0..24).len(), 4);
+        assert_eq!(inline_images.take_removed_images().len(), 1);
+        assert_eq!(inline_images.decoded_len, MAX_DECODED_INLINE_IMAGES_LEN);
+
+        // Setting the image of a removed image does nothing.
+        inline_images.set_image(ids[0], decoded_image());
+        assert_eq!(inline_images.images_within(0..24).len(), 4);
+
+        // Images that take more than the limit on their own aren't kept.
+        assert_eq!(
+            inline_images.add(&sequence(side * 3), point, size(10., 10.), 1),
+            None
+        );
+        assert_eq!(inline_images.images_within(0..24).len(), 4);
+
+        inline_images.clear();
+        assert_eq!(inline_images.decoded_len, 0);
+        assert_eq!(inline_images.take_removed_images().len(), 4);
+    }
+}
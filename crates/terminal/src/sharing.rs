@@ -0,0 +1,356 @@
+//! Sharing terminals with collaborators.
+//!
+//! The host mirrors the output of a terminal's PTY through a [`TerminalMirror`], and collaborators
+//! parse that output into terminals of their own, which aren't connected to a PTY. Collaborators
+//! who open a terminal after it's been shared start from a [`TerminalSnapshot`] of it, which
+//! redraws the host's terminal with escape sequences.
+
+use std::{fmt::Write as _, sync::Arc};
+
+use alacritty_terminal::{
+    grid::Dimensions,
+    index::{Column, Line},
+    sync::FairMutex,
+    term::{
+        cell::{Cell, Flags},
+        TermMode,
+    },
+    vte::ansi::{Color, NamedColor},
+    Term,
+};
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    StreamExt as _,
+};
+use parking_lot::Mutex;
+
+use crate::ZedListener;
+
+/// Private modes that change what the terminal sends as input, which collaborators need to know
+/// about to type into the terminal.
+const INPUT_MODES: &[(TermMode, u16)] = &[
+    (TermMode::APP_CURSOR, 1),
+    (TermMode::MOUSE_REPORT_CLICK, 1000),
+    (TermMode::MOUSE_DRAG, 1002),
+    (TermMode::MOUSE_MOTION, 1003),
+    (TermMode::FOCUS_IN_OUT, 1004),
+    (TermMode::UTF8_MOUSE, 1005),
+    (TermMode::SGR_MOUSE, 1006),
+    (TermMode::BRACKETED_PASTE, 2004),
+];
+
+/// The cell flags that SGR sequences set, and their parameters.
+const STYLE_FLAGS: &[(Flags, u8)] = &[
+    (Flags::BOLD, 1),
+    (Flags::DIM, 2),
+    (Flags::ITALIC, 3),
+    (Flags::ALL_UNDERLINES, 4),
+    (Flags::INVERSE, 7),
+    (Flags::HIDDEN, 8),
+    (Flags::STRIKEOUT, 9),
+];
+
+/// Output that a shared terminal's PTY wrote, along with the size of the terminal it was
+/// written to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedOutput {
+    pub bytes: Vec<u8>,
+    pub columns: usize,
+    pub lines: usize,
+}
+
+/// The size of a shared terminal's grid, which collaborators' terminals keep regardless of the
+/// size they're shown at, so that the host's output is laid out the same way for everyone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SharedTerminalSize {
+    pub(crate) columns: usize,
+    pub(crate) lines: usize,
+}
+
+impl Dimensions for SharedTerminalSize {
+    fn total_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+/// A terminal's scrollback and screen, written as the text and escape sequences that redraw it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TerminalSnapshot {
+    pub columns: usize,
+    pub lines: usize,
+    pub content: String,
+}
+
+impl TerminalSnapshot {
+    pub(crate) fn new(term: &Term<ZedListener>) -> Self {
+        let mut content = String::new();
+        let mode = *term.mode();
+        // Programs redraw the alternate screen themselves, so only its contents are sent.
+        if mode.contains(TermMode::ALT_SCREEN) {
+            content.push_str("\x1b[?1049h");
+        }
+
+        let grid = term.grid();
+        let mut style = CellStyle::default();
+        for line in term.topmost_line().0..=term.bottommost_line().0 {
+            let row = &grid[Line(line)];
+            // The rest of the line is already blank in a new terminal.
+            let line_length = row.line_length();
+            for column in 0..line_length.0 {
+                let cell = &row[Column(column)];
+                if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                    continue;
+                }
+                let cell_style = CellStyle::new(cell);
+                if cell_style != style {
+                    cell_style.write_sgr(&mut content);
+                    style = cell_style;
+                }
+                content.push(cell.c);
+                if let Some(zerowidth) = cell.zerowidth() {
+                    content.extend(zerowidth);
+                }
+            }
+
+            if line < term.bottommost_line().0 {
+                // Wrapped lines fill the terminal's width, so the next line continues on its own.
+                let wraps = line_length.0 == term.columns()
+                    && row[term.last_column()].flags.contains(Flags::WRAPLINE);
+                if !wraps {
+                    content.push_str("\r\n");
+                }
+            }
+        }
+        if style != CellStyle::default() {
+            content.push_str("\x1b[0m");
+        }
+
+        let cursor = grid.cursor.point;
+        write!(
+            content,
+            "\x1b[{};{}H",
+            cursor.line.0 + 1,
+            cursor.column.0 + 1
+        )
+        .ok();
+        for (input_mode, code) in INPUT_MODES {
+            if mode.contains(*input_mode) {
+                write!(content, "\x1b[?{code}h").ok();
+            }
+        }
+        if !mode.contains(TermMode::SHOW_CURSOR) {
+            content.push_str("\x1b[?25l");
+        }
+
+        Self {
+            columns: term.columns(),
+            lines: term.screen_lines(),
+            content,
+        }
+    }
+}
+
+/// The colors and flags of a cell that its escape sequences set.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CellStyle {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for CellStyle {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl CellStyle {
+    fn new(cell: &Cell) -> Self {
+        let flags = STYLE_FLAGS
+            .iter()
+            .filter(|(flag, _)| cell.flags.intersects(*flag))
+            .fold(Flags::empty(), |flags, (flag, _)| flags | *flag);
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags,
+        }
+    }
+
+    /// Writes the SGR sequence that resets the style to this one.
+    fn write_sgr(&self, output: &mut String) {
+        output.push_str("\x1b[0");
+        for (flag, code) in STYLE_FLAGS {
+            if self.flags.intersects(*flag) {
+                write!(output, ";{code}").ok();
+            }
+        }
+        write_color(output, self.fg, 30);
+        write_color(output, self.bg, 40);
+        output.push('m');
+    }
+}
+
+/// Writes the SGR parameters for a foreground (base 30) or background (base 40) color.
+fn write_color(output: &mut String, color: Color, base: u8) {
+    match color {
+        Color::Named(name) => {
+            let index = name as usize;
+            if index < 8 {
+                write!(output, ";{}", base as usize + index).ok();
+            } else if index < 16 {
+                write!(output, ";{}", base as usize + 60 + index - 8).ok();
+            }
+            // Other named colors are the default colors, which the reset already set.
+        }
+        Color::Indexed(index) => {
+            write!(output, ";{};5;{index}", base + 8).ok();
+        }
+        Color::Spec(rgb) => {
+            write!(output, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b).ok();
+        }
+    }
+}
+
+/// Receives the output of a shared terminal's PTY, which stops being mirrored when this is
+/// dropped.
+pub struct TerminalMirror {
+    term: Arc<FairMutex<Term<ZedListener>>>,
+    output_mirror: Arc<Mutex<Option<UnboundedSender<SharedOutput>>>>,
+    output_rx: UnboundedReceiver<SharedOutput>,
+}
+
+impl TerminalMirror {
+    pub(crate) fn new(
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        output_mirror: Arc<Mutex<Option<UnboundedSender<SharedOutput>>>>,
+    ) -> Self {
+        let (output_tx, output_rx) = unbounded();
+        *output_mirror.lock() = Some(output_tx);
+        Self {
+            term,
+            output_mirror,
+            output_rx,
+        }
+    }
+
+    /// Waits for the PTY's next output, along with any other output that's ready.
+    pub async fn next_output(&mut self) -> Option<SharedOutput> {
+        let output = self.output_rx.next().await?;
+        Some(self.take_ready_output(output))
+    }
+
+    /// Returns a snapshot of the terminal, along with output that's in the snapshot but that
+    /// [`Self::next_output`] hasn't returned yet, which must be sent before the snapshot.
+    pub fn snapshot(&mut self) -> (Option<SharedOutput>, TerminalSnapshot) {
+        // The PTY's output is mirrored while the terminal is locked, so everything that's been
+        // mirrored so far is in the snapshot.
+        let term = self.term.clone();
+        let term = term.lock_unfair();
+        let output = self
+            .output_rx
+            .try_next()
+            .ok()
+            .flatten()
+            .map(|output| self.take_ready_output(output));
+        (output, TerminalSnapshot::new(&term))
+    }
+
+    fn take_ready_output(&mut self, mut output: SharedOutput) -> SharedOutput {
+        while let Ok(Some(next_output)) = self.output_rx.try_next() {
+            output.bytes.extend(next_output.bytes);
+            output.columns = next_output.columns;
+            output.lines = next_output.lines;
+        }
+        output
+    }
+}
+
+impl Drop for TerminalMirror {
+    fn drop(&mut self) {
+        let mut output_mirror = self.output_mirror.lock();
+        if output_mirror.as_ref().map_or(false, |output_tx| {
+            output_tx.is_connected_to(&self.output_rx)
+        }) {
+            *output_mirror = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        grid::Dimensions,
+        index::{Column, Line, Point as AlacPoint},
+        term::{cell::Flags, Config, TermMode},
+        vte::ansi::{Color, NamedColor, Processor, Rgb},
+        Term,
+    };
+    use futures::channel::mpsc::unbounded;
+
+    use super::{SharedTerminalSize, TerminalSnapshot};
+    use crate::ZedListener;
+
+    fn term(columns: usize, lines: usize) -> Term<ZedListener> {
+        let (events_tx, _events_rx) = unbounded();
+        Term::new(
+            Config::default(),
+            &SharedTerminalSize { columns, lines },
+            ZedListener::new(events_tx),
+        )
+    }
+
+    fn text(term: &Term<ZedListener>) -> String {
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        term.bounds_to_string(start, end)
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut host = term(10, 3);
+        let mut parser: Processor = Processor::new();
+        parser.advance(
+            &mut host,
+            b"$ ls\r\n\x1b[1;31mtarget\x1b[0m \x1b[38;2;1;2;3msrc\x1b[0m\r\nwrapped line of text\r\n$ \x1b[?2004h",
+        );
+
+        let snapshot = TerminalSnapshot::new(&host);
+        assert_eq!((snapshot.columns, snapshot.lines), (10, 3));
+
+        let mut guest = term(10, 3);
+        parser.advance(&mut guest, snapshot.content.as_bytes());
+        assert_eq!(text(&guest), text(&host));
+        assert_eq!(guest.history_size(), host.history_size());
+        assert_eq!(guest.grid().cursor.point, host.grid().cursor.point);
+        assert_eq!(
+            guest.grid().cursor.point,
+            AlacPoint::new(Line(2), Column(2))
+        );
+        assert!(guest.mode().contains(TermMode::BRACKETED_PASTE));
+
+        let target = &guest.grid()[Line(-1)][Column(0)];
+        assert_eq!(target.c, 't');
+        assert_eq!(target.fg, Color::Named(NamedColor::Red));
+        assert!(target.flags.contains(Flags::BOLD));
+        let src = &guest.grid()[Line(-1)][Column(7)];
+        assert_eq!(src.c, 's');
+        assert_eq!(src.fg, Color::Spec(Rgb { r: 1, g: 2, b: 3 }));
+        assert!(!src.flags.contains(Flags::BOLD));
+        assert!(guest.grid()[Line(0)][guest.last_column()]
+            .flags
+            .contains(Flags::WRAPLINE));
+    }
+}
@@ -0,0 +1,1081 @@
+//! Shell integration, through which shells report their prompts, commands and working directory.
+//!
+//! Shells report these with OSC 133 (FinalTerm) and OSC 7 escape sequences, which Alacritty
+//! ignores. So [`ShellIntegrationPty`] wraps the PTY that's given to Alacritty's event loop and
+//! passes its output through unchanged, while following it on a terminal of its own to find where
+//! the cursor was when each sequence was written. What it finds is applied once the event loop has
+//! parsed the output into the terminal. It handles [inline images](crate::inline_images) the same
+//! way, whether or not shell integration is enabled, and mirrors the output of
+//! [shared terminals](crate::sharing) to collaborators. Zed's integration scripts for bash, zsh and
+//! fish are added to new shells by [`inject_shell_integration`].
+
+use std::{
+    cmp,
+    collections::VecDeque,
+    fmt::Write as _,
+    io::{self, Read as _},
+    mem,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use alacritty_terminal::{
+    event::{Event as AlacTermEvent, EventListener, OnResize, VoidListener, WindowSize},
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::{Config, TermMode},
+    tty::{ChildEvent, EventedPty, EventedReadWrite, Pty},
+    vte::ansi::Processor,
+    Term,
+};
+use collections::HashMap;
+use futures::channel::mpsc::UnboundedSender;
+use gpui::{size, BackgroundExecutor, Size};
+use parking_lot::Mutex;
+use polling::{Event as PollingEvent, PollMode, Poller};
+use task::Shell;
+use util::ResultExt;
+
+use crate::{
+    inline_images::{InlineImageSequence, InlineImages, INLINE_IMAGE_PREFIX, MAX_INLINE_IMAGE_LEN},
+    sharing::{SharedOutput, SharedTerminalSize},
+    TerminalSize, ZedListener,
+};
+
+/// OSC sequences longer than this are never shell marks.
+const MAX_OSC_LEN: usize = 4096;
+/// How many lines the [`OutputTracker`]'s terminal can keep in its history, which it clears after
+/// each chunk of output it parses.
+const TRACKED_HISTORY_LINES: usize = 10_000;
+/// The most line feeds in a chunk of output parsed by the [`OutputTracker`], so that the lines the
+/// chunk scrolls fit in the tracker's history.
+const MAX_LINE_FEEDS_PER_CHUNK: usize = 1_000;
+
+const SCRIPTS: &[(&str, &str)] = &[
+    ("zed.bash", include_str!("../shell_integration/zed.bash")),
+    (
+        "zsh/.zshenv",
+        include_str!("../shell_integration/zshenv.zsh"),
+    ),
+    ("zsh/zed.zsh", include_str!("../shell_integration/zed.zsh")),
+    (
+        "fish/vendor_conf.d/zed.fish",
+        include_str!("../shell_integration/zed.fish"),
+    ),
+];
+
+/// A sequence through which a shell reports what it's doing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ShellMark {
+    /// `OSC 133 ; A`, written before the prompt.
+    PromptStart,
+    /// `OSC 133 ; B`, written at the end of the prompt, where the command is typed.
+    CommandStart,
+    /// `OSC 133 ; C`, written when the command starts running.
+    OutputStart,
+    /// `OSC 133 ; D [; exit code]`, written when the command has finished.
+    CommandFinished(Option<i32>),
+    /// `OSC 7 ; file://host/path`, written when the working directory changes.
+    WorkingDirectory(PathBuf),
+}
+
+impl ShellMark {
+    fn parse(payload: &[u8]) -> Option<Self> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        if let Some(params) = payload.strip_prefix("133;") {
+            let mut params = params.split(';');
+            match params.next()? {
+                "A" => Some(Self::PromptStart),
+                "B" => Some(Self::CommandStart),
+                "C" => Some(Self::OutputStart),
+                "D" => Some(Self::CommandFinished(
+                    params.next().and_then(|exit_code| exit_code.parse().ok()),
+                )),
+                _ => None,
+            }
+        } else if let Some(url) = payload.strip_prefix("7;") {
+            parse_file_url(url).map(Self::WorkingDirectory)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parses the `file://host/path` URL reported by OSC 7, ignoring directories on other hosts, such
+/// as those reported by shells running over SSH.
+fn parse_file_url(url: &str) -> Option<PathBuf> {
+    let url = url.strip_prefix("file://")?;
+    let (host, path) = url.split_at(url.find('/')?);
+    let is_local_host = host.is_empty()
+        || host == "localhost"
+        || local_hostname().map_or(true, |hostname| hostname.eq_ignore_ascii_case(host));
+    if !is_local_host {
+        return None;
+    }
+
+    let mut path = percent_decode(path);
+    // Windows paths are reported as `file:///C:/...`.
+    if cfg!(windows) && path.as_bytes().get(2) == Some(&b':') {
+        path.remove(0);
+    }
+    Some(PathBuf::from(path))
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if bytes[ix] == b'%' {
+            let byte = text
+                .get(ix + 1..ix + 3)
+                .filter(|hex| hex.bytes().all(|byte| byte.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = byte {
+                decoded.push(byte);
+                ix += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[ix]);
+        ix += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(unix)]
+fn local_hostname() -> Option<&'static str> {
+    static HOSTNAME: OnceLock<Option<String>> = OnceLock::new();
+    HOSTNAME
+        .get_or_init(|| {
+            let mut buffer = [0u8; 256];
+            let result = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+            if result != 0 {
+                return None;
+            }
+            let len = buffer.iter().position(|byte| *byte == 0)?;
+            String::from_utf8(buffer[..len].to_vec()).ok()
+        })
+        .as_deref()
+}
+
+#[cfg(not(unix))]
+fn local_hostname() -> Option<&'static str> {
+    None
+}
+
+/// An OSC sequence that Zed handles itself, as Alacritty ignores it.
+enum OscSequence {
+    ShellMark(ShellMark),
+    InlineImage(InlineImageSequence),
+}
+
+impl OscSequence {
+    fn parse(payload: &[u8]) -> Option<Self> {
+        if payload.starts_with(INLINE_IMAGE_PREFIX) {
+            InlineImageSequence::parse(payload).map(Self::InlineImage)
+        } else {
+            ShellMark::parse(payload).map(Self::ShellMark)
+        }
+    }
+}
+
+/// Finds the shell marks and inline images in a PTY's output, which may be split across reads.
+#[derive(Default)]
+struct OscScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+impl OscScanner {
+    /// OSC sequences longer than this are ignored.
+    fn max_payload_len(&self) -> usize {
+        if self.payload.starts_with(INLINE_IMAGE_PREFIX) {
+            MAX_INLINE_IMAGE_LEN
+        } else {
+            MAX_OSC_LEN
+        }
+    }
+
+    /// Returns the sequences that end in `bytes`, with the offsets just past their ends.
+    fn scan(&mut self, bytes: &[u8]) -> Vec<(usize, OscSequence)> {
+        let mut sequences = Vec::new();
+        for (ix, &byte) in bytes.iter().enumerate() {
+            match (self.state, byte) {
+                (ScanState::Ground, 0x1b) => self.state = ScanState::Escape,
+                (ScanState::Ground, _) => {}
+                (ScanState::Escape | ScanState::OscEscape, b']') => {
+                    self.state = ScanState::Osc;
+                    self.payload.clear();
+                }
+                (ScanState::Escape, 0x1b) => {}
+                (ScanState::Escape, _) => self.state = ScanState::Ground,
+                (ScanState::Osc, 0x07) | (ScanState::OscEscape, b'\\') => {
+                    self.state = ScanState::Ground;
+                    if self.payload.len() <= self.max_payload_len() {
+                        if let Some(sequence) = OscSequence::parse(&self.payload) {
+                            sequences.push((ix + 1, sequence));
+                        }
+                    }
+                    if self.payload.capacity() > MAX_OSC_LEN {
+                        // Don't hold on to the memory of large images.
+                        self.payload = Vec::new();
+                    }
+                }
+                (ScanState::Osc | ScanState::OscEscape, 0x1b) => self.state = ScanState::OscEscape,
+                (ScanState::OscEscape, _) | (ScanState::Osc, 0x18 | 0x1a) => {
+                    self.state = ScanState::Ground
+                }
+                (ScanState::Osc, _) => {
+                    if self.payload.len() <= self.max_payload_len() {
+                        self.payload.push(byte);
+                    }
+                }
+            }
+        }
+        sequences
+    }
+}
+
+/// A position in the terminal's output, with lines counted from the first line the terminal
+/// printed, so that it doesn't change as the output scrolls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct OutputPoint {
+    line: usize,
+    column: Column,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ShellCommand {
+    prompt_start: OutputPoint,
+    command_start: Option<OutputPoint>,
+    output_start: Option<OutputPoint>,
+    output_end: Option<OutputPoint>,
+    exit_code: Option<i32>,
+}
+
+impl ShellCommand {
+    fn new(prompt_start: OutputPoint) -> Self {
+        Self {
+            prompt_start,
+            command_start: None,
+            output_start: None,
+            output_end: None,
+            exit_code: None,
+        }
+    }
+}
+
+/// What the terminal's shell has reported about its commands and working directory.
+#[derive(Default)]
+pub(crate) struct ShellIntegration {
+    /// How many lines have scrolled off the top of the screen.
+    scrolled_lines: usize,
+    commands: VecDeque<ShellCommand>,
+    working_directory: Option<PathBuf>,
+    finished_commands: Vec<Option<i32>>,
+}
+
+impl ShellIntegration {
+    fn output_point(&self, point: AlacPoint) -> OutputPoint {
+        OutputPoint {
+            line: (self.scrolled_lines as i64 + point.line.0 as i64).max(0) as usize,
+            column: point.column,
+        }
+    }
+
+    fn grid_point(&self, point: OutputPoint) -> AlacPoint {
+        let line = point.line as i64 - self.scrolled_lines as i64;
+        AlacPoint::new(Line(line as i32), point.column)
+    }
+
+    /// Records a shell mark written with the cursor at `point`.
+    fn apply(&mut self, mark: ShellMark, point: AlacPoint) {
+        let point = self.output_point(point);
+        if !matches!(mark, ShellMark::WorkingDirectory(_)) {
+            // The screen was cleared, so the commands below the cursor are gone.
+            while self
+                .commands
+                .back()
+                .map_or(false, |command| command.prompt_start > point)
+            {
+                self.commands.pop_back();
+            }
+        }
+
+        match mark {
+            ShellMark::PromptStart => {
+                if let Some(command) = self.commands.back_mut() {
+                    if command.output_start.is_none() {
+                        // The prompt was drawn again without a command being run.
+                        *command = ShellCommand::new(point);
+                        return;
+                    }
+                    if command.output_end.is_none() {
+                        command.output_end = Some(point);
+                        self.finished_commands.push(None);
+                    }
+                }
+                self.commands.push_back(ShellCommand::new(point));
+            }
+            ShellMark::CommandStart => {
+                if let Some(command) = self
+                    .commands
+                    .back_mut()
+                    .filter(|command| command.output_start.is_none())
+                {
+                    command.command_start = Some(point);
+                }
+            }
+            ShellMark::OutputStart => match self.commands.back_mut() {
+                Some(command) if command.output_start.is_none() => {
+                    command.output_start = Some(point);
+                }
+                _ => {
+                    let mut command = ShellCommand::new(point);
+                    command.output_start = Some(point);
+                    self.commands.push_back(command);
+                }
+            },
+            ShellMark::CommandFinished(exit_code) => {
+                if let Some(command) = self.commands.back_mut().filter(|command| {
+                    command.output_start.is_some() && command.output_end.is_none()
+                }) {
+                    command.output_end = Some(point);
+                    command.exit_code = exit_code;
+                    self.finished_commands.push(exit_code);
+                }
+            }
+            ShellMark::WorkingDirectory(path) => self.working_directory = Some(path),
+        }
+    }
+
+    /// Forgets the commands whose prompts have scrolled out of the terminal's history.
+    pub(crate) fn prune(&mut self, history_size: usize) {
+        let topmost_line = self.scrolled_lines.saturating_sub(history_size);
+        while self
+            .commands
+            .front()
+            .map_or(false, |command| command.prompt_start.line < topmost_line)
+        {
+            self.commands.pop_front();
+        }
+    }
+
+    /// Forgets all commands, as the terminal's lines have been cleared.
+    pub(crate) fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    pub(crate) fn working_directory(&self) -> Option<PathBuf> {
+        self.working_directory.clone()
+    }
+
+    /// Returns the lines of the terminal's grid on which prompts start.
+    pub(crate) fn prompt_lines(&self) -> Vec<Line> {
+        self.commands
+            .iter()
+            .map(|command| self.grid_point(command.prompt_start).line)
+            .collect()
+    }
+
+    /// Returns the lines on which the prompts of finished commands start, with the commands' exit
+    /// codes.
+    pub(crate) fn exit_codes(&self) -> impl Iterator<Item = (Line, i32)> + '_ {
+        self.commands.iter().filter_map(|command| {
+            command.output_end?;
+            Some((
+                self.grid_point(command.prompt_start).line,
+                command.exit_code?,
+            ))
+        })
+    }
+
+    /// Returns where the output of the last finished command starts and ends, and its exit code.
+    /// The end is exclusive.
+    pub(crate) fn last_command_output(&self) -> Option<(AlacPoint, AlacPoint, Option<i32>)> {
+        self.commands.iter().rev().find_map(|command| {
+            Some((
+                self.grid_point(command.output_start?),
+                self.grid_point(command.output_end?),
+                command.exit_code,
+            ))
+        })
+    }
+
+    /// Returns where the last command that was run starts and ends, and whether it's still
+    /// running. The end is exclusive.
+    pub(crate) fn last_command(&self) -> Option<(AlacPoint, AlacPoint, bool)> {
+        self.commands.iter().rev().find_map(|command| {
+            Some((
+                self.grid_point(command.command_start?),
+                self.grid_point(command.output_start?),
+                command.output_end.is_none(),
+            ))
+        })
+    }
+
+    /// Returns the exit codes of the commands that have finished since this was last called.
+    pub(crate) fn take_finished_commands(&mut self) -> Vec<Option<i32>> {
+        std::mem::take(&mut self.finished_commands)
+    }
+}
+
+/// Something found in the PTY's output, which is applied once Alacritty has parsed the output
+/// before it into the terminal.
+enum OutputChange {
+    /// Lines scrolled off the top of the screen.
+    Scroll(usize),
+    /// A shell mark, written with the cursor at a point.
+    ShellMark(ShellMark, AlacPoint),
+    /// An inline image, shown at a point.
+    InlineImage {
+        sequence: InlineImageSequence,
+        point: AlacPoint,
+        size: Size<f32>,
+        lines: usize,
+    },
+}
+
+/// Follows the PTY's output on a terminal of its own, which has the same size as the terminal
+/// that's shown, to find where the cursor was when shell marks and inline images were written and
+/// how many lines the output scrolled. This lets the output be passed through to Alacritty's event
+/// loop without locking the terminal that's shown.
+struct OutputTracker {
+    term: Term<VoidListener>,
+    parser: Processor,
+    scanner: OscScanner,
+    /// Whether shell marks are recorded, which is only the case when shell integration is enabled.
+    shell_marks: bool,
+    /// The size of the terminal's cells in pixels, which is zero until the terminal is resized.
+    cell_size: Size<f32>,
+}
+
+impl OutputTracker {
+    fn new(shell_marks: bool) -> Self {
+        let config = Config {
+            scrolling_history: TRACKED_HISTORY_LINES,
+            ..Config::default()
+        };
+        Self {
+            term: Term::new(config, &TerminalSize::default(), VoidListener),
+            parser: Processor::new(),
+            scanner: OscScanner::default(),
+            shell_marks,
+            cell_size: Size::default(),
+        }
+    }
+
+    fn resize(&mut self, window_size: WindowSize) {
+        self.cell_size = size(
+            window_size.cell_width as f32,
+            window_size.cell_height as f32,
+        );
+        self.term.resize(SharedTerminalSize {
+            columns: window_size.num_cols as usize,
+            lines: window_size.num_lines as usize,
+        });
+    }
+
+    /// Follows `bytes` of the PTY's output, returning the output to pass on to Alacritty, which
+    /// also moves the cursor past inline images, along with what was found in it.
+    fn process(&mut self, bytes: &[u8]) -> (Vec<u8>, Vec<OutputChange>) {
+        let mut output = Vec::with_capacity(bytes.len());
+        let mut changes = Vec::new();
+        let mut start = 0;
+        for (end, sequence) in self.scanner.scan(bytes) {
+            self.advance(&bytes[start..end], &mut changes);
+            output.extend_from_slice(&bytes[start..end]);
+            match sequence {
+                OscSequence::ShellMark(mark) => {
+                    if self.shell_marks {
+                        changes.push(OutputChange::ShellMark(mark, self.term.grid().cursor.point));
+                    }
+                }
+                OscSequence::InlineImage(sequence) => {
+                    if let Some(cursor_movement) = self.show_image(sequence, &mut changes) {
+                        self.advance(cursor_movement.as_bytes(), &mut changes);
+                        output.extend_from_slice(cursor_movement.as_bytes());
+                    }
+                }
+            }
+            start = end;
+        }
+        self.advance(&bytes[start..], &mut changes);
+        output.extend_from_slice(&bytes[start..]);
+        (output, changes)
+    }
+
+    /// Records an image shown at the cursor, returning the sequences that move the cursor to the
+    /// right of the image's last line, unless the image asks not to.
+    fn show_image(
+        &mut self,
+        sequence: InlineImageSequence,
+        changes: &mut Vec<OutputChange>,
+    ) -> Option<String> {
+        let term = &self.term;
+        // Programs redraw the alternate screen themselves, so images aren't kept on it.
+        if term.mode().contains(TermMode::ALT_SCREEN)
+            || self.cell_size.width <= 0.
+            || self.cell_size.height <= 0.
+        {
+            return None;
+        }
+        let size = sequence.size(self.cell_size, term.columns(), term.screen_lines());
+        let lines = cmp::max((size.height / self.cell_size.height).ceil() as usize, 1);
+        let columns = cmp::max((size.width / self.cell_size.width).ceil() as usize, 1);
+        let point = term.grid().cursor.point;
+        let move_cursor = sequence.move_cursor();
+        changes.push(OutputChange::InlineImage {
+            sequence,
+            point,
+            size,
+            lines,
+        });
+        if !move_cursor {
+            return None;
+        }
+
+        // The lines below the screen are scrolled into view with a single sequence, rather than
+        // a line feed each, so that the output stays about as long as what the PTY wrote.
+        let lines_below = lines.saturating_sub(1);
+        let lines_down = cmp::min(
+            lines_below,
+            (term.bottommost_line().0 - point.line.0).max(0) as usize,
+        );
+        let mut cursor_movement = String::new();
+        if lines_down > 0 {
+            write!(cursor_movement, "\x1b[{lines_down}B").ok();
+        }
+        if lines_below > lines_down {
+            write!(cursor_movement, "\x1b[{}S", lines_below - lines_down).ok();
+        }
+        write!(cursor_movement, "\x1b[{}G", point.column.0 + columns + 1).ok();
+        Some(cursor_movement)
+    }
+
+    /// Parses `bytes` into the tracker's terminal, recording the lines that scrolled off the top of
+    /// the screen.
+    fn advance(&mut self, mut bytes: &[u8], changes: &mut Vec<OutputChange>) {
+        while !bytes.is_empty() {
+            let chunk_len = bytes
+                .iter()
+                .enumerate()
+                .filter(|(_, byte)| **byte == b'\n')
+                .nth(MAX_LINE_FEEDS_PER_CHUNK - 1)
+                .map_or(bytes.len(), |(ix, _)| ix + 1);
+            let (chunk, rest) = bytes.split_at(chunk_len);
+            self.parser.advance(&mut self.term, chunk);
+            // Synchronized updates only hold back what's shown, which the tracker doesn't show.
+            if self.parser.sync_timeout().sync_timeout().is_some() {
+                self.parser.stop_sync(&mut self.term);
+            }
+            // Lines that scroll off the primary screen are moved to its history, which is cleared
+            // each time so that it never fills up and stops growing.
+            let scrolled_lines = self.term.history_size();
+            if scrolled_lines > 0 {
+                self.term.grid_mut().clear_history();
+                changes.push(OutputChange::Scroll(scrolled_lines));
+            }
+            bytes = rest;
+        }
+    }
+}
+
+/// Output that Alacritty's event loop has read from the PTY, but might not have parsed into the
+/// terminal yet.
+#[derive(Default)]
+struct PendingOutput {
+    changes: Vec<OutputChange>,
+    /// The output to mirror to collaborators, while the terminal is shared.
+    shared_output: Vec<SharedOutput>,
+}
+
+/// What [`ShellIntegrationPty`] finds in the PTY's output, which is applied when Alacritty's event
+/// loop reports that it has parsed that output, through the [`ZedListener`] it's given. The event
+/// loop sends that wakeup event while it still has the terminal locked, so shell marks, inline
+/// images and the output mirrored to collaborators always line up with the terminal's content.
+pub(crate) struct PtyOutput {
+    pending: Mutex<PendingOutput>,
+    shell_integration: Arc<Mutex<ShellIntegration>>,
+    inline_images: Arc<Mutex<InlineImages>>,
+    /// Where the PTY's output is sent while the terminal is shared with collaborators.
+    output_mirror: Arc<Mutex<Option<UnboundedSender<SharedOutput>>>>,
+    event_listener: ZedListener,
+    /// Decodes inline images off the PTY's thread.
+    executor: BackgroundExecutor,
+}
+
+impl PtyOutput {
+    pub(crate) fn new(
+        shell_integration: Arc<Mutex<ShellIntegration>>,
+        inline_images: Arc<Mutex<InlineImages>>,
+        output_mirror: Arc<Mutex<Option<UnboundedSender<SharedOutput>>>>,
+        event_listener: ZedListener,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        Self {
+            pending: Mutex::default(),
+            shell_integration,
+            inline_images,
+            output_mirror,
+            event_listener,
+            executor,
+        }
+    }
+
+    /// Applies the output that's been read from the PTY, once it's been parsed into the terminal.
+    pub(crate) fn apply_pending(&self) {
+        let pending = mem::take(&mut *self.pending.lock());
+        for change in pending.changes {
+            match change {
+                OutputChange::Scroll(scrolled_lines) => {
+                    self.shell_integration.lock().scrolled_lines += scrolled_lines;
+                    self.inline_images.lock().scrolled_lines += scrolled_lines;
+                }
+                OutputChange::ShellMark(mark, point) => {
+                    self.shell_integration.lock().apply(mark, point)
+                }
+                OutputChange::InlineImage {
+                    sequence,
+                    point,
+                    size,
+                    lines,
+                } => self.add_image(sequence, point, size, lines),
+            }
+        }
+        if let Some(output_mirror) = self.output_mirror.lock().as_ref() {
+            for output in pending.shared_output {
+                output_mirror.unbounded_send(output).ok();
+            }
+        }
+    }
+
+    fn add_image(
+        &self,
+        sequence: InlineImageSequence,
+        point: AlacPoint,
+        size: Size<f32>,
+        lines: usize,
+    ) {
+        let image_id = self.inline_images.lock().add(&sequence, point, size, lines);
+        if let Some(image_id) = image_id {
+            let inline_images = self.inline_images.clone();
+            let event_listener = self.event_listener.clone();
+            self.executor
+                .spawn(async move {
+                    if let Some(image) = sequence.decode() {
+                        inline_images.lock().set_image(image_id, image);
+                        event_listener.send_event(AlacTermEvent::Wakeup);
+                    }
+                })
+                .detach();
+        }
+    }
+}
+
+/// Wraps the PTY given to Alacritty's event loop, passing the PTY's output through to it while
+/// following the output with an [`OutputTracker`].
+pub(crate) struct ShellIntegrationPty {
+    pty: Pty,
+    tracker: OutputTracker,
+    output: Arc<PtyOutput>,
+    read_buf: Vec<u8>,
+    /// Output that's been read from the PTY, but not yet by Alacritty's event loop.
+    unread: VecDeque<u8>,
+}
+
+impl ShellIntegrationPty {
+    pub(crate) fn new(pty: Pty, shell_marks: bool, output: Arc<PtyOutput>) -> Self {
+        Self {
+            pty,
+            tracker: OutputTracker::new(shell_marks),
+            output,
+            read_buf: Vec::new(),
+            unread: VecDeque::new(),
+        }
+    }
+}
+
+impl io::Read for ShellIntegrationPty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.unread.is_empty() {
+            // Leave room for the sequences that move the cursor past inline images, so that the
+            // output fits in the event loop's buffer.
+            self.read_buf.resize(cmp::max(buf.len() / 2, 1), 0);
+            let len = self.pty.reader().read(&mut self.read_buf)?;
+            if len == 0 {
+                return Ok(0);
+            }
+            let (output, changes) = self.tracker.process(&self.read_buf[..len]);
+            let mut pending = self.output.pending.lock();
+            pending.changes.extend(changes);
+            if self.output.output_mirror.lock().is_some() {
+                pending.shared_output.push(SharedOutput {
+                    bytes: output.clone(),
+                    columns: self.tracker.term.columns(),
+                    lines: self.tracker.term.screen_lines(),
+                });
+            }
+            drop(pending);
+            self.unread.extend(output);
+        }
+        self.unread.read(buf)
+    }
+}
+
+impl EventedReadWrite for ShellIntegrationPty {
+    type Reader = Self;
+    type Writer = <Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        // SAFETY: The PTY is owned by this wrapper, so it lives as long as its registration.
+        unsafe { self.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for ShellIntegrationPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for ShellIntegrationPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.tracker.resize(window_size);
+        self.pty.on_resize(window_size)
+    }
+}
+
+/// Adds Zed's shell integration to bash, zsh and fish, by starting them with startup files that
+/// load the user's own and then report prompts, commands and the working directory. Other shells
+/// are returned unchanged.
+pub(crate) fn inject_shell_integration(shell: Shell, env: &mut HashMap<String, String>) -> Shell {
+    if cfg!(windows) {
+        return shell;
+    }
+    let program = match &shell {
+        Shell::System => match std::env::var("SHELL") {
+            Ok(program) => program,
+            Err(_) => return shell,
+        },
+        Shell::Program(program) | Shell::WithArguments { program, .. } => program.clone(),
+    };
+    let Some(dir) = shell_integration_dir() else {
+        return shell;
+    };
+    let env_var = |env: &HashMap<String, String>, name: &str| {
+        env.get(name).cloned().or_else(|| std::env::var(name).ok())
+    };
+
+    match Path::new(&program)
+        .file_name()
+        .and_then(|name| name.to_str())
+    {
+        Some("zsh") => {
+            if let Some(zdotdir) = env_var(env, "ZDOTDIR") {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), zdotdir);
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                dir.join("zsh").to_string_lossy().into_owned(),
+            );
+            shell
+        }
+        Some("fish") => {
+            let dir = dir.to_string_lossy().into_owned();
+            let data_dirs = env_var(env, "XDG_DATA_DIRS")
+                .filter(|data_dirs| !data_dirs.is_empty())
+                .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+            env.insert("XDG_DATA_DIRS".to_string(), format!("{dir}:{data_dirs}"));
+            env.insert("ZED_SHELL_INTEGRATION_DIR".to_string(), dir);
+            shell
+        }
+        // Bash's startup file can only be replaced with an argument, which isn't added to shells
+        // that already have arguments.
+        Some("bash") if !matches!(shell, Shell::WithArguments { .. }) => {
+            // The system shell is started as a login shell on macOS.
+            if cfg!(target_os = "macos") && matches!(shell, Shell::System) {
+                env.insert("ZED_SHELL_LOGIN".to_string(), "1".to_string());
+            }
+            Shell::WithArguments {
+                program,
+                args: vec![
+                    "--init-file".to_string(),
+                    dir.join("zed.bash").to_string_lossy().into_owned(),
+                ],
+                title_override: None,
+            }
+        }
+        _ => shell,
+    }
+}
+
+/// Writes the integration scripts to Zed's support directory, once per run.
+fn shell_integration_dir() -> Option<&'static Path> {
+    static DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = paths::shell_integration_dir();
+        SCRIPTS
+            .iter()
+            .try_for_each(|(path, contents)| {
+                let path = dir.join(path);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, contents)
+            })
+            .log_err()?;
+        Some(dir.clone())
+    })
+    .as_deref()
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine as _;
+
+    use super::*;
+
+    fn scan_shell_marks(scanner: &mut OscScanner, bytes: &[u8]) -> Vec<(usize, ShellMark)> {
+        scanner
+            .scan(bytes)
+            .into_iter()
+            .filter_map(|(offset, sequence)| match sequence {
+                OscSequence::ShellMark(mark) => Some((offset, mark)),
+                OscSequence::InlineImage(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_shell_marks() {
+        let mut scanner = OscScanner::default();
+        let output =
+            b"\x1b]133;A\x07$ \x1b]133;B\x1b\\ls\r\n\x1b]133;C\x07a.txt\r\n\x1b]133;D;2\x07";
+        assert_eq!(
+            scan_shell_marks(&mut scanner, output),
+            [
+                (8, ShellMark::PromptStart),
+                (19, ShellMark::CommandStart),
+                (31, ShellMark::OutputStart),
+                (output.len(), ShellMark::CommandFinished(Some(2))),
+            ]
+        );
+
+        // Sequences can be split across reads, and other sequences are ignored.
+        assert_eq!(
+            scan_shell_marks(&mut scanner, b"\x1b]0;title\x07\x1b[1m\x1b]7;file:///tm"),
+            []
+        );
+        assert_eq!(
+            scan_shell_marks(&mut scanner, b"p/a%20b\x1b\\\x1b]133;D\x07"),
+            [
+                (9, ShellMark::WorkingDirectory(PathBuf::from("/tmp/a b"))),
+                (17, ShellMark::CommandFinished(None)),
+            ]
+        );
+
+        // A new sequence aborts an unterminated one.
+        assert_eq!(
+            scan_shell_marks(&mut scanner, b"\x1b]133;A\x1b]133;C\x07"),
+            [(15, ShellMark::OutputStart)]
+        );
+    }
+
+    #[test]
+    fn test_scan_inline_images() {
+        // An image with noise, whose sequence is longer than those of shell marks can be.
+        let mut seed = 1u32;
+        let image = image::RgbaImage::from_fn(64, 64, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            image::Rgba((seed >> 8).to_le_bytes())
+        });
+        let mut png = Vec::new();
+        image
+            .write_to(&mut io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let image_data = base64::engine::general_purpose::STANDARD.encode(&png);
+        assert!(image_data.len() > MAX_OSC_LEN);
+
+        let mut scanner = OscScanner::default();
+        let output = format!("\x1b]1337;File=inline=1:{image_data}\x07\x1b]133;A\x07");
+        let (first_part, second_part) = output.as_bytes().split_at(output.len() / 2);
+        assert!(scanner.scan(first_part).is_empty());
+        let sequences = scanner.scan(second_part);
+        assert!(matches!(
+            sequences.as_slice(),
+            [
+                (_, OscSequence::InlineImage(_)),
+                (_, OscSequence::ShellMark(ShellMark::PromptStart))
+            ]
+        ));
+        assert_eq!(sequences[0].0, second_part.len() - 8);
+    }
+
+    #[test]
+    fn test_output_tracker() {
+        fn describe(changes: Vec<OutputChange>) -> Vec<String> {
+            changes
+                .into_iter()
+                .map(|change| match change {
+                    OutputChange::Scroll(lines) => format!("scroll {lines}"),
+                    OutputChange::ShellMark(mark, point) => {
+                        format!("{mark:?} at {}:{}", point.line.0, point.column.0)
+                    }
+                    OutputChange::InlineImage { point, lines, .. } => {
+                        format!(
+                            "image at {}:{}, {lines} lines",
+                            point.line.0, point.column.0
+                        )
+                    }
+                })
+                .collect()
+        }
+
+        let mut tracker = OutputTracker::new(true);
+        tracker.resize(WindowSize {
+            num_lines: 3,
+            num_cols: 20,
+            cell_width: 8,
+            cell_height: 16,
+        });
+
+        // Output is passed on unchanged, with marks at the cursor and the lines it scrolled.
+        let output = b"$ ls\r\n\x1b]133;C\x07a\r\nb\r\nc\r\n\x1b]133;D;0\x07";
+        let (passed_output, changes) = tracker.process(output);
+        assert_eq!(passed_output, output);
+        assert_eq!(
+            describe(changes),
+            [
+                "OutputStart at 1:0",
+                "scroll 2",
+                "CommandFinished(Some(0)) at 2:0"
+            ]
+        );
+
+        // The cursor is moved past images, scrolling the lines they need below the screen.
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(1, 1, image::Rgba([0, 0, 0, 255]))
+            .write_to(&mut io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let image_data = base64::engine::general_purpose::STANDARD.encode(&png);
+        let output = format!("\x1b]1337;File=inline=1;height=2:{image_data}\x07");
+        let (passed_output, changes) = tracker.process(output.as_bytes());
+        assert_eq!(
+            passed_output,
+            format!("{output}\x1b[1S\x1b[5G").into_bytes()
+        );
+        assert_eq!(describe(changes), ["image at 2:0, 2 lines", "scroll 1"]);
+
+        let (_, changes) = tracker.process(b"x\x1b]133;A\x07");
+        assert_eq!(describe(changes), ["PromptStart at 2:5"]);
+
+        // Without shell integration, only images are recorded.
+        let mut tracker = OutputTracker::new(false);
+        tracker.resize(WindowSize {
+            num_lines: 3,
+            num_cols: 20,
+            cell_width: 8,
+            cell_height: 16,
+        });
+        let (_, changes) = tracker.process(format!("\x1b]133;A\x07{output}").as_bytes());
+        assert_eq!(describe(changes), ["image at 0:0, 2 lines"]);
+    }
+
+    #[test]
+    fn test_parse_file_url() {
+        assert_eq!(
+            parse_file_url("file://localhost/home/me/my%20project"),
+            Some(PathBuf::from("/home/me/my project"))
+        );
+        assert_eq!(parse_file_url("file:///tmp"), Some(PathBuf::from("/tmp")));
+        assert_eq!(parse_file_url("https://zed.dev/tmp"), None);
+        assert_eq!(parse_file_url("file://localhost"), None);
+    }
+
+    #[test]
+    fn test_shell_commands() {
+        let point = |line, column| AlacPoint::new(Line(line), Column(column));
+        let mut shell_integration = ShellIntegration::default();
+
+        shell_integration.apply(ShellMark::PromptStart, point(0, 0));
+        shell_integration.apply(ShellMark::CommandStart, point(0, 2));
+        shell_integration.apply(ShellMark::CommandFinished(Some(0)), point(0, 2));
+        shell_integration.apply(ShellMark::OutputStart, point(1, 0));
+        shell_integration.apply(ShellMark::CommandFinished(Some(1)), point(3, 0));
+        assert_eq!(
+            shell_integration.last_command_output(),
+            Some((point(1, 0), point(3, 0), Some(1)))
+        );
+        assert_eq!(
+            shell_integration.last_command(),
+            Some((point(0, 2), point(1, 0), false))
+        );
+        assert_eq!(shell_integration.take_finished_commands(), [Some(1)]);
+        assert_eq!(shell_integration.take_finished_commands(), []);
+
+        // Marks keep their place in the output as it scrolls.
+        shell_integration.apply(ShellMark::PromptStart, point(3, 0));
+        shell_integration.scrolled_lines += 2;
+        shell_integration.apply(ShellMark::PromptStart, point(1, 0));
+        assert_eq!(shell_integration.prompt_lines(), [Line(-2), Line(1)]);
+        assert_eq!(
+            shell_integration.exit_codes().collect::<Vec<_>>(),
+            [(Line(-2), 1)]
+        );
+
+        shell_integration.prune(1);
+        assert_eq!(shell_integration.prompt_lines(), [Line(1)]);
+        assert_eq!(shell_integration.last_command_output(), None);
+
+        // Commands below the cursor are forgotten when the screen is cleared.
+        shell_integration.apply(ShellMark::OutputStart, point(2, 0));
+        shell_integration.apply(ShellMark::PromptStart, point(0, 0));
+        assert_eq!(shell_integration.prompt_lines(), [Line(0)]);
+        assert_eq!(shell_integration.take_finished_commands(), []);
+
+        // Commands are running until they finish.
+        shell_integration.apply(ShellMark::CommandStart, point(0, 2));
+        shell_integration.apply(ShellMark::OutputStart, point(1, 0));
+        assert_eq!(
+            shell_integration.last_command(),
+            Some((point(0, 2), point(1, 0), true))
+        );
+    }
+}
@@ -2,9 +2,15 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod inline_images;
 mod pty_info;
+mod sharing;
+mod shell_integration;
 pub mod terminal_settings;
 
+pub use inline_images::TerminalImage;
+pub use sharing::{SharedOutput, TerminalMirror, TerminalSnapshot};
+
 use alacritty_terminal::{
     event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
     event_loop::{EventLoop, Msg, Notifier},
@@ -21,6 +27,7 @@ use alacritty_terminal::{
     vi_mode::{ViModeCursor, ViMotion},
     vte::ansi::{
         ClearMode, CursorStyle as AlacCursorStyle, Handler, NamedPrivateMode, PrivateMode,
+        Processor,
     },
     Term,
 };
@@ -38,10 +45,16 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use inline_images::InlineImages;
+use parking_lot::Mutex;
 use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use smol::channel::{Receiver, Sender};
+use sharing::SharedTerminalSize;
+use shell_integration::{
+    inject_shell_integration, PtyOutput, ShellIntegration, ShellIntegrationPty,
+};
+use smol::channel::{bounded, Receiver, Sender};
 use task::{HideStrategy, Shell, TaskId};
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
@@ -60,7 +73,7 @@ use thiserror::Error;
 use gpui::{
     actions, black, px, AnyWindowHandle, App, Bounds, ClipboardItem, Context, EventEmitter, Hsla,
     Keystroke, Modifiers, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point,
-    Rgba, ScrollWheelEvent, SharedString, Size, Task, TouchPhase,
+    RenderImage, Rgba, ScrollWheelEvent, SharedString, Size, Task, TouchPhase,
 };
 
 use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
@@ -79,7 +92,13 @@ actions!(
         ScrollPageDown,
         ScrollToTop,
         ScrollToBottom,
+        ScrollToPreviousPrompt,
+        ScrollToNextPrompt,
+        SelectLastCommandOutput,
+        CopyLastCommandOutput,
         ToggleViMode,
+        ShareTerminal,
+        StopSharingTerminal,
     ]
 );
 
@@ -108,6 +127,9 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// A command run in the terminal's shell has finished with the given exit code, as reported
+    /// through shell integration.
+    CommandFinished(Option<i32>),
 }
 
 #[derive(Clone, Debug)]
@@ -148,11 +170,38 @@ enum InternalEvent {
 
 ///A translation struct for Alacritty to communicate with us from their event loop
 #[derive(Clone)]
-pub struct ZedListener(pub UnboundedSender<AlacTermEvent>);
+pub struct ZedListener {
+    events_tx: UnboundedSender<AlacTermEvent>,
+    /// The PTY output that's applied when the event loop reports having parsed it, which is only
+    /// set for the listener given to the event loop.
+    pty_output: Option<Arc<PtyOutput>>,
+}
+
+impl ZedListener {
+    pub fn new(events_tx: UnboundedSender<AlacTermEvent>) -> Self {
+        Self {
+            events_tx,
+            pty_output: None,
+        }
+    }
+
+    fn with_pty_output(
+        events_tx: UnboundedSender<AlacTermEvent>,
+        pty_output: Arc<PtyOutput>,
+    ) -> Self {
+        Self {
+            events_tx,
+            pty_output: Some(pty_output),
+        }
+    }
+}
 
 impl EventListener for ZedListener {
     fn send_event(&self, event: AlacTermEvent) {
-        self.0.unbounded_send(event).ok();
+        if let (AlacTermEvent::Wakeup, Some(pty_output)) = (&event, &self.pty_output) {
+            pty_output.apply_pending();
+        }
+        self.events_tx.unbounded_send(event).ok();
     }
 }
 
@@ -331,6 +380,7 @@ impl TerminalBuilder {
         cursor_shape: CursorShape,
         alternate_scroll: AlternateScroll,
         max_scroll_history_lines: Option<usize>,
+        restored_scrollback: Option<RestoredScrollback>,
         is_ssh_terminal: bool,
         window: AnyWindowHandle,
         completion_tx: Sender<()>,
@@ -355,6 +405,13 @@ impl TerminalBuilder {
 
         let mut terminal_title_override = None;
 
+        let shell_integration_enabled = TerminalSettings::get_global(cx).shell_integration;
+        let shell = if task.is_none() && !is_ssh_terminal && shell_integration_enabled {
+            inject_shell_integration(shell, &mut env)
+        } else {
+            shell
+        };
+
         let pty_options = {
             let alac_shell = match shell.clone() {
                 Shell::System => None,
@@ -408,7 +465,7 @@ impl TerminalBuilder {
         let mut term = Term::new(
             config.clone(),
             &TerminalSize::default(),
-            ZedListener(events_tx.clone()),
+            ZedListener::new(events_tx.clone()),
         );
 
         //Alacritty defaults to alternate scrolling being on, so we just need to turn it off.
@@ -416,6 +473,10 @@ impl TerminalBuilder {
             term.unset_private_mode(PrivateMode::Named(NamedPrivateMode::AlternateScroll));
         }
 
+        if let Some(restored_scrollback) = restored_scrollback {
+            restore_scrollback(&mut term, &restored_scrollback);
+        }
+
         let term = Arc::new(FairMutex::new(term));
 
         //Setup the pty...
@@ -436,10 +497,22 @@ impl TerminalBuilder {
 
         let pty_info = PtyProcessInfo::new(&pty);
 
+        let shell_integration = Arc::new(Mutex::new(ShellIntegration::default()));
+        let inline_images = Arc::new(Mutex::new(InlineImages::default()));
+        let output_mirror = Arc::new(Mutex::new(None));
+        let pty_output = Arc::new(PtyOutput::new(
+            shell_integration.clone(),
+            inline_images.clone(),
+            output_mirror.clone(),
+            ZedListener::new(events_tx.clone()),
+            cx.background_executor().clone(),
+        ));
+        let pty = ShellIntegrationPty::new(pty, shell_integration_enabled, pty_output.clone());
+
         //And connect them together
         let event_loop = EventLoop::new(
             term.clone(),
-            ZedListener(events_tx.clone()),
+            ZedListener::with_pty_output(events_tx.clone(), pty_output),
             pty,
             pty_options.drain_on_exit,
             false,
@@ -451,7 +524,10 @@ impl TerminalBuilder {
 
         let terminal = Terminal {
             task,
-            pty_tx: Notifier(pty_tx),
+            terminal_type: TerminalType::Pty {
+                pty_tx: Notifier(pty_tx),
+                info: pty_info,
+            },
             completion_tx,
             term,
             term_config: config,
@@ -461,7 +537,6 @@ impl TerminalBuilder {
             last_mouse: None,
             matches: Vec::new(),
             selection_head: None,
-            pty_info,
             breadcrumb_text: String::new(),
             scroll_px: px(0.),
             last_mouse_position: None,
@@ -474,6 +549,9 @@ impl TerminalBuilder {
             vi_mode_enabled: false,
             is_ssh_terminal,
             python_venv_directory,
+            shell_integration,
+            inline_images,
+            output_mirror,
         };
 
         Ok(TerminalBuilder {
@@ -482,6 +560,73 @@ impl TerminalBuilder {
         })
     }
 
+    /// Creates a terminal that shows a collaborator's shared terminal, starting from a snapshot of
+    /// it. Input is sent to `input_tx`, unless the terminal is read-only.
+    pub fn new_shared(
+        title: String,
+        snapshot: TerminalSnapshot,
+        read_only: bool,
+        input_tx: UnboundedSender<Vec<u8>>,
+        cursor_shape: CursorShape,
+        max_scroll_history_lines: Option<usize>,
+    ) -> TerminalBuilder {
+        let config = Config {
+            scrolling_history: max_scroll_history_lines
+                .unwrap_or(DEFAULT_SCROLL_HISTORY_LINES)
+                .min(MAX_SCROLL_HISTORY_LINES),
+            default_cursor_style: AlacCursorStyle::from(cursor_shape),
+            ..Config::default()
+        };
+        let size = SharedTerminalSize {
+            columns: snapshot.columns,
+            lines: snapshot.lines,
+        };
+        let (events_tx, events_rx) = unbounded();
+        let mut term = Term::new(config.clone(), &size, ZedListener::new(events_tx));
+        let mut parser: Processor = Processor::new();
+        parser.advance(&mut term, snapshot.content.as_bytes());
+        let (completion_tx, _) = bounded(1);
+
+        let terminal = Terminal {
+            task: None,
+            terminal_type: TerminalType::Shared {
+                parser,
+                size,
+                input_tx,
+                read_only,
+            },
+            completion_tx,
+            term: Arc::new(FairMutex::new(term)),
+            term_config: config,
+            title_override: Some(title.into()),
+            events: VecDeque::with_capacity(10),
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            last_mouse_position: None,
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            secondary_pressed: false,
+            hovered_word: false,
+            url_regex: RegexSearch::new(URL_REGEX).unwrap(),
+            word_regex: RegexSearch::new(WORD_REGEX).unwrap(),
+            vi_mode_enabled: false,
+            is_ssh_terminal: false,
+            python_venv_directory: None,
+            shell_integration: Arc::new(Mutex::new(ShellIntegration::default())),
+            inline_images: Arc::new(Mutex::new(InlineImages::default())),
+            output_mirror: Arc::new(Mutex::new(None)),
+        };
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+
     pub fn subscribe(mut self, cx: &Context<Terminal>) -> Terminal {
         //Event loop
         cx.spawn(|terminal, mut cx| async move {
@@ -572,6 +717,10 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    /// The exit codes of the visible finished commands, with the lines their prompts start on.
+    pub command_exit_codes: Vec<(Line, i32)>,
+    /// The images that are at least partly visible.
+    pub images: Vec<TerminalImage>,
 }
 
 #[derive(Clone)]
@@ -581,6 +730,33 @@ pub struct HoveredWord {
     pub id: usize,
 }
 
+/// The output of a command that was run in the terminal's shell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub output: String,
+    /// The command's exit code, if the shell reported it.
+    pub exit_code: Option<i32>,
+}
+
+/// The last command that was run in the terminal's shell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LastCommand {
+    pub command: String,
+    /// Whether the command is still running.
+    pub running: bool,
+}
+
+/// The output of a terminal from a previous session, shown in a new terminal before its shell
+/// starts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestoredScrollback {
+    /// The title the terminal had.
+    pub title: String,
+    pub text: String,
+    /// The last command that was run in the terminal, or the command of the task it ran.
+    pub last_command: Option<String>,
+}
+
 impl Default for TerminalContent {
     fn default() -> Self {
         TerminalContent {
@@ -596,6 +772,8 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            command_exit_codes: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -606,8 +784,24 @@ pub enum SelectionPhase {
     Ended,
 }
 
+/// Where a terminal's output comes from and where its input goes.
+enum TerminalType {
+    /// A terminal that's connected to a PTY.
+    Pty {
+        pty_tx: Notifier,
+        info: PtyProcessInfo,
+    },
+    /// A collaborator's shared terminal, whose PTY's output is parsed into this one.
+    Shared {
+        parser: Processor,
+        size: SharedTerminalSize,
+        input_tx: UnboundedSender<Vec<u8>>,
+        read_only: bool,
+    },
+}
+
 pub struct Terminal {
-    pty_tx: Notifier,
+    terminal_type: TerminalType,
     completion_tx: Sender<()>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     term_config: Config,
@@ -620,7 +814,6 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    pub pty_info: PtyProcessInfo,
     title_override: Option<SharedString>,
     pub python_venv_directory: Option<PathBuf>,
     scroll_px: Pixels,
@@ -633,6 +826,9 @@ pub struct Terminal {
     task: Option<TaskState>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
+    shell_integration: Arc<Mutex<ShellIntegration>>,
+    inline_images: Arc<Mutex<InlineImages>>,
+    output_mirror: Arc<Mutex<Option<UnboundedSender<SharedOutput>>>>,
 }
 
 pub struct TaskState {
@@ -688,7 +884,7 @@ impl Terminal {
                 cx.write_to_clipboard(ClipboardItem::new_string(data.to_string()))
             }
             AlacTermEvent::ClipboardLoad(_, format) => {
-                self.write_to_pty(
+                self.respond_to_pty(
                     match &cx.read_from_clipboard().and_then(|item| item.text()) {
                         // The terminal only supports pasting strings, not images.
                         Some(text) => format(text),
@@ -696,9 +892,9 @@ impl Terminal {
                     },
                 )
             }
-            AlacTermEvent::PtyWrite(out) => self.write_to_pty(out.clone()),
+            AlacTermEvent::PtyWrite(out) => self.respond_to_pty(out.clone()),
             AlacTermEvent::TextAreaSizeRequest(format) => {
-                self.write_to_pty(format(self.last_content.size.into()))
+                self.respond_to_pty(format(self.last_content.size.into()))
             }
             AlacTermEvent::CursorBlinkingChange => {
                 let terminal = self.term.lock();
@@ -715,8 +911,15 @@ impl Terminal {
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
 
-                if self.pty_info.has_changed() {
-                    cx.emit(Event::TitleChanged);
+                if let TerminalType::Pty { info, .. } = &mut self.terminal_type {
+                    if info.has_changed() {
+                        cx.emit(Event::TitleChanged);
+                    }
+                }
+
+                let finished_commands = self.shell_integration.lock().take_finished_commands();
+                for exit_code in finished_commands {
+                    cx.emit(Event::CommandFinished(exit_code));
                 }
             }
             AlacTermEvent::ColorRequest(index, format) => {
@@ -731,7 +934,7 @@ impl Terminal {
                 let color = self.term.lock().colors()[*index].unwrap_or_else(|| {
                     to_alac_rgb(get_color_at_index(*index, cx.theme().as_ref()))
                 });
-                self.write_to_pty(format(color));
+                self.respond_to_pty(format(color));
             }
             AlacTermEvent::ChildExit(error_code) => {
                 self.register_task_finished(Some(*error_code), cx);
@@ -756,9 +959,11 @@ impl Terminal {
 
                 self.last_content.size = new_size;
 
-                self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
-
-                term.resize(new_size);
+                // Shared terminals keep the host's size, which their output is laid out for.
+                if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
+                    pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                    term.resize(new_size);
+                }
             }
             InternalEvent::Clear => {
                 // Clear back buffer
@@ -790,6 +995,9 @@ impl Terminal {
                     term.grid_mut().reset_region((new_cursor.line + 1)..);
                 }
 
+                self.shell_integration.lock().clear();
+                self.inline_images.lock().clear();
+
                 cx.emit(Event::Wakeup);
             }
             InternalEvent::Scroll(scroll) => {
@@ -1081,6 +1289,167 @@ impl Terminal {
         self.set_selection(Some((make_selection(&(start..=end)), end)));
     }
 
+    /// Selects the output of the last command that finished running, and scrolls to it.
+    pub fn select_last_command_output(&mut self) {
+        let term = self.term.lock();
+        let Some((Some(range), _)) = self.last_command_output_range(&term) else {
+            return;
+        };
+        drop(term);
+        let start = *range.start();
+        self.set_selection(Some((make_selection(&range), *range.end())));
+        self.events
+            .push_back(InternalEvent::ScrollToAlacPoint(start));
+    }
+
+    pub fn copy_last_command_output(&mut self, cx: &mut Context<Self>) {
+        if let Some(command_output) = self.last_command_output() {
+            cx.write_to_clipboard(ClipboardItem::new_string(command_output.output));
+        }
+    }
+
+    /// Returns the output of the last command that finished running in the terminal's shell.
+    ///
+    /// This is only available when the shell reports its commands through shell integration, and
+    /// while the output is still in the terminal's scrollback.
+    pub fn last_command_output(&self) -> Option<CommandOutput> {
+        let term = self.term.lock();
+        let (range, exit_code) = self.last_command_output_range(&term)?;
+        let output = range
+            .map(|range| term.bounds_to_string(*range.start(), *range.end()))
+            .unwrap_or_default();
+        Some(CommandOutput {
+            output: output.trim_end().to_string(),
+            exit_code,
+        })
+    }
+
+    /// Returns the last command that was run in the terminal's shell, and whether it's still
+    /// running.
+    ///
+    /// Like [`Self::last_command_output`], this relies on shell integration.
+    pub fn last_command(&self) -> Option<LastCommand> {
+        let term = self.term.lock();
+        let (start, end, running) = self.shell_integration.lock().last_command()?;
+        if start.line < term.topmost_line() || start >= end {
+            return None;
+        }
+        let end = end
+            .sub(&*term, Boundary::Grid, 1)
+            .grid_clamp(&*term, Boundary::Grid);
+        let command = term.bounds_to_string(start, end).trim().to_string();
+        (!command.is_empty()).then_some(LastCommand { command, running })
+    }
+
+    /// Returns the images that have scrolled out of the terminal's history or been cleared since
+    /// this was last called, so that they can be dropped from the window they were painted in.
+    pub fn take_removed_images(&mut self) -> Vec<Arc<RenderImage>> {
+        self.inline_images.lock().take_removed_images()
+    }
+
+    /// Returns the text of the terminal's scrollback and screen, without the empty lines at the
+    /// bottom of the screen.
+    pub fn scrollback(&self, cx: &App) -> Task<String> {
+        let term = self.term.clone();
+        cx.background_executor().spawn(async move {
+            let term = term.lock();
+            let start = AlacPoint::new(term.topmost_line(), Column(0));
+            let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+            let text = term.bounds_to_string(start, end);
+            text.trim_end().to_string()
+        })
+    }
+
+    /// Starts mirroring the PTY's output to share the terminal with collaborators, until the
+    /// returned mirror is dropped.
+    pub fn share(&self) -> Option<TerminalMirror> {
+        self.can_share()
+            .then(|| TerminalMirror::new(self.term.clone(), self.output_mirror.clone()))
+    }
+
+    /// Whether the terminal can be shared with collaborators. Terminals that are themselves shared
+    /// can't be shared again.
+    pub fn can_share(&self) -> bool {
+        matches!(self.terminal_type, TerminalType::Pty { .. })
+    }
+
+    /// Whether this terminal shows a collaborator's shared terminal.
+    pub fn is_shared_from_collaborator(&self) -> bool {
+        matches!(self.terminal_type, TerminalType::Shared { .. })
+    }
+
+    /// Whether input to the terminal is discarded, which is the case for shared terminals that
+    /// the host hasn't given us input to.
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self.terminal_type,
+            TerminalType::Shared {
+                read_only: true,
+                ..
+            }
+        )
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool, cx: &mut Context<Self>) {
+        if let TerminalType::Shared {
+            read_only: current_read_only,
+            ..
+        } = &mut self.terminal_type
+        {
+            if *current_read_only != read_only {
+                *current_read_only = read_only;
+                cx.notify();
+            }
+        }
+    }
+
+    pub fn set_shared_title(&mut self, title: String, cx: &mut Context<Self>) {
+        if self.is_shared_from_collaborator() {
+            self.title_override = Some(title.into());
+            cx.emit(Event::TitleChanged);
+        }
+    }
+
+    /// Parses output from the PTY of the shared terminal that this terminal shows.
+    pub fn write_shared_output(&mut self, output: SharedOutput, cx: &mut Context<Self>) {
+        let TerminalType::Shared { parser, size, .. } = &mut self.terminal_type else {
+            return;
+        };
+
+        let mut term = self.term.lock();
+        let new_size = SharedTerminalSize {
+            columns: output.columns,
+            lines: output.lines,
+        };
+        if *size != new_size && new_size.columns > 0 && new_size.lines > 0 {
+            *size = new_size;
+            term.resize(new_size);
+        }
+        parser.advance(&mut *term, &output.bytes);
+        drop(term);
+
+        self.process_event(&AlacTermEvent::Wakeup, cx);
+    }
+
+    /// Returns the range of the last finished command's output, which is `None` if the command
+    /// didn't print anything, along with the command's exit code.
+    fn last_command_output_range(
+        &self,
+        term: &Term<ZedListener>,
+    ) -> Option<(Option<RangeInclusive<AlacPoint>>, Option<i32>)> {
+        let (start, end, exit_code) = self.shell_integration.lock().last_command_output()?;
+        if start.line < term.topmost_line() {
+            return None;
+        }
+        let range = (start < end).then(|| {
+            let end = end
+                .sub(term, Boundary::Grid, 1)
+                .grid_clamp(term, Boundary::Grid);
+            start..=end
+        });
+        Some((range, exit_code))
+    }
+
     fn set_selection(&mut self, selection: Option<(Selection, AlacPoint)>) {
         self.events
             .push_back(InternalEvent::SetSelection(selection));
@@ -1134,6 +1503,41 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    /// Scrolls the closest prompt above the top of the viewport to the top, if the shell reports
+    /// its prompts through shell integration.
+    pub fn scroll_to_previous_prompt(&mut self) {
+        let viewport_top = -(self.last_content.display_offset as i32);
+        let prompt_line = self
+            .shell_integration
+            .lock()
+            .prompt_lines()
+            .into_iter()
+            .rev()
+            .find(|line| line.0 < viewport_top);
+        if let Some(line) = prompt_line {
+            let delta = viewport_top - line.0;
+            self.events
+                .push_back(InternalEvent::Scroll(AlacScroll::Delta(delta)));
+        }
+    }
+
+    /// Scrolls the closest prompt below the top of the viewport to the top, or scrolls to the
+    /// bottom if there isn't one.
+    pub fn scroll_to_next_prompt(&mut self) {
+        let viewport_top = -(self.last_content.display_offset as i32);
+        let prompt_line = self
+            .shell_integration
+            .lock()
+            .prompt_lines()
+            .into_iter()
+            .find(|line| line.0 > viewport_top);
+        let scroll = match prompt_line {
+            Some(line) => AlacScroll::Delta(viewport_top - line.0),
+            None => AlacScroll::Bottom,
+        };
+        self.events.push_back(InternalEvent::Scroll(scroll));
+    }
+
     ///Resize the terminal and the PTY.
     pub fn set_size(&mut self, new_size: TerminalSize) {
         if self.last_content.size != new_size {
@@ -1143,11 +1547,30 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: String) {
-        self.pty_tx.notify(input.into_bytes());
+        self.write_bytes_to_pty(input.into_bytes());
     }
 
     fn write_bytes_to_pty(&self, input: Vec<u8>) {
-        self.pty_tx.notify(input);
+        match &self.terminal_type {
+            TerminalType::Pty { pty_tx, .. } => pty_tx.notify(input),
+            TerminalType::Shared {
+                input_tx,
+                read_only,
+                ..
+            } => {
+                if !read_only {
+                    input_tx.unbounded_send(input).ok();
+                }
+            }
+        }
+    }
+
+    /// Writes the terminal's response to a request from the program that's running in it. Shared
+    /// terminals don't respond, as the host's terminal already has.
+    fn respond_to_pty(&self, response: String) {
+        if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
+            pty_tx.notify(response.into_bytes());
+        }
     }
 
     pub fn input(&mut self, input: String) {
@@ -1306,11 +1729,26 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.shell_integration.lock().prune(terminal.history_size());
+        self.inline_images.lock().prune(terminal.history_size());
+
+        self.last_content = Self::make_content(
+            &terminal,
+            &self.shell_integration.lock(),
+            &self.inline_images.lock(),
+            &self.last_content,
+        );
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        shell_integration: &ShellIntegration,
+        inline_images: &InlineImages,
+        last_content: &TerminalContent,
+    ) -> TerminalContent {
         let content = term.renderable_content();
+        let viewport_top = -(content.display_offset as i32);
+        let viewport_lines = viewport_top..viewport_top + term.screen_lines() as i32;
         TerminalContent {
             cells: content
                 .display_iter
@@ -1334,6 +1772,15 @@ impl Terminal {
             cursor_char: term.grid()[content.cursor.point].c,
             size: last_content.size,
             last_hovered_word: last_content.last_hovered_word.clone(),
+            command_exit_codes: shell_integration
+                .exit_codes()
+                .filter(|(line, _)| viewport_lines.contains(&line.0))
+                .collect(),
+            images: if content.mode.contains(TermMode::ALT_SCREEN) {
+                Vec::new()
+            } else {
+                inline_images.images_within(viewport_lines)
+            },
         }
     }
 
@@ -1408,7 +1855,7 @@ impl Terminal {
 
             if self.mouse_changed(point, side) {
                 if let Some(bytes) = mouse_moved_report(point, e, self.last_content.mode) {
-                    self.pty_tx.notify(bytes);
+                    self.write_bytes_to_pty(bytes);
                 }
             }
         } else if self.secondary_pressed {
@@ -1487,7 +1934,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             match e.button {
@@ -1541,7 +1988,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1579,7 +2026,7 @@ impl Terminal {
                 if let Some(scrolls) = scroll_report(point, scroll_lines, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_bytes_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1588,7 +2035,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_bytes_to_pty(alt_scroll(scroll_lines))
             } else if scroll_lines != 0 {
                 let scroll = AlacScroll::Delta(scroll_lines);
 
@@ -1648,7 +2095,12 @@ impl Terminal {
             // the working directory on the client and persist that.
             None
         } else {
-            self.client_side_working_directory()
+            // Prefer the directory reported by the shell, which stays accurate while another
+            // program runs in the foreground.
+            self.shell_integration
+                .lock()
+                .working_directory()
+                .or_else(|| self.client_side_working_directory())
         }
     }
 
@@ -1659,12 +2111,21 @@ impl Terminal {
     /// This does *not* return the working directory of the shell that runs on the
     /// remote host, in case Zed is connected to a remote host.
     fn client_side_working_directory(&self) -> Option<PathBuf> {
-        self.pty_info
+        self.pty_info()?
             .current
             .as_ref()
             .map(|process| process.cwd.clone())
     }
 
+    /// Returns information about the process that's connected to the PTY, which shared terminals
+    /// don't have.
+    pub fn pty_info(&self) -> Option<&PtyProcessInfo> {
+        match &self.terminal_type {
+            TerminalType::Pty { info, .. } => Some(info),
+            TerminalType::Shared { .. } => None,
+        }
+    }
+
     pub fn title(&self, truncate: bool) -> String {
         const MAX_CHARS: usize = 25;
         match &self.task {
@@ -1680,9 +2141,8 @@ impl Terminal {
                 .as_ref()
                 .map(|title_override| title_override.to_string())
                 .unwrap_or_else(|| {
-                    self.pty_info
-                        .current
-                        .as_ref()
+                    self.pty_info()
+                        .and_then(|info| info.current.as_ref())
                         .map(|fpi| {
                             let process_file = fpi
                                 .cwd
@@ -1816,6 +2276,31 @@ fn task_summary(task: &TaskState, error_code: Option<i32>) -> (bool, String, Str
     (success, task_line, command_line)
 }
 
+/// Writes the output of a terminal from a previous session into a new terminal, followed by a line
+/// marking where the new session starts.
+///
+/// This is done before the new terminal's shell starts, so that the shell's output comes after it.
+fn restore_scrollback(term: &mut Term<ZedListener>, restored_scrollback: &RestoredScrollback) {
+    let mut parser: Processor = Processor::new();
+    for line in restored_scrollback.text.lines() {
+        // The restored text is only printed, so it must not contain any escape sequences.
+        let line = line.replace(|c: char| c.is_control(), "");
+        parser.advance(term, line.as_bytes());
+        parser.advance(term, b"\r\n");
+    }
+    let title = restored_scrollback
+        .title
+        .replace(|c: char| c.is_control(), " ");
+    let restored_line = format!("\x1b[2m{TASK_DELIMITER}Restored session: {title}\x1b[0m\r\n");
+    parser.advance(term, restored_line.as_bytes());
+    if let Some(last_command) = &restored_scrollback.last_command {
+        let last_command = last_command.replace(|c: char| c.is_control(), " ");
+        let command_line =
+            format!("\x1b[2m{TASK_DELIMITER}Last command: {last_command}\x1b[0m\r\n");
+        parser.advance(term, command_line.as_bytes());
+    }
+}
+
 /// Appends a stringified task summary to the terminal, after its output.
 ///
 /// SAFETY: This function should only be called after terminal's PTY is no longer alive.
@@ -1855,7 +2340,9 @@ unsafe fn append_text_to_term(term: &mut Term<ZedListener>, text_lines: &[&str])
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
+            pty_tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
@@ -1995,14 +2482,18 @@ pub fn rgba_color(r: u8, g: u8, b: u8) -> Hsla {
 #[cfg(test)]
 mod tests {
     use alacritty_terminal::{
+        grid::Dimensions,
         index::{Column, Line, Point as AlacPoint},
-        term::cell::Cell,
+        term::{cell::Cell, Config},
+        Term,
     };
+    use futures::channel::mpsc::unbounded;
     use gpui::{point, size, Pixels};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, restore_scrollback, rgb_for_index, IndexedCell,
+        RestoredScrollback, TerminalContent, TerminalSize, ZedListener,
     };
 
     #[test]
@@ -2180,4 +2671,31 @@ mod tests {
             vec!["Main.cs:20:5:Error", "desc"],
         );
     }
+
+    #[test]
+    fn test_restore_scrollback() {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = Term::new(
+            Config::default(),
+            &TerminalSize::default(),
+            ZedListener::new(events_tx),
+        );
+        restore_scrollback(
+            &mut term,
+            &RestoredScrollback {
+                title: "project — zsh".to_string(),
+                text: "$ echo hello\nhello\x1b[2J".to_string(),
+                last_command: Some("echo hello\nworld".to_string()),
+            },
+        );
+
+        // Escape sequences in the restored text aren't interpreted.
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        assert_eq!(
+            term.bounds_to_string(start, end).trim_end(),
+            "$ echo hello\nhello[2J\n⏵ Restored session: project — zsh\n⏵ Last command: echo hello world"
+        );
+        assert_eq!(term.grid().cursor.point, AlacPoint::new(Line(4), Column(0)));
+    }
 }
@@ -46,6 +46,9 @@ pub struct TerminalSettings {
     pub default_height: Pixels,
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
+    pub shell_integration: bool,
+    pub restore_scrollback: bool,
+    pub restore_running_commands: bool,
     pub toolbar: Toolbar,
     pub scrollbar: ScrollbarSettings,
 }
@@ -219,6 +222,25 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// Whether to add Zed's shell integration to bash, zsh and fish shells, through
+    /// which they report their prompts, commands and working directory.
+    /// This enables jumping between prompts, copying the output of the last
+    /// command and showing the exit codes of commands.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// Whether to save the output of terminals, and show it again when they're
+    /// restored after Zed restarts. Up to `max_scroll_history_lines` lines are saved
+    /// when Zed quits.
+    ///
+    /// Default: true
+    pub restore_scrollback: Option<bool>,
+    /// Whether to run the command that was running in a terminal again when the
+    /// terminal is restored after Zed restarts, such as a task or, with shell
+    /// integration, a command run in the shell.
+    ///
+    /// Default: false
+    pub restore_running_commands: Option<bool>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar-related settings
@@ -9,7 +9,14 @@ use std::path::{Path, PathBuf};
 use ui::{App, Context, Pixels, Window};
 use util::ResultExt as _;
 
-use db::{define_connection, query, sqlez::statement::Statement, sqlez_macros::sql};
+use db::{
+    define_connection, query,
+    sqlez::{
+        bindable::{Bind, Column, StaticColumnCount},
+        statement::Statement,
+    },
+    sqlez_macros::sql,
+};
 use workspace::{
     ItemHandle, ItemId, Member, Pane, PaneAxis, PaneGroup, SerializableItem as _, Workspace,
     WorkspaceDb, WorkspaceId,
@@ -61,7 +68,12 @@ fn serialize_pane(pane: &Entity<Pane>, active: bool, cx: &mut App) -> Serialized
         .items()
         .filter_map(|item| {
             let terminal_view = item.act_as::<TerminalView>(cx)?;
-            if terminal_view.read(cx).terminal().read(cx).task().is_some() {
+            if terminal_view
+                .read(cx)
+                .terminal()
+                .read(cx)
+                .is_shared_from_collaborator()
+            {
                 None
             } else {
                 let id = item.item_id().as_u64();
@@ -372,6 +384,56 @@ impl<'de> Deserialize<'de> for SerializedAxis {
     }
 }
 
+/// The state of a terminal that's restored when its workspace is opened again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct SerializedTerminal {
+    /// Empty if the working directory isn't known.
+    pub working_directory: PathBuf,
+    /// Only saved when the terminal is closed or Zed quits, `None` keeps the saved scrollback.
+    pub scrollback: Option<String>,
+    pub title: Option<String>,
+    /// The last command run in the terminal's shell, or the command of the task it ran.
+    pub last_command: Option<String>,
+    /// Whether the last command was still running.
+    pub command_running: bool,
+}
+
+impl StaticColumnCount for SerializedTerminal {
+    fn column_count() -> usize {
+        5
+    }
+}
+
+impl Bind for SerializedTerminal {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        let start_index = statement.bind(&self.working_directory, start_index)?;
+        let start_index = statement.bind(&self.scrollback, start_index)?;
+        let start_index = statement.bind(&self.title, start_index)?;
+        let start_index = statement.bind(&self.last_command, start_index)?;
+        statement.bind(&self.command_running, start_index)
+    }
+}
+
+impl Column for SerializedTerminal {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (working_directory, start_index) = Column::column(statement, start_index)?;
+        let (scrollback, start_index) = Column::column(statement, start_index)?;
+        let (title, start_index) = Column::column(statement, start_index)?;
+        let (last_command, start_index) = Column::column(statement, start_index)?;
+        let (command_running, start_index) = Column::column(statement, start_index)?;
+        Ok((
+            Self {
+                working_directory,
+                scrollback,
+                title,
+                last_command,
+                command_running,
+            },
+            start_index,
+        ))
+    }
+}
+
 define_connection! {
     pub static ref TERMINAL_DB: TerminalDb<WorkspaceDb> =
         &[sql!(
@@ -403,6 +465,12 @@ define_connection! {
             DROP TABLE terminals;
 
             ALTER TABLE terminals2 RENAME TO terminals;
+        ),
+        sql!(
+            ALTER TABLE terminals ADD COLUMN scrollback TEXT;
+            ALTER TABLE terminals ADD COLUMN title TEXT;
+            ALTER TABLE terminals ADD COLUMN last_command TEXT;
+            ALTER TABLE terminals ADD COLUMN command_running INTEGER NOT NULL DEFAULT 0;
         )];
 }
 
@@ -420,19 +488,27 @@ impl TerminalDb {
     }
 
     query! {
-        pub async fn save_working_directory(
+        pub async fn save_terminal(
             item_id: ItemId,
             workspace_id: WorkspaceId,
-            working_directory: PathBuf
+            serialized_terminal: SerializedTerminal
         ) -> Result<()> {
-            INSERT OR REPLACE INTO terminals(item_id, workspace_id, working_directory)
-            VALUES (?, ?, ?)
+            INSERT INTO terminals
+                (item_id, workspace_id, working_directory, scrollback, title, last_command, command_running)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT DO UPDATE SET
+                working_directory = ?3,
+                scrollback = COALESCE(?4, scrollback),
+                title = ?5,
+                last_command = ?6,
+                command_running = ?7
         }
     }
 
     query! {
-        pub fn get_working_directory(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
-            SELECT working_directory
+        pub fn get_terminal(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<SerializedTerminal>> {
+            SELECT working_directory, scrollback, title, last_command, command_running
             FROM terminals
             WHERE item_id = ? AND workspace_id = ?
         }
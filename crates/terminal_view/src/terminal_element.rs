@@ -21,10 +21,11 @@ use terminal::{
         },
     },
     terminal_settings::TerminalSettings,
-    HoveredWord, IndexedCell, Terminal, TerminalContent, TerminalSize,
+    HoveredWord, IndexedCell, Terminal, TerminalContent, TerminalImage, TerminalSize,
 };
 use theme::{ActiveTheme, Theme, ThemeSettings};
 use ui::{ParentElement, Tooltip};
+use util::ResultExt as _;
 use workspace::Workspace;
 
 use std::mem;
@@ -45,6 +46,11 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// The display lines of the prompts of finished commands, with the colors of their exit code
+    /// badges.
+    command_badges: Vec<(i32, Hsla)>,
+    /// The visible inline images, with their points' lines being display lines.
+    images: Vec<TerminalImage>,
     last_hovered_word: Option<HoveredWord>,
     block_below_cursor_element: Option<AnyElement>,
 }
@@ -712,17 +718,22 @@ impl Element for TerminalElement {
 
                 let background_color = theme.colors().terminal_background;
 
-                let last_hovered_word = self.terminal.update(cx, |terminal, cx| {
-                    terminal.set_size(dimensions);
-                    terminal.sync(cx);
-                    if self.can_navigate_to_selected_word
-                        && terminal.can_navigate_to_selected_word()
-                    {
-                        terminal.last_content.last_hovered_word.clone()
-                    } else {
-                        None
-                    }
-                });
+                let (last_hovered_word, removed_images) =
+                    self.terminal.update(cx, |terminal, cx| {
+                        terminal.set_size(dimensions);
+                        terminal.sync(cx);
+                        let last_hovered_word = if self.can_navigate_to_selected_word
+                            && terminal.can_navigate_to_selected_word()
+                        {
+                            terminal.last_content.last_hovered_word.clone()
+                        } else {
+                            None
+                        };
+                        (last_hovered_word, terminal.take_removed_images())
+                    });
+                for image in removed_images {
+                    window.drop_image(image).log_err();
+                }
 
                 let scroll_top = self.terminal_view.read(cx).scroll_top;
                 let hyperlink_tooltip = last_hovered_word.clone().map(|hovered_word| {
@@ -743,11 +754,33 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_exit_codes,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let command_badges = command_exit_codes
+                    .iter()
+                    .map(|(line, exit_code)| {
+                        let color = if *exit_code == 0 {
+                            theme.status().success
+                        } else {
+                            theme.status().error
+                        };
+                        (line.0 + display_offset as i32, color)
+                    })
+                    .collect();
+                let images = images
+                    .iter()
+                    .cloned()
+                    .map(|mut image| {
+                        image.point.line.0 += display_offset as i32;
+                        image
+                    })
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -866,6 +899,8 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    command_badges,
+                    images,
                     last_hovered_word,
                     block_below_cursor_element,
                 }
@@ -937,6 +972,37 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    let badge_size = layout.gutter * 0.5;
+                    for (line, color) in &layout.command_badges {
+                        let line_center =
+                            origin.y + layout.dimensions.line_height * (*line as f32 + 0.5);
+                        let badge_bounds = Bounds::new(
+                            point(
+                                bounds.origin.x + (layout.gutter - badge_size) / 2.,
+                                line_center - badge_size / 2.,
+                            ),
+                            size(badge_size, badge_size),
+                        );
+                        window.paint_quad(fill(badge_bounds, *color).corner_radii(badge_size / 2.));
+                    }
+
+                    for image in &layout.images {
+                        let image_origin = origin
+                            + point(
+                                layout.dimensions.cell_width * image.point.column.0 as f32,
+                                layout.dimensions.line_height * image.point.line.0 as f32,
+                            );
+                        window
+                            .paint_image(
+                                Bounds::new(image_origin, image.size),
+                                Default::default(),
+                                image.image.clone(),
+                                0,
+                                false,
+                            )
+                            .log_err();
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
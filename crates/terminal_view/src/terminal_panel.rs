@@ -54,7 +54,7 @@ pub fn init(cx: &mut App) {
             workspace.register_action(TerminalPanel::new_terminal);
             workspace.register_action(TerminalPanel::open_terminal);
             workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
-                if is_enabled_in_workspace(workspace, cx) {
+                if is_enabled_in_workspace(workspace, cx) || has_host_terminals(workspace, cx) {
                     workspace.toggle_panel_focus::<TerminalPanel>(window, cx);
                 }
             });
@@ -124,6 +124,8 @@ impl TerminalPanel {
 
     fn apply_tab_bar_buttons(&self, terminal_pane: &Entity<Pane>, cx: &mut Context<Self>) {
         let assistant_tab_bar_button = self.assistant_tab_bar_button.clone();
+        let terminal_panel = cx.entity().downgrade();
+        let workspace = self.workspace.clone();
         terminal_pane.update(cx, |pane, cx| {
             pane.set_render_tab_bar_buttons(cx, move |pane, window, cx| {
                 let split_context = pane
@@ -145,24 +147,66 @@ impl TerminalPanel {
                             )
                             .anchor(Corner::TopRight)
                             .with_handle(pane.new_item_context_menu_handle.clone())
-                            .menu(move |window, cx| {
-                                let focus_handle = focus_handle.clone();
-                                let menu = ContextMenu::build(window, cx, |menu, _, _| {
-                                    menu.context(focus_handle.clone())
-                                        .action(
-                                            "New Terminal",
-                                            workspace::NewTerminal.boxed_clone(),
-                                        )
-                                        // We want the focus to go back to terminal panel once task modal is dismissed,
-                                        // hence we focus that first. Otherwise, we'd end up without a focused element, as
-                                        // context menu will be gone the moment we spawn the modal.
-                                        .action(
-                                            "Spawn task",
-                                            zed_actions::Spawn::modal().boxed_clone(),
-                                        )
-                                });
-
-                                Some(menu)
+                            .menu({
+                                let terminal_panel = terminal_panel.clone();
+                                let workspace = workspace.clone();
+                                move |window, cx| {
+                                    let focus_handle = focus_handle.clone();
+                                    let project = workspace.upgrade()?.read(cx).project().clone();
+                                    let supports_terminal = project.read(cx).supports_terminal(cx);
+                                    let host_terminals = project
+                                        .read(cx)
+                                        .shared_terminal_store()
+                                        .read(cx)
+                                        .host_terminals()
+                                        .to_vec();
+                                    let menu = ContextMenu::build(window, cx, |mut menu, _, _| {
+                                        menu = menu.context(focus_handle.clone()).when(
+                                            supports_terminal,
+                                            |menu| {
+                                                menu.action(
+                                                    "New Terminal",
+                                                    workspace::NewTerminal.boxed_clone(),
+                                                )
+                                                // We want the focus to go back to terminal panel once task modal is dismissed,
+                                                // hence we focus that first. Otherwise, we'd end up without a focused element, as
+                                                // context menu will be gone the moment we spawn the modal.
+                                                .action(
+                                                    "Spawn task",
+                                                    zed_actions::Spawn::modal().boxed_clone(),
+                                                )
+                                            },
+                                        );
+                                        if !host_terminals.is_empty() {
+                                            menu = menu
+                                                .when(supports_terminal, |menu| menu.separator())
+                                                .header("Shared by Host");
+                                        }
+                                        for host_terminal in host_terminals {
+                                            let terminal_panel = terminal_panel.clone();
+                                            menu = menu.entry(
+                                                host_terminal.title,
+                                                None,
+                                                move |window, cx| {
+                                                    terminal_panel
+                                                        .update(cx, |terminal_panel, cx| {
+                                                            terminal_panel
+                                                                .add_host_terminal(
+                                                                    host_terminal.id,
+                                                                    window,
+                                                                    cx,
+                                                                )
+                                                                .detach_and_log_err(cx);
+                                                        })
+                                                        .ok();
+                                                },
+                                            );
+                                        }
+                                        menu
+                                    });
+
+                                    Some(menu)
+                                }
                             }),
                     )
                     .children(assistant_tab_bar_button.clone())
@@ -405,7 +449,13 @@ impl TerminalPanel {
         let window_handle = window.window_handle();
         let terminal = project
             .update(cx, |project, cx| {
-                project.create_terminal_with_venv(kind, python_venv_directory, window_handle, cx)
+                project.create_terminal_with_venv(
+                    kind,
+                    python_venv_directory,
+                    None,
+                    window_handle,
+                    cx,
+                )
             })
             .ok()?;
 
@@ -725,6 +775,60 @@ impl TerminalPanel {
         })
     }
 
+    /// Opens a terminal that the project's host is sharing, or activates it if it's already open.
+    fn add_host_terminal(
+        &mut self,
+        terminal_id: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, |terminal_panel, mut cx| async move {
+            let project = workspace.update(&mut cx, |workspace, _| workspace.project().clone())?;
+            let terminal = project
+                .update(&mut cx, |project, cx| {
+                    project
+                        .shared_terminal_store()
+                        .update(cx, |store, cx| store.open_host_terminal(terminal_id, cx))
+                })?
+                .await?;
+            workspace.update_in(&mut cx, |workspace, window, cx| {
+                workspace.focus_panel::<Self>(window, cx);
+                terminal_panel.update(cx, |terminal_panel, cx| {
+                    let open_terminal_view =
+                        terminal_panel.center.panes().into_iter().find_map(|pane| {
+                            let item_index = pane.read(cx).items().position(|item| {
+                                item.act_as::<TerminalView>(cx)
+                                    .map_or(false, |terminal_view| {
+                                        terminal_view.read(cx).terminal() == &terminal
+                                    })
+                            })?;
+                            Some((pane.clone(), item_index))
+                        });
+                    if let Some((pane, item_index)) = open_terminal_view {
+                        terminal_panel.activate_terminal_view(&pane, item_index, true, window, cx);
+                        return;
+                    }
+
+                    let terminal_view = Box::new(cx.new(|cx| {
+                        TerminalView::new(
+                            terminal,
+                            workspace.weak_handle(),
+                            workspace.database_id(),
+                            workspace.project().downgrade(),
+                            window,
+                            cx,
+                        )
+                    }));
+                    terminal_panel.active_pane.update(cx, |pane, cx| {
+                        pane.add_item(terminal_view, true, true, None, window, cx);
+                    });
+                })
+            })??;
+            Ok(())
+        })
+    }
+
     fn serialize(&mut self, cx: &mut Context<Self>) {
         let height = self.height;
         let width = self.width;
@@ -886,7 +990,8 @@ impl TerminalPanel {
 
     fn is_enabled(&self, cx: &App) -> bool {
         self.workspace.upgrade().map_or(false, |workspace| {
-            is_enabled_in_workspace(workspace.read(cx), cx)
+            let workspace = workspace.read(cx);
+            is_enabled_in_workspace(workspace, cx) || has_host_terminals(workspace, cx)
         })
     }
 }
@@ -895,6 +1000,18 @@ fn is_enabled_in_workspace(workspace: &Workspace, cx: &App) -> bool {
     workspace.project().read(cx).supports_terminal(cx)
 }
 
+/// Whether the host of the project that we've joined is sharing terminals with us, which we can
+/// open in the panel even though we can't create terminals of our own.
+fn has_host_terminals(workspace: &Workspace, cx: &App) -> bool {
+    !workspace
+        .project()
+        .read(cx)
+        .shared_terminal_store()
+        .read(cx)
+        .host_terminals()
+        .is_empty()
+}
+
 pub fn new_terminal_pane(
     workspace: WeakEntity<Workspace>,
     project: Entity<Project>,
@@ -12,7 +12,7 @@ use gpui::{
     FocusHandle, Focusable, KeyContext, KeyDownEvent, Keystroke, MouseButton, MouseDownEvent,
     Pixels, Render, ScrollWheelEvent, Stateful, Styled, Subscription, Task, WeakEntity,
 };
-use persistence::TERMINAL_DB;
+use persistence::{SerializedTerminal, TERMINAL_DB};
 use project::{search::SearchQuery, terminals::TerminalKind, Fs, Metadata, Project};
 use schemars::JsonSchema;
 use terminal::{
@@ -21,9 +21,11 @@ use terminal::{
         term::{search::RegexSearch, TermMode},
     },
     terminal_settings::{self, CursorShape, TerminalBlink, TerminalSettings, WorkingDirectory},
-    Clear, Copy, Event, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown,
-    ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal,
-    TerminalSize, ToggleViMode,
+    Clear, Copy, CopyLastCommandOutput, Event, LastCommand, MaybeNavigationTarget, Paste,
+    RestoredScrollback, ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom,
+    ScrollToNextPrompt, ScrollToPreviousPrompt, ScrollToTop, SelectLastCommandOutput,
+    ShareTerminal, ShowCharacterPalette, StopSharingTerminal, TaskStatus, Terminal, TerminalSize,
+    ToggleViMode,
 };
 use terminal_element::{is_blank, TerminalElement};
 use terminal_panel::TerminalPanel;
@@ -187,6 +189,16 @@ impl TerminalView {
             .unwrap_or_default();
 
         let scroll_handle = TerminalScrollHandle::new(terminal.read(cx));
+        // Saving the scrollback locks the terminal while its whole history is copied, so it's
+        // only done when the terminal is closed or Zed quits, rather than whenever it changes.
+        let save_scrollback_on_quit = cx.on_app_quit(|terminal_view, cx| {
+            let task = terminal_view.save_terminal(true, cx);
+            async move {
+                if let Some(task) = task {
+                    task.await.log_err();
+                }
+            }
+        });
 
         Self {
             terminal,
@@ -212,12 +224,57 @@ impl TerminalView {
             _subscriptions: vec![
                 focus_in,
                 focus_out,
+                save_scrollback_on_quit,
                 cx.observe_global::<SettingsStore>(Self::settings_changed),
             ],
             _terminal_subscriptions: terminal_subscriptions,
         }
     }
 
+    fn save_terminal(
+        &self,
+        with_scrollback: bool,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<gpui::Result<()>>> {
+        let terminal = self.terminal().read(cx);
+        // Collaborators' terminals go away with the project they were shared in.
+        if terminal.is_shared_from_collaborator() {
+            return None;
+        }
+        let workspace_id = self.workspace_id?;
+        let item_id = cx.entity_id().as_u64();
+
+        let scrollback = (with_scrollback && TerminalSettings::get_global(cx).restore_scrollback)
+            .then(|| terminal.scrollback(cx));
+        let working_directory = terminal.working_directory().unwrap_or_default();
+        let title = terminal.title(false);
+        let last_command = match terminal.task() {
+            Some(task) => Some(LastCommand {
+                command: task.command_label.clone(),
+                running: task.status == TaskStatus::Running,
+            }),
+            None => terminal.last_command(),
+        };
+        Some(cx.background_executor().spawn(async move {
+            let scrollback = match scrollback {
+                Some(scrollback) => Some(scrollback.await),
+                None => None,
+            };
+            let serialized_terminal = SerializedTerminal {
+                working_directory,
+                scrollback,
+                title: Some(title),
+                command_running: last_command
+                    .as_ref()
+                    .map_or(false, |last_command| last_command.running),
+                last_command: last_command.map(|last_command| last_command.command),
+            };
+            TERMINAL_DB
+                .save_terminal(item_id, workspace_id, serialized_terminal)
+                .await
+        }))
+    }
+
     pub fn entity(&self) -> &Entity<Terminal> {
         &self.terminal
     }
@@ -244,9 +301,63 @@ impl TerminalView {
             .map_or(false, |terminal_panel| {
                 terminal_panel.read(cx).assistant_enabled()
             });
+        let sharing = self.project.upgrade().and_then(|project| {
+            let project_ref = project.read(cx);
+            if !project_ref.is_shared() || !self.terminal.read(cx).can_share() {
+                return None;
+            }
+            let writer_user_ids = project_ref
+                .shared_terminal_store()
+                .read(cx)
+                .terminal_sharing(&self.terminal)
+                .cloned();
+            let user_store = project_ref.user_store().read(cx);
+            let mut guests = project_ref
+                .collaborators()
+                .values()
+                .filter(|collaborator| !collaborator.is_host)
+                .filter_map(|collaborator| user_store.get_cached_user(collaborator.user_id))
+                .collect::<Vec<_>>();
+            guests.sort_by(|a, b| a.github_login.cmp(&b.github_login));
+            Some((project.downgrade(), writer_user_ids, guests))
+        });
+        let terminal = self.terminal.clone();
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
+                .when_some(sharing, |menu, (project, writer_user_ids, guests)| {
+                    let menu = menu.separator();
+                    let Some(writer_user_ids) = writer_user_ids else {
+                        return menu.action("Share", Box::new(ShareTerminal));
+                    };
+                    guests
+                        .into_iter()
+                        .fold(menu, |menu, guest| {
+                            let can_write = writer_user_ids.contains(&guest.id);
+                            let project = project.clone();
+                            let terminal = terminal.clone();
+                            menu.toggleable_entry(
+                                format!("{} Can Type", guest.github_login),
+                                can_write,
+                                IconPosition::Start,
+                                None,
+                                move |_, cx| {
+                                    if let Some(project) = project.upgrade() {
+                                        let store =
+                                            project.read(cx).shared_terminal_store().clone();
+                                        store.update(cx, |store, cx| {
+                                            store
+                                                .set_terminal_writer(
+                                                    &terminal, guest.id, !can_write, cx,
+                                                )
+                                                .log_err();
+                                        });
+                                    }
+                                },
+                            )
+                        })
+                        .action("Stop Sharing", Box::new(StopSharingTerminal))
+                })
                 .separator()
                 .action("Copy", Box::new(Copy))
                 .action("Paste", Box::new(Paste))
@@ -457,11 +568,85 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn select_last_command_output(
+        &mut self,
+        _: &SelectLastCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.select_last_command_output());
+        cx.notify();
+    }
+
+    fn copy_last_command_output(
+        &mut self,
+        _: &CopyLastCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, cx| term.copy_last_command_output(cx));
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
     }
 
+    fn share_terminal(&mut self, _: &ShareTerminal, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_sharing(true, cx);
+    }
+
+    fn stop_sharing_terminal(
+        &mut self,
+        _: &StopSharingTerminal,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_sharing(false, cx);
+    }
+
+    /// Shares the terminal with the project's collaborators, or stops sharing it.
+    fn set_sharing(&mut self, shared: bool, cx: &mut Context<Self>) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let terminal = self.terminal.clone();
+        let store = project.read(cx).shared_terminal_store().clone();
+        store.update(cx, |store, cx| {
+            if shared {
+                store.share_terminal(&terminal, cx).log_err();
+            } else {
+                store.unshare_terminal(&terminal, cx);
+            }
+        });
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+    }
+
     pub fn should_show_cursor(&self, focused: bool, cx: &mut Context<Self>) -> bool {
         //Don't blink the cursor when not focused, blinking is disabled, or paused
         if !focused
@@ -953,6 +1138,7 @@ fn subscribe_for_terminal_events(
                 }
             },
             Event::BreadcrumbsChanged => cx.emit(ItemEvent::UpdateBreadcrumbs),
+            Event::CommandFinished(_) => {}
             Event::CloseTerminal => cx.emit(ItemEvent::CloseItem),
             Event::SelectionsChanged => {
                 window.invalidate_character_coordinates();
@@ -1146,7 +1332,13 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::select_last_command_output))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
+            .on_action(cx.listener(TerminalView::share_terminal))
+            .on_action(cx.listener(TerminalView::stop_sharing_terminal))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_key_down(cx.listener(Self::key_down))
@@ -1204,7 +1396,10 @@ impl Item for TerminalView {
     fn tab_tooltip_content(&self, cx: &App) -> Option<TabTooltipContent> {
         let terminal = self.terminal().read(cx);
         let title = terminal.title(false);
-        let pid = terminal.pty_info.pid_getter().fallback_pid();
+        let Some(pty_info) = terminal.pty_info() else {
+            return Some(TabTooltipContent::Text(title.into()));
+        };
+        let pid = pty_info.pid_getter().fallback_pid();
 
         Some(TabTooltipContent::Custom(Box::new(move |_window, cx| {
             cx.new(|_| TerminalTooltip::new(title.clone(), pid)).into()
@@ -1306,6 +1501,7 @@ impl Item for TerminalView {
                 project.create_terminal_with_venv(
                     TerminalKind::Shell(working_directory),
                     python_venv_directory,
+                    None,
                     window_handle,
                     cx,
                 )
@@ -1366,14 +1562,12 @@ impl Item for TerminalView {
         _: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if self.terminal().read(cx).task().is_none() {
-            if let Some((new_id, old_id)) = workspace.database_id().zip(self.workspace_id) {
-                cx.background_executor()
-                    .spawn(TERMINAL_DB.update_workspace_id(new_id, old_id, cx.entity_id().as_u64()))
-                    .detach();
-            }
-            self.workspace_id = workspace.database_id();
+        if let Some((new_id, old_id)) = workspace.database_id().zip(self.workspace_id) {
+            cx.background_executor()
+                .spawn(TERMINAL_DB.update_workspace_id(new_id, old_id, cx.entity_id().as_u64()))
+                .detach();
         }
+        self.workspace_id = workspace.database_id();
     }
 
     fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
@@ -1400,25 +1594,12 @@ impl SerializableItem for TerminalView {
     fn serialize(
         &mut self,
         _workspace: &mut Workspace,
-        item_id: workspace::ItemId,
-        _closing: bool,
+        _item_id: workspace::ItemId,
+        closing: bool,
         _: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Task<gpui::Result<()>>> {
-        let terminal = self.terminal().read(cx);
-        if terminal.task().is_some() {
-            return None;
-        }
-
-        if let Some((cwd, workspace_id)) = terminal.working_directory().zip(self.workspace_id) {
-            Some(cx.background_executor().spawn(async move {
-                TERMINAL_DB
-                    .save_working_directory(item_id, workspace_id, cwd)
-                    .await
-            }))
-        } else {
-            None
-        }
+        self.save_terminal(closing, cx)
     }
 
     fn should_serialize(&self, event: &Self::Event) -> bool {
@@ -1435,31 +1616,44 @@ impl SerializableItem for TerminalView {
     ) -> Task<anyhow::Result<Entity<Self>>> {
         let window_handle = window.window_handle();
         window.spawn(cx, |mut cx| async move {
-            let cwd = cx
-                .update(|_window, cx| {
-                    let from_db = TERMINAL_DB
-                        .get_working_directory(item_id, workspace_id)
-                        .log_err()
-                        .flatten();
-                    if from_db
-                        .as_ref()
-                        .is_some_and(|from_db| !from_db.as_os_str().is_empty())
-                    {
-                        from_db
-                    } else {
+            let serialized_terminal = TERMINAL_DB
+                .get_terminal(item_id, workspace_id)
+                .log_err()
+                .flatten()
+                .unwrap_or_default();
+            let (cwd, restore_scrollback, restore_running_commands) =
+                cx.update(|_window, cx| {
+                    let cwd = if serialized_terminal.working_directory.as_os_str().is_empty() {
                         workspace
                             .upgrade()
                             .and_then(|workspace| default_working_directory(workspace.read(cx), cx))
-                    }
-                })
-                .ok()
-                .flatten();
+                    } else {
+                        Some(serialized_terminal.working_directory.clone())
+                    };
+                    let settings = TerminalSettings::get_global(cx);
+                    (
+                        cwd,
+                        settings.restore_scrollback,
+                        settings.restore_running_commands,
+                    )
+                })?;
 
+            let command_to_rerun = command_to_rerun(&serialized_terminal, restore_running_commands);
+            let restored_scrollback = restored_scrollback(serialized_terminal, restore_scrollback);
             let terminal = project
                 .update(&mut cx, |project, cx| {
-                    project.create_terminal(TerminalKind::Shell(cwd), window_handle, cx)
+                    project.create_terminal_with_scrollback(
+                        TerminalKind::Shell(cwd),
+                        restored_scrollback,
+                        window_handle,
+                        cx,
+                    )
                 })?
                 .await?;
+            if let Some(command) = command_to_rerun {
+                terminal.update(&mut cx, |terminal, _| terminal.input(command))?;
+            }
+
             cx.update(|window, cx| {
                 cx.new(|cx| {
                     TerminalView::new(
@@ -1476,6 +1670,44 @@ impl SerializableItem for TerminalView {
     }
 }
 
+/// Returns what to show of a terminal from a previous session before its new shell starts: its
+/// output, if that's restored, and its title and last command.
+fn restored_scrollback(
+    serialized_terminal: SerializedTerminal,
+    restore_scrollback: bool,
+) -> Option<RestoredScrollback> {
+    let text = serialized_terminal
+        .scrollback
+        .filter(|_| restore_scrollback)
+        .unwrap_or_default();
+    if text.is_empty() && serialized_terminal.last_command.is_none() {
+        return None;
+    }
+    Some(RestoredScrollback {
+        title: serialized_terminal
+            .title
+            .unwrap_or_else(|| "Terminal".to_string()),
+        text,
+        last_command: serialized_terminal.last_command,
+    })
+}
+
+/// Returns the input that runs the command that was running in a terminal from a previous
+/// session again, if that's enabled.
+fn command_to_rerun(
+    serialized_terminal: &SerializedTerminal,
+    restore_running_commands: bool,
+) -> Option<String> {
+    if !restore_running_commands || !serialized_terminal.command_running {
+        return None;
+    }
+    let command = serialized_terminal
+        .last_command
+        .as_ref()?
+        .replace(|c: char| c.is_control(), " ");
+    Some(format!("{command}\r"))
+}
+
 impl SearchableItem for TerminalView {
     type Match = RangeInclusive<Point>;
 
@@ -1842,4 +2074,63 @@ mod tests {
     fn empty_string_stays_empty() {
         assert_eq!(regex_to_literal(""), "".to_string());
     }
+
+    #[test]
+    fn restores_title_and_last_command() {
+        let serialized_terminal = SerializedTerminal {
+            working_directory: PathBuf::from("/project"),
+            scrollback: Some("Compiling zed\n".to_string()),
+            title: Some("cargo run".to_string()),
+            last_command: Some("cargo run".to_string()),
+            command_running: true,
+        };
+        assert_eq!(
+            restored_scrollback(serialized_terminal.clone(), true),
+            Some(RestoredScrollback {
+                title: "cargo run".to_string(),
+                text: "Compiling zed\n".to_string(),
+                last_command: Some("cargo run".to_string()),
+            })
+        );
+        // The title and last command are kept even when the output isn't restored.
+        assert_eq!(
+            restored_scrollback(serialized_terminal, false),
+            Some(RestoredScrollback {
+                title: "cargo run".to_string(),
+                text: String::new(),
+                last_command: Some("cargo run".to_string()),
+            })
+        );
+        assert_eq!(
+            restored_scrollback(
+                SerializedTerminal {
+                    scrollback: Some(String::new()),
+                    ..SerializedTerminal::default()
+                },
+                true
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn reruns_running_commands_only_when_enabled() {
+        let running_task = SerializedTerminal {
+            title: Some("cargo watch".to_string()),
+            last_command: Some("cargo watch\n-x test".to_string()),
+            command_running: true,
+            ..SerializedTerminal::default()
+        };
+        assert_eq!(command_to_rerun(&running_task, false), None);
+        assert_eq!(
+            command_to_rerun(&running_task, true),
+            Some("cargo watch -x test\r".to_string())
+        );
+
+        let finished_command = SerializedTerminal {
+            command_running: false,
+            ..running_task
+        };
+        assert_eq!(command_to_rerun(&finished_command, true), None);
+    }
 }
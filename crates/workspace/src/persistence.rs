@@ -1,6 +1,9 @@
 pub mod model;
 
-use std::{path::Path, str::FromStr};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use client::DevServerProjectId;
@@ -383,6 +386,17 @@ define_connection! {
     sql!(
         ALTER TABLE toolchains ADD COLUMN raw_json TEXT DEFAULT "{}";
     ),
+    sql!(
+        CREATE TABLE bookmarks (
+            workspace_id INTEGER NOT NULL,
+            path BLOB NOT NULL,
+            row INTEGER NOT NULL,
+            label TEXT,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        ) STRICT;
+    ),
     ];
 }
 
@@ -1152,6 +1166,42 @@ impl WorkspaceDb {
             Ok(())
         }).await
     }
+
+    /// Returns the bookmarks saved for a workspace, as absolute paths and rows.
+    pub(crate) async fn bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+    ) -> Result<Vec<(PathBuf, u32, Option<String>)>> {
+        self.write(move |conn| {
+            conn.select_bound(sql!(
+                SELECT path, row, label FROM bookmarks WHERE workspace_id = ?
+            ))?(workspace_id)
+        })
+        .await
+    }
+
+    /// Replaces the bookmarks saved for a workspace.
+    pub(crate) async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: Vec<(PathBuf, u32, Option<String>)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_bookmarks", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM bookmarks WHERE workspace_id = ?
+                ))?(workspace_id)?;
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO bookmarks(workspace_id, path, row, label) VALUES (?, ?, ?, ?)
+                ))?;
+                for (path, row, label) in bookmarks {
+                    insert((workspace_id, path, row, label))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -1210,6 +1260,46 @@ mod tests {
         assert_eq!(test_text_1, "test-text-1");
     }
 
+    #[gpui::test]
+    async fn test_bookmarks() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_bookmarks").await);
+        let workspace = SerializedWorkspace {
+            id: WorkspaceId(1),
+            location: SerializedWorkspaceLocation::from_local_paths(["/tmp"]),
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            session_id: None,
+            window_id: None,
+        };
+        db.save_workspace(workspace.clone()).await;
+
+        let bookmarks = vec![
+            (PathBuf::from("/tmp/a.rs"), 3, None),
+            (
+                PathBuf::from("/tmp/b.rs"),
+                0,
+                Some("entry point".to_string()),
+            ),
+        ];
+        db.save_bookmarks(workspace.id, bookmarks.clone())
+            .await
+            .unwrap();
+        assert_eq!(db.bookmarks(workspace.id).await.unwrap(), bookmarks);
+
+        db.save_bookmarks(workspace.id, bookmarks[1..].to_vec())
+            .await
+            .unwrap();
+        assert_eq!(db.bookmarks(workspace.id).await.unwrap(), &bookmarks[1..]);
+
+        db.delete_workspace_by_id(workspace.id).await.unwrap();
+        assert_eq!(db.bookmarks(workspace.id).await.unwrap(), Vec::new());
+    }
+
     #[gpui::test]
     async fn test_workspace_id_stability() {
         env_logger::try_init().ok();
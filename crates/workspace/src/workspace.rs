@@ -59,7 +59,8 @@ use persistence::{
 };
 use postage::stream::Stream;
 use project::{
-    DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
+    BookmarkStoreEvent, DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath,
+    Worktree, WorktreeId,
 };
 use remote::{ssh_session::ConnectionIdentifier, SshClientDelegate, SshConnectionOptions};
 use schemars::JsonSchema;
@@ -422,6 +423,12 @@ type WorkspaceItemBuilder = Box<dyn FnOnce(&mut Window, &mut Context<Pane>) -> B
 
 impl Global for ProjectItemOpeners {}
 
+/// The openers used when the project item that claimed a file failed to open it.
+#[derive(Clone, Default, Deref, DerefMut)]
+struct FallbackProjectItemOpeners(Vec<ProjectItemOpener>);
+
+impl Global for FallbackProjectItemOpeners {}
+
 /// Registers a [ProjectItem] for the app. When opening a file, all the registered
 /// items will get a chance to open the file, starting from the project item that
 /// was added last.
@@ -429,20 +436,35 @@ pub fn register_project_item<I: ProjectItem>(cx: &mut App) {
     let builders = cx.default_global::<ProjectItemOpeners>();
     builders.push(|project, project_path, window, cx| {
         let project_item = <I::Item as project::ProjectItem>::try_open(project, project_path, cx)?;
-        let project = project.clone();
-        Some(window.spawn(cx, |cx| async move {
-            let project_item = project_item.await?;
-            let project_entry_id: Option<ProjectEntryId> =
-                project_item.read_with(&cx, project::ProjectItem::entry_id)?;
-            let build_workspace_item = Box::new(|window: &mut Window, cx: &mut Context<Pane>| {
-                Box::new(cx.new(|cx| I::for_project_item(project, project_item, window, cx)))
-                    as Box<dyn ItemHandle>
-            }) as Box<_>;
-            Ok((project_entry_id, build_workspace_item))
-        }))
+        Some(build_project_item::<I>(project, project_item, window, cx))
+    });
+    let fallback_builders = cx.default_global::<FallbackProjectItemOpeners>();
+    fallback_builders.push(|project, project_path, window, cx| {
+        let project_item =
+            <I::Item as project::ProjectItem>::try_open_as_fallback(project, project_path, cx)?;
+        Some(build_project_item::<I>(project, project_item, window, cx))
     });
 }
 
+fn build_project_item<I: ProjectItem>(
+    project: &Entity<Project>,
+    project_item: Task<Result<Entity<I::Item>>>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
+    let project = project.clone();
+    window.spawn(cx, |cx| async move {
+        let project_item = project_item.await?;
+        let project_entry_id: Option<ProjectEntryId> =
+            project_item.read_with(&cx, project::ProjectItem::entry_id)?;
+        let build_workspace_item = Box::new(|window: &mut Window, cx: &mut Context<Pane>| {
+            Box::new(cx.new(|cx| I::for_project_item(project, project_item, window, cx)))
+                as Box<dyn ItemHandle>
+        }) as Box<_>;
+        Ok((project_entry_id, build_workspace_item))
+    })
+}
+
 #[derive(Default)]
 pub struct FollowableViewRegistry(HashMap<TypeId, FollowableViewDescriptor>);
 
@@ -1031,6 +1053,7 @@ impl Workspace {
             Self::serialize_items(&this, serializable_items_rx, &mut cx).await
         });
 
+        let bookmark_store = project.read(cx).bookmark_store().clone();
         let subscriptions = vec![
             cx.observe_window_activation(window, Self::on_window_activation_changed),
             cx.observe_window_bounds(window, move |this, window, cx| {
@@ -1075,6 +1098,13 @@ impl Workspace {
                     store.workspaces.remove(&window_handle.clone());
                 })
             }),
+            cx.subscribe_in(
+                &bookmark_store,
+                window,
+                |this, _, event: &BookmarkStoreEvent, window, cx| match event {
+                    BookmarkStoreEvent::BookmarksChanged => this.serialize_workspace(window, cx),
+                },
+            ),
         ];
 
         cx.defer_in(window, |this, window, cx| {
@@ -1215,6 +1245,22 @@ impl Workspace {
                     })?
                     .await;
             }
+            let bookmarks = DB
+                .bookmarks(workspace_id)
+                .await
+                .log_err()
+                .unwrap_or_default();
+            project_handle.update(&mut cx, |project, cx| {
+                let bookmarks = bookmarks
+                    .into_iter()
+                    .filter_map(|(abs_path, row, label)| {
+                        Some((project.find_project_path(&abs_path, cx)?, row, label))
+                    })
+                    .collect::<Vec<_>>();
+                project
+                    .bookmark_store()
+                    .update(cx, |store, cx| store.restore(bookmarks, cx));
+            })?;
             let window = if let Some(window) = requesting_window {
                 cx.update_window(window.into(), |_, window, cx| {
                     window.replace_root(cx, |window, cx| {
@@ -2911,6 +2957,7 @@ impl Workspace {
     ) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
         let project = self.project().clone();
         let project_item_builders = cx.default_global::<ProjectItemOpeners>().clone();
+        let fallback_builders = cx.default_global::<FallbackProjectItemOpeners>().clone();
         let Some(open_project_item) = project_item_builders
             .iter()
             .rev()
@@ -2918,7 +2965,29 @@ impl Workspace {
         else {
             return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
         };
-        open_project_item
+        if fallback_builders.is_empty() {
+            return open_project_item;
+        }
+
+        window.spawn(cx, |mut cx| async move {
+            let error = match open_project_item.await {
+                Ok(project_item) => return Ok(project_item),
+                Err(error) => error,
+            };
+            for open_fallback_item in fallback_builders.iter().rev() {
+                let Some(fallback_item) = cx
+                    .update(|window, cx| open_fallback_item(&project, &path, window, cx))
+                    .ok()
+                    .flatten()
+                else {
+                    continue;
+                };
+                if let Ok(project_item) = fallback_item.await {
+                    return Ok(project_item);
+                }
+            }
+            Err(error)
+        })
     }
 
     pub fn find_project_item<T>(
@@ -4578,11 +4647,34 @@ impl Workspace {
                 session_id: self.session_id.clone(),
                 window_id: Some(window.window_handle().window_id().as_u64()),
             };
-            return window.spawn(cx, |_| persistence::DB.save_workspace(serialized_workspace));
+            let bookmarks = self.serialize_bookmarks(cx);
+            return window.spawn(cx, |_| async move {
+                persistence::DB.save_workspace(serialized_workspace).await;
+                persistence::DB
+                    .save_bookmarks(database_id, bookmarks)
+                    .await
+                    .log_err();
+            });
         }
         Task::ready(())
     }
 
+    fn serialize_bookmarks(&self, cx: &App) -> Vec<(PathBuf, u32, Option<String>)> {
+        let project = self.project.read(cx);
+        project
+            .bookmark_store()
+            .read(cx)
+            .bookmarks(cx)
+            .into_iter()
+            .filter_map(|(path, entries)| Some((project.absolute_path(&path, cx)?, entries)))
+            .flat_map(|(abs_path, entries)| {
+                entries
+                    .into_iter()
+                    .map(move |entry| (abs_path.clone(), entry.row, entry.label))
+            })
+            .collect()
+    }
+
     async fn serialize_items(
         this: &WeakEntity<Self>,
         items_rx: UnboundedReceiver<Box<dyn SerializableItemHandle>>,
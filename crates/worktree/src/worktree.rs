@@ -15,7 +15,7 @@ use futures::{
     },
     select_biased,
     task::Poll,
-    FutureExt as _, Stream, StreamExt,
+    FutureExt as _, SinkExt as _, Stream, StreamExt,
 };
 use fuzzy::CharBag;
 use git::{
@@ -27,6 +27,7 @@ use git::{
 };
 use gpui::{
     App, AppContext as _, AsyncApp, BackgroundExecutor, Context, Entity, EventEmitter, Task,
+    WeakEntity,
 };
 use ignore::IgnoreStack;
 use language::DiskState;
@@ -54,6 +55,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io::{self, Read as _},
     mem::{self},
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
@@ -114,6 +116,15 @@ pub struct LoadedBinaryFile {
     pub content: Vec<u8>,
 }
 
+/// A file whose text is read a chunk at a time.
+pub struct StreamedFile {
+    pub file: Arc<File>,
+    /// The file's first chunk of text.
+    pub text: String,
+    /// The file's remaining chunks of text, which ends at the end of the file or after an error.
+    pub remaining_text: mpsc::Receiver<Result<String>>,
+}
+
 pub struct LocalWorktree {
     snapshot: LocalSnapshot,
     scan_requests_tx: channel::Sender<ScanRequest>,
@@ -902,12 +913,38 @@ impl Worktree {
     ) -> Task<Result<LoadedBinaryFile>> {
         match self {
             Worktree::Local(this) => this.load_binary_file(path, cx),
+            Worktree::Remote(this) => this.load_binary_file(path, cx),
+        }
+    }
+
+    /// Loads a file's text by reading `chunk_len` bytes at a time, so that the beginning of
+    /// the file is available before the rest is read.
+    pub fn load_file_streamed(
+        &self,
+        path: &Path,
+        chunk_len: usize,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<StreamedFile>> {
+        match self {
+            Worktree::Local(this) => this.load_file_streamed(path, chunk_len, cx),
             Worktree::Remote(_) => {
-                Task::ready(Err(anyhow!("remote worktrees can't yet load binary files")))
+                Task::ready(Err(anyhow!("remote worktrees can't yet load files")))
             }
         }
     }
 
+    pub fn write_binary_file(
+        &self,
+        path: &Path,
+        content: Vec<u8>,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Arc<File>>> {
+        match self {
+            Worktree::Local(this) => this.write_binary_file(path, content, cx),
+            Worktree::Remote(this) => this.write_binary_file(path, content, cx),
+        }
+    }
+
     pub fn write_file(
         &self,
         path: &Path,
@@ -1200,6 +1237,37 @@ impl Worktree {
         })
     }
 
+    pub async fn handle_load_binary_file(
+        this: Entity<Self>,
+        request: proto::LoadBinaryFile,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadBinaryFileResponse> {
+        let loaded = this
+            .update(&mut cx, |this, cx| {
+                this.load_binary_file(Path::new(&request.path), cx)
+            })?
+            .await?;
+        Ok(proto::LoadBinaryFileResponse {
+            file: Some(cx.update(|cx| language::File::to_proto(&*loaded.file, cx))?),
+            content: loaded.content,
+        })
+    }
+
+    pub async fn handle_save_binary_file(
+        this: Entity<Self>,
+        request: proto::SaveBinaryFile,
+        mut cx: AsyncApp,
+    ) -> Result<proto::SaveBinaryFileResponse> {
+        let file = this
+            .update(&mut cx, |this, cx| {
+                this.write_binary_file(Path::new(&request.path), request.content, cx)
+            })?
+            .await?;
+        Ok(proto::SaveBinaryFileResponse {
+            file: Some(cx.update(|cx| language::File::to_proto(&*file, cx))?),
+        })
+    }
+
     pub async fn handle_rename_entry(
         this: Entity<Self>,
         request: proto::RenameProjectEntry,
@@ -1249,6 +1317,72 @@ impl Worktree {
     }
 }
 
+/// Returns the [`File`] for a file that was loaded from the worktree, which may be excluded from
+/// the worktree's entries.
+async fn loaded_file(
+    worktree: WeakEntity<Worktree>,
+    entry: Task<Result<Option<Entry>>>,
+    fs: Arc<dyn Fs>,
+    abs_path: &Path,
+    path: Arc<Path>,
+    is_private: bool,
+) -> Result<Arc<File>> {
+    let worktree = worktree
+        .upgrade()
+        .ok_or_else(|| anyhow!("worktree was dropped"))?;
+    Ok(match entry.await? {
+        Some(entry) => File::for_entry(entry, worktree),
+        None => {
+            let metadata = fs
+                .metadata(abs_path)
+                .await
+                .with_context(|| format!("Loading metadata for excluded file {abs_path:?}"))?
+                .with_context(|| {
+                    format!("Excluded file {abs_path:?} got removed during loading")
+                })?;
+            Arc::new(File {
+                entry_id: None,
+                worktree,
+                path,
+                disk_state: DiskState::Present {
+                    mtime: metadata.mtime,
+                },
+                is_local: true,
+                is_private,
+            })
+        }
+    })
+}
+
+/// Decodes UTF-8 text that's read a chunk at a time, holding back the bytes of characters that
+/// continue in the next chunk.
+#[derive(Default)]
+struct TextChunkDecoder {
+    pending: Vec<u8>,
+}
+
+impl TextChunkDecoder {
+    fn decode(&mut self, bytes: &[u8]) -> Result<String> {
+        self.pending.extend_from_slice(bytes);
+        let mut valid_len = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(error.into()),
+        };
+        // A carriage return is held back too, as it may start a line ending that continues in
+        // the next chunk.
+        if self.pending[..valid_len].ends_with(b"\r") {
+            valid_len -= 1;
+        }
+        let rest = self.pending.split_off(valid_len);
+        Ok(String::from_utf8(mem::replace(&mut self.pending, rest))?)
+    }
+
+    fn finish(&mut self) -> Result<String> {
+        Ok(String::from_utf8(mem::take(&mut self.pending))?)
+    }
+}
+
 impl LocalWorktree {
     pub fn fs(&self) -> &Arc<dyn Fs> {
         &self.fs
@@ -1592,36 +1726,61 @@ impl LocalWorktree {
         cx.spawn(|this, _cx| async move {
             let abs_path = abs_path?;
             let text = fs.load(&abs_path).await?;
+            let file = loaded_file(this, entry, fs, &abs_path, path, is_private).await?;
+            Ok(LoadedFile { file, text })
+        })
+    }
 
-            let worktree = this
-                .upgrade()
-                .ok_or_else(|| anyhow!("worktree was dropped"))?;
-            let file = match entry.await? {
-                Some(entry) => File::for_entry(entry, worktree),
-                None => {
-                    let metadata = fs
-                        .metadata(&abs_path)
-                        .await
-                        .with_context(|| {
-                            format!("Loading metadata for excluded file {abs_path:?}")
-                        })?
-                        .with_context(|| {
-                            format!("Excluded file {abs_path:?} got removed during loading")
-                        })?;
-                    Arc::new(File {
-                        entry_id: None,
-                        worktree,
-                        path,
-                        disk_state: DiskState::Present {
-                            mtime: metadata.mtime,
-                        },
-                        is_local: true,
-                        is_private,
-                    })
-                }
-            };
+    fn load_file_streamed(
+        &self,
+        path: &Path,
+        chunk_len: usize,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<StreamedFile>> {
+        let path = Arc::from(path);
+        let abs_path = self.absolutize(&path);
+        let fs = self.fs.clone();
+        let entry = self.refresh_entry(path.clone(), None, cx);
+        let is_private = self.is_path_private(path.as_ref());
+        let executor = cx.background_executor().clone();
 
-            Ok(LoadedFile { file, text })
+        cx.spawn(|this, _cx| async move {
+            let abs_path = abs_path?;
+            let mut reader = fs.open_sync(&abs_path).await?;
+            let (mut chunks_tx, mut chunks_rx) = mpsc::channel(1);
+            executor
+                .spawn(async move {
+                    let mut decoder = TextChunkDecoder::default();
+                    let mut bytes = vec![0; chunk_len];
+                    loop {
+                        let len = match reader.read(&mut bytes) {
+                            Ok(len) => len,
+                            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                            Err(error) => {
+                                chunks_tx.send(Err(error.into())).await.ok();
+                                return;
+                            }
+                        };
+                        let text = if len == 0 {
+                            decoder.finish()
+                        } else {
+                            decoder.decode(&bytes[..len])
+                        };
+                        let is_last = len == 0 || text.is_err();
+                        if chunks_tx.send(text).await.is_err() || is_last {
+                            return;
+                        }
+                    }
+                })
+                .detach();
+
+            let text = chunks_rx.next().await.unwrap_or(Ok(String::new()))?;
+            let file = loaded_file(this, entry, fs, &abs_path, path, is_private).await?;
+            Ok(StreamedFile {
+                file,
+                text,
+                remaining_text: chunks_rx,
+            })
         })
     }
 
@@ -1711,16 +1870,46 @@ impl LocalWorktree {
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
         let fs = self.fs.clone();
-        let is_private = self.is_path_private(&path);
         let Ok(abs_path) = self.absolutize(&path) else {
             return Task::ready(Err(anyhow!("invalid path {path:?}")));
         };
 
-        let write = cx.background_executor().spawn({
-            let fs = fs.clone();
-            let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
-        });
+        let write = cx
+            .background_executor()
+            .spawn(async move { fs.save(&abs_path, &text, line_ending).await });
+        self.file_after_write(path, write, cx)
+    }
+
+    fn write_binary_file(
+        &self,
+        path: impl Into<Arc<Path>>,
+        content: Vec<u8>,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Arc<File>>> {
+        let path = path.into();
+        let fs = self.fs.clone();
+        let Ok(abs_path) = self.absolutize(&path) else {
+            return Task::ready(Err(anyhow!("invalid path {path:?}")));
+        };
+
+        let write = cx
+            .background_executor()
+            .spawn(async move { fs.atomic_write_bytes(abs_path, content).await });
+        self.file_after_write(path, write, cx)
+    }
+
+    /// Refreshes the entry for a file once it has been written, returning its updated handle.
+    fn file_after_write(
+        &self,
+        path: Arc<Path>,
+        write: Task<Result<()>>,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Arc<File>>> {
+        let fs = self.fs.clone();
+        let is_private = self.is_path_private(&path);
+        let Ok(abs_path) = self.absolutize(&path) else {
+            return Task::ready(Err(anyhow!("invalid path {path:?}")));
+        };
 
         cx.spawn(move |this, mut cx| async move {
             write.await?;
@@ -2230,6 +2419,54 @@ impl RemoteWorktree {
         })
     }
 
+    fn load_binary_file(
+        &self,
+        path: &Path,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<LoadedBinaryFile>> {
+        let response = self.client.request(proto::LoadBinaryFile {
+            project_id: self.project_id,
+            worktree_id: self.id().to_proto(),
+            path: path.to_string_lossy().into(),
+        });
+        cx.spawn(move |this, mut cx| async move {
+            let response = response.await?;
+            let file = response.file.ok_or_else(|| anyhow!("missing file"))?;
+            let worktree = this
+                .upgrade()
+                .ok_or_else(|| anyhow!("worktree was dropped"))?;
+            let file = cx.update(|cx| File::from_proto(file, worktree, cx))??;
+            Ok(LoadedBinaryFile {
+                file: Arc::new(file),
+                content: response.content,
+            })
+        })
+    }
+
+    fn write_binary_file(
+        &self,
+        path: &Path,
+        content: Vec<u8>,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Arc<File>>> {
+        let response = self.client.request(proto::SaveBinaryFile {
+            project_id: self.project_id,
+            worktree_id: self.id().to_proto(),
+            path: path.to_string_lossy().into(),
+            content,
+        });
+        cx.spawn(move |this, mut cx| async move {
+            let response = response.await?;
+            let file = response.file.ok_or_else(|| anyhow!("missing file"))?;
+            let worktree = this
+                .upgrade()
+                .ok_or_else(|| anyhow!("worktree was dropped"))?;
+            Ok(Arc::new(
+                cx.update(|cx| File::from_proto(file, worktree, cx))??,
+            ))
+        })
+    }
+
     fn delete_entry(
         &self,
         entry_id: ProjectEntryId,
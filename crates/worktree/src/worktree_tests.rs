@@ -1,6 +1,6 @@
 use crate::{
-    worktree_settings::WorktreeSettings, Entry, EntryKind, Event, PathChange, Snapshot, Worktree,
-    WorktreeModelHandle,
+    worktree_settings::WorktreeSettings, Entry, EntryKind, Event, PathChange, Snapshot,
+    TextChunkDecoder, Worktree, WorktreeModelHandle,
 };
 use anyhow::Result;
 use fs::{FakeFs, Fs, RealFs, RemoveOptions};
@@ -3426,3 +3426,22 @@ fn assert_entry_git_state(
         "expected {path} to have is_ignored: {is_ignored}"
     );
 }
+
+#[test]
+fn test_text_chunk_decoder() {
+    let mut decoder = TextChunkDecoder::default();
+    let text = "héllo\r\nworld";
+    let (first, rest) = text.as_bytes().split_at(2);
+    assert_eq!(decoder.decode(first).unwrap(), "h");
+    let (second, rest) = rest.split_at(5);
+    assert_eq!(decoder.decode(second).unwrap(), "éllo");
+    assert_eq!(decoder.decode(rest).unwrap(), "\r\nworld");
+    assert_eq!(decoder.finish().unwrap(), "");
+
+    let mut decoder = TextChunkDecoder::default();
+    assert_eq!(decoder.decode(b"a\xe2\x82").unwrap(), "a");
+    assert!(decoder.finish().is_err());
+
+    let mut decoder = TextChunkDecoder::default();
+    assert!(decoder.decode(b"a\xffb").is_err());
+}
@@ -474,6 +474,8 @@ fn main() {
         app_state.languages.set_theme(cx.theme().clone());
         editor::init(cx);
         image_viewer::init(cx);
+        hex_editor::init(cx);
+        spell_check::init(app_state.fs.clone(), cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
 
@@ -482,12 +484,14 @@ fn main() {
 
         go_to_line::init(cx);
         file_finder::init(cx);
+        file_history::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
         project_symbols::init(cx);
         project_panel::init(Assets, cx);
         git_ui::git_panel::init(cx);
         outline_panel::init(Assets, cx);
+        bookmarks_panel::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
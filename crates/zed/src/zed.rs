@@ -13,12 +13,13 @@ use anyhow::Context as _;
 pub use app_menus::*;
 use assets::Assets;
 use assistant_context_editor::AssistantPanelDelegate;
+use bookmarks_panel::BookmarksPanel;
 use breadcrumbs::Breadcrumbs;
 use client::{zed_urls, ZED_URL_SCHEME};
 use collections::VecDeque;
 use command_palette_hooks::CommandPaletteFilter;
-use editor::ProposedChangesEditorToolbar;
 use editor::{scroll::Autoscroll, Editor, MultiBuffer};
+use editor::{LargeFileBanner, ProposedChangesEditorToolbar};
 use feature_flags::FeatureFlagAppExt;
 use futures::FutureExt;
 use futures::{channel::mpsc, select_biased, StreamExt};
@@ -370,6 +371,7 @@ fn initialize_panels(
     cx.spawn_in(window, |workspace_handle, mut cx| async move {
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
         let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+        let bookmarks_panel = BookmarksPanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
         let channels_panel =
             collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
@@ -379,30 +381,38 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let review_panel =
+            collab_ui::review_panel::ReviewPanel::load(workspace_handle.clone(), cx.clone());
 
         let (
             project_panel,
             outline_panel,
+            bookmarks_panel,
             terminal_panel,
             channels_panel,
             chat_panel,
             notification_panel,
+            review_panel,
         ) = futures::try_join!(
             project_panel,
             outline_panel,
+            bookmarks_panel,
             terminal_panel,
             channels_panel,
             chat_panel,
             notification_panel,
+            review_panel,
         )?;
 
         workspace_handle.update_in(&mut cx, |workspace, window, cx| {
             workspace.add_panel(project_panel, window, cx);
             workspace.add_panel(outline_panel, window, cx);
+            workspace.add_panel(bookmarks_panel, window, cx);
             workspace.add_panel(terminal_panel, window, cx);
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(chat_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
+            workspace.add_panel(review_panel, window, cx);
         })?;
 
         let git_ui_enabled = {
@@ -793,6 +803,14 @@ fn register_actions(
                 );
             },
         )
+        .register_action(
+            |workspace: &mut Workspace,
+             _: &collab_ui::review_panel::ToggleFocus,
+             window: &mut Window,
+             cx: &mut Context<Workspace>| {
+                workspace.toggle_panel_focus::<collab_ui::review_panel::ReviewPanel>(window, cx);
+            },
+        )
         .register_action(
             |workspace: &mut Workspace,
              _: &terminal_panel::ToggleFocus,
@@ -883,6 +901,8 @@ fn initialize_pane(
 
             let proposed_change_bar = cx.new(|_| ProposedChangesEditorToolbar::new());
             toolbar.add_item(proposed_change_bar, window, cx);
+            let large_file_banner = cx.new(|_| LargeFileBanner::new());
+            toolbar.add_item(large_file_banner, window, cx);
             let quick_action_bar =
                 cx.new(|cx| QuickActionBar::new(buffer_search_bar, workspace, cx));
             toolbar.add_item(quick_action_bar, window, cx);
@@ -4023,6 +4043,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init((), cx);
             outline_panel::init((), cx);
+            bookmarks_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),